@@ -71,7 +71,7 @@ impl ContractCreationOpts {
         sender_address: &Value,
         nonce: u64,
         chain_id: u64,
-        amount: u64,
+        amount: u128,
         gas_limit: Option<u64>,
         tx_type: &TransactionType,
         values: &ValueStore,
@@ -145,7 +145,7 @@ impl CreateDeploymentOpts {
         sender_address: &Value,
         nonce: u64,
         chain_id: u64,
-        amount: u64,
+        amount: u128,
         gas_limit: Option<u64>,
         tx_type: &TransactionType,
         values: &ValueStore,
@@ -300,7 +300,7 @@ impl Create2DeploymentOpts {
         sender_address: &Value,
         nonce: u64,
         chain_id: u64,
-        amount: u64,
+        amount: u128,
         gas_limit: Option<u64>,
         tx_type: &TransactionType,
         values: &ValueStore,
@@ -108,7 +108,7 @@ impl ProxiedCreationOpts {
         sender_address: &Value,
         nonce: u64,
         chain_id: u64,
-        amount: u64,
+        amount: u128,
         gas_limit: Option<u64>,
         tx_type: &TransactionType,
         values: &ValueStore,
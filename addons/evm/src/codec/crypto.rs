@@ -1,9 +1,11 @@
 use alloy_primitives::{hex::FromHex, keccak256, Address};
 use k256::ecdsa::{SigningKey, VerifyingKey};
 use alloy_signer_local::{coins_bip39::English, LocalSigner, MnemonicBuilder};
+use eth_keystore::KeystoreError;
 use hmac::digest::generic_array::GenericArray;
 use libsecp256k1::{recover, Message, RecoveryId, Signature};
 use txtx_addon_kit::hex;
+use zeroize::Zeroizing;
 
 use crate::constants::DEFAULT_DERIVATION_PATH;
 
@@ -40,12 +42,46 @@ pub fn secret_key_to_secret_key_signer(secret_key: &Vec<u8>) -> Result<SecretKey
     Ok(signer)
 }
 
+/// Decrypts a keystore file (Web3 Secret Storage, with either the scrypt or pbkdf2 KDF), and
+/// builds a signer from the decrypted secret key. The decrypted key material is wiped once the
+/// signer is built.
+pub fn keystore_to_secret_key_signer(
+    keystore_path: &str,
+    password: &str,
+) -> Result<SecretKeySigner, String> {
+    let secret_key = eth_keystore::decrypt_key(keystore_path, password)
+        .map(Zeroizing::new)
+        .map_err(|e| match e {
+            KeystoreError::MacMismatch => {
+                format!("unable to decrypt keystore '{keystore_path}': invalid password")
+            }
+            KeystoreError::StdIo(e) => format!("unable to read keystore '{keystore_path}': {e}"),
+            KeystoreError::SerdeJson(e) => {
+                format!("malformed keystore '{keystore_path}': invalid JSON ({e})")
+            }
+            e => format!("malformed keystore '{keystore_path}': {e}"),
+        })?;
+    secret_key_to_secret_key_signer(&secret_key)
+}
+
 pub fn field_bytes_to_secret_key_signer(field_bytes: &Vec<u8>) -> Result<SecretKeySigner, String> {
     let bytes = GenericArray::from_slice(field_bytes);
     SecretKeySigner::from_field_bytes(bytes)
         .map_err(|e| format!("failed to generate signer: {}", e))
 }
 
+/// Signs the digest of a message (see EIP-191 and EIP-712), returning the signature as
+/// `r || s || v`, with `v` being 27 or 28.
+pub fn sign_message_digest(signer: &SecretKeySigner, digest: &[u8]) -> Result<Vec<u8>, String> {
+    let (signature, recovery_id) = signer
+        .credential()
+        .sign_prehash_recoverable(digest)
+        .map_err(|e| format!("failed to sign message: {e}"))?;
+    let mut signature_bytes = signature.to_bytes().to_vec();
+    signature_bytes.push(27 + recovery_id.to_byte());
+    Ok(signature_bytes)
+}
+
 pub fn public_key_to_address(public_key_bytes: &Vec<u8>) -> Result<Address, String> {
     let pubkey = VerifyingKey::from_sec1_bytes(&public_key_bytes)
         .map_err(|e| format!("invalid public key: {}", e))?;
@@ -90,3 +126,57 @@ pub fn public_key_from_signed_message(
     let public_key_bytes = public_key.serialize().to_vec();
     Ok(public_key_bytes)
 }
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::eip191_hash_message;
+
+    use super::*;
+
+    #[test]
+    fn it_signs_message_digests() {
+        let signer = secret_key_to_secret_key_signer(&vec![7u8; 32]).unwrap();
+        let message = "hello txtx";
+
+        let signature = sign_message_digest(&signer, &eip191_hash_message(message)[..]).unwrap();
+        assert_eq!(signature.len(), 65);
+        assert!(signature[64] == 27 || signature[64] == 28);
+
+        let public_key = public_key_from_signed_message(message, &hex::encode(&signature)).unwrap();
+        assert_eq!(public_key_to_address(&public_key).unwrap(), signer.address());
+    }
+
+    #[test]
+    fn it_reports_keystore_errors() {
+        let dir = std::env::temp_dir().join(format!("txtx-keystore-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let secret_key = vec![7u8; 32];
+        eth_keystore::encrypt_key(
+            &dir,
+            &mut rand::thread_rng(),
+            &secret_key,
+            "password",
+            Some("deployer.json"),
+        )
+        .unwrap();
+        let keystore_path = dir.join("deployer.json").to_string_lossy().to_string();
+
+        let signer = keystore_to_secret_key_signer(&keystore_path, "password").unwrap();
+        let expected_signer = secret_key_to_secret_key_signer(&secret_key).unwrap();
+        assert_eq!(signer.address(), expected_signer.address());
+
+        let err = keystore_to_secret_key_signer(&keystore_path, "wrong").unwrap_err();
+        assert_eq!(err, format!("unable to decrypt keystore '{keystore_path}': invalid password"));
+
+        let malformed_path = dir.join("malformed.json").to_string_lossy().to_string();
+        std::fs::write(&malformed_path, "not a keystore").unwrap();
+        let err = keystore_to_secret_key_signer(&malformed_path, "password").unwrap_err();
+        assert!(err.starts_with(&format!("malformed keystore '{malformed_path}': invalid JSON")));
+
+        let missing_path = dir.join("missing.json").to_string_lossy().to_string();
+        let err = keystore_to_secret_key_signer(&missing_path, "password").unwrap_err();
+        assert!(err.starts_with(&format!("unable to read keystore '{missing_path}'")));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -25,6 +25,7 @@ use alloy_primitives::{Address, TxKind, U256};
 use alloy_rpc_types::TransactionRequest;
 use alloy_rpc_types::{AccessList, Log};
 use contract_deployment::AddressAbiMap;
+use txtx_addon_kit::bigdecimal::BigDecimal;
 use txtx_addon_kit::types::diagnostics::Diagnostic;
 use txtx_addon_kit::types::stores::ValueStore;
 use txtx_addon_kit::types::types::{ObjectType, Value};
@@ -60,7 +61,7 @@ pub struct CommonTransactionFields {
     pub from: Value,
     pub nonce: Option<u64>,
     pub chain_id: u64,
-    pub amount: u64,
+    pub amount: u128,
     pub gas_limit: Option<u64>,
     pub input: Option<Vec<u8>>,
     pub tx_type: TransactionType,
@@ -72,7 +73,7 @@ struct FilledCommonTransactionFields {
     pub from: Address,
     pub nonce: u64,
     pub chain_id: u64,
-    pub amount: u64,
+    pub amount: u128,
     pub gas_limit: Option<u64>,
     pub input: Option<Vec<u8>>,
     pub deploy_code: Option<Vec<u8>>,
@@ -358,32 +359,19 @@ pub fn value_to_primitive_abi_type(
     let type_specifier = TypeSpecifier::try_from(param.ty.as_str())
         .map_err(|e| diagnosed_error!("{msg}: failed to parse type specifier: {e}"))?;
 
+    let uint = |value: &Value| match value.as_decimal() {
+        Some(decimal) => decimal_to_u256(decimal).map_err(|e| diagnosed_error!("{msg}: {e}")),
+        None => U256::try_from_be_slice(&value.to_be_bytes()).ok_or(diagnosed_error!("{msg}")),
+    };
+
     let sol_value = match type_specifier.stem.span() {
         "address" => DynSolValue::Address(EvmValue::to_address(value)?),
-        "uint8" => DynSolValue::Uint(
-            U256::try_from_be_slice(&value.to_be_bytes()).ok_or(diagnosed_error!("{msg}"))?,
-            8,
-        ),
-        "uint16" => DynSolValue::Uint(
-            U256::try_from_be_slice(&value.to_be_bytes()).ok_or(diagnosed_error!("{msg}"))?,
-            16,
-        ),
-        "uint32" => DynSolValue::Uint(
-            U256::try_from_be_slice(&value.to_be_bytes()).ok_or(diagnosed_error!("{msg}"))?,
-            32,
-        ),
-        "uint64" => DynSolValue::Uint(
-            U256::try_from_be_slice(&value.to_be_bytes()).ok_or(diagnosed_error!("{msg}"))?,
-            64,
-        ),
-        "uint96" => DynSolValue::Uint(
-            U256::try_from_be_slice(&value.to_be_bytes()).ok_or(diagnosed_error!("{msg}"))?,
-            96,
-        ),
-        "uint256" => DynSolValue::Uint(
-            U256::try_from_be_slice(&value.to_be_bytes()).ok_or(diagnosed_error!("{msg}"))?,
-            256,
-        ),
+        "uint8" => DynSolValue::Uint(uint(value)?, 8),
+        "uint16" => DynSolValue::Uint(uint(value)?, 16),
+        "uint32" => DynSolValue::Uint(uint(value)?, 32),
+        "uint64" => DynSolValue::Uint(uint(value)?, 64),
+        "uint96" => DynSolValue::Uint(uint(value)?, 96),
+        "uint256" => DynSolValue::Uint(uint(value)?, 256),
         "bytes" => DynSolValue::Bytes(value.to_be_bytes()),
         "bytes32" => DynSolValue::FixedBytes(Word::from_slice(&value.to_be_bytes()), 32),
         "bool" => DynSolValue::Bool(value.as_bool().ok_or(diagnosed_error!("{msg}"))?),
@@ -480,31 +468,62 @@ pub fn value_to_struct_abi_type(value: &Value, param: &Param) -> Result<DynSolVa
     Ok(sol_value)
 }
 
+/// Converts a decimal to a `U256`, failing instead of truncating its fractional part.
+pub fn decimal_to_u256(decimal: &BigDecimal) -> Result<U256, String> {
+    if !decimal.is_integer() {
+        return Err(format!(
+            "decimal {} cannot be converted to an integer without truncation",
+            decimal
+        ));
+    }
+    U256::from_str_radix(&decimal.with_scale(0).to_plain_string(), 10)
+        .map_err(|e| format!("invalid uint256 {}: {}", decimal, e))
+}
+
 pub fn value_to_sol_value(value: &Value) -> Result<DynSolValue, String> {
     let sol_value = match value {
         Value::Bool(value) => DynSolValue::Bool(value.clone()),
         Value::Integer(value) => DynSolValue::Uint(U256::from(*value), 256),
         Value::String(value) => DynSolValue::String(value.clone()),
-        Value::Float(_value) => todo!(),
         Value::Buffer(bytes) => DynSolValue::Bytes(bytes.clone()),
-        Value::Null => {
-            todo!()
+        Value::Decimal(value) => DynSolValue::Uint(decimal_to_u256(value)?, 256),
+        // solidity timestamps are uint256 seconds since the Unix epoch, as in `block.timestamp`
+        Value::DateTime(value) => {
+            let secs = u64::try_from(value.timestamp())
+                .map_err(|_| format!("datetime {} is before the Unix epoch", value))?;
+            DynSolValue::Uint(U256::from(secs), 256)
+        }
+        Value::Float(_) | Value::Null | Value::Object(_) => {
+            return Err(format!(
+                "unsupported type for encoding Solidity value: {}",
+                value.get_type().to_string()
+            ))
         }
-        Value::Object(_) => todo!(),
         Value::Array(values) => DynSolValue::Array(
             values.iter().map(value_to_sol_value).collect::<Result<Vec<_>, _>>()?,
         ),
         Value::Addon(addon) => {
+            let uint = |bits: usize| {
+                U256::try_from_be_slice(&addon.bytes)
+                    .map(|value| DynSolValue::Uint(value, bits))
+                    .ok_or(format!("expected at most 32 bytes for {}", addon.id))
+            };
             if addon.id == EVM_ADDRESS {
-                DynSolValue::Address(Address::from_slice(&addon.bytes))
+                let bytes: [u8; 20] = addon.bytes[..].try_into().map_err(|_| {
+                    format!("expected 20 bytes for address, got {}", addon.bytes.len())
+                })?;
+                DynSolValue::Address(Address::from(bytes))
             } else if addon.id == EVM_BYTES32 {
-                DynSolValue::FixedBytes(Word::from_slice(&addon.bytes), 32)
+                let bytes: [u8; 32] = addon.bytes[..].try_into().map_err(|_| {
+                    format!("expected 32 bytes for bytes32, got {}", addon.bytes.len())
+                })?;
+                DynSolValue::FixedBytes(Word::from(bytes), 32)
             } else if addon.id == EVM_UINT256 {
-                DynSolValue::Uint(U256::from_be_slice(&addon.bytes), 256)
+                uint(256)?
             } else if addon.id == EVM_UINT32 {
-                DynSolValue::Uint(U256::from_be_slice(&addon.bytes), 32)
+                uint(32)?
             } else if addon.id == EVM_UINT8 {
-                DynSolValue::Uint(U256::from_be_slice(&addon.bytes), 8)
+                uint(8)?
             } else if addon.id == EVM_BYTES
                 || addon.id == EVM_INIT_CODE
                 || addon.id == EVM_FUNCTION_CALL
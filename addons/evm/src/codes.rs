@@ -0,0 +1,55 @@
+use txtx_addon_kit::types::diagnostics::{Diagnostic, DiagnosticCode};
+
+use crate::rpc::RpcError;
+
+pub const TRANSACTION_BROADCAST_FAILED: DiagnosticCode =
+    DiagnosticCode::new("EVM200", "transaction broadcast failed");
+pub const NONCE_TOO_LOW: DiagnosticCode = DiagnosticCode::new("EVM201", "nonce too low")
+    .with_help_url("https://docs.txtx.sh/errors/evm201");
+pub const INSUFFICIENT_FUNDS: DiagnosticCode =
+    DiagnosticCode::new("EVM202", "insufficient funds for gas and value")
+        .with_help_url("https://docs.txtx.sh/errors/evm202");
+pub const REPLACEMENT_UNDERPRICED: DiagnosticCode =
+    DiagnosticCode::new("EVM203", "replacement transaction underpriced");
+pub const INTRINSIC_GAS_TOO_LOW: DiagnosticCode =
+    DiagnosticCode::new("EVM204", "intrinsic gas too low");
+pub const FEE_CAP_TOO_LOW: DiagnosticCode =
+    DiagnosticCode::new("EVM205", "max fee per gas less than block base fee");
+pub const TRANSACTION_ALREADY_KNOWN: DiagnosticCode =
+    DiagnosticCode::new("EVM206", "transaction already known");
+
+pub const CODES: &[DiagnosticCode] = &[
+    TRANSACTION_BROADCAST_FAILED,
+    NONCE_TOO_LOW,
+    INSUFFICIENT_FUNDS,
+    REPLACEMENT_UNDERPRICED,
+    INTRINSIC_GAS_TOO_LOW,
+    FEE_CAP_TOO_LOW,
+    TRANSACTION_ALREADY_KNOWN,
+];
+
+/// Classifies the error returned by a node rejecting a transaction. Nodes only report these
+/// failures as messages, whose wording is shared by geth and most of its forks.
+pub fn classify_broadcast_error(message: &str) -> DiagnosticCode {
+    let message = message.to_lowercase();
+    if message.contains("nonce too low") {
+        NONCE_TOO_LOW
+    } else if message.contains("insufficient funds") {
+        INSUFFICIENT_FUNDS
+    } else if message.contains("replacement transaction underpriced") {
+        REPLACEMENT_UNDERPRICED
+    } else if message.contains("intrinsic gas too low") {
+        INTRINSIC_GAS_TOO_LOW
+    } else if message.contains("max fee per gas less than block base fee") {
+        FEE_CAP_TOO_LOW
+    } else if message.contains("already known") {
+        TRANSACTION_ALREADY_KNOWN
+    } else {
+        TRANSACTION_BROADCAST_FAILED
+    }
+}
+
+pub fn broadcast_error_diagnostic(error: RpcError) -> Diagnostic {
+    let message = error.to_string();
+    diagnosed_error!("{}", message).with_diagnostic_code(&classify_broadcast_error(&message))
+}
@@ -1,7 +1,7 @@
 use txtx_addon_kit::types::cloud_interface::CloudServiceContext;
 use txtx_addon_kit::types::commands::{CommandExecutionFutureResult, PreCommandSpecification};
-use txtx_addon_kit::types::frontend::LogDispatcher;
 use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
+use txtx_addon_kit::types::frontend::{LogDispatcher, LogProgress};
 use txtx_addon_kit::types::stores::ValueStore;
 use txtx_addon_kit::types::types::RunbookSupervisionContext;
 use txtx_addon_kit::types::ConstructDid;
@@ -274,15 +274,14 @@ impl CommandImplementation for CheckEvmConfirmations {
                     let block = rpc.get_block_number().await.unwrap_or(current_block);
                     // only send updates when the mined block is actually updated, so we're not spamming with updates every 500ms
                     if previous_block != block {
-                        let _ = logger.pending_info(
+                        let confirmed_blocks = current_block - tx_inclusion_block;
+                        let _ = logger.pending_progress(
                             "Pending",
                             format!(
                                 "{}/{} blocks confirmed for Tx 0x{} on chain {}",
-                                current_block - tx_inclusion_block,
-                                confirmations_required,
-                                tx_hash,
-                                chain_name
+                                confirmed_blocks, confirmations_required, tx_hash, chain_name
                             ),
+                            LogProgress::steps(confirmed_blocks, confirmations_required as u64),
                         );
                         previous_block = block.clone();
                     }
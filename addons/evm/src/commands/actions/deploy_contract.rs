@@ -665,7 +665,7 @@ pub struct ContractDeploymentTransactionRequestBuilder {
     rpc: EvmRpc,
     chain_id: u64,
     from_address: Address,
-    amount: u64,
+    amount: u128,
     gas_limit: Option<u64>,
     signer_starting_nonce: u64,
     tx_type: TransactionType,
@@ -42,7 +42,7 @@ lazy_static! {
             },
             contract_address: {
                 documentation: "The address of the contract being called.",
-                typing: Type::addon(EVM_ADDRESS),
+                typing: Type::one_of(vec![Type::string(), Type::addon(EVM_ADDRESS), Type::buffer()]),
                 optional: false,
                 tainting: true,
                 internal: false
@@ -13,13 +13,17 @@ pub mod check_confirmations;
 pub mod deploy_contract;
 pub mod eth_call;
 pub mod send_eth;
+pub mod sign_message;
 pub mod sign_transaction;
+pub mod sign_typed_data;
 
 use call_contract::SIGN_EVM_CONTRACT_CALL;
 use deploy_contract::DEPLOY_CONTRACT;
 use eth_call::ETH_CALL;
 use send_eth::SEND_ETH;
+use sign_message::SIGN_MESSAGE;
 use sign_transaction::SIGN_TRANSACTION;
+use sign_typed_data::SIGN_TYPED_DATA;
 
 use crate::constants::{GAS_LIMIT, NONCE, SIGNER, TRANSACTION_AMOUNT};
 use crate::typing::EvmValue;
@@ -30,6 +34,8 @@ lazy_static! {
         ETH_CALL.clone(),
         CHECK_CONFIRMATIONS.clone(),
         SIGN_TRANSACTION.clone(),
+        SIGN_MESSAGE.clone(),
+        SIGN_TYPED_DATA.clone(),
         SEND_ETH.clone(),
         DEPLOY_CONTRACT.clone(),
     ];
@@ -41,8 +47,15 @@ pub fn get_expected_address(value: &Value) -> Result<Address, String> {
 
 pub fn get_common_tx_params_from_args(
     args: &ValueStore,
-) -> Result<(u64, Option<u64>, Option<u64>), String> {
-    let amount = args.get_uint(TRANSACTION_AMOUNT)?.unwrap_or(0);
+) -> Result<(u128, Option<u64>, Option<u64>), String> {
+    // amounts in wei commonly exceed u64, and can be provided as decimals (e.g. `1e27`)
+    let amount = match args.get_value(TRANSACTION_AMOUNT) {
+        Some(value) => value
+            .as_u128()
+            .ok_or(format!("'{}' must be a positive integer", TRANSACTION_AMOUNT))?
+            .map_err(|e| format!("invalid '{}': {}", TRANSACTION_AMOUNT, e))?,
+        None => 0,
+    };
     let gas_limit = args.get_uint(GAS_LIMIT)?;
     let nonce = args.get_uint(NONCE)?;
     Ok((amount, gas_limit, nonce))
@@ -63,13 +63,13 @@ lazy_static! {
                 },
                 recipient_address: {
                     documentation: "The EVM address of the recipient.",
-                    typing: Type::addon(EVM_ADDRESS),
+                    typing: Type::one_of(vec![Type::string(), Type::addon(EVM_ADDRESS), Type::buffer()]),
                     optional: false,
                     tainting: true,
                     internal: false
                 },
                 amount: {
-                    documentation: "The amount to send, in WEI (1 ETH = 10^18 WEI).",
+                    documentation: "The amount to send, in WEI (1 ETH = 10^18 WEI). Decimals such as `1e27` are accepted, as long as they have no fractional part.",
                     typing: Type::integer(),
                     optional: true,
                     tainting: true,
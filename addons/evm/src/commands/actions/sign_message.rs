@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use alloy_primitives::eip191_hash_message;
+use txtx_addon_kit::constants::{DESCRIPTION, META_DESCRIPTION, SIGNED_MESSAGE_BYTES};
+use txtx_addon_kit::types::commands::{
+    CommandExecutionResult, CommandImplementation, CommandSpecification, PreCommandSpecification,
+};
+use txtx_addon_kit::types::diagnostics::Diagnostic;
+use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, return_synchronous_ok, SignerActionsFutureResult,
+    SignerCapabilities, SignerInstance, SignerSignFutureResult, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type, Value};
+use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+
+use crate::constants::{MESSAGE, MESSAGE_BYTES};
+
+use super::get_signer_did;
+
+lazy_static! {
+    pub static ref SIGN_MESSAGE: PreCommandSpecification = define_command! {
+      SignEvmMessage => {
+          name: "Sign EVM Message",
+          matcher: "sign_message",
+          documentation: "The `evm::sign_message` command signs a message, prefixed as specified by EIP-191 (`personal_sign`).",
+          implements_signing_capability: true,
+          implements_background_task_capability: false,
+          inputs: [
+            description: {
+                documentation: "A description of the message",
+                typing: Type::string(),
+                optional: true,
+                tainting: false,
+                internal: false
+            },
+            message: {
+                documentation: "The message to sign.",
+                typing: Type::string(),
+                optional: false,
+                tainting: true,
+                internal: false
+            },
+            signer: {
+                documentation: "A reference to a signer construct, which will be used to sign the message.",
+                typing: Type::string(),
+                optional: false,
+                tainting: true,
+                internal: false
+            }
+          ],
+          outputs: [
+              signed_message_bytes: {
+                  documentation: "The signature of the message, encoded as `r || s || v`.",
+                  typing: Type::buffer()
+              }
+          ],
+          example: txtx_addon_kit::indoc! {r#"
+          action "greeting" "evm::sign_message" {
+              message = "Hello, txtx!"
+              signer = signer.operator
+          }
+      "#},
+          required_signer_capabilities: SignerCapabilities {
+              sign_message: true,
+              ..SignerCapabilities::none()
+          },
+      }
+    };
+}
+
+pub struct SignEvmMessage;
+impl CommandImplementation for SignEvmMessage {
+    fn check_instantiability(
+        _ctx: &CommandSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn check_signed_executability(
+        construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &CommandSpecification,
+        values: &ValueStore,
+        supervision_context: &RunbookSupervisionContext,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        signers: SignersState,
+        auth_ctx: &AuthorizationContext,
+    ) -> SignerActionsFutureResult {
+        let message = values.get_expected_string(MESSAGE).map(|m| m.to_string());
+        check_message_signability(
+            construct_did,
+            instance_name,
+            values,
+            supervision_context,
+            signers_instances,
+            signers,
+            auth_ctx,
+            message.map(|message| (eip191_hash_message(&message).to_vec(), Value::string(message))),
+        )
+    }
+
+    fn run_signed_execution(
+        construct_did: &ConstructDid,
+        _spec: &CommandSpecification,
+        values: &ValueStore,
+        _progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        signers: SignersState,
+        _auth_context: &AuthorizationContext,
+    ) -> SignerSignFutureResult {
+        sign_message_digest(construct_did, values, signers_instances, signers)
+    }
+}
+
+/// Hands the digest of a message over to the signer of a message signing command, which keeps it
+/// in its state for the signing step. `message` is the digest to sign, along with the message
+/// displayed to the reviewer.
+pub(super) fn check_message_signability(
+    construct_did: &ConstructDid,
+    instance_name: &str,
+    values: &ValueStore,
+    supervision_context: &RunbookSupervisionContext,
+    signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    mut signers: SignersState,
+    auth_ctx: &AuthorizationContext,
+    message: Result<(Vec<u8>, Value), Diagnostic>,
+) -> SignerActionsFutureResult {
+    let signer_did = get_signer_did(values).unwrap();
+    let signer = signers_instances.get(&signer_did).unwrap();
+    let mut signer_state = signers.pop_signer_state(&signer_did).unwrap();
+    if signer_state.get_scoped_value(&construct_did.to_string(), SIGNED_MESSAGE_BYTES).is_some() {
+        return return_synchronous_actions(Ok((signers, signer_state, Actions::none())));
+    }
+
+    let (digest, displayed_message) =
+        message.map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+    signer_state.insert_scoped_value(
+        &construct_did.to_string(),
+        MESSAGE_BYTES,
+        Value::buffer(digest),
+    );
+
+    let description = values.get_expected_string(DESCRIPTION).ok().map(|d| d.to_string());
+    let meta_description = values.get_expected_string(META_DESCRIPTION).ok().map(|d| d.to_string());
+    let markdown = values
+        .get_markdown(auth_ctx)
+        .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+
+    let res = (signer.specification.check_signability)(
+        construct_did,
+        instance_name,
+        &description,
+        &meta_description,
+        &markdown,
+        &displayed_message,
+        &signer.specification,
+        values,
+        signer_state,
+        signers,
+        signers_instances,
+        supervision_context,
+        auth_ctx,
+    );
+    return_synchronous_actions(res)
+}
+
+/// Signs the digest stored by [check_message_signability] with the signer of a message signing
+/// command. The signature is output as `signed_message_bytes`.
+pub(super) fn sign_message_digest(
+    construct_did: &ConstructDid,
+    values: &ValueStore,
+    signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    mut signers: SignersState,
+) -> SignerSignFutureResult {
+    let signer_did = get_signer_did(values).unwrap();
+    let signer_state = signers.pop_signer_state(&signer_did).unwrap();
+
+    if let Some(signature) =
+        signer_state.get_scoped_value(&construct_did.to_string(), SIGNED_MESSAGE_BYTES)
+    {
+        let mut result = CommandExecutionResult::new();
+        result.outputs.insert(SIGNED_MESSAGE_BYTES.into(), signature.clone());
+        return return_synchronous_ok(signers, signer_state, result);
+    }
+
+    let signer = signers_instances.get(&signer_did).unwrap();
+    let payload = signer_state
+        .get_expected_scoped_value(&construct_did.to_string(), MESSAGE_BYTES)
+        .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?
+        .clone();
+    let title = values.get_expected_string(DESCRIPTION).unwrap_or("New Message");
+
+    (signer.specification.sign)(
+        construct_did,
+        title,
+        &payload,
+        &signer.specification,
+        values,
+        signer_state,
+        signers,
+        signers_instances,
+    )
+}
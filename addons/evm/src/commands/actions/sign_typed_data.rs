@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use alloy_dyn_abi::TypedData;
+use txtx_addon_kit::types::commands::{
+    CommandImplementation, CommandSpecification, PreCommandSpecification,
+};
+use txtx_addon_kit::types::diagnostics::Diagnostic;
+use txtx_addon_kit::types::frontend::BlockEvent;
+use txtx_addon_kit::types::signers::{
+    SignerActionsFutureResult, SignerCapabilities, SignerInstance, SignerSignFutureResult,
+    SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type, Value};
+use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+
+use crate::constants::TYPED_DATA;
+
+use super::sign_message::{check_message_signability, sign_message_digest};
+
+lazy_static! {
+    pub static ref SIGN_TYPED_DATA: PreCommandSpecification = define_command! {
+      SignEvmTypedData => {
+          name: "Sign EVM Typed Data",
+          matcher: "sign_typed_data",
+          documentation: "The `evm::sign_typed_data` command signs structured data, as specified by EIP-712 (`eth_signTypedData_v4`).",
+          implements_signing_capability: true,
+          implements_background_task_capability: false,
+          inputs: [
+            description: {
+                documentation: "A description of the typed data",
+                typing: Type::string(),
+                optional: true,
+                tainting: false,
+                internal: false
+            },
+            typed_data: {
+                documentation: "The typed data to sign, as the JSON object accepted by `eth_signTypedData_v4`: `types`, `primaryType`, `domain` and `message`.",
+                typing: Type::string(),
+                optional: false,
+                tainting: true,
+                internal: false
+            },
+            signer: {
+                documentation: "A reference to a signer construct, which will be used to sign the typed data.",
+                typing: Type::string(),
+                optional: false,
+                tainting: true,
+                internal: false
+            }
+          ],
+          outputs: [
+              signed_message_bytes: {
+                  documentation: "The signature of the typed data, encoded as `r || s || v`.",
+                  typing: Type::buffer()
+              }
+          ],
+          example: txtx_addon_kit::indoc! {r#"
+          action "permit" "evm::sign_typed_data" {
+              typed_data = file("./permit.json")
+              signer = signer.operator
+          }
+      "#},
+          required_signer_capabilities: SignerCapabilities {
+              sign_typed_data: true,
+              ..SignerCapabilities::none()
+          },
+      }
+    };
+}
+
+pub struct SignEvmTypedData;
+impl CommandImplementation for SignEvmTypedData {
+    fn check_instantiability(
+        _ctx: &CommandSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn check_signed_executability(
+        construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &CommandSpecification,
+        values: &ValueStore,
+        supervision_context: &RunbookSupervisionContext,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        signers: SignersState,
+        auth_ctx: &AuthorizationContext,
+    ) -> SignerActionsFutureResult {
+        let typed_data = values.get_expected_string(TYPED_DATA).and_then(|json| {
+            let typed_data: TypedData = serde_json::from_str(json)
+                .map_err(|e| diagnosed_error!("invalid '{}': {}", TYPED_DATA, e))?;
+            let digest = typed_data
+                .eip712_signing_hash()
+                .map_err(|e| diagnosed_error!("unable to hash '{}': {}", TYPED_DATA, e))?;
+            Ok((digest.to_vec(), Value::string(json.to_string())))
+        });
+        check_message_signability(
+            construct_did,
+            instance_name,
+            values,
+            supervision_context,
+            signers_instances,
+            signers,
+            auth_ctx,
+            typed_data,
+        )
+    }
+
+    fn run_signed_execution(
+        construct_did: &ConstructDid,
+        _spec: &CommandSpecification,
+        values: &ValueStore,
+        _progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        signers: SignersState,
+        _auth_context: &AuthorizationContext,
+    ) -> SignerSignFutureResult {
+        sign_message_digest(construct_did, values, signers_instances, signers)
+    }
+}
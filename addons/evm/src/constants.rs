@@ -12,6 +12,7 @@ pub const OUT_DIR: &str = env!("OUT_DIR");
 pub const NAMESPACE: &str = "evm";
 
 pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+pub const DERIVATION_PATH_TEMPLATE: &str = "m/44'/60'/0'/0/{account_index}";
 
 // Signer attached storage keys
 pub const CHECKED_PUBLIC_KEY: &str = "checked_public_key";
@@ -21,6 +22,11 @@ pub const EXPECTED_ADDRESS: &str = "expected_address";
 pub const CHECKED_COST_PROVISION: &str = "checked_costs";
 pub const FETCHED_BALANCE: &str = "fetched_balance";
 pub const FETCHED_NONCE: &str = "fetched_nonce";
+pub const KMS_PROVIDER: &str = "kms_provider";
+pub const KMS_KEY_ID: &str = "kms_key_id";
+pub const KMS_REGION: &str = "kms_region";
+pub const KMS_PUBLIC_KEY: &str = "kms_public_key";
+pub const KMS_ENDPOINT: &str = "kms_endpoint";
 
 // Signers
 pub const PUBLIC_KEYS: &str = "public_keys";
@@ -39,6 +45,14 @@ pub const RPC_API_URL: &str = "rpc_api_url";
 pub const BLOCK_EXPLORER_API_KEY: &str = "block_explorer_api_key";
 pub const TRANSACTION_TO: &str = "to";
 pub const SIGNER: &str = "signer";
+pub const MESSAGE: &str = "message";
+pub const TYPED_DATA: &str = "typed_data";
+pub const KEYSTORE_PATH: &str = "keystore_path";
+pub const PASSWORD: &str = "password";
+pub const KEY_ID: &str = "key_id";
+pub const REGION: &str = "region";
+pub const KEY_NAME: &str = "key_name";
+pub const ENDPOINT: &str = "endpoint";
 pub const TRANSACTION_AMOUNT: &str = "amount";
 pub const TRANSACTION_TYPE: &str = "type";
 pub const NONCE: &str = "nonce";
@@ -95,8 +109,10 @@ pub const ACTION_ITEM_CHECK_ADDRESS: &str = "check_address";
 pub const ACTION_ITEM_CHECK_NONCE: &str = "check_nonce";
 pub const ACTION_ITEM_CHECK_FEE: &str = "check_fee";
 pub const ACTION_ITEM_PROVIDE_PUBLIC_KEY: &str = "provide_public_key";
+pub const ACTION_ITEM_PROVIDE_PASSWORD: &str = "provide_password";
 pub const ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION: &str = "provide_signed_transaction";
 pub const ACTION_ITEM_SEND_TRANSACTION: &str = "send_transaction";
+pub const ACTION_ITEM_WALLET_INSTRUCTIONS: &str = "wallet_instructions";
 pub const ACTION_OPEN_MODAL: &str = "open_modal";
 
 // Default contracts
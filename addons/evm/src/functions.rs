@@ -1022,18 +1022,21 @@ impl FunctionImplementation for CreateInitCode {
             other => return Err(format_fn_error(&prefix, 2, "array", other)),
         };
 
-        let linked_libraries = args
-            .get(2)
-            .map(|lib| {
-                let lib = lib.as_object().unwrap();
+        let linked_libraries = match args.get(2) {
+            None => None,
+            Some(Value::Object(lib)) => Some(
                 lib.iter()
                     .map(|(k, v)| EvmValue::to_address(v).map(|a| (k.clone(), a)))
                     .collect::<Result<IndexMap<String, Address>, _>>()
-            })
-            .transpose()
-            .map_err(|d| {
-                to_diag(fn_spec, format!("each entry of a linked library must be an address: {d}"))
-            })?;
+                    .map_err(|d| {
+                        to_diag(
+                            fn_spec,
+                            format!("each entry of a linked library must be an address: {d}"),
+                        )
+                    })?,
+            ),
+            other => return Err(format_fn_error(&prefix, 3, "object", other)),
+        };
         let init_code = create_init_code(bytecode, Some(constructor_args), &None, linked_libraries)
             .map_err(|e| diagnosed_error!("{}: {}", prefix, e))?;
         Ok(EvmValue::init_code(init_code))
@@ -1152,3 +1155,28 @@ fn format_fn_error(ctx: &str, position: u64, expected: &str, actual: Option<&Val
         actual.and_then(|v| Some(v.get_type()))
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use txtx_addon_kit::types::types::{Type, Value};
+
+    use super::FUNCTIONS;
+    use crate::typing::EVM_ADDRESS;
+
+    #[test]
+    fn it_accepts_addresses_as_strings_in_address_arrays() {
+        let spec = FUNCTIONS.iter().find(|f| f.name == "encode_function_call").unwrap();
+        let input = spec.inputs.iter().find(|i| i.name == "function_args").unwrap();
+        let typing =
+            input.typing.iter().find(|t| **t == Type::array(Type::addon(EVM_ADDRESS))).unwrap();
+
+        let addresses = Value::array(vec![
+            Value::string("0xCe9A0D2bd7b6a0bb3a0bbDA8bA1D1d66e0a07A55".into()),
+            Value::string("0x5FbDB2315678afecb367f032d93F642f64180aa3".into()),
+        ]);
+        assert!(typing.check_value(&addresses).is_ok());
+        assert!(typing
+            .check_value(&Value::string("0x5FbDB2315678afecb367f032d93F642f64180aa3".into()))
+            .is_err());
+    }
+}
@@ -8,19 +8,21 @@ extern crate txtx_addon_kit;
 extern crate serde_derive;
 
 mod codec;
+pub mod codes;
 mod commands;
 #[allow(dead_code)]
 mod constants;
 mod functions;
 pub mod rpc;
 mod signers;
+pub mod templates;
 mod typing;
 
 use constants::NAMESPACE;
 use txtx_addon_kit::{
     types::{
-        commands::PreCommandSpecification, functions::FunctionSpecification,
-        signers::SignerSpecification,
+        commands::PreCommandSpecification, diagnostics::DiagnosticCode,
+        functions::FunctionSpecification, signers::SignerSpecification, types::Value,
     },
     Addon,
 };
@@ -62,4 +64,12 @@ impl Addon for EvmNetworkAddon {
     fn get_signers(&self) -> Vec<SignerSpecification> {
         signers::WALLETS.clone()
     }
+
+    fn get_diagnostic_codes(&self) -> Vec<DiagnosticCode> {
+        codes::CODES.to_vec()
+    }
+
+    fn format_display(&self, value: &Value) -> Option<String> {
+        typing::EvmValue::format_display(value)
+    }
 }
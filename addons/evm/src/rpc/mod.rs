@@ -125,6 +125,15 @@ impl EvmRpc {
         Ok(Self { url, provider })
     }
 
+    pub async fn send_tx_envelope(&self, tx_envelope: TxEnvelope) -> Result<[u8; 32], RpcError> {
+        let pending_tx = self
+            .provider
+            .send_tx_envelope(tx_envelope)
+            .await
+            .map_err(|e| RpcError::Message(format!("failed to send transaction: {e}")))?;
+        Ok(pending_tx.tx_hash().0)
+    }
+
     pub async fn get_chain_id(&self) -> Result<u64, RpcError> {
         self.provider
             .get_chain_id()
@@ -1,7 +1,10 @@
 use alloy_primitives::{utils::format_units, Address};
 use txtx_addon_kit::{
+    constants::SIGNED_MESSAGE_BYTES,
     indexmap::IndexMap,
     types::{
+        commands::CommandExecutionResult,
+        diagnostics::Diagnostic,
         frontend::{
             ActionItemRequest, ActionItemRequestType, ActionItemStatus, ProvidePublicKeyRequest,
             ReviewInputRequest,
@@ -13,13 +16,33 @@ use txtx_addon_kit::{
 };
 
 use crate::{
+    codec::crypto::{sign_message_digest, SecretKeySigner},
     constants::{
         ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_CHECK_BALANCE, ACTION_ITEM_PROVIDE_PUBLIC_KEY,
-        DEFAULT_MESSAGE, NAMESPACE,
+        DEFAULT_MESSAGE, MESSAGE_BYTES, NAMESPACE,
     },
     rpc::EvmRpc,
 };
 
+/// Signs the message digest left in the signer state by `evm::sign_message` and
+/// `evm::sign_typed_data`, when `caller_uuid` is one of these commands. Returns `None` for the
+/// other commands, which sign transactions.
+pub fn sign_message_in_signer_state(
+    caller_uuid: &ConstructDid,
+    signer_state: &ValueStore,
+    secret_key_signer: impl FnOnce() -> Result<SecretKeySigner, Diagnostic>,
+) -> Option<Result<CommandExecutionResult, Diagnostic>> {
+    let digest = signer_state.get_scoped_value(&caller_uuid.to_string(), MESSAGE_BYTES)?;
+    let res = secret_key_signer().and_then(|signer| {
+        let signature = sign_message_digest(&signer, &digest.to_be_bytes())
+            .map_err(|e| diagnosed_error!("{e}"))?;
+        let mut result = CommandExecutionResult::new();
+        result.outputs.insert(SIGNED_MESSAGE_BYTES.into(), Value::buffer(signature));
+        Ok(result)
+    });
+    Some(res)
+}
+
 pub async fn get_additional_actions_for_address(
     expected_address: &Option<Address>,
     signer_did: &ConstructDid,
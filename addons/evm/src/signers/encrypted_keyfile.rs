@@ -0,0 +1,255 @@
+use alloy_primitives::Address;
+use std::collections::HashMap;
+use txtx_addon_kit::channel;
+use txtx_addon_kit::crypto::keyfile::{unlock_signer_keyfile, UnlockedKeyfile};
+use txtx_addon_kit::types::commands::CommandExecutionResult;
+use txtx_addon_kit::types::frontend::{Actions, BlockEvent, ReviewInputRequest};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, return_synchronous_result, CheckSignabilityOk, SignerActionErr,
+    SignerActionsFutureResult, SignerActivateFutureResult, SignerImplementation, SignerInstance,
+    SignerSignFutureResult, SignerSpecification, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::RunbookSupervisionContext;
+use txtx_addon_kit::types::AuthorizationContext;
+use txtx_addon_kit::types::{
+    diagnostics::Diagnostic,
+    types::{Type, Value},
+    ConstructDid,
+};
+
+use crate::codec::crypto::{field_bytes_to_secret_key_signer, secret_key_to_secret_key_signer};
+use crate::constants::{ACTION_ITEM_CHECK_ADDRESS, CHECKED_ADDRESS, EXPECTED_ADDRESS};
+use crate::signers::secret_key::EvmSecretKeySigner;
+use crate::typing::EvmValue;
+
+lazy_static! {
+    pub static ref EVM_ENCRYPTED_KEYFILE_SIGNER: SignerSpecification = define_signer! {
+        EvmEncryptedKeyfileSigner => {
+          name: "EVM Encrypted Keyfile Signer",
+          matcher: "encrypted_keyfile",
+          documentation:txtx_addon_kit::indoc! {r#"The `evm::encrypted_keyfile` signer can be used to synchronously sign a transaction with a secret key stored in a keyfile encrypted with a passphrase (scrypt and AES-256-GCM), as created by `txtx keyfile new`.
+          In supervised mode, the passphrase is requested in the supervisor. In unsupervised mode, the passphrase is read from the environment variable named by the `passphrase_env` input.
+          The keyfile is decrypted once, when the signer is activated: the passphrase is then discarded, and the decrypted key is only kept in memory for the duration of the run."#},
+          inputs: [
+            keyfile_path: {
+                documentation: "The path to the encrypted keyfile, relative to the txtx workspace.",
+                typing: Type::string(),
+                optional: false,
+                tainting: true,
+                sensitive: false
+            },
+            passphrase_env: {
+                documentation: "The name of the environment variable holding the passphrase of the keyfile. Required in unsupervised mode.",
+                typing: Type::string(),
+                optional: true,
+                tainting: false,
+                sensitive: false
+            },
+            expected_address: {
+                documentation: "The address the keyfile is expected to decrypt to. The signer fails to activate if the addresses don't match.",
+                typing: Type::string(),
+                optional: true,
+                tainting: true,
+                sensitive: false
+            }
+          ],
+          outputs: [
+              address: {
+                documentation: "The address of the account stored in the keyfile.",
+                typing: Type::string()
+              }
+          ],
+          example: txtx_addon_kit::indoc! {r#"
+            signer "deployer" "evm::encrypted_keyfile" {
+                keyfile_path = "./keys/deployer.json"
+                passphrase_env = "DEPLOYER_PASSPHRASE"
+                expected_address = "0x7a1A1cD5A1B4d4A0a8a2Fd3C9b6F1e2c7F5C8E9d"
+            }
+        "#}
+      }
+    };
+}
+
+pub struct EvmEncryptedKeyfileSigner;
+impl SignerImplementation for EvmEncryptedKeyfileSigner {
+    fn check_instantiability(
+        _ctx: &SignerSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn check_activability(
+        construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &txtx_addon_kit::types::AuthorizationContext,
+        _is_balance_check_required: bool,
+        _is_public_key_required: bool,
+    ) -> SignerActionsFutureResult {
+        use txtx_addon_kit::constants::DESCRIPTION;
+
+        let mut actions = Actions::none();
+
+        let description = values.get_string(DESCRIPTION).map(|d| d.to_string());
+        let markdown = values
+            .get_markdown(auth_ctx)
+            .map_err(|d| (signers.clone(), signer_state.clone(), d))?;
+
+        // the keyfile is decrypted once, its secret key being kept like the secret key signer's
+        let signer_field_bytes = match signer_state.get_expected_buffer_bytes("signer_field_bytes")
+        {
+            Ok(signer_field_bytes) => signer_field_bytes,
+            Err(_) => {
+                let unlocked = unlock_signer_keyfile(
+                    construct_did,
+                    instance_name,
+                    values,
+                    supervision_context,
+                    auth_ctx,
+                )
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+                let secret_key = match unlocked {
+                    UnlockedKeyfile::SecretKey(secret_key) => secret_key,
+                    UnlockedKeyfile::PassphraseRequested(actions) => {
+                        return return_synchronous_actions(Ok((signers, signer_state, actions)));
+                    }
+                };
+                let signer = secret_key_to_secret_key_signer(&secret_key).map_err(|_| {
+                    (
+                        signers.clone(),
+                        signer_state.clone(),
+                        diagnosed_error!(
+                            "signer '{instance_name}': keyfile does not contain a secp256k1 secret key"
+                        ),
+                    )
+                })?;
+                signer.to_field_bytes().to_vec()
+            }
+        };
+        let address: Address = field_bytes_to_secret_key_signer(&signer_field_bytes)
+            .map_err(|e| (signers.clone(), signer_state.clone(), diagnosed_error!("{e}")))?
+            .address();
+
+        if let Some(expected_address) = values.get_string(EXPECTED_ADDRESS) {
+            let expected_address = expected_address.parse::<Address>().map_err(|e| {
+                (
+                    signers.clone(),
+                    signer_state.clone(),
+                    diagnosed_error!("invalid expected address '{expected_address}': {e}"),
+                )
+            })?;
+            if expected_address != address {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!(
+                        "signer '{instance_name}': keyfile address {address} does not match expected address {expected_address}"
+                    ),
+                ));
+            }
+        }
+
+        signer_state.insert("signer_field_bytes", EvmValue::signer_field_bytes(signer_field_bytes));
+
+        if supervision_context.review_input_values {
+            if let Ok(_) = signer_state.get_expected_string(CHECKED_ADDRESS) {
+                signer_state.insert("signer_address", Value::string(address.to_string()));
+                signer_state.insert(CHECKED_ADDRESS, Value::string(address.to_string()));
+            } else {
+                actions.push_sub_group(
+                    None,
+                    vec![ReviewInputRequest::new("", &Value::string(address.to_string()))
+                        .to_action_type()
+                        .to_request(instance_name, ACTION_ITEM_CHECK_ADDRESS)
+                        .with_construct_did(construct_did)
+                        .with_some_description(description)
+                        .with_meta_description(&format!("Check {} expected address", instance_name))
+                        .with_some_markdown(markdown)],
+                );
+            }
+        } else {
+            signer_state.insert(CHECKED_ADDRESS, Value::string(address.to_string()));
+            signer_state.insert("signer_address", Value::string(address.to_string()));
+        }
+        return_synchronous_actions(Ok((signers, signer_state, actions)))
+    }
+
+    fn activate(
+        _construct_id: &ConstructDid,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _progress_tx: &channel::Sender<BlockEvent>,
+    ) -> SignerActivateFutureResult {
+        let mut result = CommandExecutionResult::new();
+        let address = signer_state
+            .get_expected_value("signer_address")
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        result.outputs.insert("address".into(), address.clone());
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+
+    fn check_signability(
+        construct_did: &ConstructDid,
+        title: &str,
+        description: &Option<String>,
+        meta_description: &Option<String>,
+        markdown: &Option<String>,
+        payload: &Value,
+        spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &AuthorizationContext,
+    ) -> Result<CheckSignabilityOk, SignerActionErr> {
+        EvmSecretKeySigner::check_signability(
+            construct_did,
+            title,
+            description,
+            meta_description,
+            markdown,
+            payload,
+            spec,
+            values,
+            signer_state,
+            signers,
+            signers_instances,
+            supervision_context,
+            auth_ctx,
+        )
+    }
+
+    fn sign(
+        caller_uuid: &ConstructDid,
+        title: &str,
+        payload: &Value,
+        spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    ) -> SignerSignFutureResult {
+        EvmSecretKeySigner::sign(
+            caller_uuid,
+            title,
+            payload,
+            spec,
+            values,
+            signer_state,
+            signers,
+            signers_instances,
+        )
+    }
+}
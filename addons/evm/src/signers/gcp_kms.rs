@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+use txtx_addon_kit::channel;
+use txtx_addon_kit::types::commands::CommandExecutionResult;
+use txtx_addon_kit::types::frontend::BlockEvent;
+use txtx_addon_kit::types::signers::{
+    return_synchronous_result, CheckSignabilityOk, SignerActionErr, SignerActionsFutureResult,
+    SignerActivateFutureResult, SignerCapabilities, SignerImplementation, SignerInstance,
+    SignerSignFutureResult, SignerSpecification, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::RunbookSupervisionContext;
+use txtx_addon_kit::types::AuthorizationContext;
+use txtx_addon_kit::types::{
+    diagnostics::Diagnostic,
+    types::{Type, Value},
+    ConstructDid,
+};
+
+use crate::constants::{ENDPOINT, KEY_NAME, KMS_PUBLIC_KEY};
+use crate::signers::kms::gcp::GcpKmsKey;
+use crate::signers::kms::{check_kms_signer_activability, sign_with_kms_signer, KmsKey};
+use crate::signers::secret_key::EvmSecretKeySigner;
+
+lazy_static! {
+    pub static ref EVM_GCP_KMS_SIGNER: SignerSpecification = define_signer! {
+        EvmGcpKmsSigner => {
+          name: "EVM Google Cloud KMS Signer",
+          matcher: "gcp_kms",
+          documentation:txtx_addon_kit::indoc! {r#"The `evm::gcp_kms` signer can be used to synchronously sign a transaction with a secp256k1 key (`EC_SIGN_SECP256K1_SHA256`) held by Google Cloud KMS.
+          Requests are authenticated with the application-default credentials. The key never leaves Cloud KMS: its public key is retrieved to derive the signer address, and transactions are signed with the `asymmetricSign` API."#},
+          inputs: [
+            key_name: {
+                documentation: "The resource name of the key version: `projects/<project>/locations/<location>/keyRings/<key_ring>/cryptoKeys/<key>/cryptoKeyVersions/<version>`.",
+                typing: Type::string(),
+                optional: false,
+                tainting: true,
+                sensitive: false
+            },
+            endpoint: {
+                documentation: "A custom Cloud KMS endpoint, such as an emulator. Requests sent to a custom endpoint are not authenticated.",
+                typing: Type::string(),
+                optional: true,
+                tainting: true,
+                sensitive: false
+            },
+            expected_address: {
+                documentation: "The address the KMS key is expected to have. The signer fails to activate if the addresses don't match.",
+                typing: Type::string(),
+                optional: true,
+                tainting: true,
+                sensitive: false
+            }
+          ],
+          outputs: [
+              public_key: {
+                documentation: "The compressed public key of the KMS key.",
+                typing: Type::string()
+              },
+              address: {
+                documentation: "The address derived from the public key of the KMS key.",
+                typing: Type::string()
+              }
+          ],
+          example: txtx_addon_kit::indoc! {r#"
+            signer "deployer" "evm::gcp_kms" {
+                key_name = "projects/my-project/locations/global/keyRings/txtx/cryptoKeys/deployer/cryptoKeyVersions/1"
+            }
+        "#},
+          capabilities: SignerCapabilities {
+            sign_transaction: true,
+            ..SignerCapabilities::none()
+          }
+      }
+    };
+}
+
+pub struct EvmGcpKmsSigner;
+impl SignerImplementation for EvmGcpKmsSigner {
+    fn check_instantiability(
+        _ctx: &SignerSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn check_activability(
+        construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &txtx_addon_kit::types::AuthorizationContext,
+        _is_balance_check_required: bool,
+        _is_public_key_required: bool,
+    ) -> SignerActionsFutureResult {
+        let key_name = values
+            .get_expected_string(KEY_NAME)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let kms_key = KmsKey::Gcp(GcpKmsKey::new(key_name, values.get_string(ENDPOINT)));
+
+        check_kms_signer_activability(
+            kms_key,
+            construct_did,
+            instance_name,
+            values,
+            signer_state,
+            signers,
+            supervision_context,
+            auth_ctx,
+        )
+    }
+
+    fn activate(
+        _construct_id: &ConstructDid,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _progress_tx: &channel::Sender<BlockEvent>,
+    ) -> SignerActivateFutureResult {
+        let mut result = CommandExecutionResult::new();
+        let address = signer_state
+            .get_expected_value("signer_address")
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let public_key = signer_state
+            .get_expected_value(KMS_PUBLIC_KEY)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        result.outputs.insert("address".into(), address.clone());
+        result.outputs.insert("public_key".into(), public_key.clone());
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+
+    fn check_signability(
+        construct_did: &ConstructDid,
+        title: &str,
+        description: &Option<String>,
+        meta_description: &Option<String>,
+        markdown: &Option<String>,
+        payload: &Value,
+        spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &AuthorizationContext,
+    ) -> Result<CheckSignabilityOk, SignerActionErr> {
+        EvmSecretKeySigner::check_signability(
+            construct_did,
+            title,
+            description,
+            meta_description,
+            markdown,
+            payload,
+            spec,
+            values,
+            signer_state,
+            signers,
+            signers_instances,
+            supervision_context,
+            auth_ctx,
+        )
+    }
+
+    fn sign(
+        caller_uuid: &ConstructDid,
+        _title: &str,
+        _payload: &Value,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    ) -> SignerSignFutureResult {
+        sign_with_kms_signer(caller_uuid, values, signer_state, signers)
+    }
+}
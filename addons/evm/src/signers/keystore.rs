@@ -0,0 +1,384 @@
+use alloy_primitives::Address;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use txtx_addon_kit::channel;
+use txtx_addon_kit::types::commands::CommandExecutionResult;
+use txtx_addon_kit::types::frontend::{
+    ActionItemRequestType, Actions, BlockEvent, ProvideInputRequest, ReviewInputRequest,
+};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, return_synchronous_result, CheckSignabilityOk, SignerActionErr,
+    SignerActionsFutureResult, SignerActivateFutureResult, SignerImplementation, SignerInstance,
+    SignerSignFutureResult, SignerSpecification, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::RunbookSupervisionContext;
+use txtx_addon_kit::types::AuthorizationContext;
+use txtx_addon_kit::types::{
+    diagnostics::Diagnostic,
+    types::{Type, Value},
+    ConstructDid,
+};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::codec::crypto::keystore_to_secret_key_signer;
+use crate::constants::{
+    ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_PROVIDE_PASSWORD, CHECKED_ADDRESS, EXPECTED_ADDRESS,
+    KEYSTORE_PATH, PASSWORD,
+};
+use crate::signers::secret_key::EvmSecretKeySigner;
+use crate::typing::EvmValue;
+
+lazy_static! {
+    pub static ref EVM_KEYSTORE_SIGNER: SignerSpecification = define_signer! {
+        EvmKeystoreSigner => {
+          name: "EVM Keystore Signer",
+          matcher: "keystore",
+          documentation:txtx_addon_kit::indoc! {r#"The `evm::keystore` signer can be used to synchronously sign a transaction with a key stored in an encrypted keystore file (Web3 Secret Storage, as produced by geth, clef or `cast wallet`).
+          The keystore is decrypted when the signer is activated, to check its address, and again each time a payload is signed: the decrypted key is wiped from memory as soon as it's used, and is never kept for the duration of the run.
+          In supervised mode, the password is requested when it is not provided with the `password` input."#},
+          inputs: [
+            keystore_path: {
+                documentation: "The path to the keystore file, relative to the txtx workspace.",
+                typing: Type::string(),
+                optional: false,
+                tainting: true,
+                sensitive: false
+            },
+            password: {
+                documentation: "The password of the keystore. When omitted in supervised mode, the password is requested in the supervisor.",
+                typing: Type::string(),
+                optional: true,
+                tainting: true,
+                sensitive: true
+            },
+            expected_address: {
+                documentation: "The address the keystore is expected to decrypt to. The signer fails to activate if the addresses don't match.",
+                typing: Type::string(),
+                optional: true,
+                tainting: true,
+                sensitive: false
+            }
+          ],
+          outputs: [
+              address: {
+                documentation: "The address of the account stored in the keystore.",
+                typing: Type::string()
+              }
+          ],
+          example: txtx_addon_kit::indoc! {r#"
+            signer "deployer" "evm::keystore" {
+                keystore_path = "./keystores/deployer.json"
+                expected_address = "0x7a1A1cD5A1B4d4A0a8a2Fd3C9b6F1e2c7F5C8E9d"
+            }
+        "#}
+      }
+    };
+}
+
+pub struct EvmKeystoreSigner;
+impl SignerImplementation for EvmKeystoreSigner {
+    fn check_instantiability(
+        _ctx: &SignerSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn check_activability(
+        construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &txtx_addon_kit::types::AuthorizationContext,
+        _is_balance_check_required: bool,
+        _is_public_key_required: bool,
+    ) -> SignerActionsFutureResult {
+        use txtx_addon_kit::constants::DESCRIPTION;
+
+        let mut actions = Actions::none();
+
+        let description = values.get_string(DESCRIPTION).map(|d| d.to_string());
+        let markdown = values
+            .get_markdown(auth_ctx)
+            .map_err(|d| (signers.clone(), signer_state.clone(), d))?;
+
+        let keystore_path = values
+            .get_expected_string(KEYSTORE_PATH)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let keystore_location = auth_ctx
+            .get_file_location_from_path_buf(&PathBuf::from(keystore_path))
+            .map_err(|e| {
+                (
+                    signers.clone(),
+                    signer_state.clone(),
+                    diagnosed_error!("invalid keystore path '{keystore_path}': {e}"),
+                )
+            })?
+            .to_string();
+
+        let Some(password) = values.get_string(PASSWORD) else {
+            if !supervision_context.is_supervised {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!(
+                        "signer '{instance_name}': the 'password' input is required to unlock the keystore in unsupervised mode"
+                    ),
+                ));
+            }
+            actions.push_sub_group(
+                None,
+                vec![ActionItemRequestType::ProvideInput(ProvideInputRequest {
+                    default_value: None,
+                    input_name: PASSWORD.into(),
+                    typing: Type::string(),
+                    sensitive: true,
+                })
+                .to_request(instance_name, ACTION_ITEM_PROVIDE_PASSWORD)
+                .with_construct_did(construct_did)
+                .with_some_description(description)
+                .with_meta_description(&format!("Unlock {} keystore", instance_name))
+                .with_some_markdown(markdown)],
+            );
+            return return_synchronous_actions(Ok((signers, signer_state, actions)));
+        };
+
+        // the decrypted key is only used to check the address, which is kept for the next passes:
+        // the keystore is decrypted again for signing
+        let unlocked_address = match (
+            signer_state.get_string(KEYSTORE_PATH),
+            signer_state.get_string(PASSWORD),
+            signer_state.get_string(KEYSTORE_ADDRESS),
+        ) {
+            (Some(path), Some(unlocked_password), Some(address))
+                if path == keystore_location && unlocked_password == password =>
+            {
+                address.parse::<Address>().ok()
+            }
+            _ => None,
+        };
+        let address = match unlocked_address {
+            Some(address) => address,
+            None => keystore_to_secret_key_signer(&keystore_location, password)
+                .map_err(|e| {
+                    (
+                        signers.clone(),
+                        signer_state.clone(),
+                        diagnosed_error!("signer '{instance_name}': {e}"),
+                    )
+                })?
+                .address(),
+        };
+
+        if let Some(expected_address) = values.get_string(EXPECTED_ADDRESS) {
+            let expected_address = expected_address.parse::<Address>().map_err(|e| {
+                (
+                    signers.clone(),
+                    signer_state.clone(),
+                    diagnosed_error!("invalid expected address '{expected_address}': {e}"),
+                )
+            })?;
+            if expected_address != address {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!(
+                        "signer '{instance_name}': keystore address {address} does not match expected address {expected_address}"
+                    ),
+                ));
+            }
+        }
+
+        signer_state.insert(KEYSTORE_PATH, Value::string(keystore_location));
+        signer_state.insert(PASSWORD, Value::string(password.to_string()));
+        signer_state.insert(KEYSTORE_ADDRESS, Value::string(address.to_string()));
+
+        if supervision_context.review_input_values {
+            if let Ok(_) = signer_state.get_expected_string(CHECKED_ADDRESS) {
+                signer_state.insert("signer_address", Value::string(address.to_string()));
+                signer_state.insert(CHECKED_ADDRESS, Value::string(address.to_string()));
+            } else {
+                actions.push_sub_group(
+                    None,
+                    vec![ReviewInputRequest::new("", &Value::string(address.to_string()))
+                        .to_action_type()
+                        .to_request(instance_name, ACTION_ITEM_CHECK_ADDRESS)
+                        .with_construct_did(construct_did)
+                        .with_some_description(description)
+                        .with_meta_description(&format!("Check {} expected address", instance_name))
+                        .with_some_markdown(markdown)],
+                );
+            }
+        } else {
+            signer_state.insert(CHECKED_ADDRESS, Value::string(address.to_string()));
+            signer_state.insert("signer_address", Value::string(address.to_string()));
+        }
+        return_synchronous_actions(Ok((signers, signer_state, actions)))
+    }
+
+    fn activate(
+        _construct_id: &ConstructDid,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _progress_tx: &channel::Sender<BlockEvent>,
+    ) -> SignerActivateFutureResult {
+        let mut result = CommandExecutionResult::new();
+        let address = signer_state
+            .get_expected_value("signer_address")
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        result.outputs.insert("address".into(), address.clone());
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+
+    fn check_signability(
+        construct_did: &ConstructDid,
+        title: &str,
+        description: &Option<String>,
+        meta_description: &Option<String>,
+        markdown: &Option<String>,
+        payload: &Value,
+        spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &AuthorizationContext,
+    ) -> Result<CheckSignabilityOk, SignerActionErr> {
+        EvmSecretKeySigner::check_signability(
+            construct_did,
+            title,
+            description,
+            meta_description,
+            markdown,
+            payload,
+            spec,
+            values,
+            signer_state,
+            signers,
+            signers_instances,
+            supervision_context,
+            auth_ctx,
+        )
+    }
+
+    fn sign(
+        caller_uuid: &ConstructDid,
+        title: &str,
+        payload: &Value,
+        spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    ) -> SignerSignFutureResult {
+        let signing_state = match unlock_keystore(&signer_state) {
+            Ok(signer_field_bytes) => {
+                let mut signing_state = signer_state.clone();
+                signing_state.insert(
+                    SIGNER_FIELD_BYTES,
+                    EvmValue::signer_field_bytes(signer_field_bytes.to_vec()),
+                );
+                signing_state
+            }
+            Err(diag) => return Err((signers, signer_state, diag)),
+        };
+
+        let future = EvmSecretKeySigner::sign(
+            caller_uuid,
+            title,
+            payload,
+            spec,
+            values,
+            signing_state,
+            signers,
+            signers_instances,
+        )
+        .map_err(|(signers, signing_state, diag)| {
+            (signers, wipe_secret_key(signing_state), diag)
+        })?;
+        Ok(Box::pin(async move {
+            match future.await {
+                Ok((signers, signing_state, result)) => {
+                    Ok((signers, wipe_secret_key(signing_state), result))
+                }
+                Err((signers, signing_state, diag)) => {
+                    Err((signers, wipe_secret_key(signing_state), diag))
+                }
+            }
+        }))
+    }
+}
+
+/// The key under which the secret key signer expects the decrypted key in the signer state.
+const SIGNER_FIELD_BYTES: &str = "signer_field_bytes";
+/// The address of the keystore, recorded once it's been decrypted at activation.
+const KEYSTORE_ADDRESS: &str = "keystore_address";
+
+/// Decrypts the keystore recorded in the signer state at activation.
+fn unlock_keystore(signer_state: &ValueStore) -> Result<Zeroizing<Vec<u8>>, Diagnostic> {
+    let keystore_location = signer_state.get_expected_string(KEYSTORE_PATH)?;
+    let password = signer_state.get_expected_string(PASSWORD)?;
+    let secret_key_signer = keystore_to_secret_key_signer(keystore_location, password)
+        .map_err(|e| diagnosed_error!("{e}"))?;
+    Ok(Zeroizing::new(secret_key_signer.to_field_bytes().to_vec()))
+}
+
+/// Removes the decrypted key from the signer state once a payload is signed, wiping it from
+/// memory.
+fn wipe_secret_key(mut signer_state: ValueStore) -> ValueStore {
+    if let Some(Value::Addon(mut addon_data)) =
+        signer_state.inputs.store.shift_remove(SIGNER_FIELD_BYTES)
+    {
+        addon_data.bytes.zeroize();
+    }
+    signer_state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codec::crypto::secret_key_to_secret_key_signer;
+
+    #[test]
+    fn it_only_holds_the_decrypted_key_while_signing() {
+        let dir = std::env::temp_dir().join(format!("txtx-keystore-{}", rand::random::<u64>()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let secret_key = vec![7u8; 32];
+        eth_keystore::encrypt_key(
+            &dir,
+            &mut rand::thread_rng(),
+            &secret_key,
+            "password",
+            Some("deployer.json"),
+        )
+        .unwrap();
+
+        let mut signer_state = ValueStore::tmp();
+        signer_state.insert(
+            KEYSTORE_PATH,
+            Value::string(dir.join("deployer.json").to_string_lossy().to_string()),
+        );
+        signer_state.insert(PASSWORD, Value::string("password".into()));
+
+        let field_bytes = unlock_keystore(&signer_state).unwrap();
+        let expected_signer = secret_key_to_secret_key_signer(&secret_key).unwrap();
+        assert_eq!(field_bytes.as_slice(), expected_signer.to_field_bytes().as_slice());
+
+        signer_state.insert(SIGNER_FIELD_BYTES, EvmValue::signer_field_bytes(field_bytes.to_vec()));
+        let signer_state = wipe_secret_key(signer_state);
+        assert!(signer_state.get_value(SIGNER_FIELD_BYTES).is_none());
+        assert!(signer_state.get_value(KEYSTORE_PATH).is_some());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
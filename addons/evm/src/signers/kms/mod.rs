@@ -0,0 +1,359 @@
+pub use txtx_addon_kit::crypto::kms::{aws, gcp};
+
+use alloy_consensus::{SignableTransaction, TxEnvelope};
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{Address, Signature, B256};
+use alloy_rpc_types::TransactionRequest;
+use k256::ecdsa::{RecoveryId, Signature as EcdsaSignature, VerifyingKey};
+use k256::pkcs8::DecodePublicKey;
+use txtx_addon_kit::hex;
+use txtx_addon_kit::types::commands::CommandExecutionResult;
+use txtx_addon_kit::types::frontend::{Actions, ReviewInputRequest};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, SignerActionsFutureResult, SignerSignFutureResult, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::{RunbookSupervisionContext, Value};
+use txtx_addon_kit::types::{diagnostics::Diagnostic, AuthorizationContext, ConstructDid};
+
+use crate::codes::broadcast_error_diagnostic;
+use crate::constants::{
+    ACTION_ITEM_CHECK_ADDRESS, CHECKED_ADDRESS, CHECKED_PUBLIC_KEY, EXPECTED_ADDRESS, KMS_ENDPOINT,
+    KMS_KEY_ID, KMS_PROVIDER, KMS_PUBLIC_KEY, KMS_REGION, RPC_API_URL,
+    SECRET_KEY_WALLET_UNSIGNED_TRANSACTION_BYTES, TX_HASH,
+};
+use crate::rpc::EvmRpc;
+use crate::typing::EvmValue;
+
+use aws::AwsKmsKey;
+use gcp::GcpKmsKey;
+
+const AWS_PROVIDER: &str = "aws";
+const GCP_PROVIDER: &str = "gcp";
+
+/// A secp256k1 key held by a cloud KMS. The key material never leaves the KMS: the public key is
+/// fetched to derive the signer address, and transaction digests are signed remotely.
+#[derive(Clone, Debug)]
+pub enum KmsKey {
+    Aws(AwsKmsKey),
+    Gcp(GcpKmsKey),
+}
+
+impl KmsKey {
+    /// Fetches the public key of the KMS key.
+    pub async fn get_verifying_key(&self) -> Result<VerifyingKey, Diagnostic> {
+        let der = match self {
+            KmsKey::Aws(key) => key.get_public_key_der().await?,
+            KmsKey::Gcp(key) => key.get_public_key_der().await?,
+        };
+        VerifyingKey::from_public_key_der(&der).map_err(|e| {
+            diagnosed_error!("{} is not a secp256k1 (ECC_SECG_P256K1) key: {e}", self.describe())
+        })
+    }
+
+    /// Signs `digest` with the KMS key, and returns the signature in RSV form.
+    pub async fn sign_hash(
+        &self,
+        digest: &B256,
+        verifying_key: &VerifyingKey,
+    ) -> Result<Signature, Diagnostic> {
+        let der = match self {
+            KmsKey::Aws(key) => key.sign_digest(digest.as_slice()).await?,
+            KmsKey::Gcp(key) => key.sign_digest(digest.as_slice()).await?,
+        };
+        der_signature_to_rsv(&der, digest, verifying_key)
+            .map_err(|e| diagnosed_error!("invalid signature from {}: {e}", self.describe()))
+    }
+
+    /// Signs `tx`, which must be complete (nonce, gas and fees, chain id), with the KMS key.
+    pub async fn sign_transaction(
+        &self,
+        tx: TransactionRequest,
+        verifying_key: &VerifyingKey,
+    ) -> Result<TxEnvelope, Diagnostic> {
+        let unsigned_tx = tx
+            .build_unsigned()
+            .map_err(|e| diagnosed_error!("failed to build transaction envelope: {e}"))?;
+        let signature = self.sign_hash(&unsigned_tx.signature_hash(), verifying_key).await?;
+        Ok(unsigned_tx.into_signed(signature).into())
+    }
+
+    /// Records the key in the signer state, so that it can be used by [KmsKey::from_signer_state]
+    /// when signing. Only the key identifiers are stored.
+    pub fn insert_in_signer_state(&self, signer_state: &mut ValueStore) {
+        match self {
+            KmsKey::Aws(key) => {
+                signer_state.insert(KMS_PROVIDER, Value::string(AWS_PROVIDER.into()));
+                signer_state.insert(KMS_KEY_ID, Value::string(key.key_id.clone()));
+                if let Some(region) = &key.region {
+                    signer_state.insert(KMS_REGION, Value::string(region.clone()));
+                }
+            }
+            KmsKey::Gcp(key) => {
+                signer_state.insert(KMS_PROVIDER, Value::string(GCP_PROVIDER.into()));
+                signer_state.insert(KMS_KEY_ID, Value::string(key.key_name.clone()));
+                if let Some(endpoint) = &key.endpoint {
+                    signer_state.insert(KMS_ENDPOINT, Value::string(endpoint.clone()));
+                }
+            }
+        }
+    }
+
+    pub fn from_signer_state(signer_state: &ValueStore) -> Result<Self, Diagnostic> {
+        let key_id = signer_state.get_expected_string(KMS_KEY_ID)?;
+        match signer_state.get_expected_string(KMS_PROVIDER)? {
+            AWS_PROVIDER => {
+                Ok(KmsKey::Aws(AwsKmsKey::new(key_id, signer_state.get_string(KMS_REGION))))
+            }
+            GCP_PROVIDER => {
+                Ok(KmsKey::Gcp(GcpKmsKey::new(key_id, signer_state.get_string(KMS_ENDPOINT))))
+            }
+            provider => Err(diagnosed_error!("unsupported KMS provider '{provider}'")),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            KmsKey::Aws(key) => format!("AWS KMS key {}", key.key_id),
+            KmsKey::Gcp(key) => format!("Cloud KMS key {}", key.key_name),
+        }
+    }
+}
+
+pub fn verifying_key_to_address(verifying_key: &VerifyingKey) -> Address {
+    Address::from_public_key(verifying_key)
+}
+
+pub fn verifying_key_to_hex(verifying_key: &VerifyingKey) -> String {
+    hex::encode(verifying_key.to_encoded_point(true).as_bytes())
+}
+
+pub fn verifying_key_from_hex(public_key: &str) -> Result<VerifyingKey, Diagnostic> {
+    let bytes = hex::decode(public_key).map_err(|e| diagnosed_error!("invalid public key: {e}"))?;
+    VerifyingKey::from_sec1_bytes(&bytes).map_err(|e| diagnosed_error!("invalid public key: {e}"))
+}
+
+/// Converts a DER-encoded ECDSA signature of `digest` into an RSV signature. The `s` value is
+/// normalized to the lower half of the curve order, as required since EIP-2, and the recovery id
+/// is found by recovering the public key with each candidate id.
+pub fn der_signature_to_rsv(
+    der: &[u8],
+    digest: &B256,
+    verifying_key: &VerifyingKey,
+) -> Result<Signature, String> {
+    let signature =
+        EcdsaSignature::from_der(der).map_err(|e| format!("malformed DER signature: {e}"))?;
+    let signature = signature.normalize_s().unwrap_or(signature);
+    for byte in [0u8, 1] {
+        let recovery_id = RecoveryId::from_byte(byte).expect("valid recovery id");
+        let Ok(recovered) =
+            VerifyingKey::recover_from_prehash(digest.as_slice(), &signature, recovery_id)
+        else {
+            continue;
+        };
+        if &recovered == verifying_key {
+            return Ok(Signature::from_signature_and_parity(signature, recovery_id.is_y_odd()));
+        }
+    }
+    Err("the signature does not match the public key of the key".into())
+}
+
+/// Fetches the public key of `kms_key` to derive the signer address, checks it against the
+/// `expected_address` input, and requests the review of the address in supervised mode.
+pub fn check_kms_signer_activability(
+    kms_key: KmsKey,
+    construct_did: &ConstructDid,
+    instance_name: &str,
+    values: &ValueStore,
+    mut signer_state: ValueStore,
+    signers: SignersState,
+    supervision_context: &RunbookSupervisionContext,
+    auth_ctx: &AuthorizationContext,
+) -> SignerActionsFutureResult {
+    use txtx_addon_kit::constants::DESCRIPTION;
+
+    let mut actions = Actions::none();
+
+    if signer_state.get_value(CHECKED_PUBLIC_KEY).is_some() {
+        return return_synchronous_actions(Ok((signers, signer_state, actions)));
+    }
+
+    let description = values.get_string(DESCRIPTION).map(|d| d.to_string());
+    let markdown =
+        values.get_markdown(auth_ctx).map_err(|d| (signers.clone(), signer_state.clone(), d))?;
+    let expected_address = values
+        .get_string(EXPECTED_ADDRESS)
+        .map(|address| {
+            address
+                .parse::<Address>()
+                .map_err(|e| diagnosed_error!("invalid expected address '{address}': {e}"))
+        })
+        .transpose()
+        .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+    let construct_did = construct_did.clone();
+    let instance_name = instance_name.to_string();
+    let review_input_values = supervision_context.review_input_values;
+
+    let future = async move {
+        let verifying_key = match signer_state.get_string(KMS_PUBLIC_KEY) {
+            Some(public_key) => verifying_key_from_hex(public_key),
+            None => kms_key.get_verifying_key().await,
+        }
+        .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let address = verifying_key_to_address(&verifying_key);
+
+        if let Some(expected_address) = expected_address {
+            if expected_address != address {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!(
+                        "signer '{instance_name}': {} has address {address}, but {expected_address} was expected",
+                        kms_key.describe()
+                    ),
+                ));
+            }
+        }
+
+        kms_key.insert_in_signer_state(&mut signer_state);
+        let public_key = Value::string(verifying_key_to_hex(&verifying_key));
+        signer_state.insert(KMS_PUBLIC_KEY, public_key.clone());
+
+        if review_input_values {
+            if let Ok(_) = signer_state.get_expected_string(CHECKED_ADDRESS) {
+                signer_state.insert(CHECKED_PUBLIC_KEY, public_key);
+                signer_state.insert("signer_address", Value::string(address.to_string()));
+            } else {
+                actions.push_sub_group(
+                    None,
+                    vec![ReviewInputRequest::new("", &Value::string(address.to_string()))
+                        .to_action_type()
+                        .to_request(&instance_name, ACTION_ITEM_CHECK_ADDRESS)
+                        .with_construct_did(&construct_did)
+                        .with_some_description(description)
+                        .with_meta_description(&format!(
+                            "Check {} expected address ({})",
+                            instance_name,
+                            kms_key.describe()
+                        ))
+                        .with_some_markdown(markdown)],
+                );
+            }
+        } else {
+            signer_state.insert(CHECKED_PUBLIC_KEY, public_key);
+            signer_state.insert(CHECKED_ADDRESS, Value::string(address.to_string()));
+            signer_state.insert("signer_address", Value::string(address.to_string()));
+        }
+        Ok((signers, signer_state, actions))
+    };
+    Ok(Box::pin(future))
+}
+
+/// Signs the transaction prepared by `caller_uuid` with the KMS key recorded in the signer state,
+/// and broadcasts it.
+pub fn sign_with_kms_signer(
+    caller_uuid: &ConstructDid,
+    values: &ValueStore,
+    signer_state: ValueStore,
+    signers: SignersState,
+) -> SignerSignFutureResult {
+    let caller_uuid = caller_uuid.clone();
+    let values = values.clone();
+
+    let future = async move {
+        let mut result = CommandExecutionResult::new();
+
+        let rpc_api_url = values
+            .get_expected_string(RPC_API_URL)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let kms_key = KmsKey::from_signer_state(&signer_state)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let verifying_key = signer_state
+            .get_expected_string(KMS_PUBLIC_KEY)
+            .and_then(verifying_key_from_hex)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+        let payload_bytes = signer_state
+            .get_expected_scoped_buffer_bytes(
+                &caller_uuid.to_string(),
+                SECRET_KEY_WALLET_UNSIGNED_TRANSACTION_BYTES,
+            )
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+        let mut tx: TransactionRequest = serde_json::from_slice(&payload_bytes).map_err(|e| {
+            (signers.clone(), signer_state.clone(), diagnosed_error!("invalid transaction: {e}"))
+        })?;
+        if tx.to.is_none() {
+            // there's no to address on the tx, which is invalid unless it's set as "create"
+            tx.set_create();
+        }
+
+        let tx_envelope = kms_key
+            .sign_transaction(tx, &verifying_key)
+            .await
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+        let rpc = EvmRpc::new(&rpc_api_url)
+            .map_err(|e| (signers.clone(), signer_state.clone(), diagnosed_error!("{e}")))?;
+        let tx_hash = rpc
+            .send_tx_envelope(tx_envelope)
+            .await
+            .map_err(|e| (signers.clone(), signer_state.clone(), broadcast_error_diagnostic(e)))?;
+
+        result.outputs.insert(TX_HASH.to_string(), EvmValue::tx_hash(tx_hash.to_vec()));
+
+        Ok((signers, signer_state, result))
+    };
+    Ok(Box::pin(future))
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::U256;
+    use k256::ecdsa::SigningKey;
+
+    use super::*;
+
+    // RFC 6979 test vector: secret key 1, sha256("Satoshi Nakamoto")
+    const DIGEST: &str = "a0dc65ffca799873cbea0ac274015b9526505daaaed385155425f7337704883e";
+    const R: &str = "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8";
+    const S: &str = "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5";
+    const DER_SIGNATURE: &str = "3045022100934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d802202442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5";
+    // the same signature with `s` in the upper half of the curve order, as KMS may return it
+    const HIGH_S_DER_SIGNATURE: &str = "3046022100934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8022100dbbd3162d46e9f9bef7feb87c16dc13b4f6568a87f4e83f728e2443ba586675c";
+
+    fn verifying_key(secret_key: u8) -> VerifyingKey {
+        *SigningKey::from_slice(B256::with_last_byte(secret_key).as_slice())
+            .unwrap()
+            .verifying_key()
+    }
+
+    #[test]
+    fn it_converts_der_signatures_to_rsv() {
+        let digest = DIGEST.parse::<B256>().unwrap();
+        let verifying_key = verifying_key(1);
+        for der in [DER_SIGNATURE, HIGH_S_DER_SIGNATURE] {
+            let signature =
+                der_signature_to_rsv(&hex::decode(der).unwrap(), &digest, &verifying_key).unwrap();
+            assert_eq!(signature.r(), U256::from_str_radix(R, 16).unwrap());
+            assert_eq!(signature.s(), U256::from_str_radix(S, 16).unwrap());
+            assert!(signature.v());
+            assert_eq!(
+                signature.recover_address_from_prehash(&digest).unwrap(),
+                verifying_key_to_address(&verifying_key)
+            );
+        }
+    }
+
+    #[test]
+    fn it_rejects_mismatching_and_malformed_signatures() {
+        let digest = DIGEST.parse::<B256>().unwrap();
+        let der = hex::decode(DER_SIGNATURE).unwrap();
+
+        let error = der_signature_to_rsv(&der, &digest, &verifying_key(2)).unwrap_err();
+        assert_eq!(error, "the signature does not match the public key of the key");
+
+        let error = der_signature_to_rsv(&der[..20], &digest, &verifying_key(1)).unwrap_err();
+        assert!(error.starts_with("malformed DER signature"));
+    }
+}
@@ -1,13 +1,32 @@
 use txtx_addon_kit::types::signers::SignerSpecification;
 
+#[cfg(feature = "kms")]
+mod aws_kms;
 pub mod common;
+mod encrypted_keyfile;
+#[cfg(feature = "kms")]
+mod gcp_kms;
+mod keystore;
+#[cfg(feature = "kms")]
+pub mod kms;
 mod secret_key;
 mod web_wallet;
 
+use encrypted_keyfile::EVM_ENCRYPTED_KEYFILE_SIGNER;
+use keystore::EVM_KEYSTORE_SIGNER;
 use secret_key::EVM_SECRET_KEY_SIGNER;
 use web_wallet::EVM_WEB_WALLET;
 
 lazy_static! {
-    pub static ref WALLETS: Vec<SignerSpecification> =
-        vec![EVM_SECRET_KEY_SIGNER.clone(), EVM_WEB_WALLET.clone()];
+    pub static ref WALLETS: Vec<SignerSpecification> = {
+        let mut wallets = vec![
+            EVM_SECRET_KEY_SIGNER.clone(),
+            EVM_WEB_WALLET.clone(),
+            EVM_KEYSTORE_SIGNER.clone(),
+            EVM_ENCRYPTED_KEYFILE_SIGNER.clone(),
+        ];
+        #[cfg(feature = "kms")]
+        wallets.extend([aws_kms::EVM_AWS_KMS_SIGNER.clone(), gcp_kms::EVM_GCP_KMS_SIGNER.clone()]);
+        wallets
+    };
 }
@@ -4,6 +4,9 @@ use alloy_rpc_types::TransactionRequest;
 use std::collections::HashMap;
 use txtx_addon_kit::channel;
 use txtx_addon_kit::constants::SIGNATURE_APPROVED;
+use txtx_addon_kit::crypto::derivation::{
+    mnemonic_derivation_inputs, mnemonic_derivation_outputs, DERIVED_ADDRESSES,
+};
 use txtx_addon_kit::types::commands::CommandExecutionResult;
 use txtx_addon_kit::types::frontend::{
     ActionItemStatus, ProvideSignedTransactionRequest, ReviewInputRequest,
@@ -26,82 +29,80 @@ use txtx_addon_kit::types::{
 };
 
 use crate::codec::crypto::field_bytes_to_secret_key_signer;
+use crate::codes::broadcast_error_diagnostic;
 use crate::constants::{
     ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION, CHAIN_ID,
-    FORMATTED_TRANSACTION, NAMESPACE, RPC_API_URL, SECRET_KEY_WALLET_UNSIGNED_TRANSACTION_BYTES,
-    TX_HASH,
+    DERIVATION_PATH_TEMPLATE, FORMATTED_TRANSACTION, NAMESPACE, RPC_API_URL,
+    SECRET_KEY_WALLET_UNSIGNED_TRANSACTION_BYTES, TX_HASH,
 };
 use crate::rpc::EvmWalletRpc;
+use crate::signers::common::sign_message_in_signer_state;
 use crate::typing::EvmValue;
 use txtx_addon_kit::types::signers::return_synchronous_actions;
 
 use crate::constants::PUBLIC_KEYS;
 
 lazy_static! {
-    pub static ref EVM_SECRET_KEY_SIGNER: SignerSpecification = define_signer! {
-        EvmSecretKeySigner => {
-          name: "EVM Secret Key Signer",
-          matcher: "secret_key",
-          documentation:txtx_addon_kit::indoc! {r#"The `evm::secret_key` signer can be used to synchronously sign a transaction."#},
-          inputs: [
-            secret_key: {
-                documentation: "The secret key used to sign messages and transactions.",
-                typing: Type::string(),
-                optional: true,
-                tainting: true,
-                sensitive: true
-            },
-            mnemonic: {
-                documentation: "The mnemonic phrase used to generate the secret key. This input will not be used if the `secret_key` input is provided.",
-                typing: Type::string(),
-                optional: true,
-                tainting: true,
-                sensitive: true
-            },
-            derivation_path: {
-                documentation: "The derivation path used to generate the secret key. This input will not be used if the `secret_key` input is provided.",
-                typing: Type::string(),
-                optional: true,
-                tainting: true,
-                sensitive: true
-            },
-            is_encrypted: {
-                documentation: "Coming soon",
-                typing: Type::bool(),
-                optional: true,
-                tainting: true,
-                sensitive: false
-            },
-            password: {
-                documentation: "Coming soon",
-                typing: Type::string(),
-                optional: true,
-                tainting: true,
-                sensitive: true
-            }
-          ],
-          outputs: [
-              public_key: {
-                documentation: "The public key of the account generated from the secret key.",
-                typing: Type::array(Type::buffer())
-              },
-              address: {
-                documentation: "The address generated from the secret key.",
-                typing: Type::array(Type::buffer())
-              }
-          ],
-          example: txtx_addon_kit::indoc! {r#"
-            // we can create a secret key signer by providing a mnemonic and computing the secret key
-            signer "bob" "evm::secret_key" {
-                mnemonic = "board list obtain sugar hour worth raven scout denial thunder horse logic fury scorpion fold genuine phrase wealth news aim below celery when cabin"
-                derivation_path = "m/44'/5757'/0'/0/0"
-            }
-            // or we can create one by providing the secret key directly
-            signer "bob_again" "evm::secret_key" {
-                secret_key = "03b3e0a76b292b2c83fc0ac14ae6160d0438ebe94e14bbb5b7755153628886e08e"
-            }
-        "#}
-      }
+    pub static ref EVM_SECRET_KEY_SIGNER: SignerSpecification = {
+        let mut spec = define_signer! {
+            EvmSecretKeySigner => {
+              name: "EVM Secret Key Signer",
+              matcher: "secret_key",
+              documentation:txtx_addon_kit::indoc! {r#"The `evm::secret_key` signer can be used to synchronously sign a transaction."#},
+              inputs: [
+                secret_key: {
+                    documentation: "The secret key used to sign messages and transactions.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: true
+                },
+                mnemonic: {
+                    documentation: "The mnemonic phrase used to generate the secret key. This input will not be used if the `secret_key` input is provided.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: true
+                },
+                is_encrypted: {
+                    documentation: "Coming soon",
+                    typing: Type::bool(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: false
+                }
+              ],
+              outputs: [
+                  public_key: {
+                    documentation: "The public key of the account generated from the secret key.",
+                    typing: Type::array(Type::buffer())
+                  },
+                  address: {
+                    documentation: "The address generated from the secret key.",
+                    typing: Type::array(Type::buffer())
+                  }
+              ],
+              example: txtx_addon_kit::indoc! {r#"
+                // we can create a secret key signer by providing a mnemonic and computing the secret key
+                signer "bob" "evm::secret_key" {
+                    mnemonic = "board list obtain sugar hour worth raven scout denial thunder horse logic fury scorpion fold genuine phrase wealth news aim below celery when cabin"
+                    derivation_path = "m/44'/5757'/0'/0/0"
+                }
+                // or we can create one by providing the secret key directly
+                signer "bob_again" "evm::secret_key" {
+                    secret_key = "03b3e0a76b292b2c83fc0ac14ae6160d0438ebe94e14bbb5b7755153628886e08e"
+                }
+                // or we can select an account of the mnemonic by its index
+                signer "alice" "evm::secret_key" {
+                    mnemonic = input.mnemonic
+                    account_index = 2
+                }
+            "#}
+          }
+        };
+        spec.inputs.append(&mut mnemonic_derivation_inputs(DERIVATION_PATH_TEMPLATE));
+        spec.outputs.append(&mut mnemonic_derivation_outputs());
+        spec
     };
 }
 
@@ -129,6 +130,9 @@ impl SignerImplementation for EvmSecretKeySigner {
         _is_public_key_required: bool,
     ) -> SignerActionsFutureResult {
         use txtx_addon_kit::constants::DESCRIPTION;
+        use txtx_addon_kit::crypto::derivation::{
+            derive_addresses, get_derivation_path, get_passphrase,
+        };
 
         use crate::{
             codec::crypto::{mnemonic_to_secret_key_signer, secret_key_to_secret_key_signer},
@@ -153,11 +157,33 @@ impl SignerImplementation for EvmSecretKeySigner {
                 let mnemonic = values
                     .get_expected_string("mnemonic")
                     .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
-                let derivation_path = values.get_string("derivation_path");
+                let derivation_path = get_derivation_path(values, DERIVATION_PATH_TEMPLATE)
+                    .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
                 let is_encrypted = values.get_bool("is_encrypted");
-                let password = values.get_string("password");
-                mnemonic_to_secret_key_signer(mnemonic, derivation_path, is_encrypted, password)
-                    .map_err(|e| (signers.clone(), signer_state.clone(), diagnosed_error!("{e}")))?
+                let passphrase = get_passphrase(values);
+
+                let derived_addresses =
+                    derive_addresses(values, DERIVATION_PATH_TEMPLATE, |derivation_path| {
+                        mnemonic_to_secret_key_signer(
+                            mnemonic,
+                            Some(derivation_path),
+                            is_encrypted,
+                            passphrase,
+                        )
+                        .map(|signer| signer.address().to_string())
+                    })
+                    .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+                if let Some(derived_addresses) = derived_addresses {
+                    signer_state.insert(DERIVED_ADDRESSES, derived_addresses);
+                }
+
+                mnemonic_to_secret_key_signer(
+                    mnemonic,
+                    Some(&derivation_path),
+                    is_encrypted,
+                    passphrase,
+                )
+                .map_err(|e| (signers.clone(), signer_state.clone(), diagnosed_error!("{e}")))?
             };
 
         let expected_address: Address = expected_signer.address();
@@ -208,6 +234,9 @@ impl SignerImplementation for EvmSecretKeySigner {
             .get_expected_value("signer_address")
             .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
         result.outputs.insert("address".into(), address.clone());
+        if let Some(derived_addresses) = signer_state.get_value(DERIVED_ADDRESSES) {
+            result.outputs.insert(DERIVED_ADDRESSES.into(), derived_addresses.clone());
+        }
         return_synchronous_result(Ok((signers, signer_state, result)))
     }
 
@@ -285,14 +314,22 @@ impl SignerImplementation for EvmSecretKeySigner {
         let future = async move {
             let mut result = CommandExecutionResult::new();
 
-            let rpc_api_url = values
-                .get_expected_string(RPC_API_URL)
-                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
-
             let signer_field_bytes = signer_state
                 .get_expected_buffer_bytes("signer_field_bytes")
                 .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
 
+            if let Some(res) = sign_message_in_signer_state(&caller_uuid, &signer_state, || {
+                field_bytes_to_secret_key_signer(&signer_field_bytes)
+                    .map_err(|e| diagnosed_error!("{e}"))
+            }) {
+                let result = res.map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+                return Ok((signers, signer_state, result));
+            }
+
+            let rpc_api_url = values
+                .get_expected_string(RPC_API_URL)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
             let payload_bytes = signer_state
                 .get_expected_scoped_buffer_bytes(
                     &caller_uuid.to_string(),
@@ -323,7 +360,7 @@ impl SignerImplementation for EvmSecretKeySigner {
                 .map_err(|e| (signers.clone(), signer_state.clone(), diagnosed_error!("{e}")))?;
 
             let tx_hash = rpc.sign_and_send_tx(tx_envelope).await.map_err(|e| {
-                (signers.clone(), signer_state.clone(), diagnosed_error!("{}", e.to_string()))
+                (signers.clone(), signer_state.clone(), broadcast_error_diagnostic(e))
             })?;
 
             result.outputs.insert(TX_HASH.to_string(), EvmValue::tx_hash(tx_hash.to_vec()));
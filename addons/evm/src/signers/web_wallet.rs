@@ -4,13 +4,14 @@ use txtx_addon_kit::channel;
 use txtx_addon_kit::constants::TX_HASH;
 use txtx_addon_kit::types::commands::CommandExecutionResult;
 use txtx_addon_kit::types::frontend::{
-    ActionItemRequestUpdate, ActionItemStatus, Actions, BlockEvent, ReviewInputRequest,
-    SendTransactionRequest,
+    ActionItemRequestUpdate, ActionItemStatus, Actions, BlockEvent, DisplayMarkdownRequest,
+    ReviewInputRequest, SendTransactionRequest,
 };
 use txtx_addon_kit::types::signers::{
     return_synchronous_actions, return_synchronous_result, CheckSignabilityOk, SignerActionErr,
-    SignerActionsFutureResult, SignerActivateFutureResult, SignerImplementation, SignerInstance,
-    SignerSignFutureResult, SignerSpecification, SignersState,
+    SignerActionsFutureResult, SignerActivateFutureResult, SignerCapabilities,
+    SignerImplementation, SignerInstance, SignerSignFutureResult, SignerSpecification,
+    SignersState,
 };
 use txtx_addon_kit::types::stores::ValueStore;
 use txtx_addon_kit::types::types::RunbookSupervisionContext;
@@ -23,10 +24,10 @@ use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
 
 use crate::constants::{
     ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_PROVIDE_PUBLIC_KEY, ACTION_ITEM_SEND_TRANSACTION,
-    ALREADY_DEPLOYED, CHAIN_ID, CHECKED_ADDRESS, CHECKED_COST_PROVISION, CHECKED_PUBLIC_KEY,
-    CONTRACT_ADDRESS, EXPECTED_ADDRESS, FETCHED_BALANCE, FETCHED_NONCE, FORMATTED_TRANSACTION,
-    NAMESPACE, PUBLIC_KEYS, REQUESTED_STARTUP_DATA, RPC_API_URL,
-    WEB_WALLET_UNSIGNED_TRANSACTION_BYTES,
+    ACTION_ITEM_WALLET_INSTRUCTIONS, ALREADY_DEPLOYED, CHAIN_ID, CHECKED_ADDRESS,
+    CHECKED_COST_PROVISION, CHECKED_PUBLIC_KEY, CONTRACT_ADDRESS, EXPECTED_ADDRESS,
+    FETCHED_BALANCE, FETCHED_NONCE, FORMATTED_TRANSACTION, NAMESPACE, PUBLIC_KEYS,
+    REQUESTED_STARTUP_DATA, RPC_API_URL, WEB_WALLET_UNSIGNED_TRANSACTION_BYTES,
 };
 
 lazy_static! {
@@ -70,7 +71,11 @@ lazy_static! {
                 signer "alice" "evm::web_wallet" {
                     expected_address = "0xCe246168E59dd8e28e367BB49b38Dc621768F425"
                 }
-                "#}
+                "#},
+                capabilities: SignerCapabilities {
+                    sign_transaction: true,
+                    ..SignerCapabilities::none()
+                }
             }
         };
         signer.requires_interaction = true;
@@ -216,12 +221,31 @@ impl SignerImplementation for EvmWebWallet {
             .await;
             signer_state.insert(&REQUESTED_STARTUP_DATA, Value::bool(true));
 
-            let action_items = match res {
+            let mut action_items = match res {
                 Ok(action_items) => action_items,
                 Err(e) => {
                     return Err((signers.clone(), signer_state.clone(), diagnosed_error!("{e}")))
                 }
             };
+            if do_request_public_key && !action_items.is_empty() {
+                let mut instructions = format!(
+                    "Signer **{}** signs with a browser wallet: connect it from the supervisor web console, then sign the message requested to share its public key.",
+                    instance_name
+                );
+                if let Some(expected_address) = &expected_address {
+                    instructions.push_str(&format!(
+                        "\n\nThe wallet connected must use the address `{}`.",
+                        expected_address
+                    ));
+                }
+                action_items.insert(
+                    0,
+                    DisplayMarkdownRequest::new(&instructions)
+                        .to_action_type()
+                        .to_request(&instance_name, ACTION_ITEM_WALLET_INSTRUCTIONS)
+                        .with_construct_did(&signer_did),
+                );
+            }
             if !action_items.is_empty() {
                 actions.push_group(
                     "Review and check the following signer related action items",
@@ -0,0 +1,72 @@
+pub fn get_interpolated_header_template(title: &str) -> String {
+    return format!(
+        r#"################################################################
+# {}
+################################################################
+"#,
+        title
+    );
+}
+
+pub fn get_interpolated_addon_template(rpc_url: &str, chain_id: &str) -> String {
+    return format!(
+        r#"
+addon "evm" {{
+    rpc_api_url = {}
+    chain_id = {}
+}}
+"#,
+        rpc_url, chain_id
+    );
+}
+
+pub fn get_interpolated_localnet_signer_template(secret_key: &str) -> String {
+    return format!(
+        r#"
+signer "deployer" "evm::secret_key" {{
+    description = "Pays fees for contract deployments and operations"
+    secret_key = {}
+    // See documentation for other options (mnemonic, keystore, etc): https://docs.txtx.sh/addons/evm/signers
+}}
+"#,
+        secret_key
+    );
+}
+
+pub fn get_interpolated_web_wallet_signer_template() -> String {
+    return format!(
+        r#"
+// For testnet and mainnet deployments, use web wallets, hardware wallets or KMS signers to improve key security.
+
+signer "deployer" "evm::web_wallet" {{
+    description = "Pays fees for contract deployments and operations"
+    // Optional: the address of the signer can be enforced at runtime by setting an expected value
+    // expected_address = "0xCe246168E59dd8e28e367BB49b38Dc621768F425"
+}}
+"#
+    );
+}
+
+pub fn get_interpolated_foundry_contract_deployment_template(contract_name: &str) -> String {
+    return format!(
+        r#"
+action "deploy_{}" "evm::deploy_contract" {{
+    description = "Deploy {} contract"
+    contract = evm::get_contract_from_foundry_project("{}")
+    // Optional: the constructor arguments of the contract
+    // constructor_args = []
+    signer = signer.deployer
+    confirmations = 1
+}}
+
+output "{}_address" {{
+    value = action.deploy_{}.contract_address
+}}
+"#,
+        contract_name.to_lowercase(),
+        contract_name,
+        contract_name,
+        contract_name.to_lowercase(),
+        contract_name.to_lowercase()
+    );
+}
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use alloy_json_abi::{Function, Param};
-use alloy_primitives::Address;
+use alloy_primitives::{Address, U256};
 use alloy_rpc_types::Log;
 use foundry_compilers_artifacts_solc::Metadata;
 use serde::de::DeserializeOwned;
@@ -102,6 +102,21 @@ impl EvmValue {
         Ok(Address::from_slice(&bytes))
     }
 
+    /// Formats evm values for display: addresses in their checksummed form, and unsigned
+    /// integers in decimal.
+    pub fn format_display(value: &Value) -> Option<String> {
+        let addon_data = value.as_addon_data()?;
+        match addon_data.id.as_str() {
+            EVM_ADDRESS if addon_data.bytes.len() == 20 => {
+                Some(Address::from_slice(&addon_data.bytes).to_checksum(None))
+            }
+            EVM_UINT256 | EVM_UINT32 | EVM_UINT8 => {
+                U256::try_from_be_slice(&addon_data.bytes).map(|uint| uint.to_string())
+            }
+            _ => None,
+        }
+    }
+
     pub fn bytes(bytes: Vec<u8>) -> Value {
         Value::addon(bytes, EVM_BYTES)
     }
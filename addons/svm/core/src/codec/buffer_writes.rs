@@ -0,0 +1,527 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_signature::Signature;
+use solana_transaction::Transaction;
+use tokio::sync::{Semaphore, SemaphorePermit};
+use txtx_addon_kit::types::diagnostics::Diagnostic;
+use txtx_addon_kit::types::frontend::LogDispatcher;
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::Value;
+use txtx_addon_kit::types::ConstructDid;
+
+use crate::codec::send_transaction::describe_client_error;
+use crate::codec::DeploymentTransaction;
+use crate::codes::classify_transaction_error;
+use crate::constants::{MAX_RETRIES_PER_CHUNK, WRITES_PER_SECOND, WRITE_CONCURRENCY};
+
+pub const DEFAULT_WRITE_CONCURRENCY: usize = 4;
+pub const DEFAULT_WRITES_PER_SECOND: f64 = 10.0;
+pub const DEFAULT_MAX_RETRIES_PER_CHUNK: usize = 3;
+
+lazy_static! {
+    /// The limiters shared by all `WriteToBuffer` background tasks of a deployment, keyed by the
+    /// deployment construct.
+    static ref BUFFER_WRITE_LIMITERS: Mutex<HashMap<ConstructDid, Arc<BufferWriteLimiter>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Controls how `WriteToBuffer` transactions of a program deployment are sent to the RPC.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BufferWriteConfig {
+    /// The maximum number of write transactions in flight at once.
+    pub write_concurrency: usize,
+    /// The maximum number of write transactions sent per second, possibly fractional. `0` disables
+    /// pacing.
+    pub writes_per_second: f64,
+    /// The number of times a failed write transaction is resent before the deployment fails.
+    pub max_retries_per_chunk: usize,
+}
+
+impl Default for BufferWriteConfig {
+    fn default() -> Self {
+        Self {
+            write_concurrency: DEFAULT_WRITE_CONCURRENCY,
+            writes_per_second: DEFAULT_WRITES_PER_SECOND,
+            max_retries_per_chunk: DEFAULT_MAX_RETRIES_PER_CHUNK,
+        }
+    }
+}
+
+impl BufferWriteConfig {
+    pub fn from_values(values: &ValueStore) -> Result<Self, Diagnostic> {
+        let default = Self::default();
+        let get_usize = |key: &str, default: usize| -> Result<usize, Diagnostic> {
+            match values.get_integer(key) {
+                Some(value) => usize::try_from(value)
+                    .map_err(|_| diagnosed_error!("'{}' must be a positive integer", key)),
+                None => Ok(default),
+            }
+        };
+
+        let write_concurrency = get_usize(WRITE_CONCURRENCY, default.write_concurrency)?;
+        if write_concurrency == 0 {
+            return Err(diagnosed_error!("'{}' must be at least 1", WRITE_CONCURRENCY));
+        }
+        let writes_per_second = match values.get_value(WRITES_PER_SECOND) {
+            Some(Value::Integer(value)) => *value as f64,
+            Some(Value::Float(value)) => *value,
+            Some(_) => return Err(diagnosed_error!("'{}' must be a number", WRITES_PER_SECOND)),
+            None => default.writes_per_second,
+        };
+        if !writes_per_second.is_finite() || writes_per_second < 0.0 {
+            return Err(diagnosed_error!("'{}' must be a positive number", WRITES_PER_SECOND));
+        }
+
+        Ok(Self {
+            write_concurrency,
+            writes_per_second,
+            max_retries_per_chunk: get_usize(MAX_RETRIES_PER_CHUNK, default.max_retries_per_chunk)?,
+        })
+    }
+
+    fn min_interval(&self) -> Option<Duration> {
+        if self.writes_per_second == 0.0 {
+            return None;
+        }
+        Duration::try_from_secs_f64(1.0 / self.writes_per_second).ok()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BufferWriteStats {
+    pub chunks_written: usize,
+    pub retries: usize,
+    pub chunks_per_second: f64,
+}
+
+impl std::fmt::Display for BufferWriteStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} chunks written ({:.1} chunks/sec, {} retries)",
+            self.chunks_written, self.chunks_per_second, self.retries
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    next_write_at: Option<Instant>,
+    started_at: Option<Instant>,
+    chunks_written: usize,
+    retries: usize,
+}
+
+/// Limits the number of in-flight `WriteToBuffer` transactions, and the rate at which they are
+/// sent, across all of the background tasks of a single deployment.
+#[derive(Debug)]
+pub struct BufferWriteLimiter {
+    pub config: BufferWriteConfig,
+    in_flight: Semaphore,
+    state: Mutex<LimiterState>,
+}
+
+impl BufferWriteLimiter {
+    pub fn new(config: BufferWriteConfig) -> Self {
+        Self {
+            in_flight: Semaphore::new(config.write_concurrency),
+            config,
+            state: Mutex::new(LimiterState::default()),
+        }
+    }
+
+    /// Gets the limiter shared by the deployment `construct_did`, creating it if needed.
+    pub fn for_deployment(construct_did: &ConstructDid, config: BufferWriteConfig) -> Arc<Self> {
+        BUFFER_WRITE_LIMITERS
+            .lock()
+            .unwrap()
+            .entry(construct_did.clone())
+            .or_insert_with(|| Arc::new(Self::new(config)))
+            .clone()
+    }
+
+    /// Drops the limiter of the deployment `construct_did`, once all of its writes are sent.
+    pub fn release_deployment(construct_did: &ConstructDid) {
+        BUFFER_WRITE_LIMITERS.lock().unwrap().remove(construct_did);
+    }
+
+    /// Waits until a write can be sent without exceeding the configured concurrency and pacing.
+    /// The returned permit must be held until the write is sent.
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        let permit =
+            self.in_flight.acquire().await.expect("buffer write semaphore is never closed");
+
+        let write_at = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            state.started_at.get_or_insert(now);
+            let write_at = state.next_write_at.map_or(now, |next| next.max(now));
+            if let Some(min_interval) = self.config.min_interval() {
+                state.next_write_at = Some(write_at + min_interval);
+            }
+            write_at
+        };
+        tokio::time::sleep_until(write_at.into()).await;
+        permit
+    }
+
+    /// Records a confirmed write, which needed `retries` attempts beyond the first one.
+    pub fn record_write(&self, retries: usize) -> BufferWriteStats {
+        let mut state = self.state.lock().unwrap();
+        state.chunks_written += 1;
+        state.retries += retries;
+        let elapsed = state.started_at.map(|t| t.elapsed().as_secs_f64()).unwrap_or_default();
+        BufferWriteStats {
+            chunks_written: state.chunks_written,
+            retries: state.retries,
+            chunks_per_second: if elapsed > 0.0 {
+                state.chunks_written as f64 / elapsed
+            } else {
+                state.chunks_written as f64
+            },
+        }
+    }
+
+    /// The delay before resending a write that failed `attempt` times.
+    pub fn retry_backoff(&self, attempt: usize) -> Duration {
+        Duration::from_millis(500).saturating_mul(1 << attempt.min(4))
+    }
+}
+
+/// Sends the `WriteToBuffer` transaction of `deployment_transaction`, within the limits of
+/// `limiter`. The first attempt sends `signed_transaction` as is; since a failed write may be
+/// retried after its blockhash expired, each retry is signed again with a fresh blockhash.
+///
+/// Returns the signature of the write, along with the number of retries it took.
+pub async fn write_buffer_chunk(
+    rpc_client: &RpcClient,
+    deployment_transaction: &DeploymentTransaction,
+    signed_transaction: Transaction,
+    limiter: &BufferWriteLimiter,
+    logger: &LogDispatcher,
+) -> Result<(Signature, usize), Diagnostic> {
+    let mut transaction = signed_transaction;
+    let mut attempt = 0;
+    loop {
+        let permit = limiter.acquire().await;
+        let res = send_buffer_write(
+            rpc_client,
+            &transaction,
+            deployment_transaction.do_await_confirmation,
+        )
+        .await;
+        drop(permit);
+
+        let err = match res {
+            Ok(signature) => return Ok((signature, attempt)),
+            Err(e) if attempt >= limiter.config.max_retries_per_chunk => return Err(e),
+            Err(e) => e,
+        };
+        attempt += 1;
+        logger.warn(
+            "Retrying",
+            format!(
+                "Buffer write failed ({}); retrying ({}/{})",
+                err.message, attempt, limiter.config.max_retries_per_chunk
+            ),
+        );
+        tokio::time::sleep(limiter.retry_backoff(attempt)).await;
+        transaction = resign_buffer_write(rpc_client, deployment_transaction).await?;
+    }
+}
+
+async fn send_buffer_write(
+    rpc_client: &RpcClient,
+    transaction: &Transaction,
+    do_await_confirmation: bool,
+) -> Result<Signature, Diagnostic> {
+    let res = if do_await_confirmation {
+        rpc_client.send_and_confirm_transaction(transaction).await
+    } else {
+        rpc_client
+            .send_transaction_with_config(
+                transaction,
+                RpcSendTransactionConfig {
+                    skip_preflight: true,
+                    preflight_commitment: Some(rpc_client.commitment().commitment),
+                    ..Default::default()
+                },
+            )
+            .await
+    };
+    res.map_err(|e| {
+        diagnosed_error!(
+            "unable to send transaction ({})",
+            describe_client_error(&e, transaction, &[], None)
+        )
+        .with_diagnostic_code(&classify_transaction_error(e.get_transaction_error().as_ref()))
+    })
+}
+
+async fn resign_buffer_write(
+    rpc_client: &RpcClient,
+    deployment_transaction: &DeploymentTransaction,
+) -> Result<Transaction, Diagnostic> {
+    let blockhash = rpc_client
+        .get_latest_blockhash()
+        .await
+        .map_err(|e| diagnosed_error!("failed to get latest blockhash: {e}"))?;
+    let mut transaction = deployment_transaction
+        .transaction
+        .clone()
+        .ok_or_else(|| diagnosed_error!("missing buffer write transaction"))?;
+    let keypairs = deployment_transaction.get_keypairs()?;
+    transaction
+        .try_sign(&keypairs, blockhash)
+        .map_err(|e| diagnosed_error!("failed to sign transaction: {e}"))?;
+    Ok(transaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde_json::{json, Value as JsonValue};
+    use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+    use solana_hash::Hash;
+    use solana_keypair::Keypair;
+    use solana_loader_v3_interface::instruction as bpf_loader_upgradeable;
+    use solana_message::Message;
+    use solana_pubkey::Pubkey;
+    use solana_signer::Signer;
+    use txtx_addon_kit::channel;
+    use txtx_addon_kit::futures::future::join_all;
+    use txtx_addon_kit::types::Did;
+    use txtx_addon_kit::uuid::Uuid;
+
+    use super::*;
+
+    #[test]
+    fn it_reads_config_from_values() {
+        let mut values = ValueStore::tmp();
+        assert_eq!(BufferWriteConfig::from_values(&values).unwrap(), BufferWriteConfig::default());
+
+        values.insert(WRITE_CONCURRENCY, Value::integer(8));
+        values.insert(WRITES_PER_SECOND, Value::integer(0));
+        let config = BufferWriteConfig::from_values(&values).unwrap();
+        assert_eq!(config.write_concurrency, 8);
+        assert_eq!(config.min_interval(), None);
+        assert_eq!(config.max_retries_per_chunk, DEFAULT_MAX_RETRIES_PER_CHUNK);
+
+        values.insert(WRITES_PER_SECOND, Value::float(0.5));
+        let config = BufferWriteConfig::from_values(&values).unwrap();
+        assert_eq!(config.min_interval(), Some(Duration::from_secs(2)));
+        values.insert(WRITES_PER_SECOND, Value::integer(u32::MAX as i128 + 1));
+        let config = BufferWriteConfig::from_values(&values).unwrap();
+        assert!(config.min_interval().is_some());
+        values.insert(WRITES_PER_SECOND, Value::float(-1.0));
+        assert!(BufferWriteConfig::from_values(&values).is_err());
+        values.insert(WRITES_PER_SECOND, Value::integer(0));
+
+        values.insert(WRITE_CONCURRENCY, Value::integer(0));
+        assert!(BufferWriteConfig::from_values(&values).is_err());
+        values.insert(WRITE_CONCURRENCY, Value::integer(-1));
+        assert!(BufferWriteConfig::from_values(&values).is_err());
+    }
+
+    #[tokio::test]
+    async fn it_paces_writes() {
+        let limiter = BufferWriteLimiter::new(BufferWriteConfig {
+            write_concurrency: 4,
+            writes_per_second: 20.0,
+            max_retries_per_chunk: 0,
+        });
+        let start = Instant::now();
+        for _ in 0..5 {
+            let _permit = limiter.acquire().await;
+            limiter.record_write(0);
+        }
+        // the first write is immediate, the following four are spaced by 50ms
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn it_limits_in_flight_writes() {
+        let limiter = BufferWriteLimiter::new(BufferWriteConfig {
+            write_concurrency: 2,
+            writes_per_second: 0.0,
+            max_retries_per_chunk: 0,
+        });
+        let in_flight = AtomicUsize::new(0);
+        let max_in_flight = AtomicUsize::new(0);
+        join_all((0..8).map(|_| async {
+            let _permit = limiter.acquire().await;
+            let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            max_in_flight.fetch_max(current, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            limiter.record_write(1);
+        }))
+        .await;
+        // the writes did overlap, but never beyond the configured concurrency
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+
+        let stats = limiter.record_write(0);
+        assert_eq!(stats.chunks_written, 9);
+        assert_eq!(stats.retries, 8);
+        assert_eq!(limiter.in_flight.available_permits(), 2);
+    }
+
+    #[test]
+    fn it_shares_limiters_per_deployment() {
+        let construct_did = ConstructDid(Did::from_components(vec!["deploy".as_bytes()]));
+        let limiter = BufferWriteLimiter::for_deployment(&construct_did, Default::default());
+        let same = BufferWriteLimiter::for_deployment(
+            &construct_did,
+            BufferWriteConfig { write_concurrency: 1, ..Default::default() },
+        );
+        assert!(Arc::ptr_eq(&limiter, &same));
+        assert_eq!(same.config.write_concurrency, DEFAULT_WRITE_CONCURRENCY);
+
+        BufferWriteLimiter::release_deployment(&construct_did);
+        let fresh = BufferWriteLimiter::for_deployment(
+            &construct_did,
+            BufferWriteConfig { write_concurrency: 1, ..Default::default() },
+        );
+        assert!(!Arc::ptr_eq(&limiter, &fresh));
+        BufferWriteLimiter::release_deployment(&construct_did);
+    }
+
+    /// Answers the JSON-RPC requests of buffer writes. The first `sendTransaction` is rejected,
+    /// and every transaction received is recorded.
+    fn rpc_response(request: &JsonValue, sent: &Mutex<Vec<Transaction>>) -> JsonValue {
+        let result = match request["method"].as_str().unwrap() {
+            "getLatestBlockhash" => json!({
+                "context": { "slot": 1 },
+                "value": { "blockhash": FRESH_BLOCKHASH.to_string(), "lastValidBlockHeight": 100 }
+            }),
+            "sendTransaction" => {
+                let bytes = STANDARD.decode(request["params"][0].as_str().unwrap()).unwrap();
+                let transaction: Transaction = bincode::deserialize(&bytes).unwrap();
+                let signature = transaction.signatures[0].to_string();
+                let mut sent = sent.lock().unwrap();
+                sent.push(transaction);
+                if sent.len() == 1 {
+                    return json!({
+                        "jsonrpc": "2.0",
+                        "id": request["id"],
+                        "error": { "code": -32002, "message": "Blockhash not found" }
+                    });
+                }
+                json!(signature)
+            }
+            "getSignatureStatuses" => json!({
+                "context": { "slot": 1 },
+                "value": [{
+                    "slot": 1,
+                    "confirmations": null,
+                    "status": { "Ok": null },
+                    "err": null,
+                    "confirmationStatus": "confirmed"
+                }]
+            }),
+            "getVersion" => json!({ "solana-core": "3.0.0", "feature-set": 1 }),
+            method => panic!("unexpected rpc method {method}"),
+        };
+        json!({ "jsonrpc": "2.0", "id": request["id"], "result": result })
+    }
+
+    const STALE_BLOCKHASH: Hash = Hash::new_from_array([1; 32]);
+    const FRESH_BLOCKHASH: Hash = Hash::new_from_array([2; 32]);
+
+    #[tokio::test]
+    async fn it_writes_buffer_chunks_and_resigns_retries() {
+        let sent = Arc::new(Mutex::new(vec![]));
+        let mut server = mockito::Server::new_async().await;
+        let rpc = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body_from_request({
+                let sent = sent.clone();
+                move |request| {
+                    let request: JsonValue =
+                        serde_json::from_slice(request.body().unwrap()).unwrap();
+                    rpc_response(&request, &sent).to_string().into_bytes()
+                }
+            })
+            .expect_at_least(1)
+            .create_async()
+            .await;
+
+        let rpc_client =
+            RpcClient::new_with_commitment(server.url(), CommitmentConfig::confirmed());
+        let authority = Keypair::new();
+        let buffer = Pubkey::new_unique();
+        let chunks = [vec![1u8; 16], vec![2u8; 16], vec![3u8; 16]];
+        let writes = chunks
+            .iter()
+            .enumerate()
+            .map(|(i, chunk)| {
+                let instruction = bpf_loader_upgradeable::write(
+                    &buffer,
+                    &authority.pubkey(),
+                    (i * chunk.len()) as u32,
+                    chunk.clone(),
+                );
+                let message = Message::new_with_blockhash(
+                    &[instruction],
+                    Some(&authority.pubkey()),
+                    &STALE_BLOCKHASH,
+                );
+                let transaction = Transaction::new_unsigned(message);
+                let is_last = i == chunks.len() - 1;
+                let deployment_transaction = DeploymentTransaction::write_to_buffer(
+                    &transaction,
+                    vec![&authority],
+                    if is_last { CommitmentLevel::Confirmed } else { CommitmentLevel::Processed },
+                    is_last,
+                    false,
+                );
+                let mut signed_transaction = transaction.clone();
+                signed_transaction.sign(&[&authority], STALE_BLOCKHASH);
+                (deployment_transaction, signed_transaction)
+            })
+            .collect::<Vec<_>>();
+
+        let limiter = BufferWriteLimiter::new(BufferWriteConfig {
+            write_concurrency: 2,
+            writes_per_second: 0.0,
+            max_retries_per_chunk: 1,
+        });
+        let (tx, _rx) = channel::unbounded();
+        let logger = LogDispatcher::new(Uuid::new_v4(), "svm::deploy_program", &tx);
+
+        let results =
+            join_all(writes.iter().map(|(deployment_transaction, signed_transaction)| {
+                write_buffer_chunk(
+                    &rpc_client,
+                    deployment_transaction,
+                    signed_transaction.clone(),
+                    &limiter,
+                    &logger,
+                )
+            }))
+            .await;
+        let retries = results.into_iter().map(|res| res.unwrap().1).sum::<usize>();
+        assert_eq!(retries, 1);
+        rpc.assert_async().await;
+
+        // every chunk was sent once, and the rejected one was sent again with a fresh blockhash
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), chunks.len() + 1);
+        assert!(sent.iter().all(|transaction| transaction.verify().is_ok()));
+        assert!(sent[..chunks.len()]
+            .iter()
+            .all(|transaction| transaction.message.recent_blockhash == STALE_BLOCKHASH));
+        let retry = sent.last().unwrap();
+        assert_eq!(retry.message.recent_blockhash, FRESH_BLOCKHASH);
+        assert_eq!(retry.message.instructions, sent[0].message.instructions);
+        assert_ne!(retry.signatures, sent[0].signatures);
+    }
+}
@@ -0,0 +1,244 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use solana_account::Account;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_keypair::Keypair;
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_pubkey::Pubkey;
+use txtx_addon_kit::helpers::fs::FileLocation;
+use txtx_addon_kit::types::diagnostics::Diagnostic;
+
+const RECOVERY_DIRECTORY: &str = ".txtx/recovery";
+
+/// What's needed to resume an interrupted program deployment: the ephemeral authority owning the
+/// program buffer, and the buffer itself. The record is saved under the manifest directory when the
+/// deployment transactions are generated, and removed once the deployment completes. It holds a
+/// secret key, so it's only readable by its owner.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeploymentRecovery {
+    pub ephemeral_authority_secret_key: Vec<u8>,
+    pub buffer_pubkey: Pubkey,
+}
+
+impl DeploymentRecovery {
+    pub fn new(ephemeral_authority: &Keypair, buffer_pubkey: Pubkey) -> Self {
+        Self {
+            ephemeral_authority_secret_key: ephemeral_authority.to_bytes().to_vec(),
+            buffer_pubkey,
+        }
+    }
+
+    /// The location of the recovery record of the deployment of `program_id`, under `base`.
+    pub fn location(base: &FileLocation, program_id: &Pubkey) -> FileLocation {
+        let mut location = base.clone();
+        let _ = location.append_path(&format!("{}/svm-{}.json", RECOVERY_DIRECTORY, program_id));
+        location
+    }
+
+    pub fn load(base: &FileLocation, program_id: &Pubkey) -> Option<Self> {
+        let location = Self::location(base, program_id);
+        if !location.exists() {
+            return None;
+        }
+        let content = location.read_content().ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    pub fn save(&self, base: &FileLocation, program_id: &Pubkey) -> Result<(), Diagnostic> {
+        let content = serde_json::to_vec_pretty(self)
+            .map_err(|e| diagnosed_error!("failed to serialize deployment recovery: {e}"))?;
+        let path = Self::location(base, program_id).expect_path_buf();
+        write_private_file(&path, &content).map_err(|e| {
+            diagnosed_error!("failed to save deployment recovery to {}: {e}", path.display())
+        })
+    }
+
+    pub fn clear(base: &FileLocation, program_id: &Pubkey) {
+        let location = Self::location(base, program_id);
+        if location.exists() {
+            let _ = std::fs::remove_file(location.expect_path_buf());
+        }
+    }
+
+    pub fn ephemeral_authority(&self) -> Result<Keypair, Diagnostic> {
+        Keypair::try_from(self.ephemeral_authority_secret_key.as_ref()).map_err(|e| {
+            diagnosed_error!("invalid ephemeral authority in deployment recovery: {e}")
+        })
+    }
+}
+
+/// Writes `content` to `path`, creating its parent directories, with the file only readable and
+/// writable by its owner.
+fn write_private_file(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let mut file = options.open(path)?;
+        // the mode only applies to new files: restrict a record left by an earlier version as well
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        file.write_all(content)
+    }
+    #[cfg(not(unix))]
+    {
+        options.open(path)?.write_all(content)
+    }
+}
+
+/// Finds the program buffer an interrupted deployment of `binary` left behind, and `authority` can
+/// resume writing to. The `recorded` buffer is checked first; otherwise, the buffers owned by
+/// `authority` are looked up on chain, failing if they can't be listed.
+pub fn find_resumable_buffer(
+    rpc_client: &RpcClient,
+    recorded: Option<Pubkey>,
+    authority: &Pubkey,
+    binary: &[u8],
+) -> Result<Option<Pubkey>, Diagnostic> {
+    if let Some(buffer_pubkey) = recorded {
+        if let Ok(account) = rpc_client.get_account(&buffer_pubkey) {
+            if let Some(buffer_pubkey) =
+                select_resumable_buffer(&[(buffer_pubkey, account)], authority, binary)
+            {
+                return Ok(Some(buffer_pubkey));
+            }
+        }
+    }
+
+    // buffer accounts start with the `Buffer` variant tag, followed by `Some(authority)`
+    let mut buffer_prefix = vec![1, 0, 0, 0, 1];
+    buffer_prefix.extend_from_slice(authority.as_ref());
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, buffer_prefix))]),
+        ..Default::default()
+    };
+    let candidates = rpc_client
+        .get_program_accounts_with_config(&solana_sdk_ids::bpf_loader_upgradeable::id(), config)
+        .map_err(|e| {
+            diagnosed_error!("failed to look up the buffers of an interrupted deployment: {e}")
+        })?;
+    Ok(select_resumable_buffer(&candidates, authority, binary))
+}
+
+/// Picks, among the `candidates` buffer accounts, the one `authority` can resume writing `binary`
+/// to: it must be writable by `authority` and large enough for `binary`. When several buffers
+/// qualify, the one already holding the longest prefix of `binary` wins.
+pub fn select_resumable_buffer(
+    candidates: &[(Pubkey, Account)],
+    authority: &Pubkey,
+    binary: &[u8],
+) -> Option<Pubkey> {
+    candidates
+        .iter()
+        .filter_map(|(pubkey, account)| {
+            let (buffer_authority, program_bytes) = read_buffer(account)?;
+            if buffer_authority != Some(*authority) || program_bytes.len() < binary.len() {
+                return None;
+            }
+            let written = program_bytes.iter().zip(binary).take_while(|(a, b)| a == b).count();
+            Some((written, *pubkey))
+        })
+        .max_by_key(|(written, _)| *written)
+        .map(|(_, pubkey)| pubkey)
+}
+
+/// Reads a program buffer account, returning its authority and the program bytes it holds.
+fn read_buffer(account: &Account) -> Option<(Option<Pubkey>, &[u8])> {
+    if account.owner != solana_sdk_ids::bpf_loader_upgradeable::id() {
+        return None;
+    }
+    let metadata_len = UpgradeableLoaderState::size_of_buffer_metadata();
+    match bincode::deserialize(account.data.get(..metadata_len)?).ok()? {
+        UpgradeableLoaderState::Buffer { authority_address } => {
+            Some((authority_address, &account.data[metadata_len..]))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_signer::Signer;
+
+    use super::*;
+
+    fn buffer_account(authority: Option<Pubkey>, program_bytes: &[u8]) -> Account {
+        let mut data =
+            bincode::serialize(&UpgradeableLoaderState::Buffer { authority_address: authority })
+                .unwrap();
+        data.resize(UpgradeableLoaderState::size_of_buffer_metadata(), 0);
+        data.extend_from_slice(program_bytes);
+        Account {
+            lamports: 1,
+            data,
+            owner: solana_sdk_ids::bpf_loader_upgradeable::id(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn it_selects_the_buffer_holding_the_longest_prefix() {
+        let authority = Pubkey::new_unique();
+        let binary = (0..200u8).collect::<Vec<_>>();
+        let mut partial = binary[..120].to_vec();
+        partial.resize(binary.len(), 0);
+
+        let empty = (Pubkey::new_unique(), buffer_account(Some(authority), &[0; 200]));
+        let resumable = (Pubkey::new_unique(), buffer_account(Some(authority), &partial));
+        let foreign = (Pubkey::new_unique(), buffer_account(Some(Pubkey::new_unique()), &binary));
+        let too_small = (Pubkey::new_unique(), buffer_account(Some(authority), &binary[..150]));
+        let frozen = (Pubkey::new_unique(), buffer_account(None, &binary));
+
+        let candidates = vec![empty.clone(), resumable.clone(), foreign, too_small, frozen];
+        assert_eq!(select_resumable_buffer(&candidates, &authority, &binary), Some(resumable.0));
+        assert_eq!(select_resumable_buffer(&[empty.clone()], &authority, &binary), Some(empty.0));
+        assert_eq!(select_resumable_buffer(&candidates[2..], &authority, &binary), None);
+    }
+
+    #[test]
+    fn it_saves_and_clears_recovery_records() {
+        let base = FileLocation::from_path(
+            std::env::temp_dir().join(format!("txtx-recovery-{}", Pubkey::new_unique())),
+        );
+        let program_id = Pubkey::new_unique();
+        assert_eq!(DeploymentRecovery::load(&base, &program_id), None);
+
+        let authority = Keypair::new();
+        let recovery = DeploymentRecovery::new(&authority, Pubkey::new_unique());
+        recovery.save(&base, &program_id).unwrap();
+        let loaded = DeploymentRecovery::load(&base, &program_id).unwrap();
+        assert_eq!(loaded, recovery);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let path = DeploymentRecovery::location(&base, &program_id).expect_path_buf();
+            let mode = std::fs::metadata(path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert_eq!(loaded.ephemeral_authority().unwrap().pubkey(), authority.pubkey());
+
+        DeploymentRecovery::clear(&base, &program_id);
+        assert_eq!(DeploymentRecovery::load(&base, &program_id), None);
+        let _ = std::fs::remove_dir_all(base.expect_path_buf());
+    }
+
+    #[test]
+    fn it_fails_when_the_buffers_cannot_be_listed() {
+        let mut server = mockito::Server::new();
+        let _mock = server.mock("POST", "/").with_status(500).create();
+        let rpc_client = RpcClient::new(server.url());
+
+        let result = find_resumable_buffer(&rpc_client, None, &Pubkey::new_unique(), &[1, 2, 3]);
+        assert!(result.is_err());
+    }
+}
@@ -1,7 +1,10 @@
 pub mod anchor;
+pub mod buffer_writes;
+pub mod deployment_recovery;
 pub mod idl;
 pub mod instruction;
 pub mod native;
+pub mod program_error;
 pub mod send_transaction;
 pub mod squads;
 pub mod ui_encode;
@@ -208,6 +211,31 @@ impl DeploymentTransaction {
         )
     }
 
+    /// The first write to an existing buffer account that already holds a prefix of the program
+    /// binary, left behind by an interrupted deployment. Writes resume from `resume_offset`.
+    pub fn resume_write_to_buffer(
+        transaction: &Transaction,
+        keypairs: Vec<&Keypair>,
+        commitment_level: CommitmentLevel,
+        do_await_confirmation: bool,
+        buffer_pubkey: Pubkey,
+        resume_offset: u32,
+        is_upgrade: bool,
+    ) -> Self {
+        Self::new(
+            transaction,
+            keypairs,
+            None,
+            DeploymentTransactionType::ResumeWriteToBuffer {
+                buffer_pubkey,
+                resume_offset,
+                is_upgrade,
+            },
+            commitment_level,
+            do_await_confirmation,
+        )
+    }
+
     pub fn transfer_buffer_authority(transaction: &Transaction, keypairs: Vec<&Keypair>) -> Self {
         Self::new(
             transaction,
@@ -390,6 +418,7 @@ impl DeploymentTransaction {
             DeploymentTransactionType::CreateBufferAndExtendProgram { .. } => return Ok(None),
             DeploymentTransactionType::ExtendProgram => return Ok(None),
             DeploymentTransactionType::WriteToBuffer { .. } => return Ok(None),
+            DeploymentTransactionType::ResumeWriteToBuffer { .. } => return Ok(None),
             DeploymentTransactionType::TransferBufferAuthority => return Ok(None),
             DeploymentTransactionType::TransferProgramAuthority => return Ok(None),
             DeploymentTransactionType::DeployProgram => "This transaction will deploy the program.",
@@ -508,6 +537,15 @@ impl DeploymentTransaction {
                     format!("Creating program buffer account at pubkey {}", buffer_pubkey),
                 );
             }
+            DeploymentTransactionType::ResumeWriteToBuffer { buffer_pubkey, resume_offset, .. } => {
+                logger.info(
+                    "[Recovery Instructions]",
+                    format!(
+                        "Resuming writes to existing program buffer account {} from byte offset {}",
+                        buffer_pubkey, resume_offset
+                    ),
+                );
+            }
             _ => {}
         };
 
@@ -559,7 +597,8 @@ impl DeploymentTransaction {
                 logger
                     .info("Program Upgraded", format!("Program {} has been upgraded", program_id));
             }
-            DeploymentTransactionType::WriteToBuffer { is_upgrade } =>
+            DeploymentTransactionType::WriteToBuffer { is_upgrade }
+            | DeploymentTransactionType::ResumeWriteToBuffer { is_upgrade, .. } =>
             // if it's a buffer write and do_await_confirmation=true, this is our last buffer write tx
             {
                 if self.do_await_confirmation {
@@ -1322,35 +1361,58 @@ impl UpgradeableProgramDeployer {
         let mut write_transactions = vec![];
         let chunk_size = calculate_max_chunk_size(&create_msg);
 
-        let chunks = self.binary.chunks(chunk_size).collect::<Vec<_>>();
-
-        for (chunk, i) in chunks.iter().zip(0usize..) {
-            let offset = i.saturating_mul(chunk_size);
-
-            let written_chunk = &self.buffer_data.get(offset..offset.saturating_add(chunk.len()));
-            // Only write the chunk if it differs from our initial buffer data
-            let do_write = written_chunk.is_none() || written_chunk.unwrap() != *chunk;
-            if do_write {
-                let transaction =
-                    Transaction::new_unsigned(create_msg(offset as u32, chunk.to_vec()));
-
-                let (do_await_confirmation, commitment_level) = if i == chunks.len() - 1 {
-                    (true, CommitmentLevel::Confirmed)
-                } else {
-                    (false, CommitmentLevel::Processed)
-                };
+        let Some(resume_offset) =
+            get_buffer_resume_offset(&self.buffer_data, &self.binary, chunk_size)
+        else {
+            // the buffer already holds the full binary
+            return Ok(write_transactions);
+        };
+        // an existing buffer whose prefix already matches the binary was left behind by an
+        // interrupted deployment, so we pick up from the first mismatching chunk
+        let is_resumed = self.buffer_keypair.is_none() && resume_offset > 0;
+
+        // Only write the chunks that differ from our initial buffer data
+        let chunks = self
+            .binary
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(i, chunk)| (i.saturating_mul(chunk_size), chunk))
+            .filter(|(offset, chunk)| {
+                *offset >= resume_offset
+                    && self.buffer_data.get(*offset..offset.saturating_add(chunk.len()))
+                        != Some(*chunk)
+            })
+            .collect::<Vec<_>>();
+
+        for (i, (offset, chunk)) in chunks.iter().enumerate() {
+            let transaction = Transaction::new_unsigned(create_msg(*offset as u32, chunk.to_vec()));
+
+            let (do_await_confirmation, commitment_level) = if i == chunks.len() - 1 {
+                (true, CommitmentLevel::Confirmed)
+            } else {
+                (false, CommitmentLevel::Processed)
+            };
 
-                write_transactions.push(
-                    DeploymentTransaction::write_to_buffer(
-                        &transaction,
-                        vec![&self.temp_upgrade_authority],
-                        commitment_level,
-                        do_await_confirmation,
-                        self.is_program_upgrade,
-                    )
-                    .to_value()?,
-                );
-            }
+            let write_transaction = if i == 0 && is_resumed {
+                DeploymentTransaction::resume_write_to_buffer(
+                    &transaction,
+                    vec![&self.temp_upgrade_authority],
+                    commitment_level,
+                    do_await_confirmation,
+                    self.buffer_pubkey,
+                    *offset as u32,
+                    self.is_program_upgrade,
+                )
+            } else {
+                DeploymentTransaction::write_to_buffer(
+                    &transaction,
+                    vec![&self.temp_upgrade_authority],
+                    commitment_level,
+                    do_await_confirmation,
+                    self.is_program_upgrade,
+                )
+            };
+            write_transactions.push(write_transaction.to_value()?);
         }
         Ok(write_transactions)
     }
@@ -1646,6 +1708,23 @@ where
     PACKET_DATA_SIZE.saturating_sub(tx_size).saturating_sub(1)
 }
 
+/// Returns the offset of the first `chunk_size` chunk of `binary` that is missing from, or differs
+/// from, the data already written to a program buffer. Returns `None` if the buffer already holds
+/// the full binary.
+pub fn get_buffer_resume_offset(
+    buffer_data: &[u8],
+    binary: &[u8],
+    chunk_size: usize,
+) -> Option<usize> {
+    binary.chunks(chunk_size).enumerate().find_map(|(i, chunk)| {
+        let offset = i.saturating_mul(chunk_size);
+        match buffer_data.get(offset..offset.saturating_add(chunk.len())) {
+            Some(written_chunk) if written_chunk == chunk => None,
+            _ => Some(offset),
+        }
+    })
+}
+
 pub fn transaction_is_fully_signed(transaction: &Transaction) -> bool {
     let expected_signature_count = transaction.message.header.num_required_signatures as usize;
     let actual_signature_count = transaction.signatures.len();
@@ -1727,3 +1806,136 @@ pub fn validate_program_so(bin: &[u8]) -> Result<(), Diagnostic> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binary() -> Vec<u8> {
+        (0..5_000usize).map(|i| (i % 251) as u8 + 1).collect()
+    }
+
+    fn deployer(
+        buffer_data: Vec<u8>,
+        buffer_keypair: Option<Keypair>,
+    ) -> UpgradeableProgramDeployer {
+        let temp_upgrade_authority = Keypair::new();
+        UpgradeableProgramDeployer {
+            program_pubkey: Pubkey::new_unique(),
+            program_keypair: None,
+            payer_pubkey: Pubkey::new_unique(),
+            final_upgrade_authority_pubkey: Pubkey::new_unique(),
+            temp_upgrade_authority_pubkey: temp_upgrade_authority.pubkey(),
+            temp_upgrade_authority,
+            buffer_pubkey: Pubkey::new_unique(),
+            buffer_keypair,
+            buffer_data,
+            binary: binary(),
+            rpc_client: RpcClient::new("http://127.0.0.1:8899".to_string()),
+            auto_extend: true,
+            is_program_upgrade: false,
+            is_surfnet: false,
+            do_cheatcode_deploy: false,
+        }
+    }
+
+    fn chunk_size(deployer: &UpgradeableProgramDeployer) -> usize {
+        calculate_max_chunk_size(&|offset: u32, bytes: Vec<u8>| {
+            Message::new_with_blockhash(
+                &[bpf_loader_upgradeable::write(
+                    &deployer.buffer_pubkey,
+                    &deployer.temp_upgrade_authority_pubkey,
+                    offset,
+                    bytes,
+                )],
+                Some(&deployer.temp_upgrade_authority_pubkey),
+                &Hash::default(),
+            )
+        })
+    }
+
+    fn write_transactions(deployer: &UpgradeableProgramDeployer) -> Vec<DeploymentTransaction> {
+        deployer
+            .get_write_to_buffer_transactions(&Hash::default())
+            .unwrap()
+            .iter()
+            .map(|v| DeploymentTransaction::from_value(v).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn it_finds_buffer_resume_offset() {
+        let binary = binary();
+        assert_eq!(get_buffer_resume_offset(&binary, &binary, 1_000), None);
+        assert_eq!(get_buffer_resume_offset(&[], &binary, 1_000), Some(0));
+
+        let truncated = binary[..2_500].to_vec();
+        assert_eq!(get_buffer_resume_offset(&truncated, &binary, 1_000), Some(2_000));
+
+        let mut corrupted = binary.clone();
+        corrupted[3_456] = 0;
+        assert_eq!(get_buffer_resume_offset(&corrupted, &binary, 1_000), Some(3_000));
+    }
+
+    #[test]
+    fn it_writes_full_binary_to_new_buffer() {
+        let deployer = deployer(vec![0; binary().len()], Some(Keypair::new()));
+        let chunk_count = binary().len().div_ceil(chunk_size(&deployer));
+
+        let transactions = write_transactions(&deployer);
+        assert_eq!(transactions.len(), chunk_count);
+        assert!(transactions.iter().all(|tx| tx.transaction_type
+            == DeploymentTransactionType::WriteToBuffer { is_upgrade: false }));
+        assert!(transactions.last().unwrap().do_await_confirmation);
+    }
+
+    #[test]
+    fn it_resumes_writes_to_truncated_buffer() {
+        let mut buffer_data = binary();
+        let chunk_size = chunk_size(&deployer(vec![], None));
+        buffer_data.truncate(chunk_size * 2 + 10);
+        let deployer = deployer(buffer_data, None);
+        let chunk_count = binary().len().div_ceil(chunk_size);
+
+        let transactions = write_transactions(&deployer);
+        assert_eq!(transactions.len(), chunk_count - 2);
+        assert_eq!(
+            transactions[0].transaction_type,
+            DeploymentTransactionType::ResumeWriteToBuffer {
+                buffer_pubkey: deployer.buffer_pubkey,
+                resume_offset: (chunk_size * 2) as u32,
+                is_upgrade: false,
+            }
+        );
+        assert!(transactions[1..].iter().all(|tx| tx.transaction_type
+            == DeploymentTransactionType::WriteToBuffer { is_upgrade: false }));
+        assert!(transactions.last().unwrap().do_await_confirmation);
+    }
+
+    #[test]
+    fn it_resumes_writes_to_corrupted_buffer() {
+        let mut buffer_data = binary();
+        let chunk_size = chunk_size(&deployer(vec![], None));
+        // corrupt a single byte in the second chunk; the rest of the buffer is intact
+        buffer_data[chunk_size + 1] ^= 0xff;
+        let deployer = deployer(buffer_data, None);
+
+        let transactions = write_transactions(&deployer);
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(
+            transactions[0].transaction_type,
+            DeploymentTransactionType::ResumeWriteToBuffer {
+                buffer_pubkey: deployer.buffer_pubkey,
+                resume_offset: chunk_size as u32,
+                is_upgrade: false,
+            }
+        );
+        assert!(transactions[0].do_await_confirmation);
+    }
+
+    #[test]
+    fn it_skips_writes_to_complete_buffer() {
+        let deployer = deployer(binary(), None);
+        assert!(write_transactions(&deployer).is_empty());
+    }
+}
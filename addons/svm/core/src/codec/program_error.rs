@@ -0,0 +1,411 @@
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+use solana_client::rpc_client::RpcClient;
+use solana_instruction::error::InstructionError;
+use solana_pubkey::Pubkey;
+use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_network_svm_types::anchor::types::Idl;
+
+use crate::codec::idl::IdlRef;
+use crate::constants::{INSTRUCTION, PROGRAM_IDL};
+
+/// The seed of the account in which Anchor programs publish their IDL.
+const ANCHOR_IDL_SEED: &str = "anchor:idl";
+
+/// The first error code available to Anchor programs for their own errors.
+pub const ANCHOR_CUSTOM_ERROR_OFFSET: u32 = 6000;
+
+/// The errors raised by the Anchor framework itself (`anchor_lang::error::ErrorCode`, as of
+/// anchor-lang 0.30), for codes below [ANCHOR_CUSTOM_ERROR_OFFSET].
+const ANCHOR_FRAMEWORK_ERRORS: &[(u32, &str, &str)] = &[
+    (100, "InstructionMissing", "8 byte instruction identifier not provided"),
+    (101, "InstructionFallbackNotFound", "Fallback functions are not supported"),
+    (
+        102,
+        "InstructionDidNotDeserialize",
+        "The program could not deserialize the given instruction",
+    ),
+    (103, "InstructionDidNotSerialize", "The program could not serialize the given instruction"),
+    (1000, "IdlInstructionStub", "The program was compiled without idl instructions"),
+    (1001, "IdlInstructionInvalidProgram", "Invalid program given to the IDL instruction"),
+    (1002, "IdlAccountNotEmpty", "IDL account must be empty in order to resize, try closing first"),
+    (1500, "EventInstructionStub", "The program was compiled without `event-cpi` feature"),
+    (2000, "ConstraintMut", "A mut constraint was violated"),
+    (2001, "ConstraintHasOne", "A has one constraint was violated"),
+    (2002, "ConstraintSigner", "A signer constraint was violated"),
+    (2003, "ConstraintRaw", "A raw constraint was violated"),
+    (2004, "ConstraintOwner", "An owner constraint was violated"),
+    (2005, "ConstraintRentExempt", "A rent exemption constraint was violated"),
+    (2006, "ConstraintSeeds", "A seeds constraint was violated"),
+    (2007, "ConstraintExecutable", "An executable constraint was violated"),
+    (2008, "ConstraintState", "Deprecated Error, feel free to replace with something else"),
+    (2009, "ConstraintAssociated", "An associated constraint was violated"),
+    (2010, "ConstraintAssociatedInit", "An associated init constraint was violated"),
+    (2011, "ConstraintClose", "A close constraint was violated"),
+    (2012, "ConstraintAddress", "An address constraint was violated"),
+    (2013, "ConstraintZero", "Expected zero account discriminant"),
+    (2014, "ConstraintTokenMint", "A token mint constraint was violated"),
+    (2015, "ConstraintTokenOwner", "A token owner constraint was violated"),
+    (2016, "ConstraintMintMintAuthority", "A mint mint authority constraint was violated"),
+    (2017, "ConstraintMintFreezeAuthority", "A mint freeze authority constraint was violated"),
+    (2018, "ConstraintMintDecimals", "A mint decimals constraint was violated"),
+    (2019, "ConstraintSpace", "A space constraint was violated"),
+    (2020, "ConstraintAccountIsNone", "A required account for the constraint is None"),
+    (2021, "ConstraintTokenTokenProgram", "A token account token program constraint was violated"),
+    (2022, "ConstraintMintTokenProgram", "A mint token program constraint was violated"),
+    (
+        2023,
+        "ConstraintAssociatedTokenTokenProgram",
+        "An associated token account token program constraint was violated",
+    ),
+    (2500, "RequireViolated", "A require expression was violated"),
+    (2501, "RequireEqViolated", "A require_eq expression was violated"),
+    (2502, "RequireKeysEqViolated", "A require_keys_eq expression was violated"),
+    (2503, "RequireNeqViolated", "A require_neq expression was violated"),
+    (2504, "RequireKeysNeqViolated", "A require_keys_neq expression was violated"),
+    (2505, "RequireGtViolated", "A require_gt expression was violated"),
+    (2506, "RequireGteViolated", "A require_gte expression was violated"),
+    (
+        3000,
+        "AccountDiscriminatorAlreadySet",
+        "The account discriminator was already set on this account",
+    ),
+    (3001, "AccountDiscriminatorNotFound", "No 8 byte discriminator was found on the account"),
+    (3002, "AccountDiscriminatorMismatch", "8 byte discriminator did not match what was expected"),
+    (3003, "AccountDidNotDeserialize", "Failed to deserialize the account"),
+    (3004, "AccountDidNotSerialize", "Failed to serialize the account"),
+    (3005, "AccountNotEnoughKeys", "Not enough account keys given to the instruction"),
+    (3006, "AccountNotMutable", "The given account is not mutable"),
+    (
+        3007,
+        "AccountOwnedByWrongProgram",
+        "The given account is owned by a different program than expected",
+    ),
+    (3008, "InvalidProgramId", "Program ID was not as expected"),
+    (3009, "InvalidProgramExecutable", "Program account is not executable"),
+    (3010, "AccountNotSigner", "The given account did not sign"),
+    (3011, "AccountNotSystemOwned", "The given account is not owned by the system program"),
+    (3012, "AccountNotInitialized", "The program expected this account to be already initialized"),
+    (3013, "AccountNotProgramData", "The given account is not a program data account"),
+    (
+        3014,
+        "AccountNotAssociatedTokenAccount",
+        "The given account is not the associated token account",
+    ),
+    (3015, "AccountSysvarMismatch", "The given public key does not match the required sysvar"),
+    (
+        3016,
+        "AccountReallocExceedsLimit",
+        "The account reallocation exceeds the MAX_PERMITTED_DATA_INCREASE limit",
+    ),
+    (3017, "AccountDuplicateReallocs", "The account was duplicated for more than one reallocation"),
+    (
+        4100,
+        "DeclaredProgramIdMismatch",
+        "The declared program id does not match the actual program id",
+    ),
+    (
+        4101,
+        "TryingToInitPayerAsProgramAccount",
+        "You cannot/should not initialize the payer account as a program account",
+    ),
+    (
+        4102,
+        "InvalidNumericConversion",
+        concat!(
+            "The program could not perform the numeric conversion, ",
+            "out of range integral type conversion attempted"
+        ),
+    ),
+    (5000, "Deprecated", "The API being used is deprecated and should no longer be used"),
+];
+
+/// A custom program error, resolved to its name and message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedProgramError {
+    pub code: u32,
+    pub name: String,
+    pub msg: Option<String>,
+}
+
+impl DecodedProgramError {
+    /// Resolves a custom error `code` with the errors declared in `idl`, falling back on the errors
+    /// of the Anchor framework. Without an IDL, the program isn't known to be an Anchor program,
+    /// and its codes are left undecoded.
+    pub fn from_code(code: u32, idl: Option<&Idl>) -> Option<Self> {
+        let idl = idl?;
+        if let Some(error) = idl.errors.iter().find(|e| e.code == code) {
+            return Some(Self { code, name: error.name.clone(), msg: error.msg.clone() });
+        }
+        if code >= ANCHOR_CUSTOM_ERROR_OFFSET {
+            return None;
+        }
+        ANCHOR_FRAMEWORK_ERRORS.iter().find(|(c, _, _)| *c == code).map(|(_, name, msg)| Self {
+            code,
+            name: name.to_string(),
+            msg: Some(msg.to_string()),
+        })
+    }
+}
+
+/// Gathers the program IDLs found in the inputs of an action: the `program_idl` input, and the
+/// `program_idl` of each `instruction` block.
+pub fn get_idls_from_inputs(inputs: &ValueStore) -> Vec<Idl> {
+    let mut idl_strs = vec![];
+    if let Some(idl) = inputs.get_string(PROGRAM_IDL) {
+        idl_strs.push(idl);
+    }
+    if let Some(instructions) = inputs.get_map(INSTRUCTION) {
+        for instruction in instructions.iter() {
+            if let Some(idl) = instruction
+                .as_object()
+                .and_then(|i| i.get(PROGRAM_IDL))
+                .and_then(|idl| idl.as_string())
+            {
+                idl_strs.push(idl);
+            }
+        }
+    }
+    idl_strs.into_iter().filter_map(|idl| IdlRef::from_str(idl).ok()).map(|idl| idl.idl).collect()
+}
+
+/// The address of the account in which the Anchor program `program_id` publishes its IDL.
+pub fn anchor_idl_address(program_id: &Pubkey) -> Option<Pubkey> {
+    let (base, _) = Pubkey::find_program_address(&[], program_id);
+    Pubkey::create_with_seed(&base, ANCHOR_IDL_SEED, program_id).ok()
+}
+
+/// Decodes the IDL account of an Anchor program: a discriminator and the IDL authority, followed
+/// by the length of the zlib-compressed IDL, and the compressed IDL.
+pub fn decode_anchor_idl_account(data: &[u8]) -> Option<Idl> {
+    const LEN_OFFSET: usize = 8 + 32;
+    let len = u32::from_le_bytes(data.get(LEN_OFFSET..LEN_OFFSET + 4)?.try_into().ok()?);
+    let compressed = data.get(LEN_OFFSET + 4..LEN_OFFSET + 4 + len as usize)?;
+    let mut idl_bytes = vec![];
+    ZlibDecoder::new(compressed).read_to_end(&mut idl_bytes).ok()?;
+    IdlRef::from_bytes(&idl_bytes).ok().map(|idl_ref| idl_ref.idl)
+}
+
+/// Completes `idls` with the IDL published on chain by the program failing with
+/// `transaction_error`, when `idls` doesn't include the IDL of that program.
+pub fn with_onchain_idl(
+    rpc_client: Option<&RpcClient>,
+    transaction_error: &TransactionError,
+    transaction: &Transaction,
+    idls: &[Idl],
+) -> Vec<Idl> {
+    let mut idls = idls.to_vec();
+    let TransactionError::InstructionError(instruction_index, InstructionError::Custom(_)) =
+        transaction_error
+    else {
+        return idls;
+    };
+    let (Some(rpc_client), Some(program_id)) =
+        (rpc_client, transaction.message.program_id(*instruction_index as usize))
+    else {
+        return idls;
+    };
+    if idls.iter().any(|idl| Pubkey::try_from(idl.address.as_str()).ok() == Some(*program_id)) {
+        return idls;
+    }
+    let idl = anchor_idl_address(program_id)
+        .and_then(|address| rpc_client.get_account(&address).ok())
+        .and_then(|account| decode_anchor_idl_account(&account.data));
+    idls.extend(idl);
+    idls
+}
+
+/// Describes a transaction failure caused by a custom program error, naming the error from the
+/// IDL of the failing program when it is available. Returns `None` for any other failure.
+pub fn describe_custom_program_error(
+    transaction_error: &TransactionError,
+    transaction: &Transaction,
+    idls: &[Idl],
+) -> Option<String> {
+    let TransactionError::InstructionError(instruction_index, InstructionError::Custom(code)) =
+        transaction_error
+    else {
+        return None;
+    };
+    let program_id = transaction.message.program_id(*instruction_index as usize);
+    let idl = program_id.and_then(|program_id| {
+        idls.iter().find(|idl| Pubkey::try_from(idl.address.as_str()).ok() == Some(*program_id))
+    });
+
+    let mut description = format!(
+        "instruction #{} of program {} failed with custom program error: {:#x}",
+        instruction_index,
+        program_id.map(|p| p.to_string()).unwrap_or("unknown".into()),
+        code
+    );
+    if let Some(error) = DecodedProgramError::from_code(*code, idl) {
+        description.push_str(&format!(" ({}: {}", error.code, error.name));
+        if let Some(msg) = error.msg {
+            description.push_str(&format!(" - {}", msg));
+        }
+        description.push(')');
+    }
+    Some(description)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_instruction::Instruction;
+    use solana_message::Message;
+    use txtx_addon_kit::types::types::{ObjectType, Value};
+
+    use super::*;
+
+    const IDL: &str = r#"{
+        "address": "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS",
+        "metadata": { "name": "counter", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": [],
+        "errors": [
+            { "code": 6000, "name": "Overflow", "msg": "The counter overflowed" },
+            { "code": 6001, "name": "Unauthorized" }
+        ]
+    }"#;
+
+    fn transaction(program_id: &Pubkey) -> Transaction {
+        let payer = Pubkey::new_unique();
+        Transaction::new_unsigned(Message::new(
+            &[Instruction::new_with_bytes(*program_id, &[], vec![])],
+            Some(&payer),
+        ))
+    }
+
+    #[test]
+    fn it_decodes_errors_from_idl_and_anchor_table() {
+        let idl = IdlRef::from_str(IDL).unwrap().idl;
+        let error = DecodedProgramError::from_code(6000, Some(&idl)).unwrap();
+        assert_eq!(error.name, "Overflow");
+        assert_eq!(error.msg.as_deref(), Some("The counter overflowed"));
+        assert_eq!(DecodedProgramError::from_code(6001, Some(&idl)).unwrap().msg, None);
+        assert_eq!(DecodedProgramError::from_code(6002, Some(&idl)), None);
+
+        let error = DecodedProgramError::from_code(2006, Some(&idl)).unwrap();
+        assert_eq!(error.name, "ConstraintSeeds");
+        assert_eq!(DecodedProgramError::from_code(4242, Some(&idl)), None);
+
+        // without an IDL, the program may not be an Anchor program
+        assert_eq!(DecodedProgramError::from_code(2006, None), None);
+    }
+
+    #[test]
+    fn it_describes_custom_program_errors() {
+        let idl = IdlRef::from_str(IDL).unwrap().idl;
+        let program_id = Pubkey::try_from(idl.address.as_str()).unwrap();
+        let transaction = transaction(&program_id);
+
+        let error = TransactionError::InstructionError(0, InstructionError::Custom(0x1771));
+        let description =
+            describe_custom_program_error(&error, &transaction, &[idl.clone()]).unwrap();
+        assert_eq!(
+            description,
+            format!(
+                "instruction #0 of program {} failed with custom program error: {}",
+                program_id, "0x1771 (6001: Unauthorized)"
+            )
+        );
+
+        let error = TransactionError::InstructionError(0, InstructionError::Custom(2003));
+        let description =
+            describe_custom_program_error(&error, &transaction, &[idl.clone()]).unwrap();
+        assert!(description.ends_with("(2003: ConstraintRaw - A raw constraint was violated)"));
+
+        // native programs use low codes too, which aren't Anchor framework errors
+        let description = describe_custom_program_error(&error, &transaction, &[]).unwrap();
+        assert!(description.ends_with("custom program error: 0x7d3"));
+
+        let error = TransactionError::InstructionError(0, InstructionError::InvalidArgument);
+        assert_eq!(describe_custom_program_error(&error, &transaction, &[]), None);
+    }
+
+    #[test]
+    fn it_gathers_idls_from_inputs() {
+        let mut inputs = ValueStore::tmp();
+        inputs.insert(
+            INSTRUCTION,
+            Value::array(vec![
+                ObjectType::from(vec![(PROGRAM_IDL, Value::string(IDL.into()))]).to_value(),
+                ObjectType::from(vec![("raw_bytes", Value::string("0x".into()))]).to_value(),
+            ]),
+        );
+        assert_eq!(get_idls_from_inputs(&inputs).len(), 1);
+    }
+
+    /// The IDL account published by an Anchor program for `IDL`.
+    fn idl_account_data() -> Vec<u8> {
+        use flate2::write::ZlibEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(IDL.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let mut data = vec![0; 8];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&(compressed.len() as u32).to_le_bytes());
+        data.extend_from_slice(&compressed);
+        data
+    }
+
+    #[test]
+    fn it_decodes_anchor_idl_accounts() {
+        let idl = decode_anchor_idl_account(&idl_account_data()).unwrap();
+        assert_eq!(idl.address, IdlRef::from_str(IDL).unwrap().idl.address);
+        assert!(decode_anchor_idl_account(&idl_account_data()[..60]).is_none());
+        assert!(decode_anchor_idl_account(&[0; 44]).is_none());
+    }
+
+    #[test]
+    fn it_fetches_the_idl_of_the_failing_program_on_chain() {
+        use base64::engine::general_purpose::STANDARD;
+        use base64::Engine;
+
+        let program_id =
+            Pubkey::try_from(IdlRef::from_str(IDL).unwrap().idl.address.as_str()).unwrap();
+        let idl_address = anchor_idl_address(&program_id).unwrap();
+        let mut server = mockito::Server::new();
+        let rpc = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(idl_address.to_string()))
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": {
+                        "context": { "slot": 1 },
+                        "value": {
+                            "data": [STANDARD.encode(idl_account_data()), "base64"],
+                            "executable": false,
+                            "lamports": 1,
+                            "owner": program_id.to_string(),
+                            "rentEpoch": 0,
+                            "space": idl_account_data().len()
+                        }
+                    }
+                })
+                .to_string(),
+            )
+            .expect(1)
+            .create();
+        let rpc_client = RpcClient::new(server.url());
+
+        let transaction = transaction(&program_id);
+        let error = TransactionError::InstructionError(0, InstructionError::Custom(6000));
+        let idls = with_onchain_idl(Some(&rpc_client), &error, &transaction, &[]);
+        let description = describe_custom_program_error(&error, &transaction, &idls).unwrap();
+        assert!(description.ends_with("(6000: Overflow - The counter overflowed)"));
+
+        // the IDLs at hand are used as is, and other failures aren't looked up
+        assert_eq!(with_onchain_idl(Some(&rpc_client), &error, &transaction, &idls).len(), 1);
+        let error = TransactionError::InstructionError(0, InstructionError::InvalidArgument);
+        assert!(with_onchain_idl(Some(&rpc_client), &error, &transaction, &[]).is_empty());
+        rpc.assert();
+    }
+}
@@ -1,23 +1,46 @@
+use std::net::TcpStream;
 use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
+use solana_client::client_error::ClientError;
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_client::rpc_response::RpcSignatureResult;
 use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_signature::Signature;
 use solana_transaction::Transaction;
+use solana_transaction_error::TransactionError;
+use tungstenite::client::IntoClientRequest;
+use tungstenite::http::header::AUTHORIZATION;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
 use txtx_addon_kit::channel;
 use txtx_addon_kit::constants::SIGNED_TRANSACTION_BYTES;
 use txtx_addon_kit::types::commands::CommandExecutionResult;
 use txtx_addon_kit::types::commands::{CommandExecutionFutureResult, CommandSpecification};
-use txtx_addon_kit::types::diagnostics::Diagnostic;
+use txtx_addon_kit::types::diagnostics::{Diagnostic, DiagnosticKind};
 use txtx_addon_kit::types::frontend::{BlockEvent, LogDispatcher};
 use txtx_addon_kit::types::stores::ValueStore;
 use txtx_addon_kit::types::types::{RunbookSupervisionContext, ThirdPartySignatureStatus, Value};
 use txtx_addon_kit::types::ConstructDid;
+use txtx_addon_network_svm_types::anchor::types::Idl;
 
+use crate::codec::program_error::{
+    describe_custom_program_error, get_idls_from_inputs, with_onchain_idl,
+};
+
+use crate::codes::{classify_transaction_error, CONFIRMATION_EXPIRED};
 use crate::constants::{
-    COMMITMENT_LEVEL, DO_AWAIT_CONFIRMATION, IS_DEPLOYMENT, RPC_API_URL, SIGNATURE,
+    COMMITMENT_LEVEL, DO_AWAIT_CONFIRMATION, IS_DEPLOYMENT, RPC_API_AUTH_TOKEN, RPC_API_URL,
+    RPC_WS_URL, SIGNATURE,
 };
 
+/// The maximum time spent waiting for a signature notification, before falling back on polling.
+const WS_CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(60);
+/// The number of attempts made to (re)subscribe to a signature, before falling back on polling.
+const WS_SUBSCRIBE_ATTEMPTS: usize = 3;
+
 pub fn send_transaction_background_task(
     construct_did: &ConstructDid,
     _spec: &CommandSpecification,
@@ -76,11 +99,19 @@ pub fn send_transaction_background_task(
         let transaction_bytes = signed_transaction_value
             .get_buffer_bytes_result()
             .map_err(|e| diagnosed_error!("{}", e))?;
+        let idls = get_idls_from_inputs(&inputs);
+        // confirmations are only awaited over a websocket when one is configured
+        let ws_endpoint = inputs.get_string(RPC_WS_URL).map(|url| WsEndpoint {
+            url: url.to_string(),
+            auth_token: inputs.get_string(RPC_API_AUTH_TOKEN).map(|token| token.to_string()),
+        });
         let signature = send_transaction(
             client.clone(),
             do_await_confirmation,
             &transaction_bytes,
             commitment_config.commitment,
+            &idls,
+            ws_endpoint.as_ref(),
         )
         .map_err(|diag| {
             logger.failure_with_diag("Failed", "Failed to broadcast transaction", &diag);
@@ -100,14 +131,22 @@ pub fn send_transaction(
     do_await_confirmation: bool,
     transaction_bytes: &Vec<u8>,
     commitment: CommitmentLevel,
+    idls: &[Idl],
+    ws_endpoint: Option<&WsEndpoint>,
 ) -> Result<String, Diagnostic> {
     let transaction: Transaction = serde_json::from_slice(&transaction_bytes).map_err(|e| {
         diagnosed_error!("unable to deserialize transaction from bytes ({})", e.to_string())
     })?;
 
-    let signature = if do_await_confirmation {
+    let signature = if let (true, Some(ws_endpoint)) = (do_await_confirmation, ws_endpoint) {
+        send_and_confirm_transaction_with_ws(&rpc_client, ws_endpoint, &transaction, idls)?
+    } else if do_await_confirmation {
         rpc_client.send_and_confirm_transaction(&transaction).map_err(|e| {
-            diagnosed_error!("unable to send and confirm transaction ({})", e.to_string())
+            diagnosed_error!(
+                "unable to send and confirm transaction ({})",
+                describe_client_error(&e, &transaction, idls, Some(rpc_client.as_ref()))
+            )
+            .with_diagnostic_code(&classify_transaction_error(e.get_transaction_error().as_ref()))
         })?
     } else {
         rpc_client
@@ -121,8 +160,478 @@ pub fn send_transaction(
                     min_context_slot: None,
                 },
             )
-            .map_err(|e| diagnosed_error!("unable to send transaction ({})", e.to_string()))?
+            .map_err(|e| {
+                diagnosed_error!(
+                    "unable to send transaction ({})",
+                    describe_client_error(&e, &transaction, idls, Some(rpc_client.as_ref()))
+                )
+                .with_diagnostic_code(&classify_transaction_error(
+                    e.get_transaction_error().as_ref(),
+                ))
+            })?
     };
 
     Ok(signature.to_string())
 }
+
+/// The websocket endpoint over which the confirmation of transactions is awaited.
+#[derive(Debug, Clone)]
+pub struct WsEndpoint {
+    pub url: String,
+    /// The token sent as a bearer token in the headers of the websocket handshake.
+    pub auth_token: Option<String>,
+}
+
+type SignatureSubscription = WebSocket<MaybeTlsStream<TcpStream>>;
+
+/// Sends `transaction`, and awaits its confirmation with a `signatureSubscribe` websocket
+/// subscription rather than by polling `getSignatureStatuses`. If the websocket can't be reached,
+/// or doesn't deliver the notification in time, the confirmation falls back on polling.
+fn send_and_confirm_transaction_with_ws(
+    rpc_client: &RpcClient,
+    ws_endpoint: &WsEndpoint,
+    transaction: &Transaction,
+    idls: &[Idl],
+) -> Result<Signature, Diagnostic> {
+    let commitment = rpc_client.commitment();
+    let signature = transaction.signatures.first().cloned().unwrap_or_default();
+
+    // subscribe before sending, so the notification can't be missed
+    let subscription = subscribe_to_signature(ws_endpoint, &signature, commitment);
+
+    rpc_client.send_transaction(transaction).map_err(|e| {
+        diagnosed_error!(
+            "unable to send and confirm transaction ({})",
+            describe_client_error(&e, transaction, idls, Some(rpc_client))
+        )
+        .with_diagnostic_code(&classify_transaction_error(e.get_transaction_error().as_ref()))
+    })?;
+
+    let confirmation = await_signature_notification(
+        ws_endpoint,
+        &signature,
+        commitment,
+        subscription,
+        WS_CONFIRMATION_TIMEOUT,
+        || {
+            rpc_client
+                .get_signature_status_with_commitment(&signature, commitment)
+                .ok()
+                .flatten()
+                .map(|status| status.err())
+        },
+    );
+
+    match confirmation {
+        Some(None) => Ok(signature),
+        Some(Some(err)) => {
+            Err(transaction_error_diagnostic(&err, transaction, idls, Some(rpc_client)))
+        }
+        None => poll_signature_confirmation(rpc_client, transaction, &signature, idls),
+    }
+}
+
+/// Awaits the notification of `signature` on `subscription`, resubscribing when the connection
+/// drops. As the transaction may have landed while disconnected, `get_status` is checked before
+/// resubscribing. Returns the error of the transaction, if any, or `None` when no notification
+/// was received within `timeout`, for the caller to fall back on polling.
+fn await_signature_notification(
+    ws_endpoint: &WsEndpoint,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+    mut subscription: Option<SignatureSubscription>,
+    timeout: Duration,
+    mut get_status: impl FnMut() -> Option<Option<TransactionError>>,
+) -> Option<Option<TransactionError>> {
+    let deadline = Instant::now() + timeout;
+    let mut attempts = 1;
+    loop {
+        let socket = subscription.as_mut()?;
+        match read_signature_notification(socket, deadline) {
+            Ok(Some(result)) => return Some(result),
+            Ok(None) => return None,
+            Err(_) if attempts < WS_SUBSCRIBE_ATTEMPTS && Instant::now() < deadline => {
+                attempts += 1;
+                if let Some(status) = get_status() {
+                    return Some(status);
+                }
+                subscription = subscribe_to_signature(ws_endpoint, signature, commitment);
+            }
+            Err(_) => return None,
+        }
+    }
+}
+
+/// Reads the messages of `socket` until the notification of the subscribed signature, returning
+/// the error of the transaction if any. Returns `Ok(None)` once `deadline` is reached, and an
+/// error when the connection drops.
+fn read_signature_notification(
+    socket: &mut SignatureSubscription,
+    deadline: Instant,
+) -> Result<Option<Option<TransactionError>>, tungstenite::Error> {
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(None);
+        }
+        set_read_timeout(socket, remaining);
+        let message = match socket.read() {
+            Ok(message) => message,
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) =>
+            {
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let Message::Text(text) = message else {
+            continue;
+        };
+        let Ok(payload) = serde_json::from_str::<serde_json::Value>(&text) else {
+            continue;
+        };
+        if payload.get("error").is_some() {
+            return Err(tungstenite::Error::ConnectionClosed);
+        }
+        if payload["method"] != "signatureNotification" {
+            continue;
+        }
+        match serde_json::from_value(payload["params"]["result"]["value"].clone()) {
+            Ok(RpcSignatureResult::ProcessedSignature(result)) => return Ok(Some(result.err)),
+            Ok(RpcSignatureResult::ReceivedSignature(_)) | Err(_) => continue,
+        }
+    }
+}
+
+fn subscribe_to_signature(
+    ws_endpoint: &WsEndpoint,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+) -> Option<SignatureSubscription> {
+    (0..WS_SUBSCRIBE_ATTEMPTS).find_map(|attempt| {
+        if attempt > 0 {
+            sleep(Duration::from_millis(250 * attempt as u64));
+        }
+        try_subscribe_to_signature(ws_endpoint, signature, commitment).ok()
+    })
+}
+
+fn try_subscribe_to_signature(
+    ws_endpoint: &WsEndpoint,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+) -> Result<SignatureSubscription, tungstenite::Error> {
+    let mut request = ws_endpoint.url.as_str().into_client_request()?;
+    if let Some(auth_token) = &ws_endpoint.auth_token {
+        let header = format!("Bearer {}", auth_token)
+            .parse::<tungstenite::http::HeaderValue>()
+            .map_err(|e| tungstenite::Error::HttpFormat(tungstenite::http::Error::from(e)))?;
+        request.headers_mut().insert(AUTHORIZATION, header);
+    }
+    let (mut socket, _) = tungstenite::connect(request)?;
+    let subscribe = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "signatureSubscribe",
+        "params": [
+            signature.to_string(),
+            { "commitment": commitment.commitment, "enableReceivedNotification": false }
+        ]
+    });
+    socket.send(Message::Text(subscribe.to_string()))?;
+    Ok(socket)
+}
+
+fn set_read_timeout(socket: &mut SignatureSubscription, timeout: Duration) {
+    let stream = match socket.get_mut() {
+        MaybeTlsStream::Plain(stream) => stream,
+        MaybeTlsStream::Rustls(stream) => &mut stream.sock,
+        _ => return,
+    };
+    let _ = stream.set_read_timeout(Some(timeout.max(Duration::from_millis(1))));
+}
+
+/// Polls the status of `signature` until it is confirmed, or until the blockhash of `transaction`
+/// expires.
+fn poll_signature_confirmation(
+    rpc_client: &RpcClient,
+    transaction: &Transaction,
+    signature: &Signature,
+    idls: &[Idl],
+) -> Result<Signature, Diagnostic> {
+    loop {
+        let status = rpc_client
+            .get_signature_status_with_commitment(signature, rpc_client.commitment())
+            .map_err(|e| {
+                diagnosed_error!(
+                    "unable to send and confirm transaction ({})",
+                    describe_client_error(&e, transaction, idls, Some(rpc_client))
+                )
+            })?;
+        match status {
+            Some(Ok(())) => return Ok(*signature),
+            Some(Err(err)) => {
+                return Err(transaction_error_diagnostic(&err, transaction, idls, Some(rpc_client)))
+            }
+            None => {
+                let is_blockhash_valid = rpc_client
+                    .is_blockhash_valid(
+                        &transaction.message.recent_blockhash,
+                        CommitmentConfig::processed(),
+                    )
+                    .unwrap_or(true);
+                if !is_blockhash_valid {
+                    return Err(diagnosed_error!(
+                        "unable to send and confirm transaction (unable to confirm transaction {}: blockhash expired)",
+                        signature
+                    )
+                    .with_kind(DiagnosticKind::Timeout)
+                    .with_diagnostic_code(&CONFIRMATION_EXPIRED));
+                }
+                sleep(Duration::from_millis(500));
+            }
+        }
+    }
+}
+
+fn transaction_error_diagnostic(
+    error: &TransactionError,
+    transaction: &Transaction,
+    idls: &[Idl],
+    rpc_client: Option<&RpcClient>,
+) -> Diagnostic {
+    let idls = with_onchain_idl(rpc_client, error, transaction, idls);
+    let description = match describe_custom_program_error(error, transaction, &idls) {
+        Some(description) => format!("{}: {}", description, error),
+        None => error.to_string(),
+    };
+    diagnosed_error!("unable to send and confirm transaction ({})", description)
+        .with_diagnostic_code(&classify_transaction_error(Some(error)))
+}
+
+/// Describes an RPC error, translating custom program errors with the program IDLs at hand, or
+/// with the IDL the failing program published on chain when `rpc_client` is provided.
+pub(crate) fn describe_client_error(
+    error: &ClientError,
+    transaction: &Transaction,
+    idls: &[Idl],
+    rpc_client: Option<&RpcClient>,
+) -> String {
+    match error.get_transaction_error().and_then(|e| {
+        let idls = with_onchain_idl(rpc_client, &e, transaction, idls);
+        describe_custom_program_error(&e, transaction, &idls)
+    }) {
+        Some(description) => format!("{}: {}", description, error),
+        None => error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread;
+
+    use solana_instruction::error::InstructionError;
+    use tungstenite::handshake::server::{Request, Response};
+
+    use super::*;
+
+    #[test]
+    fn it_codes_transaction_errors() {
+        let transaction = Transaction::default();
+        let diag = transaction_error_diagnostic(
+            &TransactionError::BlockhashNotFound,
+            &transaction,
+            &[],
+            None,
+        );
+        assert_eq!(diag.code.as_deref(), Some("SVM201"));
+        assert_eq!(diag.help_url.as_deref(), Some("https://docs.txtx.sh/errors/svm201"));
+
+        let error = TransactionError::InstructionError(0, InstructionError::Custom(1));
+        let diag = transaction_error_diagnostic(&error, &transaction, &[], None);
+        assert_eq!(diag.code.as_deref(), Some("SVM204"));
+        assert_eq!(diag.help_url, None);
+    }
+
+    fn endpoint(listener: &TcpListener, auth_token: Option<&str>) -> WsEndpoint {
+        WsEndpoint {
+            url: format!("ws://{}", listener.local_addr().unwrap()),
+            auth_token: auth_token.map(|token| token.to_string()),
+        }
+    }
+
+    /// Accepts a connection, returning its `Authorization` header and its subscription request.
+    fn accept(listener: &TcpListener) -> (WebSocket<TcpStream>, Option<String>, serde_json::Value) {
+        let (stream, _) = listener.accept().unwrap();
+        let mut authorization = None;
+        let mut socket =
+            tungstenite::accept_hdr(stream, |request: &Request, response: Response| {
+                authorization = request
+                    .headers()
+                    .get(AUTHORIZATION)
+                    .map(|header| header.to_str().unwrap().to_string());
+                Ok(response)
+            })
+            .unwrap();
+        let Message::Text(subscription) = socket.read().unwrap() else {
+            panic!("expected a subscription request");
+        };
+        (socket, authorization, serde_json::from_str(&subscription).unwrap())
+    }
+
+    fn notify(socket: &mut WebSocket<TcpStream>, err: serde_json::Value) {
+        let ack = serde_json::json!({ "jsonrpc": "2.0", "result": 7, "id": 1 });
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "signatureNotification",
+            "params": {
+                "result": { "context": { "slot": 5 }, "value": { "err": err } },
+                "subscription": 7
+            }
+        });
+        socket.send(Message::Text(ack.to_string())).unwrap();
+        socket.send(Message::Text(notification.to_string())).unwrap();
+    }
+
+    #[test]
+    fn it_confirms_signatures_over_websocket() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ws_endpoint = endpoint(&listener, Some("secret"));
+        let signature = Signature::from([7; 64]);
+        let server = thread::spawn(move || {
+            let (mut socket, authorization, subscription) = accept(&listener);
+            notify(&mut socket, serde_json::json!({ "InstructionError": [0, { "Custom": 6000 }] }));
+            (authorization, subscription)
+        });
+
+        let subscription =
+            subscribe_to_signature(&ws_endpoint, &signature, CommitmentConfig::confirmed());
+        let confirmation = await_signature_notification(
+            &ws_endpoint,
+            &signature,
+            CommitmentConfig::confirmed(),
+            subscription,
+            Duration::from_secs(5),
+            || panic!("the subscription didn't drop"),
+        );
+        assert_eq!(
+            confirmation,
+            Some(Some(TransactionError::InstructionError(0, InstructionError::Custom(6000))))
+        );
+
+        let (authorization, subscription) = server.join().unwrap();
+        assert_eq!(authorization.as_deref(), Some("Bearer secret"));
+        assert_eq!(subscription["method"], "signatureSubscribe");
+        assert_eq!(subscription["params"][0], signature.to_string());
+        assert_eq!(subscription["params"][1]["commitment"], "confirmed");
+    }
+
+    #[test]
+    fn it_resubscribes_when_the_websocket_drops() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ws_endpoint = endpoint(&listener, None);
+        let signature = Signature::from([7; 64]);
+        let server = thread::spawn(move || {
+            let (socket, authorization, _) = accept(&listener);
+            assert_eq!(authorization, None);
+            drop(socket);
+            let (mut socket, _, _) = accept(&listener);
+            notify(&mut socket, serde_json::Value::Null);
+        });
+
+        let subscription =
+            subscribe_to_signature(&ws_endpoint, &signature, CommitmentConfig::confirmed());
+        let mut status_checks = 0;
+        let confirmation = await_signature_notification(
+            &ws_endpoint,
+            &signature,
+            CommitmentConfig::confirmed(),
+            subscription,
+            Duration::from_secs(5),
+            || {
+                status_checks += 1;
+                None
+            },
+        );
+        assert_eq!(confirmation, Some(None));
+        assert_eq!(status_checks, 1);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn it_checks_the_status_of_the_signature_before_resubscribing() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ws_endpoint = endpoint(&listener, None);
+        let signature = Signature::from([7; 64]);
+        let server = thread::spawn(move || drop(accept(&listener)));
+
+        let subscription =
+            subscribe_to_signature(&ws_endpoint, &signature, CommitmentConfig::confirmed());
+        let confirmation = await_signature_notification(
+            &ws_endpoint,
+            &signature,
+            CommitmentConfig::confirmed(),
+            subscription,
+            Duration::from_secs(5),
+            || Some(None),
+        );
+        assert_eq!(confirmation, Some(None));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn it_times_out_without_notification() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ws_endpoint = endpoint(&listener, None);
+        let signature = Signature::from([7; 64]);
+        let (done_tx, done_rx) = mpsc::channel::<()>();
+        let server = thread::spawn(move || {
+            // the connection is held open, without ever notifying
+            let (_socket, _, _) = accept(&listener);
+            let _ = done_rx.recv();
+        });
+
+        let subscription =
+            subscribe_to_signature(&ws_endpoint, &signature, CommitmentConfig::confirmed());
+        assert!(subscription.is_some());
+        let started_at = Instant::now();
+        let confirmation = await_signature_notification(
+            &ws_endpoint,
+            &signature,
+            CommitmentConfig::confirmed(),
+            subscription,
+            Duration::from_millis(300),
+            || panic!("the subscription didn't drop"),
+        );
+        assert_eq!(confirmation, None);
+        assert!(started_at.elapsed() < Duration::from_secs(5));
+        done_tx.send(()).unwrap();
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn it_falls_back_on_polling_when_the_websocket_is_unreachable() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ws_endpoint = endpoint(&listener, None);
+        drop(listener);
+        let signature = Signature::from([7; 64]);
+
+        let subscription =
+            subscribe_to_signature(&ws_endpoint, &signature, CommitmentConfig::confirmed());
+        assert!(subscription.is_none());
+        let confirmation = await_signature_notification(
+            &ws_endpoint,
+            &signature,
+            CommitmentConfig::confirmed(),
+            subscription,
+            Duration::from_secs(5),
+            || panic!("there is no subscription to drop"),
+        );
+        assert_eq!(confirmation, None);
+    }
+}
@@ -0,0 +1,40 @@
+use solana_instruction::error::InstructionError;
+use solana_transaction_error::TransactionError;
+use txtx_addon_kit::types::diagnostics::DiagnosticCode;
+
+pub const TRANSACTION_BROADCAST_FAILED: DiagnosticCode =
+    DiagnosticCode::new("SVM200", "transaction broadcast failed");
+pub const BLOCKHASH_NOT_FOUND: DiagnosticCode =
+    DiagnosticCode::new("SVM201", "blockhash not found")
+        .with_help_url("https://docs.txtx.sh/errors/svm201");
+pub const INSUFFICIENT_FUNDS_FOR_FEE: DiagnosticCode =
+    DiagnosticCode::new("SVM202", "insufficient funds for fee")
+        .with_help_url("https://docs.txtx.sh/errors/svm202");
+pub const ACCOUNT_NOT_FOUND: DiagnosticCode =
+    DiagnosticCode::new("SVM203", "fee payer account not found");
+pub const CUSTOM_PROGRAM_ERROR: DiagnosticCode =
+    DiagnosticCode::new("SVM204", "custom program error");
+pub const CONFIRMATION_EXPIRED: DiagnosticCode =
+    DiagnosticCode::new("SVM205", "blockhash expired before the transaction was confirmed");
+
+pub const CODES: &[DiagnosticCode] = &[
+    TRANSACTION_BROADCAST_FAILED,
+    BLOCKHASH_NOT_FOUND,
+    INSUFFICIENT_FUNDS_FOR_FEE,
+    ACCOUNT_NOT_FOUND,
+    CUSTOM_PROGRAM_ERROR,
+    CONFIRMATION_EXPIRED,
+];
+
+/// Classifies the error of a transaction rejected by the cluster, or failing on-chain.
+pub fn classify_transaction_error(error: Option<&TransactionError>) -> DiagnosticCode {
+    match error {
+        Some(TransactionError::BlockhashNotFound) => BLOCKHASH_NOT_FOUND,
+        Some(TransactionError::InsufficientFundsForFee) => INSUFFICIENT_FUNDS_FOR_FEE,
+        Some(TransactionError::AccountNotFound) => ACCOUNT_NOT_FOUND,
+        Some(TransactionError::InstructionError(_, InstructionError::Custom(_))) => {
+            CUSTOM_PROGRAM_ERROR
+        }
+        _ => TRANSACTION_BROADCAST_FAILED,
+    }
+}
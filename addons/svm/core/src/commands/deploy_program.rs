@@ -3,9 +3,12 @@ use std::str::FromStr;
 use std::{i128, vec};
 
 use log::debug;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
 use solana_client::rpc_client::RpcClient;
 use solana_commitment_config::CommitmentConfig;
 use solana_pubkey::Pubkey;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
 use txtx_addon_kit::channel;
 use txtx_addon_kit::constants::{
     DESCRIPTION, META_DESCRIPTION, NESTED_CONSTRUCT_COUNT, NESTED_CONSTRUCT_DID,
@@ -13,6 +16,7 @@ use txtx_addon_kit::constants::{
     SIGNED_TRANSACTION_BYTES,
 };
 use txtx_addon_kit::futures::future;
+use txtx_addon_kit::helpers::fs::FileLocation;
 use txtx_addon_kit::indexmap::IndexMap;
 use txtx_addon_kit::types::cloud_interface::CloudServiceContext;
 use txtx_addon_kit::types::commands::{
@@ -31,18 +35,21 @@ use txtx_addon_kit::types::stores::ValueStore;
 use txtx_addon_kit::types::types::{
     ObjectType, RunbookCompleteAdditionalInfo, RunbookSupervisionContext, ToFromValue, Type, Value,
 };
-use txtx_addon_kit::types::{ConstructDid, Did};
+use txtx_addon_kit::types::{AuthorizationContext, ConstructDid, Did};
 use txtx_addon_kit::uuid::Uuid;
 use txtx_addon_network_svm_types::{SVM_KEYPAIR, SVM_PUBKEY};
 
+use crate::codec::buffer_writes::{write_buffer_chunk, BufferWriteConfig, BufferWriteLimiter};
+use crate::codec::deployment_recovery::{find_resumable_buffer, DeploymentRecovery};
 use crate::codec::idl::IdlRef;
 use crate::codec::send_transaction::send_transaction_background_task;
 use crate::codec::utils::{cheatcode_deploy_program, cheatcode_register_idl};
 use crate::codec::{DeploymentTransaction, ProgramArtifacts, UpgradeableProgramDeployer};
 use crate::constants::{
     ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION, AUTHORITY, AUTO_EXTEND, BUFFER_ACCOUNT_PUBKEY,
-    CHECKED_PUBLIC_KEY, COMMITMENT_LEVEL, DEPLOYMENT_TRANSACTIONS, DEPLOYMENT_TRANSACTION_TYPE,
-    DO_AWAIT_CONFIRMATION, EPHEMERAL_AUTHORITY_SECRET_KEY, FORMATTED_TRANSACTION,
+    CHECKED_PUBLIC_KEY, COMMITMENT_LEVEL, DEPLOYMENT_RECOVERY_BASE, DEPLOYMENT_RECOVERY_WARNING,
+    DEPLOYMENT_TRANSACTIONS, DEPLOYMENT_TRANSACTION_TYPE, DO_AWAIT_CONFIRMATION,
+    EPHEMERAL_AUTHORITY_SECRET_KEY, FORMATTED_TRANSACTION,
     INITIAL_EXPECTED_DEPLOYMENT_TRANSACTIONS_COUNT, INSTANT_SURFNET_DEPLOYMENT, IS_DEPLOYMENT,
     IS_SQUADS_AUTHORITY, IS_SURFNET, NAMESPACE, NETWORK_ID, PAYER, PROGRAM,
     PROGRAM_DEPLOYMENT_KEYPAIR, PROGRAM_ID, PROGRAM_IDL, RPC_API_URL, SIGNATURE, SIGNATURES,
@@ -109,6 +116,14 @@ lazy_static! {
                         internal: false,
                         sensitive: false
                     },
+                    rpc_ws_url: {
+                        documentation: "The websocket URL over which the confirmation of transactions is awaited. If omitted, confirmations are awaited by polling the RPC API.",
+                        typing: Type::string(),
+                        optional: true,
+                        tainting: false,
+                        internal: false,
+                        sensitive: false
+                    },
                     auto_extend: {
                         documentation: "Whether to auto extend the program account for program upgrades. Defaults to `true`.",
                         typing: Type::bool(),
@@ -134,12 +149,36 @@ lazy_static! {
                         sensitive: true
                     },
                     buffer_account_pubkey: {
-                        documentation: "The public key of the buffer account to use to continue a failed deployment.",
+                        documentation: "The public key of the buffer account to use to continue a failed deployment. Writes resume from the first chunk of the program binary that is missing from the buffer; the `ephemeral_authority_secret_key` of the failed deployment must also be provided. When omitted, the buffer and ephemeral authority of an interrupted deployment are recovered from `.txtx/recovery`, or the buffers owned by the provided `ephemeral_authority_secret_key` are looked up on chain.",
                         typing: Type::addon(SVM_PUBKEY),
                         optional: true,
                         tainting: false,
                         internal: false,
                         sensitive: false
+                    },
+                    write_concurrency: {
+                        documentation: "The maximum number of buffer write transactions in flight at once. Defaults to `4`.",
+                        typing: Type::integer(),
+                        optional: true,
+                        tainting: false,
+                        internal: false,
+                        sensitive: false
+                    },
+                    writes_per_second: {
+                        documentation: "The maximum number of buffer write transactions sent per second, e.g. `0.5` for one every two seconds. Set to `0` to disable pacing, e.g. against a local validator. Defaults to `10`, which stays within the rate limits of public RPCs.",
+                        typing: Type::float(),
+                        optional: true,
+                        tainting: false,
+                        internal: false,
+                        sensitive: false
+                    },
+                    max_retries_per_chunk: {
+                        documentation: "The number of times a failed buffer write transaction is resent, with an exponential backoff, before the deployment fails. Defaults to `3`.",
+                        typing: Type::integer(),
+                        optional: true,
+                        tainting: false,
+                        internal: false,
+                        sensitive: false
                     }
                 ],
                 outputs: [
@@ -194,6 +233,7 @@ impl CommandImplementation for DeployProgram {
         values: &ValueStore,
         signers_instances: &HashMap<ConstructDid, SignerInstance>,
         mut signers: SignersState,
+        auth_ctx: &AuthorizationContext,
     ) -> PrepareSignedNestedExecutionResult {
         let (
             (authority_signer_did, mut authority_signer_state),
@@ -229,6 +269,13 @@ impl CommandImplementation for DeployProgram {
 
         let auto_extend = values.get_bool(AUTO_EXTEND);
 
+        // recovery records are kept next to the manifest, whatever the working directory
+        let recovery_base = auth_ctx
+            .workspace_location
+            .get_parent_location()
+            .unwrap_or_else(|_| FileLocation::working_dir());
+        let mut recovery_warning = None;
+
         if let Some(keypair_bytes) = program_artifacts.keypair_bytes() {
             insert_to_payer_or_authority(
                 &mut payer_signer_state,
@@ -322,6 +369,14 @@ impl CommandImplementation for DeployProgram {
                 (program_id.clone(), transactions.clone())
             }
             false => {
+                let program_pubkey = program_artifacts.program_id();
+                let recovery = match do_cheatcode_deployment {
+                    true => None,
+                    false => DeploymentRecovery::load(&recovery_base, &program_pubkey),
+                };
+
+                // whether the ephemeral authority may own the buffer of an interrupted deployment
+                let mut is_authority_reused = true;
                 let temp_authority_keypair = match authority_signer_state
                     .get_scoped_value(&construct_did.to_string(), EPHEMERAL_AUTHORITY_SECRET_KEY)
                 {
@@ -333,20 +388,29 @@ impl CommandImplementation for DeployProgram {
                         )
                     })?,
                     None => {
-                        let temp_authority_keypair =
-                            match values.get_value(EPHEMERAL_AUTHORITY_SECRET_KEY) {
-                                Some(kp) => SvmValue::to_keypair(kp).map_err(|e| {
-                                    (
-                                        signers.clone(),
-                                        authority_signer_state.clone(),
-                                        diagnosed_error!(
-                                            "invalid ephemeral authority keypair provided: {}",
-                                            e
-                                        ),
-                                    )
+                        let temp_authority_keypair = match values
+                            .get_value(EPHEMERAL_AUTHORITY_SECRET_KEY)
+                        {
+                            Some(kp) => SvmValue::to_keypair(kp).map_err(|e| {
+                                (
+                                    signers.clone(),
+                                    authority_signer_state.clone(),
+                                    diagnosed_error!(
+                                        "invalid ephemeral authority keypair provided: {}",
+                                        e
+                                    ),
+                                )
+                            })?,
+                            None => match &recovery {
+                                Some(recovery) => recovery.ephemeral_authority().map_err(|e| {
+                                    (signers.clone(), authority_signer_state.clone(), e)
                                 })?,
-                                None => UpgradeableProgramDeployer::create_temp_authority(),
-                            };
+                                None => {
+                                    is_authority_reused = false;
+                                    UpgradeableProgramDeployer::create_temp_authority()
+                                }
+                            },
+                        };
 
                         authority_signer_state.insert_scoped_value(
                             &construct_did.to_string(),
@@ -357,7 +421,6 @@ impl CommandImplementation for DeployProgram {
                     }
                 };
 
-                let program_pubkey = program_artifacts.program_id();
                 let program_keypair = match program_artifacts.keypair() {
                     Some(Ok(keypair)) => Some(keypair),
                     _ => None,
@@ -379,6 +442,21 @@ impl CommandImplementation for DeployProgram {
                         })
                     })
                     .transpose()?;
+                // without an explicit buffer, look for the one an interrupted deployment left
+                // behind: its chunks matching the program binary won't be written again
+                let buffer_pubkey = match buffer_pubkey {
+                    None if is_authority_reused && !do_cheatcode_deployment => {
+                        find_resumable_buffer(
+                            &rpc_client,
+                            recovery.as_ref().map(|recovery| recovery.buffer_pubkey),
+                            &temp_authority_keypair.pubkey(),
+                            program_artifacts.bin(),
+                        )
+                        .map_err(|e| (signers.clone(), authority_signer_state.clone(), e))?
+                    }
+                    buffer_pubkey => buffer_pubkey,
+                };
+                let temp_authority_bytes = temp_authority_keypair.to_bytes();
 
                 let mut deployer = UpgradeableProgramDeployer::new(
                     program_pubkey,
@@ -401,6 +479,19 @@ impl CommandImplementation for DeployProgram {
                     )
                 })?;
 
+                if !do_cheatcode_deployment {
+                    let recovery = DeploymentRecovery {
+                        ephemeral_authority_secret_key: temp_authority_bytes.to_vec(),
+                        buffer_pubkey: deployer.buffer_pubkey,
+                    };
+                    if let Err(e) = recovery.save(&recovery_base, &program_pubkey) {
+                        recovery_warning = Some(format!(
+                            "{}; an interrupted deployment of program {} won't be resumable",
+                            e.message, program_pubkey
+                        ));
+                    }
+                }
+
                 let program_id = SvmValue::pubkey(deployer.program_pubkey.to_bytes().to_vec());
                 authority_signer_state.insert_scoped_value(
                     &construct_did.to_string(),
@@ -518,8 +609,20 @@ impl CommandImplementation for DeployProgram {
                     IS_SQUADS_AUTHORITY,
                     Value::bool(is_squads_authority),
                 );
+                if let Some(warning) = &recovery_warning {
+                    value_store.insert_scoped_value(
+                        &new_did.to_string(),
+                        DEPLOYMENT_RECOVERY_WARNING,
+                        Value::string(warning.clone()),
+                    );
+                }
             }
             if i == transaction_count - 1 {
+                value_store.insert_scoped_value(
+                    &new_did.to_string(),
+                    DEPLOYMENT_RECOVERY_BASE,
+                    Value::string(recovery_base.to_string()),
+                );
                 if let Some(idl) = &program_idl {
                     value_store.insert_scoped_value(
                         &new_did.to_string(),
@@ -698,7 +801,7 @@ impl CommandImplementation for DeployProgram {
         _progress_tx: &channel::Sender<BlockEvent>,
         signers_instances: &HashMap<ConstructDid, SignerInstance>,
         mut signers: SignersState,
-        _auth_context: &txtx_addon_kit::types::AuthorizationContext,
+        _auth_context: &AuthorizationContext,
     ) -> SignerSignFutureResult {
         let authority_signer_did = get_custom_signer_did(values, AUTHORITY).unwrap();
         let construct_did = construct_did.clone();
@@ -731,6 +834,12 @@ impl CommandImplementation for DeployProgram {
             let nested_construct_index = values
                 .get_scoped_integer(&nested_construct_did.to_string(), NESTED_CONSTRUCT_INDEX)
                 .unwrap();
+            if let Some(warning) = values
+                .get_scoped_value(&nested_construct_did.to_string(), DEPLOYMENT_RECOVERY_WARNING)
+                .and_then(|v| v.as_string())
+            {
+                result.diagnostics.push(Diagnostic::warning(warning));
+            }
             authority_signer_state.insert_scoped_value(
                 &construct_did.to_string(),
                 SIGNED_NESTED_EXECUTION_INDEX,
@@ -916,19 +1025,74 @@ impl CommandImplementation for DeployProgram {
                         Value::bool(deployment_transaction.do_await_confirmation),
                     );
 
-                    let mut result = match send_transaction_background_task(
-                        &construct_did,
-                        &spec,
-                        &inputs,
-                        &outputs,
-                        &progress_tx,
-                        &supervision_context,
-                    ) {
-                        Ok(res) => match res.await {
-                            Ok(res) => res,
+                    let mut result = match deployment_transaction.transaction_type {
+                        DeploymentTransactionType::WriteToBuffer { .. }
+                        | DeploymentTransactionType::ResumeWriteToBuffer { .. } => {
+                            let config = BufferWriteConfig::from_values(&inputs)?;
+                            let limiter =
+                                BufferWriteLimiter::for_deployment(&construct_did, config);
+
+                            let signed_transaction: Transaction = serde_json::from_slice(
+                                &signed_transaction_value
+                                    .get_buffer_bytes_result()
+                                    .map_err(|e| diagnosed_error!("{}", e))?,
+                            )
+                            .map_err(|e| {
+                                diagnosed_error!(
+                                    "unable to deserialize transaction from bytes ({})",
+                                    e
+                                )
+                            })?;
+                            let rpc_client = NonblockingRpcClient::new_with_commitment(
+                                rpc_api_url.clone(),
+                                CommitmentConfig {
+                                    commitment: deployment_transaction.commitment_level,
+                                },
+                            );
+                            let (signature, attempt) = match write_buffer_chunk(
+                                &rpc_client,
+                                &deployment_transaction,
+                                signed_transaction,
+                                &limiter,
+                                &logger,
+                            )
+                            .await
+                            {
+                                Ok(res) => res,
+                                Err(e) => {
+                                    BufferWriteLimiter::release_deployment(&construct_did);
+                                    return Err(e);
+                                }
+                            };
+                            let mut result = CommandExecutionResult::from_value_store(&outputs);
+                            result
+                                .outputs
+                                .insert(SIGNATURE.into(), Value::string(signature.to_string()));
+
+                            let stats = limiter.record_write(attempt);
+                            if deployment_transaction.do_await_confirmation {
+                                // this is the last buffer write of the deployment
+                                logger.info("Buffer Write Throughput", stats.to_string());
+                                BufferWriteLimiter::release_deployment(&construct_did);
+                            } else {
+                                logger.pending_info("Pending", stats.to_string());
+                            }
+                            result
+                        }
+                        _ => match send_transaction_background_task(
+                            &construct_did,
+                            &spec,
+                            &inputs,
+                            &outputs,
+                            &progress_tx,
+                            &supervision_context,
+                        ) {
+                            Ok(res) => match res.await {
+                                Ok(res) => res,
+                                Err(e) => return Err(e),
+                            },
                             Err(e) => return Err(e),
                         },
-                        Err(e) => return Err(e),
                     };
 
                     let signature = result.outputs.remove(SIGNATURE).unwrap();
@@ -944,6 +1108,15 @@ impl CommandImplementation for DeployProgram {
             deployment_transaction.post_send_actions(&rpc_api_url);
 
             if transaction_index == transaction_count - 1 {
+                // the deployment is complete, there's nothing left to recover
+                if let Some(base) = inputs
+                    .get_scoped_value(&nested_construct_did.to_string(), DEPLOYMENT_RECOVERY_BASE)
+                    .and_then(|v| v.as_string())
+                    .and_then(|base| FileLocation::from_path_string(base).ok())
+                {
+                    DeploymentRecovery::clear(&base, &program_id);
+                }
+
                 let rpc_client = RpcClient::new(rpc_api_url);
                 if let Ok(slot) = rpc_client.get_slot() {
                     result.insert(SLOT, Value::integer(slot as i128));
@@ -7,8 +7,15 @@ use txtx_addon_kit::types::commands::{
 use txtx_addon_kit::types::diagnostics::Diagnostic;
 use txtx_addon_kit::types::frontend::{Actions, BlockEvent, LogDispatcher};
 use txtx_addon_kit::types::stores::ValueStore;
-use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type};
+use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type, Value};
 use txtx_addon_kit::types::ConstructDid;
+use txtx_addon_network_svm_types::subgraph::{InstructionSubgraphSource, SubgraphSchemaPreview};
+use txtx_addon_network_svm_types::INSTRUCTION_SUBGRAPH;
+
+use crate::codec::idl::IdlRef;
+use crate::constants::{
+    INSTRUCTION, PREVIEW_ONLY, PROGRAM_IDL, SCHEMA, SCHEMA_PREVIEW, SUBGRAPH_NAME,
+};
 
 lazy_static! {
     pub static ref DEPLOY_SUBGRAPH: PreCommandSpecification = {
@@ -16,12 +23,73 @@ lazy_static! {
             DeployProgram => {
                 name: "Deploy SVM Program Subgraph",
                 matcher: "deploy_subgraph",
-                documentation: "`svm::deploy_subgraph` is deprecated. If you are using this in your runbook, it should be removed.",
+                documentation: txtx_addon_kit::indoc! {r#"
+                    `svm::deploy_subgraph` is deprecated for registering subgraphs. If you are using this in your runbook to register a subgraph, it should be removed.
+                    When an `instruction` subgraph is defined, the action validates it against the program IDL and renders the GraphQL schema that would be generated for it. Instruction subgraphs are only previewed: they are never registered with the subgraph service.
+                "#},
                 implements_signing_capability: false,
                 implements_background_task_capability: false,
-                inputs: [],
-                outputs: [],
-                example: "",
+                inputs: [
+                    program_idl: {
+                        documentation: "The IDL of the program whose data is indexed by the subgraph.",
+                        typing: Type::string(),
+                        optional: true,
+                        tainting: true,
+                        internal: false
+                    },
+                    subgraph_name: {
+                        documentation: "The name of the subgraph entity. By default, the instruction name is used.",
+                        typing: Type::string(),
+                        optional: true,
+                        tainting: true,
+                        internal: false
+                    },
+                    instruction: {
+                        documentation: "A map of instruction invocations to index in the subgraph.",
+                        typing: INSTRUCTION_SUBGRAPH.clone(),
+                        optional: true,
+                        tainting: true,
+                        internal: false
+                    },
+                    preview_only: {
+                        documentation: "If true, the `instruction` subgraph is required. The subgraph is only previewed either way: it is validated and its schema is rendered, but never registered.",
+                        typing: Type::bool(),
+                        optional: true,
+                        tainting: false,
+                        internal: false
+                    }
+                ],
+                outputs: [
+                    schema: {
+                        documentation: "The GraphQL schema that would be generated for the `instruction` subgraph.",
+                        typing: Type::string()
+                    },
+                    schema_preview: {
+                        documentation: "The entity name and fields (with their GraphQL types, sources and indexing) of the `instruction` subgraph.",
+                        typing: Type::arbitrary_object()
+                    }
+                ],
+                example: txtx_addon_kit::indoc! {r#"
+                    action "orders_preview" "svm::deploy_subgraph" {
+                        program_idl = variable.program.idl
+                        preview_only = true
+                        instruction {
+                            name = "place_order"
+                            field {
+                                name = "price"
+                                indexed = true
+                            }
+                            field {
+                                name = "size"
+                                idl_key = "quantity"
+                                indexed = false
+                            }
+                        }
+                    }
+                    output "orders_schema" {
+                        value = action.orders_preview.schema
+                    }
+                "#},
             }
         };
         command
@@ -41,10 +109,14 @@ impl CommandImplementation for DeployProgram {
         _construct_id: &ConstructDid,
         _instance_name: &str,
         _spec: &CommandSpecification,
-        _values: &ValueStore,
+        values: &ValueStore,
         _supervision_context: &RunbookSupervisionContext,
         _auth_context: &txtx_addon_kit::types::AuthorizationContext,
     ) -> Result<Actions, Diagnostic> {
+        if values.get_bool(PREVIEW_ONLY).unwrap_or(false) || values.get_value(INSTRUCTION).is_some()
+        {
+            build_schema_preview(values)?;
+        }
         Ok(Actions::none())
     }
 
@@ -52,19 +124,140 @@ impl CommandImplementation for DeployProgram {
     fn run_execution(
         construct_did: &ConstructDid,
         _spec: &CommandSpecification,
-        _values: &ValueStore,
+        values: &ValueStore,
         progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
         _auth_ctx: &txtx_addon_kit::types::AuthorizationContext,
     ) -> CommandExecutionFutureResult {
-        use txtx_addon_kit::types::commands::return_synchronous_ok;
+        use txtx_addon_kit::types::commands::{return_synchronous_err, return_synchronous_ok};
 
         let logger =
             LogDispatcher::new(construct_did.as_uuid(), "svm::deploy_subgraph", &progress_tx);
 
-        logger.warn(
-            "Deprecated Action",
-            "The svm::deploy_subgraph action is deprecated. Please remove from your runbooks.",
-        );
-        return_synchronous_ok(CommandExecutionResult::new())
+        let preview_only = values.get_bool(PREVIEW_ONLY).unwrap_or(false);
+        if !preview_only && values.get_value(INSTRUCTION).is_none() {
+            logger.warn(
+                "Deprecated Action",
+                "The svm::deploy_subgraph action is deprecated. Please remove from your runbooks.",
+            );
+            return return_synchronous_ok(CommandExecutionResult::new());
+        }
+
+        let preview = match build_schema_preview(values) {
+            Ok(preview) => preview,
+            Err(diag) => return return_synchronous_err(diag),
+        };
+        if !preview_only {
+            logger.warn(
+                "Deprecated Action",
+                "The svm::deploy_subgraph action only previews subgraphs: the subgraph schema was rendered, but not registered. Set `preview_only = true` to silence this warning.",
+            );
+        }
+        let mut result = CommandExecutionResult::new();
+        result.outputs.insert(SCHEMA.into(), Value::string(preview.to_graphql_string()));
+        result.outputs.insert(SCHEMA_PREVIEW.into(), preview.to_value());
+        return_synchronous_ok(result)
+    }
+}
+
+/// Renders the schema of the `instruction` subgraph, reporting every violation of its definition
+/// (missing or invalid IDL, unknown instruction arguments, duplicate field names, unsupported IDL
+/// types) together.
+fn build_schema_preview(values: &ValueStore) -> Result<SubgraphSchemaPreview, Diagnostic> {
+    let mut diags = vec![];
+    let idl_ref = values
+        .get_expected_string(PROGRAM_IDL)
+        .and_then(IdlRef::from_str)
+        .map_err(|diag| diags.push(diag))
+        .ok();
+    let instruction = values.get_expected_value(INSTRUCTION).map_err(|diag| diags.push(diag)).ok();
+
+    if let (Some(idl_ref), Some(instruction)) = (idl_ref, instruction) {
+        match InstructionSubgraphSource::from_value(instruction, &idl_ref.idl)
+            .and_then(|source| source.schema_preview(values.get_string(SUBGRAPH_NAME)))
+        {
+            Ok(preview) => return Ok(preview),
+            Err(errs) => diags.extend(errs),
+        }
+    }
+    Err(diagnosed_error!(
+        "invalid subgraph definition:\n{}",
+        diags.iter().map(|d| format!("  - {}", d.message)).collect::<Vec<_>>().join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use txtx_addon_kit::types::types::ObjectType;
+
+    use super::*;
+
+    const IDL: &str = r#"{
+        "address": "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS",
+        "metadata": { "name": "orderbook", "version": "0.1.0", "spec": "0.1.0" },
+        "instructions": [
+            {
+                "name": "place_order",
+                "discriminator": [51, 194, 155, 175, 109, 130, 96, 106],
+                "accounts": [],
+                "args": [
+                    { "name": "price", "type": "u64" },
+                    { "name": "quantity", "type": "u32" }
+                ]
+            }
+        ]
+    }"#;
+
+    fn field(name: &str, idl_key: Option<&str>) -> Value {
+        let mut field = ObjectType::from(vec![("name", Value::string(name.to_string()))]);
+        if let Some(idl_key) = idl_key {
+            field.insert("idl_key", Value::string(idl_key.to_string()));
+        }
+        field.to_value()
+    }
+
+    fn values(program_idl: Option<&str>, fields: Option<Vec<Value>>) -> ValueStore {
+        let mut values = ValueStore::tmp();
+        if let Some(program_idl) = program_idl {
+            values.insert(PROGRAM_IDL, Value::string(program_idl.to_string()));
+        }
+        if let Some(fields) = fields {
+            values.insert(
+                INSTRUCTION,
+                Value::array(vec![ObjectType::from(vec![
+                    ("name", Value::string("place_order".into())),
+                    ("field", Value::array(fields)),
+                ])
+                .to_value()]),
+            );
+        }
+        values
+    }
+
+    #[test]
+    fn it_renders_the_schema_preview() {
+        let values =
+            values(Some(IDL), Some(vec![field("price", None), field("size", Some("quantity"))]));
+        let preview = build_schema_preview(&values).unwrap();
+        assert_eq!(preview.entity_name, "PlaceOrder");
+        let schema = preview.to_graphql_string();
+        assert!(schema.contains("  price: BigInt!\n"));
+        assert!(schema.contains("  size: Int!\n"));
+        assert!(schema.contains("  slot: BigInt! @indexed\n"));
+    }
+
+    #[test]
+    fn it_reports_all_subgraph_violations_together() {
+        let diag = build_schema_preview(&values(None, None)).unwrap_err();
+        assert!(diag.message.contains(&format!("'{}'", PROGRAM_IDL)));
+        assert!(diag.message.contains(&format!("'{}'", INSTRUCTION)));
+
+        let diag = build_schema_preview(&values(Some("{"), Some(vec![]))).unwrap_err();
+        assert_eq!(diag.message.matches("\n  - ").count(), 1);
+
+        let fields =
+            vec![field("price", None), field("price", Some("quantity")), field("fee", None)];
+        let diag = build_schema_preview(&values(Some(IDL), Some(fields))).unwrap_err();
+        assert!(diag.message.contains("argument 'fee', which does not exist"));
+        assert!(diag.message.contains("duplicate subgraph field name 'price'"));
     }
 }
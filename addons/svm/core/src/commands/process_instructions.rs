@@ -78,6 +78,14 @@ lazy_static! {
                         internal: false,
                         sensitive: false
                     },
+                    rpc_ws_url: {
+                        documentation: "The websocket URL over which the confirmation of transactions is awaited. If omitted, confirmations are awaited by polling `rpc_api_url`.",
+                        typing: Type::string(),
+                        optional: true,
+                        tainting: false,
+                        internal: false,
+                        sensitive: false
+                    },
                     rpc_api_auth_token: {
                         documentation: "The HTTP authentication token to include in the headers when making API requests.",
                         typing: Type::string(),
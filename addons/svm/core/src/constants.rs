@@ -4,7 +4,7 @@ pub const SIGNER: &str = "signer";
 pub const PAYER: &str = "payer";
 pub const AUTHORITY: &str = "authority";
 
-pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/501'/0'/0'";
+pub const DERIVATION_PATH_TEMPLATE: &str = "m/44'/501'/{account_index}'/0'";
 pub const DEFAULT_ANCHOR_TARGET_PATH: &str = "target";
 pub const DEFAULT_NATIVE_TARGET_PATH: &str = "target";
 pub const DEFAULT_SHANK_IDL_PATH: &str = "idl";
@@ -15,6 +15,16 @@ pub const REQUESTED_STARTUP_DATA: &str = "requested_startup_data";
 pub const CHECKED_ADDRESS: &str = "checked_address";
 pub const EXPECTED_ADDRESS: &str = "expected_address";
 pub const PROGRAM_DEPLOYMENT_KEYPAIR: &str = "program_deployment_keypair";
+#[cfg(feature = "kms")]
+pub const KMS_PROVIDER: &str = "kms_provider";
+#[cfg(feature = "kms")]
+pub const KMS_KEY_ID: &str = "kms_key_id";
+#[cfg(feature = "kms")]
+pub const KMS_REGION: &str = "kms_region";
+#[cfg(feature = "kms")]
+pub const KMS_PUBLIC_KEY: &str = "kms_public_key";
+#[cfg(feature = "kms")]
+pub const KMS_ENDPOINT: &str = "kms_endpoint";
 
 // Signers
 pub const IS_SIGNABLE: &str = "is_signable";
@@ -22,12 +32,19 @@ pub const FORMATTED_TRANSACTION: &str = "formatted_transaction";
 pub const SECRET_KEY: &str = "secret_key";
 pub const MNEMONIC: &str = "mnemonic";
 pub const DERIVATION_PATH: &str = "derivation_path";
+pub const CONFIRM_ADDRESS: &str = "confirm_address";
 pub const IS_ENCRYPTED: &str = "is_encrypted";
 pub const PASSWORD: &str = "password";
 pub const KEYPAIR_JSON: &str = "keypair_json";
+pub const KEY_ID: &str = "key_id";
+pub const REGION: &str = "region";
+pub const KEY_NAME: &str = "key_name";
+pub const ENDPOINT: &str = "endpoint";
 
 // Defaults keys
 pub const RPC_API_URL: &str = "rpc_api_url";
+pub const RPC_WS_URL: &str = "rpc_ws_url";
+pub const RPC_API_AUTH_TOKEN: &str = "rpc_api_auth_token";
 pub const PROGRAM_ID: &str = "program_id";
 pub const PROGRAM_IDL: &str = "program_idl";
 pub const PROGRAM: &str = "program";
@@ -72,6 +89,11 @@ pub const DEPLOYMENT_TRANSACTIONS: &str = "deployment_transactions";
 pub const INITIAL_EXPECTED_DEPLOYMENT_TRANSACTIONS_COUNT: &str =
     "initial_expected_deployment_transactions_count";
 pub const SIGNED_NESTED_EXECUTION_INDEX: &str = "signed_nested_execution_index";
+pub const DEPLOYMENT_RECOVERY_BASE: &str = "deployment_recovery_base";
+pub const DEPLOYMENT_RECOVERY_WARNING: &str = "deployment_recovery_warning";
+pub const WRITE_CONCURRENCY: &str = "write_concurrency";
+pub const WRITES_PER_SECOND: &str = "writes_per_second";
+pub const MAX_RETRIES_PER_CHUNK: &str = "max_retries_per_chunk";
 pub const IS_WRITABLE: &str = "is_writable";
 pub const IS_SIGNER: &str = "is_signer";
 pub const REMAINING_ACCOUNT: &str = "remaining_account";
@@ -79,6 +101,10 @@ pub const SKIP_PREFLIGHT: &str = "skip_preflight";
 
 // Subgraph keys
 pub const SLOT: &str = "slot";
+pub const SUBGRAPH_NAME: &str = "subgraph_name";
+pub const PREVIEW_ONLY: &str = "preview_only";
+pub const SCHEMA: &str = "schema";
+pub const SCHEMA_PREVIEW: &str = "schema_preview";
 
 // Actions items keys
 pub const ACTION_ITEM_CHECK_BALANCE: &str = "check_balance";
@@ -86,6 +112,8 @@ pub const ACTION_ITEM_CHECK_ADDRESS: &str = "check_address";
 pub const ACTION_ITEM_PROVIDE_PUBLIC_KEY: &str = "provide_public_key";
 pub const ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION: &str = "provide_signed_transaction";
 pub const ACTION_ITEM_PROVIDE_SIGNED_SQUAD_TRANSACTION: &str = "provide_signed_squad_transaction";
+pub const ACTION_ITEM_SQUADS_INSTRUCTIONS: &str = "squads_instructions";
+pub const ACTION_ITEM_WALLET_INSTRUCTIONS: &str = "wallet_instructions";
 
 // Squads keys
 pub const VAULT_INDEX: &str = "vault_index";
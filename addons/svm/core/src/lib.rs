@@ -5,6 +5,7 @@ extern crate lazy_static;
 extern crate txtx_addon_kit;
 
 pub mod codec;
+pub mod codes;
 mod commands;
 mod constants;
 pub mod functions;
@@ -21,8 +22,8 @@ pub mod typing {
 use constants::NAMESPACE;
 use txtx_addon_kit::{
     types::{
-        commands::PreCommandSpecification, functions::FunctionSpecification,
-        signers::SignerSpecification,
+        commands::PreCommandSpecification, diagnostics::DiagnosticCode,
+        functions::FunctionSpecification, signers::SignerSpecification,
     },
     Addon,
 };
@@ -66,10 +67,18 @@ impl Addon for SvmNetworkAddon {
         signers::SIGNERS.clone()
     }
 
+    fn get_diagnostic_codes(&self) -> Vec<DiagnosticCode> {
+        codes::CODES.to_vec()
+    }
+
     fn to_json(
         &self,
         value: &txtx_addon_kit::types::types::Value,
     ) -> Result<Option<serde_json::Value>, txtx_addon_kit::types::diagnostics::Diagnostic> {
         SvmValue::to_json(value)
     }
+
+    fn format_display(&self, value: &txtx_addon_kit::types::types::Value) -> Option<String> {
+        SvmValue::format_display(value)
+    }
 }
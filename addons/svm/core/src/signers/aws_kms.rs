@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use txtx_addon_kit::channel;
+use txtx_addon_kit::types::commands::CommandExecutionResult;
+use txtx_addon_kit::types::frontend::BlockEvent;
+use txtx_addon_kit::types::signers::{
+    return_synchronous_result, CheckSignabilityOk, SignerActionErr, SignerActionsFutureResult,
+    SignerActivateFutureResult, SignerCapabilities, SignerImplementation, SignerInstance,
+    SignerSignFutureResult, SignerSpecification, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::RunbookSupervisionContext;
+use txtx_addon_kit::types::AuthorizationContext;
+use txtx_addon_kit::types::{
+    diagnostics::Diagnostic,
+    types::{Type, Value},
+    ConstructDid,
+};
+
+use crate::constants::{ADDRESS, CHECKED_ADDRESS, CHECKED_PUBLIC_KEY, KEY_ID, PUBLIC_KEY, REGION};
+use crate::signers::kms::aws::AwsKmsKey;
+use crate::signers::kms::{check_kms_signer_activability, sign_with_kms_signer, KmsKey};
+use crate::signers::secret_key::SvmSecretKey;
+
+lazy_static! {
+    pub static ref SVM_AWS_KMS: SignerSpecification = define_signer! {
+        SvmAwsKms => {
+            name: "AWS KMS Signer",
+            matcher: "aws_kms",
+            documentation:txtx_addon_kit::indoc! {r#"The `svm::aws_kms` signer can be used to synchronously sign a transaction with an Ed25519 key (`ECC_NIST_EDWARDS25519`) held by AWS KMS.
+            Credentials are resolved with the standard AWS provider chain. The key never leaves AWS KMS: its public key is retrieved to derive the signer address, and transactions are signed with the KMS `Sign` API."#},
+            inputs: [
+                key_id: {
+                    documentation: "The ARN of the KMS key. A key id or alias can also be used, along with the `region` input.",
+                    typing: Type::string(),
+                    optional: false,
+                    tainting: true,
+                    sensitive: false
+                },
+                region: {
+                    documentation: "The AWS region of the KMS key. By default, the region of the key ARN is used, then the region of the AWS configuration.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: false
+                },
+                expected_address: {
+                    documentation: "The address the KMS key is expected to have. The signer fails to activate if the addresses don't match.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: false
+                }
+            ],
+            outputs: [
+                public_key: {
+                    documentation: "The public key of the KMS key.",
+                    typing: Type::string()
+                },
+                address: {
+                    documentation: "The SVM address of the KMS key. This is an alias for the `public_key` output.",
+                    typing: Type::string()
+                }
+            ],
+            example: txtx_addon_kit::indoc! {r#"
+                signer "deployer" "svm::aws_kms" {
+                    key_id = "arn:aws:kms:us-east-1:111122223333:key/1234abcd-12ab-34cd-56ef-1234567890ab"
+                }
+            "#},
+            capabilities: SignerCapabilities {
+                sign_transaction: true,
+                ..SignerCapabilities::none()
+            }
+        }
+    };
+}
+
+pub struct SvmAwsKms;
+impl SignerImplementation for SvmAwsKms {
+    fn check_instantiability(
+        _ctx: &SignerSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn check_activability(
+        construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &AuthorizationContext,
+        _is_balance_check_required: bool,
+        _is_public_key_required: bool,
+    ) -> SignerActionsFutureResult {
+        let key_id = values
+            .get_expected_string(KEY_ID)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let kms_key = KmsKey::Aws(AwsKmsKey::new(key_id, values.get_string(REGION)));
+
+        check_kms_signer_activability(
+            kms_key,
+            construct_did,
+            instance_name,
+            values,
+            signer_state,
+            signers,
+            supervision_context,
+            auth_ctx,
+        )
+    }
+
+    fn activate(
+        _construct_did: &ConstructDid,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _progress_tx: &channel::Sender<BlockEvent>,
+    ) -> SignerActivateFutureResult {
+        let mut result = CommandExecutionResult::new();
+        let public_key = signer_state
+            .get_expected_value(CHECKED_PUBLIC_KEY)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let address = signer_state
+            .get_expected_value(CHECKED_ADDRESS)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        result.outputs.insert(ADDRESS.into(), address.clone());
+        result.outputs.insert(PUBLIC_KEY.into(), public_key.clone());
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+
+    fn check_signability(
+        construct_did: &ConstructDid,
+        title: &str,
+        description: &Option<String>,
+        meta_description: &Option<String>,
+        markdown: &Option<String>,
+        payload: &Value,
+        spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &AuthorizationContext,
+    ) -> Result<CheckSignabilityOk, SignerActionErr> {
+        SvmSecretKey::check_signability(
+            construct_did,
+            title,
+            description,
+            meta_description,
+            markdown,
+            payload,
+            spec,
+            values,
+            signer_state,
+            signers,
+            signers_instances,
+            supervision_context,
+            auth_ctx,
+        )
+    }
+
+    fn sign(
+        construct_did: &ConstructDid,
+        _title: &str,
+        payload: &Value,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    ) -> SignerSignFutureResult {
+        sign_with_kms_signer(construct_did, payload, values, signer_state, signers)
+    }
+}
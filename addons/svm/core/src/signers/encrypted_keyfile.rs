@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use solana_keypair::{keypair_from_seed, Keypair};
+use solana_signer::Signer;
+use txtx_addon_kit::channel;
+use txtx_addon_kit::crypto::keyfile::{unlock_signer_keyfile, UnlockedKeyfile};
+use txtx_addon_kit::types::commands::CommandExecutionResult;
+use txtx_addon_kit::types::frontend::{Actions, BlockEvent, ReviewInputRequest};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, return_synchronous_result, CheckSignabilityOk, SignerActionErr,
+    SignerActionsFutureResult, SignerActivateFutureResult, SignerImplementation, SignerInstance,
+    SignerSignFutureResult, SignerSpecification, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::RunbookSupervisionContext;
+use txtx_addon_kit::types::AuthorizationContext;
+use txtx_addon_kit::types::{
+    diagnostics::Diagnostic,
+    types::{Type, Value},
+    ConstructDid,
+};
+
+use crate::constants::{
+    ACTION_ITEM_CHECK_ADDRESS, ADDRESS, CHECKED_ADDRESS, CHECKED_PUBLIC_KEY, EXPECTED_ADDRESS,
+    PUBLIC_KEY, REQUESTED_STARTUP_DATA, SECRET_KEY,
+};
+use crate::signers::secret_key::SvmSecretKey;
+
+lazy_static! {
+    pub static ref SVM_ENCRYPTED_KEYFILE: SignerSpecification = define_signer! {
+        SvmEncryptedKeyfile => {
+            name: "Encrypted Keyfile Signer",
+            matcher: "encrypted_keyfile",
+            documentation:txtx_addon_kit::indoc! {r#"The `svm::encrypted_keyfile` signer can be used to synchronously sign a transaction with a secret key stored in a keyfile encrypted with a passphrase (scrypt and AES-256-GCM), as created by `txtx keyfile new`.
+            In supervised mode, the passphrase is requested in the supervisor. In unsupervised mode, the passphrase is read from the environment variable named by the `passphrase_env` input.
+            The keyfile is decrypted once, when the signer is activated: the passphrase is then discarded, and the decrypted key is only kept in memory for the duration of the run."#},
+            inputs: [
+                keyfile_path: {
+                    documentation: "The path to the encrypted keyfile, relative to the txtx workspace.",
+                    typing: Type::string(),
+                    optional: false,
+                    tainting: true,
+                    sensitive: false
+                },
+                passphrase_env: {
+                    documentation: "The name of the environment variable holding the passphrase of the keyfile. Required in unsupervised mode.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: false,
+                    sensitive: false
+                },
+                expected_address: {
+                    documentation: "The address the keyfile is expected to decrypt to. The signer fails to activate if the addresses don't match.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: false
+                }
+            ],
+            outputs: [
+                public_key: {
+                    documentation: "The public key of the account stored in the keyfile.",
+                    typing: Type::string()
+                },
+                address: {
+                    documentation: "The SVM address of the account stored in the keyfile. This is an alias for the `public_key` output.",
+                    typing: Type::string()
+                }
+            ],
+            example: txtx_addon_kit::indoc! {r#"
+                signer "deployer" "svm::encrypted_keyfile" {
+                    keyfile_path = "./keys/deployer.json"
+                    passphrase_env = "DEPLOYER_PASSPHRASE"
+                }
+            "#}
+        }
+    };
+}
+
+/// Builds the keypair held by a decrypted keyfile, either a 32-byte seed or a 64-byte keypair.
+fn secret_key_to_keypair(secret_key: &[u8]) -> Result<Keypair, String> {
+    let keypair = if secret_key.len() == 32 {
+        keypair_from_seed(&secret_key).ok()
+    } else {
+        Keypair::try_from(secret_key).ok()
+    };
+    keypair.ok_or("keyfile does not contain an Ed25519 secret key".into())
+}
+
+pub struct SvmEncryptedKeyfile;
+impl SignerImplementation for SvmEncryptedKeyfile {
+    fn check_instantiability(
+        _ctx: &SignerSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn check_activability(
+        construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &AuthorizationContext,
+        _is_balance_check_required: bool,
+        _is_public_key_required: bool,
+    ) -> SignerActionsFutureResult {
+        use txtx_addon_kit::constants::DESCRIPTION;
+
+        let mut actions = Actions::none();
+
+        if signer_state.get_value(CHECKED_PUBLIC_KEY).is_some() {
+            return return_synchronous_actions(Ok((signers, signer_state, actions)));
+        }
+
+        let description = values.get_string(DESCRIPTION).map(|d| d.to_string());
+        let markdown = values
+            .get_markdown(auth_ctx)
+            .map_err(|d| (signers.clone(), signer_state.clone(), d))?;
+
+        // the keyfile is decrypted once, its keypair being kept like the secret key signer's
+        let keypair = match signer_state.get_expected_buffer_bytes(SECRET_KEY) {
+            Ok(secret_key) => secret_key_to_keypair(&secret_key),
+            Err(_) => {
+                let unlocked = unlock_signer_keyfile(
+                    construct_did,
+                    instance_name,
+                    values,
+                    supervision_context,
+                    auth_ctx,
+                )
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+                match unlocked {
+                    UnlockedKeyfile::SecretKey(secret_key) => secret_key_to_keypair(&secret_key),
+                    UnlockedKeyfile::PassphraseRequested(actions) => {
+                        return return_synchronous_actions(Ok((signers, signer_state, actions)));
+                    }
+                }
+            }
+        }
+        .map_err(|e| {
+            (
+                signers.clone(),
+                signer_state.clone(),
+                diagnosed_error!("signer '{instance_name}': {e}"),
+            )
+        })?;
+        let pubkey = keypair.pubkey();
+
+        if let Some(expected_address) = values.get_string(EXPECTED_ADDRESS) {
+            if expected_address != pubkey.to_string() {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!(
+                        "signer '{instance_name}': keyfile address {pubkey} does not match expected address {expected_address}"
+                    ),
+                ));
+            }
+        }
+
+        signer_state.insert(SECRET_KEY, Value::buffer(keypair.to_bytes().to_vec()));
+        let public_key_value = Value::string(pubkey.to_string());
+
+        if supervision_context.review_input_values {
+            signer_state.insert(&REQUESTED_STARTUP_DATA, Value::bool(true));
+            if let Ok(_) = signer_state.get_expected_string(CHECKED_ADDRESS) {
+                signer_state.insert(CHECKED_PUBLIC_KEY, public_key_value.clone());
+                signer_state.insert(CHECKED_ADDRESS, public_key_value.clone());
+            } else {
+                actions.push_sub_group(
+                    None,
+                    vec![ReviewInputRequest::new("", &public_key_value)
+                        .to_action_type()
+                        .to_request(instance_name, ACTION_ITEM_CHECK_ADDRESS)
+                        .with_construct_did(construct_did)
+                        .with_some_description(description)
+                        .with_meta_description(&format!("Check {} expected address", instance_name))
+                        .with_some_markdown(markdown)],
+                );
+            }
+        } else {
+            signer_state.insert(CHECKED_PUBLIC_KEY, public_key_value.clone());
+            signer_state.insert(CHECKED_ADDRESS, public_key_value.clone());
+        }
+        return_synchronous_actions(Ok((signers, signer_state, actions)))
+    }
+
+    fn activate(
+        _construct_did: &ConstructDid,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _progress_tx: &channel::Sender<BlockEvent>,
+    ) -> SignerActivateFutureResult {
+        let mut result = CommandExecutionResult::new();
+        let public_key = signer_state
+            .get_expected_value(CHECKED_PUBLIC_KEY)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let address = signer_state
+            .get_expected_value(CHECKED_ADDRESS)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        result.outputs.insert(ADDRESS.into(), address.clone());
+        result.outputs.insert(PUBLIC_KEY.into(), public_key.clone());
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+
+    fn check_signability(
+        construct_did: &ConstructDid,
+        title: &str,
+        description: &Option<String>,
+        meta_description: &Option<String>,
+        markdown: &Option<String>,
+        payload: &Value,
+        spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &AuthorizationContext,
+    ) -> Result<CheckSignabilityOk, SignerActionErr> {
+        SvmSecretKey::check_signability(
+            construct_did,
+            title,
+            description,
+            meta_description,
+            markdown,
+            payload,
+            spec,
+            values,
+            signer_state,
+            signers,
+            signers_instances,
+            supervision_context,
+            auth_ctx,
+        )
+    }
+
+    fn sign(
+        construct_did: &ConstructDid,
+        title: &str,
+        payload: &Value,
+        spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    ) -> SignerSignFutureResult {
+        SvmSecretKey::sign(
+            construct_did,
+            title,
+            payload,
+            spec,
+            values,
+            signer_state,
+            signers,
+            signers_instances,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_keypairs_from_seeds_and_keypairs() {
+        let keypair = Keypair::new();
+        assert_eq!(secret_key_to_keypair(&keypair.to_bytes()).unwrap().pubkey(), keypair.pubkey());
+
+        let seed = [7u8; 32];
+        assert_eq!(
+            secret_key_to_keypair(&seed).unwrap().pubkey(),
+            keypair_from_seed(&seed).unwrap().pubkey()
+        );
+
+        assert!(secret_key_to_keypair(&[7u8; 16]).is_err());
+    }
+}
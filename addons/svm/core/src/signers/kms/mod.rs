@@ -0,0 +1,347 @@
+pub use txtx_addon_kit::crypto::kms::{aws, gcp};
+
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_transaction::Transaction;
+use txtx_addon_kit::types::commands::CommandExecutionResult;
+use txtx_addon_kit::types::frontend::{Actions, ReviewInputRequest};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, SignerActionsFutureResult, SignerSignFutureResult, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::{RunbookSupervisionContext, Value};
+use txtx_addon_kit::types::{diagnostics::Diagnostic, AuthorizationContext, ConstructDid};
+use txtx_addon_network_svm_types::SvmValue;
+
+use crate::codec::DeploymentTransaction;
+use crate::constants::{
+    ACTION_ITEM_CHECK_ADDRESS, CHECKED_ADDRESS, CHECKED_PUBLIC_KEY, COMMITMENT_LEVEL,
+    EXPECTED_ADDRESS, IS_DEPLOYMENT, KMS_ENDPOINT, KMS_KEY_ID, KMS_PROVIDER, KMS_PUBLIC_KEY,
+    KMS_REGION, PARTIALLY_SIGNED_TRANSACTION_BYTES, PREVIOUSLY_SIGNED_BLOCKHASH,
+    REQUESTED_STARTUP_DATA, RPC_API_URL,
+};
+use crate::utils::build_transaction_from_svm_value;
+
+use aws::AwsKmsKey;
+use gcp::GcpKmsKey;
+
+const AWS_PROVIDER: &str = "aws";
+const GCP_PROVIDER: &str = "gcp";
+
+/// The DER prefix of an Ed25519 SubjectPublicKeyInfo (RFC 8410), followed by the 32-byte key.
+const ED25519_SPKI_PREFIX: [u8; 12] =
+    [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+/// An Ed25519 key held by a cloud KMS. The key material never leaves the KMS: the public key is
+/// fetched to derive the signer address, and transaction messages are signed remotely.
+#[derive(Clone, Debug)]
+pub enum KmsKey {
+    Aws(AwsKmsKey),
+    Gcp(GcpKmsKey),
+}
+
+impl KmsKey {
+    /// Fetches the public key of the KMS key.
+    pub async fn get_pubkey(&self) -> Result<Pubkey, Diagnostic> {
+        let der = match self {
+            KmsKey::Aws(key) => key.get_public_key_der().await?,
+            KmsKey::Gcp(key) => key.get_public_key_der().await?,
+        };
+        ed25519_pubkey_from_der(&der).map_err(|e| diagnosed_error!("{}: {e}", self.describe()))
+    }
+
+    /// Signs `message` with the KMS key.
+    pub async fn sign_message(&self, message: &[u8]) -> Result<Signature, Diagnostic> {
+        let signature = match self {
+            KmsKey::Aws(key) => key.sign_ed25519(message).await?,
+            KmsKey::Gcp(key) => key.sign_ed25519(message).await?,
+        };
+        Signature::try_from(signature.as_slice()).map_err(|_| {
+            diagnosed_error!(
+                "invalid signature from {}: expected 64 bytes, got {}",
+                self.describe(),
+                signature.len()
+            )
+        })
+    }
+
+    /// Adds the signature of `pubkey`, which must be a required signer of `transaction`.
+    pub async fn sign_transaction(
+        &self,
+        pubkey: &Pubkey,
+        transaction: &mut Transaction,
+    ) -> Result<(), Diagnostic> {
+        let position = transaction
+            .get_signing_keypair_positions(&[*pubkey])
+            .map_err(|e| diagnosed_error!("failed to sign transaction: {e}"))?
+            .first()
+            .cloned()
+            .flatten()
+            .ok_or(diagnosed_error!(
+                "failed to sign transaction: {} is not a signer of the transaction",
+                pubkey
+            ))?;
+        let signature = self.sign_message(&transaction.message_data()).await?;
+        transaction.signatures[position] = signature;
+        Ok(())
+    }
+
+    /// Records the key in the signer state, so that it can be used by [KmsKey::from_signer_state]
+    /// when signing. Only the key identifiers are stored.
+    pub fn insert_in_signer_state(&self, signer_state: &mut ValueStore) {
+        match self {
+            KmsKey::Aws(key) => {
+                signer_state.insert(KMS_PROVIDER, Value::string(AWS_PROVIDER.into()));
+                signer_state.insert(KMS_KEY_ID, Value::string(key.key_id.clone()));
+                if let Some(region) = &key.region {
+                    signer_state.insert(KMS_REGION, Value::string(region.clone()));
+                }
+            }
+            KmsKey::Gcp(key) => {
+                signer_state.insert(KMS_PROVIDER, Value::string(GCP_PROVIDER.into()));
+                signer_state.insert(KMS_KEY_ID, Value::string(key.key_name.clone()));
+                if let Some(endpoint) = &key.endpoint {
+                    signer_state.insert(KMS_ENDPOINT, Value::string(endpoint.clone()));
+                }
+            }
+        }
+    }
+
+    pub fn from_signer_state(signer_state: &ValueStore) -> Result<Self, Diagnostic> {
+        let key_id = signer_state.get_expected_string(KMS_KEY_ID)?;
+        match signer_state.get_expected_string(KMS_PROVIDER)? {
+            AWS_PROVIDER => {
+                Ok(KmsKey::Aws(AwsKmsKey::new(key_id, signer_state.get_string(KMS_REGION))))
+            }
+            GCP_PROVIDER => {
+                Ok(KmsKey::Gcp(GcpKmsKey::new(key_id, signer_state.get_string(KMS_ENDPOINT))))
+            }
+            provider => Err(diagnosed_error!("unsupported KMS provider '{provider}'")),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            KmsKey::Aws(key) => format!("AWS KMS key {}", key.key_id),
+            KmsKey::Gcp(key) => format!("Cloud KMS key {}", key.key_name),
+        }
+    }
+}
+
+/// Extracts the Ed25519 public key of a DER-encoded SubjectPublicKeyInfo.
+pub fn ed25519_pubkey_from_der(der: &[u8]) -> Result<Pubkey, String> {
+    match der.strip_prefix(&ED25519_SPKI_PREFIX[..]) {
+        Some(key) if key.len() == 32 => Ok(Pubkey::try_from(key).expect("32 bytes public key")),
+        _ => Err("not an Ed25519 (ECC_NIST_EDWARDS25519) public key".into()),
+    }
+}
+
+/// Fetches the public key of `kms_key`, checks it against the `expected_address` input, and
+/// requests the review of the address in supervised mode.
+pub fn check_kms_signer_activability(
+    kms_key: KmsKey,
+    construct_did: &ConstructDid,
+    instance_name: &str,
+    values: &ValueStore,
+    mut signer_state: ValueStore,
+    signers: SignersState,
+    supervision_context: &RunbookSupervisionContext,
+    auth_ctx: &AuthorizationContext,
+) -> SignerActionsFutureResult {
+    use txtx_addon_kit::constants::DESCRIPTION;
+
+    let mut actions = Actions::none();
+
+    if signer_state.get_value(CHECKED_PUBLIC_KEY).is_some() {
+        return return_synchronous_actions(Ok((signers, signer_state, actions)));
+    }
+
+    let description = values.get_string(DESCRIPTION).map(|d| d.to_string());
+    let markdown =
+        values.get_markdown(auth_ctx).map_err(|d| (signers.clone(), signer_state.clone(), d))?;
+    let expected_address = values.get_string(EXPECTED_ADDRESS).map(|a| a.to_string());
+    let construct_did = construct_did.clone();
+    let instance_name = instance_name.to_string();
+    let review_input_values = supervision_context.review_input_values;
+
+    let future = async move {
+        let pubkey = match signer_state.get_string(KMS_PUBLIC_KEY) {
+            Some(pubkey) => Pubkey::try_from(pubkey)
+                .map_err(|e| diagnosed_error!("invalid public key '{pubkey}': {e}")),
+            None => kms_key.get_pubkey().await,
+        }
+        .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+        if let Some(expected_address) = expected_address {
+            if expected_address != pubkey.to_string() {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!(
+                        "signer '{instance_name}': {} has address {pubkey}, but {expected_address} was expected",
+                        kms_key.describe()
+                    ),
+                ));
+            }
+        }
+
+        kms_key.insert_in_signer_state(&mut signer_state);
+        let public_key_value = Value::string(pubkey.to_string());
+        signer_state.insert(KMS_PUBLIC_KEY, public_key_value.clone());
+
+        if review_input_values {
+            signer_state.insert(&REQUESTED_STARTUP_DATA, Value::bool(true));
+            if let Ok(_) = signer_state.get_expected_string(CHECKED_ADDRESS) {
+                signer_state.insert(CHECKED_PUBLIC_KEY, public_key_value.clone());
+                signer_state.insert(CHECKED_ADDRESS, public_key_value.clone());
+            } else {
+                actions.push_sub_group(
+                    None,
+                    vec![ReviewInputRequest::new("", &public_key_value)
+                        .to_action_type()
+                        .to_request(&instance_name, ACTION_ITEM_CHECK_ADDRESS)
+                        .with_construct_did(&construct_did)
+                        .with_some_description(description)
+                        .with_meta_description(&format!(
+                            "Check {} expected address ({})",
+                            instance_name,
+                            kms_key.describe()
+                        ))
+                        .with_some_markdown(markdown)],
+                );
+            }
+        } else {
+            signer_state.insert(CHECKED_PUBLIC_KEY, public_key_value.clone());
+            signer_state.insert(CHECKED_ADDRESS, public_key_value.clone());
+        }
+        Ok((signers, signer_state, actions))
+    };
+    Ok(Box::pin(future))
+}
+
+/// Signs the transaction `payload` with the KMS key recorded in the signer state.
+pub fn sign_with_kms_signer(
+    construct_did: &ConstructDid,
+    payload: &Value,
+    values: &ValueStore,
+    mut signer_state: ValueStore,
+    signers: SignersState,
+) -> SignerSignFutureResult {
+    let construct_did = construct_did.clone();
+    let payload = payload.clone();
+    let values = values.clone();
+
+    let future = async move {
+        let mut result = CommandExecutionResult::new();
+
+        let kms_key = KmsKey::from_signer_state(&signer_state)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let pubkey = signer_state
+            .get_expected_string(KMS_PUBLIC_KEY)
+            .and_then(|pubkey| {
+                Pubkey::try_from(pubkey)
+                    .map_err(|e| diagnosed_error!("invalid public key '{pubkey}': {e}"))
+            })
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+        // value signed (partially, maybe) by another signer
+        let previously_signed_blockhash = signer_state
+            .remove_scoped_value(&construct_did.to_string(), PREVIOUSLY_SIGNED_BLOCKHASH);
+
+        // prevent discrepancies between new block hash and a hash on the transaction that's already been signed
+        let blockhash = if let Some(blockhash) = &previously_signed_blockhash {
+            solana_hash::Hash::new_from_array(blockhash.to_be_bytes().try_into().unwrap())
+        } else {
+            let rpc_api_url = values
+                .get_expected_string(RPC_API_URL)
+                .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?
+                .to_string();
+
+            let commitment = match values.get_string(COMMITMENT_LEVEL).unwrap_or("processed") {
+                "finalized" => CommitmentLevel::Finalized,
+                "processed" => CommitmentLevel::Processed,
+                "confirmed" => CommitmentLevel::Confirmed,
+                _ => CommitmentLevel::Processed,
+            };
+            let rpc_client =
+                RpcClient::new_with_commitment(rpc_api_url, CommitmentConfig { commitment });
+
+            rpc_client.get_latest_blockhash().map_err(|e| {
+                (
+                    signers.clone(),
+                    signer_state.clone(),
+                    diagnosed_error!("failed to get latest blockhash: {e}"),
+                )
+            })?
+        };
+
+        let is_deployment = values.get_bool(IS_DEPLOYMENT).unwrap_or(false);
+
+        let (mut transaction, do_sign_with_txtx_signer) = if is_deployment {
+            let deployment_transaction = DeploymentTransaction::from_value(&payload)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+            let mut transaction: Transaction =
+                deployment_transaction.transaction.as_ref().unwrap().clone();
+
+            transaction.message.recent_blockhash = blockhash;
+
+            let keypairs = deployment_transaction
+                .get_keypairs()
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+            transaction.try_partial_sign(&keypairs, transaction.message.recent_blockhash).map_err(
+                |e| {
+                    (
+                        signers.clone(),
+                        signer_state.clone(),
+                        diagnosed_error!("failed to sign transaction: {e}"),
+                    )
+                },
+            )?;
+
+            (transaction, deployment_transaction.signers.is_some())
+        } else {
+            let mut transaction: Transaction = build_transaction_from_svm_value(&payload)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+            transaction.message.recent_blockhash = blockhash;
+
+            (transaction, true)
+        };
+
+        if do_sign_with_txtx_signer {
+            kms_key
+                .sign_transaction(&pubkey, &mut transaction)
+                .await
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        }
+        result.outputs.insert(
+            PARTIALLY_SIGNED_TRANSACTION_BYTES.into(),
+            SvmValue::transaction(&transaction)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?,
+        );
+
+        Ok((signers, signer_state, result))
+    };
+    Ok(Box::pin(future))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decodes_ed25519_public_keys() {
+        let pubkey = Pubkey::new_unique();
+        let der = [&ED25519_SPKI_PREFIX[..], pubkey.as_ref()].concat();
+        assert_eq!(ed25519_pubkey_from_der(&der).unwrap(), pubkey);
+
+        // a secp256k1 key is rejected
+        let mut der = der.clone();
+        der[8] = 0x71;
+        assert!(ed25519_pubkey_from_der(&der).is_err());
+        assert!(ed25519_pubkey_from_der(&ED25519_SPKI_PREFIX).is_err());
+    }
+}
@@ -0,0 +1,592 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use solana_client::rpc_client::RpcClient;
+use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_derivation_path::DerivationPath;
+use solana_pubkey::Pubkey;
+use solana_remote_wallet::ledger::LedgerWallet;
+use solana_remote_wallet::ledger_error::LedgerError;
+use solana_remote_wallet::locator::Locator;
+use solana_remote_wallet::remote_keypair::generate_remote_keypair;
+use solana_remote_wallet::remote_wallet::{
+    initialize_wallet_manager, RemoteWallet, RemoteWalletError, RemoteWalletType,
+};
+use solana_signature::Signature;
+use solana_transaction::Transaction;
+use txtx_addon_kit::channel;
+use txtx_addon_kit::constants::{SIGNATURE_APPROVED, SIGNATURE_SKIPPABLE, SIGNED_MESSAGE_BYTES};
+use txtx_addon_kit::crypto::derivation::get_derivation_path;
+use txtx_addon_kit::types::commands::CommandExecutionResult;
+use txtx_addon_kit::types::frontend::{
+    ActionItemStatus, Actions, BlockEvent, LogDispatcher, ProvideSignedTransactionRequest,
+};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, return_synchronous_result, CheckSignabilityOk, SignerActionErr,
+    SignerActionsFutureResult, SignerActivateFutureResult, SignerImplementation, SignerInstance,
+    SignerSignFutureResult, SignerSpecification, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::RunbookSupervisionContext;
+use txtx_addon_kit::types::{
+    commands::CommandSpecification,
+    diagnostics::Diagnostic,
+    types::{Type, Value},
+};
+use txtx_addon_kit::types::{AuthorizationContext, ConstructDid, Did};
+use txtx_addon_network_svm_types::SvmValue;
+
+use crate::codec::DeploymentTransaction;
+use crate::constants::{
+    ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION, ADDRESS, CHECKED_ADDRESS, CHECKED_PUBLIC_KEY,
+    COMMITMENT_LEVEL, CONFIRM_ADDRESS, DERIVATION_PATH, DERIVATION_PATH_TEMPLATE, EXPECTED_ADDRESS,
+    FORMATTED_TRANSACTION, IS_DEPLOYMENT, IS_SIGNABLE, NAMESPACE, NETWORK_ID,
+    PARTIALLY_SIGNED_TRANSACTION_BYTES, PREVIOUSLY_SIGNED_BLOCKHASH, PUBLIC_KEY, RPC_API_URL,
+    TRANSACTION_BYTES,
+};
+use crate::utils::build_transaction_from_svm_value;
+
+const LEDGER_LOCATOR: &str = "usb://ledger";
+
+thread_local! {
+    /// The Ledger accounts connected to when the signers were activated, by signer. Device handles
+    /// can't be kept in the signer state: they're kept on the thread running the runbook instead.
+    static LEDGER_SESSIONS: RefCell<HashMap<Did, LedgerSession>> = RefCell::new(HashMap::new());
+}
+
+/// The connection of an activated Ledger signer, reused by every signature.
+#[derive(Clone)]
+struct LedgerSession {
+    account: Rc<LedgerAccount>,
+    progress_tx: channel::Sender<BlockEvent>,
+}
+
+impl LedgerSession {
+    fn get(signer_did: &Did) -> Option<Self> {
+        LEDGER_SESSIONS.with(|sessions| sessions.borrow().get(signer_did).cloned())
+    }
+
+    fn insert(signer_did: &Did, session: Self) {
+        LEDGER_SESSIONS.with(|sessions| sessions.borrow_mut().insert(signer_did.clone(), session));
+    }
+
+    /// Runs `f` with the connected account, reporting that the `operation` awaits a confirmation
+    /// on the device until it returns.
+    fn confirm_on_device<T>(
+        &self,
+        construct_did: &ConstructDid,
+        operation: &str,
+        f: impl FnOnce(&LedgerAccount) -> Result<T, Diagnostic>,
+    ) -> Result<T, Diagnostic> {
+        let logger = LogDispatcher::new(construct_did.as_uuid(), "svm::ledger", &self.progress_tx);
+        logger.pending_info(
+            "Awaiting Confirmation",
+            format!("Review and confirm the {operation} on the Ledger device"),
+        );
+        let res = f(&self.account);
+        match &res {
+            Ok(_) => logger
+                .success_info("Confirmed", format!("{operation} confirmed on the Ledger device")),
+            Err(diag) => logger.failure_info("Not Confirmed", &diag.message),
+        }
+        res
+    }
+}
+
+lazy_static! {
+    pub static ref SVM_LEDGER: SignerSpecification = define_signer! {
+        SvmLedger => {
+            name: "Ledger Signer",
+            matcher: "ledger",
+            documentation:txtx_addon_kit::indoc! {r#"The `svm::ledger` signer can be used to sign transactions with the Solana app of a Ledger hardware wallet.
+            The device must be connected and unlocked, with the Solana app open, for the duration of the Runbook execution. The device is connected to once, when the signer is activated, and each transaction must then be confirmed on the device."#},
+            inputs: [
+                derivation_path: {
+                    documentation: "The derivation path of the Ledger account used to sign transactions. The default is `m/44'/501'/0'/0'`. This input can't be combined with `account_index`.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: false
+                },
+                account_index: {
+                    documentation: "The index of the Ledger account used to sign transactions, following the `m/44'/501'/{account_index}'/0'` derivation path. Defaults to 0.",
+                    typing: Type::integer(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: false
+                },
+                expected_address: {
+                    documentation: "The SVM address that is expected for the Ledger account. If provided, the address of the connected device is checked against it.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    sensitive: false
+                },
+                confirm_address: {
+                    documentation: "If set to `true`, the Ledger account address is displayed on the device and must be confirmed before it is used. The default is `false`.",
+                    typing: Type::bool(),
+                    optional: true,
+                    tainting: false,
+                    sensitive: false
+                }
+            ],
+            outputs: [
+                public_key: {
+                    documentation: "The public key of the Ledger account.",
+                    typing: Type::string()
+                },
+                address: {
+                    documentation: "The SVM address of the Ledger account. This is an alias for the `public_key` output.",
+                    typing: Type::string()
+                }
+            ],
+            example: txtx_addon_kit::indoc! {r#"
+                signer "deployer" "svm::ledger" {
+                    derivation_path = "m/44'/501'/1'/0'"
+                }
+            "#}
+        }
+    };
+}
+
+/// A Ledger account, identified by the derivation path of its key on the device.
+pub struct LedgerAccount {
+    pub wallet: Rc<LedgerWallet>,
+    pub derivation_path: DerivationPath,
+    pub pubkey: Pubkey,
+}
+
+impl LedgerAccount {
+    /// Connects to the first Ledger device found, and retrieves the public key at `derivation_path`.
+    /// If `confirm_address` is true, the address must be confirmed on the device.
+    pub fn connect(derivation_path: &str, confirm_address: bool) -> Result<Self, Diagnostic> {
+        let derivation_path = parse_derivation_path(derivation_path)?;
+        let wallet_manager = initialize_wallet_manager().map_err(ledger_diagnostic)?;
+        let locator = Locator::new_from_path(LEDGER_LOCATOR)
+            .map_err(|e| diagnosed_error!("invalid ledger locator: {e}"))?;
+        let keypair = generate_remote_keypair(
+            locator,
+            derivation_path,
+            &wallet_manager,
+            confirm_address,
+            "ledger",
+        )
+        .map_err(ledger_diagnostic)?;
+
+        match keypair.wallet_type {
+            RemoteWalletType::Ledger(wallet) => Ok(Self {
+                wallet,
+                derivation_path: keypair.derivation_path,
+                pubkey: keypair.pubkey,
+            }),
+        }
+    }
+
+    /// Signs `transaction` with the Ledger account. The account must be a required signer of the
+    /// transaction. Blocks until the transaction is confirmed or rejected on the device.
+    pub fn sign_transaction(&self, transaction: &mut Transaction) -> Result<(), Diagnostic> {
+        let position = transaction
+            .get_signing_keypair_positions(&[self.pubkey])
+            .map_err(|e| diagnosed_error!("failed to sign transaction: {e}"))?
+            .first()
+            .cloned()
+            .flatten()
+            .ok_or(diagnosed_error!(
+                "failed to sign transaction: ledger account {} is not a signer of the transaction",
+                self.pubkey
+            ))?;
+
+        let signature = self
+            .wallet
+            .sign_message(&self.derivation_path, &transaction.message_data())
+            .map_err(ledger_diagnostic)?;
+        transaction.signatures[position] = signature;
+        Ok(())
+    }
+
+    /// Signs an off-chain `message` with the Ledger account, for Solana app versions supporting it.
+    pub fn sign_offchain_message(&self, message: &[u8]) -> Result<Signature, Diagnostic> {
+        self.wallet.sign_offchain_message(&self.derivation_path, message).map_err(|e| match e {
+            RemoteWalletError::LedgerError(LedgerError::UnimplementedInstruction) => {
+                diagnosed_error!(
+                    "the Solana app on the Ledger device does not support signing off-chain messages; update the app to its latest version"
+                )
+            }
+            e => ledger_diagnostic(e),
+        })
+    }
+}
+
+fn parse_derivation_path(derivation_path: &str) -> Result<DerivationPath, Diagnostic> {
+    DerivationPath::from_absolute_path_str(derivation_path)
+        .map_err(|e| diagnosed_error!("invalid derivation path '{}': {}", derivation_path, e))
+}
+
+/// Translates the errors of the Ledger transport and of the Solana app into actionable diagnostics.
+fn ledger_diagnostic(error: RemoteWalletError) -> Diagnostic {
+    match error {
+        RemoteWalletError::NoDeviceFound => diagnosed_error!(
+            "no Ledger device found: connect and unlock the device, then open the Solana app"
+        ),
+        RemoteWalletError::LedgerError(LedgerError::SdkNotSupported) => diagnosed_error!(
+            "the Solana app on the Ledger device rejected the transaction because it requires blind signing: enable 'Blind signing' in the Solana app settings on the device, then retry"
+        ),
+        RemoteWalletError::UserCancel | RemoteWalletError::LedgerError(LedgerError::UserCancel) => {
+            diagnosed_error!("the operation was rejected on the Ledger device")
+        }
+        RemoteWalletError::LedgerError(LedgerError::NoAppResponse)
+        | RemoteWalletError::LedgerError(LedgerError::InvalidCla) => diagnosed_error!(
+            "the Ledger device did not respond: make sure the Solana app is open on the device"
+        ),
+        e => diagnosed_error!("ledger error: {e}"),
+    }
+}
+
+pub struct SvmLedger;
+impl SignerImplementation for SvmLedger {
+    fn check_instantiability(
+        _ctx: &SignerSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    #[cfg(not(feature = "wasm"))]
+    fn check_activability(
+        _construct_did: &ConstructDid,
+        _instance_name: &str,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _supervision_context: &RunbookSupervisionContext,
+        _auth_ctx: &AuthorizationContext,
+        _is_balance_check_required: bool,
+        _is_public_key_required: bool,
+    ) -> SignerActionsFutureResult {
+        // the device is only connected to once, when the signer is activated
+        let derivation_path = get_derivation_path(values, DERIVATION_PATH_TEMPLATE)
+            .and_then(|path| parse_derivation_path(&path).map(|_| path))
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        if let Some(expected_address) = values.get_string(EXPECTED_ADDRESS) {
+            Pubkey::from_str(expected_address)
+                .map_err(|e| diagnosed_error!("invalid expected address '{expected_address}': {e}"))
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        }
+        signer_state.insert(DERIVATION_PATH, Value::string(derivation_path));
+        return_synchronous_actions(Ok((signers, signer_state, Actions::none())))
+    }
+
+    fn activate(
+        construct_did: &ConstructDid,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        progress_tx: &channel::Sender<BlockEvent>,
+    ) -> SignerActivateFutureResult {
+        let mut result = CommandExecutionResult::new();
+
+        let derivation_path = signer_state
+            .get_expected_string(DERIVATION_PATH)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?
+            .to_string();
+        let confirm_address = values.get_bool(CONFIRM_ADDRESS).unwrap_or(false);
+
+        let account = match LedgerSession::get(&construct_did.value()) {
+            Some(session)
+                if parse_derivation_path(&derivation_path).ok().as_ref()
+                    == Some(&session.account.derivation_path) =>
+            {
+                session.account
+            }
+            _ => {
+                let logger =
+                    LogDispatcher::new(construct_did.as_uuid(), "svm::ledger", progress_tx);
+                let message = match confirm_address {
+                    true => format!("Confirm the address of the Ledger account at derivation path {derivation_path} on the device"),
+                    false => format!("Connecting to the Ledger account at derivation path {derivation_path}"),
+                };
+                logger.pending_info("Connecting", message);
+                let account =
+                    LedgerAccount::connect(&derivation_path, confirm_address).map_err(|e| {
+                        logger.failure_info("Connection Failed", &e.message);
+                        (signers.clone(), signer_state.clone(), e)
+                    })?;
+                logger.success_info(
+                    "Connected",
+                    format!("Ledger account {} connected", account.pubkey),
+                );
+                Rc::new(account)
+            }
+        };
+
+        if let Some(expected_address) = values.get_string(EXPECTED_ADDRESS) {
+            if expected_address != account.pubkey.to_string() {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!(
+                        "the Ledger account at derivation path {} has address {}, but {} was expected",
+                        derivation_path,
+                        account.pubkey,
+                        expected_address
+                    ),
+                ));
+            }
+        }
+
+        let public_key = Value::string(account.pubkey.to_string());
+        signer_state.insert(CHECKED_PUBLIC_KEY, public_key.clone());
+        signer_state.insert(CHECKED_ADDRESS, public_key.clone());
+        LedgerSession::insert(
+            &construct_did.value(),
+            LedgerSession { account, progress_tx: progress_tx.clone() },
+        );
+
+        result.outputs.insert(ADDRESS.into(), public_key.clone());
+        result.outputs.insert(PUBLIC_KEY.into(), public_key);
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+
+    fn check_signability(
+        construct_did: &ConstructDid,
+        title: &str,
+        description: &Option<String>,
+        meta_description: &Option<String>,
+        markdown: &Option<String>,
+        payload: &Value,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        _auth_ctx: &AuthorizationContext,
+    ) -> Result<CheckSignabilityOk, SignerActionErr> {
+        signer_state.insert_scoped_value(
+            &construct_did.to_string(),
+            TRANSACTION_BYTES,
+            payload.clone(),
+        );
+
+        let actions = if supervision_context.review_input_values {
+            let construct_did_str = &construct_did.to_string();
+            if let Some(_) = signer_state.get_scoped_value(&construct_did_str, SIGNATURE_APPROVED) {
+                return Ok((signers, signer_state, Actions::none()));
+            }
+
+            let network_id = match values.get_expected_string(NETWORK_ID) {
+                Ok(value) => value,
+                Err(diag) => return Err((signers, signer_state, diag)),
+            };
+            let signable = signer_state
+                .get_scoped_value(&construct_did_str, IS_SIGNABLE)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(true);
+
+            let status = match signable {
+                true => ActionItemStatus::Todo,
+                false => ActionItemStatus::Blocked,
+            };
+            let skippable = signer_state
+                .get_scoped_value(&construct_did_str, SIGNATURE_SKIPPABLE)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let formatted_payload =
+                signer_state.get_scoped_value(&construct_did_str, FORMATTED_TRANSACTION);
+
+            // the device prompt only appears once the signature is approved here
+            let meta_description = format!(
+                "{}Once approved, review and confirm the transaction on your Ledger device.",
+                meta_description.as_ref().map(|d| format!("{d}\n")).unwrap_or_default()
+            );
+
+            let request = ProvideSignedTransactionRequest::new(
+                &signer_state.uuid,
+                &payload,
+                NAMESPACE,
+                &network_id,
+            )
+            .skippable(skippable)
+            .check_expectation_action_uuid(construct_did)
+            .formatted_payload(formatted_payload)
+            .only_approval_needed()
+            .to_action_type()
+            .to_request(title, ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION)
+            .with_construct_did(construct_did)
+            .with_some_description(description.clone())
+            .with_meta_description(&meta_description)
+            .with_some_markdown(markdown.clone())
+            .with_status(status);
+
+            Actions::append_item(
+                request,
+                Some("Review and sign the transactions from the list below"),
+                Some("Transaction Signing"),
+            )
+        } else {
+            Actions::none()
+        };
+        Ok((signers, signer_state, actions))
+    }
+
+    fn sign(
+        construct_did: &ConstructDid,
+        _title: &str,
+        payload: &Value,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    ) -> SignerSignFutureResult {
+        let mut result = CommandExecutionResult::new();
+
+        let session = LedgerSession::get(&signer_state.uuid).ok_or_else(|| {
+            (
+                signers.clone(),
+                signer_state.clone(),
+                diagnosed_error!(
+                    "the Ledger device is not connected: the signer was not activated"
+                ),
+            )
+        })?;
+
+        // off-chain messages are provided as raw strings or buffers, rather than as transactions
+        if payload.as_addon_data().is_none() {
+            let message = payload.to_be_bytes();
+            let signature = session
+                .confirm_on_device(construct_did, "message", |account| {
+                    account.sign_offchain_message(&message)
+                })
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+            result
+                .outputs
+                .insert(SIGNED_MESSAGE_BYTES.into(), Value::string(signature.to_string()));
+            return return_synchronous_result(Ok((signers, signer_state, result)));
+        }
+
+        // value signed (partially, maybe) by another signer
+        let previously_signed_blockhash = signer_state
+            .remove_scoped_value(&construct_did.to_string(), PREVIOUSLY_SIGNED_BLOCKHASH);
+
+        // prevent discrepancies between new block hash and a hash on the transaction that's already been signed
+        let blockhash = if let Some(blockhash) = &previously_signed_blockhash {
+            solana_hash::Hash::new_from_array(blockhash.to_be_bytes().try_into().unwrap())
+        } else {
+            let rpc_api_url = values
+                .get_expected_string(RPC_API_URL)
+                .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?
+                .to_string();
+
+            let commitment = match values.get_string(COMMITMENT_LEVEL).unwrap_or("processed") {
+                "finalized" => CommitmentLevel::Finalized,
+                "processed" => CommitmentLevel::Processed,
+                "confirmed" => CommitmentLevel::Confirmed,
+                _ => CommitmentLevel::Processed,
+            };
+            let rpc_client = RpcClient::new_with_commitment(
+                rpc_api_url.clone(),
+                CommitmentConfig { commitment },
+            );
+
+            rpc_client.get_latest_blockhash().map_err(|e| {
+                (
+                    signers.clone(),
+                    signer_state.clone(),
+                    diagnosed_error!("failed to get latest blockhash: {e}"),
+                )
+            })?
+        };
+
+        let is_deployment = values.get_bool(IS_DEPLOYMENT).unwrap_or(false);
+
+        let (mut transaction, do_sign_with_txtx_signer) = if is_deployment {
+            let deployment_transaction = DeploymentTransaction::from_value(&payload)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+            let mut transaction: Transaction =
+                deployment_transaction.transaction.as_ref().unwrap().clone();
+
+            transaction.message.recent_blockhash = blockhash;
+
+            let keypairs = deployment_transaction
+                .get_keypairs()
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+
+            transaction.try_partial_sign(&keypairs, transaction.message.recent_blockhash).map_err(
+                |e| {
+                    (
+                        signers.clone(),
+                        signer_state.clone(),
+                        diagnosed_error!("failed to sign transaction: {e}"),
+                    )
+                },
+            )?;
+
+            (transaction, deployment_transaction.signers.is_some())
+        } else {
+            let mut transaction: Transaction = build_transaction_from_svm_value(&payload)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+            transaction.message.recent_blockhash = blockhash;
+
+            (transaction, true)
+        };
+
+        if do_sign_with_txtx_signer {
+            session
+                .confirm_on_device(construct_did, "transaction", |account| {
+                    account.sign_transaction(&mut transaction)
+                })
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        }
+        result.outputs.insert(
+            PARTIALLY_SIGNED_TRANSACTION_BYTES.into(),
+            SvmValue::transaction(&transaction)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?,
+        );
+
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_explains_blind_signing_errors() {
+        let diag = ledger_diagnostic(RemoteWalletError::LedgerError(LedgerError::SdkNotSupported));
+        assert!(diag.message.contains("enable 'Blind signing'"));
+
+        let diag = ledger_diagnostic(RemoteWalletError::NoDeviceFound);
+        assert!(diag.message.contains("no Ledger device found"));
+
+        let diag = ledger_diagnostic(RemoteWalletError::LedgerError(LedgerError::UserCancel));
+        assert!(diag.message.contains("rejected on the Ledger device"));
+    }
+
+    #[test]
+    fn it_parses_derivation_paths() {
+        assert!(parse_derivation_path("m/44'/501'/0'/0'").is_ok());
+        assert!(parse_derivation_path("m/44'/501'/1'/0'").is_ok());
+        assert!(parse_derivation_path("not a path").is_err());
+    }
+
+    #[test]
+    fn it_derives_the_account_index_path() {
+        let mut values = ValueStore::tmp();
+        let derivation_path = get_derivation_path(&values, DERIVATION_PATH_TEMPLATE).unwrap();
+        assert_eq!(derivation_path, "m/44'/501'/0'/0'");
+
+        values.insert("account_index", Value::integer(2));
+        let derivation_path = get_derivation_path(&values, DERIVATION_PATH_TEMPLATE).unwrap();
+        assert_eq!(derivation_path, "m/44'/501'/2'/0'");
+        assert!(parse_derivation_path(&derivation_path).is_ok());
+    }
+}
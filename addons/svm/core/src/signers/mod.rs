@@ -1,8 +1,18 @@
+#[cfg(feature = "kms")]
+pub mod aws_kms;
+pub mod encrypted_keyfile;
+#[cfg(feature = "kms")]
+pub mod gcp_kms;
+#[cfg(feature = "kms")]
+pub mod kms;
+#[cfg(feature = "ledger")]
+pub mod ledger;
 pub mod secret_key;
 pub mod squads;
 pub mod web_wallet;
 
 use crate::functions::lamports_to_sol;
+use encrypted_keyfile::SVM_ENCRYPTED_KEYFILE;
 use secret_key::SVM_SECRET_KEY;
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_pubkey::Pubkey;
@@ -24,8 +34,19 @@ use crate::constants::{
 };
 
 lazy_static! {
-    pub static ref SIGNERS: Vec<SignerSpecification> =
-        vec![SVM_SECRET_KEY.clone(), SVM_WEB_WALLET.clone(), SVM_SQUADS.clone()];
+    pub static ref SIGNERS: Vec<SignerSpecification> = {
+        let mut signers = vec![
+            SVM_SECRET_KEY.clone(),
+            SVM_WEB_WALLET.clone(),
+            SVM_SQUADS.clone(),
+            SVM_ENCRYPTED_KEYFILE.clone(),
+        ];
+        #[cfg(feature = "kms")]
+        signers.extend([aws_kms::SVM_AWS_KMS.clone(), gcp_kms::SVM_GCP_KMS.clone()]);
+        #[cfg(feature = "ledger")]
+        signers.push(ledger::SVM_LEDGER.clone());
+        signers
+    };
 }
 
 pub async fn get_additional_actions_for_address(
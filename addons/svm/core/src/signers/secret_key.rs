@@ -3,17 +3,24 @@ use std::collections::HashMap;
 use solana_client::rpc_client::RpcClient;
 use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_keypair::{keypair_from_seed, Keypair};
+use solana_signer::Signer;
 use solana_transaction::Transaction;
 use txtx_addon_kit::channel;
-use txtx_addon_kit::constants::{SIGNATURE_APPROVED, SIGNATURE_SKIPPABLE};
+use txtx_addon_kit::constants::{
+    PARTIAL_SIGNATURE, PARTIAL_SIGNATURE_REQUESTED, SIGNATURE_APPROVED, SIGNATURE_SKIPPABLE,
+};
+use txtx_addon_kit::crypto::derivation::{
+    mnemonic_derivation_inputs, mnemonic_derivation_outputs, DERIVED_ADDRESSES,
+};
 use txtx_addon_kit::types::commands::CommandExecutionResult;
 use txtx_addon_kit::types::frontend::{
     ActionItemStatus, ProvideSignedTransactionRequest, ReviewInputRequest,
 };
 use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
 use txtx_addon_kit::types::signers::{
-    return_synchronous_result, CheckSignabilityOk, SignerActionErr, SignerActionsFutureResult,
-    SignerActivateFutureResult, SignerInstance, SignerSignFutureResult, SignersState,
+    return_synchronous_result, CheckSignabilityOk, PartialSignature, SignerActionErr,
+    SignerActionsFutureResult, SignerActivateFutureResult, SignerInstance, SignerSignFutureResult,
+    SignersState,
 };
 use txtx_addon_kit::types::signers::{SignerImplementation, SignerSpecification};
 use txtx_addon_kit::types::stores::ValueStore;
@@ -29,80 +36,77 @@ use txtx_addon_network_svm_types::SvmValue;
 use crate::codec::DeploymentTransaction;
 use crate::constants::{
     ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION, ADDRESS, CHECKED_ADDRESS,
-    CHECKED_PUBLIC_KEY, COMMITMENT_LEVEL, FORMATTED_TRANSACTION, IS_DEPLOYMENT, IS_SIGNABLE,
-    NAMESPACE, NETWORK_ID, PARTIALLY_SIGNED_TRANSACTION_BYTES, PREVIOUSLY_SIGNED_BLOCKHASH,
-    PUBLIC_KEY, RPC_API_URL, SECRET_KEY, TRANSACTION_BYTES,
+    CHECKED_PUBLIC_KEY, COMMITMENT_LEVEL, DERIVATION_PATH_TEMPLATE, FORMATTED_TRANSACTION,
+    IS_DEPLOYMENT, IS_SIGNABLE, NAMESPACE, NETWORK_ID, PARTIALLY_SIGNED_TRANSACTION_BYTES,
+    PREVIOUSLY_SIGNED_BLOCKHASH, PUBLIC_KEY, RPC_API_URL, SECRET_KEY, TRANSACTION_BYTES,
 };
 use crate::utils::build_transaction_from_svm_value;
 use txtx_addon_kit::types::signers::return_synchronous_actions;
 use txtx_addon_kit::types::types::RunbookSupervisionContext;
 
 lazy_static! {
-    pub static ref SVM_SECRET_KEY: SignerSpecification = define_signer! {
-        SvmSecretKey => {
-            name: "Secret Key Signer",
-            matcher: "secret_key",
-            documentation:txtx_addon_kit::indoc! {r#"The `svm::secret_key` signer can be used to synchronously sign a transaction."#},
-            inputs: [
-                secret_key: {
-                    documentation: "The secret key used to sign messages and transactions.",
-                    typing: Type::string(),
-                    optional: true,
-                    tainting: true,
-                    sensitive: true
-                },
-                mnemonic: {
-                    documentation: "The mnemonic phrase used to generate the secret key. This input will not be used if the `secret_key` input is provided.",
-                    typing: Type::string(),
-                    optional: true,
-                    tainting: true,
-                    sensitive: true
-                },
-                derivation_path: {
-                    documentation: "The derivation path used to generate the secret key. This input will not be used if the `secret_key` input is provided.",
-                    typing: Type::string(),
-                    optional: true,
-                    tainting: true,
-                    sensitive: true
-                },
-                keypair_json: {
-                    documentation: "A path to a keypair.json file containing the secret key. This input will not be used if the `secret_key` or `mnemonic` inputs are provided.",
-                    typing: Type::string(),
-                    optional: true,
-                    tainting: true,
-                    sensitive: true
-                },
-                is_encrypted: {
-                    documentation: "Coming soon",
-                    typing: Type::bool(),
-                    optional: true,
-                    tainting: true,
-                    sensitive: false
-                },
-                password: {
-                    documentation: "Coming soon",
-                    typing: Type::string(),
-                    optional: true,
-                    tainting: true,
-                    sensitive: true
-                }
-            ],
-            outputs: [
-                public_key: {
-                    documentation: "The public key of the account generated from the secret key, mnemonic, or keypair file.",
-                    typing: Type::string()
-                },
-                address: {
-                    documentation: "The SVM address generated from the secret key, mnemonic, or keypair file. This is an alias for the `public_key` output.",
-                    typing: Type::string()
-                }
-            ],
-            example: txtx_addon_kit::indoc! {r#"
-                signer "deployer" "svm::secret_key" {
-                    secret_key = input.secret_key
-                }
-            "#}
-        }
+    pub static ref SVM_SECRET_KEY: SignerSpecification = {
+        let mut spec = define_signer! {
+            SvmSecretKey => {
+                name: "Secret Key Signer",
+                matcher: "secret_key",
+                documentation:txtx_addon_kit::indoc! {r#"The `svm::secret_key` signer can be used to synchronously sign a transaction."#},
+                inputs: [
+                    secret_key: {
+                        documentation: "The secret key used to sign messages and transactions.",
+                        typing: Type::string(),
+                        optional: true,
+                        tainting: true,
+                        sensitive: true
+                    },
+                    mnemonic: {
+                        documentation: "The mnemonic phrase used to generate the secret key. This input will not be used if the `secret_key` input is provided.",
+                        typing: Type::string(),
+                        optional: true,
+                        tainting: true,
+                        sensitive: true
+                    },
+                    keypair_json: {
+                        documentation: "A path to a keypair.json file containing the secret key. This input will not be used if the `secret_key` or `mnemonic` inputs are provided.",
+                        typing: Type::string(),
+                        optional: true,
+                        tainting: true,
+                        sensitive: true
+                    },
+                    is_encrypted: {
+                        documentation: "Coming soon",
+                        typing: Type::bool(),
+                        optional: true,
+                        tainting: true,
+                        sensitive: false
+                    }
+                ],
+                outputs: [
+                    public_key: {
+                        documentation: "The public key of the account generated from the secret key, mnemonic, or keypair file.",
+                        typing: Type::string()
+                    },
+                    address: {
+                        documentation: "The SVM address generated from the secret key, mnemonic, or keypair file. This is an alias for the `public_key` output.",
+                        typing: Type::string()
+                    }
+                ],
+                example: txtx_addon_kit::indoc! {r#"
+                    signer "deployer" "svm::secret_key" {
+                        secret_key = input.secret_key
+                    }
+                    // or we can derive the secret key of the second account of a mnemonic
+                    signer "deployer" "svm::secret_key" {
+                        mnemonic = input.mnemonic
+                        account_index = 1
+                    }
+                "#},
+                finalize_partial_signatures: finalize_partial_signatures
+            }
+        };
+        spec.inputs.append(&mut mnemonic_derivation_inputs(DERIVATION_PATH_TEMPLATE));
+        spec.outputs.append(&mut mnemonic_derivation_outputs());
+        spec
     };
 }
 
@@ -132,11 +136,12 @@ impl SignerImplementation for SvmSecretKey {
         use std::path::PathBuf;
 
         use crate::constants::{
-            DEFAULT_DERIVATION_PATH, DERIVATION_PATH, IS_ENCRYPTED, KEYPAIR_JSON, MNEMONIC,
-            PASSWORD, REQUESTED_STARTUP_DATA, SECRET_KEY,
+            IS_ENCRYPTED, KEYPAIR_JSON, MNEMONIC, REQUESTED_STARTUP_DATA, SECRET_KEY,
         };
         use solana_keypair::Keypair;
-        use solana_signer::Signer;
+        use txtx_addon_kit::crypto::derivation::{
+            derive_addresses, get_derivation_path, get_passphrase,
+        };
         use txtx_addon_kit::{constants::DESCRIPTION, crypto::ed25519_secret_key_from_mnemonic};
         let mut actions = Actions::none();
 
@@ -195,15 +200,33 @@ impl SignerImplementation for SvmSecretKey {
                     keypair
                 }
                 Some(mnemonic) => {
-                    let derivation_path =
-                        values.get_string(DERIVATION_PATH).unwrap_or(DEFAULT_DERIVATION_PATH);
+                    let derivation_path = get_derivation_path(values, DERIVATION_PATH_TEMPLATE)
+                        .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
                     let is_encrypted = values.get_bool(IS_ENCRYPTED).unwrap_or(false);
-                    let password = values.get_string(PASSWORD);
+                    let passphrase = get_passphrase(values);
+
+                    let derived_addresses =
+                        derive_addresses(values, DERIVATION_PATH_TEMPLATE, |derivation_path| {
+                            let seed = ed25519_secret_key_from_mnemonic(
+                                mnemonic,
+                                derivation_path,
+                                is_encrypted,
+                                passphrase,
+                            )?;
+                            keypair_from_seed(&seed)
+                                .map(|keypair| keypair.pubkey().to_string())
+                                .map_err(|e| e.to_string())
+                        })
+                        .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+                    if let Some(derived_addresses) = derived_addresses {
+                        signer_state.insert(DERIVED_ADDRESSES, derived_addresses);
+                    }
+
                     ed25519_secret_key_from_mnemonic(
                         mnemonic,
-                        derivation_path,
+                        &derivation_path,
                         is_encrypted,
-                        password,
+                        passphrase,
                     )
                     .map_err(|e| {
                         (
@@ -280,6 +303,9 @@ impl SignerImplementation for SvmSecretKey {
         let address = signer_state.get_value(CHECKED_ADDRESS).unwrap();
         result.outputs.insert(ADDRESS.into(), address.clone());
         result.outputs.insert(PUBLIC_KEY.into(), public_key.clone());
+        if let Some(derived_addresses) = signer_state.get_value(DERIVED_ADDRESSES) {
+            result.outputs.insert(DERIVED_ADDRESSES.into(), derived_addresses.clone());
+        }
         return_synchronous_result(Ok((signers, signer_state, result)))
     }
 
@@ -386,112 +412,242 @@ impl SignerImplementation for SvmSecretKey {
         payload: &Value,
         _spec: &SignerSpecification,
         values: &ValueStore,
-        mut signer_state: ValueStore,
+        signer_state: ValueStore,
         signers: SignersState,
         _signers_instances: &HashMap<ConstructDid, SignerInstance>,
     ) -> SignerSignFutureResult {
-        let mut result = CommandExecutionResult::new();
-
         let secret_key_bytes = signer_state
             .get_expected_buffer_bytes(SECRET_KEY)
             .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
 
         let keypair = Keypair::try_from(secret_key_bytes.as_ref()).unwrap();
 
-        // value signed (partially, maybe) by another signer
-        let previously_signed_blockhash = signer_state
-            .remove_scoped_value(&construct_did.to_string(), PREVIOUSLY_SIGNED_BLOCKHASH);
-
-        // prevent discrepancies between new block hash and a hash on the transaction that's already been signed
-        let blockhash = if let Some(blockhash) = &previously_signed_blockhash {
-            solana_hash::Hash::new_from_array(blockhash.to_be_bytes().try_into().unwrap())
-        } else {
-            let rpc_api_url = values
-                .get_expected_string(RPC_API_URL)
-                .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?
-                .to_string();
-
-            let commitment = match values.get_string(COMMITMENT_LEVEL).unwrap_or("processed") {
-                "finalized" => CommitmentLevel::Finalized,
-                "processed" => CommitmentLevel::Processed,
-                "confirmed" => CommitmentLevel::Confirmed,
-                _ => CommitmentLevel::Processed,
-            };
-            let rpc_client = RpcClient::new_with_commitment(
-                rpc_api_url.clone(),
-                CommitmentConfig { commitment },
+        if signer_state
+            .get_scoped_bool(&construct_did.to_string(), PARTIAL_SIGNATURE_REQUESTED)
+            .unwrap_or(false)
+        {
+            return sign_partially_with_keypair(
+                keypair,
+                construct_did,
+                payload,
+                signer_state,
+                signers,
             );
+        }
+        sign_with_keypair(keypair, construct_did, payload, values, signer_state, signers)
+    }
+}
 
-            let blockhash = rpc_client.get_latest_blockhash().map_err(|e| {
-                (
-                    signers.clone(),
-                    signer_state.clone(),
-                    diagnosed_error!("failed to get latest blockhash: {e}"),
-                )
-            })?;
-            blockhash
+/// Signs the message of the transaction `payload` with `keypair`, on behalf of a threshold
+/// signer. The transaction is signed as is: all of the members of the threshold signer must sign
+/// the same message, so its blockhash is not refreshed.
+fn sign_partially_with_keypair(
+    keypair: Keypair,
+    construct_did: &ConstructDid,
+    payload: &Value,
+    mut signer_state: ValueStore,
+    signers: SignersState,
+) -> SignerSignFutureResult {
+    signer_state.remove_scoped_value(&construct_did.to_string(), PARTIAL_SIGNATURE_REQUESTED);
+
+    let transaction = build_transaction_from_svm_value(payload)
+        .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+    if transaction.message.recent_blockhash == solana_hash::Hash::default() {
+        return Err((
+            signers,
+            signer_state,
+            diagnosed_error!(
+                "threshold signing requires the transaction to have a recent blockhash"
+            ),
+        ));
+    }
+    let signature = keypair.sign_message(&transaction.message_data());
+
+    let partial_signature = PartialSignature::new(
+        &ConstructDid(signer_state.uuid.clone()),
+        SvmValue::pubkey(keypair.pubkey().to_bytes().to_vec()),
+        SvmValue::signature(signature.as_ref().to_vec()),
+    );
+    let mut result = CommandExecutionResult::new();
+    result.outputs.insert(PARTIAL_SIGNATURE.into(), partial_signature.to_value());
+    return_synchronous_result(Ok((signers, signer_state, result)))
+}
+
+/// Assembles the signatures collected by a threshold signer into the transaction `payload`.
+/// Every member must be one of the required signers of the transaction, and its signature must
+/// verify against the transaction message.
+pub fn finalize_partial_signatures(
+    _caller_uuid: &ConstructDid,
+    payload: &Value,
+    partial_signatures: &Vec<PartialSignature>,
+    _values: &ValueStore,
+) -> Result<CommandExecutionResult, Diagnostic> {
+    let mut transaction = build_transaction_from_svm_value(payload)?;
+    let message_data = transaction.message_data();
+    let num_required_signatures = transaction.message.header.num_required_signatures as usize;
+    let Some(required_signers) = transaction.message.account_keys.get(..num_required_signatures)
+    else {
+        return Err(diagnosed_error!(
+            "the transaction requires {} signatures, but only lists {} accounts",
+            num_required_signatures,
+            transaction.message.account_keys.len()
+        ));
+    };
+
+    for partial_signature in partial_signatures.iter() {
+        let public_key = SvmValue::to_pubkey(&partial_signature.public_key)
+            .map_err(|e| diagnosed_error!("invalid partial signature public key: {e}"))?;
+        let signature = SvmValue::to_signature(&partial_signature.signature)
+            .map_err(|e| diagnosed_error!("invalid partial signature: {e}"))?;
+        let Some(position) = required_signers.iter().position(|key| key.eq(&public_key)) else {
+            return Err(diagnosed_error!(
+                "threshold signer member {} is not a required signer of the transaction",
+                public_key
+            ));
         };
+        if !signature.verify(public_key.as_ref(), &message_data) {
+            return Err(diagnosed_error!(
+                "the signature of threshold signer member {} does not match the transaction",
+                public_key
+            ));
+        }
+        let Some(slot) = transaction.signatures.get_mut(position) else {
+            return Err(diagnosed_error!(
+                "the transaction has no signature slot for threshold signer member {}",
+                public_key
+            ));
+        };
+        *slot = signature;
+    }
 
-        let is_deployment = values.get_bool(IS_DEPLOYMENT).unwrap_or(false);
+    let mut result = CommandExecutionResult::new();
+    result
+        .outputs
+        .insert(PARTIALLY_SIGNED_TRANSACTION_BYTES.into(), SvmValue::transaction(&transaction)?);
+    Ok(result)
+}
 
-        let (mut transaction, do_sign_with_txtx_signer) = if is_deployment {
-            let deployment_transaction = DeploymentTransaction::from_value(&payload)
-                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+/// Signs the transaction `payload` with `keypair`, along with the deployment keypairs when the
+/// transaction is a deployment.
+pub fn sign_with_keypair(
+    keypair: Keypair,
+    construct_did: &ConstructDid,
+    payload: &Value,
+    values: &ValueStore,
+    mut signer_state: ValueStore,
+    signers: SignersState,
+) -> SignerSignFutureResult {
+    let mut result = CommandExecutionResult::new();
+
+    // value signed (partially, maybe) by another signer
+    let previously_signed_blockhash =
+        signer_state.remove_scoped_value(&construct_did.to_string(), PREVIOUSLY_SIGNED_BLOCKHASH);
+
+    // prevent discrepancies between new block hash and a hash on the transaction that's already been signed
+    let blockhash = if let Some(blockhash) = &previously_signed_blockhash {
+        solana_hash::Hash::new_from_array(blockhash.to_be_bytes().try_into().unwrap())
+    } else {
+        let rpc_api_url = values
+            .get_expected_string(RPC_API_URL)
+            .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?
+            .to_string();
+
+        let commitment = match values.get_string(COMMITMENT_LEVEL).unwrap_or("processed") {
+            "finalized" => CommitmentLevel::Finalized,
+            "processed" => CommitmentLevel::Processed,
+            "confirmed" => CommitmentLevel::Confirmed,
+            _ => CommitmentLevel::Processed,
+        };
+        let rpc_client =
+            RpcClient::new_with_commitment(rpc_api_url.clone(), CommitmentConfig { commitment });
+
+        let blockhash = rpc_client.get_latest_blockhash().map_err(|e| {
+            (
+                signers.clone(),
+                signer_state.clone(),
+                diagnosed_error!("failed to get latest blockhash: {e}"),
+            )
+        })?;
+        blockhash
+    };
 
-            let mut transaction: Transaction =
-                deployment_transaction.transaction.as_ref().unwrap().clone();
+    let is_deployment = values.get_bool(IS_DEPLOYMENT).unwrap_or(false);
 
-            transaction.message.recent_blockhash = blockhash;
+    let (mut transaction, do_sign_with_txtx_signer) = if is_deployment {
+        let deployment_transaction = DeploymentTransaction::from_value(&payload)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
 
-            let keypairs = deployment_transaction
-                .get_keypairs()
-                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let mut transaction: Transaction =
+            deployment_transaction.transaction.as_ref().unwrap().clone();
 
-            transaction.try_partial_sign(&keypairs, transaction.message.recent_blockhash).map_err(
-                |e| {
-                    (
-                        signers.clone(),
-                        signer_state.clone(),
-                        diagnosed_error!("failed to sign transaction: {e}"),
-                    )
-                },
-            )?;
+        transaction.message.recent_blockhash = blockhash;
 
-            (transaction, deployment_transaction.signers.is_some())
-        } else {
-            let mut transaction: Transaction = build_transaction_from_svm_value(&payload)
-                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
-            transaction.message.recent_blockhash = blockhash;
+        let keypairs = deployment_transaction
+            .get_keypairs()
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
 
-            (transaction, true)
-        };
+        transaction.try_partial_sign(&keypairs, transaction.message.recent_blockhash).map_err(
+            |e| {
+                (
+                    signers.clone(),
+                    signer_state.clone(),
+                    diagnosed_error!("failed to sign transaction: {e}"),
+                )
+            },
+        )?;
 
-        if do_sign_with_txtx_signer {
-            transaction
-                .try_partial_sign(&[keypair], transaction.message.recent_blockhash)
-                .map_err(|e| {
-                    (
-                        signers.clone(),
-                        signer_state.clone(),
-                        diagnosed_error!("failed to sign transaction: {e}"),
-                    )
-                })?;
-        }
-        result.outputs.insert(
-            PARTIALLY_SIGNED_TRANSACTION_BYTES.into(),
-            SvmValue::transaction(&transaction)
-                .map_err(|e| (signers.clone(), signer_state.clone(), e))?,
-        );
+        (transaction, deployment_transaction.signers.is_some())
+    } else {
+        let mut transaction: Transaction = build_transaction_from_svm_value(&payload)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        transaction.message.recent_blockhash = blockhash;
 
-        return_synchronous_result(Ok((signers, signer_state, result)))
+        (transaction, true)
+    };
+
+    if do_sign_with_txtx_signer {
+        transaction.try_partial_sign(&[keypair], transaction.message.recent_blockhash).map_err(
+            |e| {
+                (
+                    signers.clone(),
+                    signer_state.clone(),
+                    diagnosed_error!("failed to sign transaction: {e}"),
+                )
+            },
+        )?;
     }
+    result.outputs.insert(
+        PARTIALLY_SIGNED_TRANSACTION_BYTES.into(),
+        SvmValue::transaction(&transaction)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?,
+    );
+
+    return_synchronous_result(Ok((signers, signer_state, result)))
 }
 
 #[cfg(test)]
 mod tests {
-    use solana_keypair::keypair_from_seed;
+    use solana_hash::Hash;
+    use solana_instruction::{AccountMeta, Instruction};
+    use solana_keypair::{keypair_from_seed, Keypair};
+    use solana_message::Message;
+    use solana_pubkey::Pubkey;
+    use solana_signature::Signature;
     use solana_signer::Signer;
+    use solana_transaction::Transaction;
+    use txtx_addon_kit::constants::PARTIAL_SIGNATURE;
     use txtx_addon_kit::crypto::ed25519_secret_key_from_mnemonic;
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::types::diagnostics::Diagnostic;
+    use txtx_addon_kit::types::signers::{PartialSignature, SignersState};
+    use txtx_addon_kit::types::stores::ValueStore;
+    use txtx_addon_kit::types::types::Value;
+    use txtx_addon_kit::types::{ConstructDid, Did};
+    use txtx_addon_network_svm_types::SvmValue;
+
+    use super::{finalize_partial_signatures, sign_partially_with_keypair};
+    use crate::constants::PARTIALLY_SIGNED_TRANSACTION_BYTES;
+    use crate::utils::build_transaction_from_svm_value;
 
     // Standard BIP39 test mnemonic (12 words)
     const TEST_MNEMONIC: &str =
@@ -566,4 +722,125 @@ mod tests {
             "Mnemonic with password should produce different keypair"
         );
     }
+
+    fn threshold_transaction(members: &[Keypair]) -> Transaction {
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[],
+            members.iter().map(|k| AccountMeta::new_readonly(k.pubkey(), true)).collect(),
+        );
+        let message = Message::new_with_blockhash(
+            &[instruction],
+            Some(&members[0].pubkey()),
+            &Hash::new_from_array([7; 32]),
+        );
+        Transaction::new_unsigned(message)
+    }
+
+    fn sign_partially(member: &Keypair, payload: &Value) -> Result<PartialSignature, Diagnostic> {
+        let caller_uuid = ConstructDid(Did::from_components(vec!["sign".as_bytes()]));
+        let member_did = Did::from_components(vec![member.pubkey().as_ref()]);
+        let signer_state = ValueStore::new("member", &member_did);
+        let future = sign_partially_with_keypair(
+            member.insecure_clone(),
+            &caller_uuid,
+            payload,
+            signer_state,
+            SignersState::new(),
+        )
+        .map_err(|(_, _, diag)| diag)?;
+        let (_, _, result) = block_on(future).map_err(|(_, _, diag)| diag)?;
+        PartialSignature::from_value(result.outputs.get(PARTIAL_SIGNATURE).unwrap())
+    }
+
+    #[test]
+    fn test_threshold_members_sign_partially() {
+        let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+        let transaction = threshold_transaction(&members);
+        let payload = SvmValue::transaction(&transaction).unwrap();
+        let caller_uuid = ConstructDid(Did::from_components(vec!["sign".as_bytes()]));
+
+        let partial_signatures = members[1..]
+            .iter()
+            .map(|member| sign_partially(member, &payload).unwrap())
+            .collect::<Vec<_>>();
+        let result = finalize_partial_signatures(
+            &caller_uuid,
+            &payload,
+            &partial_signatures,
+            &ValueStore::tmp(),
+        )
+        .unwrap();
+        let signed = build_transaction_from_svm_value(
+            result.outputs.get(PARTIALLY_SIGNED_TRANSACTION_BYTES).unwrap(),
+        )
+        .unwrap();
+
+        let message_data = signed.message_data();
+        assert_eq!(signed.signatures[0], Signature::default());
+        for (i, member) in members.iter().enumerate().skip(1) {
+            assert!(signed.signatures[i].verify(member.pubkey().as_ref(), &message_data));
+        }
+    }
+
+    #[test]
+    fn test_threshold_finalization_rejects_foreign_signatures() {
+        let members = [Keypair::new(), Keypair::new()];
+        let payload = SvmValue::transaction(&threshold_transaction(&members)).unwrap();
+        let caller_uuid = ConstructDid(Did::from_components(vec!["sign".as_bytes()]));
+
+        let outsider = sign_partially(&Keypair::new(), &payload).unwrap();
+        let diag = finalize_partial_signatures(
+            &caller_uuid,
+            &payload,
+            &vec![outsider],
+            &ValueStore::tmp(),
+        )
+        .unwrap_err();
+        assert!(diag.message.contains("is not a required signer"));
+
+        let mut forged = sign_partially(&members[0], &payload).unwrap();
+        forged.signature = sign_partially(&members[1], &payload).unwrap().signature;
+        let diag =
+            finalize_partial_signatures(&caller_uuid, &payload, &vec![forged], &ValueStore::tmp())
+                .unwrap_err();
+        assert!(diag.message.contains("does not match the transaction"));
+
+        let mut unhashed = threshold_transaction(&members);
+        unhashed.message.recent_blockhash = Hash::default();
+        let diag =
+            sign_partially(&members[0], &SvmValue::transaction(&unhashed).unwrap()).unwrap_err();
+        assert!(diag.message.contains("recent blockhash"));
+    }
+
+    #[test]
+    fn test_threshold_finalization_rejects_malformed_transactions() {
+        let members = [Keypair::new(), Keypair::new()];
+        let transaction = threshold_transaction(&members);
+        let payload = SvmValue::transaction(&transaction).unwrap();
+        let caller_uuid = ConstructDid(Did::from_components(vec!["sign".as_bytes()]));
+        let partial_signatures = vec![sign_partially(&members[1], &payload).unwrap()];
+
+        let mut overstated = transaction.clone();
+        overstated.message.header.num_required_signatures = 10;
+        let diag = finalize_partial_signatures(
+            &caller_uuid,
+            &SvmValue::transaction(&overstated).unwrap(),
+            &partial_signatures,
+            &ValueStore::tmp(),
+        )
+        .unwrap_err();
+        assert!(diag.message.contains("requires 10 signatures"));
+
+        let mut truncated = transaction.clone();
+        truncated.signatures.truncate(1);
+        let diag = finalize_partial_signatures(
+            &caller_uuid,
+            &SvmValue::transaction(&truncated).unwrap(),
+            &partial_signatures,
+            &ValueStore::tmp(),
+        )
+        .unwrap_err();
+        assert!(diag.message.contains("no signature slot"));
+    }
 }
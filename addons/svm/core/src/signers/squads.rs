@@ -9,12 +9,13 @@ use txtx_addon_kit::constants::{
 };
 use txtx_addon_kit::types::commands::CommandExecutionResult;
 use txtx_addon_kit::types::frontend::{
-    ActionItemStatus, ReviewInputRequest, VerifyThirdPartySignatureRequest,
+    ActionItemStatus, DisplayMarkdownRequest, ReviewInputRequest, VerifyThirdPartySignatureRequest,
 };
 use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
 use txtx_addon_kit::types::signers::{
     return_synchronous_result, CheckSignabilityOk, SignerActionErr, SignerActionsFutureResult,
-    SignerActivateFutureResult, SignerInstance, SignerSignFutureResult, SignersState,
+    SignerActivateFutureResult, SignerCapabilities, SignerInstance, SignerSignFutureResult,
+    SignersState,
 };
 use txtx_addon_kit::types::signers::{SignerImplementation, SignerSpecification};
 use txtx_addon_kit::types::stores::ValueStore;
@@ -35,11 +36,11 @@ use crate::codec::squads::SquadsMultisig;
 use crate::codec::ui_encode::get_formatted_transaction_meta_description;
 use crate::commands::sign_transaction::{check_signed_executability, run_signed_execution};
 use crate::constants::{
-    ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_PROVIDE_SIGNED_SQUAD_TRANSACTION, ADDRESS,
-    CHECKED_ADDRESS, CHECKED_PUBLIC_KEY, FORMATTED_TRANSACTION, INITIATOR, IS_DEPLOYMENT,
-    IS_SIGNABLE, MULTISIG_ACCOUNT_ADDRESS, MULTISIG_ACCOUNT_PUBLIC_KEY, NAMESPACE, NETWORK_ID,
-    PAYER, PUBLIC_KEY, RPC_API_URL, SIGNATURE, SIGNERS, SQUADS_MULTISIG, TRANSACTION_BYTES,
-    VAULT_ADDRESS, VAULT_PUBLIC_KEY,
+    ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_PROVIDE_SIGNED_SQUAD_TRANSACTION,
+    ACTION_ITEM_SQUADS_INSTRUCTIONS, ADDRESS, CHECKED_ADDRESS, CHECKED_PUBLIC_KEY,
+    FORMATTED_TRANSACTION, INITIATOR, IS_DEPLOYMENT, IS_SIGNABLE, MULTISIG_ACCOUNT_ADDRESS,
+    MULTISIG_ACCOUNT_PUBLIC_KEY, NAMESPACE, NETWORK_ID, PAYER, PUBLIC_KEY, RPC_API_URL, SIGNATURE,
+    SIGNERS, SQUADS_MULTISIG, TRANSACTION_BYTES, VAULT_ADDRESS, VAULT_PUBLIC_KEY,
 };
 use crate::typing::SvmValue;
 use crate::utils::build_transaction_from_svm_value;
@@ -92,7 +93,7 @@ lazy_static! {
                 },
                 program_id: {
                     documentation: "The program ID of the Squad program. If omitted, the default program ID will be used.",
-                    typing: Type::addon(SVM_PUBKEY),
+                    typing: Type::one_of(vec![Type::string(), Type::addon(SVM_PUBKEY)]),
                     optional: true,
                     tainting: false,
                     sensitive: false
@@ -140,7 +141,14 @@ lazy_static! {
                     initiator = signer.initiator
                 }
             "#},
-            force_sequential_signing: true
+            force_sequential_signing: true,
+            capabilities: SignerCapabilities {
+                // transactions are proposed to the multisig, and approved by its members
+                sign_message: false,
+                sign_typed_data: false,
+                supports_offline: false,
+                ..SignerCapabilities::all()
+            }
         }
     };
     pub static ref SQUADS_DEPLOYMENT_ADDITIONAL_INFO_TITLE: String =
@@ -671,9 +679,23 @@ impl SignerImplementation for SvmSecretKey {
                 let formatted_payload =
                     signer_state.get_scoped_value(&construct_did_str, FORMATTED_TRANSACTION);
 
+                let proposal_url = multisig.vault_transaction_url(&construct_did);
+                let instructions = DisplayMarkdownRequest::new(&txtx_addon_kit::formatdoc! {r#"
+                    The transaction of **{}** was proposed to the Squads multisig `{}` (vault `{}`).
+
+                    1. Open the proposal in Squads.
+                    2. Gather the approvals of enough members to reach the threshold of the multisig.
+                    3. Execute the transaction, then check its signature status below."#,
+                    instance_name, multisig.multisig_pda, multisig.vault_pda
+                })
+                .with_link("Open the proposal in Squads", &proposal_url)
+                .to_action_type()
+                .to_request(instance_name, ACTION_ITEM_SQUADS_INSTRUCTIONS)
+                .with_construct_did(construct_did);
+
                 let request = VerifyThirdPartySignatureRequest::new(
                     &signer_state.uuid,
-                    &multisig.vault_transaction_url(&construct_did),
+                    &proposal_url,
                     &instance_name,
                     "Squads",
                     payload,
@@ -690,11 +712,14 @@ impl SignerImplementation for SvmSecretKey {
                 .with_some_markdown(markdown.clone())
                 .with_status(status);
 
-                let actions = Actions::append_item(
-                    request,
-                    Some("Review and sign the transactions from the list below"),
-                    Some("Transaction Signing"),
-                );
+                let mut actions = Actions::none();
+                for item in [instructions, request] {
+                    actions.append(&mut Actions::append_item(
+                        item,
+                        Some("Review and sign the transactions from the list below"),
+                        Some("Transaction Signing"),
+                    ));
+                }
                 return Ok((signers, signer_state, actions));
             }
             // Step 3: When the ThirdPartySignatureStatus is Submitted, we just need to maintain that the VerifyThirdPartySignature action
@@ -8,12 +8,13 @@ use txtx_addon_kit::channel;
 use txtx_addon_kit::constants::{SIGNATURE_SKIPPABLE, SIGNED_TRANSACTION_BYTES};
 use txtx_addon_kit::types::commands::CommandExecutionResult;
 use txtx_addon_kit::types::frontend::{
-    ActionItemRequestUpdate, ActionItemStatus, Actions, BlockEvent, ProvideSignedTransactionRequest,
+    ActionItemRequestUpdate, ActionItemStatus, Actions, BlockEvent, DisplayMarkdownRequest,
+    ProvideSignedTransactionRequest,
 };
 use txtx_addon_kit::types::signers::{
     return_synchronous_result, CheckSignabilityOk, SignerActionErr, SignerActionsFutureResult,
-    SignerActivateFutureResult, SignerImplementation, SignerInstance, SignerSignFutureResult,
-    SignerSpecification, SignersState,
+    SignerActivateFutureResult, SignerCapabilities, SignerImplementation, SignerInstance,
+    SignerSignFutureResult, SignerSpecification, SignersState,
 };
 use txtx_addon_kit::types::stores::ValueStore;
 use txtx_addon_kit::types::types::RunbookSupervisionContext;
@@ -27,10 +28,10 @@ use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
 use crate::codec::{transaction_is_fully_signed, DeploymentTransaction};
 use crate::constants::{
     ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_PROVIDE_PUBLIC_KEY,
-    ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION, ADDRESS, CHECKED_ADDRESS, CHECKED_PUBLIC_KEY,
-    EXPECTED_ADDRESS, FORMATTED_TRANSACTION, IS_DEPLOYMENT, IS_SIGNABLE, NAMESPACE, NETWORK_ID,
-    PARTIALLY_SIGNED_TRANSACTION_BYTES, PUBLIC_KEY, REQUESTED_STARTUP_DATA, RPC_API_URL,
-    TRANSACTION_BYTES, UPDATED_PARTIALLY_SIGNED_TRANSACTION,
+    ACTION_ITEM_PROVIDE_SIGNED_TRANSACTION, ACTION_ITEM_WALLET_INSTRUCTIONS, ADDRESS,
+    CHECKED_ADDRESS, CHECKED_PUBLIC_KEY, EXPECTED_ADDRESS, FORMATTED_TRANSACTION, IS_DEPLOYMENT,
+    IS_SIGNABLE, NAMESPACE, NETWORK_ID, PARTIALLY_SIGNED_TRANSACTION_BYTES, PUBLIC_KEY,
+    REQUESTED_STARTUP_DATA, RPC_API_URL, TRANSACTION_BYTES, UPDATED_PARTIALLY_SIGNED_TRANSACTION,
 };
 use crate::typing::SvmValue;
 use crate::utils::build_transaction_from_svm_value;
@@ -67,7 +68,11 @@ lazy_static! {
                     signer "alice" "svm::web_wallet" {
                         expected_address = "zbBjhHwuqyKMmz8ber5oUtJJ3ZV4B6ePmANfGyKzVGV"
                     }
-                "#}
+                "#},
+                capabilities: SignerCapabilities {
+                    sign_transaction: true,
+                    ..SignerCapabilities::none()
+                }
             }
         };
         signer.requires_interaction = true;
@@ -218,10 +223,29 @@ impl SignerImplementation for SvmWebWallet {
             .await;
             signer_state.insert(&REQUESTED_STARTUP_DATA, Value::bool(true));
 
-            let action_items = match res {
+            let mut action_items = match res {
                 Ok(action_items) => action_items,
                 Err(diag) => return Err((signers, signer_state, diag)),
             };
+            if do_request_public_key && !action_items.is_empty() {
+                let mut instructions = format!(
+                    "Signer **{}** signs with a browser wallet: connect it from the supervisor web console to share its public key.",
+                    instance_name
+                );
+                if let Some(expected_address) = &expected_address {
+                    instructions.push_str(&format!(
+                        "\n\nThe wallet connected must use the address `{}`.",
+                        expected_address
+                    ));
+                }
+                action_items.insert(
+                    0,
+                    DisplayMarkdownRequest::new(&instructions)
+                        .to_action_type()
+                        .to_request(&instance_name, ACTION_ITEM_WALLET_INSTRUCTIONS)
+                        .with_construct_did(&signer_did),
+                );
+            }
             if !action_items.is_empty() {
                 actions.push_group(
                     "Review and check the following signer related action items",
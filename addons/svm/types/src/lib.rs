@@ -5,6 +5,10 @@ extern crate lazy_static;
 extern crate txtx_addon_kit;
 
 pub mod idl;
+pub mod subgraph;
+
+#[cfg(test)]
+mod tests;
 
 use std::str::FromStr;
 
@@ -228,6 +232,14 @@ impl SvmValue {
         }
     }
 
+    /// Formats svm values for display: pubkeys and signatures in base58, and numbers in decimal.
+    pub fn format_display(value: &Value) -> Option<String> {
+        match Self::to_json(value).ok().flatten()? {
+            serde_json::Value::String(display) => Some(display),
+            json => Some(json.to_string()),
+        }
+    }
+
     pub fn u8(value: u8) -> Value {
         Value::addon(value.to_le_bytes().to_vec(), SVM_U8)
     }
@@ -624,6 +636,30 @@ lazy_static! {
         }
     };
 
+    pub static ref INSTRUCTION_SUBGRAPH: Type = define_strict_map_type! {
+        name: {
+            documentation: "The name of the instruction, as indexed by the IDL, whose invocations should be added to the subgraph.",
+            typing: Type::string(),
+            optional: false,
+            tainting: true
+        },
+        field: {
+            documentation: "A map of fields to index. Each field is mapped to one of the instruction's IDL arguments.",
+            typing: SUBGRAPH_DEFINED_FIELD.clone(),
+            optional: false,
+            tainting: true
+        },
+        intrinsic_fields: {
+            documentation: indoc!{r#"A map of intrinsic fields to index. For Instruction subgraphs, intrinsics are:
+                - `slot`(indexed): The slot in which the instruction was invoked.
+                - `transactionSignature`(indexed): The transaction signature in which the instruction was invoked.
+                - `signers`(not indexed): The public keys of the accounts that signed the instruction."#},
+            typing: Type::array(SUBGRAPH_INTRINSIC_FIELD.clone()),
+            optional: true,
+            tainting: true
+        }
+    };
+
     pub static ref PDA_ACCOUNT_SUBGRAPH: Type = define_strict_map_type! {
         type: {
             documentation: "The type field of the account, as indexed by the IDL. This type definition will be used to parse the PDA account data.",
@@ -752,7 +788,7 @@ lazy_static! {
             tainting: false
         },
         idl_key: {
-            documentation: "A key from the event's type in the IDL, indicating which argument from the IDL type to map to this field. By default, the field name is used.",
+            documentation: "A key from the event's type (or the instruction's arguments) in the IDL, indicating which argument from the IDL type to map to this field. By default, the field name is used.",
             typing: Type::string(),
             optional: true,
             tainting: true
@@ -1023,6 +1059,7 @@ pub enum DeploymentTransactionType {
     ExtendProgram,
     ResizeBuffer,
     WriteToBuffer { is_upgrade: bool },
+    ResumeWriteToBuffer { buffer_pubkey: Pubkey, resume_offset: u32, is_upgrade: bool },
     TransferBufferAuthority,
     TransferProgramAuthority,
     DeployProgram,
@@ -1044,6 +1081,7 @@ impl DeploymentTransactionType {
             DeploymentTransactionType::ResizeBuffer => "resize_buffer",
             DeploymentTransactionType::ExtendProgram => "extend_program",
             DeploymentTransactionType::WriteToBuffer { .. } => "write_to_buffer",
+            DeploymentTransactionType::ResumeWriteToBuffer { .. } => "resume_write_to_buffer",
             DeploymentTransactionType::TransferBufferAuthority => "transfer_buffer_authority",
             DeploymentTransactionType::TransferProgramAuthority => "transfer_program_authority",
             DeploymentTransactionType::DeployProgram => "deploy_program",
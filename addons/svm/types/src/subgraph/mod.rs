@@ -0,0 +1,444 @@
+use std::collections::HashSet;
+
+use anchor_lang_idl::types::{Idl, IdlInstruction, IdlType};
+use serde::{Deserialize, Serialize};
+use txtx_addon_kit::{
+    indexmap::IndexMap,
+    types::{
+        diagnostics::Diagnostic,
+        types::{ObjectType, Value},
+    },
+};
+
+pub const SUBGRAPH_NAME: &str = "name";
+pub const SUBGRAPH_FIELD: &str = "field";
+pub const SUBGRAPH_INTRINSIC_FIELDS: &str = "intrinsic_fields";
+pub const SUBGRAPH_IDL_KEY: &str = "idl_key";
+pub const SUBGRAPH_DISPLAY_NAME: &str = "display_name";
+pub const SUBGRAPH_DESCRIPTION: &str = "description";
+pub const SUBGRAPH_INDEXED: &str = "indexed";
+
+/// The intrinsic fields available to instruction subgraphs, along with whether
+/// they are indexed by default.
+pub const INSTRUCTION_INTRINSIC_FIELDS: [(&str, bool); 3] =
+    [("slot", true), ("transactionSignature", true), ("signers", false)];
+
+/// A field of a subgraph whose value is sourced from the program's IDL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexedSubgraphField {
+    /// The name of the field as it will appear in the subgraph schema.
+    pub display_name: String,
+    /// The name of the IDL field/argument the value is read from.
+    pub source_key: String,
+    /// The IDL type of the source field.
+    pub expected_type: IdlType,
+    pub description: Option<String>,
+    pub is_indexed: bool,
+}
+
+/// A field of a subgraph whose value is intrinsic to the indexed data source (slot, signature, etc.).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntrinsicSubgraphField {
+    pub display_name: String,
+    pub source_key: String,
+    pub description: Option<String>,
+    pub is_indexed: bool,
+}
+
+/// The data source of a subgraph, as sent to the subgraph registration service.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "camelCase")]
+pub enum SubgraphSourceType {
+    Instruction(InstructionSubgraphSource),
+}
+
+impl SubgraphSourceType {
+    pub fn to_json(&self) -> Result<serde_json::Value, Diagnostic> {
+        serde_json::to_value(self)
+            .map_err(|e| diagnosed_error!("failed to serialize subgraph source: {e}"))
+    }
+}
+
+/// Indexes the arguments of every invocation of an instruction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstructionSubgraphSource {
+    pub instruction: IdlInstruction,
+    pub defined_fields: Vec<IndexedSubgraphField>,
+    pub intrinsic_fields: Vec<IntrinsicSubgraphField>,
+}
+
+impl InstructionSubgraphSource {
+    /// Builds an instruction subgraph source from a value matching the `INSTRUCTION_SUBGRAPH` type,
+    /// validating that the instruction and every referenced argument exist in the IDL.
+    /// All violations are reported together.
+    pub fn from_value(value: &Value, idl: &Idl) -> Result<Self, Vec<Diagnostic>> {
+        let entry = match value {
+            Value::Array(entries) => {
+                let mut entries = entries.iter();
+                let Some(entry) = entries.next() else {
+                    return Err(vec![diagnosed_error!(
+                        "an instruction subgraph definition is required"
+                    )]);
+                };
+                if entries.next().is_some() {
+                    return Err(vec![diagnosed_error!(
+                        "only one instruction can be indexed per subgraph"
+                    )]);
+                }
+                entry
+            }
+            other => other,
+        };
+        let entry = entry
+            .as_object()
+            .ok_or(vec![diagnosed_error!("instruction subgraph definition must be a map")])?;
+
+        let instruction_name = entry
+            .get(SUBGRAPH_NAME)
+            .and_then(|v| v.as_string())
+            .ok_or(vec![diagnosed_error!("instruction subgraph definition is missing 'name'")])?;
+
+        let instruction =
+            idl.instructions.iter().find(|i| i.name == instruction_name).ok_or_else(|| {
+                vec![diagnosed_error!(
+                    "instruction '{}' not found in program IDL",
+                    instruction_name
+                )]
+            })?;
+
+        let mut diags = vec![];
+        let defined_fields = match entry.get(SUBGRAPH_FIELD) {
+            Some(fields) => parse_defined_fields(fields, instruction, &mut diags),
+            None => vec![],
+        };
+
+        let intrinsic_fields = match entry.get(SUBGRAPH_INTRINSIC_FIELDS) {
+            Some(fields) => {
+                parse_intrinsic_fields(fields, &INSTRUCTION_INTRINSIC_FIELDS, &mut diags)
+            }
+            None => default_intrinsic_fields(&INSTRUCTION_INTRINSIC_FIELDS),
+        };
+
+        let mut display_names = HashSet::new();
+        for display_name in defined_fields
+            .iter()
+            .map(|f| &f.display_name)
+            .chain(intrinsic_fields.iter().map(|f| &f.display_name))
+        {
+            if !display_names.insert(display_name) {
+                diags.push(diagnosed_error!(
+                    "duplicate subgraph field name '{}'; each field must have a unique display name",
+                    display_name
+                ));
+            }
+        }
+
+        if !diags.is_empty() {
+            return Err(diags);
+        }
+        Ok(Self { instruction: instruction.clone(), defined_fields, intrinsic_fields })
+    }
+
+    /// Renders the schema of the subgraph that would be registered for this source, without
+    /// contacting the subgraph service.
+    pub fn schema_preview(
+        &self,
+        entity_name: Option<&str>,
+    ) -> Result<SubgraphSchemaPreview, Vec<Diagnostic>> {
+        let mut diags = vec![];
+        let mut fields = vec![];
+        for field in self.defined_fields.iter() {
+            match idl_type_to_graphql_type(&field.expected_type) {
+                Ok(graphql_type) => fields.push(SchemaPreviewField {
+                    name: field.display_name.clone(),
+                    graphql_type,
+                    is_indexed: field.is_indexed,
+                    source: format!("idl:{}", field.source_key),
+                    description: field.description.clone(),
+                }),
+                Err(e) => diags.push(diagnosed_error!(
+                    "subgraph field '{}' cannot be indexed: {}",
+                    field.display_name,
+                    e
+                )),
+            }
+        }
+        for field in self.intrinsic_fields.iter() {
+            fields.push(SchemaPreviewField {
+                name: field.display_name.clone(),
+                graphql_type: intrinsic_graphql_type(&field.source_key).to_string(),
+                is_indexed: field.is_indexed,
+                source: format!("intrinsic:{}", field.source_key),
+                description: field.description.clone(),
+            });
+        }
+        if !diags.is_empty() {
+            return Err(diags);
+        }
+        let entity_name = entity_name
+            .map(|n| n.to_string())
+            .unwrap_or_else(|| to_pascal_case(&self.instruction.name));
+        Ok(SubgraphSchemaPreview { entity_name, fields })
+    }
+}
+
+/// The GraphQL schema a subgraph would expose once registered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubgraphSchemaPreview {
+    pub entity_name: String,
+    pub fields: Vec<SchemaPreviewField>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaPreviewField {
+    pub name: String,
+    pub graphql_type: String,
+    pub is_indexed: bool,
+    /// Where the field's value comes from, e.g. `idl:amount` or `intrinsic:slot`.
+    pub source: String,
+    pub description: Option<String>,
+}
+
+impl SubgraphSchemaPreview {
+    pub fn to_graphql_string(&self) -> String {
+        let mut schema = format!("type {} {{\n", self.entity_name);
+        for field in self.fields.iter() {
+            if let Some(description) = &field.description {
+                schema.push_str(&format!("  \"{}\"\n", description.replace('"', "\\\"")));
+            }
+            schema.push_str(&format!(
+                "  {}: {}{}\n",
+                field.name,
+                field.graphql_type,
+                if field.is_indexed { " @indexed" } else { "" }
+            ));
+        }
+        schema.push('}');
+        schema
+    }
+
+    pub fn to_value(&self) -> Value {
+        ObjectType::from(vec![
+            ("entity_name", Value::string(self.entity_name.clone())),
+            (
+                "fields",
+                Value::array(
+                    self.fields
+                        .iter()
+                        .map(|f| {
+                            ObjectType::from(vec![
+                                ("name", Value::string(f.name.clone())),
+                                ("type", Value::string(f.graphql_type.clone())),
+                                ("indexed", Value::bool(f.is_indexed)),
+                                ("source", Value::string(f.source.clone())),
+                                (
+                                    "description",
+                                    f.description
+                                        .as_ref()
+                                        .map(|d| Value::string(d.clone()))
+                                        .unwrap_or(Value::null()),
+                                ),
+                            ])
+                            .to_value()
+                        })
+                        .collect(),
+                ),
+            ),
+        ])
+        .to_value()
+    }
+}
+
+/// Maps an IDL type to the GraphQL type used for it in subgraph schemas.
+/// Non-optional types are marked non-null.
+pub fn idl_type_to_graphql_type(idl_type: &IdlType) -> Result<String, String> {
+    fn inner(idl_type: &IdlType) -> Result<String, String> {
+        let ty = match idl_type {
+            IdlType::Bool => "Boolean!".to_string(),
+            IdlType::U8
+            | IdlType::I8
+            | IdlType::U16
+            | IdlType::I16
+            | IdlType::U32
+            | IdlType::I32 => "Int!".to_string(),
+            IdlType::U64
+            | IdlType::I64
+            | IdlType::U128
+            | IdlType::I128
+            | IdlType::U256
+            | IdlType::I256 => "BigInt!".to_string(),
+            IdlType::F32 | IdlType::F64 => "Float!".to_string(),
+            IdlType::String => "String!".to_string(),
+            IdlType::Bytes => "Bytes!".to_string(),
+            IdlType::Pubkey => "PublicKey!".to_string(),
+            IdlType::Option(inner_type) => {
+                let inner_type = inner(inner_type)?;
+                inner_type.trim_end_matches('!').to_string()
+            }
+            IdlType::Vec(inner_type) | IdlType::Array(inner_type, _) => {
+                format!("[{}]!", inner(inner_type)?)
+            }
+            IdlType::Defined { .. } => "JSON!".to_string(),
+            other => return Err(format!("unsupported IDL type {:?}", other)),
+        };
+        Ok(ty)
+    }
+    inner(idl_type)
+}
+
+fn intrinsic_graphql_type(intrinsic: &str) -> &'static str {
+    match intrinsic {
+        "slot" => "BigInt!",
+        "signers" => "[PublicKey!]!",
+        _ => "String!",
+    }
+}
+
+fn to_pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn parse_defined_fields(
+    value: &Value,
+    instruction: &IdlInstruction,
+    diags: &mut Vec<Diagnostic>,
+) -> Vec<IndexedSubgraphField> {
+    let mut fields = vec![];
+    let entries = match map_entries(value, SUBGRAPH_FIELD) {
+        Ok(entries) => entries,
+        Err(e) => {
+            diags.push(e);
+            return fields;
+        }
+    };
+    for field in entries {
+        let Some(display_name) = field.get(SUBGRAPH_NAME).and_then(|v| v.as_string()) else {
+            diags.push(diagnosed_error!("subgraph field is missing 'name'"));
+            continue;
+        };
+        let source_key =
+            field.get(SUBGRAPH_IDL_KEY).and_then(|v| v.as_string()).unwrap_or(display_name);
+
+        let Some(arg) = instruction.args.iter().find(|a| a.name == source_key) else {
+            diags.push(diagnosed_error!(
+                "subgraph field '{}' references argument '{}', which does not exist in instruction '{}'; available arguments: {}",
+                display_name,
+                source_key,
+                instruction.name,
+                instruction.args.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ")
+            ));
+            continue;
+        };
+
+        if let Err(e) = idl_type_to_graphql_type(&arg.ty) {
+            diags.push(diagnosed_error!(
+                "subgraph field '{}' cannot be indexed: {}",
+                display_name,
+                e
+            ));
+            continue;
+        }
+
+        fields.push(IndexedSubgraphField {
+            display_name: display_name.to_string(),
+            source_key: source_key.to_string(),
+            expected_type: arg.ty.clone(),
+            description: field
+                .get(SUBGRAPH_DESCRIPTION)
+                .and_then(|v| v.as_string())
+                .map(|s| s.to_string())
+                .or_else(|| (!arg.docs.is_empty()).then(|| arg.docs.join(" "))),
+            is_indexed: field.get(SUBGRAPH_INDEXED).and_then(|v| v.as_bool()).unwrap_or(false),
+        });
+    }
+    fields
+}
+
+fn parse_intrinsic_fields(
+    value: &Value,
+    available: &[(&str, bool)],
+    diags: &mut Vec<Diagnostic>,
+) -> Vec<IntrinsicSubgraphField> {
+    let mut fields = vec![];
+    let entries = match map_entries(value, SUBGRAPH_INTRINSIC_FIELDS) {
+        Ok(entries) => entries,
+        Err(e) => {
+            diags.push(e);
+            return fields;
+        }
+    };
+    for field in entries {
+        let Some(name) = field.get(SUBGRAPH_NAME).and_then(|v| v.as_string()) else {
+            diags.push(diagnosed_error!("subgraph intrinsic field is missing 'name'"));
+            continue;
+        };
+        let Some((_, indexed_by_default)) = available.iter().find(|(n, _)| *n == name) else {
+            diags.push(diagnosed_error!(
+                "invalid intrinsic field '{}'; available intrinsic fields: {}",
+                name,
+                available.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ")
+            ));
+            continue;
+        };
+        fields.push(IntrinsicSubgraphField {
+            display_name: field
+                .get(SUBGRAPH_DISPLAY_NAME)
+                .and_then(|v| v.as_string())
+                .unwrap_or(name)
+                .to_string(),
+            source_key: name.to_string(),
+            description: field
+                .get(SUBGRAPH_DESCRIPTION)
+                .and_then(|v| v.as_string())
+                .map(|s| s.to_string()),
+            is_indexed: field
+                .get(SUBGRAPH_INDEXED)
+                .and_then(|v| v.as_bool())
+                .unwrap_or(*indexed_by_default),
+        });
+    }
+    fields
+}
+
+fn default_intrinsic_fields(available: &[(&str, bool)]) -> Vec<IntrinsicSubgraphField> {
+    available
+        .iter()
+        .map(|(name, is_indexed)| IntrinsicSubgraphField {
+            display_name: name.to_string(),
+            source_key: name.to_string(),
+            description: None,
+            is_indexed: *is_indexed,
+        })
+        .collect()
+}
+
+fn map_entries<'a>(
+    value: &'a Value,
+    key: &str,
+) -> Result<Vec<&'a IndexMap<String, Value>>, Diagnostic> {
+    match value {
+        Value::Array(entries) => entries
+            .iter()
+            .map(|e| e.as_object().ok_or(diagnosed_error!("each '{}' entry must be a map", key)))
+            .collect(),
+        Value::Object(entry) => Ok(vec![entry]),
+        _ => Err(diagnosed_error!("'{}' must be a map", key)),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+use anchor_lang_idl::types::{Idl, IdlType};
+use txtx_addon_kit::types::types::{ObjectType, Value};
+
+use super::{idl_type_to_graphql_type, InstructionSubgraphSource, SubgraphSourceType};
+
+lazy_static! {
+    pub static ref IDL: Idl =
+        serde_json::from_slice(&include_bytes!("../idl/fixtures/idl.json").to_vec()).unwrap();
+}
+
+fn field(name: &str, idl_key: Option<&str>, indexed: bool) -> Value {
+    let mut field = ObjectType::from(vec![
+        ("name", Value::string(name.to_string())),
+        ("indexed", Value::bool(indexed)),
+    ]);
+    if let Some(idl_key) = idl_key {
+        field.insert("idl_key", Value::string(idl_key.to_string()));
+    }
+    field.to_value()
+}
+
+fn instruction_subgraph(name: &str, fields: Vec<Value>) -> Value {
+    Value::array(vec![ObjectType::from(vec![
+        ("name", Value::string(name.to_string())),
+        ("field", Value::array(fields)),
+    ])
+    .to_value()])
+}
+
+#[test]
+fn it_builds_instruction_subgraph_source() {
+    let value = instruction_subgraph(
+        "split_token_transfer",
+        vec![field("amount", None, true), field("recipient_amount", Some("u64"), false)],
+    );
+    let source = InstructionSubgraphSource::from_value(&value, &IDL).unwrap();
+    assert_eq!(source.instruction.name, "split_token_transfer");
+    assert_eq!(source.defined_fields.len(), 2);
+    assert_eq!(source.defined_fields[1].display_name, "recipient_amount");
+    assert_eq!(source.defined_fields[1].source_key, "u64");
+    assert!(source.defined_fields[0].is_indexed);
+    assert_eq!(
+        source.intrinsic_fields.iter().map(|f| f.source_key.as_str()).collect::<Vec<_>>(),
+        vec!["slot", "transactionSignature", "signers"]
+    );
+}
+
+#[test]
+fn it_rejects_unknown_instruction() {
+    let value = instruction_subgraph("place_order", vec![field("price", None, false)]);
+    let err = InstructionSubgraphSource::from_value(&value, &IDL).unwrap_err();
+    assert!(err[0].message.contains("instruction 'place_order' not found"));
+}
+
+#[test]
+fn it_rejects_unknown_instruction_argument() {
+    let value = instruction_subgraph("split_token_transfer", vec![field("price", None, false)]);
+    let err = InstructionSubgraphSource::from_value(&value, &IDL).unwrap_err();
+    assert!(err[0].message.contains("argument 'price', which does not exist"));
+}
+
+#[test]
+fn it_rejects_unknown_intrinsic_field() {
+    let value = Value::array(vec![ObjectType::from(vec![
+        ("name", Value::string("split_token_transfer".into())),
+        ("field", Value::array(vec![field("amount", None, false)])),
+        (
+            "intrinsic_fields",
+            Value::array(vec![ObjectType::from(vec![
+                ("name", Value::string("pubkey".into())),
+                ("display_name", Value::string("pubkey".into())),
+                ("indexed", Value::bool(true)),
+            ])
+            .to_value()]),
+        ),
+    ])
+    .to_value()]);
+    let err = InstructionSubgraphSource::from_value(&value, &IDL).unwrap_err();
+    assert!(err[0].message.contains("invalid intrinsic field 'pubkey'"));
+}
+
+#[test]
+fn it_serializes_instruction_variant() {
+    let value = instruction_subgraph("split_token_transfer", vec![field("amount", None, true)]);
+    let source = InstructionSubgraphSource::from_value(&value, &IDL).unwrap();
+    let json = SubgraphSourceType::Instruction(source).to_json().unwrap();
+    assert_eq!(json["type"], "instruction");
+    assert_eq!(json["data"]["instruction"]["name"], "split_token_transfer");
+    assert_eq!(json["data"]["definedFields"][0]["sourceKey"], "amount");
+}
+
+#[test]
+fn it_reports_all_violations_together() {
+    let value = instruction_subgraph(
+        "split_token_transfer",
+        vec![
+            field("price", None, false),
+            field("amount", None, false),
+            field("amount", Some("u64"), false),
+            field("size", Some("unknown"), false),
+        ],
+    );
+    let errs = InstructionSubgraphSource::from_value(&value, &IDL).unwrap_err();
+    assert_eq!(errs.len(), 3);
+    assert!(errs[0].message.contains("argument 'price'"));
+    assert!(errs[1].message.contains("argument 'unknown'"));
+    assert!(errs[2].message.contains("duplicate subgraph field name 'amount'"));
+}
+
+#[test]
+fn it_renders_schema_preview() {
+    let value = instruction_subgraph(
+        "split_token_transfer",
+        vec![field("amount", None, true), field("memo", Some("string"), false)],
+    );
+    let source = InstructionSubgraphSource::from_value(&value, &IDL).unwrap();
+    let preview = source.schema_preview(None).unwrap();
+    assert_eq!(preview.entity_name, "SplitTokenTransfer");
+    let schema = preview.to_graphql_string();
+    assert!(schema.starts_with("type SplitTokenTransfer {"));
+    assert!(schema.contains("  amount: BigInt! @indexed\n"));
+    assert!(schema.contains("  memo: String!\n"));
+    assert!(schema.contains("  slot: BigInt! @indexed\n"));
+    assert!(schema.contains("  signers: [PublicKey!]!\n"));
+
+    let value = preview.to_value();
+    let fields = value.expect_object().get("fields").unwrap().expect_array();
+    assert_eq!(fields.len(), 5);
+    assert_eq!(fields[0].expect_object().get("source").unwrap().expect_string(), "idl:amount");
+}
+
+#[test]
+fn it_maps_idl_types_to_graphql_types() {
+    assert_eq!(idl_type_to_graphql_type(&IdlType::U8).unwrap(), "Int!");
+    assert_eq!(
+        idl_type_to_graphql_type(&IdlType::Option(Box::new(IdlType::Pubkey))).unwrap(),
+        "PublicKey"
+    );
+    assert_eq!(
+        idl_type_to_graphql_type(&IdlType::Vec(Box::new(IdlType::Option(Box::new(IdlType::U64)))))
+            .unwrap(),
+        "[BigInt]!"
+    );
+    assert!(idl_type_to_graphql_type(&IdlType::Generic("T".into())).is_err());
+}
@@ -0,0 +1,32 @@
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use txtx_addon_kit::types::types::Value;
+
+use super::SvmValue;
+
+#[test]
+fn it_formats_pubkeys_in_base58() {
+    let pubkey = Pubkey::new_from_array([7; 32]);
+    let value = SvmValue::pubkey(pubkey.to_bytes().to_vec());
+    assert_eq!(SvmValue::format_display(&value), Some(pubkey.to_string()));
+}
+
+#[test]
+fn it_formats_signatures_in_base58() {
+    let signature = Signature::from([9; 64]);
+    let value = SvmValue::signature(signature.as_ref().to_vec());
+    assert_eq!(SvmValue::format_display(&value), Some(signature.to_string()));
+}
+
+#[test]
+fn it_formats_numbers_in_decimal() {
+    assert_eq!(SvmValue::format_display(&SvmValue::u64(1_500_000)), Some("1500000".into()));
+    assert_eq!(SvmValue::format_display(&SvmValue::i32(-42)), Some("-42".into()));
+    assert_eq!(SvmValue::format_display(&SvmValue::u128(u128::MAX)), Some(u128::MAX.to_string()));
+}
+
+#[test]
+fn it_leaves_other_values_unformatted() {
+    assert_eq!(SvmValue::format_display(&Value::string("hello".into())), None);
+    assert_eq!(SvmValue::format_display(&Value::addon(vec![1, 2], "evm::address")), None);
+}
@@ -10,6 +10,7 @@ pub const NESTED_CONSTRUCT_INDEX: &str = "nested_construct_index";
 pub const NESTED_CONSTRUCT_COUNT: &str = "nested_construct_count";
 pub const DESCRIPTION: &str = "description";
 pub const DEPENDS_ON: &str = "depends_on";
+pub const ADDON_INSTANCE: &str = "addon_instance";
 pub const META_DESCRIPTION: &str = "meta_description";
 pub const MARKDOWN: &str = "markdown";
 pub const MARKDOWN_FILEPATH: &str = "markdown_filepath";
@@ -25,3 +26,8 @@ pub const RE_EXECUTE_COMMAND: &str = "re_execute_command";
 
 pub const THIRD_PARTY_SIGNATURE_STATUS: &str = "third_party_signature_status";
 pub const RUNBOOK_COMPLETE_ADDITIONAL_INFO: &str = "runbook_complete_additional_info";
+
+// Threshold signing
+pub const PARTIAL_SIGNATURE_REQUESTED: &str = "partial_signature_requested";
+pub const PARTIAL_SIGNATURE: &str = "partial_signature";
+pub const PARTIAL_SIGNATURES: &str = "partial_signatures";
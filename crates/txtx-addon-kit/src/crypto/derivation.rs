@@ -0,0 +1,288 @@
+use crate::types::commands::{CommandInput, CommandOutput};
+use crate::types::diagnostics::Diagnostic;
+use crate::types::stores::ValueStore;
+use crate::types::types::{ObjectType, Type, Value};
+
+// Inputs shared by the signers deriving their keys from a mnemonic
+pub const MNEMONIC: &str = "mnemonic";
+pub const DERIVATION_PATH: &str = "derivation_path";
+pub const ACCOUNT_INDEX: &str = "account_index";
+pub const PASSPHRASE: &str = "passphrase";
+pub const DERIVE_ADDRESSES: &str = "derive_addresses";
+// Deprecated alias of `passphrase`
+pub const PASSWORD: &str = "password";
+
+pub const DERIVED_ADDRESSES: &str = "derived_addresses";
+
+/// Placeholder substituted with the `account_index` input in a derivation path template.
+pub const ACCOUNT_INDEX_PLACEHOLDER: &str = "{account_index}";
+pub const MAX_DERIVED_ADDRESSES: u64 = 50;
+
+/// Returns the specifications of the derivation inputs (`derivation_path`, `account_index`,
+/// `passphrase` and `derive_addresses`) for a signer deriving its keys from a mnemonic with the
+/// given derivation path template, such as `m/44'/60'/0'/0/{account_index}`.
+pub fn mnemonic_derivation_inputs(derivation_path_template: &str) -> Vec<CommandInput> {
+    let default_path = derivation_path_for_account(derivation_path_template, 0);
+    vec![
+        derivation_input(
+            DERIVATION_PATH,
+            &format!("The derivation path used to generate the secret key from the mnemonic. Defaults to `{default_path}`. This input can't be combined with `account_index`."),
+            Type::string(),
+            false,
+        ),
+        derivation_input(
+            ACCOUNT_INDEX,
+            &format!("The index of the account to derive from the mnemonic, following the `{derivation_path_template}` derivation path. Defaults to 0."),
+            Type::integer(),
+            false,
+        ),
+        derivation_input(
+            PASSPHRASE,
+            "The BIP39 passphrase protecting the mnemonic, if any.",
+            Type::string(),
+            true,
+        ),
+        derivation_input(
+            DERIVE_ADDRESSES,
+            &format!("When set, the addresses of the first `derive_addresses` accounts of the mnemonic (at most {MAX_DERIVED_ADDRESSES}) are listed in the `derived_addresses` output, so that the account used by the signer can be confirmed before anything is signed."),
+            Type::integer(),
+            false,
+        ),
+        derivation_input(
+            PASSWORD,
+            "Deprecated: use `passphrase` instead.",
+            Type::string(),
+            true,
+        ),
+    ]
+}
+
+pub fn mnemonic_derivation_outputs() -> Vec<CommandOutput> {
+    vec![CommandOutput {
+        name: DERIVED_ADDRESSES.into(),
+        documentation: "The accounts derived from the mnemonic when `derive_addresses` is set, as a list of objects with the `account_index`, `derivation_path` and `address` keys.".into(),
+        typing: Type::array(Type::arbitrary_object()),
+    }]
+}
+
+fn derivation_input(
+    name: &str,
+    documentation: &str,
+    typing: Type,
+    sensitive: bool,
+) -> CommandInput {
+    CommandInput {
+        name: name.into(),
+        documentation: documentation.into(),
+        typing,
+        optional: true,
+        tainting: true,
+        check_required: false,
+        check_performed: false,
+        sensitive,
+        internal: false,
+        self_referencing: false,
+        deprecation: None,
+    }
+}
+
+pub fn derivation_path_for_account(derivation_path_template: &str, account_index: u32) -> String {
+    derivation_path_template.replace(ACCOUNT_INDEX_PLACEHOLDER, &account_index.to_string())
+}
+
+/// Resolves the derivation path of a mnemonic signer: the `derivation_path` input when provided,
+/// the template applied to the `account_index` input (defaulting to 0) otherwise.
+pub fn get_derivation_path(
+    values: &ValueStore,
+    derivation_path_template: &str,
+) -> Result<String, Diagnostic> {
+    let account_index = get_account_index(values)?;
+    match (values.get_string(DERIVATION_PATH), account_index) {
+        (Some(_), Some(_)) => Err(Diagnostic::error_from_string(format!(
+            "the `{DERIVATION_PATH}` and `{ACCOUNT_INDEX}` inputs can't be combined"
+        ))),
+        (Some(derivation_path), None) => Ok(derivation_path.to_string()),
+        (None, account_index) => {
+            Ok(derivation_path_for_account(derivation_path_template, account_index.unwrap_or(0)))
+        }
+    }
+}
+
+fn get_account_index(values: &ValueStore) -> Result<Option<u32>, Diagnostic> {
+    let Some(_) = values.get_value(ACCOUNT_INDEX) else {
+        return Ok(None);
+    };
+    let account_index = values.get_expected_uint(ACCOUNT_INDEX)?;
+    // hardened indexes start at 2^31
+    u32::try_from(account_index).ok().filter(|i| *i < (1 << 31)).map(Some).ok_or(
+        Diagnostic::error_from_string(format!(
+            "invalid `{ACCOUNT_INDEX}` input: expected a value lower than 2^31, got {account_index}"
+        )),
+    )
+}
+
+/// Returns the BIP39 passphrase of a mnemonic signer, accepting the deprecated `password` input.
+pub fn get_passphrase(values: &ValueStore) -> Option<&str> {
+    values.get_string(PASSPHRASE).or(values.get_string(PASSWORD))
+}
+
+/// Derives the addresses of the first `derive_addresses` accounts of the mnemonic, using
+/// `derive_address` to compute the address found at a derivation path. The accounts follow the
+/// `derivation_path` input when provided, by varying its last index, and the template otherwise.
+/// Returns `None` when the `derive_addresses` input isn't set.
+pub fn derive_addresses<F>(
+    values: &ValueStore,
+    derivation_path_template: &str,
+    derive_address: F,
+) -> Result<Option<Value>, Diagnostic>
+where
+    F: Fn(&str) -> Result<String, String>,
+{
+    let Some(_) = values.get_value(DERIVE_ADDRESSES) else {
+        return Ok(None);
+    };
+    let count = values.get_expected_uint(DERIVE_ADDRESSES)?;
+    if count == 0 || count > MAX_DERIVED_ADDRESSES {
+        return Err(Diagnostic::error_from_string(format!(
+            "invalid `{DERIVE_ADDRESSES}` input: expected a value between 1 and {MAX_DERIVED_ADDRESSES}, got {count}"
+        )));
+    }
+    let derivation_path_template = match values.get_string(DERIVATION_PATH) {
+        Some(derivation_path) => derivation_path_template_from(derivation_path)?,
+        None => derivation_path_template.to_string(),
+    };
+    let derivation_path_template = derivation_path_template.as_str();
+    let mut accounts = vec![];
+    for account_index in 0..count as u32 {
+        let derivation_path = derivation_path_for_account(derivation_path_template, account_index);
+        let address = derive_address(&derivation_path).map_err(|e| {
+            Diagnostic::error_from_string(format!(
+                "failed to derive address at {derivation_path}: {e}"
+            ))
+        })?;
+        accounts.push(
+            ObjectType::from(vec![
+                (ACCOUNT_INDEX, Value::integer(account_index as i128)),
+                (DERIVATION_PATH, Value::string(derivation_path)),
+                ("address", Value::string(address)),
+            ])
+            .to_value(),
+        );
+    }
+    Ok(Some(Value::array(accounts)))
+}
+
+/// Turns a derivation path such as `m/44'/60'/0'/0/5` into a template varying its last index,
+/// such as `m/44'/60'/0'/0/{account_index}`.
+fn derivation_path_template_from(derivation_path: &str) -> Result<String, Diagnostic> {
+    let invalid_path = || {
+        Diagnostic::error_from_string(format!(
+            "invalid `{DERIVATION_PATH}` input: expected a path ending with an index, got {derivation_path}"
+        ))
+    };
+    let (prefix, last) = derivation_path.rsplit_once('/').ok_or_else(invalid_path)?;
+    let (index, hardened) = match last.strip_suffix(['\'', 'h']) {
+        Some(index) => (index, &last[index.len()..]),
+        None => (last, ""),
+    };
+    index.parse::<u32>().map_err(|_| invalid_path())?;
+    Ok(format!("{prefix}/{ACCOUNT_INDEX_PLACEHOLDER}{hardened}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Did;
+
+    const TEMPLATE: &str = "m/44'/501'/{account_index}'/0'";
+
+    fn values(inputs: Vec<(&str, Value)>) -> ValueStore {
+        let mut values = ValueStore::new("signer", &Did::zero());
+        for (key, value) in inputs {
+            values.insert(key, value);
+        }
+        values
+    }
+
+    #[test]
+    fn test_get_derivation_path() {
+        assert_eq!(get_derivation_path(&values(vec![]), TEMPLATE).unwrap(), "m/44'/501'/0'/0'");
+        assert_eq!(
+            get_derivation_path(&values(vec![(ACCOUNT_INDEX, Value::integer(3))]), TEMPLATE)
+                .unwrap(),
+            "m/44'/501'/3'/0'"
+        );
+        assert_eq!(
+            get_derivation_path(
+                &values(vec![(DERIVATION_PATH, Value::string("m/44'/501'/7'/1'".into()))]),
+                TEMPLATE
+            )
+            .unwrap(),
+            "m/44'/501'/7'/1'"
+        );
+        assert!(get_derivation_path(
+            &values(vec![
+                (DERIVATION_PATH, Value::string("m/44'/501'/7'/1'".into())),
+                (ACCOUNT_INDEX, Value::integer(1))
+            ]),
+            TEMPLATE
+        )
+        .is_err());
+        assert!(get_derivation_path(
+            &values(vec![(ACCOUNT_INDEX, Value::integer(1 << 31))]),
+            TEMPLATE
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_derive_addresses() {
+        let derive = |path: &str| Ok(format!("address at {path}"));
+        assert!(derive_addresses(&values(vec![]), TEMPLATE, derive).unwrap().is_none());
+
+        let accounts = derive_addresses(
+            &values(vec![(DERIVE_ADDRESSES, Value::integer(2))]),
+            TEMPLATE,
+            derive,
+        )
+        .unwrap()
+        .unwrap();
+        let accounts = accounts.expect_array();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(
+            accounts[1].expect_object().get("address").unwrap().expect_string(),
+            "address at m/44'/501'/1'/0'"
+        );
+
+        let accounts = derive_addresses(
+            &values(vec![
+                (DERIVE_ADDRESSES, Value::integer(2)),
+                (DERIVATION_PATH, Value::string("m/44'/501'/7'/5'".into())),
+            ]),
+            TEMPLATE,
+            derive,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(
+            accounts.expect_array()[1].expect_object().get("address").unwrap().expect_string(),
+            "address at m/44'/501'/7'/1'"
+        );
+
+        assert!(derive_addresses(
+            &values(vec![
+                (DERIVE_ADDRESSES, Value::integer(2)),
+                (DERIVATION_PATH, Value::string("m".into())),
+            ]),
+            TEMPLATE,
+            derive
+        )
+        .is_err());
+        assert!(derive_addresses(
+            &values(vec![(DERIVE_ADDRESSES, Value::integer(0))]),
+            TEMPLATE,
+            derive
+        )
+        .is_err());
+    }
+}
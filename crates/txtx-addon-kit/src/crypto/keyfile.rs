@@ -0,0 +1,355 @@
+use std::path::PathBuf;
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use super::derivation::PASSPHRASE;
+use crate::constants::DESCRIPTION;
+use crate::types::diagnostics::Diagnostic;
+use crate::types::frontend::{ActionItemRequestType, Actions, ProvideInputRequest};
+use crate::types::stores::ValueStore;
+use crate::types::types::{RunbookSupervisionContext, Type};
+use crate::types::{AuthorizationContext, ConstructDid};
+
+// Inputs shared by the encrypted keyfile signers
+pub const KEYFILE_PATH: &str = "keyfile_path";
+pub const PASSPHRASE_ENV: &str = "passphrase_env";
+
+pub const ACTION_ITEM_PROVIDE_PASSPHRASE: &str = "provide_passphrase";
+
+pub const KEYFILE_VERSION: u8 = 1;
+
+/// The only error reported when a keyfile can't be decrypted. A malformed or tampered keyfile
+/// and a wrong passphrase are deliberately indistinguishable.
+pub const KEYFILE_DECRYPTION_ERROR: &str =
+    "unable to decrypt keyfile: the keyfile is invalid or the passphrase is incorrect";
+
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+// Upper bounds of the scrypt parameters, read from keyfiles before deriving any key: a keyfile
+// can't make a derivation use more than 1GiB of memory, or 4 times the default computation.
+const MAX_KDF_LOG_N: u8 = 20;
+const MAX_KDF_R: u32 = 8;
+const MAX_KDF_P: u32 = 4;
+
+/// The scrypt parameters used to derive the encryption key from the passphrase.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct KeyfileKdfParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+}
+
+impl Default for KeyfileKdfParams {
+    fn default() -> Self {
+        // 2^17 iterations with r = 8: ~128MiB of memory per derivation
+        KeyfileKdfParams { log_n: 17, r: 8, p: 1 }
+    }
+}
+
+/// A secret key encrypted with AES-256-GCM, under a key derived from a passphrase with scrypt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Keyfile {
+    version: u8,
+    kdf: String,
+    kdf_params: KeyfileKdfParams,
+    salt: String,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Encrypts `secret_key` with `passphrase`, and returns the JSON keyfile.
+pub fn encrypt_keyfile(secret_key: &[u8], passphrase: &str) -> Result<String, String> {
+    encrypt_keyfile_with_params(secret_key, passphrase, KeyfileKdfParams::default())
+}
+
+pub fn encrypt_keyfile_with_params(
+    secret_key: &[u8],
+    passphrase: &str,
+    kdf_params: KeyfileKdfParams,
+) -> Result<String, String> {
+    if passphrase.is_empty() {
+        return Err("keyfile passphrase can't be empty".into());
+    }
+    let mut rng = rand::thread_rng();
+    let mut salt = [0u8; SALT_LEN];
+    rng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt, &kdf_params)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_slice()));
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), secret_key)
+        .map_err(|e| format!("failed to encrypt keyfile: {e}"))?;
+
+    let keyfile = Keyfile {
+        version: KEYFILE_VERSION,
+        kdf: "scrypt".into(),
+        kdf_params,
+        salt: hex::encode(salt),
+        cipher: "aes-256-gcm".into(),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&keyfile).map_err(|e| format!("failed to serialize keyfile: {e}"))
+}
+
+/// Decrypts a JSON keyfile produced by [encrypt_keyfile]. The returned secret key is wiped from
+/// memory when dropped. All failures are reported with [KEYFILE_DECRYPTION_ERROR].
+pub fn decrypt_keyfile(keyfile: &[u8], passphrase: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+    try_decrypt_keyfile(keyfile, passphrase).ok_or(KEYFILE_DECRYPTION_ERROR.to_string())
+}
+
+fn try_decrypt_keyfile(keyfile: &[u8], passphrase: &str) -> Option<Zeroizing<Vec<u8>>> {
+    let keyfile: Keyfile = serde_json::from_slice(keyfile).ok()?;
+    if keyfile.version != KEYFILE_VERSION
+        || keyfile.kdf != "scrypt"
+        || keyfile.cipher != "aes-256-gcm"
+    {
+        return None;
+    }
+    let salt = hex::decode(&keyfile.salt).ok()?;
+    let nonce = hex::decode(&keyfile.nonce).ok()?;
+    let ciphertext = hex::decode(&keyfile.ciphertext).ok()?;
+    if nonce.len() != NONCE_LEN {
+        return None;
+    }
+
+    let key = derive_key(passphrase, &salt, &keyfile.kdf_params).ok()?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_slice()));
+    cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice()).ok().map(Zeroizing::new)
+}
+
+/// The outcome of [unlock_signer_keyfile].
+pub enum UnlockedKeyfile {
+    /// The decrypted secret key, wiped from memory when dropped.
+    SecretKey(Zeroizing<Vec<u8>>),
+    /// The passphrase is unknown: these actions request it in the supervisor.
+    PassphraseRequested(Actions),
+}
+
+/// Reads and decrypts the keyfile of an encrypted keyfile signer (`keyfile_path` input). The
+/// passphrase is either provided in the supervisor, where it's requested with a masked input, or
+/// read from the environment variable named by the `passphrase_env` input. The passphrase isn't
+/// kept once the keyfile is decrypted: signers are expected to hold on to the secret key instead.
+pub fn unlock_signer_keyfile(
+    construct_did: &ConstructDid,
+    instance_name: &str,
+    values: &ValueStore,
+    supervision_context: &RunbookSupervisionContext,
+    auth_ctx: &AuthorizationContext,
+) -> Result<UnlockedKeyfile, Diagnostic> {
+    let keyfile_path = values.get_expected_string(KEYFILE_PATH)?;
+    let keyfile = auth_ctx
+        .get_file_location_from_path_buf(&PathBuf::from(keyfile_path))
+        .map_err(|e| {
+            Diagnostic::error_from_string(format!("invalid keyfile path '{keyfile_path}': {e}"))
+        })?
+        .read_content()
+        .map_err(|e| {
+            Diagnostic::error_from_string(format!("unable to read keyfile '{keyfile_path}': {e}"))
+        })?;
+
+    let passphrase_env = values.get_string(PASSPHRASE_ENV);
+    let passphrase = match values
+        .get_string(PASSPHRASE)
+        .map(|p| Zeroizing::new(p.to_string()))
+        .or_else(|| passphrase_env.and_then(|name| std::env::var(name).ok()).map(Zeroizing::new))
+    {
+        Some(passphrase) => passphrase,
+        None if supervision_context.is_supervised => {
+            let description = values.get_string(DESCRIPTION).map(|d| d.to_string());
+            let markdown = values.get_markdown(auth_ctx)?;
+            let mut actions = Actions::none();
+            actions.push_sub_group(
+                None,
+                vec![ActionItemRequestType::ProvideInput(ProvideInputRequest {
+                    default_value: None,
+                    input_name: PASSPHRASE.into(),
+                    typing: Type::string(),
+                    sensitive: true,
+                })
+                .to_request(instance_name, ACTION_ITEM_PROVIDE_PASSPHRASE)
+                .with_construct_did(construct_did)
+                .with_some_description(description)
+                .with_meta_description(&format!("Unlock {} keyfile", instance_name))
+                .with_some_markdown(markdown)],
+            );
+            return Ok(UnlockedKeyfile::PassphraseRequested(actions));
+        }
+        None => {
+            let message = match passphrase_env {
+                Some(name) => format!(
+                    "signer '{instance_name}': the environment variable '{name}' holding the keyfile passphrase is not set"
+                ),
+                None => format!(
+                    "signer '{instance_name}': the '{PASSPHRASE_ENV}' input is required to unlock the keyfile in unsupervised mode"
+                ),
+            };
+            return Err(Diagnostic::error_from_string(message));
+        }
+    };
+
+    decrypt_keyfile(&keyfile, &passphrase)
+        .map(UnlockedKeyfile::SecretKey)
+        .map_err(|e| Diagnostic::error_from_string(format!("signer '{instance_name}': {e}")))
+}
+
+fn derive_key(
+    passphrase: &str,
+    salt: &[u8],
+    kdf_params: &KeyfileKdfParams,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, String> {
+    if kdf_params.log_n > MAX_KDF_LOG_N || kdf_params.r > MAX_KDF_R || kdf_params.p > MAX_KDF_P {
+        return Err(format!(
+            "invalid scrypt parameters: log_n, r and p can't exceed {MAX_KDF_LOG_N}, {MAX_KDF_R} and {MAX_KDF_P}"
+        ));
+    }
+    let params = scrypt::Params::new(kdf_params.log_n, kdf_params.r, kdf_params.p, KEY_LEN)
+        .map_err(|e| format!("invalid scrypt parameters: {e}"))?;
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+    scrypt::scrypt(passphrase.as_bytes(), salt, &params, key.as_mut_slice())
+        .map_err(|e| format!("failed to derive keyfile key: {e}"))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::types::Value;
+
+    // cheap parameters, to keep the tests fast
+    const TEST_PARAMS: KeyfileKdfParams = KeyfileKdfParams { log_n: 4, r: 8, p: 1 };
+
+    #[test]
+    fn test_keyfile_roundtrip() {
+        let secret_key = [42u8; 32];
+        let keyfile = encrypt_keyfile_with_params(&secret_key, "passphrase", TEST_PARAMS).unwrap();
+        assert!(!keyfile.contains(&hex::encode(secret_key)));
+        let decrypted = decrypt_keyfile(keyfile.as_bytes(), "passphrase").unwrap();
+        assert_eq!(decrypted.as_slice(), &secret_key);
+    }
+
+    #[test]
+    fn test_keyfile_errors_are_indistinguishable() {
+        let keyfile = encrypt_keyfile_with_params(&[1u8; 64], "passphrase", TEST_PARAMS).unwrap();
+
+        let wrong_passphrase = decrypt_keyfile(keyfile.as_bytes(), "wrong").unwrap_err();
+
+        let mut tampered: serde_json::Value = serde_json::from_str(&keyfile).unwrap();
+        let mut ciphertext = hex::decode(tampered["ciphertext"].as_str().unwrap()).unwrap();
+        ciphertext[0] ^= 1;
+        tampered["ciphertext"] = serde_json::Value::String(hex::encode(ciphertext));
+        let tampered = decrypt_keyfile(tampered.to_string().as_bytes(), "passphrase").unwrap_err();
+
+        let malformed = decrypt_keyfile(b"not a keyfile", "passphrase").unwrap_err();
+
+        assert_eq!(wrong_passphrase, KEYFILE_DECRYPTION_ERROR);
+        assert_eq!(tampered, KEYFILE_DECRYPTION_ERROR);
+        assert_eq!(malformed, KEYFILE_DECRYPTION_ERROR);
+    }
+
+    #[test]
+    fn test_keyfile_rejects_costly_kdf_params() {
+        let keyfile = encrypt_keyfile_with_params(&[1u8; 32], "passphrase", TEST_PARAMS).unwrap();
+        for (name, value) in [("log_n", 40), ("r", 1 << 20), ("p", 1 << 20)] {
+            let mut costly: serde_json::Value = serde_json::from_str(&keyfile).unwrap();
+            costly["kdf_params"][name] = value.into();
+            let err = decrypt_keyfile(costly.to_string().as_bytes(), "passphrase").unwrap_err();
+            assert_eq!(err, KEYFILE_DECRYPTION_ERROR);
+        }
+        let costly = KeyfileKdfParams { log_n: MAX_KDF_LOG_N + 1, ..TEST_PARAMS };
+        assert!(encrypt_keyfile_with_params(&[1u8; 32], "passphrase", costly).is_err());
+    }
+
+    #[test]
+    fn test_keyfile_rejects_empty_passphrase() {
+        assert!(encrypt_keyfile_with_params(&[1u8; 32], "", TEST_PARAMS).is_err());
+    }
+
+    fn signer_values(keyfile_path: &std::path::Path, inputs: Vec<(&str, &str)>) -> ValueStore {
+        let mut values = ValueStore::new("signer", &crate::types::Did::zero());
+        values.insert(KEYFILE_PATH, Value::string(keyfile_path.to_string_lossy().to_string()));
+        for (name, value) in inputs {
+            values.insert(name, Value::string(value.to_string()));
+        }
+        values
+    }
+
+    fn unlock(
+        values: &ValueStore,
+        supervision_context: &RunbookSupervisionContext,
+    ) -> Result<UnlockedKeyfile, Diagnostic> {
+        unlock_signer_keyfile(
+            &ConstructDid(crate::types::Did::zero()),
+            "deployer",
+            values,
+            supervision_context,
+            &AuthorizationContext::empty(),
+        )
+    }
+
+    #[test]
+    fn test_signer_keyfile_unlocking() {
+        let secret_key = [42u8; 32];
+        let keyfile = encrypt_keyfile_with_params(&secret_key, "passphrase", TEST_PARAMS).unwrap();
+        let keyfile_path =
+            std::env::temp_dir().join(format!("txtx-keyfile-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&keyfile_path, keyfile).unwrap();
+
+        let supervised = RunbookSupervisionContext {
+            review_input_default_values: false,
+            review_input_values: false,
+            is_supervised: true,
+        };
+        let unsupervised = RunbookSupervisionContext { is_supervised: false, ..supervised.clone() };
+
+        // the passphrase is requested with a masked input in the supervisor
+        let values = signer_values(&keyfile_path, vec![]);
+        let Ok(UnlockedKeyfile::PassphraseRequested(actions)) = unlock(&values, &supervised) else {
+            panic!("expected the passphrase to be requested");
+        };
+        let requests = actions.get_new_action_item_requests();
+        assert_eq!(requests.len(), 1);
+        let request = requests[0].action_type.as_provide_input().unwrap();
+        assert_eq!(request.input_name, PASSPHRASE);
+        assert!(request.sensitive);
+
+        // and decrypts the keyfile once provided
+        let values = signer_values(&keyfile_path, vec![(PASSPHRASE, "passphrase")]);
+        let Ok(UnlockedKeyfile::SecretKey(decrypted)) = unlock(&values, &supervised) else {
+            panic!("expected the keyfile to be decrypted");
+        };
+        assert_eq!(decrypted.as_slice(), &secret_key);
+
+        // unsupervised runs read the passphrase from the environment
+        let values = signer_values(&keyfile_path, vec![]);
+        let diag = unlock(&values, &unsupervised).err().unwrap();
+        assert!(diag.message.contains("the 'passphrase_env' input is required"));
+
+        let env_name = "TXTX_TEST_KEYFILE_PASSPHRASE";
+        let values = signer_values(&keyfile_path, vec![(PASSPHRASE_ENV, env_name)]);
+        let diag = unlock(&values, &unsupervised).err().unwrap();
+        assert!(diag.message.contains(&format!("the environment variable '{env_name}'")));
+
+        std::env::set_var(env_name, "wrong");
+        let diag = unlock(&values, &unsupervised).err().unwrap();
+        assert_eq!(diag.message, format!("signer 'deployer': {KEYFILE_DECRYPTION_ERROR}"));
+
+        std::env::set_var(env_name, "passphrase");
+        let Ok(UnlockedKeyfile::SecretKey(decrypted)) = unlock(&values, &unsupervised) else {
+            panic!("expected the keyfile to be decrypted");
+        };
+        assert_eq!(decrypted.as_slice(), &secret_key);
+
+        std::env::remove_var(env_name);
+        let _ = std::fs::remove_file(keyfile_path);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::types::diagnostics::Diagnostic;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_kms::error::{DisplayErrorContext, ProvideErrorMetadata, SdkError};
+use aws_sdk_kms::primitives::Blob;
+use aws_sdk_kms::types::{MessageType, SigningAlgorithmSpec};
+use aws_sdk_kms::Client;
+
+/// A key held by AWS KMS. Credentials are resolved with the standard AWS provider chain
+/// (environment, shared config and credentials files, SSO, instance metadata, ...).
+#[derive(Clone, Debug)]
+pub struct AwsKmsKey {
+    pub key_id: String,
+    pub region: Option<String>,
+}
+
+impl AwsKmsKey {
+    /// Creates a key from its ARN (or id / alias). When `region` is omitted, the region of the
+    /// ARN is used, then the region of the AWS configuration.
+    pub fn new(key_id: &str, region: Option<&str>) -> Self {
+        let region = region.map(|r| r.to_string()).or_else(|| region_from_arn(key_id));
+        Self { key_id: key_id.to_string(), region }
+    }
+
+    async fn client(&self) -> Client {
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = &self.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        Client::new(&loader.load().await)
+    }
+
+    /// Retrieves the DER-encoded SubjectPublicKeyInfo of the key.
+    pub async fn get_public_key_der(&self) -> Result<Vec<u8>, Diagnostic> {
+        let output = self
+            .client()
+            .await
+            .get_public_key()
+            .key_id(&self.key_id)
+            .send()
+            .await
+            .map_err(|e| aws_kms_diagnostic(&self.key_id, "kms:GetPublicKey", e))?;
+        output.public_key.map(|key| key.into_inner()).ok_or_else(|| {
+            Diagnostic::error_from_string(format!(
+                "AWS KMS returned no public key for key {}",
+                self.key_id
+            ))
+        })
+    }
+
+    /// Signs `message` with the KMS Sign API, and returns the signature as encoded by KMS.
+    pub async fn sign(
+        &self,
+        message: Vec<u8>,
+        message_type: MessageType,
+        signing_algorithm: SigningAlgorithmSpec,
+    ) -> Result<Vec<u8>, Diagnostic> {
+        let output = self
+            .client()
+            .await
+            .sign()
+            .key_id(&self.key_id)
+            .message(Blob::new(message))
+            .message_type(message_type)
+            .signing_algorithm(signing_algorithm)
+            .send()
+            .await
+            .map_err(|e| aws_kms_diagnostic(&self.key_id, "kms:Sign", e))?;
+        output.signature.map(|signature| signature.into_inner()).ok_or_else(|| {
+            Diagnostic::error_from_string(format!(
+                "AWS KMS returned no signature for key {}",
+                self.key_id
+            ))
+        })
+    }
+
+    /// Signs a 32-byte digest with ECDSA over secp256k1, and returns the DER-encoded signature.
+    pub async fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>, Diagnostic> {
+        self.sign(digest.to_vec(), MessageType::Digest, SigningAlgorithmSpec::EcdsaSha256).await
+    }
+
+    /// Signs `message` with pure Ed25519 (`ED25519_SHA_512`), and returns the 64-byte signature.
+    pub async fn sign_ed25519(&self, message: &[u8]) -> Result<Vec<u8>, Diagnostic> {
+        self.sign(message.to_vec(), MessageType::Raw, SigningAlgorithmSpec::Ed25519Sha512).await
+    }
+}
+
+/// Extracts the region of a key ARN (`arn:aws:kms:<region>:<account>:key/<id>`).
+fn region_from_arn(key_id: &str) -> Option<String> {
+    let mut parts = key_id.split(':');
+    match (parts.next(), parts.nth(1), parts.next()) {
+        (Some("arn"), Some("kms"), Some(region)) if !region.is_empty() => Some(region.to_string()),
+        _ => None,
+    }
+}
+
+fn aws_kms_diagnostic<E, R>(key_id: &str, operation: &str, error: SdkError<E, R>) -> Diagnostic
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+    R: std::fmt::Debug + Send + Sync + 'static,
+{
+    describe_aws_kms_error(
+        key_id,
+        operation,
+        error.code(),
+        &DisplayErrorContext(&error).to_string(),
+    )
+}
+
+/// Translates the error codes of the KMS API into actionable diagnostics.
+fn describe_aws_kms_error(
+    key_id: &str,
+    operation: &str,
+    code: Option<&str>,
+    details: &str,
+) -> Diagnostic {
+    let message = match code {
+        Some("ThrottlingException") => format!(
+            "AWS KMS throttled the {operation} request for key {key_id}: retry later, or request a higher KMS request quota for the account"
+        ),
+        Some("AccessDeniedException") => format!(
+            "access denied for {operation} on key {key_id}: make sure the credentials resolved by the AWS provider chain are granted {operation} by the key policy"
+        ),
+        Some("NotFoundException") => {
+            format!("AWS KMS key {key_id} not found: check the key ARN and region")
+        }
+        Some("DisabledException") | Some("KMSInvalidStateException") => {
+            format!("AWS KMS key {key_id} is disabled or pending deletion")
+        }
+        _ => format!("{operation} request failed for AWS KMS key {key_id}: {details}"),
+    };
+    Diagnostic::error_from_string(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_ARN: &str =
+        "arn:aws:kms:eu-west-3:111122223333:key/1234abcd-12ab-34cd-56ef-1234567890ab";
+
+    #[test]
+    fn it_reads_region_from_key_arn() {
+        assert_eq!(AwsKmsKey::new(KEY_ARN, None).region.as_deref(), Some("eu-west-3"));
+        assert_eq!(AwsKmsKey::new(KEY_ARN, Some("us-east-1")).region.as_deref(), Some("us-east-1"));
+        assert_eq!(AwsKmsKey::new("alias/deployer", None).region, None);
+    }
+
+    #[test]
+    fn it_maps_throttling_and_access_errors() {
+        let diag =
+            describe_aws_kms_error(KEY_ARN, "kms:Sign", Some("ThrottlingException"), "throttled");
+        assert!(diag.message.contains("request a higher KMS request quota"));
+
+        let diag =
+            describe_aws_kms_error(KEY_ARN, "kms:Sign", Some("AccessDeniedException"), "denied");
+        assert!(diag.message.contains("granted kms:Sign by the key policy"));
+
+        let diag = describe_aws_kms_error(KEY_ARN, "kms:GetPublicKey", None, "dispatch failure");
+        assert!(diag.message.ends_with("dispatch failure"));
+    }
+}
@@ -0,0 +1,262 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reqwest::{Client, RequestBuilder};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value as JsonValue};
+
+use crate::types::diagnostics::Diagnostic;
+
+const DEFAULT_ENDPOINT: &str = "https://cloudkms.googleapis.com";
+const CLOUD_KMS_SCOPE: &str = "https://www.googleapis.com/auth/cloudkms";
+
+/// A key version held by Google Cloud KMS, identified by its resource name
+/// (`projects/<project>/locations/<location>/keyRings/<ring>/cryptoKeys/<key>/cryptoKeyVersions/<version>`).
+/// Requests are authenticated with the application-default credentials.
+#[derive(Clone, Debug)]
+pub struct GcpKmsKey {
+    pub key_name: String,
+    /// A custom Cloud KMS endpoint, such as an emulator. Requests sent to a custom endpoint are
+    /// not authenticated.
+    pub endpoint: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PublicKeyResponse {
+    pem: String,
+}
+
+#[derive(Deserialize)]
+struct AsymmetricSignResponse {
+    signature: String,
+}
+
+impl GcpKmsKey {
+    pub fn new(key_name: &str, endpoint: Option<&str>) -> Self {
+        Self {
+            key_name: key_name.trim_start_matches('/').to_string(),
+            endpoint: endpoint.map(|e| e.trim_end_matches('/').to_string()),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/v1/{}{}",
+            self.endpoint.as_deref().unwrap_or(DEFAULT_ENDPOINT),
+            self.key_name,
+            path
+        )
+    }
+
+    async fn authenticate(&self, request: RequestBuilder) -> Result<RequestBuilder, Diagnostic> {
+        if self.endpoint.is_some() {
+            return Ok(request);
+        }
+        let provider = gcp_auth::provider().await.map_err(|e| {
+            Diagnostic::error_from_string(format!(
+                "failed to load Google Cloud application-default credentials: {e}"
+            ))
+        })?;
+        let token = provider.token(&[CLOUD_KMS_SCOPE]).await.map_err(|e| {
+            Diagnostic::error_from_string(format!(
+                "failed to get a Google Cloud access token for Cloud KMS: {e}"
+            ))
+        })?;
+        Ok(request.bearer_auth(token.as_str()))
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        operation: &str,
+        request: RequestBuilder,
+    ) -> Result<T, Diagnostic> {
+        let response = self.authenticate(request).await?.send().await.map_err(|e| {
+            Diagnostic::error_from_string(format!(
+                "{operation} request failed for Cloud KMS key {}: {e}",
+                self.key_name
+            ))
+        })?;
+        let status = response.status();
+        let body = response.text().await.map_err(|e| {
+            Diagnostic::error_from_string(format!(
+                "{operation} request failed for Cloud KMS key {}: {e}",
+                self.key_name
+            ))
+        })?;
+        if !status.is_success() {
+            return Err(describe_gcp_kms_error(&self.key_name, operation, status.as_u16(), &body));
+        }
+        serde_json::from_str(&body).map_err(|e| {
+            Diagnostic::error_from_string(format!(
+                "invalid {operation} response for Cloud KMS key {}: {e}",
+                self.key_name
+            ))
+        })
+    }
+
+    /// Retrieves the DER-encoded SubjectPublicKeyInfo of the key version.
+    pub async fn get_public_key_der(&self) -> Result<Vec<u8>, Diagnostic> {
+        let request = Client::new().get(self.url("/publicKey"));
+        let response: PublicKeyResponse = self.send("getPublicKey", request).await?;
+        pem_to_der(&response.pem).map_err(|e| {
+            Diagnostic::error_from_string(format!(
+                "invalid public key for Cloud KMS key {}: {e}",
+                self.key_name
+            ))
+        })
+    }
+
+    /// Signs with the asymmetricSign API, and returns the signature as encoded by Cloud KMS.
+    pub async fn asymmetric_sign(&self, body: JsonValue) -> Result<Vec<u8>, Diagnostic> {
+        let request = Client::new().post(self.url(":asymmetricSign")).json(&body);
+        let response: AsymmetricSignResponse = self.send("asymmetricSign", request).await?;
+        STANDARD.decode(&response.signature).map_err(|e| {
+            Diagnostic::error_from_string(format!(
+                "invalid signature for Cloud KMS key {}: {e}",
+                self.key_name
+            ))
+        })
+    }
+
+    /// Signs a 32-byte digest with ECDSA over secp256k1 (`EC_SIGN_SECP256K1_SHA256`), and returns
+    /// the DER-encoded signature.
+    pub async fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>, Diagnostic> {
+        self.asymmetric_sign(json!({ "digest": { "sha256": STANDARD.encode(digest) } })).await
+    }
+
+    /// Signs `message` with Ed25519 (`EC_SIGN_ED25519`), and returns the 64-byte signature.
+    pub async fn sign_ed25519(&self, message: &[u8]) -> Result<Vec<u8>, Diagnostic> {
+        self.asymmetric_sign(json!({ "data": STANDARD.encode(message) })).await
+    }
+}
+
+/// Decodes the body of a PEM document.
+pub fn pem_to_der(pem: &str) -> Result<Vec<u8>, String> {
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with("-----"))
+        .collect();
+    STANDARD.decode(body).map_err(|e| format!("malformed PEM: {e}"))
+}
+
+/// Translates the error statuses of the Cloud KMS API into actionable diagnostics.
+fn describe_gcp_kms_error(
+    key_name: &str,
+    operation: &str,
+    status: u16,
+    details: &str,
+) -> Diagnostic {
+    let message = match status {
+        429 => format!(
+            "Cloud KMS throttled the {operation} request for key {key_name}: retry later, or request a higher Cloud KMS quota for the project"
+        ),
+        401 | 403 => format!(
+            "permission denied for {operation} on key {key_name}: make sure the application-default credentials are granted the roles/cloudkms.signerVerifier role on the key"
+        ),
+        400 if details.contains("FAILED_PRECONDITION") => format!(
+            "Cloud KMS key {key_name} is not enabled, or its algorithm does not support {operation}"
+        ),
+        404 => format!(
+            "Cloud KMS key {key_name} not found: the key must be the full resource name of a key version"
+        ),
+        _ => format!(
+            "{operation} request failed for Cloud KMS key {key_name} (status {status}): {details}"
+        ),
+    };
+    Diagnostic::error_from_string(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_NAME: &str =
+        "projects/txtx/locations/global/keyRings/txtx/cryptoKeys/deployer/cryptoKeyVersions/1";
+
+    const ED25519_PEM: &str = "-----BEGIN PUBLIC KEY-----\nMCowBQYDK2VwAyEAGb9ECWmEzf6FQbrBZ9w7lshQhqowtrbLDFw4rXAxZuE=\n-----END PUBLIC KEY-----\n";
+
+    #[test]
+    fn it_decodes_pem_public_keys() {
+        let der = pem_to_der(ED25519_PEM).unwrap();
+        assert_eq!(der.len(), 44);
+        assert_eq!(
+            &der[..12],
+            &[0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00]
+        );
+        assert!(pem_to_der("-----BEGIN PUBLIC KEY-----\n%%%\n-----END PUBLIC KEY-----").is_err());
+    }
+
+    #[tokio::test]
+    async fn it_fetches_public_keys_and_signs_with_a_mocked_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let public_key = server
+            .mock("GET", format!("/v1/{KEY_NAME}/publicKey").as_str())
+            .with_status(200)
+            .with_body(json!({ "pem": ED25519_PEM, "algorithm": "EC_SIGN_ED25519" }).to_string())
+            .create_async()
+            .await;
+        let signature = [7u8; 64];
+        let sign = server
+            .mock("POST", format!("/v1/{KEY_NAME}:asymmetricSign").as_str())
+            .match_body(mockito::Matcher::Json(json!({ "data": STANDARD.encode(b"message") })))
+            .with_status(200)
+            .with_body(json!({ "signature": STANDARD.encode(signature) }).to_string())
+            .create_async()
+            .await;
+
+        let key = GcpKmsKey::new(KEY_NAME, Some(&server.url()));
+        assert_eq!(key.get_public_key_der().await.unwrap(), pem_to_der(ED25519_PEM).unwrap());
+        assert_eq!(key.sign_ed25519(b"message").await.unwrap(), signature.to_vec());
+        public_key.assert_async().await;
+        sign.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_signs_secp256k1_digests_with_a_mocked_endpoint() {
+        let mut server = mockito::Server::new_async().await;
+        let digest = [1u8; 32];
+        let der_signature = [0x30u8, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x01];
+        let sign = server
+            .mock("POST", format!("/v1/{KEY_NAME}:asymmetricSign").as_str())
+            .match_body(mockito::Matcher::Json(
+                json!({ "digest": { "sha256": STANDARD.encode(digest) } }),
+            ))
+            .with_status(200)
+            .with_body(json!({ "signature": STANDARD.encode(der_signature) }).to_string())
+            .create_async()
+            .await;
+
+        let key = GcpKmsKey::new(KEY_NAME, Some(&server.url()));
+        assert_eq!(key.sign_digest(&digest).await.unwrap(), der_signature.to_vec());
+        sign.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn it_maps_api_errors() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", format!("/v1/{KEY_NAME}/publicKey").as_str())
+            .with_status(403)
+            .with_body(r#"{"error":{"code":403,"status":"PERMISSION_DENIED"}}"#)
+            .create_async()
+            .await;
+
+        let key = GcpKmsKey::new(KEY_NAME, Some(&server.url()));
+        let diag = key.get_public_key_der().await.unwrap_err();
+        assert!(diag.message.contains("roles/cloudkms.signerVerifier"));
+    }
+
+    #[test]
+    fn it_maps_error_statuses() {
+        let diag = describe_gcp_kms_error(KEY_NAME, "asymmetricSign", 429, "");
+        assert!(diag.message.contains("request a higher Cloud KMS quota"));
+
+        let diag = describe_gcp_kms_error(KEY_NAME, "asymmetricSign", 400, "FAILED_PRECONDITION");
+        assert!(diag.message.contains("is not enabled"));
+
+        let diag = describe_gcp_kms_error(KEY_NAME, "getPublicKey", 404, "");
+        assert!(diag.message.contains("full resource name of a key version"));
+
+        let diag = describe_gcp_kms_error(KEY_NAME, "getPublicKey", 500, "internal error");
+        assert!(diag.message.ends_with("(status 500): internal error"));
+    }
+}
@@ -0,0 +1,5 @@
+//! Keys held by cloud key management services. The key material never leaves the KMS: the
+//! network addons fetch the public keys, and have their payloads signed remotely.
+
+pub mod aws;
+pub mod gcp;
@@ -1,3 +1,8 @@
+pub mod derivation;
+pub mod keyfile;
+#[cfg(feature = "kms")]
+pub mod kms;
+
 use std::str::FromStr;
 
 use bip32::{DerivationPath, XPrv as ExtendedPrivKey};
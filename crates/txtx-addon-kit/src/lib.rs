@@ -8,16 +8,20 @@ extern crate lazy_static;
 mod macros;
 pub mod constants;
 
+pub use bigdecimal;
+pub use chrono;
 pub use hex;
 // pub use hiro_system_kit;
 pub use indoc::formatdoc;
 pub use indoc::indoc;
 use types::commands::CommandInputsEvaluationResult;
 use types::commands::CommandInstance;
-use types::diagnostics::Diagnostic;
+use types::diagnostics::{Diagnostic, DiagnosticCode};
 use types::AddonPostProcessingResult;
 use types::ConstructDid;
+use types::RunbookLifecycleContext;
 pub use uuid;
+pub use zeroize;
 pub extern crate crossbeam_channel as channel;
 pub use futures;
 pub use hmac;
@@ -78,9 +82,32 @@ pub trait Addon: Debug + Sync + Send {
     fn get_signers(&self) -> Vec<SignerSpecification> {
         vec![]
     }
+    /// The diagnostic codes the addon can emit, for their uniqueness to be checked across addons
+    fn get_diagnostic_codes(&self) -> Vec<DiagnosticCode> {
+        vec![]
+    }
+    /// Called once per run, before any construct executes. A failure aborts the run.
+    fn on_runbook_start(&self, _ctx: &mut RunbookLifecycleContext) -> Result<(), Diagnostic> {
+        Ok(())
+    }
+    /// Called once per run with its result, when `on_runbook_start` succeeded, including when the
+    /// run failed or was aborted by the start hook of another addon. A failure is reported as a
+    /// warning.
+    fn on_runbook_end(
+        &self,
+        _ctx: &mut RunbookLifecycleContext,
+        _result: &Result<(), Vec<Diagnostic>>,
+    ) -> Result<(), Diagnostic> {
+        Ok(())
+    }
     fn to_json(&self, _value: &Value) -> Result<Option<serde_json::Value>, Diagnostic> {
         Ok(None)
     }
+    /// Formats the addon values this addon knows of for display, in outputs, review items and
+    /// diagnostics (e.g. an address in its checksummed form). Returns `None` for other values.
+    fn format_display(&self, _value: &Value) -> Option<String> {
+        None
+    }
     ///
     fn build_function_lookup(self: &Self) -> HashMap<String, FunctionSpecification> {
         let mut functions = HashMap::new();
@@ -14,7 +14,10 @@ macro_rules! define_function {
         name: $fn_name:expr,
         documentation: $doc:expr,
         example: $example:expr,
-        inputs: [$($input_name:ident: { documentation: $input_doc:expr, typing: $input_ts:expr $(, optional: $input_opt:expr)? }),*],
+        inputs: [$($input_name:ident: { documentation: $input_doc:expr, typing: $input_ts:expr $(, optional: $input_opt:expr)? $(, deprecated: $input_deprecated:expr)? }),*],
+        $(variadic: $variadic_name:ident: { documentation: $variadic_doc:expr, typing: $variadic_ts:expr },)?
+        $(named_arguments: $named_arguments:expr,)?
+        $(deprecated: $deprecated:expr,)?
         output: { documentation: $output_doc:expr, typing: $output_ts:expr },
     }) => {
         txtx_addon_kit::types::functions::FunctionSpecification {
@@ -31,11 +34,33 @@ macro_rules! define_function {
                     )?
                     is_optional
                 },
+                deprecation: None $(.or(Some($input_deprecated)))?,
             }),*],
+            variadic: {
+                let mut variadic = None;
+                $(
+                    variadic = Some(txtx_addon_kit::types::functions::FunctionInput {
+                        name: String::from(stringify!($variadic_name)),
+                        documentation: String::from($variadic_doc),
+                        typing: $variadic_ts,
+                        optional: true,
+                        deprecation: None,
+                    });
+                )?
+                variadic
+            },
+            accepts_named_arguments: {
+                let mut accepts_named_arguments = false;
+                $(
+                    accepts_named_arguments = $named_arguments;
+                )?
+                accepts_named_arguments
+            },
             output: txtx_addon_kit::types::functions::FunctionOutput {
                 documentation: String::from($output_doc),
                 typing: $output_ts,
             },
+            deprecation: None $(.or(Some($deprecated)))?,
             example: String::from($example),
             snippet: String::from(""),
             runner: $func_key::run,
@@ -53,13 +78,17 @@ macro_rules! define_command {
         implements_signing_capability: $implements_signing_capability:expr,
         implements_background_task_capability: $implements_background_task_capability:expr,
         // todo: add key field and use the input_name as the key, so the user can also provide a web-ui facing name
-        inputs: [$($input_name:ident: { documentation: $input_doc:expr, typing: $input_ts:expr, optional: $optional:expr, tainting: $tainting:expr, internal: $internal:expr $(, sensitive: $sensitive:expr)? }),*],
+        inputs: [$($input_name:ident: { documentation: $input_doc:expr, typing: $input_ts:expr, optional: $optional:expr, tainting: $tainting:expr, internal: $internal:expr $(, sensitive: $sensitive:expr)? $(, deprecated: $input_deprecated:expr)? }),*],
         outputs: [$($output_name:ident: { documentation: $output_doc:expr, typing: $output_ts:expr }),*],
-        example: $example:expr,
+        example: $example:expr
         $(, implements_cloud_service: $implements_cloud_service:expr)?
+        $(, deprecated: $deprecated:expr)?
+        $(, required_signer_capabilities: $required_signer_capabilities:expr)?
+        $(,)?
     }) => {
         {
         use txtx_addon_kit::types::commands::{PreCommandSpecification, CommandSpecification, CommandInput, CommandOutput, CommandExecutionClosure};
+        use txtx_addon_kit::types::signers::SignerCapabilities;
         let implements_signing_capability: bool = $implements_signing_capability;
         let implements_background_task_capability: bool = $implements_background_task_capability;
         PreCommandSpecification::Atomic(
@@ -89,7 +118,8 @@ macro_rules! define_command {
                     )?
                     is_sensitive
                 },
-                self_referencing: false
+                self_referencing: false,
+                deprecation: None $(.or(Some($input_deprecated)))?,
             }),*],
             default_inputs: CommandSpecification::default_inputs(),
             outputs: vec![$(CommandOutput {
@@ -117,6 +147,18 @@ macro_rules! define_command {
             aggregate_nested_execution_results: $func_key::aggregate_nested_execution_results,
             evaluate_post_conditions: $func_key::evaluate_post_conditions,
             example: String::from($example),
+            deprecation: None $(.or(Some($deprecated)))?,
+            // signing commands require signers able to sign transactions, unless specified
+            required_signer_capabilities: {
+                let mut required_signer_capabilities = SignerCapabilities {
+                    sign_transaction: implements_signing_capability,
+                    ..SignerCapabilities::none()
+                };
+                $(
+                    required_signer_capabilities = $required_signer_capabilities;
+                )?
+                required_signer_capabilities
+            },
         }
       )
     }
@@ -255,13 +297,16 @@ macro_rules! define_signer {
         name: $fn_name:expr,
         matcher: $matcher:expr,
         documentation: $doc:expr,
-        inputs: [$($input_name:ident: { documentation: $input_doc:expr, typing: $input_ts:expr, optional: $optional:expr, tainting: $tainting:expr, sensitive: $sensitive:expr }),*],
+        inputs: [$($input_name:ident: { documentation: $input_doc:expr, typing: $input_ts:expr, optional: $optional:expr, tainting: $tainting:expr, sensitive: $sensitive:expr $(, deprecated: $input_deprecated:expr)? }),*],
         outputs: [$($output_name:ident: { documentation: $output_doc:expr, typing: $output_ts:expr }),*],
         example: $example:expr
         $(, force_sequential_signing: $force_sequential_signing:expr)?
+        $(, finalize_partial_signatures: $finalize_partial_signatures:expr)?
+        $(, deprecated: $deprecated:expr)?
+        $(, capabilities: $capabilities:expr)?
     }) => {
         {
-          use txtx_addon_kit::types::signers::{SignerSpecification, SignerSignClosure};
+          use txtx_addon_kit::types::signers::{SignerSpecification, SignerSignClosure, SignerFinalizePartialSignaturesClosure, SignerCapabilities};
           use txtx_addon_kit::types::commands::{CommandInput, CommandOutput};
             SignerSpecification {
                 name: String::from($fn_name),
@@ -279,6 +324,7 @@ macro_rules! define_signer {
                     check_performed: false,
                     internal: false,
                     self_referencing: false,
+                    deprecation: None $(.or(Some($input_deprecated)))?,
                 }),*],
                 default_inputs: CommandSpecification::default_inputs(),
                 outputs: vec![$(CommandOutput {
@@ -292,7 +338,17 @@ macro_rules! define_signer {
                 check_signability: $func_key::check_signability,
                 sign: Box::new($func_key::sign),
                 example: String::from($example),
-                force_sequential_signing: false $(|| $force_sequential_signing)?
+                force_sequential_signing: false $(|| $force_sequential_signing)?,
+                finalize_partial_signatures: None::<SignerFinalizePartialSignaturesClosure>
+                    $(.or(Some($finalize_partial_signatures as SignerFinalizePartialSignaturesClosure)))?,
+                deprecation: None $(.or(Some($deprecated)))?,
+                capabilities: {
+                    let mut capabilities = SignerCapabilities::all();
+                    $(
+                        capabilities = $capabilities;
+                    )?
+                    capabilities
+                },
             }
         }
     };
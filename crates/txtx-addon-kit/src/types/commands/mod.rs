@@ -18,7 +18,7 @@ use indexmap::IndexMap;
 
 use crate::{
     constants::{
-        DESCRIPTION, MARKDOWN, MARKDOWN_FILEPATH, RUNBOOK_COMPLETE_ADDITIONAL_INFO,
+        ADDON_INSTANCE, DESCRIPTION, MARKDOWN, MARKDOWN_FILEPATH, RUNBOOK_COMPLETE_ADDITIONAL_INFO,
         SIGNED_MESSAGE_BYTES, SIGNED_TRANSACTION_BYTES,
     },
     helpers::hcl::{
@@ -31,16 +31,18 @@ use crate::{helpers::hcl::get_object_expression_key, types::stores::ValueStore};
 use super::{
     cloud_interface::CloudServiceContext,
     construct_type::ConstructType,
+    deprecation::Deprecation,
     diagnostics::Diagnostic,
     frontend::{
         ActionItemRequest, ActionItemRequestType, ActionItemRequestUpdate, ActionItemResponse,
         ActionItemResponseType, ActionItemStatus, Actions, BlockEvent, ProvideInputRequest,
-        ProvidedInputResponse, ReviewedInputResponse,
+        ProvidedFileResponse, ProvidedInputResponse, ReviewedInputResponse,
     },
     signers::{
         consolidate_nested_execution_result, consolidate_signer_activate_future_result,
         consolidate_signer_future_result, return_synchronous, PrepareSignedNestedExecutionResult,
-        SignerActionsFutureResult, SignerInstance, SignerSignFutureResult, SignersState,
+        SignerActionsFutureResult, SignerCapabilities, SignerInstance, SignerSignFutureResult,
+        SignersState,
     },
     stores::ValueMap,
     types::{ObjectDefinition, ObjectProperty, RunbookSupervisionContext, Type, Value},
@@ -56,6 +58,8 @@ pub use execution_conditions::{PreConditionEvaluatableInput, PreConditionEvaluat
 #[derive(Clone, Debug)]
 pub struct CommandExecutionResult {
     pub outputs: HashMap<String, Value>, // todo: change value to be Result<Value, Diagnostic>
+    /// Warnings and notes reported by the command, which don't interrupt the execution
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl Serialize for CommandExecutionResult {
@@ -72,7 +76,7 @@ impl Serialize for CommandExecutionResult {
 }
 impl CommandExecutionResult {
     pub fn new() -> Self {
-        Self { outputs: HashMap::new() }
+        Self { outputs: HashMap::new(), diagnostics: vec![] }
     }
 
     pub fn from<S: ToString, T: IntoIterator<Item = (S, Value)>>(default: T) -> Self {
@@ -80,13 +84,14 @@ impl CommandExecutionResult {
         for (key, value) in default {
             outputs.insert(key.to_string(), value);
         }
-        Self { outputs }
+        Self { outputs, diagnostics: vec![] }
     }
 
     pub fn append(&mut self, other: &mut CommandExecutionResult) {
         for (key, value) in other.outputs.drain() {
             self.outputs.insert(key, value);
         }
+        self.diagnostics.append(&mut other.diagnostics);
     }
 
     pub fn from_value_store(store: &ValueStore) -> Self {
@@ -94,7 +99,7 @@ impl CommandExecutionResult {
         for (key, value) in store.iter() {
             outputs.insert(key.clone(), value.clone());
         }
-        Self { outputs }
+        Self { outputs, diagnostics: vec![] }
     }
 
     pub fn insert(&mut self, key: &str, value: Value) {
@@ -164,6 +169,8 @@ impl DependencyExecutionResultCache {
 pub struct CommandInputsEvaluationResult {
     pub inputs: ValueStore,
     pub unevaluated_inputs: UnevaluatedInputsMap,
+    /// The member of the one-of type matched by the value of each input accepting several types
+    pub matched_types: IndexMap<String, Type>,
 }
 
 impl CommandInputsEvaluationResult {
@@ -230,12 +237,17 @@ impl CommandInputsEvaluationResult {
             inputs: ValueStore::new(&format!("{name}_inputs"), &Did::zero())
                 .with_defaults(defaults),
             unevaluated_inputs: UnevaluatedInputsMap::new(),
+            matched_types: IndexMap::new(),
         }
     }
 
     pub fn insert(&mut self, key: &str, value: Value) {
         self.inputs.insert(key, value);
     }
+
+    pub fn get_matched_type(&self, key: &str) -> Option<&Type> {
+        self.matched_types.get(key)
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
@@ -250,6 +262,7 @@ pub struct CommandInput {
     pub sensitive: bool,
     pub internal: bool,
     pub self_referencing: bool,
+    pub deprecation: Option<Deprecation>,
 }
 impl EvaluatableInput for CommandInput {
     fn documentation(&self) -> String {
@@ -264,6 +277,9 @@ impl EvaluatableInput for CommandInput {
     fn name(&self) -> String {
         self.name.clone()
     }
+    fn sensitive(&self) -> bool {
+        self.sensitive
+    }
 }
 
 impl CommandInput {
@@ -279,10 +295,18 @@ impl CommandInput {
     pub fn as_map(&self) -> Option<&ObjectDefinition> {
         self.typing.as_map()
     }
+    /// Checks the value of the input, reporting every violation along with its path from the
+    /// input, e.g. `event[2].field[0].indexed: expected bool, found string`.
     pub fn check_value(&self, value: &Value) -> Result<(), Diagnostic> {
-        self.typing.check_value(value).map_err(|e| {
-            Diagnostic::error_from_string(format!("error in input '{}': {}", self.name, e.message))
-        })
+        let violations = self.typing.type_violations(value, &self.name);
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(Diagnostic::error_from_string(format!(
+            "error in input '{}': {}",
+            self.name,
+            violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("; ")
+        )))
     }
 }
 
@@ -296,6 +320,7 @@ impl Serialize for CommandInput {
         ser.serialize_field("documentation", &self.documentation)?;
         ser.serialize_field("typing", &self.typing)?;
         ser.serialize_field("optional", &self.optional)?;
+        serialize_deprecation::<S>(&mut ser, &self.deprecation)?;
         ser.end()
     }
 }
@@ -383,6 +408,9 @@ pub struct CommandSpecification {
     pub implements_cloud_service: bool,
     pub aggregate_nested_execution_results: CommandAggregateNestedExecutionResults,
     pub evaluate_post_conditions: CommandEvaluatePostConditions,
+    pub deprecation: Option<Deprecation>,
+    /// Capabilities the signers bound to the command must have
+    pub required_signer_capabilities: SignerCapabilities,
 }
 
 #[allow(unpredictable_function_pointer_comparisons)]
@@ -411,6 +439,7 @@ impl CommandSpecification {
                 check_required: false,
                 sensitive: false,
                 self_referencing: false,
+                deprecation: None,
             },
             CommandInput {
                 name: MARKDOWN.into(),
@@ -423,6 +452,7 @@ impl CommandSpecification {
                 check_required: false,
                 sensitive: false,
                 self_referencing: false,
+                deprecation: None,
             },
             CommandInput {
                 name: MARKDOWN_FILEPATH.into(),
@@ -435,6 +465,7 @@ impl CommandSpecification {
                 check_required: false,
                 sensitive: false,
                 self_referencing: false,
+                deprecation: None,
             },
             CommandInput {
                 name: "labels".into(),
@@ -447,6 +478,7 @@ impl CommandSpecification {
                 check_required: false,
                 sensitive: false,
                 self_referencing: false,
+                deprecation: None,
             },
             CommandInput {
                 name: "environments".into(),
@@ -459,6 +491,7 @@ impl CommandSpecification {
                 check_required: false,
                 sensitive: false,
                 self_referencing: false,
+                deprecation: None,
             },
             CommandInput {
                 name: "sensitive".into(),
@@ -471,6 +504,7 @@ impl CommandSpecification {
                 check_required: false,
                 sensitive: false,
                 self_referencing: false,
+                deprecation: None,
             },
             CommandInput {
                 name: "group".into(),
@@ -483,6 +517,7 @@ impl CommandSpecification {
                 check_required: false,
                 sensitive: false,
                 self_referencing: false,
+                deprecation: None,
             },
             CommandInput {
                 name: "depends_on".into(),
@@ -495,6 +530,20 @@ impl CommandSpecification {
                 check_required: false,
                 sensitive: false,
                 self_referencing: false,
+                deprecation: None,
+            },
+            CommandInput {
+                name: ADDON_INSTANCE.into(),
+                documentation: "The label of the addon block providing the defaults of the command, e.g. 'base' for `addon \"evm\" \"base\" { ... }`".into(),
+                typing: Type::string(),
+                optional: true,
+                tainting: true,
+                internal: false,
+                check_performed: false,
+                check_required: false,
+                sensitive: false,
+                self_referencing: false,
+                deprecation: None,
             },
         ]
     }
@@ -512,10 +561,22 @@ impl Serialize for CommandSpecification {
         ser.serialize_field("inputs", &self.inputs)?;
         ser.serialize_field("outputs", &self.outputs)?;
         ser.serialize_field("example", &self.example)?;
+        serialize_deprecation::<S>(&mut ser, &self.deprecation)?;
         ser.end()
     }
 }
 
+/// Only lists the deprecation of deprecated items, leaving the others untouched.
+fn serialize_deprecation<S: Serializer>(
+    ser: &mut S::SerializeStruct,
+    deprecation: &Option<Deprecation>,
+) -> Result<(), S::Error> {
+    match deprecation {
+        Some(deprecation) => ser.serialize_field("deprecation", deprecation),
+        None => ser.skip_field("deprecation"),
+    }
+}
+
 impl Serialize for CompositeCommandSpecification {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -631,6 +692,7 @@ pub type CommandSignedPrepareNestedExecution = fn(
     &ValueStore,
     &HashMap<ConstructDid, SignerInstance>,
     SignersState,
+    &AuthorizationContext,
 ) -> PrepareSignedNestedExecutionResult;
 
 pub type CommandPrepareNestedExecution =
@@ -827,6 +889,12 @@ impl CommandInstance {
         group.value.to_string()
     }
 
+    /// The label of the addon block whose defaults apply to this command, set with `addon_instance`.
+    pub fn get_addon_instance(&self) -> Option<String> {
+        let attribute = self.block.body.get_attribute(ADDON_INSTANCE)?;
+        attribute.value.as_str().map(|label| label.to_string())
+    }
+
     pub fn evaluate_pre_conditions(
         &self,
         construct_did: &ConstructDid,
@@ -895,6 +963,7 @@ impl CommandInstance {
                                             default_value: Some(updated_value.clone()),
                                             input_name: input_name.clone(),
                                             typing: updated_value.get_type(),
+                                            sensitive: false,
                                         },
                                     ))
                                     .set_status(ActionItemStatus::Success(None));
@@ -907,6 +976,16 @@ impl CommandInstance {
                                 }
                             }
                         }
+                        ActionItemResponseType::ProvideFile(ProvidedFileResponse {
+                            filename,
+                            ..
+                        }) => {
+                            // the decoded file is inserted in the evaluated inputs by the runloop
+                            let action_item_update =
+                                ActionItemRequestUpdate::from_id(&action_item_id)
+                                    .set_status(ActionItemStatus::Success(Some(filename.clone())));
+                            consolidated_actions.push_action_item_update(action_item_update);
+                        }
                         ActionItemResponseType::ProvideSignedTransaction(response) => {
                             match &response.signed_transaction_bytes {
                                 Some(bytes) => values
@@ -983,6 +1062,9 @@ impl CommandInstance {
                 ActionItemRequestType::ProvideInput(_) => {
                     request.action_status = status.clone();
                 }
+                ActionItemRequestType::ProvideFile(_) => {
+                    request.action_status = status.clone();
+                }
                 ActionItemRequestType::ProvidePublicKey(_) => {
                     if success {
                         request.action_status = status.clone();
@@ -1003,6 +1085,11 @@ impl CommandInstance {
                         request.action_status = status.clone();
                     }
                 }
+                ActionItemRequestType::DisplayMarkdown(_) => {
+                    if success {
+                        request.action_status = status.clone();
+                    }
+                }
                 _ => unreachable!(),
             }
         }
@@ -1015,6 +1102,7 @@ impl CommandInstance {
         evaluated_inputs: &CommandInputsEvaluationResult,
         signers: SignersState,
         signer_instances: &HashMap<ConstructDid, SignerInstance>,
+        auth_context: &AuthorizationContext,
     ) -> Result<(SignersState, Vec<(ConstructDid, ValueStore)>), (SignersState, Diagnostic)> {
         let values = ValueStore::new(&self.name, &construct_did.value())
             .with_defaults(&evaluated_inputs.inputs.defaults)
@@ -1027,6 +1115,7 @@ impl CommandInstance {
             &values,
             signer_instances,
             signers,
+            auth_context,
         );
         return consolidate_nested_execution_result(future, self.block.span()).await;
     }
@@ -1062,6 +1151,21 @@ impl CommandInstance {
             .with_inputs(&evaluated_inputs.inputs.inputs)
             .append_inputs(&nested_evaluation_values.inputs);
 
+        // signers are bound to the command by their construct did, and shouldn't be prompted for
+        // a command they won't be able to sign
+        for (signer_did, signer_instance) in signer_instances.iter() {
+            let signer_did = signer_did.to_string();
+            let is_bound = evaluated_inputs
+                .inputs
+                .iter()
+                .any(|(_, value)| value.as_string().map_or(false, |did| did == signer_did));
+            if is_bound {
+                signer_instance
+                    .check_capabilities(self)
+                    .map_err(|diag| (signers.clone(), diag.set_span_range(self.block.span())))?;
+            }
+        }
+
         // TODO
         let mut consolidated_actions = Actions::none();
         match action_item_response {
@@ -1078,16 +1182,30 @@ impl CommandInstance {
                             }
                         }
                         ActionItemResponseType::ProvideInput(update) => {
-                            let action_item_update =
-                                ActionItemRequestUpdate::from_id(&action_item_id)
-                                    .set_type(ActionItemRequestType::ProvideInput(
-                                        ProvideInputRequest {
-                                            default_value: Some(update.updated_value.clone()),
-                                            input_name: update.input_name.clone(),
-                                            typing: update.updated_value.get_type(),
-                                        },
-                                    ))
-                                    .set_status(ActionItemStatus::Success(None));
+                            // sensitive values (passphrases...) are never sent back to the
+                            // supervisor
+                            let is_sensitive = action_item_requests.map_or(false, |requests| {
+                                requests.iter().any(|request| {
+                                    request.id.eq(action_item_id)
+                                        && request
+                                            .action_type
+                                            .as_provide_input()
+                                            .map_or(false, |request| request.sensitive)
+                                })
+                            });
+                            let mut action_item_update =
+                                ActionItemRequestUpdate::from_id(&action_item_id);
+                            if !is_sensitive {
+                                action_item_update.set_type(ActionItemRequestType::ProvideInput(
+                                    ProvideInputRequest {
+                                        default_value: Some(update.updated_value.clone()),
+                                        input_name: update.input_name.clone(),
+                                        typing: update.updated_value.get_type(),
+                                        sensitive: false,
+                                    },
+                                ));
+                            }
+                            action_item_update.set_status(ActionItemStatus::Success(None));
                             consolidated_actions.push_action_item_update(action_item_update);
                         }
                         ActionItemResponseType::ProvideSignedTransaction(_) => {
@@ -1191,6 +1309,11 @@ impl CommandInstance {
                         request.action_status = status.clone();
                     }
                 }
+                ActionItemRequestType::DisplayMarkdown(_) => {
+                    if success {
+                        request.action_status = status.clone();
+                    }
+                }
                 // idk what this does
                 ActionItemRequestType::VerifyThirdPartySignature(_) => {
                     // if success {
@@ -1404,6 +1527,7 @@ pub trait CommandImplementation {
         _values: &ValueStore,
         _signers_instances: &HashMap<ConstructDid, SignerInstance>,
         signers_state: SignersState,
+        _auth_ctx: &AuthorizationContext,
     ) -> PrepareSignedNestedExecutionResult {
         let signer_state = signers_state
             .get_first_signer()
@@ -0,0 +1,99 @@
+use std::fmt::Display;
+
+use super::diagnostics::Diagnostic;
+
+/// Marks a command, signer, function or input as deprecated, so that runbooks using it are
+/// warned ahead of its removal.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deprecation {
+    /// Version of the addon deprecating the item
+    pub deprecated_since: String,
+    /// Version of the addon expected to remove the item
+    pub removal_planned: Option<String>,
+    /// Item to use instead, e.g. `evm::deploy_contract`
+    pub replacement: Option<String>,
+}
+
+impl Deprecation {
+    pub fn since(version: &str) -> Self {
+        Deprecation {
+            deprecated_since: version.to_string(),
+            removal_planned: None,
+            replacement: None,
+        }
+    }
+
+    pub fn removal_planned(mut self, version: &str) -> Self {
+        self.removal_planned = Some(version.to_string());
+        self
+    }
+
+    pub fn replaced_by(mut self, replacement: &str) -> Self {
+        self.replacement = Some(replacement.to_string());
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeprecatedItemKind {
+    Command,
+    Signer,
+    Function,
+    Input,
+}
+
+impl Display for DeprecatedItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeprecatedItemKind::Command => write!(f, "command"),
+            DeprecatedItemKind::Signer => write!(f, "signer"),
+            DeprecatedItemKind::Function => write!(f, "function"),
+            DeprecatedItemKind::Input => write!(f, "input"),
+        }
+    }
+}
+
+/// A deprecated item used by a construct of a runbook.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeprecationUsage {
+    pub kind: DeprecatedItemKind,
+    /// Name of the item, e.g. `evm::deploy_contract`, or `evm::deploy_contract.abi` for an input
+    pub item: String,
+    /// Construct using the item, e.g. `action.deploy`
+    pub construct: String,
+    #[serde(flatten)]
+    pub deprecation: Deprecation,
+}
+
+impl DeprecationUsage {
+    pub fn new(
+        kind: DeprecatedItemKind,
+        item: impl Into<String>,
+        construct: impl Into<String>,
+        deprecation: &Deprecation,
+    ) -> Self {
+        DeprecationUsage {
+            kind,
+            item: item.into(),
+            construct: construct.into(),
+            deprecation: deprecation.clone(),
+        }
+    }
+
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        let mut message = format!(
+            "{}: {} '{}' is deprecated since {}",
+            self.construct, self.kind, self.item, self.deprecation.deprecated_since
+        );
+        if let Some(version) = &self.deprecation.removal_planned {
+            message.push_str(&format!(" and will be removed in {}", version));
+        }
+        if let Some(replacement) = &self.deprecation.replacement {
+            message.push_str(&format!("; use '{}' instead", replacement));
+        }
+        Diagnostic::warning_from_string(message)
+    }
+}
@@ -1,11 +1,13 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
-/// Severity level for diagnostics
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+/// Severity level for diagnostics. Only errors interrupt an execution: warnings and notes are
+/// reported along with it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum DiagnosticLevel {
     Note,
     Warning,
+    #[default]
     Error,
 }
 
@@ -19,6 +21,21 @@ impl Display for DiagnosticLevel {
     }
 }
 
+/// Class of failure reported by a diagnostic, for callers to tell failures apart
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// The execution failed, e.g. a transaction reverted
+    Execution,
+    /// The runbook is invalid: syntax errors, unknown references, invalid inputs
+    Validation,
+    /// The workspace is misconfigured: manifest, environments, input files
+    Configuration,
+    /// The operator cancelled the execution
+    Cancelled,
+    /// An operation did not complete in time
+    Timeout,
+}
+
 /// Span information with line/column ranges
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct DiagnosticSpan {
@@ -57,3 +74,50 @@ impl RelatedLocation {
         Self { file, line, column, message }
     }
 }
+
+/// A stable code identifying a class of diagnostics, e.g. `EVM201` for a nonce too low.
+///
+/// Codes are made of the uppercased namespace of the crate or addon emitting them (`CORE`,
+/// `EVM`, `SVM`, ...), followed by three digits, the hundreds telling the range of the failure:
+/// - `0xx`: configuration of the workspace (manifest, environments, addons)
+/// - `1xx`: validation and evaluation of the runbook
+/// - `2xx`: execution, such as the broadcast of a transaction
+///
+/// Codes are never reused for another class of diagnostics once released.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiagnosticCode {
+    pub code: &'static str,
+    pub summary: &'static str,
+    pub help_url: Option<&'static str>,
+}
+
+impl DiagnosticCode {
+    pub const fn new(code: &'static str, summary: &'static str) -> Self {
+        DiagnosticCode { code, summary, help_url: None }
+    }
+
+    pub const fn with_help_url(mut self, help_url: &'static str) -> Self {
+        self.help_url = Some(help_url);
+        self
+    }
+
+    /// The namespace prefix of the code, e.g. `EVM` for `EVM201`
+    pub fn namespace(&self) -> &'static str {
+        self.code.trim_end_matches(|c: char| c.is_ascii_digit())
+    }
+
+    /// The numeric part of the code, e.g. `201` for `EVM201`
+    pub fn number(&self) -> Option<u16> {
+        let digits = &self.code[self.namespace().len()..];
+        if digits.len() != 3 {
+            return None;
+        }
+        digits.parse().ok()
+    }
+}
+
+impl Display for DiagnosticCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.code, self.summary)
+    }
+}
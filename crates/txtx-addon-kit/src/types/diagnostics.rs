@@ -5,13 +5,21 @@ use hcl_edit::{expr::Expression, structure::Block};
 use crate::helpers::fs::FileLocation;
 
 // Re-export diagnostic types for use and convenience
-pub use super::diagnostic_types::{DiagnosticLevel, DiagnosticSpan, RelatedLocation};
+pub use super::diagnostic_types::{
+    DiagnosticCode, DiagnosticKind, DiagnosticLevel, DiagnosticSpan, RelatedLocation,
+};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Diagnostic {
     pub level: DiagnosticLevel,
+    /// Class of the failure, if known
+    #[serde(default)]
+    pub kind: Option<DiagnosticKind>,
     pub message: String,
     pub code: Option<String>,
+    /// Link to the documentation of the code of the diagnostic
+    #[serde(default)]
+    pub help_url: Option<String>,
     pub span: Option<DiagnosticSpan>,
     #[serde(skip)]
     span_range: Option<Range<usize>>,
@@ -55,8 +63,10 @@ impl Diagnostic {
     pub fn error_from_string(message: String) -> Diagnostic {
         Diagnostic {
             level: DiagnosticLevel::Error,
+            kind: None,
             message,
             code: None,
+            help_url: None,
             span: None,
             span_range: None,
             location: None,
@@ -75,8 +85,10 @@ impl Diagnostic {
     pub fn warning_from_string(message: String) -> Diagnostic {
         Diagnostic {
             level: DiagnosticLevel::Warning,
+            kind: None,
             message,
             code: None,
+            help_url: None,
             span: None,
             span_range: None,
             location: None,
@@ -95,8 +107,10 @@ impl Diagnostic {
     pub fn note_from_string(message: String) -> Diagnostic {
         Diagnostic {
             level: DiagnosticLevel::Note,
+            kind: None,
             message,
             code: None,
+            help_url: None,
             span: None,
             span_range: None,
             location: None,
@@ -125,11 +139,36 @@ impl Diagnostic {
         Self::note_from_string(message.into())
     }
 
+    pub fn with_kind(mut self, kind: DiagnosticKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Sets the class of the failure, unless already known.
+    pub fn with_default_kind(mut self, kind: DiagnosticKind) -> Self {
+        self.kind.get_or_insert(kind);
+        self
+    }
+
     pub fn with_code(mut self, code: impl AsRef<str>) -> Self {
         self.code = Some(code.as_ref().to_string());
         self
     }
 
+    /// Sets the code of the diagnostic, along with its documentation link if any.
+    pub fn with_diagnostic_code(mut self, code: &DiagnosticCode) -> Self {
+        self.code = Some(code.code.to_string());
+        if let Some(help_url) = code.help_url {
+            self.help_url = Some(help_url.to_string());
+        }
+        self
+    }
+
+    pub fn with_help_url(mut self, help_url: impl Into<String>) -> Self {
+        self.help_url = Some(help_url.into());
+        self
+    }
+
     pub fn with_file(mut self, file: impl AsRef<str>) -> Self {
         self.file = Some(file.as_ref().to_string());
         self
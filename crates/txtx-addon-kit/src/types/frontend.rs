@@ -1,4 +1,9 @@
-use std::{borrow::BorrowMut, collections::BTreeMap, fmt::Display};
+use std::{
+    borrow::BorrowMut,
+    collections::BTreeMap,
+    fmt::Display,
+    time::{Duration, Instant},
+};
 
 use crate::{
     constants::ACTION_ITEM_BEGIN_FLOW,
@@ -7,10 +12,11 @@ use crate::{
 
 use super::{
     block_id::BlockId,
-    diagnostics::Diagnostic,
+    diagnostics::{Diagnostic, DiagnosticLevel},
     types::{Type, Value},
     ConstructDid, Did,
 };
+use indexmap::IndexMap;
 use serde::Serialize;
 use uuid::Uuid;
 
@@ -24,6 +30,19 @@ pub enum BlockEvent {
     LogEvent(LogEvent),
     Modal(Block),
     Error(Block),
+    UpdateConstructStatuses(Vec<ConstructStatusUpdate>),
+    Heartbeat(HeartbeatEvent),
+}
+
+/// Sent by the runtime while long-running background tasks are making progress.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HeartbeatEvent {
+    pub construct_did: ConstructDid,
+    /// Time elapsed since the background task started.
+    pub elapsed: Duration,
+    /// What the task is currently waiting on, if known.
+    pub detail: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -122,6 +141,13 @@ impl LogEvent {
             LogEvent::Transient(event) => &event.namespace,
         }
     }
+
+    pub fn progress(&self) -> Option<&LogProgress> {
+        match self {
+            LogEvent::Static(_) => None,
+            LogEvent::Transient(event) => event.progress.as_ref(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,6 +181,74 @@ pub struct TransientLogEvent {
     pub uuid: Uuid,
     pub status: TransientLogEventStatus,
     pub namespace: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub progress: Option<LogProgress>,
+}
+
+/// Structured progress of a long running operation, attached to a transient log event so that
+/// frontends can render more than the status message.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogProgress {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_steps: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_steps: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub percentage: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eta_seconds: Option<u64>,
+}
+
+impl LogProgress {
+    pub fn steps(completed_steps: u64, total_steps: u64) -> Self {
+        LogProgress {
+            completed_steps: Some(completed_steps),
+            total_steps: Some(total_steps),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_percentage(mut self, percentage: f64) -> Self {
+        self.percentage = Some(percentage);
+        self
+    }
+
+    pub fn with_eta_seconds(mut self, eta_seconds: u64) -> Self {
+        self.eta_seconds = Some(eta_seconds);
+        self
+    }
+
+    /// The percentage of completion, computed from the steps when not explicitly set.
+    pub fn percentage(&self) -> Option<f64> {
+        if let Some(percentage) = self.percentage {
+            return Some(percentage.clamp(0.0, 100.0));
+        }
+        match (self.completed_steps, self.total_steps) {
+            (Some(completed), Some(total)) if total > 0 => {
+                Some((completed as f64 / total as f64 * 100.0).min(100.0))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for LogProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = vec![];
+        match (self.completed_steps, self.total_steps) {
+            (Some(completed), Some(total)) => parts.push(format!("{}/{}", completed, total)),
+            (Some(completed), None) => parts.push(completed.to_string()),
+            _ => {}
+        }
+        if let Some(percentage) = self.percentage() {
+            parts.push(format!("{:.0}%", percentage));
+        }
+        if let Some(eta_seconds) = self.eta_seconds {
+            parts.push(format!("ETA {}s", eta_seconds));
+        }
+        write!(f, "{}", parts.join(" "))
+    }
 }
 
 impl TransientLogEvent {
@@ -179,6 +273,10 @@ impl TransientLogEvent {
             TransientLogEventStatus::Failure(_) => "Failure".into(),
         }
     }
+    pub fn with_progress(mut self, progress: LogProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
 }
 
 impl TransientLogEvent {
@@ -196,6 +294,7 @@ impl TransientLogEvent {
                 summary: summary.to_string(),
             }),
             namespace: namespace.to_string(),
+            progress: None,
         }
     }
 
@@ -213,6 +312,7 @@ impl TransientLogEvent {
                 summary: summary.to_string(),
             }),
             namespace: namespace.to_string(),
+            progress: None,
         }
     }
 
@@ -230,6 +330,7 @@ impl TransientLogEvent {
                 summary: summary.to_string(),
             }),
             namespace: namespace.to_string(),
+            progress: None,
         }
     }
 }
@@ -280,6 +381,19 @@ impl LogDispatcher {
         )));
     }
 
+    /// Same as `pending_info`, with the structured progress of the operation.
+    pub fn pending_progress(
+        &self,
+        summary: impl ToString,
+        message: impl ToString,
+        progress: LogProgress,
+    ) {
+        let _ = self.tx.try_send(BlockEvent::LogEvent(LogEvent::Transient(
+            TransientLogEvent::pending_info(self.uuid, summary, message, &self.namespace)
+                .with_progress(progress),
+        )));
+    }
+
     pub fn success_info(&self, summary: impl ToString, message: impl ToString) {
         let _ = self.tx.try_send(BlockEvent::LogEvent(LogEvent::Transient(
             TransientLogEvent::success_info(self.uuid, summary, message, &self.namespace),
@@ -380,6 +494,137 @@ impl BlockEvent {
     }
 }
 
+/// The execution status of a command or signer construct of the active runbook.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConstructStatus {
+    Pending,
+    AwaitingInput,
+    Executing,
+    BackgroundTaskRunning,
+    Completed,
+    Failed,
+    Skipped,
+}
+
+impl ConstructStatus {
+    pub fn is_finished(&self) -> bool {
+        match self {
+            ConstructStatus::Completed | ConstructStatus::Failed | ConstructStatus::Skipped => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstructStatusUpdate {
+    pub construct_did: ConstructDid,
+    /// Type of construct (e.g. `action`, `variable` or `signer`)
+    pub construct_type: String,
+    pub name: String,
+    pub namespace: String,
+    pub matcher: String,
+    /// The names of the constructs referenced by the inputs of this construct.
+    pub dependencies: Vec<String>,
+    pub status: ConstructStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConstructStatusEntry {
+    pub construct: ConstructStatusUpdate,
+    pub started_at: Option<Instant>,
+    pub duration: Option<Duration>,
+    /// When the background task of this construct last sent a heartbeat.
+    pub last_heartbeat: Option<Instant>,
+    pub heartbeat_detail: Option<String>,
+}
+
+impl ConstructStatusEntry {
+    /// Describes when the running background task of this construct last reported progress,
+    /// e.g. `last update 45s ago`.
+    pub fn last_update(&self) -> Option<String> {
+        if self.construct.status != ConstructStatus::BackgroundTaskRunning {
+            return None;
+        }
+        let last_heartbeat = self.last_heartbeat?;
+        Some(describe_last_update(last_heartbeat.elapsed(), self.heartbeat_detail.as_deref()))
+    }
+}
+
+pub fn describe_last_update(elapsed: Duration, detail: Option<&str>) -> String {
+    match detail {
+        Some(detail) => format!("last update {}s ago ({})", elapsed.as_secs(), detail),
+        None => format!("last update {}s ago", elapsed.as_secs()),
+    }
+}
+
+/// The latest status of each construct, in the order they were first reported.
+/// Durations are measured from the first time a construct starts executing.
+#[derive(Debug, Clone, Default)]
+pub struct ConstructStatusStore {
+    entries: IndexMap<ConstructDid, ConstructStatusEntry>,
+}
+
+impl ConstructStatusStore {
+    pub fn new() -> Self {
+        ConstructStatusStore { entries: IndexMap::new() }
+    }
+
+    pub fn apply(&mut self, update: ConstructStatusUpdate) {
+        let entry = self.entries.entry(update.construct_did.clone()).or_insert_with(|| {
+            ConstructStatusEntry {
+                construct: update.clone(),
+                started_at: None,
+                duration: None,
+                last_heartbeat: None,
+                heartbeat_detail: None,
+            }
+        });
+        match update.status {
+            ConstructStatus::Executing | ConstructStatus::BackgroundTaskRunning => {
+                if entry.started_at.is_none() {
+                    entry.started_at = Some(Instant::now());
+                }
+                entry.duration = None;
+            }
+            ConstructStatus::Completed | ConstructStatus::Failed => {
+                entry.duration = entry.started_at.map(|started_at| started_at.elapsed());
+            }
+            ConstructStatus::Pending => {
+                entry.started_at = None;
+                entry.duration = None;
+                entry.last_heartbeat = None;
+                entry.heartbeat_detail = None;
+            }
+            ConstructStatus::AwaitingInput | ConstructStatus::Skipped => {}
+        }
+        entry.construct = update;
+    }
+
+    /// Records a heartbeat of a running background task. Heartbeats of constructs that aren't
+    /// tracked yet are ignored.
+    pub fn apply_heartbeat(&mut self, heartbeat: &HeartbeatEvent) {
+        let Some(entry) = self.entries.get_mut(&heartbeat.construct_did) else {
+            return;
+        };
+        entry.last_heartbeat = Some(Instant::now());
+        entry.heartbeat_detail = heartbeat.detail.clone();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &ConstructStatusEntry> {
+        self.entries.values()
+    }
+
+    pub fn get(&self, construct_did: &ConstructDid) -> Option<&ConstructStatusEntry> {
+        self.entries.get(construct_did)
+    }
+}
+
 pub enum RunbookExecutionState {
     RunbookGenesis,
     RunbookGlobalsUpdated,
@@ -734,24 +979,44 @@ pub struct ErrorPanelData {
     pub title: String,
     pub description: String,
     pub groups: Vec<ActionGroup>,
+    /// The highest level of the diagnostics of the panel, for warnings to be styled apart
+    #[serde(default)]
+    pub level: DiagnosticLevel,
 }
 
 impl ErrorPanelData {
     pub fn from_diagnostics(diagnostics: &Vec<Diagnostic>) -> Self {
         let mut diag_actions = vec![];
         for (i, diag) in diagnostics.iter().enumerate() {
+            let status = match diag.level {
+                DiagnosticLevel::Error => ActionItemStatus::Error(diag.clone()),
+                DiagnosticLevel::Warning | DiagnosticLevel::Note => {
+                    ActionItemStatus::Warning(diag.clone())
+                }
+            };
             let mut action = ActionItemRequestType::DisplayErrorLog(DisplayErrorLogRequest {
                 diagnostic: diag.clone(),
             })
             .to_request("", "diagnostic")
-            .with_status(ActionItemStatus::Error(diag.clone()));
+            .with_status(status);
 
             action.index = (i + 1) as u16;
             diag_actions.push(action);
         }
+        let level = diagnostics.iter().map(|diag| diag.level.clone()).max().unwrap_or_default();
+        let (title, description) = match level {
+            DiagnosticLevel::Error => (
+                "EXECUTION ERROR",
+                "Review the following execution errors and restart the runbook.",
+            ),
+            DiagnosticLevel::Warning | DiagnosticLevel::Note => {
+                ("EXECUTION WARNING", "Review the following execution warnings.")
+            }
+        };
         ErrorPanelData {
-            title: "EXECUTION ERROR".into(),
-            description: "Review the following execution errors and restart the runbook.".into(),
+            level,
+            title: title.into(),
+            description: description.into(),
             groups: vec![ActionGroup {
                 title: "".into(),
                 sub_groups: vec![ActionSubGroup {
@@ -1061,6 +1326,42 @@ impl Actions {
         self.store.append(&mut actions.store);
     }
 
+    /// Sets the display form of the values reviewed or displayed by the action items, computed by
+    /// `format` (see [ActionItemRequestType::format_value]).
+    pub fn format_values(&mut self, format: &dyn Fn(&Value) -> Option<String>) {
+        let format_sub_group = |sub_group: &mut ActionSubGroup| {
+            for item in sub_group.action_items.iter_mut() {
+                item.action_type.format_value(format);
+            }
+        };
+        let format_groups = |groups: &mut Vec<ActionGroup>| {
+            groups
+                .iter_mut()
+                .flat_map(|group| group.sub_groups.iter_mut())
+                .for_each(format_sub_group)
+        };
+        for action in self.store.iter_mut() {
+            match action {
+                ActionType::UpdateActionItemRequest(update) => {
+                    if let Some(action_type) = update.action_type.as_mut() {
+                        action_type.format_value(format);
+                    }
+                }
+                ActionType::AppendSubGroup(sub_group) => format_sub_group(sub_group),
+                ActionType::AppendGroup(group) => {
+                    group.sub_groups.iter_mut().for_each(format_sub_group)
+                }
+                ActionType::AppendItem(item, _, _) => item.action_type.format_value(format),
+                ActionType::NewBlock(panel) => format_groups(&mut panel.groups),
+                ActionType::NewModal(block) => {
+                    if let Panel::ModalPanel(modal) = &mut block.panel {
+                        format_groups(&mut modal.groups)
+                    }
+                }
+            }
+        }
+    }
+
     pub fn push_modal(&mut self, block: Block) {
         self.store.push(ActionType::NewModal(block));
     }
@@ -1534,6 +1835,7 @@ impl Actions {
 pub enum ActionItemRequestType {
     ReviewInput(ReviewInputRequest),
     ProvideInput(ProvideInputRequest),
+    ProvideFile(ProvideFileRequest),
     PickInputOption(PickInputOptionRequest),
     ProvidePublicKey(ProvidePublicKeyRequest),
     ProvideSignedTransaction(ProvideSignedTransactionRequest),
@@ -1542,6 +1844,7 @@ pub enum ActionItemRequestType {
     SendTransaction(SendTransactionRequest),
     DisplayOutput(DisplayOutputRequest),
     DisplayErrorLog(DisplayErrorLogRequest),
+    DisplayMarkdown(DisplayMarkdownRequest),
     OpenModal(OpenModalData),
     ValidateBlock(ValidateBlockData),
     ValidateModal,
@@ -1549,6 +1852,19 @@ pub enum ActionItemRequestType {
 }
 
 impl ActionItemRequestType {
+    /// Sets the display form of the value reviewed or displayed by the item, if any.
+    pub fn format_value(&mut self, format: &dyn Fn(&Value) -> Option<String>) {
+        match self {
+            ActionItemRequestType::ReviewInput(request) => {
+                request.formatted_value = format(&request.value)
+            }
+            ActionItemRequestType::DisplayOutput(request) => {
+                request.formatted_value = format(&request.value)
+            }
+            _ => {}
+        }
+    }
+
     pub fn to_request(
         self,
         construct_instance_name: &str,
@@ -1568,6 +1884,12 @@ impl ActionItemRequestType {
             _ => None,
         }
     }
+    pub fn as_provide_file(&self) -> Option<&ProvideFileRequest> {
+        match &self {
+            ActionItemRequestType::ProvideFile(value) => Some(value),
+            _ => None,
+        }
+    }
     pub fn as_pick_input(&self) -> Option<&PickInputOptionRequest> {
         match &self {
             ActionItemRequestType::PickInputOption(value) => Some(value),
@@ -1616,6 +1938,12 @@ impl ActionItemRequestType {
             _ => None,
         }
     }
+    pub fn as_display_markdown(&self) -> Option<&DisplayMarkdownRequest> {
+        match &self {
+            ActionItemRequestType::DisplayMarkdown(value) => Some(value),
+            _ => None,
+        }
+    }
     pub fn as_open_modal(&self) -> Option<&OpenModalData> {
         match &self {
             ActionItemRequestType::OpenModal(value) => Some(value),
@@ -1623,6 +1951,31 @@ impl ActionItemRequestType {
         }
     }
 
+    /// The `ActionItemResponseType` variant completing this request, if any: display-only
+    /// requests don't expect a response.
+    pub fn expected_response_type(&self) -> Option<&'static str> {
+        match self {
+            ActionItemRequestType::ReviewInput(_) => Some("ReviewInput"),
+            ActionItemRequestType::ProvideInput(_) => Some("ProvideInput"),
+            ActionItemRequestType::ProvideFile(_) => Some("ProvideFile"),
+            ActionItemRequestType::PickInputOption(_) => Some("PickInputOption"),
+            ActionItemRequestType::ProvidePublicKey(_) => Some("ProvidePublicKey"),
+            ActionItemRequestType::ProvideSignedTransaction(_) => Some("ProvideSignedTransaction"),
+            ActionItemRequestType::VerifyThirdPartySignature(_) => {
+                Some("VerifyThirdPartySignature")
+            }
+            ActionItemRequestType::ProvideSignedMessage(_) => Some("ProvideSignedMessage"),
+            ActionItemRequestType::SendTransaction(_) => Some("SendTransaction"),
+            ActionItemRequestType::ValidateBlock(_) => Some("ValidateBlock"),
+            ActionItemRequestType::ValidateModal => Some("ValidateModal"),
+            ActionItemRequestType::DisplayOutput(_)
+            | ActionItemRequestType::DisplayErrorLog(_)
+            | ActionItemRequestType::DisplayMarkdown(_)
+            | ActionItemRequestType::OpenModal(_)
+            | ActionItemRequestType::BeginFlow(_) => None,
+        }
+    }
+
     ///
     /// Serialize the immutable properties of the type to be used for an `ActionItemRequest`'s `BlockId`.
     ///
@@ -1636,6 +1989,12 @@ impl ActionItemRequestType {
                 val.input_name,
                 serde_json::to_string(&val.typing).unwrap() //todo: make to_string prop?
             ),
+            ActionItemRequestType::ProvideFile(val) => format!(
+                "ProvideFile({}-{}-{})",
+                val.input_name,
+                val.expected_extensions.join(","),
+                val.max_size.map(|s| s.to_string()).unwrap_or("None".to_string())
+            ),
             ActionItemRequestType::PickInputOption(_) => format!("PickInputOption"),
             ActionItemRequestType::ProvidePublicKey(val) => format!(
                 "ProvidePublicKey({}-{}-{})",
@@ -1700,6 +2059,11 @@ impl ActionItemRequestType {
             ActionItemRequestType::DisplayErrorLog(val) => {
                 format!("DisplayErrorLog({})", val.diagnostic.to_string())
             }
+            ActionItemRequestType::DisplayMarkdown(val) => format!(
+                "DisplayMarkdown({}-{})",
+                val.markdown,
+                val.links.iter().map(|l| l.url.clone()).collect::<Vec<_>>().join(",")
+            ),
             ActionItemRequestType::OpenModal(val) => {
                 format!("OpenModal({}-{})", val.modal_uuid, val.title)
             }
@@ -1755,6 +2119,19 @@ impl ActionItemRequestType {
                     None
                 }
             }
+            ActionItemRequestType::ProvideFile(new) => {
+                let Some(existing) = existing_item.as_provide_file() else {
+                    unreachable!("cannot change action item request type")
+                };
+                if new.description != existing.description {
+                    if new.input_name != existing.input_name {
+                        unreachable!("cannot change provide file request input_name")
+                    }
+                    Some(new_type.clone())
+                } else {
+                    None
+                }
+            }
             ActionItemRequestType::PickInputOption(_) => {
                 let Some(_) = existing_item.as_pick_input() else {
                     unreachable!("cannot change action item request type")
@@ -1889,6 +2266,7 @@ impl ActionItemRequestType {
             }
             ActionItemRequestType::DisplayOutput(_) => None,
             ActionItemRequestType::DisplayErrorLog(_) => None,
+            ActionItemRequestType::DisplayMarkdown(_) => None,
             ActionItemRequestType::OpenModal(_) => None,
             ActionItemRequestType::ValidateBlock(_) => None,
             ActionItemRequestType::ValidateModal => None,
@@ -1902,6 +2280,9 @@ impl ActionItemRequestType {
 pub struct ReviewInputRequest {
     pub input_name: String,
     pub value: Value,
+    /// Display form of the value, when it holds addon values (e.g. a checksummed address)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted_value: Option<String>,
     pub force_execution: bool,
 }
 
@@ -1910,6 +2291,7 @@ impl ReviewInputRequest {
         ReviewInputRequest {
             input_name: input_name.to_string(),
             value: value.clone(),
+            formatted_value: None,
             force_execution: false,
         }
     }
@@ -1928,6 +2310,67 @@ pub struct ProvideInputRequest {
     pub default_value: Option<Value>,
     pub input_name: String,
     pub typing: Type,
+    /// Sensitive inputs (passwords, passphrases) are masked when entered, and never displayed.
+    #[serde(default)]
+    pub sensitive: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvideFileRequest {
+    pub input_name: String,
+    pub description: Option<String>,
+    /// Extensions accepted, without the leading dot (e.g. `csv`); any file is accepted when empty.
+    #[serde(default)]
+    pub expected_extensions: Vec<String>,
+    /// Maximum size of the file, in bytes
+    pub max_size: Option<u64>,
+}
+
+impl ProvideFileRequest {
+    pub fn new(input_name: &str) -> Self {
+        ProvideFileRequest {
+            input_name: input_name.to_string(),
+            description: None,
+            expected_extensions: vec![],
+            max_size: None,
+        }
+    }
+
+    /// Checks the name and the size (in bytes) of a provided file against the expectations of
+    /// the request.
+    pub fn check_file(&self, filename: &str, size: u64) -> Result<(), String> {
+        if !self.expected_extensions.is_empty() {
+            let extension = std::path::Path::new(filename)
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let accepted = self
+                .expected_extensions
+                .iter()
+                .any(|e| e.trim_start_matches('.').to_lowercase() == extension);
+            if !accepted {
+                return Err(format!(
+                    "file '{}' must have one of the extensions: {}",
+                    filename,
+                    self.expected_extensions.join(", ")
+                ));
+            }
+        }
+        if let Some(max_size) = self.max_size {
+            if size > max_size {
+                return Err(format!(
+                    "file '{}' is {} bytes, exceeding the limit of {} bytes",
+                    filename, size, max_size
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn to_action_type(&self) -> ActionItemRequestType {
+        ActionItemRequestType::ProvideFile(self.clone())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1936,6 +2379,9 @@ pub struct DisplayOutputRequest {
     pub name: String,
     pub description: Option<String>,
     pub value: Value,
+    /// Display form of the value, when it holds addon values (e.g. a checksummed address)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub formatted_value: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -1944,6 +2390,86 @@ pub struct DisplayErrorLogRequest {
     pub diagnostic: Diagnostic,
 }
 
+/// Formatted instructions displayed mid-run (e.g. how to approve a multisig proposal), along
+/// with links rendered as buttons. No response is expected.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayMarkdownRequest {
+    pub markdown: String,
+    #[serde(default)]
+    pub links: Vec<MarkdownLink>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkdownLink {
+    pub label: String,
+    pub url: String,
+}
+
+impl DisplayMarkdownRequest {
+    pub fn new(markdown: &str) -> Self {
+        DisplayMarkdownRequest { markdown: markdown.to_string(), links: vec![] }
+    }
+
+    pub fn with_link(mut self, label: &str, url: &str) -> Self {
+        self.links.push(MarkdownLink { label: label.to_string(), url: url.to_string() });
+        self
+    }
+
+    pub fn to_action_type(&self) -> ActionItemRequestType {
+        ActionItemRequestType::DisplayMarkdown(self.clone())
+    }
+
+    /// Renders the markdown for terminals: heading markers and emphasis are dropped, inline
+    /// links are written as `label (url)`, and the link buttons are listed at the end.
+    pub fn to_plain_text(&self) -> String {
+        let mut lines = self
+            .markdown
+            .lines()
+            .map(|line| {
+                let line = match line.trim_start_matches('#') {
+                    heading if heading.len() < line.len() && heading.starts_with(' ') => {
+                        heading.trim_start()
+                    }
+                    _ => line,
+                };
+                let line = line.replace("**", "").replace("__", "").replace('`', "");
+                inline_links_to_plain_text(&line)
+            })
+            .collect::<Vec<_>>();
+        for link in self.links.iter() {
+            lines.push(format!("{}: {}", link.label, link.url));
+        }
+        lines.join("\n").trim().to_string()
+    }
+}
+
+/// Rewrites the `[label](url)` links of a line as `label (url)`.
+fn inline_links_to_plain_text(line: &str) -> String {
+    let mut rendered = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('[') {
+        let Some(middle) = rest[start..].find("](").map(|i| start + i) else {
+            break;
+        };
+        let Some(end) = rest[middle..].find(')').map(|i| middle + i) else {
+            break;
+        };
+        if rest[start + 1..middle].contains(']') {
+            // a bracket which isn't the label of a link
+            rendered.push_str(&rest[..=start]);
+            rest = &rest[start + 1..];
+            continue;
+        }
+        rendered.push_str(&rest[..start]);
+        rendered.push_str(&format!("{} ({})", &rest[start + 1..middle], &rest[middle + 2..end]));
+        rest = &rest[end + 1..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ValidateBlockData {
@@ -2172,6 +2698,7 @@ pub struct ActionItemResponse {
 pub enum ActionItemResponseType {
     ReviewInput(ReviewedInputResponse),
     ProvideInput(ProvidedInputResponse),
+    ProvideFile(ProvidedFileResponse),
     PickInputOption(String),
     ProvidePublicKey(ProvidePublicKeyResponse),
     ProvideSignedMessage(ProvideSignedMessageResponse),
@@ -2183,6 +2710,23 @@ pub enum ActionItemResponseType {
 }
 
 impl ActionItemResponseType {
+    /// The name of the variant, matching `ActionItemRequestType::expected_response_type`.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ActionItemResponseType::ReviewInput(_) => "ReviewInput",
+            ActionItemResponseType::ProvideInput(_) => "ProvideInput",
+            ActionItemResponseType::ProvideFile(_) => "ProvideFile",
+            ActionItemResponseType::PickInputOption(_) => "PickInputOption",
+            ActionItemResponseType::ProvidePublicKey(_) => "ProvidePublicKey",
+            ActionItemResponseType::ProvideSignedMessage(_) => "ProvideSignedMessage",
+            ActionItemResponseType::ProvideSignedTransaction(_) => "ProvideSignedTransaction",
+            ActionItemResponseType::VerifyThirdPartySignature(_) => "VerifyThirdPartySignature",
+            ActionItemResponseType::SendTransaction(_) => "SendTransaction",
+            ActionItemResponseType::ValidateBlock => "ValidateBlock",
+            ActionItemResponseType::ValidateModal => "ValidateModal",
+        }
+    }
+
     pub fn is_validate_panel(&self) -> bool {
         match &self {
             ActionItemResponseType::ValidateBlock => true,
@@ -2206,6 +2750,32 @@ pub struct ProvidedInputResponse {
     pub updated_value: Value,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvidedFileResponse {
+    pub input_name: String,
+    pub filename: String,
+    /// Content of the file, base64 encoded
+    pub content: String,
+}
+
+impl ProvidedFileResponse {
+    /// Size of the file, in bytes, computed from its base64 encoded content.
+    pub fn size(&self) -> u64 {
+        let content = self.content.trim_end().trim_end_matches('=');
+        (content.len() as u64 * 3) / 4
+    }
+
+    /// The value of the input receiving the file: an object with its `filename`, and its decoded
+    /// `content` as a buffer.
+    pub fn to_value(&self, content: Vec<u8>) -> Value {
+        Value::object(IndexMap::from([
+            ("filename".to_string(), Value::string(self.filename.clone())),
+            ("content".to_string(), Value::buffer(content)),
+        ]))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProvidePublicKeyResponse {
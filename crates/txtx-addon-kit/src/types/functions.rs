@@ -1,4 +1,7 @@
+use indexmap::IndexMap;
+
 use super::{
+    deprecation::Deprecation,
     diagnostics::Diagnostic,
     types::{Type, Value},
     AuthorizationContext,
@@ -10,6 +13,7 @@ pub struct FunctionInput {
     pub documentation: String,
     pub typing: Vec<Type>,
     pub optional: bool,
+    pub deprecation: Option<Deprecation>,
 }
 
 #[derive(Clone, Debug)]
@@ -23,13 +27,96 @@ pub struct FunctionSpecification {
     pub name: String,
     pub documentation: String,
     pub inputs: Vec<FunctionInput>,
+    /// Trailing input accepting any number of arguments, after the positional `inputs`
+    pub variadic: Option<FunctionInput>,
+    /// When true, the `inputs` can also be passed by name, with a trailing object literal:
+    /// `fn(a, { c = 1 })`
+    pub accepts_named_arguments: bool,
     pub output: FunctionOutput,
+    pub deprecation: Option<Deprecation>,
     pub example: String,
     pub snippet: String,
     pub runner: FunctionRunner,
     pub checker: FunctionChecker,
 }
 
+impl FunctionSpecification {
+    /// Describes the arguments accepted by the function, e.g. `format(template, values...)`.
+    pub fn signature(&self) -> String {
+        let mut params = self
+            .inputs
+            .iter()
+            .map(|input| format!("{}{}", input.name, if input.optional { "?" } else { "" }))
+            .collect::<Vec<_>>();
+        if let Some(variadic) = &self.variadic {
+            params.push(format!("{}...", variadic.name));
+        }
+        format!("{}({})", self.name, params.join(", "))
+    }
+
+    /// Maps the arguments of a call to the positional arguments of the function. Named arguments
+    /// fill the slot of the input they're named after, and the optional inputs skipped in between
+    /// are passed as null.
+    pub fn resolve_arguments(
+        &self,
+        mut args: Vec<Value>,
+        named_args: Option<IndexMap<String, Value>>,
+    ) -> Result<Vec<Value>, Diagnostic> {
+        let Some(named_args) = named_args else {
+            return Ok(args);
+        };
+        if !self.accepts_named_arguments {
+            return Err(self.arguments_mismatch("named arguments are not supported"));
+        }
+        if args.len() > self.inputs.len() {
+            return Err(self.arguments_mismatch(&format!(
+                "{} positional arguments can't be followed by named arguments",
+                args.len()
+            )));
+        }
+        let positional_count = args.len();
+        for (name, value) in named_args.into_iter() {
+            let Some(position) = self.inputs.iter().position(|input| input.name == name) else {
+                return Err(self.arguments_mismatch(&format!("unknown argument '{}'", name)));
+            };
+            if position < positional_count {
+                return Err(self.arguments_mismatch(&format!(
+                    "argument '{}' is given both by position and by name",
+                    name
+                )));
+            }
+            if position >= args.len() {
+                args.resize(position + 1, Value::null());
+            }
+            args[position] = value;
+        }
+        for (input, arg) in self.inputs.iter().zip(args.iter()).skip(positional_count) {
+            if !input.optional && arg.as_null().is_some() {
+                return Err(
+                    self.arguments_mismatch(&format!("missing required argument '{}'", input.name))
+                );
+            }
+        }
+        Ok(args)
+    }
+
+    /// Whether the arguments of a call to the function can include a trailing object literal of
+    /// named arguments, with these keys.
+    pub fn names_inputs<'a>(&self, mut keys: impl Iterator<Item = &'a str>) -> bool {
+        self.accepts_named_arguments
+            && keys.all(|key| self.inputs.iter().any(|input| input.name == key))
+    }
+
+    fn arguments_mismatch(&self, message: &str) -> Diagnostic {
+        Diagnostic::error_from_string(format!(
+            "function '{}': {}; expected {}",
+            self.name,
+            message,
+            self.signature()
+        ))
+    }
+}
+
 type FunctionRunner =
     fn(&FunctionSpecification, &AuthorizationContext, &Vec<Value>) -> Result<Value, Diagnostic>;
 type FunctionChecker =
@@ -63,68 +150,74 @@ pub fn arg_checker_with_ctx(
 ) -> impl Fn(&FunctionSpecification, &Vec<Value>) -> Result<(), Diagnostic> {
     let fn_checker =
         move |fn_spec: &FunctionSpecification, args: &Vec<Value>| -> Result<(), Diagnostic> {
+            let type_mismatch = |i: usize, input: &FunctionInput, arg: &Value| {
+                let expected_types =
+                    input.typing.iter().map(|t| t.to_string()).collect::<Vec<String>>().join(",");
+                Diagnostic::error_from_string(format!(
+                    "function '{}::{}' argument #{} ({}) should be of type ({}), found {}",
+                    namespace,
+                    fn_spec.name,
+                    i + 1,
+                    input.name,
+                    expected_types,
+                    arg.get_type().to_string()
+                ))
+            };
             for (i, input) in fn_spec.inputs.iter().enumerate() {
                 if !input.optional {
                     if let Some(arg) = args.get(i) {
-                        let mut has_type_match = false;
-                        for typing in input.typing.iter() {
-                            let arg_type = arg.get_type();
-                            // special case if both are addons: we don't want to be so strict that
-                            // we check the addon id here
-                            if let Type::Addon(_) = arg_type {
-                                if let Type::Addon(_) = typing {
-                                    has_type_match = true;
-                                    break;
-                                }
-                            }
-                            // special case for empty arrays
-                            if let Type::Array(_) = arg_type {
-                                if arg.expect_array().len() == 0 {
-                                    has_type_match = true;
-                                    break;
-                                }
-                            }
-                            // we don't have an "any" type, so if the array is of type null, we won't check types
-                            if let Type::Array(inner) = typing {
-                                if let Type::Null(_) = **inner {
-                                    has_type_match = true;
-                                    break;
-                                }
-                            }
-                            if arg_type.eq(typing) {
-                                has_type_match = true;
-                                break;
-                            }
-                        }
-                        if !has_type_match {
-                            let expected_types = input
-                                .typing
-                                .iter()
-                                .map(|t| t.to_string())
-                                .collect::<Vec<String>>()
-                                .join(",");
-                            return Err(Diagnostic::error_from_string(format!(
-                            "function '{}::{}' argument #{} ({}) should be of type ({}), found {}",
-                            namespace,
-                            fn_spec.name,
-                            i + 1,
-                            input.name,
-                            expected_types,
-                            arg.get_type().to_string()
-                        )));
+                        if !arg_matches_typing(arg, &input.typing) {
+                            return Err(type_mismatch(i, input, arg));
                         }
                     } else {
                         return Err(Diagnostic::error_from_string(format!(
-                            "function '{}::{}' missing required argument #{} ({})",
+                            "function '{}::{}' missing required argument #{} ({}); expected {}",
                             namespace,
                             fn_spec.name,
                             i + 1,
                             input.name,
+                            fn_spec.signature()
                         )));
                     }
                 }
             }
+            if let Some(variadic) = &fn_spec.variadic {
+                for (i, arg) in args.iter().enumerate().skip(fn_spec.inputs.len()) {
+                    if !arg_matches_typing(arg, &variadic.typing) {
+                        return Err(type_mismatch(i, variadic, arg));
+                    }
+                }
+            }
             Ok(())
         };
     return fn_checker;
 }
+
+fn arg_matches_typing(arg: &Value, typing: &Vec<Type>) -> bool {
+    let arg_type = arg.get_type();
+    for typing in typing.iter() {
+        // special case if both are addons: we don't want to be so strict that
+        // we check the addon id here
+        if let Type::Addon(_) = arg_type {
+            if let Type::Addon(_) = typing {
+                return true;
+            }
+        }
+        // special case for empty arrays
+        if let Type::Array(_) = arg_type {
+            if arg.expect_array().len() == 0 {
+                return true;
+            }
+        }
+        // we don't have an "any" type, so if the array is of type null, we won't check types
+        if let Type::Array(inner) = typing {
+            if let Type::Null(_) = **inner {
+                return true;
+            }
+        }
+        if arg_type.eq(typing) {
+            return true;
+        }
+    }
+    false
+}
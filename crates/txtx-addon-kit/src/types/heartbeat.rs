@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::frontend::HeartbeatEvent;
+use super::ConstructDid;
+
+/// Running background tasks send a heartbeat once per interval, as long as they make progress.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// Background tasks silent for longer than this are reported as possibly stalled.
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(Debug, Clone)]
+struct BackgroundTaskHeartbeat {
+    started_at: Instant,
+    /// When the task was last polled, i.e. when it last made progress.
+    last_progress_at: Instant,
+    /// Whether the task made progress since its last heartbeat.
+    progressed: bool,
+    /// Whether the task was already reported as silent.
+    reported_silent: bool,
+}
+
+/// Heartbeats due on a tick of the background tasks watch.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeartbeatsTick {
+    /// The heartbeats of the tasks that made progress since their last heartbeat.
+    pub heartbeats: Vec<HeartbeatEvent>,
+    /// The tasks that just became silent for longer than [HEARTBEAT_TIMEOUT], along with how
+    /// long they've been silent.
+    pub silent: Vec<(ConstructDid, Duration)>,
+}
+
+/// The heartbeats of the background tasks of a runbook. The runtime records the progress of each
+/// task while awaiting them, and ticks every [HEARTBEAT_INTERVAL]: tasks that made progress since
+/// the previous tick send a heartbeat, and tasks that made none for longer than
+/// [HEARTBEAT_TIMEOUT] raise a single warning, until they make progress again.
+#[derive(Debug, Clone, Default)]
+pub struct BackgroundTasksHeartbeats {
+    tasks: HashMap<ConstructDid, BackgroundTaskHeartbeat>,
+}
+
+impl BackgroundTasksHeartbeats {
+    pub fn new() -> Self {
+        BackgroundTasksHeartbeats { tasks: HashMap::new() }
+    }
+
+    pub fn start(&mut self, construct_did: &ConstructDid, now: Instant) {
+        self.tasks.insert(
+            construct_did.clone(),
+            BackgroundTaskHeartbeat {
+                started_at: now,
+                last_progress_at: now,
+                progressed: false,
+                reported_silent: false,
+            },
+        );
+    }
+
+    /// Records that the task of a construct made progress. Constructs without a running task are
+    /// ignored.
+    pub fn record_progress(&mut self, construct_did: &ConstructDid, now: Instant) {
+        let Some(task) = self.tasks.get_mut(construct_did) else {
+            return;
+        };
+        task.last_progress_at = now;
+        task.progressed = true;
+        task.reported_silent = false;
+    }
+
+    pub fn stop(&mut self, construct_did: &ConstructDid) {
+        self.tasks.remove(construct_did);
+    }
+
+    pub fn tick(&mut self, now: Instant) -> HeartbeatsTick {
+        let mut tick = HeartbeatsTick::default();
+        for (construct_did, task) in self.tasks.iter_mut() {
+            if task.progressed {
+                task.progressed = false;
+                tick.heartbeats.push(HeartbeatEvent {
+                    construct_did: construct_did.clone(),
+                    elapsed: now.duration_since(task.started_at),
+                    detail: None,
+                });
+                continue;
+            }
+            let silence = now.duration_since(task.last_progress_at);
+            if silence >= HEARTBEAT_TIMEOUT && !task.reported_silent {
+                task.reported_silent = true;
+                tick.silent.push((construct_did.clone(), silence));
+            }
+        }
+        tick
+    }
+}
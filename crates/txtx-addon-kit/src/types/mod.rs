@@ -22,15 +22,18 @@ pub mod commands;
 pub mod construct_type;
 pub mod typed_block;
 pub mod diagnostic_types;
+pub mod deprecation;
 pub mod diagnostics;
 
 // Re-export common diagnostic types for convenience
-pub use diagnostic_types::{DiagnosticLevel, DiagnosticSpan, RelatedLocation};
+pub use diagnostic_types::{DiagnosticKind, DiagnosticLevel, DiagnosticSpan, RelatedLocation};
 
 pub mod embedded_runbooks;
 pub mod frontend;
 pub mod functions;
+pub mod heartbeat;
 pub mod package;
+pub mod redaction;
 pub mod signers;
 pub mod stores;
 pub mod types;
@@ -308,15 +311,19 @@ pub struct Construct {
 #[derive(Debug, Clone)]
 pub struct AuthorizationContext {
     pub workspace_location: FileLocation,
+    /// Name of the runbook being executed
+    pub runbook_name: Option<String>,
+    /// Environment selected for the execution
+    pub environment: Option<String>,
 }
 
 impl AuthorizationContext {
     pub fn new(workspace_location: FileLocation) -> Self {
-        Self { workspace_location }
+        Self { workspace_location, runbook_name: None, environment: None }
     }
 
     pub fn empty() -> Self {
-        Self { workspace_location: FileLocation::working_dir() }
+        Self::new(FileLocation::working_dir())
     }
 
     pub fn get_file_location_from_path_buf(&self, input: &PathBuf) -> Result<FileLocation, String> {
@@ -375,6 +382,36 @@ impl AddonPostProcessingResult {
     }
 }
 
+/// Context handed to the lifecycle hooks of an addon ([crate::Addon::on_runbook_start] and
+/// [crate::Addon::on_runbook_end]).
+pub struct RunbookLifecycleContext {
+    pub runbook_id: RunbookId,
+    /// Defaults of the addon, for each package of the runbook instantiating it
+    pub addons_defaults: Vec<stores::AddonDefaults>,
+    /// Emits log events under the namespace of the addon
+    pub logger: frontend::LogDispatcher,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl RunbookLifecycleContext {
+    pub fn new(
+        runbook_id: &RunbookId,
+        addons_defaults: Vec<stores::AddonDefaults>,
+        logger: frontend::LogDispatcher,
+    ) -> Self {
+        Self { runbook_id: runbook_id.clone(), addons_defaults, logger, diagnostics: vec![] }
+    }
+
+    /// Reports a warning or a note, which doesn't fail the run.
+    pub fn report(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        std::mem::take(&mut self.diagnostics)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AddonInstance {
     pub addon_id: String,
@@ -426,6 +463,10 @@ pub trait EvaluatableInput: DynClone {
     fn optional(&self) -> bool;
     fn typing(&self) -> &Type;
     fn name(&self) -> String;
+    /// Sensitive inputs have their evaluated values redacted from diagnostics and events.
+    fn sensitive(&self) -> bool {
+        false
+    }
     fn as_object(&self) -> Option<&ObjectDefinition> {
         self.typing().as_object()
     }
@@ -0,0 +1,317 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value as JsonValue;
+
+use super::diagnostics::Diagnostic;
+use super::frontend::{BlockEvent, LogDetails, LogEvent, LogLevel, TransientLogEventStatus};
+use super::types::Value;
+use crate::channel;
+use uuid::Uuid;
+
+pub const REDACTED: &str = "<redacted>";
+
+/// Secrets shorter than this are not registered, to avoid scrubbing unrelated content.
+pub const MIN_SECRET_LENGTH: usize = 8;
+/// Any run of at least this many consecutive words of a multi-word secret (e.g. a mnemonic) is
+/// scrubbed, so that partially echoed secrets don't leak either.
+pub const MIN_SECRET_WORDS: usize = 3;
+
+/// The secrets recorded while running a runbook, scrubbed from everything leaving the runtime.
+/// Each run has its own registry, so that secrets are neither shared across runbooks nor kept
+/// once the run is over.
+#[derive(Debug, Clone, Default)]
+pub struct SecretRegistry {
+    secrets: Arc<RwLock<BTreeSet<String>>>,
+}
+
+impl SecretRegistry {
+    pub fn new() -> Self {
+        SecretRegistry { secrets: Arc::new(RwLock::new(BTreeSet::new())) }
+    }
+
+    /// Records the encodings of a value evaluated for a sensitive input.
+    pub fn register(&self, value: &Value) {
+        let mut forms = vec![];
+        collect_secret_forms(value, &mut forms);
+        let forms = forms
+            .into_iter()
+            .filter(|f| f.chars().count() >= MIN_SECRET_LENGTH)
+            .collect::<Vec<_>>();
+        if forms.is_empty() {
+            return;
+        }
+        let Ok(mut secrets) = self.secrets.write() else { return };
+        secrets.extend(forms);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.secrets.read().map(|secrets| secrets.is_empty()).unwrap_or(true)
+    }
+
+    /// Replaces the registered secrets with `<redacted>`.
+    pub fn redact(&self, text: &str) -> String {
+        let Ok(secrets) = self.secrets.read() else { return text.to_string() };
+        let mut ranges = vec![];
+        for secret in secrets.iter() {
+            ranges.extend(text.match_indices(secret.as_str()).map(|(i, m)| (i, i + m.len())));
+        }
+        if ranges.is_empty() {
+            return text.to_string();
+        }
+
+        ranges.sort();
+        let mut redacted = String::with_capacity(text.len());
+        let mut cursor = 0;
+        let mut ranges = ranges.into_iter().peekable();
+        while let Some((start, mut end)) = ranges.next() {
+            while let Some((next_start, next_end)) = ranges.peek() {
+                if *next_start > end {
+                    break;
+                }
+                end = end.max(*next_end);
+                ranges.next();
+            }
+            redacted.push_str(&text[cursor..start]);
+            redacted.push_str(REDACTED);
+            cursor = end;
+        }
+        redacted.push_str(&text[cursor..]);
+        redacted
+    }
+
+    pub fn redact_diagnostic(&self, mut diag: Diagnostic) -> Diagnostic {
+        if self.is_empty() {
+            return diag;
+        }
+        diag.message = self.redact(&diag.message);
+        diag.context = diag.context.map(|c| self.redact(&c));
+        diag.documentation = diag.documentation.map(|d| self.redact(&d));
+        diag.suggestion = diag.suggestion.map(|s| self.redact(&s));
+        diag.example = diag.example.map(|e| self.redact(&e));
+        diag.parent_diagnostic =
+            diag.parent_diagnostic.map(|p| Box::new(self.redact_diagnostic(*p)));
+        diag
+    }
+
+    pub fn redact_diagnostics(&self, diags: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diags.into_iter().map(|diag| self.redact_diagnostic(diag)).collect()
+    }
+
+    fn redact_log_details(&self, details: LogDetails) -> LogDetails {
+        LogDetails {
+            message: self.redact(&details.message),
+            summary: self.redact(&details.summary),
+        }
+    }
+
+    pub fn redact_log_event(&self, event: LogEvent) -> LogEvent {
+        if self.is_empty() {
+            return event;
+        }
+        match event {
+            LogEvent::Static(mut event) => {
+                event.details = self.redact_log_details(event.details);
+                LogEvent::Static(event)
+            }
+            LogEvent::Transient(mut event) => {
+                event.status = match event.status {
+                    TransientLogEventStatus::Pending(details) => {
+                        TransientLogEventStatus::Pending(self.redact_log_details(details))
+                    }
+                    TransientLogEventStatus::Success(details) => {
+                        TransientLogEventStatus::Success(self.redact_log_details(details))
+                    }
+                    TransientLogEventStatus::Failure(details) => {
+                        TransientLogEventStatus::Failure(self.redact_log_details(details))
+                    }
+                };
+                LogEvent::Transient(event)
+            }
+        }
+    }
+
+    /// Scrubs the registered secrets from every string carried by the event (panels, action
+    /// items, progress statuses, modals, errors and logs).
+    pub fn redact_event(&self, event: BlockEvent) -> BlockEvent {
+        if self.is_empty() {
+            return event;
+        }
+        if let BlockEvent::LogEvent(event) = event {
+            return BlockEvent::LogEvent(self.redact_log_event(event));
+        }
+        let Ok(mut json) = serde_json::to_value(&event) else { return event };
+        if !self.redact_json(&mut json) {
+            return event;
+        }
+        // an event that can't be rebuilt once scrubbed is withheld rather than leaked
+        serde_json::from_value(json).unwrap_or_else(|_| {
+            BlockEvent::static_log(
+                LogLevel::Warn,
+                Uuid::new_v4(),
+                "txtx::redaction".into(),
+                "Event withheld",
+                "an event containing sensitive data was withheld",
+            )
+        })
+    }
+
+    /// Scrubs the strings of a json value, returning true if anything was redacted.
+    fn redact_json(&self, value: &mut JsonValue) -> bool {
+        match value {
+            JsonValue::String(string) => {
+                let redacted = self.redact(string);
+                if redacted.eq(string) {
+                    return false;
+                }
+                *string = redacted;
+                true
+            }
+            JsonValue::Array(entries) => entries
+                .iter_mut()
+                .fold(false, |redacted, entry| self.redact_json(entry) || redacted),
+            JsonValue::Object(props) => {
+                props.values_mut().fold(false, |redacted, prop| self.redact_json(prop) || redacted)
+            }
+            JsonValue::Null | JsonValue::Bool(_) | JsonValue::Number(_) => false,
+        }
+    }
+
+    /// Returns a sender forwarding every event to `tx` once its secrets are scrubbed. Events are
+    /// forwarded in order, from a dedicated thread that stops once every sender is dropped.
+    pub fn redacting_sender(
+        &self,
+        tx: &channel::Sender<BlockEvent>,
+    ) -> channel::Sender<BlockEvent> {
+        let (redacting_tx, rx) = channel::unbounded::<BlockEvent>();
+        let tx = tx.clone();
+        let registry = self.clone();
+        std::thread::spawn(move || {
+            while let Ok(event) = rx.recv() {
+                if tx.send(registry.redact_event(event)).is_err() {
+                    break;
+                }
+            }
+        });
+        redacting_tx
+    }
+}
+
+/// Collects the encodings of a secret: strings as they are, as escaped by `Debug`, and their
+/// runs of consecutive words; bytes as hex, as rendered by `Debug` and, when valid, as UTF-8.
+fn collect_secret_forms(value: &Value, forms: &mut Vec<String>) {
+    match value {
+        Value::String(string) => collect_string_forms(string, forms),
+        Value::Buffer(bytes) => collect_bytes_forms(bytes, forms),
+        Value::Addon(addon_data) => collect_bytes_forms(&addon_data.bytes, forms),
+        Value::Array(values) => values.iter().for_each(|v| collect_secret_forms(v, forms)),
+        Value::Object(props) => props.values().for_each(|v| collect_secret_forms(v, forms)),
+        Value::Bool(_)
+        | Value::Null
+        | Value::Integer(_)
+        | Value::Float(_)
+        | Value::Decimal(_)
+        | Value::DateTime(_) => {}
+    }
+}
+
+fn collect_string_forms(string: &str, forms: &mut Vec<String>) {
+    forms.push(string.to_string());
+    let escaped = string.escape_debug().to_string();
+    if escaped != string {
+        forms.push(escaped);
+    }
+    let words = string.split_whitespace().collect::<Vec<_>>();
+    if words.len() > MIN_SECRET_WORDS {
+        for len in MIN_SECRET_WORDS..=words.len() {
+            forms.extend(words.windows(len).map(|window| window.join(" ")));
+        }
+    }
+}
+
+fn collect_bytes_forms(bytes: &Vec<u8>, forms: &mut Vec<String>) {
+    forms.push(hex::encode(bytes));
+    forms.push(format!("{bytes:?}"));
+    if let Ok(string) = String::from_utf8(bytes.clone()) {
+        collect_string_forms(&string, forms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::frontend::StaticLogEvent;
+
+    const MNEMONIC: &str =
+        "zone quiet brisk ocean vapor ladder candle tiger ripple empty saddle orbit";
+
+    #[test]
+    fn test_redact() {
+        let secrets = SecretRegistry::new();
+        secrets.register(&Value::string(MNEMONIC.into()));
+        secrets.register(&Value::buffer(vec![0xab; 32]));
+        secrets.register(&Value::string("short".into()));
+
+        assert_eq!(
+            secrets.redact(&format!("invalid mnemonic '{MNEMONIC}'")),
+            "invalid mnemonic '<redacted>'"
+        );
+        assert_eq!(secrets.redact(&format!("key 0x{}", "ab".repeat(32))), "key 0x<redacted>");
+        assert_eq!(secrets.redact("a short message"), "a short message");
+        // partially echoed mnemonics are scrubbed, down to a few words
+        assert_eq!(
+            secrets.redact("words 'ladder candle tiger ripple' are invalid"),
+            "words '<redacted>' are invalid"
+        );
+        assert_eq!(
+            secrets.redact("starting with 'zone quiet brisk'"),
+            "starting with '<redacted>'"
+        );
+        assert_eq!(secrets.redact("the ocean is quiet"), "the ocean is quiet");
+        // hex fragments are not
+        assert_eq!(
+            secrets.redact(&format!("key 0x{}", "ab".repeat(16))),
+            format!("key 0x{}", "ab".repeat(16))
+        );
+
+        let diag =
+            secrets.redact_diagnostic(Diagnostic::error_from_string(format!("failed: {MNEMONIC}")));
+        assert_eq!(diag.message, "failed: <redacted>");
+
+        let event = secrets.redact_event(BlockEvent::LogEvent(LogEvent::Static(StaticLogEvent {
+            level: LogLevel::Error,
+            uuid: Uuid::new_v4(),
+            details: LogDetails { message: MNEMONIC.into(), summary: "Error".into() },
+            namespace: "test".into(),
+        })));
+        let BlockEvent::LogEvent(event) = event else { unreachable!() };
+        assert_eq!(event.message(), REDACTED);
+    }
+
+    #[test]
+    fn test_redact_value_store_debug_output() {
+        use crate::types::stores::ValueStore;
+
+        let secrets = SecretRegistry::new();
+        let key = vec![0xab; 32];
+        let password = "hunter2\"hunter2";
+        secrets.register(&Value::buffer(key.clone()));
+        secrets.register(&Value::string(password.into()));
+
+        let mut store = ValueStore::tmp();
+        store.insert("private_key", Value::buffer(key));
+        store.insert("password", Value::string(password.into()));
+        let output = secrets.redact(&format!("{store:?}"));
+        assert!(output.contains(REDACTED));
+        assert!(!output.contains("171, 171, 171"), "key leaked: {output}");
+        assert!(!output.contains("hunter2"), "password leaked: {output}");
+    }
+
+    #[test]
+    fn test_registries_are_independent() {
+        let secrets = SecretRegistry::new();
+        secrets.register(&Value::string(MNEMONIC.into()));
+        assert_eq!(secrets.clone().redact(MNEMONIC), REDACTED);
+        assert_eq!(SecretRegistry::new().redact(MNEMONIC), MNEMONIC);
+    }
+}
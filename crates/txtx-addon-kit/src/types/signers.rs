@@ -1,6 +1,6 @@
 use crate::constants::{
-    ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_CHECK_BALANCE, CHECKED_ADDRESS, IS_BALANCE_CHECKED,
-    PROVIDE_PUBLIC_KEY_ACTION_RESULT,
+    ACTION_ITEM_CHECK_ADDRESS, ACTION_ITEM_CHECK_BALANCE, ADDON_INSTANCE, CHECKED_ADDRESS,
+    IS_BALANCE_CHECKED, PROVIDE_PUBLIC_KEY_ACTION_RESULT,
 };
 use crate::helpers::hcl::visit_optional_untyped_attribute;
 use crate::types::stores::ValueStore;
@@ -8,16 +8,17 @@ use futures::future;
 use hcl_edit::{expr::Expression, structure::Block, Span};
 use std::{collections::HashMap, future::Future, pin::Pin};
 
-use super::commands::ConstructInstance;
+use super::commands::{CommandInstance, ConstructInstance};
 use super::{
     commands::{
         CommandExecutionResult, CommandInput, CommandInputsEvaluationResult, CommandOutput,
     },
+    deprecation::Deprecation,
     diagnostics::Diagnostic,
     frontend::{
         ActionItemRequest, ActionItemResponse, ActionItemResponseType, Actions, BlockEvent,
     },
-    types::{ObjectProperty, RunbookSupervisionContext, Type, Value},
+    types::{ObjectProperty, ObjectType, RunbookSupervisionContext, Type, Value},
     ConstructDid, PackageId,
 };
 use super::{AuthorizationContext, Did, EvaluatableInput};
@@ -306,6 +307,126 @@ pub struct SignerSpecification {
     pub check_signability: SignerCheckSignabilityClosure,
     pub sign: SignerSignClosure,
     pub force_sequential_signing: bool,
+    /// Set by signers able to take part in a threshold signer. When `sign` is called with
+    /// [crate::constants::PARTIAL_SIGNATURE_REQUESTED] set in the signer state (scoped by the
+    /// calling construct), the signer returns a [PartialSignature] under the
+    /// [crate::constants::PARTIAL_SIGNATURE] output instead of broadcasting. This closure then
+    /// assembles the collected partial signatures into the final signing result.
+    pub finalize_partial_signatures: Option<SignerFinalizePartialSignaturesClosure>,
+    pub deprecation: Option<Deprecation>,
+    /// What the signer is able to do, checked against the
+    /// [CommandSpecification::required_signer_capabilities] of the commands it's bound to before
+    /// the signers are activated.
+    ///
+    /// [CommandSpecification::required_signer_capabilities]: super::commands::CommandSpecification::required_signer_capabilities
+    pub capabilities: SignerCapabilities,
+}
+
+impl SignerSpecification {
+    pub fn supports_partial_signatures(&self) -> bool {
+        self.finalize_partial_signatures.is_some()
+    }
+}
+
+/// Flags describing what a signer is able to do, or what a command requires from its signers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SignerCapabilities {
+    pub sign_transaction: bool,
+    pub sign_message: bool,
+    pub sign_typed_data: bool,
+    /// The public key is known without querying a remote service, such as a key management
+    /// service or a browser wallet
+    pub provides_public_key: bool,
+    /// Signing doesn't require any network access
+    pub supports_offline: bool,
+}
+
+impl SignerCapabilities {
+    pub const fn all() -> Self {
+        SignerCapabilities {
+            sign_transaction: true,
+            sign_message: true,
+            sign_typed_data: true,
+            provides_public_key: true,
+            supports_offline: true,
+        }
+    }
+
+    pub const fn none() -> Self {
+        SignerCapabilities {
+            sign_transaction: false,
+            sign_message: false,
+            sign_typed_data: false,
+            provides_public_key: false,
+            supports_offline: false,
+        }
+    }
+
+    /// Lists the capabilities of `required` that are missing from these capabilities.
+    pub fn missing(&self, required: &SignerCapabilities) -> Vec<&'static str> {
+        [
+            (required.sign_transaction && !self.sign_transaction, "sign transactions"),
+            (required.sign_message && !self.sign_message, "sign messages"),
+            (required.sign_typed_data && !self.sign_typed_data, "sign typed data"),
+            (required.provides_public_key && !self.provides_public_key, "provide a public key"),
+            (required.supports_offline && !self.supports_offline, "sign offline"),
+        ]
+        .into_iter()
+        .filter_map(|(missing, capability)| missing.then_some(capability))
+        .collect()
+    }
+}
+
+pub type SignerFinalizePartialSignaturesClosure = fn(
+    &ConstructDid,
+    &Value,
+    &Vec<PartialSignature>,
+    &ValueStore,
+) -> Result<CommandExecutionResult, Diagnostic>;
+
+/// A signature produced by one member of a threshold signer, as exchanged between the member
+/// signers and the addon finalizing the signed payload.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartialSignature {
+    pub signer_did: ConstructDid,
+    pub public_key: Value,
+    pub signature: Value,
+}
+
+impl PartialSignature {
+    pub fn new(signer_did: &ConstructDid, public_key: Value, signature: Value) -> Self {
+        Self { signer_did: signer_did.clone(), public_key, signature }
+    }
+
+    pub fn to_value(&self) -> Value {
+        ObjectType::from(vec![
+            ("signer_did", Value::string(self.signer_did.to_string())),
+            ("public_key", self.public_key.clone()),
+            ("signature", self.signature.clone()),
+        ])
+        .to_value()
+    }
+
+    pub fn from_value(value: &Value) -> Result<Self, Diagnostic> {
+        let object = value.as_object().ok_or(Diagnostic::error_from_string(
+            "expected partial signature to be an object".into(),
+        ))?;
+        let get = |key: &str| {
+            object.get(key).cloned().ok_or(Diagnostic::error_from_string(format!(
+                "partial signature is missing field '{}'",
+                key
+            )))
+        };
+        let signer_did = get("signer_did")?;
+        let signer_did = signer_did.as_string().ok_or(Diagnostic::error_from_string(
+            "expected partial signature signer_did to be a string".into(),
+        ))?;
+        Ok(Self {
+            signer_did: ConstructDid::from_hex_string(signer_did),
+            public_key: get("public_key")?,
+            signature: get("signature")?,
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -318,6 +439,26 @@ pub struct SignerInstance {
 }
 
 impl SignerInstance {
+    /// Checks that the signer has the capabilities `command` requires from the signers bound to
+    /// it. The diagnostic names both the command and the capabilities the signer is missing.
+    pub fn check_capabilities(&self, command: &CommandInstance) -> Result<(), Diagnostic> {
+        let required = &command.specification.required_signer_capabilities;
+        let missing = self.specification.capabilities.missing(required);
+        if missing.is_empty() {
+            return Ok(());
+        }
+        Err(Diagnostic::error_from_string(format!(
+            "signer '{}' ({}::{}) is unable to {}, as required by command '{}' ({}::{})",
+            self.name,
+            self.namespace,
+            self.specification.matcher,
+            missing.join(", "),
+            command.name,
+            command.namespace,
+            command.specification.matcher
+        )))
+    }
+
     pub fn compute_fingerprint(&self, evaluated_inputs: &CommandInputsEvaluationResult) -> Did {
         let mut comps = vec![];
         for input in self.specification.inputs.iter() {
@@ -341,6 +482,12 @@ impl SignerInstance {
         group.value.to_string()
     }
 
+    /// The label of the addon block whose defaults apply to this signer, set with `addon_instance`.
+    pub fn get_addon_instance(&self) -> Option<String> {
+        let attribute = self.block.body.get_attribute(ADDON_INSTANCE)?;
+        attribute.value.as_str().map(|label| label.to_string())
+    }
+
     pub fn get_expression_from_object_property(
         &self,
         input_name: &str,
@@ -388,6 +535,18 @@ impl SignerInstance {
                                 Value::string(update.public_key.clone()),
                             );
                         }
+                        ActionItemResponseType::ProvideInput(update) => {
+                            // only the inputs requested by this signer are taken into account
+                            let is_requested_by_signer = action_item_requests.map_or(false, |r| {
+                                r.iter().any(|request| {
+                                    request.id.eq(action_item_id)
+                                        && request.construct_did.as_ref() == Some(construct_did)
+                                })
+                            });
+                            if is_requested_by_signer {
+                                values.insert(&update.input_name, update.updated_value.clone());
+                            }
+                        }
                         ActionItemResponseType::ReviewInput(response) => {
                             let request = action_item_requests
                                 .map(|requests| requests.iter().find(|r| r.id.eq(&action_item_id)));
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 use indexmap::IndexMap;
 
 use crate::{
@@ -239,6 +240,17 @@ impl ValueStore {
             .transpose()
     }
 
+    /// Defaults are not evaluated against the input typing, so they can still hold the ISO-8601
+    /// or epoch seconds notation of the datetime.
+    pub fn get_datetime(&self, key: &str) -> Result<Option<DateTime<Utc>>, Diagnostic> {
+        self.get_value(key)
+            .map(|v| {
+                v.to_datetime()
+                    .map_err(|e| format!("invalid datetime for value '{key}': {e}").into())
+            })
+            .transpose()
+    }
+
     pub fn get_array(&self, key: &str) -> Option<&Box<Vec<Value>>> {
         self.inputs.get_array(key).or(self.defaults.get_array(key))
     }
@@ -385,20 +397,74 @@ impl ValueStore {
     }
 }
 
+/// The defaults of an addon. Commands resolve them, from the highest precedence to the lowest:
+/// 1. the inputs of the action block itself,
+/// 2. the labeled addon block of the instance selected with `addon_instance` (`addon "evm" "base" { ... }`),
+/// 3. the addon block of the flow (`addon "evm" { ... }`),
+/// 4. the `addon.<addon_id>.<key>` entries of the manifest environment.
+///
+/// Object defaults are deep-merged across these levels rather than replaced, so that an action
+/// overriding `fee { strategy = "fast" }` keeps the other fields of the `fee` default.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddonDefaults {
     pub uuid: Did,
     pub name: String,
     pub store: ValueMap,
+    /// Defaults of the labeled addon blocks, keyed by instance label
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub instances: IndexMap<String, ValueMap>,
 }
 
 impl AddonDefaults {
     pub fn new(key: &str) -> AddonDefaults {
-        AddonDefaults { store: ValueMap::new(), name: key.to_string(), uuid: Did::zero() }
+        AddonDefaults {
+            store: ValueMap::new(),
+            name: key.to_string(),
+            uuid: Did::zero(),
+            instances: IndexMap::new(),
+        }
     }
     pub fn insert(&mut self, key: &str, value: Value) {
         self.store.insert(key, value);
     }
+    /// The defaults set by the addon block labeled `instance`, or by the unlabeled one.
+    pub fn scope_mut(&mut self, instance: Option<&str>) -> &mut ValueMap {
+        match instance {
+            Some(label) => self.instances.entry(label.to_string()).or_insert_with(ValueMap::new),
+            None => &mut self.store,
+        }
+    }
+    /// Resolves the defaults applying to a command targeting `instance`, deep-merging the
+    /// defaults of the instance over `environment_defaults` and the defaults of the addon block.
+    pub fn resolve(
+        &self,
+        environment_defaults: Option<&ValueMap>,
+        instance: Option<&str>,
+    ) -> Result<AddonDefaults, Diagnostic> {
+        let mut resolved = AddonDefaults::new(&self.name);
+        resolved.uuid = self.uuid.clone();
+        if let Some(environment_defaults) = environment_defaults {
+            resolved.store.deep_merge(environment_defaults);
+        }
+        resolved.store.deep_merge(&self.store);
+        if let Some(label) = instance {
+            let Some(instance_defaults) = self.instances.get(label) else {
+                return Err(Diagnostic::error_from_string(format!(
+                    "unknown instance '{}' of addon '{}': no addon \"{}\" \"{}\" block found",
+                    label, self.name, self.name, label
+                )));
+            };
+            resolved.store.deep_merge(instance_defaults);
+        }
+        Ok(resolved)
+    }
+    /// Deep-merges an object input over the object default of the same name, if any.
+    pub fn merge_object_default(&self, key: &str, value: Value) -> Value {
+        match self.store.get_value(key) {
+            Some(default @ Value::Object(_)) => default.deep_merge(&value),
+            _ => value,
+        }
+    }
     pub fn iter(&self) -> indexmap::map::Iter<String, Value> {
         self.store.iter()
     }
@@ -427,6 +493,16 @@ impl ValueMap {
         }
         self
     }
+    /// Merges `overrides` over the entries of this map, see [Value::deep_merge].
+    pub fn deep_merge(&mut self, overrides: &ValueMap) {
+        for (k, v) in overrides.iter() {
+            let merged = match self.store.get(k) {
+                Some(existing) => existing.deep_merge(v),
+                None => v.clone(),
+            };
+            self.store.insert(k.to_string(), merged);
+        }
+    }
 
     pub fn get_expected_value(&self, key: &str) -> Result<&Value, Diagnostic> {
         let Some(value) = self.store.get(key) else {
@@ -1,10 +1,28 @@
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use crate::helpers::fs::FileLocation;
 use crate::types::AuthorizationContext;
 
-use super::types::Value;
+use super::diagnostics::{Diagnostic, DiagnosticLevel};
+use super::frontend::{
+    describe_last_update, ActionItemRequestType, ActionItemStatus, Actions, BlockEvent,
+    ConstructStatus, ConstructStatusStore, ConstructStatusUpdate, DisplayMarkdownRequest,
+    ErrorPanelData, HeartbeatEvent, LogDispatcher, LogEvent, LogProgress, ReviewInputRequest,
+    TransientLogEvent,
+};
+use super::functions::{
+    arg_checker_with_ctx, FunctionInput, FunctionOutput, FunctionSpecification,
+};
+use super::heartbeat::{BackgroundTasksHeartbeats, HEARTBEAT_INTERVAL, HEARTBEAT_TIMEOUT};
+use super::signers::SignerCapabilities;
+use super::stores::{AddonDefaults, ValueMap};
+use super::types::{
+    decimal_from_base_units, decimal_to_base_units, AddonDisplayFormatter, ObjectProperty,
+    ObjectType, Type, Value,
+};
+use super::{ConstructDid, Did};
 use serde_json::json;
 use serde_json::Value as JsonValue;
 use test_case::test_case;
@@ -29,6 +47,9 @@ lazy_static! {
 })]
 #[test_case(Value::buffer(BYTES.clone()))]
 #[test_case(Value::addon(BYTES.clone(), "ns::type"))]
+#[test_case(Value::parse_decimal("1000000000000000000000000000").unwrap())]
+#[test_case(Value::parse_decimal("-0.000001").unwrap())]
+#[test_case(Value::parse_datetime("2025-01-31T12:00:00.250Z").unwrap())]
 fn it_serdes_values(value: Value) {
     let ser = serde_json::to_string(&value).unwrap();
     println!("\nserialized: {}", ser);
@@ -45,12 +66,476 @@ fn it_serdes_values(value: Value) {
 #[test_case(json!({"type": "bool", "value": true }))]
 #[test_case(json!({"type": "null"}))]
 #[test_case(json!({"type":"buffer","value":"0xFFFFFF"}))]
+#[test_case(json!({"type": "decimal", "value": "1.000000000000000001" }))]
+#[test_case(json!({"type": "datetime", "value": "2025-01-31T12:00:00Z" }))]
 fn it_deserializes_values(val: JsonValue) {
     let _: Value = serde_json::from_value(val.clone())
         .map_err(|e| format!("failed to deserialize value {}: {}", val, e))
         .unwrap();
 }
 
+#[test]
+fn it_serializes_decimals_as_strings() {
+    let value = Value::parse_decimal("1e27").unwrap();
+    assert_eq!(
+        serde_json::to_value(&value).unwrap(),
+        json!({"type": "decimal", "value": "1000000000000000000000000000"})
+    );
+    assert_eq!(value.to_json(None), json!("1000000000000000000000000000"));
+}
+
+#[test]
+fn it_converts_decimals_without_truncation() {
+    let wei = Value::parse_decimal("1e27").unwrap();
+    assert_eq!(wei.as_u128().unwrap(), Ok(1_000_000_000_000_000_000_000_000_000));
+    assert!(wei.as_uint().unwrap().is_err());
+
+    let amount = Value::parse_decimal("42.0").unwrap();
+    assert_eq!(amount.as_uint().unwrap(), Ok(42));
+    let fractional = Value::parse_decimal("42.5").unwrap();
+    assert!(fractional.as_uint().unwrap().is_err());
+    assert!(fractional.as_u128().unwrap().is_err());
+}
+
+#[test]
+fn it_scales_decimals_by_decimals() {
+    let amount = Value::parse_decimal("1.5").unwrap();
+    let base_units = decimal_to_base_units(amount.expect_decimal(), 6).unwrap();
+    assert_eq!(base_units.to_plain_string(), "1500000");
+    assert_eq!(decimal_from_base_units(&base_units, 6), *amount.expect_decimal());
+
+    let too_precise = Value::parse_decimal("1.0000001").unwrap();
+    assert!(decimal_to_base_units(too_precise.expect_decimal(), 6).is_err());
+}
+
+#[test_case("2025-01-31T12:00:00Z", "2025-01-31T12:00:00Z")]
+#[test_case("2025-01-31T13:30:00+01:30", "2025-01-31T12:00:00Z")]
+#[test_case("2025-01-31T12:00:00.5Z", "2025-01-31T12:00:00.500Z")]
+#[test_case("1738324800", "2025-01-31T12:00:00Z")]
+fn it_parses_datetimes_to_utc(input: &str, expected: &str) {
+    let value = Value::parse_datetime(input).unwrap();
+    assert_eq!(value.to_string(), expected);
+    assert_eq!(value.to_json(None), json!(expected));
+}
+
+#[test_case("2025-01-31")]
+#[test_case("31/01/2025 12:00")]
+#[test_case("")]
+fn it_rejects_malformed_datetimes(input: &str) {
+    assert!(Value::parse_datetime(input).is_err());
+}
+
+#[test]
+fn it_coerces_datetime_inputs() {
+    let from_string = Type::datetime().coerce_value(Value::string("1970-01-01T00:01:00Z".into()));
+    let from_epoch = Type::datetime().coerce_value(Value::integer(60));
+    assert_eq!(from_string.unwrap(), from_epoch.unwrap());
+    assert!(Type::datetime().coerce_value(Value::string("tomorrow".into())).is_err());
+    assert_eq!(
+        Type::string().coerce_value(Value::string("tomorrow".into())).unwrap(),
+        Value::string("tomorrow".into())
+    );
+}
+
+#[test]
+fn it_matches_one_of_types() {
+    let typing = Type::one_of(vec![Type::string(), Type::addon("svm::pubkey")]);
+
+    let matched = typing.matching_type(&Value::string("11111111111111111111111111111111".into()));
+    assert_eq!(matched.unwrap(), &Type::string());
+    let matched = typing.matching_type(&Value::addon(vec![0; 32], "svm::pubkey"));
+    assert_eq!(matched.unwrap(), &Type::addon("svm::pubkey"));
+
+    let err = typing.matching_type(&Value::addon(vec![0; 20], "evm::address")).unwrap_err();
+    assert_eq!(
+        err.message,
+        "expected one of string, addon(svm::pubkey), found addon(evm::address)"
+    );
+    let err = typing.check_value(&Value::integer(1)).unwrap_err();
+    assert_eq!(err.message, "expected one of string, addon(svm::pubkey), found integer");
+
+    assert_eq!(typing.to_string(), "string | addon(svm::pubkey)");
+    assert_eq!(Type::try_from(typing.to_string()).unwrap(), typing);
+}
+
+#[test]
+fn it_coerces_one_of_inputs() {
+    let typing = Type::one_of(vec![Type::integer(), Type::datetime()]);
+    assert_eq!(typing.coerce_value(Value::integer(60)).unwrap(), Value::integer(60));
+    let coerced = typing.coerce_value(Value::string("1970-01-01T00:01:00Z".into())).unwrap();
+    assert_eq!(typing.matching_type(&coerced).unwrap(), &Type::datetime());
+}
+
+fn property(name: &str, typing: Type, optional: bool) -> ObjectProperty {
+    ObjectProperty {
+        name: name.into(),
+        documentation: "".into(),
+        typing,
+        optional,
+        tainting: false,
+        internal: false,
+    }
+}
+
+fn object(entries: Vec<(&str, Value)>) -> Value {
+    ObjectType::from(entries).to_value()
+}
+
+#[test]
+fn it_reports_every_violation_of_strict_objects() {
+    let typing = Type::strict_object(vec![
+        property("name", Type::string(), false),
+        property("decimals", Type::integer(), false),
+        property("owner", Type::strict_object(vec![property("active", Type::bool(), true)]), true),
+    ]);
+    let value = object(vec![
+        ("decimals", Value::string("6".into())),
+        ("owner", object(vec![("active", Value::string("yes".into()))])),
+    ]);
+
+    let violations =
+        typing.type_violations(&value, "token").iter().map(|v| v.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        violations,
+        vec![
+            "token.name: missing required property",
+            "token.decimals: expected integer, found string",
+            "token.owner.active: expected bool, found string",
+        ]
+    );
+    assert!(typing
+        .check_value(&object(vec![
+            ("name", Value::string("usdc".into())),
+            ("decimals", Value::integer(6))
+        ]))
+        .is_ok());
+}
+
+#[test]
+fn it_locates_violations_in_nested_maps() {
+    let field = Type::strict_map(vec![
+        property("name", Type::string(), false),
+        property("indexed", Type::bool(), true),
+    ]);
+    let typing = Type::strict_map(vec![property("field", field, false)]);
+    let value = Value::array(vec![
+        object(vec![(
+            "field",
+            Value::array(vec![object(vec![("name", Value::string("a".into()))])]),
+        )]),
+        object(vec![(
+            "field",
+            Value::array(vec![
+                object(vec![
+                    ("name", Value::string("b".into())),
+                    ("indexed", Value::string("true".into())),
+                ]),
+                object(vec![("name", Value::integer(1))]),
+            ]),
+        )]),
+    ]);
+
+    let violations =
+        typing.type_violations(&value, "event").iter().map(|v| v.to_string()).collect::<Vec<_>>();
+    assert_eq!(
+        violations,
+        vec![
+            "event[1].field[0].indexed: expected bool, found string",
+            "event[1].field[1].name: expected string, found integer",
+        ]
+    );
+}
+
+#[test]
+fn it_checks_entries_of_arbitrary_maps() {
+    let typing = Type::arbitrary_map();
+    let value = Value::array(vec![object(vec![("any", Value::integer(1))]), Value::bool(true)]);
+
+    let violations = typing.type_violations(&value, "event");
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].to_string(), "event[1]: expected object, found bool");
+}
+
+#[test]
+fn it_locates_violations_in_arrays_of_objects() {
+    let typing = Type::array(Type::strict_object(vec![property("amount", Type::integer(), false)]));
+    let value = Value::array(vec![
+        object(vec![("amount", Value::integer(1))]),
+        object(vec![("amount", Value::float(1.5))]),
+        Value::string("2".into()),
+    ]);
+
+    let violations = typing
+        .type_violations(&value, "transfers")
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        violations,
+        vec![
+            "transfers[1].amount: expected integer, found float",
+            "transfers[2]: expected object, found string",
+        ]
+    );
+}
+
+fn fee(entries: Vec<(&str, Value)>) -> Value {
+    ObjectType::from(entries).to_value()
+}
+
+#[test]
+fn it_resolves_addon_defaults_by_precedence() {
+    let mut environment_defaults = ValueMap::new();
+    environment_defaults.insert("chain_id", Value::integer(1));
+    environment_defaults.insert("rpc_api_url", Value::string("http://env".into()));
+    environment_defaults.insert(
+        "fee",
+        fee(vec![("strategy", Value::string("slow".into())), ("max", Value::integer(100))]),
+    );
+
+    let mut addon_defaults = AddonDefaults::new("evm");
+    addon_defaults.scope_mut(None).insert("rpc_api_url", Value::string("http://flow".into()));
+    addon_defaults
+        .scope_mut(None)
+        .insert("fee", fee(vec![("strategy", Value::string("normal".into()))]));
+    addon_defaults.scope_mut(Some("base")).insert("chain_id", Value::integer(8453));
+    addon_defaults
+        .scope_mut(Some("base"))
+        .insert("fee", fee(vec![("strategy", Value::string("fast".into()))]));
+
+    let flow = addon_defaults.resolve(Some(&environment_defaults), None).unwrap();
+    assert_eq!(flow.store.get_integer("chain_id"), Some(1));
+    assert_eq!(flow.store.get_string("rpc_api_url"), Some("http://flow"));
+    assert_eq!(
+        flow.store.get_value("fee"),
+        Some(&fee(vec![
+            ("strategy", Value::string("normal".into())),
+            ("max", Value::integer(100))
+        ]))
+    );
+
+    let base = addon_defaults.resolve(Some(&environment_defaults), Some("base")).unwrap();
+    assert_eq!(base.store.get_integer("chain_id"), Some(8453));
+    assert_eq!(base.store.get_string("rpc_api_url"), Some("http://flow"));
+    assert_eq!(
+        base.store.get_value("fee"),
+        Some(&fee(vec![("strategy", Value::string("fast".into())), ("max", Value::integer(100))]))
+    );
+
+    // the inputs of the action are merged over the resolved object defaults
+    let input = fee(vec![("max", Value::integer(5))]);
+    assert_eq!(
+        base.merge_object_default("fee", input),
+        fee(vec![("strategy", Value::string("fast".into())), ("max", Value::integer(5))])
+    );
+
+    assert!(addon_defaults.resolve(None, Some("arbitrum")).is_err());
+}
+
+fn hex_addresses_as_upper(value: &Value) -> Option<String> {
+    let addon_data = value.as_addon_data().filter(|data| data.id == "evm::address")?;
+    Some(format!("0x{}", hex::encode_upper(&addon_data.bytes)))
+}
+
+#[test]
+fn it_formats_addon_values_for_display() {
+    let formatters: Vec<AddonDisplayFormatter> = vec![Box::new(hex_addresses_as_upper)];
+    let address = Value::addon(vec![0xab; 2], "evm::address");
+    let value = ObjectType::from(vec![
+        ("to", address.clone()),
+        ("hashes", Value::array(vec![Value::addon(vec![0xcd], "std::hash")])),
+    ])
+    .to_value();
+    assert_eq!(value.to_display_string(&formatters), r#"{"to": 0xABAB, "hashes": [0xcd]}"#);
+    assert_eq!(
+        value.interpolate_display("expected 0xabab, found 0xcd", &formatters),
+        "expected 0xABAB, found 0xcd"
+    );
+}
+
+#[test]
+fn it_formats_the_values_of_review_and_output_items() {
+    let address = Value::addon(vec![0xab; 2], "evm::address");
+    let mut actions = Actions::none();
+    actions.push_sub_group(
+        None,
+        vec![
+            ReviewInputRequest::new("to", &address).to_action_type().to_request("a", "to"),
+            ReviewInputRequest::new("nonce", &Value::integer(1))
+                .to_action_type()
+                .to_request("a", "nonce"),
+        ],
+    );
+    let formatters: Vec<AddonDisplayFormatter> = vec![Box::new(hex_addresses_as_upper)];
+    actions.format_values(&|value| {
+        value.contains_addon_data().then(|| value.to_display_string(&formatters))
+    });
+
+    let formatted = actions
+        .get_new_action_item_requests()
+        .into_iter()
+        .map(|item| match &item.action_type {
+            ActionItemRequestType::ReviewInput(request) => request.formatted_value.clone(),
+            _ => unreachable!(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(formatted, vec![Some("0xABAB".to_string()), None]);
+}
+
+#[test]
+fn it_renders_markdown_as_plain_text() {
+    let request = DisplayMarkdownRequest::new(
+        "## Approve the proposal\nOpen [Squads](https://app.squads.so) and **approve** `#42` [x]",
+    )
+    .with_link("Open proposal", "https://app.squads.so/proposals/42");
+    assert_eq!(
+        request.to_plain_text(),
+        "Approve the proposal\nOpen Squads (https://app.squads.so) and approve #42 [x]\nOpen proposal: https://app.squads.so/proposals/42"
+    );
+
+    let action_type = request.to_action_type();
+    assert_eq!(action_type.expected_response_type(), None);
+    let json = serde_json::to_value(&action_type).unwrap();
+    assert_eq!(json["type"], "DisplayMarkdown");
+    assert_eq!(json["data"]["links"][0]["label"], "Open proposal");
+    assert_eq!(serde_json::from_value::<ActionItemRequestType>(json).unwrap(), action_type);
+}
+
+fn function_spec(variadic: bool, accepts_named_arguments: bool) -> FunctionSpecification {
+    fn run(
+        _: &FunctionSpecification,
+        _: &AuthorizationContext,
+        _: &Vec<Value>,
+    ) -> Result<Value, Diagnostic> {
+        Ok(Value::null())
+    }
+    fn check(
+        _: &FunctionSpecification,
+        _: &AuthorizationContext,
+        _: &Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        Ok(Type::null())
+    }
+    let input = |name: &str, optional: bool| FunctionInput {
+        name: name.into(),
+        documentation: "".into(),
+        typing: vec![Type::string()],
+        optional,
+        deprecation: None,
+    };
+    FunctionSpecification {
+        name: "format".into(),
+        documentation: "".into(),
+        inputs: vec![input("template", false), input("locale", true), input("timezone", true)],
+        variadic: variadic.then(|| input("values", true)),
+        accepts_named_arguments,
+        output: FunctionOutput { documentation: "".into(), typing: Type::string() },
+        deprecation: None,
+        example: "".into(),
+        snippet: "".into(),
+        runner: run,
+        checker: check,
+    }
+}
+
+#[test]
+fn it_describes_function_signatures() {
+    assert_eq!(function_spec(false, false).signature(), "format(template, locale?, timezone?)");
+    assert_eq!(
+        function_spec(true, false).signature(),
+        "format(template, locale?, timezone?, values...)"
+    );
+}
+
+#[test]
+fn it_resolves_named_arguments() {
+    let spec = function_spec(false, true);
+    let named = |args: Vec<(&str, &str)>| {
+        Some(args.into_iter().map(|(k, v)| (k.to_string(), Value::string(v.into()))).collect())
+    };
+
+    let args = spec
+        .resolve_arguments(vec![Value::string("{}".into())], named(vec![("timezone", "UTC")]))
+        .unwrap();
+    assert_eq!(args, vec![Value::string("{}".into()), Value::null(), Value::string("UTC".into())]);
+
+    let err = spec
+        .resolve_arguments(vec![Value::string("{}".into())], named(vec![("template", "{}")]))
+        .unwrap_err();
+    assert_eq!(
+        err.message,
+        "function 'format': argument 'template' is given both by position and by name; expected format(template, locale?, timezone?)"
+    );
+
+    let err = spec.resolve_arguments(vec![], named(vec![("timezone", "UTC")])).unwrap_err();
+    assert_eq!(
+        err.message,
+        "function 'format': missing required argument 'template'; expected format(template, locale?, timezone?)"
+    );
+
+    let err = function_spec(false, false)
+        .resolve_arguments(vec![], named(vec![("template", "{}")]))
+        .unwrap_err();
+    assert!(err.message.starts_with("function 'format': named arguments are not supported"));
+}
+
+#[test]
+fn it_checks_variadic_arguments() {
+    let checker = arg_checker_with_ctx("std".into());
+    let spec = function_spec(true, false);
+
+    assert!(checker(&spec, &vec![Value::string("{}".into())]).is_ok());
+    let five_strings = (0..5).map(|i| Value::string(i.to_string())).collect();
+    assert!(checker(&spec, &five_strings).is_ok());
+
+    let mut with_integer = (0..4).map(|i| Value::string(i.to_string())).collect::<Vec<_>>();
+    with_integer.push(Value::integer(4));
+    assert_eq!(
+        checker(&spec, &with_integer).unwrap_err().message,
+        "function 'std::format' argument #5 (values) should be of type (string), found integer"
+    );
+
+    assert_eq!(
+        checker(&spec, &vec![]).unwrap_err().message,
+        "function 'std::format' missing required argument #1 (template); expected format(template, locale?, timezone?, values...)"
+    );
+}
+
+#[test]
+fn it_lists_missing_signer_capabilities() {
+    let web_wallet = SignerCapabilities {
+        provides_public_key: false,
+        supports_offline: false,
+        ..SignerCapabilities::all()
+    };
+    let sign_transaction =
+        SignerCapabilities { sign_transaction: true, ..SignerCapabilities::none() };
+    assert!(web_wallet.missing(&sign_transaction).is_empty());
+    assert!(web_wallet.missing(&SignerCapabilities::none()).is_empty());
+    assert_eq!(
+        web_wallet.missing(&SignerCapabilities::all()),
+        vec!["provide a public key", "sign offline"]
+    );
+    assert_eq!(SignerCapabilities::none().missing(&sign_transaction), vec!["sign transactions"]);
+}
+
+#[test]
+fn it_styles_error_panels_by_level() {
+    let warning = Diagnostic::warning("webhook not acknowledged");
+    let panel = ErrorPanelData::from_diagnostics(&vec![warning.clone()]);
+    assert_eq!(panel.level, DiagnosticLevel::Warning);
+    assert_eq!(panel.title, "EXECUTION WARNING");
+
+    let panel = ErrorPanelData::from_diagnostics(&vec![warning, Diagnostic::error("reverted")]);
+    assert_eq!(panel.level, DiagnosticLevel::Error);
+    let statuses = panel.groups[0].sub_groups[0]
+        .action_items
+        .iter()
+        .map(|item| matches!(item.action_status, ActionItemStatus::Warning(_)))
+        .collect::<Vec<_>>();
+    assert_eq!(statuses, vec![true, false]);
+}
+
 #[test]
 fn it_rejects_invalid_keys() {
     match serde_json::from_value::<Value>(json!({"type": "strin", "value": "my string"})) {
@@ -72,3 +557,207 @@ fn test_auth_context_get_path_from_str(path_str: &str, expected: &str) {
     let result = auth_context.get_file_location_from_path_buf(&PathBuf::from(path_str)).unwrap();
     assert_eq!(result.to_string(), expected);
 }
+
+#[test]
+fn it_reports_the_last_heartbeat_of_running_background_tasks() {
+    let construct_did = ConstructDid(Did::from_components(vec!["deploy"]));
+    let update = |status| ConstructStatusUpdate {
+        construct_did: construct_did.clone(),
+        construct_type: "action".into(),
+        name: "deploy".into(),
+        namespace: "evm".into(),
+        matcher: "deploy_contract".into(),
+        dependencies: vec![],
+        status,
+    };
+    let heartbeat = HeartbeatEvent {
+        construct_did: construct_did.clone(),
+        elapsed: Duration::from_secs(600),
+        detail: Some("2/3 blocks confirmed".into()),
+    };
+
+    let mut store = ConstructStatusStore::new();
+    store.apply(update(ConstructStatus::BackgroundTaskRunning));
+    assert_eq!(store.get(&construct_did).unwrap().last_update(), None);
+
+    store.apply_heartbeat(&heartbeat);
+    assert_eq!(
+        store.get(&construct_did).unwrap().last_update(),
+        Some("last update 0s ago (2/3 blocks confirmed)".into())
+    );
+    assert_eq!(describe_last_update(Duration::from_secs(45), None), "last update 45s ago");
+
+    store.apply(update(ConstructStatus::Completed));
+    assert_eq!(store.get(&construct_did).unwrap().last_update(), None);
+}
+
+#[test]
+fn it_sends_heartbeats_for_background_tasks_making_progress() {
+    let deploy = ConstructDid(Did::from_components(vec!["deploy"]));
+    let transfer = ConstructDid(Did::from_components(vec!["transfer"]));
+    let started_at = Instant::now();
+    let after = |interval: u32| started_at + HEARTBEAT_INTERVAL * interval;
+
+    let mut heartbeats = BackgroundTasksHeartbeats::new();
+    heartbeats.start(&deploy, started_at);
+    heartbeats.start(&transfer, started_at);
+    // tasks that haven't been polled yet send no heartbeat
+    assert_eq!(heartbeats.tick(after(1)).heartbeats, vec![]);
+
+    heartbeats.record_progress(&deploy, after(1));
+    let tick = heartbeats.tick(after(2));
+    assert_eq!(
+        tick.heartbeats,
+        vec![HeartbeatEvent {
+            construct_did: deploy.clone(),
+            elapsed: HEARTBEAT_INTERVAL * 2,
+            detail: None
+        }]
+    );
+    assert_eq!(tick.silent, vec![]);
+    // a single heartbeat is sent per tick, whatever the progress made in between
+    assert_eq!(heartbeats.tick(after(3)).heartbeats, vec![]);
+
+    // stopped tasks are forgotten
+    heartbeats.stop(&deploy);
+    heartbeats.record_progress(&deploy, after(3));
+    assert_eq!(heartbeats.tick(after(4)).heartbeats, vec![]);
+}
+
+#[test]
+fn it_reports_silent_background_tasks_once() {
+    let deploy = ConstructDid(Did::from_components(vec!["deploy"]));
+    let started_at = Instant::now();
+
+    let mut heartbeats = BackgroundTasksHeartbeats::new();
+    heartbeats.start(&deploy, started_at);
+    assert_eq!(heartbeats.tick(started_at + HEARTBEAT_TIMEOUT / 2).silent, vec![]);
+
+    let silent_at = started_at + HEARTBEAT_TIMEOUT;
+    assert_eq!(heartbeats.tick(silent_at).silent, vec![(deploy.clone(), HEARTBEAT_TIMEOUT)]);
+    assert_eq!(heartbeats.tick(silent_at + HEARTBEAT_INTERVAL).silent, vec![]);
+
+    // making progress again resets the silence
+    heartbeats.record_progress(&deploy, silent_at + HEARTBEAT_INTERVAL);
+    assert_eq!(heartbeats.tick(silent_at + HEARTBEAT_INTERVAL * 2).heartbeats.len(), 1);
+    assert_eq!(heartbeats.tick(silent_at + HEARTBEAT_TIMEOUT).silent, vec![]);
+    let silent_again_at = silent_at + HEARTBEAT_INTERVAL + HEARTBEAT_TIMEOUT;
+    assert_eq!(heartbeats.tick(silent_again_at).silent, vec![(deploy, HEARTBEAT_TIMEOUT)]);
+}
+
+#[test]
+fn it_tracks_the_status_transitions_of_constructs() {
+    let deploy = ConstructDid(Did::from_components(vec!["deploy"]));
+    let transfer = ConstructDid(Did::from_components(vec!["transfer"]));
+    let update = |construct_did: &ConstructDid, status| ConstructStatusUpdate {
+        construct_did: construct_did.clone(),
+        construct_type: "action".into(),
+        name: "deploy".into(),
+        namespace: "evm".into(),
+        matcher: "deploy_contract".into(),
+        dependencies: vec!["signer.deployer".into()],
+        status,
+    };
+
+    let mut store = ConstructStatusStore::new();
+    store.apply(update(&deploy, ConstructStatus::Pending));
+    store.apply(update(&transfer, ConstructStatus::Pending));
+    let entry = store.get(&deploy).unwrap();
+    assert_eq!(entry.construct.status, ConstructStatus::Pending);
+    assert_eq!(entry.construct.dependencies, vec!["signer.deployer".to_string()]);
+    assert!(entry.started_at.is_none());
+
+    // the duration is measured from the first time the construct starts executing, including
+    // the time spent awaiting inputs and its background task
+    store.apply(update(&deploy, ConstructStatus::Executing));
+    let started_at = store.get(&deploy).unwrap().started_at.expect("missing start");
+    store.apply(update(&deploy, ConstructStatus::AwaitingInput));
+    store.apply(update(&deploy, ConstructStatus::BackgroundTaskRunning));
+    let entry = store.get(&deploy).unwrap();
+    assert_eq!(entry.construct.status, ConstructStatus::BackgroundTaskRunning);
+    assert_eq!(entry.started_at, Some(started_at));
+    assert_eq!(entry.duration, None);
+
+    // the background task completing completes the construct
+    store.apply(update(&deploy, ConstructStatus::Completed));
+    let entry = store.get(&deploy).unwrap();
+    assert_eq!(entry.construct.status, ConstructStatus::Completed);
+    assert!(entry.duration.is_some());
+
+    store.apply(update(&transfer, ConstructStatus::Executing));
+    store.apply(update(&transfer, ConstructStatus::Failed));
+    assert!(store.get(&transfer).unwrap().duration.is_some());
+
+    // constructs re-evaluated from scratch are reset
+    store.apply(update(&transfer, ConstructStatus::Pending));
+    let entry = store.get(&transfer).unwrap();
+    assert_eq!((entry.started_at, entry.duration), (None, None));
+
+    // constructs keep the order in which they were first reported
+    let construct_dids = store.entries().map(|entry| entry.construct.construct_did.clone());
+    assert_eq!(construct_dids.collect::<Vec<_>>(), vec![deploy, transfer]);
+}
+
+#[test]
+fn it_computes_the_percentage_of_progress() {
+    assert_eq!(LogProgress::steps(3, 12).percentage(), Some(25.0));
+    // steps past the total don't overflow the percentage
+    assert_eq!(LogProgress::steps(14, 12).percentage(), Some(100.0));
+    assert_eq!(LogProgress::steps(3, 0).percentage(), None);
+    assert_eq!(LogProgress::steps(3, 12).with_percentage(40.0).percentage(), Some(40.0));
+    assert_eq!(LogProgress::default().with_percentage(120.0).percentage(), Some(100.0));
+    assert_eq!(LogProgress::default().with_eta_seconds(5).percentage(), None);
+}
+
+#[test_case(LogProgress::steps(3, 10).with_eta_seconds(12), "3/10 30% ETA 12s")]
+#[test_case(LogProgress::steps(3, 10), "3/10 30%")]
+#[test_case(LogProgress { completed_steps: Some(3), ..Default::default() }, "3")]
+#[test_case(LogProgress::default().with_percentage(42.4), "42%")]
+#[test_case(LogProgress::default(), "")]
+fn it_displays_progress(progress: LogProgress, expected: &str) {
+    assert_eq!(progress.to_string(), expected);
+}
+
+#[test]
+fn it_serializes_the_progress_of_transient_log_events() {
+    let uuid = uuid::Uuid::new_v4();
+    let event = LogEvent::Transient(
+        TransientLogEvent::pending_info(uuid, "Confirming", "2 blocks", "txtx::evm")
+            .with_progress(LogProgress::steps(2, 4).with_eta_seconds(24)),
+    );
+    let json = serde_json::to_value(&event).unwrap();
+    assert_eq!(
+        json["log"]["progress"],
+        json!({ "completedSteps": 2, "totalSteps": 4, "etaSeconds": 24 })
+    );
+    let event: LogEvent = serde_json::from_value(json).unwrap();
+    assert_eq!(event.progress(), Some(&LogProgress::steps(2, 4).with_eta_seconds(24)));
+
+    // events without progress are serialized as before
+    let json = serde_json::to_value(&LogEvent::Transient(TransientLogEvent::pending_info(
+        uuid,
+        "Confirming",
+        "2 blocks",
+        "txtx::evm",
+    )))
+    .unwrap();
+    assert!(json["log"].get("progress").is_none());
+    let event: LogEvent = serde_json::from_value(json).unwrap();
+    assert_eq!(event.progress(), None);
+}
+
+#[test]
+fn it_dispatches_pending_progress() {
+    let (tx, rx) = crate::channel::unbounded();
+    let uuid = uuid::Uuid::new_v4();
+    let logger = LogDispatcher::new(uuid, "evm", &tx);
+    logger.pending_progress("Confirming", "3 blocks", LogProgress::steps(3, 6));
+    logger.pending_info("Confirming", "4 blocks");
+
+    let Ok(BlockEvent::LogEvent(event)) = rx.try_recv() else { panic!("missing log event") };
+    assert_eq!(event.uuid(), uuid);
+    assert_eq!(event.namespace(), "txtx::evm");
+    assert_eq!(event.progress(), Some(&LogProgress::steps(3, 6)));
+    let Ok(BlockEvent::LogEvent(event)) = rx.try_recv() else { panic!("missing log event") };
+    assert_eq!(event.progress(), None);
+}
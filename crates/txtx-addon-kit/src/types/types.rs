@@ -1,3 +1,5 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+use chrono::{DateTime, SecondsFormat, Utc};
 use hcl_edit::expr::Expression;
 use hcl_edit::structure::Block;
 use indexmap::IndexMap;
@@ -8,6 +10,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{Map, Value as JsonValue};
 use std::collections::VecDeque;
 use std::fmt::{self, Debug};
+use std::str::FromStr;
 
 use crate::helpers::hcl::{
     collect_constructs_references_from_block, collect_constructs_references_from_expression,
@@ -27,6 +30,10 @@ pub enum Value {
     #[serde(serialize_with = "i128_serializer")]
     Integer(i128),
     Float(f64),
+    #[serde(serialize_with = "decimal_serializer")]
+    Decimal(BigDecimal),
+    #[serde(serialize_with = "datetime_serializer")]
+    DateTime(DateTime<Utc>),
     String(String),
     Array(Box<Vec<Value>>),
     Object(IndexMap<String, Value>),
@@ -44,6 +51,8 @@ impl PartialEq<Value> for Value {
             (Value::Null, Value::Null) => true,
             (Value::Integer(lhs), Value::Integer(rhs)) => lhs == rhs,
             (Value::Float(lhs), Value::Float(rhs)) => lhs == rhs,
+            (Value::Decimal(lhs), Value::Decimal(rhs)) => lhs == rhs,
+            (Value::DateTime(lhs), Value::DateTime(rhs)) => lhs == rhs,
             (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
             (Value::Buffer(lhs), Value::Buffer(rhs)) => lhs == rhs,
             (Value::Object(lhs), Value::Object(rhs)) => {
@@ -90,6 +99,20 @@ where
     ser.serialize_str(&value.to_string())
 }
 
+fn decimal_serializer<S>(value: &BigDecimal, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.serialize_str(&value.to_plain_string())
+}
+
+fn datetime_serializer<S>(value: &DateTime<Utc>, ser: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    ser.serialize_str(&format_datetime(value))
+}
+
 fn hex_serializer<S>(bytes: &Vec<u8>, ser: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -174,6 +197,17 @@ impl<'de> Deserialize<'de> for Value {
                                     return Ok(Value::integer(i128));
                                 }
                                 "float" => return Ok(Value::float(map.next_value()?)),
+                                "decimal" => {
+                                    let value: String = map.next_value()?;
+                                    let decimal = BigDecimal::from_str(&value)
+                                        .map_err(serde::de::Error::custom)?;
+                                    return Ok(Value::decimal(decimal));
+                                }
+                                "datetime" => {
+                                    let value: String = map.next_value()?;
+                                    return Value::parse_datetime(&value)
+                                        .map_err(serde::de::Error::custom);
+                                }
                                 "string" => return Ok(Value::string(map.next_value()?)),
                                 "null" => unreachable!(),
                                 "buffer" => {
@@ -222,6 +256,7 @@ impl<'de> Deserialize<'de> for Value {
 }
 
 pub type AddonJsonConverter<'a> = Box<dyn Fn(&Value) -> Result<Option<JsonValue>, Diagnostic> + 'a>;
+pub type AddonDisplayFormatter<'a> = Box<dyn Fn(&Value) -> Option<String> + 'a>;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunbookCompleteAdditionalInfo {
@@ -409,6 +444,22 @@ impl Value {
     pub fn float(value: f64) -> Value {
         Value::Float(value)
     }
+    pub fn decimal(value: BigDecimal) -> Value {
+        Value::Decimal(value)
+    }
+    pub fn parse_decimal(value: &str) -> Result<Value, String> {
+        BigDecimal::from_str(value.trim())
+            .map(Value::decimal)
+            .map_err(|e| format!("invalid decimal '{}': {}", value, e))
+    }
+    pub fn datetime(value: DateTime<Utc>) -> Value {
+        Value::DateTime(value)
+    }
+    /// Parses an ISO-8601 / RFC 3339 timestamp (e.g. `2025-01-31T12:00:00Z`), or a number of
+    /// seconds since the Unix epoch, into a UTC datetime.
+    pub fn parse_datetime(value: &str) -> Result<Value, String> {
+        parse_datetime(value).map(Value::datetime)
+    }
     pub fn null() -> Value {
         Value::Null
     }
@@ -444,6 +495,7 @@ impl Value {
     pub fn expect_uint(&self) -> Result<u64, String> {
         match &self {
             Value::Integer(value) => i128_to_u64(*value),
+            Value::Decimal(value) => decimal_to_u64(value),
             _ => unreachable!(),
         }
     }
@@ -453,6 +505,18 @@ impl Value {
             _ => unreachable!(),
         }
     }
+    pub fn expect_decimal(&self) -> &BigDecimal {
+        match &self {
+            Value::Decimal(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    pub fn expect_datetime(&self) -> &DateTime<Utc> {
+        match &self {
+            Value::DateTime(value) => value,
+            _ => unreachable!(),
+        }
+    }
     pub fn expect_null(&self) -> () {
         match &self {
             Value::Null => (),
@@ -562,6 +626,16 @@ impl Value {
     pub fn as_uint(&self) -> Option<Result<u64, String>> {
         match &self {
             Value::Integer(value) => Some(i128_to_u64(*value)),
+            Value::Decimal(value) => Some(decimal_to_u64(value)),
+            _ => None,
+        }
+    }
+    pub fn as_u128(&self) -> Option<Result<u128, String>> {
+        match &self {
+            Value::Integer(value) => {
+                Some(u128::try_from(*value).map_err(|e| format!("invalid u128: {e}")))
+            }
+            Value::Decimal(value) => Some(decimal_to_u128(value)),
             _ => None,
         }
     }
@@ -570,6 +644,10 @@ impl Value {
             Value::Integer(value) => {
                 Some(u8::try_from(*value).map_err(|e| format!("invalid u8: {e}")))
             }
+            Value::Decimal(value) => Some(
+                decimal_to_u64(value)
+                    .and_then(|v| u8::try_from(v).map_err(|e| format!("invalid u8: {e}"))),
+            ),
             _ => None,
         }
     }
@@ -578,6 +656,10 @@ impl Value {
             Value::Integer(value) => {
                 Some(u16::try_from(*value).map_err(|e| format!("invalid u16: {e}")))
             }
+            Value::Decimal(value) => Some(
+                decimal_to_u64(value)
+                    .and_then(|v| u16::try_from(v).map_err(|e| format!("invalid u16: {e}"))),
+            ),
             _ => None,
         }
     }
@@ -587,6 +669,46 @@ impl Value {
             _ => None,
         }
     }
+    pub fn as_decimal(&self) -> Option<&BigDecimal> {
+        match &self {
+            Value::Decimal(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Converts a numeric value, or a string holding a number, to a decimal. Floats are
+    /// converted from their shortest representation, so `0.1` becomes exactly `0.1`.
+    pub fn to_decimal(&self) -> Result<BigDecimal, String> {
+        match &self {
+            Value::Integer(value) => Ok(BigDecimal::from(*value)),
+            Value::Decimal(value) => Ok(value.clone()),
+            Value::Float(value) if value.is_finite() => BigDecimal::from_str(&value.to_string())
+                .map_err(|e| format!("invalid decimal '{}': {}", value, e)),
+            Value::String(value) => BigDecimal::from_str(value.trim())
+                .map_err(|e| format!("invalid decimal '{}': {}", value, e)),
+            _ => Err(format!("cannot convert {} to a decimal", self.get_type().to_string())),
+        }
+    }
+    pub fn as_datetime(&self) -> Option<&DateTime<Utc>> {
+        match &self {
+            Value::DateTime(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Converts a datetime, an ISO-8601 string or an integer number of seconds since the Unix
+    /// epoch to a UTC datetime.
+    pub fn to_datetime(&self) -> Result<DateTime<Utc>, String> {
+        match &self {
+            Value::DateTime(value) => Ok(*value),
+            Value::String(value) => parse_datetime(value),
+            Value::Integer(value) => i64::try_from(*value)
+                .ok()
+                .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                .ok_or(format!("invalid datetime: {} is out of range", value)),
+            _ => Err(format!("cannot convert {} to a datetime", self.get_type().to_string())),
+        }
+    }
     pub fn as_null(&self) -> Option<()> {
         match &self {
             Value::Null => Some(()),
@@ -639,6 +761,25 @@ impl Value {
         }
     }
 
+    /// Returns `overrides` merged over this value: the entries of two objects are merged
+    /// recursively, any other value of `overrides` replaces this one.
+    pub fn deep_merge(&self, overrides: &Value) -> Value {
+        match (self, overrides) {
+            (Value::Object(base), Value::Object(overrides)) => {
+                let mut merged = base.clone();
+                for (k, v) in overrides.iter() {
+                    let value = match base.get(k) {
+                        Some(existing) => existing.deep_merge(v),
+                        None => v.clone(),
+                    };
+                    merged.insert(k.clone(), value);
+                }
+                Value::Object(merged)
+            }
+            _ => overrides.clone(),
+        }
+    }
+
     pub fn get_keys_from_object(&self, mut keys: VecDeque<String>) -> Result<Value, Diagnostic> {
         let Some(key) = keys.pop_front() else {
             return Ok(self.clone());
@@ -662,6 +803,8 @@ impl Value {
             (Value::Bool(_), Value::Bool(_)) => true,
             (Value::Integer(_), Value::Integer(_)) => true,
             (Value::Float(_), Value::Float(_)) => true,
+            (Value::Decimal(_), Value::Decimal(_)) => true,
+            (Value::DateTime(_), Value::DateTime(_)) => true,
             (Value::String(_), Value::String(_)) => true,
             (Value::Buffer(_), Value::Buffer(_)) => true,
             (Value::Object(_), Value::Object(_)) => true,
@@ -669,6 +812,8 @@ impl Value {
             (Value::Bool(_), _) => false,
             (Value::Integer(_), _) => false,
             (Value::Float(_), _) => false,
+            (Value::Decimal(_), _) => false,
+            (Value::DateTime(_), _) => false,
             (Value::String(_), _) => false,
             (Value::Buffer(_), _) => false,
             (Value::Object(_), _) => false,
@@ -698,7 +843,10 @@ impl Value {
             }
             Value::String(bytes) => {
                 let bytes = if bytes.starts_with("0x") {
-                    crate::hex::decode(&bytes[2..]).unwrap()
+                    match crate::hex::decode(&bytes[2..]) {
+                        Ok(res) => res,
+                        Err(_) => bytes.as_bytes().to_vec(),
+                    }
                 } else {
                     match crate::hex::decode(&bytes) {
                         Ok(res) => res,
@@ -710,6 +858,8 @@ impl Value {
             Value::Addon(data) => data.bytes.clone(),
             Value::Integer(value) => value.to_be_bytes().to_vec(),
             Value::Float(value) => value.to_be_bytes().to_vec(),
+            Value::Decimal(value) => value.to_plain_string().into_bytes(),
+            Value::DateTime(value) => format_datetime(value).into_bytes(),
             Value::Bool(value) => vec![*value as u8],
             Value::Null => vec![],
             Value::Object(values) => {
@@ -735,7 +885,10 @@ impl Value {
             }
             Value::String(bytes) => {
                 let bytes = if bytes.starts_with("0x") {
-                    crate::hex::decode(&bytes[2..]).unwrap()
+                    match crate::hex::decode(&bytes[2..]) {
+                        Ok(res) => res,
+                        Err(_) => bytes.as_bytes().to_vec(),
+                    }
                 } else {
                     match crate::hex::decode(&bytes) {
                         Ok(res) => res,
@@ -747,6 +900,8 @@ impl Value {
             Value::Addon(data) => data.bytes.clone(),
             Value::Integer(value) => value.to_le_bytes().to_vec(),
             Value::Float(value) => value.to_le_bytes().to_vec(),
+            Value::Decimal(value) => value.to_plain_string().into_bytes(),
+            Value::DateTime(value) => format_datetime(value).into_bytes(),
             Value::Bool(value) => vec![*value as u8],
             Value::Null => vec![],
             Value::Object(values) => {
@@ -791,6 +946,8 @@ impl Value {
             Value::Null => JsonValue::Null,
             Value::Integer(i) => JsonValue::Number(serde_json::Number::from(*i as i64)),
             Value::Float(f) => JsonValue::Number(serde_json::Number::from_f64(*f).unwrap()),
+            Value::Decimal(d) => JsonValue::String(d.to_plain_string()),
+            Value::DateTime(d) => JsonValue::String(format_datetime(d)),
             Value::String(s) => JsonValue::String(s.to_string()),
             Value::Array(vec) => JsonValue::Array(
                 vec.iter().map(|v| v.to_json(addon_converters)).collect::<Vec<JsonValue>>(),
@@ -819,9 +976,138 @@ impl Value {
     }
 }
 
+impl Value {
+    /// The same as [Value::to_string], with addon values formatted by the first of
+    /// `addon_formatters` supporting them (e.g. a checksummed `evm::address`), instead of hex.
+    pub fn to_display_string(&self, addon_formatters: &Vec<AddonDisplayFormatter>) -> String {
+        match self {
+            Value::Array(items) => format!(
+                "[{}]",
+                items
+                    .iter()
+                    .map(|item| item.to_display_string(addon_formatters))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Value::Object(props) => format!(
+                "{{{}}}",
+                props
+                    .iter()
+                    .map(|(k, v)| format!(r#""{}": {}"#, k, v.to_display_string(addon_formatters)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Value::Addon(addon_data) => addon_formatters
+                .iter()
+                .find_map(|formatter| formatter(self))
+                .unwrap_or_else(|| addon_data.to_string()),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Replaces the hex form of the addon values held by `self` with their display form in
+    /// `text`, for messages interpolating values without access to the addons.
+    pub fn interpolate_display(
+        &self,
+        text: &str,
+        addon_formatters: &Vec<AddonDisplayFormatter>,
+    ) -> String {
+        match self {
+            Value::Array(items) => items.iter().fold(text.to_string(), |text, item| {
+                item.interpolate_display(&text, addon_formatters)
+            }),
+            Value::Object(props) => props.values().fold(text.to_string(), |text, prop| {
+                prop.interpolate_display(&text, addon_formatters)
+            }),
+            Value::Addon(addon_data) => {
+                match addon_formatters.iter().find_map(|formatter| formatter(self)) {
+                    Some(display) => text.replace(&addon_data.to_string(), &display),
+                    None => text.to_string(),
+                }
+            }
+            _ => text.to_string(),
+        }
+    }
+
+    /// Whether the value is, or holds, an addon value.
+    pub fn contains_addon_data(&self) -> bool {
+        match self {
+            Value::Addon(_) => true,
+            Value::Array(items) => items.iter().any(|item| item.contains_addon_data()),
+            Value::Object(props) => props.values().any(|prop| prop.contains_addon_data()),
+            _ => false,
+        }
+    }
+}
+
 fn i128_to_u64(i128: i128) -> Result<u64, String> {
     u64::try_from(i128).map_err(|e| format!("invalid uint: {e}"))
 }
+
+fn check_decimal_is_integer(decimal: &BigDecimal) -> Result<(), String> {
+    if decimal.is_integer() {
+        Ok(())
+    } else {
+        Err(format!("decimal {} cannot be converted to an integer without truncation", decimal))
+    }
+}
+
+/// Converts a decimal to an `i128`, failing instead of truncating its fractional part.
+pub fn decimal_to_i128(decimal: &BigDecimal) -> Result<i128, String> {
+    check_decimal_is_integer(decimal)?;
+    decimal.to_i128().ok_or(format!("invalid integer: {} is out of range", decimal))
+}
+
+/// Converts a decimal to a `u128`, failing instead of truncating its fractional part.
+pub fn decimal_to_u128(decimal: &BigDecimal) -> Result<u128, String> {
+    check_decimal_is_integer(decimal)?;
+    decimal.to_u128().ok_or(format!("invalid u128: {} is out of range", decimal))
+}
+
+/// Converts a decimal to a `u64`, failing instead of truncating its fractional part.
+pub fn decimal_to_u64(decimal: &BigDecimal) -> Result<u64, String> {
+    check_decimal_is_integer(decimal)?;
+    decimal.to_u64().ok_or(format!("invalid uint: {} is out of range", decimal))
+}
+
+/// Parses an ISO-8601 / RFC 3339 timestamp, or a number of seconds since the Unix epoch, into a
+/// UTC datetime. Timestamps with an offset are converted to UTC.
+pub fn parse_datetime(value: &str) -> Result<DateTime<Utc>, String> {
+    let trimmed = value.trim();
+    if let Ok(secs) = trimmed.parse::<i64>() {
+        return DateTime::from_timestamp(secs, 0)
+            .ok_or(format!("invalid datetime: {} is out of range", value));
+    }
+    DateTime::parse_from_rfc3339(trimmed)
+        .map(|datetime| datetime.with_timezone(&Utc))
+        .map_err(|e| format!("invalid datetime '{}': {}", value, e))
+}
+
+/// Formats a datetime as an RFC 3339 string in UTC, e.g. `2025-01-31T12:00:00Z`.
+pub fn format_datetime(value: &DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Scales an amount to its base units, e.g. `1.5` with 6 decimals is `1500000`. Fails if the
+/// amount has more fractional digits than `decimals`.
+pub fn decimal_to_base_units(amount: &BigDecimal, decimals: u8) -> Result<BigDecimal, String> {
+    let (digits, scale) = amount.as_bigint_and_exponent();
+    let scaled = BigDecimal::new(digits, scale - decimals as i64);
+    if !scaled.is_integer() {
+        return Err(format!(
+            "amount {} has more than {} decimals and cannot be converted to base units without truncation",
+            amount, decimals
+        ));
+    }
+    Ok(scaled.with_scale(0))
+}
+
+/// Scales an amount of base units back to a decimal amount, e.g. `1500000` with 6 decimals is
+/// `1.5`.
+pub fn decimal_from_base_units(amount: &BigDecimal, decimals: u8) -> BigDecimal {
+    let (digits, scale) = amount.as_bigint_and_exponent();
+    BigDecimal::new(digits, scale + decimals as i64).normalized()
+}
 impl Value {
     pub fn to_string(&self) -> String {
         match self {
@@ -829,6 +1115,8 @@ impl Value {
             Value::Bool(val) => val.to_string(),
             Value::Integer(val) => val.to_string(),
             Value::Float(val) => val.to_string(),
+            Value::Decimal(val) => val.to_plain_string(),
+            Value::DateTime(val) => format_datetime(val),
             Value::Null => "null".to_string(),
             Value::Buffer(bytes) => {
                 format!("0x{}", hex::encode(&bytes))
@@ -863,6 +1151,8 @@ impl Value {
             Value::Bool(val) => val.to_string(),
             Value::Integer(val) => val.to_string(),
             Value::Float(val) => val.to_string(),
+            Value::Decimal(val) => format!(r#""{}""#, val.to_plain_string()),
+            Value::DateTime(val) => format!(r#""{}""#, format_datetime(val)),
             Value::Null => "null".to_string(),
             Value::Buffer(bytes) => {
                 format!(r#""0x{}""#, hex::encode(&bytes))
@@ -899,10 +1189,11 @@ impl Value {
         let res = match value {
             Val::Null => Value::null(),
             Val::Bool(val) => Value::bool(*val),
-            Val::Num(val) => val
-                .parse::<i128>()
-                .map(Value::integer)
-                .map_err(|e| format!("Failed to parse number: {}", e))?,
+            Val::Num(val) => match val.parse::<i128>() {
+                Ok(value) => Value::integer(value),
+                Err(e) => Value::parse_decimal(val)
+                    .map_err(|_| format!("Failed to parse number: {}", e))?,
+            },
             Val::Int(val) => i128::try_from(*val)
                 .map(Value::integer)
                 .map_err(|e| format!("Failed to convert integer: {}", e))?,
@@ -928,6 +1219,8 @@ impl Value {
             Value::Null => Type::null(),
             Value::Integer(_) => Type::Integer,
             Value::Float(_) => Type::Float,
+            Value::Decimal(_) => Type::Decimal,
+            Value::DateTime(_) => Type::DateTime,
             Value::String(_) => Type::String,
             Value::Buffer(_) => Type::Buffer,
             Value::Object(_) => Type::Object(ObjectDefinition::arbitrary()),
@@ -1027,12 +1320,16 @@ pub enum Type {
     Null(Option<Box<Type>>),
     Integer,
     Float,
+    Decimal,
+    DateTime,
     String,
     Buffer,
     Object(ObjectDefinition),
     Addon(String),
     Array(Box<Type>),
     Map(ObjectDefinition),
+    /// Any of the listed types, e.g. an address provided either as a string or as an addon value
+    OneOf(Vec<Type>),
 }
 
 impl Type {
@@ -1045,6 +1342,13 @@ impl Type {
     pub fn float() -> Type {
         Type::Float
     }
+    pub fn decimal() -> Type {
+        Type::Decimal
+    }
+    /// A UTC instant, provided as an ISO-8601 string or a number of seconds since the Unix epoch.
+    pub fn datetime() -> Type {
+        Type::DateTime
+    }
     pub fn null() -> Type {
         Type::Null(None)
     }
@@ -1087,75 +1391,135 @@ impl Type {
     pub fn array(array_item_type: Type) -> Type {
         Type::Array(Box::new(array_item_type))
     }
+    pub fn one_of(types: Vec<Type>) -> Type {
+        Type::OneOf(types)
+    }
 
-    pub fn check_value(&self, value: &Value) -> Result<(), Diagnostic> {
-        let mismatch_err = |expected: &str| {
-            Diagnostic::error_from_string(format!(
-                "expected {}, got {}",
-                expected,
-                value.get_type().to_string()
-            ))
+    /// Converts an evaluated input value to the representation of this type, when the value is
+    /// an accepted notation for it, e.g. an ISO-8601 string or epoch seconds for a datetime.
+    /// Values of other types are returned unchanged.
+    pub fn coerce_value(&self, value: Value) -> Result<Value, String> {
+        match (self, &value) {
+            (Type::DateTime, Value::String(_) | Value::Integer(_)) => {
+                value.to_datetime().map(Value::datetime)
+            }
+            (Type::OneOf(types), _) => {
+                if self.matching_type(&value).is_ok() {
+                    return Ok(value);
+                }
+                // the value is converted by the first member accepting its notation
+                let coerced = types.iter().find_map(|typing| {
+                    typing
+                        .coerce_value(value.clone())
+                        .ok()
+                        .filter(|coerced| typing.matching_type(coerced).is_ok())
+                });
+                Ok(coerced.unwrap_or(value))
+            }
+            _ => Ok(value),
+        }
+    }
+
+    /// Returns the member of a one-of type accepting `value`, or the type itself for other types.
+    /// Members typed as addon values only accept values of the same addon type.
+    pub fn matching_type(&self, value: &Value) -> Result<&Type, Diagnostic> {
+        let Type::OneOf(types) = self else {
+            return self.check_value(value).map(|_| self);
         };
+        types
+            .iter()
+            .find(|typing| match (typing, value) {
+                (Type::Addon(addon_type), Value::Addon(data)) => data.id.eq(addon_type),
+                (typing, value) => typing.matching_type(value).is_ok(),
+            })
+            .ok_or_else(|| {
+                Diagnostic::error_from_string(format!(
+                    "expected one of {}, found {}",
+                    types.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(", "),
+                    value.get_type().to_string()
+                ))
+            })
+    }
 
-        match &self {
-            Type::Bool => value.as_bool().map(|_| ()).ok_or_else(|| mismatch_err("bool"))?,
-            Type::Null(inner) => {
-                if let Some(inner) = inner {
-                    value
-                        .as_null()
-                        .map(|_| ())
-                        .ok_or_else(|| mismatch_err(&format!("null<{}>", inner.to_string())))?
-                } else {
-                    value.as_null().map(|_| ()).ok_or_else(|| mismatch_err("null"))?
+    pub fn check_value(&self, value: &Value) -> Result<(), Diagnostic> {
+        let violations = self.type_violations(value, "");
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(Diagnostic::error_from_string(
+            violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("; "),
+        ))
+    }
+
+    /// Lists every part of `value` violating this type, rather than stopping at the first one.
+    /// Violations are located by their path from `root`, e.g. `event[2].field[0].indexed`.
+    pub fn type_violations(&self, value: &Value, root: &str) -> Vec<TypeViolation> {
+        let mut violations = vec![];
+        self.collect_type_violations(value, root.to_string(), &mut violations);
+        violations
+    }
+
+    fn collect_type_violations(
+        &self,
+        value: &Value,
+        path: String,
+        violations: &mut Vec<TypeViolation>,
+    ) {
+        let accepted = match &self {
+            Type::Bool => value.as_bool().is_some(),
+            Type::Null(_) => value.as_null().is_some(),
+            Type::Integer => value.as_integer().is_some(),
+            Type::Float => value.as_float().is_some(),
+            Type::Decimal => value.as_decimal().is_some(),
+            Type::DateTime => value.as_datetime().is_some(),
+            Type::String => value.as_string().is_some(),
+            Type::Buffer => value.as_buffer_data().is_some(),
+            Type::Addon(_) => value.as_addon_data().is_some(),
+            Type::Array(item_type) => {
+                let Some(items) = value.as_array() else {
+                    violations.push(TypeViolation::mismatch(path, self, value));
+                    return;
+                };
+                // arrays of null are untyped, and addons decode the items of addon-typed arrays
+                // from the other notations they accept (e.g. addresses given as strings)
+                if !matches!(**item_type, Type::Null(None) | Type::Addon(_)) {
+                    for (i, item) in items.iter().enumerate() {
+                        item_type.collect_type_violations(item, format!("{path}[{i}]"), violations);
+                    }
                 }
+                true
             }
-            Type::Integer => {
-                value.as_integer().map(|_| ()).ok_or_else(|| mismatch_err("integer"))?
-            }
-            Type::Float => value.as_float().map(|_| ()).ok_or_else(|| mismatch_err("float"))?,
-            Type::String => value.as_string().map(|_| ()).ok_or_else(|| mismatch_err("string"))?,
-            Type::Buffer => {
-                value.as_buffer_data().map(|_| ()).ok_or_else(|| mismatch_err("buffer"))?
+            Type::Object(object_def) => {
+                object_def.collect_type_violations(value, path, violations);
+                return;
             }
-            Type::Addon(addon_type) => value
-                .as_addon_data()
-                .map(|_| ())
-                .ok_or_else(|| mismatch_err(&format!("addon type '{}'", addon_type)))?,
-            Type::Array(array_type) => value
-                .as_array()
-                .map(|_| ())
-                .ok_or_else(|| mismatch_err(&format!("array<{}>", array_type.to_string())))?,
-            Type::Object(object_def) | Type::Map(object_def) => match object_def {
-                ObjectDefinition::Strict(props) => {
-                    let object = value.as_object().ok_or_else(|| mismatch_err("object"))?;
-                    for expected_prop in props.iter() {
-                        let prop_value = object.get(&expected_prop.name);
-                        if expected_prop.optional && prop_value.is_none() {
-                            continue;
-                        }
-                        let prop_value = prop_value.ok_or_else(|| {
-                            Diagnostic::error_from_string(format!(
-                                "missing required property '{}'",
-                                expected_prop.name,
-                            ))
-                        })?;
-                        expected_prop.typing.check_value(prop_value).map_err(|e| {
-                            Diagnostic::error_from_string(format!(
-                                "object property '{}': {}",
-                                expected_prop.name, e.message
-                            ))
-                        })?;
+            // maps are evaluated to an array of objects, one per block
+            Type::Map(object_def) => match value.as_array() {
+                Some(entries) => {
+                    for (i, entry) in entries.iter().enumerate() {
+                        object_def.collect_type_violations(
+                            entry,
+                            format!("{path}[{i}]"),
+                            violations,
+                        );
                     }
+                    true
                 }
-                ObjectDefinition::Arbitrary(_) => {
-                    let _ = value.as_object().ok_or_else(|| mismatch_err("object"))?;
+                None => {
+                    object_def.collect_type_violations(value, path, violations);
+                    return;
                 }
-                ObjectDefinition::Tuple(_) | ObjectDefinition::Enum(_) => {
-                    unimplemented!("ObjectDefinition::Tuple and ObjectDefinition::Enum are not supported for runbook types");
+            },
+            Type::OneOf(_) => {
+                if let Err(e) = self.matching_type(value) {
+                    violations.push(TypeViolation { path, message: e.message });
                 }
-            }, //  => todo!(),
+                return;
+            }
         };
-        Ok(())
+        if !accepted {
+            violations.push(TypeViolation::mismatch(path, self, value));
+        }
     }
 
     pub fn as_object(&self) -> Option<&ObjectDefinition> {
@@ -1288,12 +1652,17 @@ impl Type {
             }
             Type::Integer => "integer".into(),
             Type::Float => "float".into(),
+            Type::Decimal => "decimal".into(),
+            Type::DateTime => "datetime".into(),
             Type::String => "string".into(),
             Type::Buffer => "buffer".into(),
             Type::Object(_) => "object".into(),
             Type::Addon(addon) => format!("addon({})", addon),
             Type::Array(typing) => format!("array[{}]", typing.to_string()),
             Type::Map(_) => "map".into(),
+            Type::OneOf(types) => {
+                types.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" | ")
+            }
         }
     }
 }
@@ -1310,9 +1679,17 @@ impl TryFrom<String> for Type {
             "string" => Type::String,
             "integer" => Type::Integer,
             "float" => Type::Float,
+            "decimal" => Type::Decimal,
+            "datetime" => Type::DateTime,
             "bool" => Type::Bool,
             "buffer" => Type::Buffer,
             "object" => Type::Object(ObjectDefinition::arbitrary()),
+            other if split_type_alternatives(other).len() > 1 => Type::OneOf(
+                split_type_alternatives(other)
+                    .into_iter()
+                    .map(|alternative| Type::try_from(alternative.to_string()))
+                    .collect::<Result<_, _>>()?,
+            ),
             other => {
                 if other == "null" {
                     return Ok(Type::null());
@@ -1339,6 +1716,27 @@ impl TryFrom<String> for Type {
     }
 }
 
+/// Splits the alternatives of a one-of type (`string | addon(svm::pubkey)`), ignoring the
+/// separators nested in the brackets of array or null types.
+fn split_type_alternatives(typing: &str) -> Vec<&str> {
+    let mut alternatives = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in typing.char_indices() {
+        match c {
+            '[' | '<' | '(' => depth += 1,
+            ']' | '>' | ')' => depth -= 1,
+            '|' if depth == 0 => {
+                alternatives.push(typing[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    alternatives.push(typing[start..].trim());
+    alternatives
+}
+
 impl Serialize for Type {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1359,6 +1757,32 @@ impl<'de> Deserialize<'de> for Type {
     }
 }
 
+/// A part of a value violating the type it is checked against
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypeViolation {
+    /// Path of the part within the checked value, e.g. `event[2].field[0].indexed`
+    pub path: String,
+    pub message: String,
+}
+
+impl TypeViolation {
+    fn mismatch(path: String, expected: &Type, value: &Value) -> Self {
+        let message =
+            format!("expected {}, found {}", expected.to_string(), value.get_type().to_string());
+        TypeViolation { path, message }
+    }
+}
+
+impl fmt::Display for TypeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub enum ObjectDefinition {
     /// Strict object definition with a list of properties
@@ -1396,6 +1820,43 @@ impl ObjectDefinition {
         ObjectDefinition::Enum(props)
     }
 
+    fn collect_type_violations(
+        &self,
+        value: &Value,
+        path: String,
+        violations: &mut Vec<TypeViolation>,
+    ) {
+        let Some(object) = value.as_object() else {
+            let message = format!("expected object, found {}", value.get_type().to_string());
+            violations.push(TypeViolation { path, message });
+            return;
+        };
+        match self {
+            ObjectDefinition::Strict(props) => {
+                for prop in props.iter() {
+                    let prop_path = match path.is_empty() {
+                        true => prop.name.clone(),
+                        false => format!("{path}.{}", prop.name),
+                    };
+                    match object.get(&prop.name) {
+                        Some(prop_value) => {
+                            prop.typing.collect_type_violations(prop_value, prop_path, violations)
+                        }
+                        None if prop.optional => {}
+                        None => violations.push(TypeViolation {
+                            path: prop_path,
+                            message: "missing required property".into(),
+                        }),
+                    }
+                }
+            }
+            ObjectDefinition::Arbitrary(_) => {}
+            ObjectDefinition::Tuple(_) | ObjectDefinition::Enum(_) => {
+                unimplemented!("ObjectDefinition::Tuple and ObjectDefinition::Enum are not supported for runbook types");
+            }
+        }
+    }
+
     pub fn join_documentation(&self, recursion_depth: usize) -> String {
         match self {
             ObjectDefinition::Strict(props) | ObjectDefinition::Arbitrary(Some(props)) => props
@@ -61,3 +61,39 @@ pub fn extract_addon_specifications(
 
     specifications
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use txtx_core::errors::codes::CODES as CORE_CODES;
+
+    use super::get_all_addons;
+
+    #[test]
+    fn diagnostic_codes_are_unique_across_the_workspace() {
+        let mut codes =
+            CORE_CODES.iter().map(|code| ("CORE".to_string(), *code)).collect::<Vec<_>>();
+        for addon in get_all_addons().iter() {
+            let namespace = addon.get_namespace().to_uppercase();
+            codes.extend(
+                addon.get_diagnostic_codes().into_iter().map(|code| (namespace.clone(), code)),
+            );
+        }
+
+        let mut seen = HashSet::new();
+        for (namespace, code) in codes.iter() {
+            assert!(seen.insert(code.code), "diagnostic code {} is registered twice", code.code);
+            assert_eq!(
+                code.namespace(),
+                namespace.as_str(),
+                "diagnostic code {} is out of its namespace",
+                code.code
+            );
+            let number = code
+                .number()
+                .unwrap_or_else(|| panic!("diagnostic code {} is malformed", code.code));
+            assert!(number < 300, "diagnostic code {} is out of the known ranges", code.code);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Exit codes of the txtx commands
+//!
+//! Each class of failure exits with its own code, so that scripts wrapping txtx can tell a
+//! runbook with a syntax error from a reverted transaction or a cancelled execution.
+
+use std::fmt::Display;
+
+use txtx_core::kit::types::diagnostics::{Diagnostic, DiagnosticKind};
+
+/// Listed in `txtx --help`
+pub const EXIT_CODES_HELP: &str = "\
+Exit codes:
+  0  Success
+  1  Execution failure (e.g. a transaction reverted)
+  2  Validation failure (syntax errors, unknown references, invalid inputs)
+  3  Configuration error (manifest, environments, input files)
+  4  Cancelled by the operator
+  5  Timeout";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    ExecutionFailure = 1,
+    ValidationFailure = 2,
+    ConfigurationError = 3,
+    Cancelled = 4,
+    Timeout = 5,
+}
+
+impl ExitCode {
+    pub fn code(&self) -> i32 {
+        *self as i32
+    }
+
+    /// Returns the exit code of the first error reported. Failures that are not classified are
+    /// execution failures.
+    pub fn from_diagnostics(diags: &[Diagnostic]) -> ExitCode {
+        diags
+            .iter()
+            .find(|diag| diag.is_error())
+            .or(diags.first())
+            .and_then(|diag| diag.kind)
+            .map(ExitCode::from)
+            .unwrap_or(ExitCode::ExecutionFailure)
+    }
+
+    pub fn exit(&self) -> ! {
+        std::process::exit(self.code())
+    }
+}
+
+impl From<DiagnosticKind> for ExitCode {
+    fn from(kind: DiagnosticKind) -> Self {
+        match kind {
+            DiagnosticKind::Execution => ExitCode::ExecutionFailure,
+            DiagnosticKind::Validation => ExitCode::ValidationFailure,
+            DiagnosticKind::Configuration => ExitCode::ConfigurationError,
+            DiagnosticKind::Cancelled => ExitCode::Cancelled,
+            DiagnosticKind::Timeout => ExitCode::Timeout,
+        }
+    }
+}
+
+/// Failure of a command, along with the code to exit with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError {
+    pub message: String,
+    pub exit_code: ExitCode,
+}
+
+impl CliError {
+    pub fn new(exit_code: ExitCode, message: impl Into<String>) -> Self {
+        CliError { message: message.into(), exit_code }
+    }
+
+    pub fn configuration(message: impl Into<String>) -> Self {
+        CliError::new(ExitCode::ConfigurationError, message)
+    }
+
+    pub fn cancelled() -> Self {
+        CliError::new(ExitCode::Cancelled, "execution cancelled")
+    }
+
+    pub fn from_diagnostics(message: impl Into<String>, diags: &[Diagnostic]) -> Self {
+        CliError::new(ExitCode::from_diagnostics(diags), message)
+    }
+}
+
+/// Errors that are not classified are execution failures.
+impl From<String> for CliError {
+    fn from(message: String) -> Self {
+        CliError::new(ExitCode::ExecutionFailure, message)
+    }
+}
+
+impl Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_of_diagnostics() {
+        assert_eq!(ExitCode::from_diagnostics(&[]), ExitCode::ExecutionFailure);
+
+        let unclassified = Diagnostic::error("transaction reverted");
+        assert_eq!(ExitCode::from_diagnostics(&[unclassified]), ExitCode::ExecutionFailure);
+
+        // the first error wins over the warnings reported before it
+        let diags = vec![
+            Diagnostic::warning("deprecated attribute").with_kind(DiagnosticKind::Validation),
+            Diagnostic::error("blockhash expired").with_kind(DiagnosticKind::Timeout),
+            Diagnostic::error("unknown reference").with_kind(DiagnosticKind::Validation),
+        ];
+        assert_eq!(ExitCode::from_diagnostics(&diags), ExitCode::Timeout);
+    }
+
+    #[test]
+    fn test_default_kind_does_not_override_classification() {
+        let diag = Diagnostic::error("blockhash expired")
+            .with_kind(DiagnosticKind::Timeout)
+            .with_default_kind(DiagnosticKind::Validation);
+        assert_eq!(diag.kind, Some(DiagnosticKind::Timeout));
+        let diag =
+            Diagnostic::error("unknown reference").with_default_kind(DiagnosticKind::Validation);
+        assert_eq!(ExitCode::from_diagnostics(&[diag]), ExitCode::ValidationFailure);
+    }
+}
@@ -1,3 +1,4 @@
 // Common utilities shared across CLI commands
 
 pub mod addon_registry;
+pub mod exit_code;
@@ -1,11 +1,12 @@
 use std::fs::File;
 use std::path::PathBuf;
 
+mod serve;
+
+use super::common::addon_registry::get_all_addons;
 use super::{Context, GetDocumentation};
 use itertools::Itertools;
 use serde_json::json;
-use txtx_addon_network_evm::EvmNetworkAddon;
-use txtx_addon_network_svm::SvmNetworkAddon;
 use txtx_core::kit::helpers::fs::FileLocation;
 use txtx_core::kit::indexmap::IndexMap;
 use txtx_core::kit::types::commands::{CommandOutput, PreCommandSpecification};
@@ -17,28 +18,46 @@ use txtx_core::kit::{
 use txtx_core::mustache;
 use txtx_core::std::commands::actions::http;
 use txtx_core::std::functions::{assertions, base64, crypto, hash, hex, json, list, operators};
-use txtx_core::std::StdAddon;
 use txtx_gql::kit::types::commands::{PostConditionEvaluatableInput, PreConditionEvaluatableInput};
 use txtx_gql::kit::types::types::Type;
 use txtx_gql::kit::types::EvaluatableInput;
 
-pub async fn handle_docs_command(_cmd: &GetDocumentation, _ctx: &Context) -> Result<(), String> {
-    let std: Box<dyn Addon> = Box::new(StdAddon::new());
-    let evm: Box<dyn Addon> = Box::new(EvmNetworkAddon::new());
-    let svm: Box<dyn Addon> = Box::new(SvmNetworkAddon::new());
+/// Generates the documentation of the installed addons in `cmd.output_dir`, from the
+/// specifications of the addon versions compiled in this binary.
+pub async fn handle_docs_command(cmd: &GetDocumentation, _ctx: &Context) -> Result<(), String> {
+    let installed_addons = get_all_addons();
+    let addons = installed_addons
+        .iter()
+        .filter(|addon| match cmd.namespace {
+            Some(ref namespace) => addon.get_namespace().eq(namespace),
+            None => true,
+        })
+        .collect::<Vec<_>>();
+    if addons.is_empty() {
+        return Err(format!(
+            "unknown addon '{}' (installed addons: {})",
+            cmd.namespace.as_deref().unwrap_or_default(),
+            installed_addons.iter().map(|addon| addon.get_namespace()).join(", ")
+        ));
+    }
 
-    let addons = vec![&std, &evm, &svm];
+    let output_dir = PathBuf::from(&cmd.output_dir);
+    if !cmd.serve {
+        display_documentation(&addons);
+    }
+    generate_mdx(&addons, &output_dir)?;
+    generate_json(&addons, &output_dir)
+        .map_err(|e| format!("Failed to generate JSON documentation: {}", e))?;
+    println!("{} Documentation written to {}", green!("✓"), output_dir.display());
 
-    display_documentation(&addons);
-    generate_mdx(&addons);
-    generate_json(&addons).map_err(|e| format!("Failed to generate JSON documentation: {}", e))?;
+    if cmd.serve {
+        serve::serve_documentation(output_dir, cmd.port).await?;
+    }
     Ok(())
 }
 
-pub fn generate_json(addons: &Vec<&Box<dyn Addon>>) -> Result<(), String> {
-    let mut path = PathBuf::new();
-    path.push("doc");
-    path.push("addons");
+pub fn generate_json(addons: &Vec<&Box<dyn Addon>>, output_dir: &PathBuf) -> Result<(), String> {
+    let mut path = output_dir.clone();
     std::fs::create_dir_all(&path).map_err(|e| format!("Failed to create directories: {}", e))?;
     path.push("actions.json");
 
@@ -64,130 +83,104 @@ pub fn generate_json(addons: &Vec<&Box<dyn Addon>>) -> Result<(), String> {
     let content = json!(docs);
     let formatted_content =
         serde_json::to_string_pretty(&content).expect("unable to pretty print docs");
-    let _ = file.write_content(formatted_content.as_bytes());
+    file.write_content(formatted_content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", file, e))?;
     return Ok(());
 }
 
-pub fn generate_mdx(addons: &Vec<&Box<dyn Addon>>) {
-    let mut path = PathBuf::new();
-    path.push("doc");
-    path.push("addons");
+pub fn generate_mdx(addons: &Vec<&Box<dyn Addon>>, output_dir: &PathBuf) -> Result<(), String> {
     for addon in addons.iter() {
-        let mut addon_path = path.clone();
+        let mut addon_path = output_dir.clone();
         let addon_ns = addon.get_namespace();
         addon_path.push(addon_ns);
 
         if addon_ns == "std" {
-            generate_std_mdx(addon, addon_path);
+            generate_std_mdx(addon, addon_path)?;
         } else {
-            generate_addon_mdx(addon, addon_path);
+            generate_addon_mdx(addon, addon_path)?;
         }
     }
+    Ok(())
 }
 
-pub fn generate_std_mdx(addon: &Box<dyn Addon>, addon_path: PathBuf) {
-    // functions
-    {
-        let map = vec![
-            ("json", "JSON", json::JSON_FUNCTIONS.clone()),
-            ("hex", "Hex", hex::FUNCTIONS.clone()),
-            ("operators", "Operator", operators::OPERATORS_FUNCTIONS.clone()),
-            ("crypto", "Crypto", crypto::FUNCTIONS.clone()),
-            ("list", "List", list::LIST_FUNCTIONS.clone()),
-            ("base64", "Base64", base64::FUNCTIONS.clone()),
-            ("hash", "Hash", hash::FUNCTIONS.clone()),
-            ("assertions", "Assertions", assertions::FUNCTIONS.clone()),
-        ];
-        for (path, title, fns) in map.into_iter() {
-            let mut page_path = addon_path.clone();
-            page_path.push("functions");
-            page_path.push(path);
-            page_path.push("page.mdx");
-            let mut doc_file = File::create(&page_path)
-                .expect(format!("creation failed for {}", page_path.display()).as_str());
-            let doc_data = build_addon_function_group_doc_data(&addon, title, fns);
-            let template = mustache::compile_str(&DEFAULT_ADDON_FUNCTIONS_TEMPLATE)
-                .expect("Failed to compile template");
-            template.render_data(&mut doc_file, &doc_data).expect("Failed to render template");
-        }
-    }
-    // actions
-    {
-        let map = vec![("http", "HTTP", vec![http::SEND_HTTP_REQUEST.clone()])];
-        for (path, title, actions) in map.into_iter() {
-            let mut page_path = addon_path.clone();
-            page_path.push("actions");
-            page_path.push(path);
-            page_path.push("page.mdx");
-            let mut doc_file = File::create(page_path).expect("creation failed");
-            let doc_data = build_addon_action_group_doc_data(&addon, title, actions);
-            let template = mustache::compile_str(&DEFAULT_ADDON_ACTIONS_TEMPLATE)
-                .expect("Failed to compile template");
-            template.render_data(&mut doc_file, &doc_data).expect("Failed to render template");
-        }
-    }
-    // an overview page for each category (functions/actions)
-    {
-        let mut page_path = addon_path.clone();
-        page_path.push("functions/overview/page.mdx");
-        let mut doc_file = File::create(page_path).expect("creation failed");
-        let doc_data = build_addon_overview_doc_data(&addon);
-        let template = mustache::compile_str(&DEFAULT_ADDON_OVERVIEW_TEMPLATE)
-            .expect("Failed to compile template");
-        template.render_data(&mut doc_file, &doc_data).expect("Failed to render template");
-    }
-    {
-        let mut page_path = addon_path.clone();
-        page_path.push("actions/overview/page.mdx");
-        let mut doc_file = File::create(page_path).expect("creation failed");
-        let doc_data = build_addon_overview_doc_data(&addon);
-        let template = mustache::compile_str(&DEFAULT_ADDON_OVERVIEW_TEMPLATE)
-            .expect("Failed to compile template");
-        template.render_data(&mut doc_file, &doc_data).expect("Failed to render template");
+/// Renders `template` with `data` into `page_path`, creating the missing directories.
+fn render_page(page_path: PathBuf, template: &str, data: &mustache::Data) -> Result<(), String> {
+    if let Some(parent) = page_path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
     }
+    let mut doc_file = File::create(&page_path)
+        .map_err(|e| format!("creation failed for {}: {}", page_path.display(), e))?;
+    let template = mustache::compile_str(template)
+        .map_err(|e| format!("Failed to compile template: {}", e))?;
+    template
+        .render_data(&mut doc_file, data)
+        .map_err(|e| format!("Failed to render {}: {}", page_path.display(), e))
 }
 
-pub fn generate_addon_mdx(addon: &Box<dyn Addon>, addon_path: PathBuf) {
+pub fn generate_std_mdx(addon: &Box<dyn Addon>, addon_path: PathBuf) -> Result<(), String> {
     // functions
-    {
-        let mut page_path = addon_path.clone();
-        page_path.push("functions/page.mdx");
-        let mut doc_file = File::create(page_path).expect("creation failed");
-        let doc_data = build_addon_function_doc_data(&addon);
-        let template = mustache::compile_str(&DEFAULT_ADDON_FUNCTIONS_TEMPLATE)
-            .expect("Failed to compile template");
-        template.render_data(&mut doc_file, &doc_data).expect("Failed to render template");
+    let map = vec![
+        ("json", "JSON", json::JSON_FUNCTIONS.clone()),
+        ("hex", "Hex", hex::FUNCTIONS.clone()),
+        ("operators", "Operator", operators::OPERATORS_FUNCTIONS.clone()),
+        ("crypto", "Crypto", crypto::FUNCTIONS.clone()),
+        ("list", "List", list::LIST_FUNCTIONS.clone()),
+        ("base64", "Base64", base64::FUNCTIONS.clone()),
+        ("hash", "Hash", hash::FUNCTIONS.clone()),
+        ("assertions", "Assertions", assertions::FUNCTIONS.clone()),
+    ];
+    for (path, title, fns) in map.into_iter() {
+        let doc_data = build_addon_function_group_doc_data(&addon, title, fns);
+        render_page(
+            addon_path.join("functions").join(path).join("page.mdx"),
+            &DEFAULT_ADDON_FUNCTIONS_TEMPLATE,
+            &doc_data,
+        )?;
     }
     // actions
-    {
-        let mut page_path = addon_path.clone();
-        page_path.push("actions/page.mdx");
-        let mut doc_file = File::create(page_path).expect("creation failed");
-        let doc_data = build_addon_action_doc_data(&addon);
-        let template = mustache::compile_str(&DEFAULT_ADDON_ACTIONS_TEMPLATE)
-            .expect("Failed to compile template");
-        template.render_data(&mut doc_file, &doc_data).expect("Failed to render template");
+    let map = vec![("http", "HTTP", vec![http::SEND_HTTP_REQUEST.clone()])];
+    for (path, title, actions) in map.into_iter() {
+        let doc_data = build_addon_action_group_doc_data(&addon, title, actions);
+        render_page(
+            addon_path.join("actions").join(path).join("page.mdx"),
+            &DEFAULT_ADDON_ACTIONS_TEMPLATE,
+            &doc_data,
+        )?;
     }
-    // signers
-    {
-        let mut page_path = addon_path.clone();
-        page_path.push("signers/page.mdx");
-        let mut doc_file = File::create(page_path).expect("creation failed");
-        let doc_data = build_signers_action_doc_data(&addon);
-        let template = mustache::compile_str(&DEFAULT_ADDON_WALLETS_TEMPLATE)
-            .expect("Failed to compile template");
-        template.render_data(&mut doc_file, &doc_data).expect("Failed to render template");
-    }
-    // overview page
-    {
-        let mut page_path = addon_path.clone();
-        page_path.push("overview/page.mdx");
-        let mut doc_file = File::create(page_path).expect("creation failed");
+    // an overview page for each category (functions/actions)
+    for category in ["functions", "actions"] {
         let doc_data = build_addon_overview_doc_data(&addon);
-        let template = mustache::compile_str(&DEFAULT_ADDON_OVERVIEW_TEMPLATE)
-            .expect("Failed to compile template");
-        template.render_data(&mut doc_file, &doc_data).expect("Failed to render template");
+        render_page(
+            addon_path.join(category).join("overview/page.mdx"),
+            &DEFAULT_ADDON_OVERVIEW_TEMPLATE,
+            &doc_data,
+        )?;
     }
+    Ok(())
+}
+
+pub fn generate_addon_mdx(addon: &Box<dyn Addon>, addon_path: PathBuf) -> Result<(), String> {
+    render_page(
+        addon_path.join("functions/page.mdx"),
+        &DEFAULT_ADDON_FUNCTIONS_TEMPLATE,
+        &build_addon_function_doc_data(&addon),
+    )?;
+    render_page(
+        addon_path.join("actions/page.mdx"),
+        &DEFAULT_ADDON_ACTIONS_TEMPLATE,
+        &build_addon_action_doc_data(&addon),
+    )?;
+    render_page(
+        addon_path.join("signers/page.mdx"),
+        &DEFAULT_ADDON_WALLETS_TEMPLATE,
+        &build_signers_action_doc_data(&addon),
+    )?;
+    render_page(
+        addon_path.join("overview/page.mdx"),
+        &DEFAULT_ADDON_OVERVIEW_TEMPLATE,
+        &build_addon_overview_doc_data(&addon),
+    )
 }
 
 pub fn display_documentation(addons: &Vec<&Box<dyn Addon>>) {
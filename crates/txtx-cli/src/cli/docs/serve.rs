@@ -0,0 +1,73 @@
+use std::path::{Component, Path, PathBuf};
+
+use actix_web::web::{self, Data};
+use actix_web::{App, HttpRequest, HttpResponse, HttpServer};
+
+/// Serves the pages generated in `docs_dir` on localhost, until the process is interrupted.
+pub async fn serve_documentation(docs_dir: PathBuf, port: u16) -> Result<(), String> {
+    let docs_dir = Data::new(docs_dir);
+    let server = HttpServer::new(move || {
+        App::new().app_data(docs_dir.clone()).default_service(web::get().to(get_page))
+    })
+    .workers(1)
+    .bind(("127.0.0.1", port))
+    .map_err(|e| format!("unable to bind port {port}: {e}"))?
+    .run();
+
+    println!("Serving the documentation on http://127.0.0.1:{port} (Ctrl-C to stop)");
+    server.await.map_err(|e| format!("documentation server failed: {e}"))
+}
+
+async fn get_page(req: HttpRequest, docs_dir: Data<PathBuf>) -> HttpResponse {
+    let requested = req.path().trim_start_matches('/');
+    // only plain relative paths are served, to stay within the documentation directory
+    let relative_path = Path::new(requested);
+    if relative_path.components().any(|component| !matches!(component, Component::Normal(_))) {
+        return HttpResponse::NotFound().body("404 Not Found");
+    }
+
+    let path = docs_dir.join(relative_path);
+    if path.is_dir() {
+        return HttpResponse::Ok().content_type("text/html; charset=utf-8").body(index(&docs_dir));
+    }
+    let Ok(content) = std::fs::read(&path) else {
+        return HttpResponse::NotFound().body("404 Not Found");
+    };
+    let content_type = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => "application/json",
+        _ => "text/markdown; charset=utf-8",
+    };
+    HttpResponse::Ok().content_type(content_type).body(content)
+}
+
+/// Lists the generated pages.
+fn index(docs_dir: &Path) -> String {
+    let mut pages = vec![];
+    collect_pages(docs_dir, docs_dir, &mut pages);
+    pages.sort();
+
+    let mut html = "<!DOCTYPE html>\n<html><head><title>txtx addons documentation</title></head>\n<body><h1>txtx addons documentation</h1>\n<ul>\n".to_string();
+    for page in pages.iter() {
+        html.push_str(&format!("<li><a href=\"/{page}\">{page}</a></li>\n"));
+    }
+    html.push_str("</ul></body></html>\n");
+    html
+}
+
+fn collect_pages(docs_dir: &Path, dir: &Path, pages: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_pages(docs_dir, &path, pages);
+        } else if let Ok(relative_path) = path.strip_prefix(docs_dir) {
+            pages.push(
+                relative_path
+                    .components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/"),
+            );
+        }
+    }
+}
@@ -0,0 +1,592 @@
+use txtx_core::kit::hcl::expr::{
+    Array, Expression, FuncArgs, Object, ObjectKey, ObjectValueTerminator,
+};
+use txtx_core::kit::hcl::parser::parse_body;
+use txtx_core::kit::hcl::structure::{Block, BlockLabel, Body};
+use txtx_core::kit::hcl::visit_mut::{
+    visit_array_mut, visit_block_mut, visit_func_args_mut, visit_object_mut, VisitMut,
+};
+use txtx_core::kit::hcl::{Decor, Decorate, Decorated};
+
+const INDENTATION: &str = "    ";
+
+/// Formats a runbook source in the canonical style:
+/// - nested constructs are indented with 4 spaces;
+/// - the `=` of consecutive attributes are aligned;
+/// - blocks are separated by a single blank line, and blank lines are collapsed;
+/// - block labels are quoted.
+///
+/// Comments and heredocs are preserved, and blocks are never reordered. The formatted source is
+/// parsed again and compared with the original, so that formatting never changes semantics.
+pub fn format_runbook(source: &str) -> Result<String, String> {
+    let mut body = parse_body(source).map_err(|e| e.to_string())?;
+    let original = without_formatting(&body);
+
+    format_body(&mut body, 0);
+    let trailing = Trivia::parse(decor_suffix(body.decor()));
+    let previous_is_block = body.iter().last().map(|structure| structure.is_block());
+    let (comments, _) = comment_lines(
+        &trailing.full_lines(),
+        "",
+        previous_is_block == Some(true),
+        previous_is_block.is_some(),
+    );
+    body.decor_mut().set_suffix(comments);
+    body.set_prefer_omit_trailing_newline(false);
+    let formatted = body.to_string();
+
+    let formatted_body = parse_body(&formatted)
+        .map_err(|e| format!("formatting produced an invalid runbook: {}", e))?;
+    if without_formatting(&formatted_body) != original {
+        return Err("formatting would change the semantics of the runbook".into());
+    }
+    Ok(formatted)
+}
+
+fn format_body(body: &mut Body, depth: usize) {
+    let indent = INDENTATION.repeat(depth);
+    // the key width of each attribute, and whether it is aligned with the previous one
+    let mut keys = vec![];
+    let mut previous: Option<Previous> = None;
+    for (index, mut structure) in body.iter_mut().enumerate() {
+        let prefix = Trivia::parse(decor_prefix(structure.decor()));
+        let suffix = Trivia::parse(decor_suffix(structure.decor()));
+        let is_block = structure.is_block();
+        // blocks are followed by a blank line
+        let after_block = matches!(previous, Some(Previous::Block));
+        structure.decor_mut().set_prefix(leading(&prefix, &indent, after_block, index > 0));
+        structure.decor_mut().set_suffix(inline(&suffix.comments()));
+
+        if let Some(mut attribute) = structure.as_attribute_mut() {
+            let aligned =
+                matches!(previous, Some(Previous::SingleLineAttribute)) && !prefix.has_newline();
+            let key_width = attribute.key.as_str().chars().count();
+            let value = attribute.value_mut();
+            value.decor_mut().set_prefix(" ");
+            format_expr(value, depth);
+            keys.push(Some((key_width, aligned)));
+            previous = match value.to_string().contains('\n') {
+                true => Some(Previous::MultiLineAttribute),
+                false => Some(Previous::SingleLineAttribute),
+            };
+        } else if let Some(block) = structure.as_block_mut() {
+            format_block(block, depth);
+            keys.push(None);
+        }
+        if is_block {
+            previous = Some(Previous::Block);
+        }
+    }
+
+    let widths = aligned_widths(&keys);
+    for (mut structure, width) in body.iter_mut().zip(widths) {
+        if let (Some(mut attribute), Some(width)) = (structure.as_attribute_mut(), width) {
+            let padding = width - attribute.key.as_str().chars().count() + 1;
+            attribute.key_decor_mut().set_prefix("");
+            attribute.key_decor_mut().set_suffix(" ".repeat(padding));
+        }
+    }
+}
+
+enum Previous {
+    Block,
+    SingleLineAttribute,
+    MultiLineAttribute,
+}
+
+/// Returns the width every key is padded to, so that the `=` of aligned keys line up.
+fn aligned_widths(keys: &[Option<(usize, bool)>]) -> Vec<Option<usize>> {
+    let mut widths = vec![None; keys.len()];
+    let mut group_start = 0;
+    for index in 0..=keys.len() {
+        if let Some(Some((_, true))) = keys.get(index) {
+            continue;
+        }
+        let width = keys[group_start..index].iter().flatten().map(|(width, _)| *width).max();
+        for (slot, key) in widths[group_start..index].iter_mut().zip(&keys[group_start..index]) {
+            *slot = key.and(width);
+        }
+        group_start = index;
+    }
+    widths
+}
+
+fn format_block(block: &mut Block, depth: usize) {
+    block.ident.decor_mut().clear();
+    block.ident.decor_mut().set_suffix(" ");
+    for label in block.labels.iter_mut() {
+        if let BlockLabel::Ident(ident) = label {
+            *label = BlockLabel::String(Decorated::new(ident.as_str().to_string()));
+        }
+        label.decor_mut().clear();
+        label.decor_mut().set_suffix(" ");
+    }
+
+    let body = &mut block.body;
+    if body.is_empty() && body.prefer_oneline() {
+        body.decor_mut().clear();
+        return;
+    }
+    let prefix = Trivia::parse(decor_prefix(body.decor()));
+    let suffix = Trivia::parse(decor_suffix(body.decor()));
+    body.set_prefer_oneline(false);
+    body.decor_mut().set_prefix(inline(&prefix.comments()));
+    body.decor_mut().set_suffix(closing(
+        &suffix.lines,
+        &INDENTATION.repeat(depth + 1),
+        &INDENTATION.repeat(depth),
+    ));
+    format_body(body, depth + 1);
+}
+
+fn format_expr(expr: &mut Expression, depth: usize) {
+    match expr {
+        Expression::Array(array) => {
+            let (trailing_comma, trailing) = (array.trailing_comma(), array.trailing().to_string());
+            let trailing =
+                format_sequence(array.iter_mut().collect(), trailing_comma, &trailing, depth);
+            array.set_trailing(trailing);
+        }
+        Expression::FuncCall(call) => {
+            let args = &mut call.args;
+            let (trailing_comma, trailing) = (args.trailing_comma(), args.trailing().to_string());
+            let trailing =
+                format_sequence(args.iter_mut().collect(), trailing_comma, &trailing, depth);
+            args.set_trailing(trailing);
+        }
+        Expression::Object(object) => format_object(object, depth),
+        Expression::Parenthesis(parenthesis) => format_expr(parenthesis.inner_mut(), depth),
+        _ => {}
+    }
+}
+
+/// Lays out the items of an array or of function arguments one per line, when they span
+/// several lines. Returns the trivia to set after the trailing comma.
+fn format_sequence(
+    items: Vec<&mut Expression>,
+    trailing_comma: bool,
+    trailing: &str,
+    depth: usize,
+) -> String {
+    let is_multiline = trailing.contains('\n')
+        || items.iter().any(|item| {
+            decor_prefix(item.decor()).contains('\n') || decor_suffix(item.decor()).contains('\n')
+        });
+    if items.is_empty() || !is_multiline {
+        for item in items {
+            format_expr(item, depth);
+        }
+        return trailing.to_string();
+    }
+
+    let indent = INDENTATION.repeat(depth + 1);
+    let closing_indent = INDENTATION.repeat(depth);
+    let count = items.len();
+    for (index, item) in items.into_iter().enumerate() {
+        // items start after the opening bracket or a comma, on the same line
+        let prefix = Trivia::parse(decor_prefix(item.decor()));
+        item.decor_mut().set_prefix(after_separator(&prefix, &indent, index > 0));
+
+        let suffix = Trivia::parse(decor_suffix(item.decor()));
+        let suffix = match index == count - 1 && !trailing_comma {
+            true => closing_after_separator(&suffix, &indent, &closing_indent),
+            false => before_separator(&suffix, &indent),
+        };
+        item.decor_mut().set_suffix(suffix);
+        format_expr(item, depth + 1);
+    }
+    match trailing_comma {
+        true => closing_after_separator(&Trivia::parse(trailing), &indent, &closing_indent),
+        false => String::new(),
+    }
+}
+
+/// Lays out the items of an object one per line, when they span several lines, aligning their
+/// `=`.
+fn format_object(object: &mut Object, depth: usize) {
+    let is_multiline = object.trailing().contains('\n')
+        || object.iter().any(|(key, value)| {
+            decor_prefix(key.decor()).contains('\n')
+                || value.terminator() == ObjectValueTerminator::Newline
+        });
+    if object.is_empty() || !is_multiline {
+        for (_, value) in object.iter_mut() {
+            format_expr(value.expr_mut(), depth);
+        }
+        return;
+    }
+
+    let indent = INDENTATION.repeat(depth + 1);
+    let closing_indent = INDENTATION.repeat(depth);
+    let mut keys = vec![];
+    let mut previous: Option<(ObjectValueTerminator, bool)> = None;
+    for (mut key, value) in object.iter_mut() {
+        let prefix = Trivia::parse(decor_prefix(key.decor()));
+        // the first key starts after the opening brace, and the keys following a comma after it
+        let starts_mid_line = !matches!(previous, Some((ObjectValueTerminator::Newline, _)));
+        let aligned = matches!(previous, Some((_, false)))
+            && prefix.lines.iter().filter(|comments| !comments.is_empty()).count() == 0
+            && prefix.lines.len() <= starts_mid_line as usize + 1;
+        let rendered_prefix = match starts_mid_line {
+            true => after_separator(&prefix, &indent, previous.is_some()),
+            false => leading(&prefix, &indent, false, true),
+        };
+        key.decor_mut().set_prefix(rendered_prefix);
+        keys.push(Some((object_key_width(&key), aligned)));
+
+        if value.terminator() == ObjectValueTerminator::None {
+            value.set_terminator(ObjectValueTerminator::Newline);
+        }
+        let expr = value.expr_mut();
+        let suffix = Trivia::parse(decor_suffix(expr.decor()));
+        expr.decor_mut().set_prefix(" ");
+        expr.decor_mut().set_suffix(inline(&suffix.comments()));
+        format_expr(expr, depth + 1);
+        previous = Some((value.terminator(), value.expr().to_string().contains('\n')));
+    }
+    let trailing = Trivia::parse(object.trailing());
+    let trailing = match previous {
+        Some((ObjectValueTerminator::Comma, _)) => {
+            closing_after_separator(&trailing, &indent, &closing_indent)
+        }
+        _ => closing(&trailing.lines, &indent, &closing_indent),
+    };
+    object.set_trailing(trailing);
+
+    let widths = aligned_widths(&keys);
+    for ((mut key, _), width) in object.iter_mut().zip(widths) {
+        let padding = width.unwrap_or(0) - object_key_width(&key) + 1;
+        key.decor_mut().set_suffix(" ".repeat(padding));
+    }
+}
+
+fn object_key_width(key: &ObjectKey) -> usize {
+    match key {
+        ObjectKey::Ident(ident) => ident.as_str().chars().count(),
+        ObjectKey::Expression(expr) => {
+            let mut expr = expr.clone();
+            expr.decor_mut().clear();
+            expr.to_string().chars().count()
+        }
+    }
+}
+
+/// The comments found in the whitespace between two syntax items, line by line.
+struct Trivia {
+    lines: Vec<Vec<String>>,
+}
+
+impl Trivia {
+    fn parse(raw: &str) -> Trivia {
+        let mut lines = vec![vec![]];
+        let mut rest = raw;
+        while let Some(c) = rest.chars().next() {
+            let len = if c == '#' || rest.starts_with("//") {
+                rest.find('\n').unwrap_or(rest.len())
+            } else if rest.starts_with("/*") {
+                rest.find("*/").map_or(rest.len(), |end| end + 2)
+            } else {
+                if c == '\n' {
+                    lines.push(vec![]);
+                }
+                rest = &rest[c.len_utf8()..];
+                continue;
+            };
+            lines.last_mut().unwrap().push(rest[..len].trim_end().to_string());
+            rest = &rest[len..];
+        }
+        Trivia { lines }
+    }
+
+    fn has_newline(&self) -> bool {
+        self.lines.len() > 1
+    }
+
+    fn comments(&self) -> Vec<String> {
+        self.lines.concat()
+    }
+
+    /// The lines of the trivia, the last one included when it has comments.
+    fn full_lines(&self) -> Vec<Vec<String>> {
+        let mut lines = self.lines.clone();
+        if lines.last().map_or(false, |comments| comments.is_empty()) {
+            lines.pop();
+        }
+        lines
+    }
+}
+
+fn is_line_comment(comment: &str) -> bool {
+    comment.starts_with('#') || comment.starts_with("//")
+}
+
+/// Renders comments staying on the current line.
+fn inline(comments: &[String]) -> String {
+    comments.iter().map(|comment| format!(" {comment}")).collect()
+}
+
+/// Renders lines of comments at the given indentation, collapsing the blank lines between them.
+/// Blank lines are only kept after some content, and one is added when `blank_line` is set.
+/// Returns whether a blank line is pending.
+fn comment_lines(
+    lines: &[Vec<String>],
+    indent: &str,
+    mut blank_line: bool,
+    mut after_content: bool,
+) -> (String, bool) {
+    let mut rendered = String::new();
+    for comments in lines {
+        if comments.is_empty() {
+            blank_line = true;
+            continue;
+        }
+        if blank_line && after_content {
+            rendered.push('\n');
+        }
+        rendered.push_str(&format!("{indent}{}\n", comments.join(" ")));
+        blank_line = false;
+        after_content = true;
+    }
+    (rendered, blank_line && after_content)
+}
+
+/// Renders the trivia starting at the beginning of a line and preceding an item.
+fn leading(trivia: &Trivia, indent: &str, blank_line: bool, after_content: bool) -> String {
+    let (last, lines) = trivia.lines.split_last().unwrap();
+    let (mut rendered, blank_line) = comment_lines(lines, indent, blank_line, after_content);
+    if blank_line {
+        rendered.push('\n');
+    }
+    rendered.push_str(indent);
+    rendered.extend(last.iter().map(|comment| format!("{comment} ")));
+    rendered
+}
+
+/// Renders the trivia starting at the beginning of a line and preceding a closing bracket.
+fn closing(lines: &[Vec<String>], indent: &str, closing_indent: &str) -> String {
+    let mut rendered = String::new();
+    for comments in lines.iter().filter(|comments| !comments.is_empty()) {
+        rendered.push_str(&format!("{indent}{}\n", comments.join(" ")));
+    }
+    rendered.push_str(closing_indent);
+    rendered
+}
+
+/// Renders the trivia following an opening bracket or a separator and preceding an item, which is
+/// moved to its own line.
+fn after_separator(trivia: &Trivia, indent: &str, after_content: bool) -> String {
+    match trivia.has_newline() {
+        true => {
+            let rest = Trivia { lines: trivia.lines[1..].to_vec() };
+            format!(
+                "{}\n{}",
+                inline(&trivia.lines[0]),
+                leading(&rest, indent, false, after_content)
+            )
+        }
+        false => format!("\n{}", leading(trivia, indent, false, after_content)),
+    }
+}
+
+/// Renders the trivia following an item or a separator and preceding a closing bracket, which is
+/// moved to its own line.
+fn closing_after_separator(trivia: &Trivia, indent: &str, closing_indent: &str) -> String {
+    match trivia.has_newline() {
+        true => format!(
+            "{}\n{}",
+            inline(&trivia.lines[0]),
+            closing(&trivia.lines[1..], indent, closing_indent)
+        ),
+        false => format!("{}\n{}", inline(&trivia.lines[0]), closing_indent),
+    }
+}
+
+/// Renders the trivia between an item and the following separator.
+fn before_separator(trivia: &Trivia, indent: &str) -> String {
+    let comments = trivia.comments();
+    let mut rendered = inline(&comments);
+    if comments.last().map_or(false, |comment| is_line_comment(comment)) {
+        rendered.push('\n');
+        rendered.push_str(indent);
+    }
+    rendered
+}
+
+fn decor_prefix(decor: &Decor) -> &str {
+    decor.prefix().map_or("", |prefix| prefix)
+}
+
+fn decor_suffix(decor: &Decor) -> &str {
+    decor.suffix().map_or("", |suffix| suffix)
+}
+
+/// Strips a body of what formatting changes (the notation of block labels, and the whitespace
+/// closing collections and terminating object items), so that bodies only differing by their
+/// formatting are equal.
+fn without_formatting(body: &Body) -> Body {
+    struct Unformat;
+
+    impl VisitMut for Unformat {
+        fn visit_block_mut(&mut self, block: &mut Block) {
+            for label in block.labels.iter_mut() {
+                if let BlockLabel::Ident(ident) = label {
+                    *label = BlockLabel::String(Decorated::new(ident.as_str().to_string()));
+                }
+            }
+            visit_block_mut(self, block);
+        }
+
+        fn visit_array_mut(&mut self, array: &mut Array) {
+            array.set_trailing("");
+            visit_array_mut(self, array);
+        }
+
+        fn visit_object_mut(&mut self, object: &mut Object) {
+            object.set_trailing("");
+            for (_, value) in object.iter_mut() {
+                value.set_terminator(ObjectValueTerminator::None);
+            }
+            visit_object_mut(self, object);
+        }
+
+        fn visit_func_args_mut(&mut self, args: &mut FuncArgs) {
+            args.set_trailing("");
+            visit_func_args_mut(self, args);
+        }
+    }
+
+    let mut body = body.clone();
+    Unformat.visit_body_mut(&mut body);
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_format_runbook() {
+        let source = r#"
+
+addon "evm" {
+  chain_id = input.chain_id
+      rpc_api_url=input.rpc_api_url
+}
+signer deployer "evm::secret_key" {
+secret_key = input.secret_key
+}
+
+
+
+action "deploy"   "evm::deploy_contract" {
+
+    contract = {
+      abi = "[]"
+        bytecode = "0x00"
+    }
+    signer = signer.deployer
+
+}
+"#;
+        let expected = r#"addon "evm" {
+    chain_id    = input.chain_id
+    rpc_api_url = input.rpc_api_url
+}
+
+signer "deployer" "evm::secret_key" {
+    secret_key = input.secret_key
+}
+
+action "deploy" "evm::deploy_contract" {
+    contract = {
+        abi      = "[]"
+        bytecode = "0x00"
+    }
+    signer = signer.deployer
+}
+"#;
+        assert_eq!(format_runbook(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_comments_and_heredocs_are_preserved() {
+        let source = r#"// deployment of the counter contract
+variable "counter" {
+  # the initial value
+  value = 1 // inline comment
+    description = <<EOF
+  indented text
+{ not a block
+EOF
+}
+/* a block
+   comment */
+output "counter" {
+value = variable.counter
+}
+"#;
+        let expected = r#"// deployment of the counter contract
+variable "counter" {
+    # the initial value
+    value       = 1 // inline comment
+    description = <<EOF
+  indented text
+{ not a block
+EOF
+}
+
+/* a block
+   comment */
+output "counter" {
+    value = variable.counter
+}
+"#;
+        assert_eq!(format_runbook(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_invalid_runbooks_are_rejected() {
+        assert!(format_runbook("variable \"a\" {\n  value = \n").is_err());
+    }
+
+    fn collect_runbooks(dir: &Path, runbooks: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect_runbooks(&path, runbooks);
+            } else if path.extension().map_or(false, |ext| ext == "tx") {
+                runbooks.push(path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_formatting_round_trips() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let mut runbooks = vec![];
+        for dir in ["crates/txtx-core/src/tests/fixtures", "addons", "examples"] {
+            collect_runbooks(&root.join(dir), &mut runbooks);
+        }
+        for path in runbooks.iter() {
+            let source = std::fs::read_to_string(path).unwrap();
+            // fixtures for parsing errors can't be formatted
+            let Ok(body) = parse_body(&source) else { continue };
+            let formatted = format_runbook(&source)
+                .unwrap_or_else(|e| panic!("{} can't be formatted: {}", path.display(), e));
+            let formatted_body = parse_body(&formatted).unwrap();
+            assert!(
+                without_formatting(&body) == without_formatting(&formatted_body),
+                "{}",
+                path.display()
+            );
+            assert_eq!(
+                format_runbook(&formatted).unwrap(),
+                formatted,
+                "formatting {} is not idempotent",
+                path.display()
+            );
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+
+use similar::TextDiff;
+
+use super::{Context, FormatRunbooks};
+use formatter::format_runbook;
+
+mod formatter;
+
+/// Formats the runbooks found at the given path (a file, or a directory searched recursively).
+///
+/// With `--check`, files are left untouched: the diff of the files that would be reformatted is
+/// printed, and the command exits with code 1 if any.
+pub async fn handle_fmt_command(cmd: &FormatRunbooks, _ctx: &Context) -> Result<(), String> {
+    let path = PathBuf::from(cmd.path.as_deref().unwrap_or("."));
+    let runbooks = match path.is_dir() {
+        true => {
+            let mut runbooks = vec![];
+            collect_runbooks(&path, &mut runbooks);
+            runbooks.sort();
+            runbooks
+        }
+        false if path.exists() => vec![path],
+        false => return Err(format!("{} does not exist", path.display())),
+    };
+
+    let mut unformatted_count = 0;
+    let mut failures = vec![];
+    for runbook_path in runbooks.iter() {
+        let source = std::fs::read_to_string(runbook_path)
+            .map_err(|e| format!("unable to read {}: {}", runbook_path.display(), e))?;
+        let formatted = match format_runbook(&source) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                println!("{} {}: {}", red!("x"), runbook_path.display(), e);
+                failures.push(runbook_path);
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+        unformatted_count += 1;
+        if cmd.check {
+            let file_name = runbook_path.display().to_string();
+            let diff = TextDiff::from_lines(&source, &formatted)
+                .unified_diff()
+                .header(&file_name, &file_name)
+                .to_string();
+            print!("{}", diff);
+        } else {
+            std::fs::write(runbook_path, formatted)
+                .map_err(|e| format!("unable to write {}: {}", runbook_path.display(), e))?;
+            println!("{} {}", green!("Formatted"), runbook_path.display());
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(format!("{} runbook(s) could not be formatted", failures.len()));
+    }
+    if cmd.check && unformatted_count > 0 {
+        println!("\n{} runbook(s) would be reformatted", unformatted_count);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn collect_runbooks(dir: &Path, runbooks: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if path.is_dir() {
+            // skip hidden directories and build artifacts
+            if !file_name.starts_with('.') && file_name != "target" && file_name != "node_modules" {
+                collect_runbooks(&path, runbooks);
+            }
+        } else if path.extension().map_or(false, |ext| ext == "tx") {
+            runbooks.push(path);
+        }
+    }
+}
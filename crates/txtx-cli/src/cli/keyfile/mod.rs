@@ -0,0 +1,170 @@
+use console::Style;
+use dialoguer::{theme::ColorfulTheme, Password};
+use txtx_core::kit::crypto::keyfile::{decrypt_keyfile, encrypt_keyfile};
+use txtx_core::kit::hex;
+use txtx_core::kit::zeroize::Zeroizing;
+
+use super::{Context, CreateKeyfile, RotateKeyfile};
+
+pub async fn handle_new_command(cmd: &CreateKeyfile, _ctx: &Context) -> Result<(), String> {
+    let path = std::path::Path::new(&cmd.path);
+    if path.exists() && !cmd.force {
+        return Err(format!("keyfile {} already exists (use --force to overwrite it)", cmd.path));
+    }
+
+    let secret_key = match &cmd.secret_key_env {
+        Some(name) => Zeroizing::new(
+            std::env::var(name).map_err(|_| format!("environment variable {name} is not set"))?,
+        ),
+        None => Zeroizing::new(
+            Password::with_theme(&theme())
+                .with_prompt("Enter the secret key (hex, or JSON array of bytes)")
+                .interact()
+                .map_err(|e| format!("failed to read secret key: {e}"))?,
+        ),
+    };
+    let secret_key = parse_secret_key(&secret_key)?;
+    let passphrase = read_passphrase(&cmd.passphrase_env, "Enter the keyfile passphrase", true)?;
+
+    let keyfile = encrypt_keyfile(&secret_key, &passphrase)?;
+    write_keyfile(&cmd.path, &keyfile)?;
+    println!("{} {}", green!("Created keyfile"), cmd.path);
+    Ok(())
+}
+
+pub async fn handle_rotate_command(cmd: &RotateKeyfile, _ctx: &Context) -> Result<(), String> {
+    let keyfile = std::fs::read(&cmd.path)
+        .map_err(|e| format!("unable to read keyfile {}: {e}", cmd.path))?;
+    let passphrase =
+        read_passphrase(&cmd.passphrase_env, "Enter the current keyfile passphrase", false)?;
+    let secret_key = decrypt_keyfile(&keyfile, &passphrase)?;
+
+    let new_passphrase =
+        read_passphrase(&cmd.new_passphrase_env, "Enter the new keyfile passphrase", true)?;
+    let keyfile = encrypt_keyfile(&secret_key, &new_passphrase)?;
+    write_keyfile(&cmd.path, &keyfile)?;
+    println!("{} {}", green!("Rotated keyfile"), cmd.path);
+    Ok(())
+}
+
+fn theme() -> ColorfulTheme {
+    ColorfulTheme {
+        values_style: Style::new().green(),
+        hint_style: Style::new().cyan(),
+        ..ColorfulTheme::default()
+    }
+}
+
+fn read_passphrase(
+    env: &Option<String>,
+    prompt: &str,
+    confirm: bool,
+) -> Result<Zeroizing<String>, String> {
+    if let Some(name) = env {
+        return std::env::var(name)
+            .map(Zeroizing::new)
+            .map_err(|_| format!("environment variable {name} is not set"));
+    }
+    let theme = theme();
+    let mut password = Password::with_theme(&theme).with_prompt(prompt);
+    if confirm {
+        password = password.with_confirmation("Confirm the passphrase", "Passphrases don't match");
+    }
+    password.interact().map(Zeroizing::new).map_err(|e| format!("failed to read passphrase: {e}"))
+}
+
+/// Parses a secret key encoded in hex (with or without the `0x` prefix), or as a JSON array of
+/// bytes (such as Solana keypair files).
+fn parse_secret_key(secret_key: &str) -> Result<Zeroizing<Vec<u8>>, String> {
+    let secret_key = secret_key.trim();
+    let bytes = if secret_key.starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(secret_key)
+            .map_err(|_| "invalid secret key: expected a JSON array of bytes".to_string())?
+    } else {
+        hex::decode(secret_key.trim_start_matches("0x"))
+            .map_err(|_| "invalid secret key: expected a hex encoded key".to_string())?
+    };
+    if bytes.is_empty() {
+        return Err("invalid secret key: the key is empty".into());
+    }
+    Ok(Zeroizing::new(bytes))
+}
+
+/// Writes the keyfile to a temporary file next to `path`, only readable by its owner, then moves
+/// it over `path`: an interrupted write never leaves a truncated keyfile behind.
+fn write_keyfile(path: &str, keyfile: &str) -> Result<(), String> {
+    use std::io::Write;
+
+    let target = std::path::Path::new(path);
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| format!("unable to write keyfile {path}: not a file path"))?;
+    let temp_path = target.with_file_name(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let res = options
+        .open(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(keyfile.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp_path, target));
+    if let Err(e) = res {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(format!("unable to write keyfile {path}: {e}"));
+    }
+    // persist the rename itself
+    #[cfg(unix)]
+    {
+        let parent = target.parent().filter(|p| !p.as_os_str().is_empty());
+        let parent = parent.unwrap_or(std::path::Path::new("."));
+        let _ = std::fs::File::open(parent).and_then(|dir| dir.sync_all());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secret_key() {
+        assert_eq!(parse_secret_key("0x0102ff").unwrap().as_slice(), &[1, 2, 255]);
+        assert_eq!(parse_secret_key("0102ff\n").unwrap().as_slice(), &[1, 2, 255]);
+        assert_eq!(parse_secret_key("[1, 2, 255]").unwrap().as_slice(), &[1, 2, 255]);
+        assert!(parse_secret_key("0xzz").is_err());
+        assert!(parse_secret_key("[256]").is_err());
+        assert!(parse_secret_key("").is_err());
+    }
+
+    #[test]
+    fn test_write_keyfile_replaces_the_keyfile() {
+        let dir = std::env::temp_dir().join(format!("txtx-keyfile-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("deployer.json");
+        let path_str = path.to_str().unwrap();
+
+        write_keyfile(path_str, "first").unwrap();
+        write_keyfile(path_str, "second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+        // no temporary file is left behind
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -302,6 +302,7 @@ mod tests {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
 
@@ -402,6 +403,7 @@ mod tests {
                         id: "test-id".to_string(),
                         runbooks: vec![],
                         environments: Default::default(),
+                        signers: None,
                         location: None,
                     },
                     effective_inputs: HashMap::new(),
@@ -15,6 +15,7 @@ pub mod manifest_builders {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         }
     }
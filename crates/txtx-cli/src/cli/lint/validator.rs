@@ -327,6 +327,8 @@ mod tests {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
+            signers: None,
             location: None,
         };
 
@@ -420,6 +422,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
 
@@ -538,6 +541,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
 
@@ -578,6 +582,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
 
@@ -631,6 +636,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
 
@@ -707,6 +713,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
 
@@ -746,6 +753,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
         manifest.environments.insert("global".to_string(), global_env.into_iter().collect());
@@ -787,6 +795,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
         manifest.environments.insert("global".to_string(), global_env.into_iter().collect());
@@ -834,6 +843,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
 
@@ -879,6 +889,7 @@ action "test" {
             id: "test-id".to_string(),
             runbooks: vec![],
             environments: Default::default(),
+            signers: None,
             location: None,
         };
         manifest.environments.insert("global".to_string(), global_env.into_iter().collect());
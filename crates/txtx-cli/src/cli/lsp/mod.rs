@@ -2,7 +2,9 @@ mod native_bridge;
 
 use self::native_bridge::LspNativeBridge;
 use std::sync::mpsc;
-use tower_lsp::lsp_types::{Diagnostic, DiagnosticSeverity, Position, Range};
+use tower_lsp::lsp_types::{
+    CodeDescription, Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range, Url,
+};
 use tower_lsp::{LspService, Server};
 use txtx_core::kit::channel::unbounded;
 use txtx_core::kit::types::diagnostics::{Diagnostic as TxtxDiagnostic, DiagnosticLevel};
@@ -57,9 +59,13 @@ pub fn clarity_diagnostic_to_tower_lsp_type(
             DiagnosticLevel::Warning => Some(DiagnosticSeverity::WARNING),
             DiagnosticLevel::Note => Some(DiagnosticSeverity::INFORMATION),
         },
-        code: None,
-        code_description: None,
-        source: Some("clarity".to_string()),
+        code: diagnostic.code.clone().map(NumberOrString::String),
+        code_description: diagnostic
+            .help_url
+            .as_ref()
+            .and_then(|url| Url::parse(url).ok())
+            .map(|href| CodeDescription { href }),
+        source: Some("txtx".to_string()),
         message: diagnostic.message.clone(),
         related_information: None,
         tags: None,
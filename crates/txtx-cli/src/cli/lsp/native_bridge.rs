@@ -1,4 +1,5 @@
 use super::clarity_diagnostics_to_tower_lsp_type;
+use crate::cli::common::addon_registry::get_all_addons;
 use serde_json::Value;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::Arc;
@@ -18,8 +19,9 @@ use txtx_lsp::backend::{
     LspNotification, LspNotificationResponse, LspRequest, LspRequestResponse,
 };
 use txtx_lsp::lsp_types::{
-    DocumentSymbolParams, DocumentSymbolResponse, GotoDefinitionParams, GotoDefinitionResponse,
-    SignatureHelp, SignatureHelpParams,
+    CodeActionParams, CodeActionResponse, DocumentSymbolParams, DocumentSymbolResponse,
+    GotoDefinitionParams, GotoDefinitionResponse, Location, ReferenceParams, RenameParams,
+    SignatureHelp, SignatureHelpParams, SymbolInformation, WorkspaceEdit, WorkspaceSymbolParams,
 };
 use txtx_lsp::state::EditorState;
 use txtx_lsp::utils;
@@ -34,7 +36,9 @@ pub async fn start_language_server(
     request_rx: MultiplexableReceiver<LspRequest>,
     response_tx: Sender<LspResponse>,
 ) {
-    let mut editor_state = EditorStateInput::Owned(EditorState::new());
+    // the editor completes the same addons as the ones available to txtx run
+    let addons = get_all_addons();
+    let mut editor_state = EditorStateInput::Owned(EditorState::new_with_addons(&addons));
 
     let mut sel = Select::new();
     let notifications_oper = sel.recv(&notification_rx);
@@ -45,10 +49,11 @@ pub async fn start_language_server(
         match oper.index() {
             i if i == notifications_oper => match oper.recv(&notification_rx) {
                 Ok(notification) => {
-                    let result = process_notification(notification, &mut editor_state, None).await;
-                    if let Ok(response) = result {
-                        let _ = response_tx.send(LspResponse::Notification(response));
-                    }
+                    // a response is always sent, since the bridge waits for one
+                    let response = process_notification(notification, &mut editor_state, None)
+                        .await
+                        .unwrap_or_else(|e| LspNotificationResponse::error(&e));
+                    let _ = response_tx.send(LspResponse::Notification(response));
                 }
                 Err(_e) => {
                     continue;
@@ -177,6 +182,54 @@ impl LanguageServer for LspNativeBridge {
         Ok(None)
     }
 
+    async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::References(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::References(locations)) = response {
+            return Ok(Some(locations.to_vec()));
+        }
+
+        Ok(None)
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::Rename(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        match response {
+            LspResponse::Request(LspRequestResponse::Rename(Ok(edit))) => Ok(edit.to_owned()),
+            // the rejection is shown to the user, and nothing is renamed
+            LspResponse::Request(LspRequestResponse::Rename(Err(message))) => {
+                Err(Error::invalid_params(message.to_owned()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::CodeAction(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::CodeAction(actions)) = response {
+            return Ok(Some(actions.to_vec()));
+        }
+
+        Ok(None)
+    }
+
     async fn document_symbol(
         &self,
         params: DocumentSymbolParams,
@@ -195,6 +248,24 @@ impl LanguageServer for LspNativeBridge {
         Ok(None)
     }
 
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<Vec<SymbolInformation>>> {
+        let _ = match self.request_tx.lock() {
+            Ok(tx) => tx.send(LspRequest::WorkspaceSymbol(params)),
+            Err(_) => return Ok(None),
+        };
+
+        let response_rx = self.response_rx.lock().expect("failed to lock response_rx");
+        let response = &response_rx.recv().expect("failed to get value from recv");
+        if let LspResponse::Request(LspRequestResponse::WorkspaceSymbol(symbols)) = response {
+            return Ok(Some(symbols.to_vec()));
+        }
+
+        Ok(None)
+    }
+
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
         let _ = match self.request_tx.lock() {
             Ok(tx) => tx.send(LspRequest::Hover(params)),
@@ -337,6 +408,10 @@ impl LanguageServer for LspNativeBridge {
                     params.content_changes[0].text.to_string(),
                 ));
             };
+            // the response is consumed, so that it's not mistaken for the next request's
+            if let Ok(response_rx) = self.response_rx.lock() {
+                let _ = response_rx.recv();
+            }
         }
     }
 
@@ -345,6 +420,9 @@ impl LanguageServer for LspNativeBridge {
             if let Ok(tx) = self.notification_tx.lock() {
                 let _ = tx.send(LspNotification::RunbookClosed(contract_location));
             };
+            if let Ok(response_rx) = self.response_rx.lock() {
+                let _ = response_rx.recv();
+            }
         }
     }
 }
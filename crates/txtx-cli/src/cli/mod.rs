@@ -2,10 +2,15 @@ use atty::Stream;
 use clap::{ArgAction, Parser, Subcommand};
 use dotenvy::dotenv;
 use hiro_system_kit::{self, Logger};
+use std::io::Read;
 use std::process;
 
+use common::exit_code::{CliError, EXIT_CODES_HELP};
+
 mod common;
 mod docs;
+mod fmt;
+mod keyfile;
 mod lint;
 mod lsp;
 mod runbooks;
@@ -38,10 +43,13 @@ impl Context {
 }
 
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
+#[clap(author, version, about, long_about = None, after_help = EXIT_CODES_HELP)]
 struct Opts {
     #[clap(subcommand)]
     command: Command,
+    /// Disable colors in the output. Colors are also disabled when the `NO_COLOR` environment variable is set, or when stdout is not a terminal
+    #[arg(long = "no-color", global = true)]
+    no_color: bool,
 }
 
 #[derive(Subcommand, PartialEq, Clone, Debug)]
@@ -49,18 +57,27 @@ enum Command {
     /// List the runbooks indexed in the txtx manifest
     #[clap(name = "ls", bin_name = "ls")]
     List(ListRunbooks),
-    /// Create a new runbook
+    /// Create a new runbook, optionally from a template
     #[clap(name = "new", bin_name = "new")]
     New(CreateRunbook),
-    /// Check a runbook against a previous execution's statefile to list which actions will be re-executed
+    /// Validate runbooks without executing them, then list the actions to re-execute against a previous execution's statefile
     #[clap(name = "check", bin_name = "check")]
     Check(CheckRunbook),
+    /// Export the execution graph of a runbook
+    #[clap(name = "graph", bin_name = "graph")]
+    Graph(GraphRunbook),
+    /// Report the addons, signers, actions and outputs of a runbook, without executing it
+    #[clap(name = "inspect", bin_name = "inspect")]
+    Inspect(InspectRunbook),
     /// Execute a runbook. Run, runbook, run!
     #[clap(name = "run", bin_name = "run")]
     Run(ExecuteRunbook),
-    /// Display documentation
+    /// Generate the documentation of the installed addons
     #[clap(name = "docs", bin_name = "docs")]
     Docs(GetDocumentation),
+    /// Format runbooks in the canonical style
+    #[clap(name = "fmt", bin_name = "fmt")]
+    Fmt(FormatRunbooks),
     /// Lint runbooks for issues and style violations
     #[clap(name = "lint", bin_name = "lint")]
     Lint(LintRunbook),
@@ -68,8 +85,21 @@ enum Command {
     #[clap(name = "lsp", bin_name = "lsp")]
     Lsp,
     /// Snapshot management (work in progress)
-    #[clap(subcommand)]
+    #[clap(subcommand, alias = "snapshot")]
     Snapshots(SnapshotCommand),
+    /// Encrypted keyfiles management
+    #[clap(subcommand)]
+    Keyfile(KeyfileCommand),
+}
+
+#[derive(Subcommand, PartialEq, Clone, Debug)]
+enum KeyfileCommand {
+    /// Create a keyfile holding a secret key encrypted with a passphrase
+    #[clap(name = "new", bin_name = "new")]
+    New(CreateKeyfile),
+    /// Re-encrypt a keyfile with a new passphrase
+    #[clap(name = "rotate", bin_name = "rotate")]
+    Rotate(RotateKeyfile),
 }
 
 #[derive(Subcommand, PartialEq, Clone, Debug)]
@@ -80,6 +110,9 @@ enum SnapshotCommand {
     /// Finalize snapshot
     #[clap(name = "end", bin_name = "end")]
     Commit(CommitSnapshot),
+    /// Report the per-construct changes between two executions, or between the latest execution and the current runbook
+    #[clap(name = "diff", bin_name = "diff")]
+    Diff(DiffSnapshots),
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
@@ -102,12 +135,101 @@ pub struct CommitSnapshot {
     pub snapshot_path: String,
 }
 
+#[derive(Parser, PartialEq, Clone, Debug)]
+pub struct DiffSnapshots {
+    /// Path to the state file of the previous execution (defaults to the latest state of the runbook)
+    pub old: Option<String>,
+    /// Path to the state file to compare with (defaults to a simulation of the current runbook)
+    pub new: Option<String>,
+    /// Path to the manifest
+    #[arg(long = "manifest-file-path", short = 'm', default_value = "./txtx.yml")]
+    pub manifest_path: String,
+    /// Name of the runbook to compare with its latest state, required unless two state files are provided
+    #[arg(long = "runbook", short = 'r')]
+    pub runbook: Option<String>,
+    /// Choose the environment variable to set from those configured in the txtx.yml
+    #[arg(long = "env")]
+    pub environment: Option<String>,
+    /// A set of inputs to use for simulating the current runbook
+    #[arg(long = "input")]
+    pub inputs: Vec<String>,
+    /// JSON or YAML files of inputs (`-` to read from stdin). Later files override earlier ones, and `--input` values override all files
+    #[arg(long = "input-file")]
+    pub input_files: Vec<String>,
+    /// Format of the reported changes
+    #[arg(long = "format", value_enum, default_value = "text")]
+    pub format: OutputFormat,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+pub struct CreateKeyfile {
+    /// Path of the keyfile to create
+    pub path: String,
+    /// Name of the environment variable holding the secret key (hex, or JSON array of bytes). The secret key is prompted otherwise
+    #[arg(long = "secret-key-env")]
+    pub secret_key_env: Option<String>,
+    /// Name of the environment variable holding the passphrase. The passphrase is prompted otherwise
+    #[arg(long = "passphrase-env")]
+    pub passphrase_env: Option<String>,
+    /// Overwrite the keyfile if it already exists
+    #[arg(long = "force", short = 'f')]
+    pub force: bool,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+pub struct RotateKeyfile {
+    /// Path of the keyfile to re-encrypt
+    pub path: String,
+    /// Name of the environment variable holding the current passphrase. The passphrase is prompted otherwise
+    #[arg(long = "passphrase-env")]
+    pub passphrase_env: Option<String>,
+    /// Name of the environment variable holding the new passphrase. The passphrase is prompted otherwise
+    #[arg(long = "new-passphrase-env")]
+    pub new_passphrase_env: Option<String>,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+pub struct FormatRunbooks {
+    /// Path to the runbook file, or to a directory to search for runbooks (defaults to the current directory)
+    pub path: Option<String>,
+    /// Don't write the files: print the diff of the runbooks that would be reformatted, and exit with a non-zero code if any
+    #[arg(long = "check", action=ArgAction::SetTrue)]
+    pub check: bool,
+}
+
 #[derive(Parser, PartialEq, Clone, Debug)]
 pub struct CheckRunbook {
     /// Path to the manifest
     #[arg(long = "manifest-file-path", short = 'm', default_value = "./txtx.yml")]
     pub manifest_path: String,
-    /// Name of the runbook as indexed in the txtx.yml, or the path of the .tx file to run
+    /// Name of the runbook as indexed in the txtx.yml (omit to check all runbooks)
+    pub runbook: Option<String>,
+    /// Choose the environment variable to set from those configured in the txtx.yml
+    #[arg(long = "env")]
+    pub environment: Option<String>,
+    /// A set of inputs to use for batch processing
+    #[arg(long = "input")]
+    pub inputs: Vec<String>,
+    /// JSON or YAML files of inputs (`-` to read from stdin). Later files override earlier ones, and `--input` values override all files
+    #[arg(long = "input-file")]
+    pub input_files: Vec<String>,
+    /// Exit with code 2 when only warnings are reported
+    #[arg(long = "deny-warnings")]
+    pub deny_warnings: bool,
+    /// Format of the reported diagnostics
+    #[arg(long = "format", value_enum, default_value = "text")]
+    pub format: OutputFormat,
+    /// Check the runbooks again every time the manifest or the runbooks sources change
+    #[arg(long = "watch", action=ArgAction::SetTrue)]
+    pub watch: bool,
+}
+
+#[derive(Parser, PartialEq, Clone, Debug)]
+pub struct GraphRunbook {
+    /// Path to the manifest
+    #[arg(long = "manifest-file-path", short = 'm', default_value = "./txtx.yml")]
+    pub manifest_path: String,
+    /// Name of the runbook as indexed in the txtx.yml
     pub runbook: String,
     /// Choose the environment variable to set from those configured in the txtx.yml
     #[arg(long = "env")]
@@ -115,29 +237,60 @@ pub struct CheckRunbook {
     /// A set of inputs to use for batch processing
     #[arg(long = "input")]
     pub inputs: Vec<String>,
+    /// JSON or YAML files of inputs (`-` to read from stdin). Later files override earlier ones, and `--input` values override all files
+    #[arg(long = "input-file")]
+    pub input_files: Vec<String>,
+    /// Format of the exported graph
+    #[arg(long = "format", value_enum, default_value = "dot")]
+    pub format: GraphFormat,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT digraph
+    Dot,
+    /// Mermaid flowchart, embeddable in markdown
+    Mermaid,
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
-pub struct GetDocumentation;
+pub struct GetDocumentation {
+    /// Namespace of the addon to document (omit to document all the installed addons)
+    pub namespace: Option<String>,
+    /// Directory where the documentation pages are written
+    #[arg(long = "output-dir", short = 'o', default_value = "doc/addons")]
+    pub output_dir: String,
+    /// Preview the generated pages over a local HTTP server
+    #[arg(long = "serve")]
+    pub serve: bool,
+    /// Set the port of the preview server
+    #[arg(long = "port", short = 'p', default_value = "8489", requires = "serve")]
+    pub port: u16,
+}
 
 #[derive(Parser, PartialEq, Clone, Debug)]
 pub struct InspectRunbook {
     /// Path to the manifest
     #[arg(long = "manifest-file-path", short = 'm', default_value = "./txtx.yml")]
     pub manifest_path: String,
-    /// Disable Terminal UI
-    #[clap(long = "no-term-ui")]
-    pub no_tui: bool,
-    /// Path to runbook root file
-    // #[clap(long = "runbook-path", conflicts_with = "runbook")]
-    // pub runbook_path: Option<String>,
-    /// Name of runbook as indexed in txtx.yml
-    #[clap(long = "runbook")]
-    pub runbook: Option<String>,
+    /// Name of the runbook as indexed in the txtx.yml
+    pub runbook: String,
+    /// Choose the environment variable to set from those configured in the txtx.yml
+    #[arg(long = "env")]
+    pub environment: Option<String>,
+    /// A set of inputs to use for batch processing
+    #[arg(long = "input")]
+    pub inputs: Vec<String>,
+    /// JSON or YAML files of inputs (`-` to read from stdin). Later files override earlier ones, and `--input` values override all files
+    #[arg(long = "input-file")]
+    pub input_files: Vec<String>,
+    /// Print the report in JSON format
+    #[arg(long = "json")]
+    pub json: bool,
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
-#[command(group = clap::ArgGroup::new("execution_mode").multiple(false).args(["unsupervised", "web_console", "term_console"]).required(false))]
+#[command(group = clap::ArgGroup::new("execution_mode").multiple(false).args(["unsupervised", "web_console", "term_console", "unattended"]).required(false))]
 pub struct ExecuteRunbook {
     /// Path to the manifest
     #[arg(long = "manifest-file-path", short = 'm', default_value = "./txtx.yml")]
@@ -154,19 +307,33 @@ pub struct ExecuteRunbook {
     /// Execute the runbook with supervision via the terminal console (coming soon)
     #[arg(long = "terminal", short = 't', action=ArgAction::SetTrue, group = "execution_mode")]
     pub term_console: bool,
+    /// Execute the runbook with supervision, without a browser: reviews and validations are auto-approved and logged, and actions requiring a human (signatures from interactive signers, inputs without default values) fail the execution. Sensitive inputs, such as keyfile passphrases, are prompted for when running in a terminal
+    #[arg(long = "unattended", action=ArgAction::SetTrue, group = "execution_mode")]
+    pub unattended: bool,
     /// When running in unsupervised mode, print outputs in JSON format. If a directory is provided, the output will be written a file at the directory.
     #[arg(long = "output-json")]
     pub output_json: Option<Option<String>>,
     /// Pick a specific output to stdout at the end of the execution
     #[arg(long = "output", conflicts_with = "output_json")]
     pub output: Option<String>,
+    /// When running in unsupervised mode, the format of the execution report. With `json`, a single JSON document (status, constructs, outputs and diagnostics) is printed to stdout once the execution completes, and the progress is reported to stderr.
+    #[arg(long = "format", value_enum, default_value = "text")]
+    pub format: OutputFormat,
+    /// Do not report the execution progress
+    #[arg(long = "quiet", short = 'q')]
+    pub quiet: bool,
     /// Explain how the runbook will be executed.
     #[arg(long = "explain", action=ArgAction::SetTrue)]
     pub explain: bool,
-    /// Set the port for hosting the web UI
-    #[arg(long = "port", short = 'p', default_value = txtx_supervisor_ui::DEFAULT_BINDING_PORT )]
+    /// Set the port for hosting the web UI. Unlike the default port, an explicit port is not
+    /// replaced by the next available one when it's already in use.
+    #[arg(long = "port", short = 'p', alias = "supervisor-port")]
     #[cfg(feature = "supervisor_ui")]
-    pub network_binding_port: u16,
+    pub network_binding_port: Option<u16>,
+    /// Number of successive ports to try when the default port of the web UI is already in use
+    #[arg(long = "port-fallback-range", default_value = txtx_supervisor_ui::DEFAULT_PORT_FALLBACK_RANGE )]
+    #[cfg(feature = "supervisor_ui")]
+    pub port_fallback_range: u16,
     /// Set the port for hosting the web UI
     #[arg(long = "ip", short = 'i', default_value = txtx_supervisor_ui::DEFAULT_BINDING_ADDRESS )]
     #[cfg(feature = "supervisor_ui")]
@@ -177,18 +344,77 @@ pub struct ExecuteRunbook {
     /// A set of inputs to use for batch processing
     #[arg(long = "input")]
     pub inputs: Vec<String>,
+    /// JSON or YAML files of inputs (`-` to read from stdin). Later files override earlier ones, and `--input` values override all files
+    #[arg(long = "input-file")]
+    pub input_files: Vec<String>,
+    /// Print the merged inputs, with their sensitive values redacted, before starting the execution
+    #[arg(long = "print-inputs")]
+    pub print_inputs: bool,
+    /// Do not prompt for the environment and the inputs before the run, when the manifest defines several environments
+    #[arg(long = "no-interactive")]
+    pub no_interactive: bool,
 
     /// Execute the Runbook even if the cached state suggests this Runbook has already been executed
     #[arg(long = "force", short = 'f')]
     pub force_execution: bool,
+    /// Execute the runbook again every time the manifest or the runbook sources change. Restricted to unsupervised executions in environments declared local with `--local-env`
+    #[arg(long = "watch", action=ArgAction::SetTrue, requires = "unsupervised")]
+    pub watch: bool,
+    /// Declare the selected environment as local (e.g. a localnet), allowing `--watch` to execute the runbook again, without prompts, on every change
+    #[arg(long = "local-env", requires = "watch")]
+    pub local_environment: bool,
+    /// Fail the execution on warnings, as if they were errors (e.g. in CI)
+    #[arg(long = "deny-warnings")]
+    pub deny_warnings: bool,
     /// The log level to use for the runbook execution. Options are "trace", "debug", "info", "warn", "error".
     #[arg(long = "log-level", short = 'l', default_value = "info")]
     pub log_level: String,
+    /// Report more of the execution progress: `-v` for debug logs, `-vv` for trace logs. Overrides `--log-level`
+    #[arg(short = 'v', long = "verbose", action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable report
+    Text,
+    /// Machine-readable report
+    Json,
 }
 
 impl ExecuteRunbook {
     pub fn do_start_supervisor_ui(&self) -> bool {
-        self.web_console || (!self.unsupervised && !self.term_console)
+        self.web_console || (!self.unsupervised && !self.term_console && !self.unattended)
+    }
+
+    /// Returns the port of the web UI, along with the number of successive ports to fall back to
+    /// if it's already in use.
+    #[cfg(feature = "supervisor_ui")]
+    pub fn supervisor_port_binding(&self) -> (u16, u16) {
+        match self.network_binding_port {
+            Some(port) => (port, 0),
+            None => (txtx_supervisor_ui::DEFAULT_BINDING_PORT, self.port_fallback_range),
+        }
+    }
+
+    /// Returns true if the environment and the inputs are to be picked in the terminal before the
+    /// run: only from a terminal, when no environment is passed and no one else is answering.
+    pub fn do_pick_environment_and_inputs(&self) -> bool {
+        !self.no_interactive
+            && self.environment.is_none()
+            && !self.unattended
+            && self.format == OutputFormat::Text
+            && atty::is(atty::Stream::Stdin)
+            && atty::is(atty::Stream::Stdout)
+    }
+
+    /// Returns the log level of the execution, raised by `-v` flags.
+    pub fn log_level(&self) -> &str {
+        match self.verbose {
+            0 => &self.log_level,
+            1 => "debug",
+            _ => "trace",
+        }
     }
 }
 
@@ -197,6 +423,14 @@ pub struct CreateRunbook {
     /// Path to the manifest
     #[arg(long = "manifest-file-path", short = 'm', default_value = "./txtx.yml")]
     pub manifest_path: String,
+    /// Scaffold the runbook from a template (evm-deploy, svm-anchor-deploy)
+    pub template: Option<String>,
+    /// Name of the project scaffolded from the template
+    #[arg(long = "name", requires = "template")]
+    pub name: Option<String>,
+    /// Network targeted by the environment scaffolded from the template
+    #[arg(long = "network", requires = "template")]
+    pub network: Option<String>,
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
@@ -208,6 +442,10 @@ pub struct ListRunbooks {
     /// List environments instead of runbooks
     #[arg(long = "envs", alias = "env", short = 'e')]
     pub envs: bool,
+
+    /// Print the runbooks and environments as JSON
+    #[arg(long = "json")]
+    pub json: bool,
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
@@ -253,8 +491,9 @@ fn load_stdin() -> Option<String> {
     if atty::is(Stream::Stdin) {
         return None;
     }
+    // piped input files span multiple lines
     let mut buffer = String::new();
-    std::io::stdin().read_line(&mut buffer).ok()?;
+    std::io::stdin().read_to_string(&mut buffer).ok()?;
     return Some(buffer);
 }
 
@@ -267,6 +506,7 @@ pub fn main() {
         Ok(opts) => opts,
         Err(e) => e.exit(),
     };
+    crate::macros::init_colors(opts.no_color);
 
     let buffer_stdin = if let Command::Lsp = opts.command { None } else { load_stdin() };
 
@@ -274,7 +514,7 @@ pub fn main() {
         Err(e) => {
             error!(ctx.expect_logger(), "{e}");
             std::thread::sleep(std::time::Duration::from_millis(500));
-            process::exit(1);
+            process::exit(e.exit_code.code());
         }
         Ok(_) => {}
     }
@@ -284,11 +524,17 @@ async fn handle_command(
     opts: Opts,
     ctx: &Context,
     buffer_stdin: Option<String>,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
     match opts.command {
         Command::Check(cmd) => {
             runbooks::handle_check_command(&cmd, buffer_stdin, ctx).await?;
         }
+        Command::Graph(cmd) => {
+            runbooks::handle_graph_command(&cmd, buffer_stdin, ctx).await?;
+        }
+        Command::Inspect(cmd) => {
+            runbooks::handle_inspect_command(&cmd, buffer_stdin, ctx).await?;
+        }
         Command::Run(cmd) => {
             runbooks::handle_run_command(&cmd, buffer_stdin, ctx).await?;
         }
@@ -301,6 +547,9 @@ async fn handle_command(
         Command::Docs(cmd) => {
             docs::handle_docs_command(&cmd, ctx).await?;
         }
+        Command::Fmt(cmd) => {
+            fmt::handle_fmt_command(&cmd, ctx).await?;
+        }
         Command::Lint(cmd) => {
             handle_lint_command(&cmd).map_err(|e| e.to_string())?;
         }
@@ -310,6 +559,15 @@ async fn handle_command(
         Command::Snapshots(SnapshotCommand::Commit(cmd)) => {
             snapshots::handle_commit_command(&cmd, ctx).await?;
         }
+        Command::Snapshots(SnapshotCommand::Diff(cmd)) => {
+            snapshots::handle_diff_command(&cmd, buffer_stdin, ctx).await?;
+        }
+        Command::Keyfile(KeyfileCommand::New(cmd)) => {
+            keyfile::handle_new_command(&cmd, ctx).await?;
+        }
+        Command::Keyfile(KeyfileCommand::Rotate(cmd)) => {
+            keyfile::handle_rotate_command(&cmd, ctx).await?;
+        }
         Command::Lsp => {
             lsp::run_lsp().await?;
         }
@@ -369,12 +627,19 @@ mod tests {
         assert_eq!(result.unsupervised, false);
         assert_eq!(result.web_console, false);
         assert_eq!(result.term_console, false);
+        assert_eq!(result.unattended, false);
+        #[cfg(feature = "supervisor_ui")]
+        assert_eq!(result.network_binding_port, None);
         #[cfg(feature = "supervisor_ui")]
-        assert_eq!(result.network_binding_port, 8488);
+        assert_eq!(result.supervisor_port_binding(), (8488, 10));
         #[cfg(feature = "supervisor_ui")]
         assert_eq!(result.network_binding_ip_address, "localhost");
         assert_eq!(result.environment, None);
         assert!(result.inputs.is_empty());
+        assert!(result.input_files.is_empty());
+        assert_eq!(result.format, OutputFormat::Text);
+        assert_eq!(result.quiet, false);
+        assert_eq!(result.deny_warnings, false);
     }
 
     #[test]
@@ -386,6 +651,48 @@ mod tests {
         assert_eq!(result.term_console, false);
     }
 
+    #[test]
+    fn test_json_format() {
+        let args = vec!["txtx", "runbook", "--unsupervised", "--format", "json", "--quiet"];
+        let result = parse_args(args);
+        assert_eq!(result.format, OutputFormat::Json);
+        assert_eq!(result.quiet, true);
+    }
+
+    #[test]
+    fn test_deny_warnings() {
+        let result = parse_args(vec!["txtx", "runbook", "--unsupervised", "--deny-warnings"]);
+        assert_eq!(result.deny_warnings, true);
+    }
+
+    #[test]
+    fn test_unattended_mode() {
+        let args = vec!["txtx", "runbook", "--unattended"];
+        let result = parse_args(args);
+        assert_eq!(result.unattended, true);
+        assert_eq!(result.unsupervised, false);
+        assert_eq!(result.do_start_supervisor_ui(), false);
+    }
+
+    #[test]
+    fn test_no_interactive_skips_picker() {
+        let result = parse_args(vec!["txtx", "runbook", "--no-interactive"]);
+        assert_eq!(result.do_pick_environment_and_inputs(), false);
+        let result = parse_args(vec!["txtx", "runbook", "--env", "devnet"]);
+        assert_eq!(result.do_pick_environment_and_inputs(), false);
+    }
+
+    #[test]
+    fn test_verbosity_overrides_log_level() {
+        let result = parse_args(vec!["txtx", "runbook", "--log-level", "warn"]);
+        assert_eq!(result.log_level(), "warn");
+        let result = parse_args(vec!["txtx", "runbook", "-v"]);
+        assert_eq!(result.log_level(), "debug");
+        let result = parse_args(vec!["txtx", "runbook", "-vv", "--log-level", "warn"]);
+        assert_eq!(result.log_level(), "trace");
+        assert!(ExecuteRunbook::try_parse_from(vec!["txtx", "runbook", "-v", "--quiet"]).is_err());
+    }
+
     #[test]
     fn test_web_console_mode() {
         let args = vec!["txtx", "runbook", "--browser"];
@@ -409,7 +716,21 @@ mod tests {
     fn test_port_setting() {
         let args = vec!["txtx", "runbook", "--port", "9090"];
         let result = parse_args(args);
-        assert_eq!(result.network_binding_port, 9090);
+        assert_eq!(result.network_binding_port, Some(9090));
+        // an explicit port disables the fallback
+        assert_eq!(result.supervisor_port_binding(), (9090, 0));
+
+        let args = vec!["txtx", "runbook", "--supervisor-port", "9090"];
+        let result = parse_args(args);
+        assert_eq!(result.network_binding_port, Some(9090));
+    }
+
+    #[test]
+    #[cfg(feature = "supervisor_ui")]
+    fn test_port_fallback_range_setting() {
+        let args = vec!["txtx", "runbook", "--port-fallback-range", "3"];
+        let result = parse_args(args);
+        assert_eq!(result.supervisor_port_binding(), (8488, 3));
     }
 
     #[test]
@@ -434,9 +755,35 @@ mod tests {
         assert_eq!(result.inputs, vec!["input1", "input2"]);
     }
 
+    #[test]
+    fn test_input_files_setting() {
+        let args = vec!["txtx", "runbook", "--input-file", "a.json", "--input-file", "-"];
+        let result = parse_args(args);
+        assert_eq!(result.input_files, vec!["a.json", "-"]);
+        assert_eq!(result.print_inputs, false);
+    }
+
+    #[test]
+    fn test_watch_requires_unsupervised() {
+        let args = vec!["txtx", "runbook", "--watch"];
+        assert!(ExecuteRunbook::try_parse_from(args).is_err());
+        let args = vec!["txtx", "runbook", "--watch", "--unsupervised"];
+        assert_eq!(parse_args(args).watch, true);
+    }
+
+    #[test]
+    fn test_local_env_requires_watch() {
+        let args = vec!["txtx", "runbook", "--local-env", "--unsupervised"];
+        assert!(ExecuteRunbook::try_parse_from(args).is_err());
+        let args = vec!["txtx", "runbook", "--watch", "--local-env", "--unsupervised"];
+        assert_eq!(parse_args(args).local_environment, true);
+    }
+
     #[test_case("--unsupervised", "--browser")]
     #[test_case("--unsupervised", "--terminal")]
     #[test_case("--browser", "--terminal")]
+    #[test_case("--unattended", "--unsupervised")]
+    #[test_case("--unattended", "--browser")]
     fn test_conflicting_arguments(arg1: &str, arg2: &str) {
         let args = vec!["txtx", "runbook", arg1, arg2];
         let thing = ExecuteRunbook::try_parse_from(args);
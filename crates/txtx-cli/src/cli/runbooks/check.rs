@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use txtx_core::kit::types::cloud_interface::CloudServiceContext;
+use txtx_core::kit::types::diagnostics::Diagnostic;
+use txtx_core::kit::types::AuthorizationContext;
+use txtx_core::manifest::file::read_runbooks_from_manifest;
+use txtx_core::validation::hcl_validator::validate_with_hcl_and_addons;
+use txtx_core::validation::ValidationResult;
+
+use super::{display_state_changes, load_workspace_manifest_from_manifest_path, watch};
+use crate::cli::common::addon_registry;
+use crate::cli::common::exit_code::{CliError, ExitCode};
+use crate::cli::{CheckRunbook, Context, OutputFormat};
+use crate::get_addon_by_namespace;
+
+/// Statically validates the runbooks of the manifest, without executing anything: the sources
+/// are parsed and checked against the addons specifications (unknown attributes, unresolved
+/// references), then the runbook contexts and dependency graphs are built.
+///
+/// Fails with a validation failure (exit code 2) if errors are reported, or if only warnings are
+/// reported and `--deny-warnings` is set.
+///
+/// With `--watch`, the runbooks are checked again every time the manifest or their sources change.
+pub async fn handle_check_command(
+    cmd: &CheckRunbook,
+    buffer_stdin: Option<String>,
+    _ctx: &Context,
+) -> Result<(), CliError> {
+    if cmd.watch {
+        loop {
+            watch::clear_screen();
+            if let Err(e) = check_runbooks(cmd, buffer_stdin.clone()).await {
+                println!("{} {}", red!("x"), e);
+            }
+            println!(
+                "\n{} Checked at {}, watching for changes (Ctrl-C to stop)",
+                purple!("→"),
+                chrono::Local::now().format("%H:%M:%S")
+            );
+            watch::wait_for_changes(&cmd.manifest_path, &cmd.input_files).await;
+        }
+    }
+
+    let (error_count, warning_count) = check_runbooks(cmd, buffer_stdin).await?;
+    if error_count > 0 || (warning_count > 0 && cmd.deny_warnings) {
+        return Err(CliError::new(
+            ExitCode::ValidationFailure,
+            format!("check failed with {} error(s) and {} warning(s)", error_count, warning_count),
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the runbooks and reports their diagnostics, returning the number of errors and warnings.
+async fn check_runbooks(
+    cmd: &CheckRunbook,
+    buffer_stdin: Option<String>,
+) -> Result<(usize, usize), CliError> {
+    let manifest = load_workspace_manifest_from_manifest_path(&cmd.manifest_path)
+        .map_err(CliError::configuration)?;
+    let top_level_inputs_map = manifest
+        .get_runbook_inputs(&cmd.environment, &cmd.input_files, &cmd.inputs, buffer_stdin)
+        .map_err(CliError::configuration)?;
+    let environment_selector =
+        cmd.environment.clone().or(manifest.environments.first().map(|(k, _)| k.clone()));
+
+    let runbooks = read_runbooks_from_manifest(&manifest, &environment_selector, None)
+        .map_err(CliError::configuration)?;
+    if let Some(ref desired_runbook_name) = cmd.runbook {
+        if !runbooks.iter().any(|(id, (_, _, name, _))| {
+            name.eq(desired_runbook_name) || id.eq(desired_runbook_name)
+        }) {
+            return Err(CliError::configuration(format!(
+                "unable to retrieve runbook '{}' in manifest",
+                desired_runbook_name
+            )));
+        }
+    }
+
+    let addons = addon_registry::get_all_addons();
+    let addon_specs = addon_registry::extract_addon_specifications(&addons);
+
+    let mut diagnostics = vec![];
+    let mut checked_runbooks = vec![];
+    for (runbook_id, (mut runbook, runbook_sources, runbook_name, runbook_state)) in
+        runbooks.into_iter()
+    {
+        if let Some(ref desired_runbook_name) = cmd.runbook {
+            if !runbook_name.eq(desired_runbook_name) && !runbook_id.eq(desired_runbook_name) {
+                continue;
+            }
+        }
+
+        let mut runbook_diagnostics = vec![];
+        for (location, (_, raw_content)) in runbook_sources.tree.iter() {
+            let file_path = location.to_string();
+            let mut result = ValidationResult::new();
+            if let Err(e) = validate_with_hcl_and_addons(
+                &raw_content.to_string(),
+                &mut result,
+                &file_path,
+                addon_specs.clone(),
+            ) {
+                result.errors.push(Diagnostic::error_from_string(e).location(location));
+            }
+            runbook_diagnostics.extend(result.errors);
+            runbook_diagnostics.extend(result.warnings);
+        }
+
+        // sources that can't be parsed would be reported twice
+        if !runbook_diagnostics.iter().any(|d| d.is_error()) {
+            let authorization_context =
+                AuthorizationContext::new(manifest.location.clone().unwrap());
+            if let Err(diags) = runbook
+                .build_contexts_from_sources(
+                    runbook_sources,
+                    top_level_inputs_map.clone(),
+                    authorization_context,
+                    get_addon_by_namespace,
+                    CloudServiceContext::new(),
+                )
+                .await
+            {
+                runbook_diagnostics.extend(diags);
+            }
+        }
+
+        if !runbook_diagnostics.iter().any(|d| d.is_error()) {
+            checked_runbooks.push((runbook, runbook_state));
+        }
+        diagnostics.extend(runbook_diagnostics);
+    }
+
+    for diag in diagnostics.iter_mut() {
+        if diag.file.is_none() {
+            diag.file = diag.location.as_ref().map(|location| location.to_string());
+        }
+    }
+    let error_count = diagnostics.iter().filter(|d| d.is_error()).count();
+    let warning_count = diagnostics.iter().filter(|d| d.is_warning()).count();
+
+    match cmd.format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&diagnostics).map_err(|e| e.to_string())?);
+        }
+        OutputFormat::Text => {
+            display_diagnostics(&diagnostics);
+            if error_count == 0 && warning_count == 0 {
+                println!("{} No issues found", green!("✓"));
+            } else {
+                println!("\n{} error(s), {} warning(s)", error_count, warning_count);
+            }
+            // the actions to re-execute are only listed when checking a single runbook, and
+            // left out of the compact summary of watch mode
+            if cmd.runbook.is_some() && !cmd.watch {
+                for (mut runbook, runbook_state) in checked_runbooks.into_iter() {
+                    let Some(runbook_state) = runbook_state else { continue };
+                    let state_file_location = runbook_state.get_location_for_ctx(
+                        &runbook.runbook_id.name,
+                        Some(&runbook.top_level_inputs_map.current_top_level_input_name()),
+                    );
+                    if state_file_location.exists() {
+                        display_state_changes(&mut runbook, &runbook_state).await?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok((error_count, warning_count))
+}
+
+/// Prints the diagnostics grouped by file.
+fn display_diagnostics(diagnostics: &Vec<Diagnostic>) {
+    let mut diagnostics_by_file: BTreeMap<String, Vec<&Diagnostic>> = BTreeMap::new();
+    for diag in diagnostics.iter() {
+        let file = diag.file.clone().unwrap_or_else(|| "<unknown file>".to_string());
+        diagnostics_by_file.entry(file).or_default().push(diag);
+    }
+    for (file, diagnostics) in diagnostics_by_file.iter() {
+        println!("\n{}", file);
+        for diag in diagnostics.iter() {
+            let marker = if diag.is_error() { red!("x") } else { yellow!("!") };
+            println!("  {} {}", marker, diag);
+        }
+    }
+}
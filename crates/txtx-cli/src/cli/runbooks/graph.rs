@@ -0,0 +1,223 @@
+use std::collections::HashSet;
+
+use txtx_core::kit::constants::DEPENDS_ON;
+use txtx_core::kit::types::commands::ConstructInstance;
+use txtx_core::kit::types::{ConstructDid, EvaluatableInput};
+use txtx_core::runbook::flow_context::FlowContext;
+
+use super::build_runbook_contexts;
+use crate::cli::{Context, GraphFormat, GraphRunbook};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum EdgeKind {
+    /// The construct consumes a value of its dependency
+    Data,
+    /// The dependency is declared with a `depends_on` attribute
+    DependsOn,
+    /// The construct is signed by the signer
+    Signer,
+    /// The dependency is contributed by an addon
+    Addon,
+}
+
+impl EdgeKind {
+    fn label(&self) -> &'static str {
+        match self {
+            EdgeKind::Data => "data",
+            EdgeKind::DependsOn => DEPENDS_ON,
+            EdgeKind::Signer => "signer",
+            EdgeKind::Addon => "addon",
+        }
+    }
+}
+
+struct GraphNode {
+    id: String,
+    label: String,
+}
+
+struct FlowGraph {
+    name: String,
+    nodes: Vec<GraphNode>,
+    /// Edges going from a dependency to the construct depending on it
+    edges: Vec<(String, String, EdgeKind)>,
+}
+
+pub async fn handle_graph_command(
+    cmd: &GraphRunbook,
+    buffer_stdin: Option<String>,
+    _ctx: &Context,
+) -> Result<(), String> {
+    let (runbook_name, runbook) = build_runbook_contexts(
+        &cmd.manifest_path,
+        &cmd.runbook,
+        &cmd.environment,
+        &cmd.input_files,
+        &cmd.inputs,
+        buffer_stdin,
+    )
+    .await?;
+
+    let flows = runbook
+        .flow_contexts
+        .iter()
+        .enumerate()
+        .map(|(index, flow_context)| build_flow_graph(index, flow_context))
+        .collect::<Vec<_>>();
+
+    let graph = match cmd.format {
+        GraphFormat::Dot => render_dot(&runbook_name, &flows),
+        GraphFormat::Mermaid => render_mermaid(&flows),
+    };
+    println!("{}", graph);
+    Ok(())
+}
+
+fn build_flow_graph(flow_index: usize, flow_context: &FlowContext) -> FlowGraph {
+    let execution_context = &flow_context.execution_context;
+    // constructs can be shared by flows, their ids are scoped to the flow
+    let node_id =
+        |construct_did: &ConstructDid| format!("f{}_{}", flow_index, construct_did.to_string());
+
+    let mut nodes = vec![];
+    let mut known_constructs = HashSet::new();
+    for (position, construct_did) in
+        execution_context.order_for_commands_execution.iter().enumerate()
+    {
+        let (construct_type, name, namespace) = if let Some(command_instance) =
+            execution_context.commands_instances.get(construct_did)
+        {
+            (
+                command_instance.typing.to_ident(),
+                &command_instance.name,
+                &command_instance.namespace,
+            )
+        } else if let Some(signer_instance) = execution_context.signers_instances.get(construct_did)
+        {
+            ("signer", &signer_instance.name, &signer_instance.namespace)
+        } else {
+            continue;
+        };
+        known_constructs.insert(construct_did.clone());
+        nodes.push(GraphNode {
+            id: node_id(construct_did),
+            label: format!("#{} {}.{} ({})", position + 1, construct_type, name, namespace),
+        });
+    }
+
+    let mut edges = vec![];
+    let mut add_edge = |dependency: &ConstructDid, construct_did: &ConstructDid, kind: EdgeKind| {
+        if !known_constructs.contains(dependency) || !known_constructs.contains(construct_did) {
+            return;
+        }
+        let edge = (node_id(dependency), node_id(construct_did), kind);
+        if !edges.contains(&edge) {
+            edges.push(edge);
+        }
+    };
+
+    for construct_did in execution_context.order_for_commands_execution.iter() {
+        let Some(command_instance) = execution_context.commands_instances.get(construct_did) else {
+            continue;
+        };
+        for (input, expression) in
+            command_instance.get_expressions_referencing_commands_from_inputs()
+        {
+            let Ok(Some((dependency, _, _))) =
+                flow_context.workspace_context.try_resolve_construct_reference_in_expression(
+                    &command_instance.package_id,
+                    &expression,
+                )
+            else {
+                continue;
+            };
+            let kind = match input {
+                Some(input) if input.name().eq(DEPENDS_ON) => EdgeKind::DependsOn,
+                _ => EdgeKind::Data,
+            };
+            add_edge(&dependency, construct_did, kind);
+        }
+    }
+
+    for (signer_did, signed_commands) in execution_context.signers_downstream_dependencies.iter() {
+        for construct_did in signed_commands.iter() {
+            add_edge(signer_did, construct_did, EdgeKind::Signer);
+        }
+    }
+
+    for (construct_did, dependencies) in
+        flow_context.graph_context.domain_specific_dependencies.iter()
+    {
+        for dependency in dependencies.iter() {
+            add_edge(dependency, construct_did, EdgeKind::Addon);
+        }
+    }
+
+    FlowGraph { name: flow_context.name.clone(), nodes, edges }
+}
+
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn render_dot(runbook_name: &str, flows: &Vec<FlowGraph>) -> String {
+    let mut dot = format!("digraph \"{}\" {{\n", escape(runbook_name));
+    dot.push_str("  node [shape=box];\n");
+    for (index, flow) in flows.iter().enumerate() {
+        dot.push_str(&format!("  subgraph \"cluster_{}\" {{\n", index));
+        dot.push_str(&format!("    label=\"{}\";\n", escape(&flow.name)));
+        for node in flow.nodes.iter() {
+            dot.push_str(&format!("    \"{}\" [label=\"{}\"];\n", node.id, escape(&node.label)));
+        }
+        dot.push_str("  }\n");
+    }
+    for flow in flows.iter() {
+        for (from, to, kind) in flow.edges.iter() {
+            let style = match kind {
+                EdgeKind::Data => "solid",
+                EdgeKind::DependsOn => "dashed",
+                EdgeKind::Signer => "bold",
+                EdgeKind::Addon => "dotted",
+            };
+            dot.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\", style={}];\n",
+                from,
+                to,
+                kind.label(),
+                style
+            ));
+        }
+    }
+    dot.push_str("}");
+    dot
+}
+
+fn render_mermaid(flows: &Vec<FlowGraph>) -> String {
+    let mut mermaid = "flowchart TD\n".to_string();
+    for (index, flow) in flows.iter().enumerate() {
+        mermaid.push_str(&format!(
+            "  subgraph flow_{}[\"{}\"]\n",
+            index,
+            flow.name.replace('"', "#quot;")
+        ));
+        for node in flow.nodes.iter() {
+            mermaid.push_str(&format!(
+                "    {}[\"{}\"]\n",
+                node.id,
+                node.label.replace('"', "#quot;")
+            ));
+        }
+        mermaid.push_str("  end\n");
+    }
+    for flow in flows.iter() {
+        for (from, to, kind) in flow.edges.iter() {
+            let arrow = match kind {
+                EdgeKind::Data | EdgeKind::Addon => "-->",
+                EdgeKind::DependsOn => "-.->",
+                EdgeKind::Signer => "==>",
+            };
+            mermaid.push_str(&format!("  {} {}|{}| {}\n", from, arrow, kind.label(), to));
+        }
+    }
+    mermaid
+}
@@ -0,0 +1,380 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use txtx_core::kit::hcl::expr::{Expression, TraversalOperator};
+use txtx_core::kit::helpers::hcl::collect_constructs_references_from_expression;
+use txtx_core::kit::types::commands::CommandInstanceType;
+use txtx_core::types::Runbook;
+
+use super::build_runbook_contexts;
+use crate::cli::{Context, InspectRunbook};
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RunbookReport {
+    runbook: String,
+    environment: Option<String>,
+    addons: Vec<AddonReport>,
+    signers: Vec<SignerReport>,
+    flows: Vec<FlowReport>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddonReport {
+    namespace: String,
+    /// Keys of the defaults set by the `addon` blocks
+    defaults_keys: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SignerReport {
+    name: String,
+    signer_type: String,
+    requires_interaction: bool,
+    /// Actions signed by the signer
+    signs: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlowReport {
+    name: String,
+    actions: Vec<ActionReport>,
+    outputs: Vec<ValueReport>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ActionReport {
+    name: String,
+    action_type: String,
+    inputs: Vec<ValueReport>,
+}
+
+/// A declared input of an action, or the value of an output, with where its value comes from.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ValueReport {
+    name: String,
+    source: ValueSource,
+    references: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ValueSource {
+    Literal,
+    Variable,
+    /// A top-level input, set by the environment or the command line
+    Env,
+    Action,
+    Signer,
+    /// An expression computed without references to constructs, such as a function call
+    Expression,
+    /// An expression combining several sources
+    Mixed,
+}
+
+impl ValueSource {
+    fn label(&self) -> &'static str {
+        match self {
+            ValueSource::Literal => "literal",
+            ValueSource::Variable => "variable",
+            ValueSource::Env => "env",
+            ValueSource::Action => "action",
+            ValueSource::Signer => "signer",
+            ValueSource::Expression => "expression",
+            ValueSource::Mixed => "mixed",
+        }
+    }
+}
+
+/// Prints a read-only report of a runbook: the addons it requires, its signers and the actions
+/// they sign, its actions with the sources of their inputs, and its outputs. Contexts are built,
+/// nothing is executed.
+pub async fn handle_inspect_command(
+    cmd: &InspectRunbook,
+    buffer_stdin: Option<String>,
+    _ctx: &Context,
+) -> Result<(), String> {
+    let (runbook_name, runbook) = build_runbook_contexts(
+        &cmd.manifest_path,
+        &cmd.runbook,
+        &cmd.environment,
+        &cmd.input_files,
+        &cmd.inputs,
+        buffer_stdin,
+    )
+    .await?;
+
+    let report = build_report(runbook_name, &runbook);
+    if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+fn build_report(runbook_name: String, runbook: &Runbook) -> RunbookReport {
+    let mut addons: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let mut signers: BTreeMap<String, SignerReport> = BTreeMap::new();
+    let mut flows = vec![];
+
+    for flow_context in runbook.flow_contexts.iter() {
+        let execution_context = &flow_context.execution_context;
+
+        for ((_, addon_id), addon_defaults) in flow_context.workspace_context.addons_defaults.iter()
+        {
+            addons
+                .entry(addon_id.clone())
+                .or_default()
+                .extend(addon_defaults.store.store.keys().cloned());
+        }
+
+        for signer_instance in execution_context.signers_instances.values() {
+            addons.entry(signer_instance.namespace.clone()).or_default();
+            signers.entry(signer_instance.name.clone()).or_insert_with(|| SignerReport {
+                name: signer_instance.name.clone(),
+                signer_type: format!(
+                    "{}::{}",
+                    signer_instance.namespace, signer_instance.specification.matcher
+                ),
+                requires_interaction: signer_instance.specification.requires_interaction,
+                signs: vec![],
+            });
+        }
+        for (signer_did, signed_commands) in
+            execution_context.signers_downstream_dependencies.iter()
+        {
+            let Some(signer) = execution_context
+                .signers_instances
+                .get(signer_did)
+                .and_then(|signer_instance| signers.get_mut(&signer_instance.name))
+            else {
+                continue;
+            };
+            for construct_did in signed_commands.iter() {
+                let Some(command_instance) =
+                    execution_context.commands_instances.get(construct_did)
+                else {
+                    continue;
+                };
+                let action = format!("action.{}", command_instance.name);
+                if !signer.signs.contains(&action) {
+                    signer.signs.push(action);
+                }
+            }
+        }
+
+        let mut actions = vec![];
+        let mut outputs = vec![];
+        for construct_did in execution_context.order_for_commands_execution.iter() {
+            let Some(command_instance) = execution_context.commands_instances.get(construct_did)
+            else {
+                continue;
+            };
+            match command_instance.typing {
+                CommandInstanceType::Action(_) => {
+                    addons.entry(command_instance.namespace.clone()).or_default();
+                    let inputs = command_instance
+                        .block
+                        .body
+                        .attributes()
+                        .map(|attribute| value_report(attribute.key.to_string(), &attribute.value))
+                        .collect();
+                    actions.push(ActionReport {
+                        name: command_instance.name.clone(),
+                        action_type: format!(
+                            "{}::{}",
+                            command_instance.namespace, command_instance.specification.matcher
+                        ),
+                        inputs,
+                    });
+                }
+                CommandInstanceType::Output => {
+                    let value = command_instance
+                        .block
+                        .body
+                        .attributes()
+                        .find(|attribute| attribute.key.to_string().eq("value"));
+                    if let Some(attribute) = value {
+                        outputs.push(value_report(command_instance.name.clone(), &attribute.value));
+                    }
+                }
+                _ => {}
+            }
+        }
+        flows.push(FlowReport { name: flow_context.name.clone(), actions, outputs });
+    }
+
+    // constructs of the standard library don't require an addon
+    addons.remove("std");
+
+    RunbookReport {
+        runbook: runbook_name,
+        environment: runbook.get_active_inputs_selector(),
+        addons: addons
+            .into_iter()
+            .map(|(namespace, defaults_keys)| AddonReport {
+                namespace,
+                defaults_keys: defaults_keys.into_iter().collect(),
+            })
+            .collect(),
+        signers: signers.into_values().collect(),
+        flows,
+    }
+}
+
+fn value_report(name: String, expression: &Expression) -> ValueReport {
+    let mut dependencies = vec![];
+    collect_constructs_references_from_expression(expression, None, &mut dependencies);
+    let mut references = vec![];
+    for (_, dependency) in dependencies.iter() {
+        if let Some(reference) = reference_path(dependency) {
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+    }
+
+    let sources =
+        references.iter().map(|reference| reference_source(reference)).collect::<BTreeSet<_>>();
+    let source = match sources.len() {
+        0 if is_literal(expression) => ValueSource::Literal,
+        0 => ValueSource::Expression,
+        1 => *sources.first().unwrap(),
+        _ => ValueSource::Mixed,
+    };
+    ValueReport { name, source, references }
+}
+
+/// Returns the path of a reference, such as `action.deploy.contract_address`.
+fn reference_path(expression: &Expression) -> Option<String> {
+    let (root, operators) = match expression {
+        Expression::Variable(variable) => (variable.to_string(), None),
+        Expression::Traversal(traversal) => {
+            (traversal.expr.as_variable()?.to_string(), Some(&traversal.operators))
+        }
+        _ => return None,
+    };
+    let mut components = vec![root];
+    for operator in operators.into_iter().flatten() {
+        if let TraversalOperator::GetAttr(attribute) = operator.value() {
+            components.push(attribute.to_string());
+        }
+    }
+    Some(components.join("."))
+}
+
+fn reference_source(reference: &str) -> ValueSource {
+    match reference.split('.').next().unwrap_or_default() {
+        "variable" => ValueSource::Variable,
+        "input" => ValueSource::Env,
+        "action" => ValueSource::Action,
+        "signer" => ValueSource::Signer,
+        _ => ValueSource::Expression,
+    }
+}
+
+fn is_literal(expression: &Expression) -> bool {
+    match expression {
+        Expression::String(_)
+        | Expression::Number(_)
+        | Expression::Bool(_)
+        | Expression::Null(_) => true,
+        Expression::Array(elements) => elements.iter().all(is_literal),
+        Expression::Object(object) => object.iter().all(|(_, value)| is_literal(value.expr())),
+        _ => false,
+    }
+}
+
+fn print_report(report: &RunbookReport) {
+    let environment = report.environment.as_deref().unwrap_or("default");
+    println!("{} Runbook '{}' (environment '{}')", purple!("→"), report.runbook, environment);
+
+    println!("\n{}", yellow!("Addons"));
+    if report.addons.is_empty() {
+        println!("  none");
+    }
+    for addon in report.addons.iter() {
+        match addon.defaults_keys.is_empty() {
+            true => println!("  {}", addon.namespace),
+            false => {
+                println!("  {} (defaults: {})", addon.namespace, addon.defaults_keys.join(", "))
+            }
+        }
+    }
+
+    println!("\n{}", yellow!("Signers"));
+    if report.signers.is_empty() {
+        println!("  none");
+    }
+    for signer in report.signers.iter() {
+        let interaction = if signer.requires_interaction { ", interactive" } else { "" };
+        println!("  signer.{} ({}{})", signer.name, signer.signer_type, interaction);
+        for action in signer.signs.iter() {
+            println!("    signs {}", action);
+        }
+    }
+
+    for flow in report.flows.iter() {
+        println!("\n{}", yellow!("Flow '{}'", flow.name));
+        for action in flow.actions.iter() {
+            println!("  action.{} ({})", action.name, action.action_type);
+            for input in action.inputs.iter() {
+                print_value(input, "    ");
+            }
+        }
+        if !flow.outputs.is_empty() {
+            println!("  outputs");
+            for output in flow.outputs.iter() {
+                print_value(output, "    ");
+            }
+        }
+    }
+}
+
+fn print_value(value: &ValueReport, indent: &str) {
+    match value.references.is_empty() {
+        true => println!("{}{} ← {}", indent, value.name, value.source.label()),
+        false => println!(
+            "{}{} ← {} ({})",
+            indent,
+            value.name,
+            value.source.label(),
+            value.references.join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use txtx_core::kit::hcl::parser::parse_body;
+
+    fn value_of(source: &str) -> ValueReport {
+        let body = parse_body(&format!("value = {}\n", source)).unwrap();
+        let attribute = body.attributes().next().unwrap();
+        value_report("value".into(), &attribute.value)
+    }
+
+    #[test]
+    fn test_value_sources() {
+        assert_eq!(value_of(r#""0x1234""#).source, ValueSource::Literal);
+        assert_eq!(value_of(r#"[1, { a = true }]"#).source, ValueSource::Literal);
+        assert_eq!(value_of(r#"std::encode_hex("hi")"#).source, ValueSource::Expression);
+        assert_eq!(value_of("input.rpc_api_url").source, ValueSource::Env);
+        assert_eq!(value_of("variable.amount").source, ValueSource::Variable);
+
+        let value = value_of("action.deploy.contract_address");
+        assert_eq!(value.source, ValueSource::Action);
+        assert_eq!(value.references, vec!["action.deploy.contract_address"]);
+
+        let value = value_of("[variable.amount, input.fee, variable.amount]");
+        assert_eq!(value.source, ValueSource::Mixed);
+        assert_eq!(value.references, vec!["variable.amount", "input.fee"]);
+    }
+}
@@ -1,4 +1,6 @@
-use super::{CheckRunbook, Context, CreateRunbook, ExecuteRunbook, ListRunbooks};
+use super::common::exit_code::{CliError, ExitCode};
+use super::{Context, CreateRunbook, ExecuteRunbook, ListRunbooks, OutputFormat};
+use crate::term_ui::picker::{self, PickedInput};
 use crate::{get_addon_by_namespace, get_available_addons};
 use ascii_table::AsciiTable;
 use console::Style;
@@ -6,12 +8,17 @@ use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use itertools::Itertools;
 use log::{debug, error, info, trace, warn};
+use serde_json::json;
 use std::{
     collections::{BTreeMap, HashSet},
     env,
     fs::{self, File},
+    io::IsTerminal,
     path::PathBuf,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     time::Duration,
 };
 use tokio::sync::RwLock;
@@ -31,9 +38,10 @@ use txtx_core::{
             commands::{CommandId, CommandInputsEvaluationResult},
             diagnostics::Diagnostic,
             frontend::BlockEvent,
+            redaction::{SecretRegistry, REDACTED},
             stores::AddonDefaults,
             types::Value,
-            AuthorizationContext, Did, PackageId,
+            AuthorizationContext, Did, PackageId, RunbookId,
         },
         Addon,
     },
@@ -54,7 +62,10 @@ use txtx_core::{
 use txtx_gql::kit::{
     types::{
         cloud_interface::CloudServiceContext,
-        frontend::{LogDetails, LogEvent, LogLevel, TransientLogEventStatus},
+        frontend::{
+            ConstructStatusStore, HeartbeatEvent, LogDetails, LogEvent, LogLevel, LogProgress,
+            Panel, TransientLogEventStatus,
+        },
         types::AddonJsonConverter,
         RunbookInstanceContext,
     },
@@ -65,6 +76,33 @@ use txtx_gql::kit::{
 use actix_web::dev::ServerHandle;
 #[cfg(feature = "supervisor_ui")]
 use txtx_gql::kit::types::frontend::SupervisorAddonData;
+#[cfg(feature = "supervisor_ui")]
+use txtx_supervisor_ui::sessions::SessionStoreConfig;
+
+/// Prints human-readable output to stdout, or to stderr when stdout is reserved to the JSON report.
+macro_rules! human_println {
+    ($is_json_output:expr) => {
+        human_println!($is_json_output, "")
+    };
+    ($is_json_output:expr, $($arg:tt)*) => {
+        if $is_json_output {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+mod check;
+mod graph;
+mod inspect;
+mod scaffold;
+mod unattended;
+mod watch;
+
+pub use check::handle_check_command;
+pub use graph::handle_graph_command;
+pub use inspect::handle_inspect_command;
 
 lazy_static::lazy_static! {
     static ref CLI_SPINNER_STYLE: ProgressStyle = {
@@ -73,16 +111,27 @@ lazy_static::lazy_static! {
             .tick_strings(&["⠋", "⠙", "⠸", "⠴", "⠦", "⠇"]);
         style
     };
+    static ref CLI_HEARTBEAT_SPINNER_STYLE: ProgressStyle = {
+        let style = ProgressStyle::with_template("{spinner} {msg} (last update {elapsed} ago)")
+            .unwrap()
+            .tick_strings(&["⠋", "⠙", "⠸", "⠴", "⠦", "⠇"]);
+        style
+    };
 }
 
+/// When stdout is not a terminal, pending logs are reported with a status line at this interval
+/// instead of a spinner.
+const STATUS_LINE_INTERVAL: Duration = Duration::from_secs(5);
+
 pub fn display_snapshot_diffing(
     consolidated_changes: ConsolidatedChanges,
+    is_json_output: bool,
 ) -> Option<ConsolidatedChanges> {
     let synthesized_changes = consolidated_changes.get_synthesized_changes();
 
     if !consolidated_changes.new_plans_to_add.is_empty() {
-        println!("\n{}", yellow!("New chain to synchronize:"));
-        println!("{}\n", consolidated_changes.new_plans_to_add.join(", "));
+        human_println!(is_json_output, "\n{}", yellow!("New chain to synchronize:"));
+        human_println!(is_json_output, "{}\n", consolidated_changes.new_plans_to_add.join(", "));
     }
 
     let has_critical_changes = synthesized_changes
@@ -95,7 +144,7 @@ pub fn display_snapshot_diffing(
         .count()
         > 0;
     if has_critical_changes {
-        println!("\n{}\n", yellow!("Changes detected:"));
+        human_println!(is_json_output, "\n{}\n", yellow!("Changes detected:"));
         for (i, (change, _impacted)) in synthesized_changes.iter().enumerate() {
             match change {
                 SynthesizedChange::Edition(change, _) => {
@@ -103,12 +152,12 @@ pub fn display_snapshot_diffing(
                         .iter()
                         .map(|c| if c.starts_with("-") { red!(c) } else { green!(c) })
                         .join("");
-                    println!("{}. The following edits:\n-------------------------\n{}\n-------------------------", i + 1, formatted_change);
-                    println!("will introduce breaking changes.\n\n");
+                    human_println!(is_json_output, "{}. The following edits:\n-------------------------\n{}\n-------------------------", i + 1, formatted_change);
+                    human_println!(is_json_output, "will introduce breaking changes.\n\n");
                 }
                 SynthesizedChange::FormerFailure(_construct_to_run, command_name) => {
-                    println!("{}. The action error:\n-------------------------\n{}\n-------------------------", i + 1, command_name);
-                    println!("will be re-executed.\n\n");
+                    human_println!(is_json_output, "{}. The action error:\n-------------------------\n{}\n-------------------------", i + 1, command_name);
+                    human_println!(is_json_output, "will be re-executed.\n\n");
                 }
                 SynthesizedChange::Addition(_new_construct_did) => {}
             }
@@ -125,24 +174,28 @@ pub fn display_snapshot_diffing(
         .count()
         > 0;
     if has_unexecuted {
-        println!("\n{}", yellow!("Runbook Recovery Plan"));
-        println!("The previous runbook execution was interrupted before completion, causing the following actions to be aborted:");
+        human_println!(is_json_output, "\n{}", yellow!("Runbook Recovery Plan"));
+        human_println!(is_json_output, "The previous runbook execution was interrupted before completion, causing the following actions to be aborted:");
 
         for (_i, (change, _impacted)) in synthesized_changes.iter().enumerate() {
             match change {
                 SynthesizedChange::Edition(_, _) => {}
                 SynthesizedChange::FormerFailure(_construct_to_run, command_name) => {
-                    println!("- {}", command_name);
+                    human_println!(is_json_output, "- {}", command_name);
                 }
                 SynthesizedChange::Addition(_new_construct_did) => {}
             }
         }
-        println!("These actions will be re-executed in the next run.\n");
+        human_println!(is_json_output, "These actions will be re-executed in the next run.\n");
     }
 
     if !has_critical_changes && !has_unexecuted && consolidated_changes.new_plans_to_add.is_empty()
     {
-        println!("{} Latest snapshot in sync with latest runbook updates\n", green!("✓"));
+        human_println!(
+            is_json_output,
+            "{} Latest snapshot in sync with latest runbook updates\n",
+            green!("✓")
+        );
 
         // todo: if we had no critical changes, but there are some synthesized changes, this means the
         // synthesized changes were non-critical. we should consider updating our runbook state file
@@ -163,66 +216,57 @@ pub fn display_snapshot_diffing(
     Some(consolidated_changes)
 }
 
-pub async fn handle_check_command(
-    cmd: &CheckRunbook,
-    buffer_stdin: Option<String>,
-    _ctx: &Context,
+/// Lists the actions that would be re-executed, by diffing a simulated execution of the runbook
+/// against the snapshot of its previous execution.
+async fn display_state_changes(
+    runbook: &mut Runbook,
+    state_file_location: &RunbookStateLocation,
 ) -> Result<(), String> {
-    let (_manifest, _runbook_name, mut runbook, runbook_state) = load_runbook_from_manifest(
-        &cmd.manifest_path,
-        &cmd.runbook,
-        &cmd.environment,
-        &cmd.inputs,
-        buffer_stdin,
-    )
-    .await?;
-
-    match &runbook_state {
-        Some(state_file_location) => {
-            let ctx = RunbookSnapshotContext::new();
-            let old = state_file_location.load_execution_snapshot(
-                true,
-                &runbook.runbook_id.name,
-                &runbook.top_level_inputs_map.current_top_level_input_name(),
-            )?;
-            for run in runbook.flow_contexts.iter_mut() {
-                let frontier = HashSet::new();
-                let _res = run
-                    .execution_context
-                    .simulate_execution(
-                        &runbook.runtime_context,
-                        &run.workspace_context,
-                        &runbook.supervision_context,
-                        &frontier,
-                    )
-                    .await;
-            }
-            runbook.enable_full_execution_mode();
-            let new = ctx
-                .snapshot_runbook_execution(
-                    &runbook.runbook_id,
-                    &runbook.flow_contexts,
-                    None,
-                    &runbook.top_level_inputs_map,
-                )
-                .map_err(|e| e.message)?;
-
-            let consolidated_changes = match ctx.diff(old, new) {
-                Ok(changes) => changes,
-                Err(e) => {
-                    println!("{} Failed to process snapshot: {}", red!("x"), e);
-                    return Ok(());
-                }
-            };
+    let ctx = RunbookSnapshotContext::new();
+    let old = state_file_location.load_execution_snapshot(
+        true,
+        &runbook.runbook_id.name,
+        &runbook.top_level_inputs_map.current_top_level_input_name(),
+    )?;
+    for run in runbook.flow_contexts.iter_mut() {
+        let frontier = HashSet::new();
+        let _res = run
+            .execution_context
+            .simulate_execution(
+                &runbook.runtime_context,
+                &run.workspace_context,
+                &runbook.supervision_context,
+                &frontier,
+            )
+            .await;
+    }
+    runbook.enable_full_execution_mode();
+    let new = ctx
+        .snapshot_runbook_execution(
+            &runbook.runbook_id,
+            &runbook.flow_contexts,
+            None,
+            &runbook.top_level_inputs_map,
+        )
+        .map_err(|e| e.message)?;
 
-            display_snapshot_diffing(consolidated_changes);
+    let consolidated_changes = match ctx.diff(old, new) {
+        Ok(changes) => changes,
+        Err(e) => {
+            println!("{} Failed to process snapshot: {}", red!("x"), e);
+            return Ok(());
         }
-        None => {}
-    }
+    };
+
+    display_snapshot_diffing(consolidated_changes, false);
     Ok(())
 }
 
 pub async fn handle_new_command(cmd: &CreateRunbook, _ctx: &Context) -> Result<(), String> {
+    if let Some(ref template) = cmd.template {
+        return scaffold::handle_new_from_template(cmd, template);
+    }
+
     let manifest_location = FileLocation::from_path_string(&cmd.manifest_path)?;
     let manifest_res = WorkspaceManifest::from_location(&manifest_location);
 
@@ -403,43 +447,113 @@ pub async fn handle_list_command(cmd: &ListRunbooks, _ctx: &Context) -> Result<(
     let manifest_location = FileLocation::from_path_string(&cmd.manifest_path)?;
     let manifest = WorkspaceManifest::from_location(&manifest_location)?;
 
-    if cmd.envs {
-        // List environments
-        let mut env_names: Vec<&String> =
-            manifest.environments.keys().filter(|name| *name != "global").collect();
+    let mut env_names: Vec<&String> =
+        manifest.environments.keys().filter(|name| *name != "global").collect();
+    env_names.sort();
 
-        if env_names.is_empty() {
-            println!("No environments defined in manifest");
-            return Ok(());
-        }
-
-        env_names.sort();
+    let runbooks = manifest
+        .runbooks
+        .iter()
+        .map(|runbook| {
+            // the state of a runbook is tracked per environment
+            let states = runbook.state.as_ref().map(|state| {
+                let environments = if env_names.is_empty() {
+                    vec![DEFAULT_TOP_LEVEL_INPUTS_NAME.to_ascii_lowercase()]
+                } else {
+                    env_names.iter().map(|env| env.to_string()).collect()
+                };
+                environments
+                    .into_iter()
+                    .filter(|env| state.get_location_for_ctx(&runbook.name, Some(env)).exists())
+                    .collect::<Vec<_>>()
+            });
+            (runbook, states)
+        })
+        .collect::<Vec<_>>();
+
+    if cmd.json {
+        let runbooks_json = runbooks
+            .iter()
+            .map(|(runbook, states)| {
+                json!({
+                    "id": RunbookId::new(None, None, &runbook.name).did().to_string(),
+                    "name": runbook.name,
+                    "description": runbook.description,
+                    "location": runbook.location,
+                    "stateEnvironments": states,
+                })
+            })
+            .collect::<Vec<_>>();
+        let environments_json = env_names
+            .iter()
+            .map(|env| {
+                let inputs = manifest.environments[*env].keys().collect::<Vec<_>>();
+                json!({ "name": env, "inputs": inputs })
+            })
+            .collect::<Vec<_>>();
+        let json = if cmd.envs {
+            json!({ "environments": environments_json })
+        } else {
+            json!({ "runbooks": runbooks_json, "environments": environments_json })
+        };
+        println!("{}", serde_json::to_string_pretty(&json).map_err(|e| e.to_string())?);
+        return Ok(());
+    }
 
-        for env in env_names {
-            println!("{}", env);
+    if !cmd.envs {
+        if manifest.runbooks.is_empty() {
+            println!("{}: no runbooks referenced in the txtx.yml manifest.\nRun the command `txtx new` to create a new runbook.", yellow!("warning"));
+            std::process::exit(1);
         }
 
-        return Ok(());
+        let mut data = vec![vec![
+            "Id".to_string(),
+            "Name".to_string(),
+            "Description".to_string(),
+            "Location".to_string(),
+            "State".to_string(),
+        ]];
+        for (runbook, states) in runbooks.iter() {
+            let heading = runbook
+                .description
+                .as_deref()
+                .unwrap_or("")
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .unwrap_or("");
+            let state = match states {
+                None => "-".to_string(),
+                Some(states) if states.is_empty() => "none".to_string(),
+                Some(states) => states.join(", "),
+            };
+            data.push(vec![
+                RunbookId::new(None, None, &runbook.name).did().to_string(),
+                runbook.name.clone(),
+                heading.to_string(),
+                runbook.location.clone(),
+                state,
+            ]);
+        }
+        println!("{}", yellow!("Runbooks"));
+        let mut ascii_table = AsciiTable::default();
+        ascii_table.set_max_width(150);
+        ascii_table.print(data);
     }
 
-    // List runbooks
-    if manifest.runbooks.is_empty() {
-        println!("{}: no runbooks referenced in the txtx.yml manifest.\nRun the command `txtx new` to create a new runbook.", yellow!("warning"));
-        std::process::exit(1);
+    if env_names.is_empty() {
+        println!("No environments defined in manifest");
+        return Ok(());
     }
 
-    println!("{:<35}\t{}", "Name", yellow!("Description"));
-    for runbook in manifest.runbooks {
-        let heading = runbook
-            .description
-            .as_deref()
-            .unwrap_or("")
-            .lines()
-            .find(|line| !line.trim().is_empty())
-            .unwrap_or("");
-
-        println!("{:<35}\t{}", runbook.name, yellow!(heading));
+    let mut data = vec![vec!["Name".to_string(), "Inputs".to_string()]];
+    for env in env_names {
+        let inputs = manifest.environments[env].keys().join(", ");
+        data.push(vec![env.to_string(), inputs]);
     }
+    println!("{}", yellow!("Environments"));
+    let mut ascii_table = AsciiTable::default();
+    ascii_table.set_max_width(150);
+    ascii_table.print(data);
     Ok(())
 }
 
@@ -479,7 +593,11 @@ pub async fn run_action(
         inputs.insert(input_name, new_value);
     }
     let unevaluated_inputs = UnevaluatedInputsMap::new();
-    let evaluated_inputs = CommandInputsEvaluationResult { inputs, unevaluated_inputs };
+    let evaluated_inputs = CommandInputsEvaluationResult {
+        inputs,
+        unevaluated_inputs,
+        matched_types: IndexMap::new(),
+    };
 
     let _res = command
         .perform_execution(
@@ -499,7 +617,10 @@ pub async fn handle_run_command(
     cmd: &ExecuteRunbook,
     buffer_stdin: Option<String>,
     _ctx: &Context,
-) -> Result<(), String> {
+) -> Result<(), CliError> {
+    if cmd.watch {
+        return Ok(watch::handle_run_watch(cmd, buffer_stdin).await?);
+    }
     let is_execution_unsupervised = cmd.unsupervised;
 
     let available_addons = get_available_addons();
@@ -515,13 +636,16 @@ pub async fn handle_run_command(
         }
     }
 
+    let is_json_output = cmd.format == OutputFormat::Json;
     let (progress_tx, progress_rx) = txtx_core::kit::channel::unbounded();
     let res = load_runbook_from_manifest(
         &cmd.manifest_path,
         &cmd.runbook,
         &cmd.environment,
+        &cmd.input_files,
         &cmd.inputs,
         buffer_stdin.clone(),
+        is_json_output,
     )
     .await;
     let (runbook_name, mut runbook, runbook_state_location) = match res {
@@ -529,12 +653,41 @@ pub async fn handle_run_command(
             (runbook_name, runbook, state_file_location)
         }
         Err(_) => {
-            let (runbook_name, runbook) =
-                load_runbook_from_file_path(&cmd.runbook, &cmd.inputs, buffer_stdin).await?;
+            let res = load_runbook_from_file_path(
+                &cmd.runbook,
+                &cmd.input_files,
+                &cmd.inputs,
+                buffer_stdin,
+                is_json_output,
+            )
+            .await;
+            let (runbook_name, runbook) = match res {
+                Ok(res) => res,
+                Err(e) if is_json_output => {
+                    print_json_report(
+                        "failure",
+                        cmd.environment.clone(),
+                        &vec![Diagnostic::error_from_string(e.clone())],
+                    )?;
+                    return Err(CliError::configuration(e));
+                }
+                Err(e) => return Err(CliError::configuration(e)),
+            };
             (runbook_name, runbook, None)
         }
     };
 
+    let has_several_environments = runbook.get_inputs_selectors().len() > 1;
+    if has_several_environments && cmd.do_pick_environment_and_inputs() {
+        if !pick_environment_and_inputs(&runbook_name, &mut runbook).await? {
+            return Err(CliError::cancelled());
+        }
+    }
+
+    if cmd.print_inputs {
+        display_inputs(&runbook, is_json_output);
+    }
+
     let previous_state_opt = if let Some(state_file_location) = runbook_state_location.clone() {
         match state_file_location.load_execution_snapshot(
             true,
@@ -543,7 +696,7 @@ pub async fn handle_run_command(
         ) {
             Ok(snapshot) => Some(snapshot),
             Err(e) => {
-                println!("{} {}", red!("x"), e);
+                human_println!(is_json_output, "{} {}", red!("x"), e);
                 None
             }
         }
@@ -552,6 +705,7 @@ pub async fn handle_run_command(
     };
 
     runbook.enable_full_execution_mode();
+    runbook.deny_warnings = cmd.deny_warnings;
 
     if !cmd.force_execution {
         if let Some(old) = previous_state_opt {
@@ -562,7 +716,8 @@ pub async fn handle_run_command(
 
             for flow_context in runbook.flow_contexts.iter() {
                 if old.flows.get(&flow_context.name).is_none() {
-                    println!(
+                    human_println!(
+                        is_json_output,
                         "{} Previous snapshot not found for flow {}",
                         yellow!("!"),
                         flow_context.name
@@ -573,12 +728,36 @@ pub async fn handle_run_command(
             let consolidated_changes = match ctx.diff(old, new) {
                 Ok(changes) => changes,
                 Err(e) => {
-                    println!("{} Failed to process snapshot: {}", red!("x"), e);
+                    human_println!(
+                        is_json_output,
+                        "{} Failed to process snapshot: {}",
+                        red!("x"),
+                        e
+                    );
+                    if is_json_output {
+                        let message = format!("failed to process snapshot: {}", e);
+                        print_json_report(
+                            "failure",
+                            Some(runbook.top_level_inputs_map.current_top_level_input_name()),
+                            &vec![Diagnostic::error_from_string(message.clone())],
+                        )?;
+                        return Err(CliError::from(message));
+                    }
                     return Ok(());
                 }
             };
 
-            let Some(consolidated_changes) = display_snapshot_diffing(consolidated_changes) else {
+            let Some(consolidated_changes) =
+                display_snapshot_diffing(consolidated_changes, is_json_output)
+            else {
+                // the latest execution is up to date, there's nothing to execute
+                if is_json_output {
+                    print_json_report(
+                        "unchanged",
+                        Some(runbook.top_level_inputs_map.current_top_level_input_name()),
+                        &vec![],
+                    )?;
+                }
                 return Ok(());
             };
 
@@ -593,37 +772,39 @@ pub async fn handle_run_command(
             let has_actions_to_re_execute =
                 actions_to_re_execute.iter().filter(|(_, actions)| !actions.is_empty()).count() > 0;
             if has_actions_to_re_execute {
-                println!("The following actions will be re-executed:");
+                human_println!(is_json_output, "The following actions will be re-executed:");
                 for (context, actions) in actions_to_re_execute.iter() {
                     let documentation_missing = black!("<description field empty>");
-                    println!("\n{}", yellow!(format!("{}", context)));
+                    human_println!(is_json_output, "\n{}", yellow!(format!("{}", context)));
                     for (action_name, documentation) in actions.into_iter() {
-                        println!(
+                        human_println!(
+                            is_json_output,
                             "- {}: {}",
                             action_name,
                             documentation.as_ref().unwrap_or(&documentation_missing)
                         );
                     }
                 }
-                println!("\n");
+                human_println!(is_json_output, "\n");
             }
 
             let has_actions_to_execute_count =
                 actions_to_execute.iter().filter(|(_, actions)| !actions.is_empty()).count() > 0;
             if has_actions_to_execute_count {
-                println!("The following actions have been added and will be executed for the first time:");
+                human_println!(is_json_output, "The following actions have been added and will be executed for the first time:");
                 for (context, actions) in actions_to_execute.iter() {
                     let documentation_missing = black!("<description field empty>");
-                    println!("\n{}", green!(format!("{}", context)));
+                    human_println!(is_json_output, "\n{}", green!(format!("{}", context)));
                     for (action_name, documentation) in actions.into_iter() {
-                        println!(
+                        human_println!(
+                            is_json_output,
                             "- {}: {}",
                             action_name,
                             documentation.as_ref().unwrap_or(&documentation_missing)
                         );
                     }
                 }
-                println!("\n");
+                human_println!(is_json_output, "\n");
             }
 
             if has_actions_to_execute_count || has_actions_to_re_execute {
@@ -638,20 +819,28 @@ pub async fn handle_run_command(
                     .unwrap();
 
                 if !confirm {
-                    return Ok(());
+                    if is_json_output {
+                        print_json_report(
+                            "cancelled",
+                            Some(runbook.top_level_inputs_map.current_top_level_input_name()),
+                            &vec![],
+                        )?;
+                    }
+                    return Err(CliError::cancelled());
                 }
             }
         }
-    } else {
-        println!(
+    } else if !cmd.quiet {
+        let message = format!(
             "{} Executing Runbook with 'force' flag - ignoring previous execution state",
             yellow!("→"),
         );
+        human_println!(is_json_output, "{message}");
     }
 
     if cmd.explain {
         for (location, _) in runbook.sources.tree.iter() {
-            println!("Loading {}", location);
+            human_println!(is_json_output, "Loading {}", location);
         }
         for running_context in runbook.flow_contexts.iter_mut() {
             // running_context.execution_context.simulate_inputs_execution(&runbook.runtime_context, &running_context.workspace_context);
@@ -662,50 +851,99 @@ pub async fn handle_run_command(
                 else {
                     continue;
                 };
-                println!("{}::{}", command_instance.specification.matcher, command_instance.name);
+                human_println!(
+                    is_json_output,
+                    "{}::{}",
+                    command_instance.specification.matcher,
+                    command_instance.name
+                );
             }
         }
         // return Ok(());
     }
 
-    setup_logger(runbook.to_instance_context(), &cmd.log_level).unwrap();
-    let log_filter: LogLevel = cmd.log_level.as_str().into();
+    setup_logger(runbook.to_instance_context(), cmd.log_level()).unwrap();
+    let log_filter: LogLevel = cmd.log_level().into();
 
     // should not be generating actions
     if is_execution_unsupervised {
+        let quiet = cmd.quiet;
+        let construct_store = Arc::new(std::sync::RwLock::new(ConstructStatusStore::new()));
+        let moved_construct_store = construct_store.clone();
+        let (display_done_tx, display_done_rx) = channel::bounded::<()>(1);
         let _ = hiro_system_kit::thread_named("Display background tasks logs").spawn(move || {
             let mut active_spinners: IndexMap<Uuid, ProgressBar> = IndexMap::new();
             let mut multi_progress = MultiProgress::new();
 
             while let Ok(msg) = progress_rx.recv() {
                 match msg {
+                    BlockEvent::LogEvent(_) if quiet => {}
+                    // stdout is reserved to the json report
+                    BlockEvent::LogEvent(log) if is_json_output => {
+                        if log_filter.should_log(&log.level()) {
+                            eprintln!("{} - {}", log.summary(), log.message());
+                        }
+                    }
                     BlockEvent::LogEvent(log) => handle_log_event(
                         &mut multi_progress,
                         log,
                         &log_filter,
                         &mut active_spinners,
                     ),
+                    BlockEvent::UpdateConstructStatuses(updates) => {
+                        if let Ok(mut construct_store) = moved_construct_store.write() {
+                            for update in updates.into_iter() {
+                                construct_store.apply(update);
+                            }
+                        }
+                    }
+                    BlockEvent::Heartbeat(heartbeat) => {
+                        if !quiet && !is_json_output {
+                            handle_heartbeat_event(&heartbeat, &active_spinners);
+                        }
+                        if let Ok(mut construct_store) = moved_construct_store.write() {
+                            construct_store.apply_heartbeat(&heartbeat);
+                        }
+                    }
                     _ => {}
                 }
             }
+            let _ = display_done_tx.send(());
         });
 
-        println!(
-            "{} Starting runbook '{}' execution in unsupervised mode",
-            purple!("→"),
-            runbook_name
-        );
+        if !quiet {
+            let message = format!(
+                "{} Starting runbook '{}' execution in unsupervised mode",
+                purple!("→"),
+                runbook_name
+            );
+            human_println!(is_json_output, "{message}");
+        }
 
         let res = start_unsupervised_runbook_runloop(&mut runbook, &progress_tx).await;
-        process_runbook_execution_output(
+
+        if is_json_output {
+            // let the pending progress events be processed before reporting the construct statuses
+            drop(progress_tx);
+            let _ = display_done_rx.recv_timeout(Duration::from_secs(1));
+            let construct_store =
+                construct_store.read().map(|store| store.clone()).unwrap_or_default();
+            return process_runbook_execution_json_output(
+                res,
+                &mut runbook,
+                runbook_state_location,
+                &construct_store,
+                quiet,
+            );
+        }
+
+        return process_runbook_execution_output(
             res,
             &mut runbook,
             runbook_state_location,
             &cmd.output_json,
             &cmd.output,
         );
-
-        return Ok(());
     }
 
     let (block_tx, block_rx) = channel::unbounded::<BlockEvent>();
@@ -713,8 +951,20 @@ pub async fn handle_run_command(
     let (log_broadcaster, _) = tokio::sync::broadcast::channel(5);
     let block_store = Arc::new(RwLock::new(BTreeMap::new()));
     let log_store = Arc::new(RwLock::new(Vec::new()));
+    let construct_store = Arc::new(RwLock::new(ConstructStatusStore::new()));
+    let completion_store = Arc::new(RwLock::new(None));
     let (kill_loops_tx, kill_loops_rx) = channel::bounded(1);
     let (action_item_events_tx, action_item_events_rx) = tokio::sync::broadcast::channel(32);
+    // when unattended, the action items are answered from the block store loop
+    let mut unattended_supervisor = match cmd.unattended {
+        true => Some((
+            unattended::UnattendedSupervisor::new(std::io::stdin().is_terminal()),
+            action_item_events_tx.clone(),
+            block_tx.clone(),
+            kill_loops_tx.clone(),
+        )),
+        false => None,
+    };
 
     #[cfg(feature = "supervisor_ui")]
     let runbook_description = runbook.description.clone();
@@ -735,23 +985,42 @@ pub async fn handle_run_command(
         }
         addons
     };
+    // supervised sessions are persisted next to the runbooks outputs, to be reviewed later on
+    #[cfg(feature = "supervisor_ui")]
+    let session_store = match runbook
+        .runtime_context
+        .authorization_context
+        .workspace_location
+        .get_parent_location()
+    {
+        Ok(FileLocation::FileSystem { path }) => {
+            Some(SessionStoreConfig::new(path.join("runs").join("sessions").join(&runbook_name)))
+        }
+        _ => None,
+    };
 
     let moved_block_tx = block_tx.clone();
     let moved_kill_loops_tx = kill_loops_tx.clone();
     let moved_runbook_state = runbook_state_location.clone();
     let output_json = cmd.output_json.clone();
     let output_filter = cmd.output.clone();
+    let execution_failure = Arc::new(std::sync::Mutex::new(None));
+    let moved_execution_failure = execution_failure.clone();
     let _ = hiro_system_kit::thread_named("Runbook Runloop").spawn(move || {
         let runloop_future =
             start_supervised_runbook_runloop(&mut runbook, moved_block_tx, action_item_events_rx);
 
-        process_runbook_execution_output(
+        if let Err(failure) = process_runbook_execution_output(
             hiro_system_kit::nestable_block_on(runloop_future),
             &mut runbook,
             moved_runbook_state,
             &output_json,
             &output_filter,
-        );
+        ) {
+            if let Ok(mut execution_failure) = moved_execution_failure.lock() {
+                *execution_failure = Some(failure);
+            }
+        }
 
         if let Err(_e) = moved_kill_loops_tx.send(true) {
             std::process::exit(1);
@@ -764,19 +1033,25 @@ pub async fn handle_run_command(
     let web_ui_handle: Option<ServerHandle> = if cmd.do_start_supervisor_ui() {
         use txtx_supervisor_ui::start_supervisor_ui;
         let (supervisor_events_tx, supervisor_events_rx) = channel::unbounded();
+        let (network_binding_port, port_fallback_range) = cmd.supervisor_port_binding();
         let web_ui_handle = start_supervisor_ui(
             runbook_name,
             runbook_description,
             supervisor_addon_data,
             block_store.clone(),
             log_store.clone(),
+            construct_store.clone(),
+            completion_store.clone(),
+            get_available_addons(),
             block_broadcaster.clone(),
             log_broadcaster.clone(),
             action_item_events_tx,
             moved_kill_loops_tx.clone(),
             &cmd.network_binding_ip_address,
-            cmd.network_binding_port,
+            network_binding_port,
+            port_fallback_range,
             supervisor_events_tx,
+            session_store,
         )
         .await
         .map_err(|e| format!("failed to start web console: {}", e))?;
@@ -799,20 +1074,27 @@ pub async fn handle_run_command(
         None
     };
 
+    let quiet = cmd.quiet;
     let block_store_handle = tokio::spawn(async move {
         let mut active_spinners: IndexMap<Uuid, ProgressBar> = IndexMap::new();
         let mut multi_progress = MultiProgress::new();
+        let mut unattended_failure = None;
         loop {
             if let Ok(mut block_event) = block_rx.try_recv() {
                 let mut block_store = block_store.write().await;
                 let mut do_propagate_event = true;
                 match block_event.clone() {
                     BlockEvent::Action(new_block) => {
+                        if !quiet {
+                            print_markdown_items(&new_block.panel);
+                        }
                         let len = block_store.len();
                         block_store.insert(len, new_block.clone());
                     }
                     BlockEvent::Clear => {
                         *block_store = BTreeMap::new();
+                        construct_store.write().await.clear();
+                        *completion_store.write().await = None;
                     }
                     BlockEvent::UpdateActionItems(updates) => {
                         // for action item updates, track if we actually changed anything before propagating the event
@@ -830,10 +1112,14 @@ pub async fn handle_run_command(
                         block_event = BlockEvent::UpdateActionItems(filtered_updates);
                     }
                     BlockEvent::Modal(new_block) => {
+                        if !quiet {
+                            print_markdown_items(&new_block.panel);
+                        }
                         let len = block_store.len();
                         block_store.insert(len, new_block.clone());
                     }
                     BlockEvent::RunbookCompleted(additional_info) => {
+                        *completion_store.write().await = Some(additional_info.clone());
                         for info in additional_info.into_iter() {
                             let events: Vec<LogEvent> = info.into();
                             for log_event in events.into_iter() {
@@ -852,27 +1138,73 @@ pub async fn handle_run_command(
                         block_store.insert(len, new_block.clone());
                     }
                     BlockEvent::LogEvent(log_event) => {
-                        handle_log_event(
-                            &mut multi_progress,
-                            log_event.clone(),
-                            &log_filter,
-                            &mut active_spinners,
-                        );
+                        if !quiet {
+                            handle_log_event(
+                                &mut multi_progress,
+                                log_event.clone(),
+                                &log_filter,
+                                &mut active_spinners,
+                            );
+                        }
                         let mut log_store = log_store.write().await;
                         log_store.push(log_event.clone());
                         let _ = log_broadcaster.send(log_event);
                     }
+                    BlockEvent::UpdateConstructStatuses(updates) => {
+                        let mut construct_store = construct_store.write().await;
+                        for update in updates.into_iter() {
+                            construct_store.apply(update);
+                        }
+                    }
+                    BlockEvent::Heartbeat(heartbeat) => {
+                        if !quiet {
+                            handle_heartbeat_event(&heartbeat, &active_spinners);
+                        }
+                        construct_store.write().await.apply_heartbeat(&heartbeat);
+                    }
                     BlockEvent::Exit => break,
                 }
 
                 if do_propagate_event {
                     let _ = block_broadcaster.send(block_event.clone());
                 }
+
+                if let Some((supervisor, action_item_events_tx, block_tx, kill_loops_tx)) =
+                    unattended_supervisor.as_mut()
+                {
+                    let has_new_action_items = matches!(
+                        block_event,
+                        BlockEvent::Action(_)
+                            | BlockEvent::Modal(_)
+                            | BlockEvent::UpdateActionItems(_)
+                    );
+                    if has_new_action_items && unattended_failure.is_none() {
+                        match supervisor.next_responses(&block_store) {
+                            Ok(responses) => {
+                                for (response, description) in responses.into_iter() {
+                                    let _ = block_tx.send(BlockEvent::static_log(
+                                        LogLevel::Info,
+                                        Uuid::new_v4(),
+                                        "txtx".to_string(),
+                                        "Auto-approved",
+                                        description,
+                                    ));
+                                    let _ = action_item_events_tx.send(response);
+                                }
+                            }
+                            Err(reason) => {
+                                unattended_failure = Some(reason);
+                                let _ = kill_loops_tx.send(true);
+                            }
+                        }
+                    }
+                }
             }
 
             tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
             // println!("waiting for next block event");
         }
+        unattended_failure
     });
 
     let _ = hiro_system_kit::thread_named("Kill Runloops Thread")
@@ -895,13 +1227,25 @@ pub async fn handle_run_command(
         })
         .unwrap();
 
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let moved_cancelled = cancelled.clone();
     ctrlc::set_handler(move || {
+        moved_cancelled.store(true, Ordering::SeqCst);
         if let Err(_e) = kill_loops_tx.send(true) {
-            std::process::exit(1);
+            ExitCode::Cancelled.exit();
         }
     })
     .expect("Error setting Ctrl-C handler");
-    let _ = tokio::join!(block_store_handle);
+    let (unattended_failure,) = tokio::join!(block_store_handle);
+    if let Ok(Some(reason)) = unattended_failure {
+        return Err(format!("unattended execution aborted: {}", reason).into());
+    }
+    if let Some(failure) = execution_failure.lock().ok().and_then(|mut failure| failure.take()) {
+        return Err(failure);
+    }
+    if cancelled.load(Ordering::SeqCst) {
+        return Err(CliError::cancelled());
+    }
     Ok(())
 }
 
@@ -916,10 +1260,11 @@ pub async fn load_runbooks_from_manifest(
     manifest: &WorkspaceManifest,
     manifest_path: &str,
     environment_selector: &Option<String>,
+    is_json_output: bool,
 ) -> Result<IndexMap<String, (Runbook, RunbookSources, String, Option<RunbookStateLocation>)>, String>
 {
     let runbooks = read_runbooks_from_manifest(&manifest, environment_selector, None)?;
-    println!("\n{} Processing manifest '{}'", purple!("→"), manifest_path);
+    human_println!(is_json_output, "\n{} Processing manifest '{}'", purple!("→"), manifest_path);
     Ok(runbooks)
 }
 
@@ -927,18 +1272,25 @@ pub async fn load_runbook_from_manifest(
     manifest_path: &str,
     desired_runbook_name: &str,
     environment_selector: &Option<String>,
+    input_files: &Vec<String>,
     cli_inputs: &Vec<String>,
     buffer_stdin: Option<String>,
+    is_json_output: bool,
 ) -> Result<(WorkspaceManifest, String, Runbook, Option<RunbookStateLocation>), String> {
     let manifest = load_workspace_manifest_from_manifest_path(manifest_path)?;
     let top_level_inputs_map =
-        manifest.get_runbook_inputs(environment_selector, cli_inputs, buffer_stdin)?;
+        manifest.get_runbook_inputs(environment_selector, input_files, cli_inputs, buffer_stdin)?;
 
     let environment_selector =
         environment_selector.clone().or(manifest.environments.first().map(|(k, _)| k.clone()));
 
-    let runbooks =
-        load_runbooks_from_manifest(&manifest, manifest_path, &environment_selector).await?;
+    let runbooks = load_runbooks_from_manifest(
+        &manifest,
+        manifest_path,
+        &environment_selector,
+        is_json_output,
+    )
+    .await?;
     // Select first runbook by default
     for (runbook_id, (mut runbook, runbook_sources, runbook_name, runbook_state)) in
         runbooks.into_iter()
@@ -959,10 +1311,7 @@ pub async fn load_runbook_from_manifest(
                 )
                 .await;
             if let Err(diags) = res {
-                for diag in diags.iter() {
-                    println!("{} {}", red!("x"), diag);
-                }
-                std::process::exit(1);
+                exit_with_validation_failure(&diags, environment_selector.clone(), is_json_output);
             }
             return Ok((manifest, runbook_name, runbook, runbook_state));
         }
@@ -970,17 +1319,61 @@ pub async fn load_runbook_from_manifest(
     Err(format!("unable to retrieve runbook '{}' in manifest", desired_runbook_name))
 }
 
+/// Builds the contexts of a runbook indexed in the manifest, for commands inspecting runbooks
+/// without executing them. Unlike `load_runbook_from_manifest`, failures are returned.
+async fn build_runbook_contexts(
+    manifest_path: &str,
+    desired_runbook_name: &str,
+    environment_selector: &Option<String>,
+    input_files: &Vec<String>,
+    cli_inputs: &Vec<String>,
+    buffer_stdin: Option<String>,
+) -> Result<(String, Runbook), String> {
+    let manifest = load_workspace_manifest_from_manifest_path(manifest_path)?;
+    let top_level_inputs_map =
+        manifest.get_runbook_inputs(environment_selector, input_files, cli_inputs, buffer_stdin)?;
+    let environment_selector =
+        environment_selector.clone().or(manifest.environments.first().map(|(k, _)| k.clone()));
+
+    let runbooks = read_runbooks_from_manifest(&manifest, &environment_selector, None)?;
+    let Some((_, (mut runbook, runbook_sources, runbook_name, _))) =
+        runbooks.into_iter().find(|(runbook_id, (_, _, runbook_name, _))| {
+            runbook_name.eq(desired_runbook_name) || runbook_id.eq(desired_runbook_name)
+        })
+    else {
+        return Err(format!("unable to retrieve runbook '{}' in manifest", desired_runbook_name));
+    };
+
+    let authorization_context = AuthorizationContext::new(manifest.location.clone().unwrap());
+    runbook
+        .build_contexts_from_sources(
+            runbook_sources,
+            top_level_inputs_map,
+            authorization_context,
+            get_addon_by_namespace,
+            CloudServiceContext::new(),
+        )
+        .await
+        .map_err(|diags| {
+            diags.iter().map(|diag| diag.to_string()).collect::<Vec<_>>().join("\n")
+        })?;
+    Ok((runbook_name, runbook))
+}
+
 pub async fn load_runbook_from_file_path(
     file_path: &str,
+    input_files: &Vec<String>,
     cli_inputs: &Vec<String>,
     buffer_stdin: Option<String>,
+    is_json_output: bool,
 ) -> Result<(String, Runbook), String> {
     let location = FileLocation::from_path_string(file_path)?;
     let (runbook_name, mut runbook, runbook_sources) =
         read_runbook_from_location(&location, &None, &None, None)?;
 
-    println!("\n{} Processing file '{}'", purple!("→"), file_path);
+    human_println!(is_json_output, "\n{} Processing file '{}'", purple!("→"), file_path);
     let mut inputs_map = RunbookTopLevelInputsMap::new();
+    inputs_map.override_values_with_input_files(input_files, &buffer_stdin)?;
     inputs_map.override_values_with_cli_inputs(cli_inputs, buffer_stdin)?;
 
     let authorization_context = AuthorizationContext::new(location);
@@ -997,29 +1390,231 @@ pub async fn load_runbook_from_file_path(
         )
         .await;
     if let Err(diags) = res {
-        for diag in diags.iter() {
-            println!("{} {}", red!("x"), diag);
-        }
-        std::process::exit(1);
+        exit_with_validation_failure(&diags, None, is_json_output);
     }
 
-    println!("{} '{}' successfully checked", green!("✓"), runbook_name);
+    human_println!(is_json_output, "{} '{}' successfully checked", green!("✓"), runbook_name);
 
     // Select first runbook by default
     Ok((runbook_name, runbook))
 }
 
+/// Input names hinting at a secret, whose values are never printed.
+const SENSITIVE_INPUT_NAME_HINTS: &[&str] =
+    &["secret", "private", "mnemonic", "password", "passphrase", "token", "seed", "api_key"];
+
+fn is_sensitive_input(name: &str) -> bool {
+    let lowercased_name = name.to_lowercase();
+    SENSITIVE_INPUT_NAME_HINTS.iter().any(|hint| lowercased_name.contains(hint))
+}
+
+fn display_input_value(name: &str, value: &Value, secrets: &SecretRegistry) -> String {
+    if is_sensitive_input(name) {
+        REDACTED.to_string()
+    } else {
+        secrets.redact(&value.to_json(None).to_string())
+    }
+}
+
+/// Prints the inputs of the current environment, once merged with the input files and the cli
+/// inputs. In JSON mode, stdout is reserved to the execution report.
+fn display_inputs(runbook: &Runbook, is_json_output: bool) {
+    let inputs_map = &runbook.top_level_inputs_map;
+    let mut lines = vec![format!(
+        "{} Inputs of environment '{}'",
+        purple!("→"),
+        inputs_map.current_top_level_input_name()
+    )];
+    for (name, value) in inputs_map.current_values().iter() {
+        lines.push(format!(
+            "  {} = {}",
+            name,
+            display_input_value(name, value, &runbook.runtime_context.secrets)
+        ));
+    }
+    if is_json_output {
+        eprintln!("{}", lines.join("\n"));
+    } else {
+        println!("{}", lines.join("\n"));
+    }
+}
+
+/// Lets the operator pick the environment, then review and edit the inputs before the run. The
+/// selections are applied as `--env` and `--input` would be. Returns false if the run is cancelled.
+async fn pick_environment_and_inputs(
+    runbook_name: &str,
+    runbook: &mut Runbook,
+) -> Result<bool, String> {
+    let diags_to_string =
+        |diags: Vec<Diagnostic>| diags.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("\n");
+
+    let environment = picker::pick_environment(
+        &runbook.get_inputs_selectors(),
+        &runbook.get_active_inputs_selector(),
+    )?;
+    runbook
+        .update_inputs_selector(Some(environment.clone()), false)
+        .await
+        .map_err(diags_to_string)?;
+
+    let mut inputs = runbook
+        .top_level_inputs_map
+        .current_values()
+        .into_iter()
+        .map(|(name, value)| PickedInput {
+            displayed_value: display_input_value(&name, &value, &runbook.runtime_context.secrets),
+            value: value.to_string(),
+            is_sensitive: is_sensitive_input(&name),
+            name,
+        })
+        .collect::<Vec<_>>();
+    let Some(edits) = picker::review_inputs(runbook_name, &environment, &mut inputs)? else {
+        return Ok(false);
+    };
+    if !edits.is_empty() {
+        runbook.top_level_inputs_map.override_values_with_cli_inputs(&edits, None)?;
+        runbook.update_inputs_selector(Some(environment), true).await.map_err(diags_to_string)?;
+    }
+    Ok(true)
+}
+
+/// Writes the state of the runbook, then prints a single JSON report of the execution to stdout:
+/// its status, the statuses and durations of the constructs of each flow, the outputs and the
+/// diagnostics. Returns an error if the execution failed, for the exit code to reflect it.
+fn process_runbook_execution_json_output(
+    execution_result: Result<(), Vec<Diagnostic>>,
+    runbook: &mut Runbook,
+    runbook_state_location: Option<RunbookStateLocation>,
+    construct_store: &ConstructStatusStore,
+    quiet: bool,
+) -> Result<(), CliError> {
+    let state_result = match execution_result {
+        Err(_) => runbook.mark_failed_and_write_transient_state(runbook_state_location),
+        Ok(_) => runbook.write_runbook_state(runbook_state_location),
+    };
+    if !quiet {
+        match state_result {
+            Ok(Some(location)) => {
+                eprintln!("{} Saved execution state to {}", green!("✓"), location)
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("{} Failed to write runbook state: {}", red!("x"), e),
+        }
+    }
+
+    let converters = runbook
+        .runtime_context
+        .addons_context
+        .registered_addons
+        .values()
+        .map(|(addon, _)| Box::new(move |value: &Value| addon.to_json(value)) as AddonJsonConverter)
+        .collect::<Vec<_>>();
+
+    let mut flows = json!({});
+    for flow_context in runbook.flow_contexts.iter() {
+        let execution_context = &flow_context.execution_context;
+        let constructs = construct_store
+            .entries()
+            .filter(|entry| {
+                let construct_did = &entry.construct.construct_did;
+                execution_context.commands_instances.contains_key(construct_did)
+                    || execution_context.signers_instances.contains_key(construct_did)
+            })
+            .map(|entry| {
+                json!({
+                    "id": entry.construct.construct_did.to_string(),
+                    "constructType": entry.construct.construct_type,
+                    "name": entry.construct.name,
+                    "status": entry.construct.status,
+                    "durationMs": entry.duration.map(|d| d.as_millis() as u64),
+                })
+            })
+            .collect::<Vec<_>>();
+        flows[&flow_context.name] = json!({ "constructs": constructs });
+    }
+
+    let (status, diagnostics) = match &execution_result {
+        Ok(_) => ("success", vec![]),
+        Err(diags) => ("failure", diags.clone()),
+    };
+    let report = json!({
+        "status": status,
+        "environment": runbook.top_level_inputs_map.current_top_level_input_name(),
+        "flows": flows,
+        "outputs": runbook.collect_formatted_outputs().to_json(&converters),
+        "diagnostics": diagnostics,
+        "warnings": runbook.warnings,
+        "deprecations": runbook.deprecations,
+    });
+    println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+
+    match execution_result {
+        Ok(_) => Ok(()),
+        Err(diags) => Err(CliError::from_diagnostics(
+            format!("runbook '{}' execution failed", runbook.runbook_id.name),
+            &diags,
+        )),
+    }
+}
+
+/// Prints the JSON report of a run that ended before the runbook was executed.
+fn print_json_report(
+    status: &str,
+    environment: Option<String>,
+    diagnostics: &Vec<Diagnostic>,
+) -> Result<(), CliError> {
+    let report = json!({
+        "status": status,
+        "environment": environment,
+        "flows": {},
+        "outputs": {},
+        "diagnostics": diagnostics,
+        "warnings": [],
+        "deprecations": [],
+    });
+    println!("{}", serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?);
+    Ok(())
+}
+
+/// Reports the diagnostics of a runbook that failed to build, along with a failure report in
+/// JSON mode, and exits.
+fn exit_with_validation_failure(
+    diags: &Vec<Diagnostic>,
+    environment: Option<String>,
+    is_json_output: bool,
+) -> ! {
+    display_diagnostics(diags, is_json_output);
+    if is_json_output {
+        let _ = print_json_report("failure", environment, diags);
+    }
+    ExitCode::from_diagnostics(diags).exit()
+}
+
+/// Prints the diagnostics, marking errors apart from warnings and notes.
+fn display_diagnostics(diags: &Vec<Diagnostic>, is_json_output: bool) {
+    for diag in diags.iter() {
+        let marker = if diag.is_error() { red!("x") } else { yellow!("!") };
+        human_println!(is_json_output, "{} {}", marker, diag);
+        if let Some(help_url) = &diag.help_url {
+            human_println!(is_json_output, "\thelp: {}", help_url);
+        }
+    }
+}
+
 fn process_runbook_execution_output(
     execution_result: Result<(), Vec<Diagnostic>>,
     runbook: &mut Runbook,
     runbook_state_location: Option<RunbookStateLocation>,
     output_json: &Option<Option<String>>,
     output_filter: &Option<String>,
-) {
+) -> Result<(), CliError> {
     if let Err(diags) = execution_result {
-        for diag in diags.iter() {
-            println!("{} {}", red!("x"), diag);
-        }
+        display_diagnostics(&diags, false);
+        println!(
+            "\n{} error(s), {} warning(s)",
+            diags.iter().filter(|d| d.is_error()).count(),
+            runbook.warnings.len() + diags.iter().filter(|d| !d.is_error()).count()
+        );
         match runbook.mark_failed_and_write_transient_state(runbook_state_location) {
             Ok(Some(location)) => {
                 println!("{} Saving transient state to {}", yellow!("!"), location);
@@ -1029,7 +1624,18 @@ fn process_runbook_execution_output(
                 println!("{} Failed to write transient runbook state: {}", red!("x"), e);
             }
         };
+        return Err(CliError::from_diagnostics(
+            format!("runbook '{}' execution failed", runbook.runbook_id.name),
+            &diags,
+        ));
     } else {
+        if !runbook.warnings.is_empty() {
+            println!(
+                "{} Execution completed with {} warning(s)",
+                yellow!("!"),
+                runbook.warnings.len()
+            );
+        }
         let runbook_outputs = runbook.collect_formatted_outputs();
 
         let converters = runbook
@@ -1072,7 +1678,10 @@ fn process_runbook_execution_output(
                     );
                 }
             } else {
-                for (flow_name, data) in runbook_outputs.get_output_row_data(&output_filter) {
+                let formatters = runbook.runtime_context.display_formatters();
+                for (flow_name, data) in
+                    runbook_outputs.get_output_row_data(&output_filter, &formatters)
+                {
                     println!("{}", yellow!(format!("{} Outputs: ", flow_name)));
                     let mut ascii_table = AsciiTable::default();
                     ascii_table.set_max_width(150);
@@ -1091,6 +1700,7 @@ fn process_runbook_execution_output(
             }
         };
     }
+    Ok(())
 }
 
 fn setup_logger(
@@ -1187,6 +1797,55 @@ fn persist_log(
     }
 }
 
+/// Suffix of a pending status line rendering the structured progress of the operation, if any.
+fn format_progress(progress: &Option<LogProgress>) -> String {
+    match progress.as_ref().map(|p| p.to_string()) {
+        Some(progress) if !progress.is_empty() => format!(" [{}]", progress),
+        _ => String::new(),
+    }
+}
+
+/// Prints the markdown displayed by the action items of a panel as plain text, for operators
+/// following the run from the terminal.
+fn print_markdown_items(panel: &Panel) {
+    let groups = match panel {
+        Panel::ActionPanel(data) => &data.groups,
+        Panel::ModalPanel(data) => &data.groups,
+        Panel::ErrorPanel(_) => return,
+    };
+    let action_items = groups
+        .iter()
+        .flat_map(|group| group.sub_groups.iter())
+        .flat_map(|sub_group| sub_group.action_items.iter());
+    for item in action_items {
+        if let Some(request) = item.action_type.as_display_markdown() {
+            println!(
+                "\n{} {}\n{}",
+                purple!("→"),
+                item.construct_instance_name,
+                request.to_plain_text()
+            );
+        }
+    }
+}
+
+/// Shows when a background task last sent a heartbeat next to its spinner. Background tasks log
+/// their progress under the uuid of their construct.
+fn handle_heartbeat_event(
+    heartbeat: &HeartbeatEvent,
+    active_spinners: &IndexMap<Uuid, ProgressBar>,
+) {
+    let Some(pb) = active_spinners.get(&heartbeat.construct_did.as_uuid()) else {
+        return;
+    };
+    // hidden spinners pace the status lines printed when stdout is not a terminal
+    if pb.is_hidden() {
+        return;
+    }
+    pb.set_style(CLI_HEARTBEAT_SPINNER_STYLE.clone());
+    pb.reset_elapsed();
+}
+
 fn handle_log_event(
     multi_progress: &mut MultiProgress,
     log: LogEvent,
@@ -1206,36 +1865,55 @@ fn handle_log_event(
             );
         }
         LogEvent::Transient(log) => match log.status {
+            TransientLogEventStatus::Pending(LogDetails { message, summary })
+                if !atty::is(atty::Stream::Stdout) =>
+            {
+                let is_new = !active_spinners.contains_key(&log.uuid);
+                // the position of the hidden progress bar counts the status lines printed
+                let pb = active_spinners.entry(log.uuid).or_insert_with(ProgressBar::hidden);
+                if pb.elapsed() >= STATUS_LINE_INTERVAL * pb.position() as u32 {
+                    println!(
+                        "{} {} {}{}",
+                        yellow!("…"),
+                        yellow!("{}", summary),
+                        message,
+                        format_progress(&log.progress)
+                    );
+                    pb.inc(1);
+                }
+                if is_new {
+                    persist_log(&message, &summary, &log.namespace, &log.level, &log_filter, false);
+                }
+            }
             TransientLogEventStatus::Pending(LogDetails { message, summary }) => {
+                let progress = format_progress(&log.progress);
                 if let Some(pb) = active_spinners.get(&log.uuid) {
                     // update existing spinner
-                    pb.set_message(format!("{} {}", yellow!(&summary), &message));
+                    pb.set_message(format!("{} {}{}", yellow!(&summary), &message, progress));
                 } else {
                     // create new spinner
                     let pb = multi_progress.add(ProgressBar::new_spinner());
                     pb.set_style(CLI_SPINNER_STYLE.clone());
                     pb.enable_steady_tick(Duration::from_millis(80));
-                    pb.set_message(format!("{} {}", yellow!(&summary), message));
+                    pb.set_message(format!("{} {}{}", yellow!(&summary), message, progress));
                     active_spinners.insert(log.uuid, pb);
                     persist_log(&message, &summary, &log.namespace, &log.level, &log_filter, false);
                 }
             }
             TransientLogEventStatus::Success(LogDetails { summary, message }) => {
                 let msg = format!("{} {} {}", green!("✓"), green!(&summary), message);
-                if let Some(pb) = active_spinners.swap_remove(&log.uuid) {
-                    pb.finish_with_message(msg);
-                } else {
-                    println!("{}", msg);
+                match active_spinners.swap_remove(&log.uuid) {
+                    Some(pb) if !pb.is_hidden() => pb.finish_with_message(msg),
+                    _ => println!("{}", msg),
                 }
 
                 persist_log(&message, &summary, &log.namespace, &log.level, &log_filter, false);
             }
             TransientLogEventStatus::Failure(LogDetails { summary, message }) => {
                 let msg = format!("{} {}: {}", red!("x"), red!(&summary), message);
-                if let Some(pb) = active_spinners.swap_remove(&log.uuid) {
-                    pb.finish_with_message(msg);
-                } else {
-                    println!("{}", msg);
+                match active_spinners.swap_remove(&log.uuid) {
+                    Some(pb) if !pb.is_hidden() => pb.finish_with_message(msg),
+                    _ => println!("{}", msg),
                 }
                 persist_log(&message, &summary, &log.namespace, &log.level, &log_filter, false);
             }
@@ -0,0 +1,308 @@
+use std::env;
+use std::fs::{self, File};
+use std::path::PathBuf;
+
+use console::Style;
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+use txtx_addon_network_evm::templates as evm_templates;
+use txtx_addon_network_svm::templates as svm_templates;
+use txtx_core::kit::helpers::fs::FileLocation;
+use txtx_core::kit::indexmap::IndexMap;
+use txtx_core::manifest::{RunbookMetadata, WorkspaceManifest};
+use txtx_core::mustache;
+use txtx_core::templates::{build_manifest_data, TXTX_MANIFEST_TEMPLATE, TXTX_README_TEMPLATE};
+
+use crate::cli::CreateRunbook;
+
+/// The well-known first account of the anvil / hardhat local nodes.
+const LOCALNET_EVM_SECRET_KEY: &str =
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+/// The contract created by `forge init`.
+const DEFAULT_FOUNDRY_CONTRACT: &str = "Counter";
+
+pub struct TemplateNetwork {
+    pub name: &'static str,
+    /// The inputs of the environment created for the network
+    pub inputs: &'static [(&'static str, &'static str)],
+}
+
+/// A runbook scaffolded by `txtx new <template>`, assembled from the templates module of its
+/// addon.
+pub struct RunbookTemplate {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub networks: &'static [TemplateNetwork],
+    /// Renders the runbook for a project name and a network
+    pub render: fn(&str, &str) -> String,
+}
+
+pub const RUNBOOK_TEMPLATES: &[RunbookTemplate] = &[
+    RunbookTemplate {
+        name: "evm-deploy",
+        description: "Deploy a contract from a Foundry project",
+        networks: &[
+            TemplateNetwork {
+                name: "localnet",
+                inputs: &[
+                    ("chain_id", "31337"),
+                    ("rpc_api_url", "http://127.0.0.1:8545"),
+                    ("deployer_secret_key", LOCALNET_EVM_SECRET_KEY),
+                ],
+            },
+            TemplateNetwork {
+                name: "sepolia",
+                inputs: &[
+                    ("chain_id", "11155111"),
+                    ("rpc_api_url", "https://ethereum-sepolia-rpc.publicnode.com"),
+                ],
+            },
+            TemplateNetwork {
+                name: "mainnet",
+                inputs: &[
+                    ("chain_id", "1"),
+                    ("rpc_api_url", "https://ethereum-rpc.publicnode.com"),
+                ],
+            },
+        ],
+        render: render_evm_deploy,
+    },
+    RunbookTemplate {
+        name: "svm-anchor-deploy",
+        description: "Deploy a program from an Anchor project",
+        networks: &[
+            TemplateNetwork {
+                name: "localnet",
+                inputs: &[("network_id", "localnet"), ("rpc_api_url", "http://127.0.0.1:8899")],
+            },
+            TemplateNetwork {
+                name: "devnet",
+                inputs: &[
+                    ("network_id", "devnet"),
+                    ("rpc_api_url", "https://api.devnet.solana.com"),
+                ],
+            },
+            TemplateNetwork {
+                name: "mainnet",
+                inputs: &[
+                    ("network_id", "mainnet-beta"),
+                    ("rpc_api_url", "https://api.mainnet-beta.solana.com"),
+                ],
+            },
+        ],
+        render: render_svm_anchor_deploy,
+    },
+];
+
+fn render_evm_deploy(_project_name: &str, network: &str) -> String {
+    let signer = match network {
+        "localnet" => {
+            evm_templates::get_interpolated_localnet_signer_template("input.deployer_secret_key")
+        }
+        _ => evm_templates::get_interpolated_web_wallet_signer_template(),
+    };
+    [
+        evm_templates::get_interpolated_header_template("Signers"),
+        evm_templates::get_interpolated_addon_template("input.rpc_api_url", "input.chain_id"),
+        signer,
+        evm_templates::get_interpolated_header_template("Deployments"),
+        evm_templates::get_interpolated_foundry_contract_deployment_template(
+            DEFAULT_FOUNDRY_CONTRACT,
+        ),
+    ]
+    .join("\n")
+}
+
+fn render_svm_anchor_deploy(project_name: &str, network: &str) -> String {
+    // anchor names the program of a new project after the project
+    let program_name = project_name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>();
+    let signer = match network {
+        "localnet" => {
+            svm_templates::get_interpolated_localnet_signer_template("\"~/.config/solana/id.json\"")
+        }
+        "devnet" => svm_templates::get_interpolated_devnet_signer_template(),
+        _ => svm_templates::get_interpolated_mainnet_signer_template(""),
+    };
+    [
+        svm_templates::get_interpolated_header_template("Signers"),
+        svm_templates::get_interpolated_addon_template("input.rpc_api_url", "input.network_id"),
+        signer,
+        svm_templates::get_interpolated_header_template("Deployments"),
+        svm_templates::get_interpolated_anchor_program_deployment_template(&program_name),
+    ]
+    .join("\n")
+}
+
+fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Scaffolds a manifest, an environment and a commented runbook from `template_name`, prompting
+/// for the project name and the network when they're not provided.
+pub fn handle_new_from_template(cmd: &CreateRunbook, template_name: &str) -> Result<(), String> {
+    let Some(template) = RUNBOOK_TEMPLATES.iter().find(|t| t.name.eq(template_name)) else {
+        return Err(format!(
+            "unknown template '{}' (available templates: {})",
+            template_name,
+            RUNBOOK_TEMPLATES.iter().map(|t| t.name).collect::<Vec<_>>().join(", ")
+        ));
+    };
+
+    let theme = ColorfulTheme {
+        values_style: Style::new().green(),
+        hint_style: Style::new().cyan(),
+        ..ColorfulTheme::default()
+    };
+
+    let manifest_location = FileLocation::from_path_string(&cmd.manifest_path)?;
+    let existing_manifest = WorkspaceManifest::from_location(&manifest_location).ok();
+
+    let project_name = match (&cmd.name, &existing_manifest) {
+        (Some(name), _) => name.clone(),
+        (None, Some(manifest)) => manifest.name.clone(),
+        (None, None) => {
+            let default = env::current_dir()
+                .ok()
+                .and_then(|d| d.file_name().map(|f| f.to_string_lossy().to_string()))
+                .unwrap_or_default();
+            Input::with_theme(&theme)
+                .with_prompt("Enter the name of this project")
+                .default(default)
+                .interact_text()
+                .map_err(|e| e.to_string())?
+        }
+    };
+
+    let network = match &cmd.network {
+        Some(network) => template.networks.iter().find(|n| n.name.eq(network)).ok_or(format!(
+            "unknown network '{}' for template {} (available networks: {})",
+            network,
+            template.name,
+            template.networks.iter().map(|n| n.name).collect::<Vec<_>>().join(", ")
+        ))?,
+        None => {
+            let choices = template.networks.iter().map(|n| n.name).collect::<Vec<_>>();
+            let choice = Select::with_theme(&theme)
+                .with_prompt("Choose a network:")
+                .default(0)
+                .items(&choices)
+                .interact()
+                .map_err(|e| e.to_string())?;
+            &template.networks[choice]
+        }
+    };
+
+    let runbook_name = format!("deploy-{}", slugify(&project_name));
+    let mut manifest =
+        existing_manifest.unwrap_or_else(|| WorkspaceManifest::new(project_name.clone()));
+    if manifest.runbooks.iter().any(|r| r.name.eq(&runbook_name)) {
+        return Err(format!("runbook '{}' is already indexed in the manifest", runbook_name));
+    }
+    let runbook =
+        RunbookMetadata::new("deployments", &runbook_name, Some(template.description.to_string()));
+
+    let root_location_path = env::current_dir().expect("Failed to get current directory");
+    let runbook_file_path = root_location_path.join(&runbook.location);
+    if runbook_file_path.exists() {
+        return Err(format!(
+            "file {} already exists. choose a different project name, or rename the existing file",
+            runbook_file_path.display()
+        ));
+    }
+    manifest.runbooks.push(runbook.clone());
+    manifest.environments.entry(network.name.to_string()).or_insert_with(|| {
+        network
+            .inputs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<IndexMap<_, _>>()
+    });
+
+    // Write the manifest
+    let manifest_path = match manifest.location {
+        Some(ref location) => PathBuf::from(location.to_string()),
+        None => root_location_path.join("txtx.yml"),
+    };
+    let mut manifest_file = File::create(&manifest_path)
+        .map_err(|e| format!("unable to write {}: {}", manifest_path.display(), e))?;
+    mustache::compile_str(TXTX_MANIFEST_TEMPLATE)
+        .expect("Failed to compile template")
+        .render_data(&mut manifest_file, &build_manifest_data(&manifest))
+        .map_err(|e| format!("unable to write {}: {}", manifest_path.display(), e))?;
+    println!("{} {}", green!("Updated manifest"), manifest_path.display());
+
+    // Write the runbook, along with the runbooks README
+    let runbooks_dir = root_location_path.join("runbooks");
+    let readme_file_path = runbooks_dir.join("README.md");
+    if let Some(parent) = runbook_file_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            format!("unable to create parent directory {}\n{}", parent.display(), e)
+        })?;
+    }
+    if !readme_file_path.exists() {
+        let mut readme_file = File::create(&readme_file_path).expect("creation failed");
+        mustache::compile_str(TXTX_README_TEMPLATE)
+            .expect("Failed to compile template")
+            .render_data(&mut readme_file, &build_manifest_data(&manifest))
+            .expect("Failed to render template");
+        println!("{} runbooks/README.md", green!("Created file"));
+    }
+
+    let content = format!(
+        "// {}, generated with `txtx new {}`.\n// Access tutorials and documentation at [docs.txtx.sh](https://docs.txtx.sh)\n// to understand the syntax and discover the powerful features of txtx.\n\n{}",
+        template.description,
+        template.name,
+        (template.render)(&project_name, network.name)
+    );
+    fs::write(&runbook_file_path, content)
+        .map_err(|e| format!("unable to write {}: {}", runbook_file_path.display(), e))?;
+    println!("{} {}", green!("Created runbook"), runbook.location);
+    println!("Run it with `txtx run {} --env {}`", runbook_name, network.name);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::common::addon_registry;
+    use txtx_core::validation::hcl_validator::validate_with_hcl_and_addons;
+    use txtx_core::validation::ValidationResult;
+
+    #[test]
+    fn test_templates_pass_static_validation() {
+        let addons = addon_registry::get_all_addons();
+        let addon_specs = addon_registry::extract_addon_specifications(&addons);
+        for template in RUNBOOK_TEMPLATES.iter() {
+            for network in template.networks.iter() {
+                let content = (template.render)("My Project", network.name);
+                let mut result = ValidationResult::new();
+                let file_path = format!("{}-{}.tx", template.name, network.name);
+                validate_with_hcl_and_addons(
+                    &content,
+                    &mut result,
+                    &file_path,
+                    addon_specs.clone(),
+                )
+                .unwrap_or_else(|e| panic!("{file_path} can't be parsed: {e}"));
+                assert!(
+                    result.errors.is_empty(),
+                    "{file_path} is invalid: {:?}",
+                    result.errors.iter().map(|e| e.to_string()).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("My Project"), "my-project");
+        assert_eq!(slugify("  hello_sol!"), "hello-sol");
+    }
+}
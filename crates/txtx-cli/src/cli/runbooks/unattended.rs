@@ -0,0 +1,234 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use txtx_core::kit::types::block_id::BlockId;
+use txtx_core::kit::types::frontend::{
+    ActionItemRequest, ActionItemRequestType, ActionItemResponse, ActionItemResponseType,
+    ActionItemStatus, Block, Panel, ProvidedInputResponse, ReviewedInputResponse,
+};
+use txtx_core::kit::types::types::Value;
+
+use crate::term_ui::picker;
+
+/// Answers the action items of a supervised execution on behalf of the operator, for runs
+/// without a browser: inputs are reviewed with their current values, provided with their
+/// default values, options are picked with their current selection, and panels are validated
+/// once their items are answered. Items requiring a human (signatures, public keys, inputs
+/// without default values) make the run fail. When running in a terminal, sensitive inputs
+/// (keyfile passphrases...) are prompted for, without being echoed.
+pub struct UnattendedSupervisor {
+    answered_action_items: BTreeSet<BlockId>,
+    is_interactive: bool,
+}
+
+impl UnattendedSupervisor {
+    pub fn new(is_interactive: bool) -> Self {
+        UnattendedSupervisor { answered_action_items: BTreeSet::new(), is_interactive }
+    }
+
+    /// Returns the responses to the pending action items of the block store, along with a
+    /// description of what was approved, or an error naming the construct that needs a human.
+    pub fn next_responses(
+        &mut self,
+        block_store: &BTreeMap<usize, Block>,
+    ) -> Result<Vec<(ActionItemResponse, String)>, String> {
+        let mut responses = vec![];
+        for block in block_store.values() {
+            let groups = match &block.panel {
+                Panel::ActionPanel(data) => &data.groups,
+                Panel::ModalPanel(data) => &data.groups,
+                Panel::ErrorPanel(_) => continue,
+            };
+            let action_items = groups
+                .iter()
+                .flat_map(|group| group.sub_groups.iter())
+                .flat_map(|sub_group| sub_group.action_items.iter())
+                .filter(|item| !self.answered_action_items.contains(&item.id))
+                .filter(|item| item.action_type.expected_response_type().is_some())
+                .filter(|item| {
+                    matches!(item.action_status, ActionItemStatus::Todo | ActionItemStatus::Blocked)
+                })
+                .collect::<Vec<_>>();
+
+            let mut is_panel_answered = true;
+            let mut validations = vec![];
+            for item in action_items.into_iter() {
+                match &item.action_type {
+                    ActionItemRequestType::ValidateBlock(_)
+                    | ActionItemRequestType::ValidateModal => {
+                        validations.push(item);
+                        continue;
+                    }
+                    _ => {}
+                }
+                if let ActionItemStatus::Blocked = item.action_status {
+                    // blocked items are answered once unblocked, unless they require a human
+                    if let Some(reason) = requires_human(item, self.is_interactive) {
+                        return Err(reason);
+                    }
+                    is_panel_answered = false;
+                    continue;
+                }
+                responses.push(self.answer(item)?);
+            }
+
+            if is_panel_answered {
+                for item in validations.into_iter() {
+                    responses.push(self.answer(item)?);
+                }
+            }
+        }
+        Ok(responses)
+    }
+
+    fn answer(&mut self, item: &ActionItemRequest) -> Result<(ActionItemResponse, String), String> {
+        if let Some(reason) = requires_human(item, self.is_interactive) {
+            return Err(reason);
+        }
+        let construct = &item.construct_instance_name;
+        let (payload, description) = match &item.action_type {
+            ActionItemRequestType::ReviewInput(request) => (
+                ActionItemResponseType::ReviewInput(ReviewedInputResponse {
+                    input_name: request.input_name.clone(),
+                    value_checked: true,
+                    force_execution: request.force_execution,
+                }),
+                format!("input '{}' of {} reviewed", request.input_name, construct),
+            ),
+            ActionItemRequestType::ProvideInput(request) => match &request.default_value {
+                Some(default_value) => (
+                    ActionItemResponseType::ProvideInput(ProvidedInputResponse {
+                        input_name: request.input_name.clone(),
+                        updated_value: default_value.clone(),
+                    }),
+                    format!(
+                        "input '{}' of {} provided with its default value",
+                        request.input_name, construct
+                    ),
+                ),
+                // sensitive inputs, as checked by requires_human
+                None => {
+                    let value = picker::prompt_sensitive_input(&format!(
+                        "{} of {}",
+                        request.input_name, construct
+                    ))?;
+                    (
+                        ActionItemResponseType::ProvideInput(ProvidedInputResponse {
+                            input_name: request.input_name.clone(),
+                            updated_value: Value::string(value),
+                        }),
+                        format!("input '{}' of {} provided", request.input_name, construct),
+                    )
+                }
+            },
+            ActionItemRequestType::PickInputOption(request) => (
+                ActionItemResponseType::PickInputOption(request.selected.value.clone()),
+                format!("option '{}' picked for {}", request.selected.displayed_value, construct),
+            ),
+            ActionItemRequestType::ValidateBlock(_) => {
+                (ActionItemResponseType::ValidateBlock, "panel validated".to_string())
+            }
+            ActionItemRequestType::ValidateModal => {
+                (ActionItemResponseType::ValidateModal, "modal validated".to_string())
+            }
+            _ => unreachable!(),
+        };
+        self.answered_action_items.insert(item.id.clone());
+        Ok((ActionItemResponse { action_item_id: item.id.clone(), payload }, description))
+    }
+}
+
+/// Returns why an action item can't be answered without a human, if so. Sensitive inputs can be
+/// prompted for when `is_interactive`.
+fn requires_human(item: &ActionItemRequest, is_interactive: bool) -> Option<String> {
+    let construct = &item.construct_instance_name;
+    let reason = match &item.action_type {
+        ActionItemRequestType::ProvideInput(request)
+            if request.default_value.is_none() && !(request.sensitive && is_interactive) =>
+        {
+            format!("input '{}' has no default value", request.input_name)
+        }
+        ActionItemRequestType::ProvideFile(request) => {
+            format!("file '{}' must be uploaded", request.input_name)
+        }
+        ActionItemRequestType::ProvidePublicKey(_) => "a public key must be provided".into(),
+        ActionItemRequestType::ProvideSignedTransaction(_)
+        | ActionItemRequestType::ProvideSignedMessage(_)
+        | ActionItemRequestType::SendTransaction(_)
+        | ActionItemRequestType::VerifyThirdPartySignature(_) => "a signature is required".into(),
+        _ => return None,
+    };
+    Some(format!(
+        "{construct} requires a human operator ({reason}): use a non-interactive signer or provide the value as an input to run unattended"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use txtx_core::kit::types::frontend::{
+        ActionGroup, ActionSubGroup, ProvideInputRequest, ProvidePublicKeyRequest,
+        ReviewInputRequest, ValidateBlockData,
+    };
+    use txtx_core::kit::types::types::Type;
+    use txtx_core::kit::uuid::Uuid;
+
+    fn block_store(action_items: Vec<ActionItemRequest>) -> BTreeMap<usize, Block> {
+        let panel = Panel::new_action_panel(
+            "Review",
+            "",
+            vec![ActionGroup::new("Inputs", vec![ActionSubGroup::new(None, action_items, false)])],
+        );
+        BTreeMap::from([(0, Block::new(&Uuid::new_v4(), panel))])
+    }
+
+    #[test]
+    fn test_reviews_are_approved_before_validation() {
+        let review = ActionItemRequestType::ReviewInput(ReviewInputRequest::new(
+            "value",
+            &Value::integer(1),
+        ))
+        .to_request("deploy", "check_input");
+        let validate = ActionItemRequestType::ValidateBlock(ValidateBlockData::new(0))
+            .to_request("", "validate_block");
+        let store = block_store(vec![review.clone(), validate.clone()]);
+
+        let mut supervisor = UnattendedSupervisor::new(false);
+        let responses = supervisor.next_responses(&store).unwrap();
+        assert_eq!(
+            responses.iter().map(|(r, _)| r.action_item_id.clone()).collect::<Vec<_>>(),
+            vec![review.id, validate.id]
+        );
+        // action items are answered once
+        assert!(supervisor.next_responses(&store).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_public_keys_require_a_human() {
+        let provide_public_key = ActionItemRequestType::ProvidePublicKey(ProvidePublicKeyRequest {
+            check_expectation_action_uuid: None,
+            message: "".into(),
+            namespace: "evm".into(),
+            network_id: "1".into(),
+        })
+        .to_request("deployer", "provide_public_key");
+        let store = block_store(vec![provide_public_key]);
+
+        let err = UnattendedSupervisor::new(false).next_responses(&store).unwrap_err();
+        assert!(err.starts_with("deployer requires a human operator"));
+    }
+
+    #[test]
+    fn test_sensitive_inputs_require_a_terminal() {
+        let provide_passphrase = ActionItemRequestType::ProvideInput(ProvideInputRequest {
+            default_value: None,
+            input_name: "passphrase".into(),
+            typing: Type::string(),
+            sensitive: true,
+        })
+        .to_request("deployer", "provide_passphrase");
+
+        assert!(requires_human(&provide_passphrase, true).is_none());
+        let reason = requires_human(&provide_passphrase, false).unwrap();
+        assert!(reason.contains("input 'passphrase' has no default value"));
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::SystemTime;
+
+use txtx_core::kit::helpers::fs::FileLocation;
+
+use super::load_workspace_manifest_from_manifest_path;
+use crate::cli::ExecuteRunbook;
+
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(300);
+/// Changes are only acted upon once the watched files stopped changing for this long.
+const DEBOUNCE_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Returns the paths to watch for a manifest: the manifest itself, the locations of its runbooks
+/// and shared signers, and the input files. The manifest is read again on every call, so that
+/// the runbooks added to it are picked up.
+fn watched_paths(manifest_path: &str, input_files: &Vec<String>) -> Vec<PathBuf> {
+    let mut paths = vec![PathBuf::from(manifest_path)];
+    paths.extend(input_files.iter().filter(|f| !f.eq(&"-")).map(PathBuf::from));
+
+    // a manifest being edited may be invalid, in which case only the manifest is watched
+    let Ok(manifest) = load_workspace_manifest_from_manifest_path(manifest_path) else {
+        return paths;
+    };
+    let Some(Ok(FileLocation::FileSystem { path: root_path })) =
+        manifest.location.as_ref().map(|location| location.get_parent_location())
+    else {
+        return paths;
+    };
+    paths.extend(manifest.runbooks.iter().map(|runbook| root_path.join(&runbook.location)));
+    if let Some(signers) = &manifest.signers {
+        paths.push(root_path.join(signers));
+    }
+    paths
+}
+
+/// Records the modification time of the watched files. Runbook locations being directories,
+/// the txtx files they contain are listed, so that new files are detected along with edits.
+fn snapshot(paths: &Vec<PathBuf>) -> BTreeMap<PathBuf, Option<SystemTime>> {
+    let modified = |path: &PathBuf| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    let mut snapshot = BTreeMap::new();
+    for path in paths.iter() {
+        let Ok(entries) = std::fs::read_dir(path) else {
+            // missing files are recorded as well, to detect their creation
+            snapshot.insert(path.clone(), modified(path));
+            continue;
+        };
+        for entry in entries.flatten() {
+            let entry_path = entry.path();
+            let is_txtx_file =
+                entry_path.extension().map_or(false, |ext| ext.eq("tx") || ext.eq("txvars"));
+            if is_txtx_file {
+                let modified_at = modified(&entry_path);
+                snapshot.insert(entry_path, modified_at);
+            }
+        }
+    }
+    snapshot
+}
+
+/// Waits until the manifest, the runbooks sources or the input files change.
+pub async fn wait_for_changes(manifest_path: &str, input_files: &Vec<String>) {
+    let initial_snapshot = snapshot(&watched_paths(manifest_path, input_files));
+    let mut current_snapshot = loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let current_snapshot = snapshot(&watched_paths(manifest_path, input_files));
+        if current_snapshot != initial_snapshot {
+            break current_snapshot;
+        }
+    };
+    // editors usually write files in several steps
+    loop {
+        tokio::time::sleep(DEBOUNCE_INTERVAL).await;
+        let next_snapshot = snapshot(&watched_paths(manifest_path, input_files));
+        if next_snapshot == current_snapshot {
+            break;
+        }
+        current_snapshot = next_snapshot;
+    }
+}
+
+pub fn clear_screen() {
+    let _ = console::Term::stdout().clear_screen();
+}
+
+/// Executes the runbook again every time its sources change.
+///
+/// Each execution is a new `txtx run` process, so that a runbook failing to load doesn't end the
+/// watch. Previous execution states are ignored, as with `--force`.
+pub async fn handle_run_watch(
+    cmd: &ExecuteRunbook,
+    buffer_stdin: Option<String>,
+) -> Result<(), String> {
+    // runbooks are re-executed without prompts, which is only acceptable against local networks
+    if !cmd.local_environment {
+        let manifest = load_workspace_manifest_from_manifest_path(&cmd.manifest_path)?;
+        let environment = cmd
+            .environment
+            .clone()
+            .or(manifest.environments.first().map(|(k, _)| k.clone()))
+            .unwrap_or_default();
+        return Err(format!(
+            "txtx run --watch executes the runbook again on every change, without prompts: pass --local-env to confirm that the environment '{}' is local",
+            environment
+        ));
+    }
+
+    let executable =
+        std::env::current_exe().map_err(|e| format!("unable to locate txtx executable: {e}"))?;
+    // `--local-env` requires `--watch`, and is only relevant to the watch
+    let mut args = std::env::args()
+        .skip(1)
+        .filter(|arg| arg != "--watch" && arg != "--local-env")
+        .collect::<Vec<_>>();
+    if !cmd.force_execution {
+        args.push("--force".into());
+    }
+    // the environment was declared local, the runbook is re-executed without prompts
+    if !cmd.no_interactive {
+        args.push("--no-interactive".into());
+    }
+
+    loop {
+        clear_screen();
+        let mut child = std::process::Command::new(&executable)
+            .args(&args)
+            .stdin(if buffer_stdin.is_some() { Stdio::piped() } else { Stdio::inherit() })
+            .spawn()
+            .map_err(|e| format!("unable to execute runbook: {e}"))?;
+        if let (Some(mut stdin), Some(buffer)) = (child.stdin.take(), &buffer_stdin) {
+            let _ = stdin.write_all(buffer.as_bytes());
+        }
+        let status = child.wait().map_err(|e| format!("unable to execute runbook: {e}"))?;
+        let marker = if status.success() { green!("✓") } else { red!("x") };
+        println!(
+            "\n{} Executed at {}, watching for changes (Ctrl-C to stop)",
+            marker,
+            chrono::Local::now().format("%H:%M:%S")
+        );
+        wait_for_changes(&cmd.manifest_path, &cmd.input_files).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_detects_new_and_edited_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let runbook_dir = dir.path().to_path_buf();
+        std::fs::write(runbook_dir.join("main.tx"), "").unwrap();
+        let paths = vec![runbook_dir.clone(), runbook_dir.join("inputs.json")];
+        let initial_snapshot = snapshot(&paths);
+
+        // files not matched by the runbook location are ignored
+        std::fs::write(runbook_dir.join("notes.md"), "").unwrap();
+        assert_eq!(snapshot(&paths), initial_snapshot);
+
+        std::fs::write(runbook_dir.join("signers.tx"), "").unwrap();
+        let snapshot_with_new_file = snapshot(&paths);
+        assert_ne!(snapshot_with_new_file, initial_snapshot);
+
+        std::fs::write(runbook_dir.join("inputs.json"), "{}").unwrap();
+        assert_ne!(snapshot(&paths), snapshot_with_new_file);
+    }
+}
@@ -1,4 +1,8 @@
-use super::{BeginSnapshot, CommitSnapshot, Context};
+use txtx_core::kit::helpers::fs::FileLocation;
+use txtx_core::runbook::{ConstructChangeKind, ExecutionSnapshotsDiff, RunbookExecutionSnapshot};
+
+use super::runbooks::load_runbook_from_manifest;
+use super::{BeginSnapshot, CommitSnapshot, Context, DiffSnapshots, OutputFormat};
 
 pub async fn handle_begin_command(_cmd: &BeginSnapshot, _ctx: &Context) -> Result<(), String> {
     // Create a .lock file on a DB file path specified
@@ -11,3 +15,124 @@ pub async fn handle_commit_command(_cmd: &CommitSnapshot, _ctx: &Context) -> Res
     // Write state transitions accumulated to db file specified
     Ok(())
 }
+
+/// Reports the per-construct changes between two state files, or between the latest state of a
+/// runbook (or a given state file) and a simulation of the current runbook.
+pub async fn handle_diff_command(
+    cmd: &DiffSnapshots,
+    buffer_stdin: Option<String>,
+    _ctx: &Context,
+) -> Result<(), String> {
+    let (old, new) = match (&cmd.old, &cmd.new) {
+        (Some(old), Some(new)) => (load_snapshot_file(old)?, load_snapshot_file(new)?),
+        (old, _) => {
+            let Some(runbook_name) = &cmd.runbook else {
+                return Err(
+                    "a runbook (--runbook) is required unless two state files are provided".into(),
+                );
+            };
+            let (_, _, mut runbook, runbook_state_location) = load_runbook_from_manifest(
+                &cmd.manifest_path,
+                runbook_name,
+                &cmd.environment,
+                &cmd.input_files,
+                &cmd.inputs,
+                buffer_stdin,
+                cmd.format == OutputFormat::Json,
+            )
+            .await?;
+            let old = match (old, runbook_state_location) {
+                (Some(old), _) => load_snapshot_file(old)?,
+                (None, Some(state_location)) => state_location.load_execution_snapshot(
+                    true,
+                    &runbook.runbook_id.name,
+                    &runbook.top_level_inputs_map.current_top_level_input_name(),
+                )?,
+                (None, None) => {
+                    return Err(format!(
+                        "runbook '{}' has no state location configured in the manifest",
+                        runbook_name
+                    ))
+                }
+            };
+            runbook.enable_full_execution_mode();
+            let new = runbook.simulate_and_snapshot_flows(&old).await?;
+            (old, new)
+        }
+    };
+
+    let diff = old.diff_constructs(&new);
+    match cmd.format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&diff).map_err(|e| e.to_string())?);
+        }
+        OutputFormat::Text => display_snapshots_diff(&diff),
+    }
+    Ok(())
+}
+
+fn load_snapshot_file(path: &str) -> Result<RunbookExecutionSnapshot, String> {
+    let location = FileLocation::from_path_string(path)?;
+    let bytes = location.read_content()?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("unable to read {}: {}", path, e))
+}
+
+/// Prints the changes as a tree: flows, then constructs, then their inputs and outputs.
+fn display_snapshots_diff(diff: &ExecutionSnapshotsDiff) {
+    if diff.is_empty() {
+        println!("{} No changes", green!("✓"));
+        return;
+    }
+    for flow in diff.flows_added.iter() {
+        println!("{} flow {}", green!("+"), flow);
+    }
+    for flow in diff.flows_removed.iter() {
+        println!("{} flow {}", red!("-"), flow);
+    }
+    for flow in diff.flows.iter().filter(|flow| !flow.constructs.is_empty()) {
+        println!("flow {}", flow.name);
+        for (i, construct) in flow.constructs.iter().enumerate() {
+            let is_last_construct = i == flow.constructs.len() - 1;
+            let (branch, indent) =
+                if is_last_construct { ("└──", "    ") } else { ("├──", "│   ") };
+            let label = format!("{}.{}", construct.construct_type, construct.name);
+            let label = match construct.change {
+                ConstructChangeKind::Added => format!("{} {}", green!("+"), green!("{}", label)),
+                ConstructChangeKind::Removed => format!("{} {}", red!("-"), red!("{}", label)),
+                ConstructChangeKind::Updated => {
+                    format!("{} {}", yellow!("~"), yellow!("{}", label))
+                }
+            };
+            let re_execution = match construct.would_re_execute {
+                true => format!(" {}", yellow!("(would be re-executed)")),
+                false => String::new(),
+            };
+            println!("{} {}{}", branch, label, re_execution);
+
+            let changes = construct
+                .inputs
+                .iter()
+                .map(|change| ("input", change))
+                .chain(construct.outputs.iter().map(|change| ("output", change)))
+                .collect::<Vec<_>>();
+            for (j, (kind, change)) in changes.iter().enumerate() {
+                let branch = if j == changes.len() - 1 { "└──" } else { "├──" };
+                let critical = if change.critical {
+                    format!(" {}", red!("(critical)"))
+                } else {
+                    String::new()
+                };
+                println!(
+                    "{}{} {} {}: {} → {}{}",
+                    indent,
+                    branch,
+                    kind,
+                    change.name,
+                    red!("{}", change.old.as_deref().unwrap_or("<none>")),
+                    green!("{}", change.new.as_deref().unwrap_or("<none>")),
+                    critical
+                );
+            }
+        }
+    }
+}
@@ -1,14 +1,40 @@
 #![allow(unused)]
 
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static COLORS_DISABLED: AtomicBool = AtomicBool::new(false);
+
+fn is_env_set(name: &str) -> bool {
+    std::env::var(name).map_or(false, |value| !value.is_empty() && value != "0")
+}
+
+/// Disables colors when requested with `--no-color` or the `NO_COLOR` environment variable,
+/// including in the dialogs and progress bars.
+pub fn init_colors(no_color: bool) {
+    if no_color || is_env_set("NO_COLOR") {
+        COLORS_DISABLED.store(true, Ordering::Relaxed);
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+        colored::control::set_override(false);
+    }
+}
+
+/// Colors are used when stdout is a terminal (or `CLICOLOR_FORCE` is set), unless disabled.
+pub fn colors_enabled() -> bool {
+    if COLORS_DISABLED.load(Ordering::Relaxed) || is_env_set("NO_COLOR") {
+        return false;
+    }
+    is_env_set("CLICOLOR_FORCE") || atty::is(atty::Stream::Stdout)
+}
+
 /// Base macro for colorizing text
 /// This macro handles the common logic for all color macros
 #[allow(unused_macros)]
 macro_rules! colorize_impl {
     ($color_expr:expr, $($arg:tt)*) => {
         {
-            use atty::Stream;
             use ansi_term::Style;
-            if atty::is(Stream::Stdout) {
+            if $crate::macros::colors_enabled() {
                 format!("{}", $color_expr.paint(format!($($arg)*)))
             } else {
                 format!($($arg)*)
@@ -0,0 +1,108 @@
+use console::Style;
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Password, Select};
+
+/// A top-level input, as reviewed before a run.
+pub struct PickedInput {
+    pub name: String,
+    /// Value pre-filled when editing the input
+    pub value: String,
+    /// Value listed in the review, with secrets masked
+    pub displayed_value: String,
+    pub is_sensitive: bool,
+}
+
+fn theme() -> ColorfulTheme {
+    ColorfulTheme {
+        values_style: Style::new().green(),
+        hint_style: Style::new().cyan(),
+        ..ColorfulTheme::default()
+    }
+}
+
+/// Asks for the environment to run the runbook in, the current one being pre-selected.
+pub fn pick_environment(
+    environments: &Vec<String>,
+    current_environment: &Option<String>,
+) -> Result<String, String> {
+    let default = current_environment
+        .as_ref()
+        .and_then(|current| environments.iter().position(|env| env.eq(current)))
+        .unwrap_or(0);
+    let selection = Select::with_theme(&theme())
+        .with_prompt("Select the environment")
+        .items(environments)
+        .default(default)
+        .interact()
+        .map_err(|e| format!("unable to select environment: {e}"))?;
+    Ok(environments[selection].clone())
+}
+
+/// Lists the inputs of the selected environment, letting the operator edit them before
+/// confirming the run. Returns the edits as `name=value` pairs, formatted as `--input` flags, or
+/// `None` if the run was not confirmed.
+pub fn review_inputs(
+    runbook_name: &str,
+    environment: &str,
+    inputs: &mut Vec<PickedInput>,
+) -> Result<Option<Vec<String>>, String> {
+    let theme = theme();
+    let mut edits = vec![];
+    while !inputs.is_empty() {
+        let mut items = inputs
+            .iter()
+            .map(|input| format!("{} = {}", input.name, input.displayed_value))
+            .collect::<Vec<_>>();
+        items.push("Continue".to_string());
+        let selection = Select::with_theme(&theme)
+            .with_prompt("Review the inputs (select an input to edit it)")
+            .items(&items)
+            .default(items.len() - 1)
+            .interact()
+            .map_err(|e| format!("unable to review inputs: {e}"))?;
+        let Some(input) = inputs.get_mut(selection) else {
+            break;
+        };
+
+        let new_value = if input.is_sensitive {
+            // secrets are not echoed, an empty value keeps the current one
+            let value = Password::with_theme(&theme)
+                .with_prompt(format!("{} (leave empty to keep the current value)", input.name))
+                .allow_empty_password(true)
+                .interact()
+                .map_err(|e| format!("unable to read input: {e}"))?;
+            if value.is_empty() {
+                continue;
+            }
+            value
+        } else {
+            Input::<String>::with_theme(&theme)
+                .with_prompt(&input.name)
+                .with_initial_text(&input.value)
+                .interact_text()
+                .map_err(|e| format!("unable to read input: {e}"))?
+        };
+        if new_value.eq(&input.value) {
+            continue;
+        }
+        if !input.is_sensitive {
+            input.displayed_value = new_value.clone();
+        }
+        input.value = new_value.clone();
+        edits.push(format!("{}={}", input.name, new_value));
+    }
+
+    let confirmed = Confirm::with_theme(&theme)
+        .with_prompt(format!("Run '{}' in environment '{}'?", runbook_name, environment))
+        .default(true)
+        .interact()
+        .map_err(|e| format!("unable to confirm: {e}"))?;
+    Ok(confirmed.then_some(edits))
+}
+
+/// Asks for the value of a sensitive input (a keyfile passphrase...), which isn't echoed.
+pub fn prompt_sensitive_input(prompt: &str) -> Result<String, String> {
+    Password::with_theme(&theme())
+        .with_prompt(prompt)
+        .interact()
+        .map_err(|e| format!("unable to read input: {e}"))
+}
@@ -0,0 +1,95 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use tempfile::TempDir;
+
+const MANIFEST: &str = r#"---
+name: check
+id: check
+runbooks:
+  - name: main
+    id: main
+    description:
+    location: main.tx
+environments:
+  localnet:
+    greeting: hello
+"#;
+
+const CLEAN_RUNBOOK: &str = r#"
+variable "greeting" {
+    value = input.greeting
+}
+output "greeting" {
+    value = variable.greeting
+}
+"#;
+
+const INVALID_RUNBOOK: &str = r#"
+output "greeting" {
+    value = variable.missing
+}
+"#;
+
+fn workspace(runbook: &str) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("txtx.yml"), MANIFEST).unwrap();
+    std::fs::write(dir.path().join("main.tx"), runbook).unwrap();
+    dir
+}
+
+fn check(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir)
+        .arg("check")
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("unable to run txtx")
+}
+
+#[test]
+fn test_clean_runbook_passes() {
+    let dir = workspace(CLEAN_RUNBOOK);
+    let output = check(dir.path(), &[]);
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No issues found"));
+
+    let output = check(dir.path(), &["main", "--deny-warnings"]);
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn test_invalid_runbook_fails_with_validation_failure() {
+    let dir = workspace(INVALID_RUNBOOK);
+    let output = check(dir.path(), &[]);
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("main.tx"), "{stdout}");
+    assert!(stdout.contains("error(s)"), "{stdout}");
+}
+
+#[test]
+fn test_diagnostics_are_reported_as_json() {
+    let dir = workspace(INVALID_RUNBOOK);
+    let output = check(dir.path(), &["--format", "json"]);
+    assert_eq!(output.status.code(), Some(2));
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let diagnostics = diagnostics.as_array().unwrap();
+    assert!(diagnostics.iter().any(|diag| diag["level"] == "Error"
+        && diag["file"].as_str().is_some_and(|file| file.ends_with("main.tx"))));
+
+    let dir = workspace(CLEAN_RUNBOOK);
+    let output = check(dir.path(), &["--format", "json"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap(),
+        serde_json::json!([])
+    );
+}
+
+#[test]
+fn test_unknown_runbook_is_a_configuration_error() {
+    let dir = workspace(CLEAN_RUNBOOK);
+    assert_eq!(check(dir.path(), &["missing"]).status.code(), Some(3));
+}
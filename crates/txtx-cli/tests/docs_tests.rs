@@ -0,0 +1,120 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::process::{Child, Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+fn docs(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir)
+        .arg("docs")
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("unable to run txtx")
+}
+
+fn read_page(dir: &Path, page: &str) -> String {
+    std::fs::read_to_string(dir.join(page)).unwrap_or_else(|e| panic!("{page}: {e}"))
+}
+
+#[test]
+fn test_addon_documentation_is_generated() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = docs(dir.path(), &["evm", "--output-dir", "docs"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let docs_dir = dir.path().join("docs");
+    let actions = read_page(&docs_dir, "evm/actions/page.mdx");
+    assert!(actions.contains("## deploy_contract"));
+    assert!(actions.contains("<Property name=\"signer\""));
+    assert!(read_page(&docs_dir, "evm/signers/page.mdx").contains("encrypted_keyfile"));
+    assert!(read_page(&docs_dir, "evm/functions/page.mdx").contains("evm"));
+    assert!(read_page(&docs_dir, "evm/overview/page.mdx").contains("evm"));
+    // only the requested addon is documented
+    assert!(!docs_dir.join("svm").exists());
+    let actions: Value = serde_json::from_str(&read_page(&docs_dir, "actions.json")).unwrap();
+    let namespaces = actions.as_object().unwrap().keys().collect::<Vec<_>>();
+    assert_eq!(namespaces, vec!["evm"]);
+}
+
+#[test]
+fn test_std_documentation_is_grouped() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = docs(dir.path(), &["std", "--output-dir", "docs"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let docs_dir = dir.path().join("docs");
+    assert!(read_page(&docs_dir, "std/actions/http/page.mdx").contains("send_http_request"));
+    assert!(docs_dir.join("std/functions/json/page.mdx").exists());
+    assert!(docs_dir.join("std/functions/overview/page.mdx").exists());
+    assert!(docs_dir.join("std/actions/overview/page.mdx").exists());
+}
+
+#[test]
+fn test_unknown_addon_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = docs(dir.path(), &["unknown", "--output-dir", "docs"]);
+    assert!(!output.status.success());
+    assert!(!dir.path().join("docs").exists());
+}
+
+/// Kills the preview server when the test ends, whether it passed or not.
+struct Server(Child);
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Sends a GET request for `path`, returning the status line and the body of the response.
+fn get(port: u16, path: &str) -> Option<(String, String)> {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(10))).ok()?;
+    write!(stream, "GET {path} HTTP/1.1\r\nHost: 127.0.0.1\r\nConnection: close\r\n\r\n").ok()?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    Some((head.lines().next()?.to_string(), body.to_string()))
+}
+
+#[test]
+fn test_documentation_is_served() {
+    let dir = tempfile::tempdir().unwrap();
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let _server = Server(
+        Command::new(env!("CARGO_BIN_EXE_txtx"))
+            .current_dir(dir.path())
+            .args(["docs", "evm", "--output-dir", "docs", "--serve", "--port", &port.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("unable to run txtx"),
+    );
+
+    let deadline = Instant::now() + Duration::from_secs(60);
+    let index = loop {
+        if let Some(response) = get(port, "/") {
+            break response;
+        }
+        assert!(Instant::now() < deadline, "the documentation server didn't start");
+        std::thread::sleep(Duration::from_millis(100));
+    };
+    assert!(index.0.contains("200"), "{}", index.0);
+    assert!(index.1.contains("<a href=\"/evm/actions/page.mdx\">"));
+
+    let (status, page) = get(port, "/evm/actions/page.mdx").unwrap();
+    assert!(status.contains("200"), "{status}");
+    assert!(page.contains("## deploy_contract"));
+
+    let (status, _) = get(port, "/evm/missing.mdx").unwrap();
+    assert!(status.contains("404"), "{status}");
+    // pages outside of the documentation directory are never served
+    std::fs::write(dir.path().join("secret.txt"), "secret").unwrap();
+    let (status, body) = get(port, "/../secret.txt").unwrap();
+    assert!(!body.contains("secret"), "{status}");
+}
@@ -0,0 +1,131 @@
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use tempfile::TempDir;
+
+const SUCCESSFUL_RUNBOOK: &str = r#"
+variable "greeting" {
+    value = "hello"
+}
+output "greeting" {
+    value = variable.greeting
+}
+"#;
+
+const INVALID_RUNBOOK: &str = r#"
+output "greeting" {
+    value = variable.missing
+}
+"#;
+
+fn http_runbook(url: &str, timeout_ms: u64) -> String {
+    format!(
+        r#"
+action "ping" "std::send_http_request" {{
+    url = "{url}"
+    timeout_ms = {timeout_ms}
+}}
+output "status_code" {{
+    value = action.ping.status_code
+}}
+"#
+    )
+}
+
+/// Starts a server accepting connections without ever responding, and returns its url. Each
+/// connection accepted is notified on the returned channel.
+fn silent_server() -> (String, mpsc::Receiver<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (accepted_tx, accepted_rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        // connections are kept open until the end of the test
+        let mut connections = vec![];
+        for stream in listener.incoming() {
+            connections.push(stream);
+            let _ = accepted_tx.send(());
+        }
+    });
+    (url, accepted_rx)
+}
+
+fn workspace(runbook: &str) -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.tx"), runbook).unwrap();
+    dir
+}
+
+fn exit_code(dir: &Path, args: &[&str]) -> Option<i32> {
+    let output = Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .expect("unable to run txtx");
+    output.status.code()
+}
+
+fn run_exit_code(runbook: &str) -> Option<i32> {
+    let dir = workspace(runbook);
+    exit_code(dir.path(), &["run", "main.tx", "--unsupervised"])
+}
+
+#[test]
+fn test_success_exits_with_0() {
+    assert_eq!(run_exit_code(SUCCESSFUL_RUNBOOK), Some(0));
+}
+
+#[test]
+fn test_execution_failure_exits_with_1() {
+    // nothing listens on a port released right after being bound
+    let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+    let runbook = http_runbook(&format!("http://127.0.0.1:{port}"), 200);
+    assert_eq!(run_exit_code(&runbook), Some(1));
+}
+
+#[test]
+fn test_validation_failure_exits_with_2() {
+    assert_eq!(run_exit_code(INVALID_RUNBOOK), Some(2));
+}
+
+#[test]
+fn test_configuration_error_exits_with_3() {
+    let dir = workspace(SUCCESSFUL_RUNBOOK);
+    assert_eq!(exit_code(dir.path(), &["run", "missing.tx", "--unsupervised"]), Some(3));
+    assert_eq!(exit_code(dir.path(), &["check", "-m", "missing.yml"]), Some(3));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cancellation_exits_with_4() {
+    let (url, accepted_rx) = silent_server();
+    let dir = workspace(&http_runbook(&url, 60_000));
+    let mut child = Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir.path())
+        .args(["run", "main.tx", "--unattended"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("unable to run txtx");
+
+    // the operator interrupts the execution while the request is pending
+    accepted_rx.recv_timeout(Duration::from_secs(60)).expect("request never received");
+    let interrupted =
+        Command::new("kill").args(["-INT", &child.id().to_string()]).status().unwrap();
+    assert!(interrupted.success());
+    assert_eq!(child.wait().unwrap().code(), Some(4));
+}
+
+#[test]
+fn test_timeout_exits_with_5() {
+    let (url, _accepted_rx) = silent_server();
+    assert_eq!(run_exit_code(&http_runbook(&url, 200)), Some(5));
+}
+
+#[test]
+fn test_exit_codes_are_listed_in_help() {
+    let output = Command::new(env!("CARGO_BIN_EXE_txtx")).arg("--help").output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Exit codes:"));
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+use std::process::Command;
+
+use tempfile::TempDir;
+
+const MANIFEST: &str = r#"---
+name: graph
+id: graph
+runbooks:
+  - name: main
+    location: main.tx
+environments:
+  localnet:
+    url: http://127.0.0.1:8080
+"#;
+
+const RUNBOOK: &str = r#"
+variable "greeting" {
+    value = "hello"
+}
+action "ping" "std::send_http_request" {
+    url = input.url
+    depends_on = [variable.greeting]
+}
+output "greeting" {
+    value = variable.greeting
+}
+output "status_code" {
+    value = action.ping.status_code
+}
+"#;
+
+fn workspace() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("txtx.yml"), MANIFEST).unwrap();
+    std::fs::write(dir.path().join("main.tx"), RUNBOOK).unwrap();
+    dir
+}
+
+fn graph(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir)
+        .arg("graph")
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("unable to run txtx");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn count_lines(graph: &str, pattern: &str) -> usize {
+    graph.lines().filter(|line| line.contains(pattern)).count()
+}
+
+#[test]
+fn test_execution_graph_is_exported_as_dot() {
+    let dir = workspace();
+    let dot = graph(dir.path(), &["main"]);
+    assert!(dot.starts_with("digraph \"main\" {\n"), "{dot}");
+    assert!(dot.trim_end().ends_with('}'));
+    assert_eq!(count_lines(&dot, "subgraph \"cluster_"), 1);
+    // nodes are labeled with their position in the execution order
+    assert!(dot.contains("[label=\"#1 variable.greeting"), "{dot}");
+    assert_eq!(count_lines(&dot, "action.ping"), 1);
+    assert_eq!(count_lines(&dot, "output.greeting"), 1);
+    assert_eq!(count_lines(&dot, "output.status_code"), 1);
+    // greeting -> output.greeting and ping -> output.status_code
+    assert_eq!(count_lines(&dot, "[label=\"data\", style=solid]"), 2, "{dot}");
+    assert_eq!(count_lines(&dot, "[label=\"depends_on\", style=dashed]"), 1, "{dot}");
+}
+
+#[test]
+fn test_execution_graph_is_exported_as_mermaid() {
+    let dir = workspace();
+    let mermaid = graph(dir.path(), &["main", "--format", "mermaid"]);
+    assert!(mermaid.starts_with("flowchart TD\n"), "{mermaid}");
+    assert_eq!(count_lines(&mermaid, "subgraph flow_0["), 1);
+    assert_eq!(count_lines(&mermaid, "end"), 1);
+    assert_eq!(count_lines(&mermaid, " -->|data| "), 2, "{mermaid}");
+    assert_eq!(count_lines(&mermaid, " -.->|depends_on| "), 1, "{mermaid}");
+}
+
+#[test]
+fn test_unknown_runbook_fails() {
+    let dir = workspace();
+    let output = Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir.path())
+        .args(["graph", "missing"])
+        .output()
+        .expect("unable to run txtx");
+    assert!(!output.status.success());
+}
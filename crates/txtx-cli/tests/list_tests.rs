@@ -0,0 +1,99 @@
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::{json, Value};
+use tempfile::TempDir;
+
+const MANIFEST: &str = r#"---
+name: list
+id: list
+runbooks:
+  - name: deploy
+    description: |
+      Deploy the contracts
+
+      Then verify them
+    location: runbooks/deploy.tx
+    state:
+      location: states
+  - name: notify
+    location: runbooks/notify.tx
+environments:
+  testnet:
+    rpc_api_url: http://127.0.0.1:8545
+    chain_id: "31337"
+  devnet:
+    rpc_api_url: http://127.0.0.1:8899
+"#;
+
+fn workspace() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("txtx.yml"), MANIFEST).unwrap();
+    std::fs::create_dir(dir.path().join("states")).unwrap();
+    std::fs::write(dir.path().join("states/deploy.testnet.tx-state.json"), "{}").unwrap();
+    dir
+}
+
+fn ls(dir: &Path, args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir)
+        .arg("ls")
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("unable to run txtx");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_runbooks_and_environments_are_listed_as_json() {
+    let dir = workspace();
+    let listed: Value = serde_json::from_str(&ls(dir.path(), &["--json"])).unwrap();
+
+    let runbooks = listed["runbooks"].as_array().unwrap();
+    assert_eq!(runbooks.len(), 2);
+    assert_eq!(runbooks[0]["name"], "deploy");
+    assert_eq!(runbooks[0]["location"], "runbooks/deploy.tx");
+    assert!(runbooks[0]["description"].as_str().unwrap().starts_with("Deploy the contracts"));
+    // states are only looked up for the runbooks tracking them
+    assert_eq!(runbooks[0]["stateEnvironments"], json!(["testnet"]));
+    assert_eq!(runbooks[1]["name"], "notify");
+    assert_eq!(runbooks[1]["stateEnvironments"], Value::Null);
+    assert_ne!(runbooks[0]["id"], runbooks[1]["id"]);
+
+    // environments are sorted by name, their inputs listed in the manifest order
+    assert_eq!(
+        listed["environments"],
+        json!([
+            { "name": "devnet", "inputs": ["rpc_api_url"] },
+            { "name": "testnet", "inputs": ["rpc_api_url", "chain_id"] },
+        ])
+    );
+}
+
+#[test]
+fn test_only_environments_are_listed_with_envs() {
+    let dir = workspace();
+    let listed: Value = serde_json::from_str(&ls(dir.path(), &["--envs", "--json"])).unwrap();
+    assert!(listed.get("runbooks").is_none());
+    assert_eq!(listed["environments"].as_array().unwrap().len(), 2);
+
+    let stdout = ls(dir.path(), &["--envs"]);
+    assert!(!stdout.contains("Runbooks"));
+    assert!(stdout.contains("Environments"));
+    assert!(stdout.contains("rpc_api_url, chain_id"));
+}
+
+#[test]
+fn test_runbooks_are_listed_as_tables() {
+    let dir = workspace();
+    let stdout = ls(dir.path(), &[]);
+    assert!(stdout.contains("Runbooks"));
+    assert!(stdout.contains("Environments"));
+    // only the first line of the description is displayed
+    assert!(stdout.contains("Deploy the contracts"));
+    assert!(!stdout.contains("Then verify them"));
+    assert!(stdout.contains("runbooks/notify.tx"));
+    assert!(stdout.contains("testnet"));
+}
@@ -0,0 +1,114 @@
+use std::path::Path;
+use std::process::Command;
+
+use tempfile::TempDir;
+
+const RUNBOOK: &str = r#"
+variable "greeting" {
+    value = "hello"
+}
+output "greeting" {
+    value = variable.greeting
+}
+"#;
+
+const ANSI_ESCAPE: &str = "\u{1b}[";
+const SPINNER_TICKS: [&str; 6] = ["⠋", "⠙", "⠸", "⠴", "⠦", "⠇"];
+
+fn workspace() -> TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.tx"), RUNBOOK).unwrap();
+    dir
+}
+
+/// Runs txtx with stdout captured, which is never a terminal. `CLICOLOR_FORCE` emulates a
+/// terminal as far as colors are concerned.
+fn run_txtx(dir: &Path, args: &[&str], envs: &[(&str, &str)]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir)
+        .args(args)
+        .env_remove("NO_COLOR")
+        .env_remove("CLICOLOR_FORCE")
+        .envs(envs.iter().copied())
+        .output()
+        .expect("unable to run txtx");
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn run_runbook(args: &[&str], envs: &[(&str, &str)]) -> String {
+    let dir = workspace();
+    let mut run_args = vec!["run", "main.tx", "--unsupervised"];
+    run_args.extend_from_slice(args);
+    run_txtx(dir.path(), &run_args, envs)
+}
+
+#[test]
+fn test_piped_output_is_plain_text() {
+    let stdout = run_runbook(&[], &[]);
+    assert!(stdout.contains("execution in unsupervised mode"));
+    assert!(!stdout.contains(ANSI_ESCAPE));
+    assert!(SPINNER_TICKS.iter().all(|tick| !stdout.contains(tick)));
+}
+
+#[test]
+fn test_terminal_output_is_colored() {
+    let stdout = run_runbook(&[], &[("CLICOLOR_FORCE", "1")]);
+    assert!(stdout.contains(ANSI_ESCAPE));
+}
+
+#[test]
+fn test_no_color_env_disables_colors() {
+    let stdout = run_runbook(&[], &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")]);
+    assert!(stdout.contains("Starting runbook"));
+    assert!(!stdout.contains(ANSI_ESCAPE));
+}
+
+#[test]
+fn test_no_color_flag_disables_colors() {
+    let stdout = run_runbook(&["--no-color"], &[("CLICOLOR_FORCE", "1")]);
+    assert!(stdout.contains("Starting runbook"));
+    assert!(!stdout.contains(ANSI_ESCAPE));
+}
+
+#[test]
+fn test_quiet_hides_progress() {
+    let stdout = run_runbook(&["--quiet"], &[]);
+    assert!(!stdout.contains("Starting runbook"));
+}
+
+/// Runs txtx with `--format json`, returning its exit code and the report printed to stdout, which
+/// must be the only output on stdout.
+fn run_json_report(runbook: &str) -> (Option<i32>, serde_json::Value) {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("main.tx"), runbook).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_txtx"))
+        .current_dir(dir.path())
+        .args(["run", "main.tx", "--unsupervised", "--format", "json"])
+        .output()
+        .expect("unable to run txtx");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report = serde_json::from_str(&stdout).unwrap_or_else(|e| panic!("{e}: {stdout}"));
+    (output.status.code(), report)
+}
+
+#[test]
+fn test_json_format_reports_on_stdout_only() {
+    let (code, report) = run_json_report(RUNBOOK);
+    assert_eq!(code, Some(0));
+    assert_eq!(report["status"], "success");
+}
+
+#[test]
+fn test_json_format_reports_validation_failures() {
+    let (code, report) = run_json_report(
+        r#"
+output "greeting" {
+    value = variable.missing
+}
+"#,
+    );
+    assert_eq!(code, Some(2));
+    assert_eq!(report["status"], "failure");
+    assert!(!report["diagnostics"].as_array().unwrap().is_empty());
+}
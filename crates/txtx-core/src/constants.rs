@@ -4,4 +4,5 @@ pub const NAMESPACE: &str = "std";
 pub const ACTION_ITEM_ENV: &str = "env";
 pub const ACTION_ITEM_GENESIS: &str = "genesis";
 pub const ACTION_ITEM_CHECK_OUTPUT: &str = "check_output";
+pub const ACTION_ITEM_PROVIDE_FILE: &str = "provide_file";
 pub const ACTION_ITEM_VALIDATE_BLOCK: &str = "validate_block";
@@ -0,0 +1,35 @@
+use txtx_addon_kit::types::diagnostics::DiagnosticCode;
+
+pub const UNKNOWN_ADDON: DiagnosticCode = DiagnosticCode::new("CORE001", "unknown addon");
+
+pub const UNRESOLVED_CONSTRUCT_REFERENCE: DiagnosticCode =
+    DiagnosticCode::new("CORE101", "unresolved construct reference")
+        .with_help_url("https://docs.txtx.sh/errors/core101");
+pub const DEPENDENCY_CYCLE: DiagnosticCode =
+    DiagnosticCode::new("CORE102", "dependency cycle between constructs");
+pub const UNKNOWN_COMMAND: DiagnosticCode = DiagnosticCode::new("CORE103", "unknown command");
+pub const UNKNOWN_FUNCTION: DiagnosticCode = DiagnosticCode::new("CORE104", "unknown function");
+pub const INVALID_INPUT_VALUE: DiagnosticCode =
+    DiagnosticCode::new("CORE105", "invalid input value");
+pub const UNSUPPORTED_VARIABLE_REFERENCE: DiagnosticCode =
+    DiagnosticCode::new("CORE106", "unsupported variable reference");
+pub const DEPRECATED_ITEM_USED: DiagnosticCode =
+    DiagnosticCode::new("CORE107", "deprecated item used");
+
+pub const ADDON_START_FAILED: DiagnosticCode =
+    DiagnosticCode::new("CORE201", "addon failed to start the run");
+pub const ADDON_END_FAILED: DiagnosticCode =
+    DiagnosticCode::new("CORE202", "addon failed to end the run");
+
+pub const CODES: &[DiagnosticCode] = &[
+    UNKNOWN_ADDON,
+    UNRESOLVED_CONSTRUCT_REFERENCE,
+    DEPENDENCY_CYCLE,
+    UNKNOWN_COMMAND,
+    UNKNOWN_FUNCTION,
+    INVALID_INPUT_VALUE,
+    UNSUPPORTED_VARIABLE_REFERENCE,
+    DEPRECATED_ITEM_USED,
+    ADDON_START_FAILED,
+    ADDON_END_FAILED,
+];
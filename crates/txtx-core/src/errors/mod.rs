@@ -1,3 +1,5 @@
+pub mod codes;
+
 use txtx_addon_kit::types::diagnostics::Diagnostic;
 
 #[derive(Debug, Clone)]
@@ -1,9 +1,13 @@
+use crate::errors::codes::{
+    INVALID_INPUT_VALUE, UNRESOLVED_CONSTRUCT_REFERENCE, UNSUPPORTED_VARIABLE_REFERENCE,
+};
 use crate::runbook::embedded_runbook::ExecutableEmbeddedRunbookInstance;
 use crate::runbook::{
     get_source_context_for_diagnostic, RunbookExecutionMode, RunbookWorkspaceContext,
     RuntimeContext,
 };
 use crate::types::{ConstructType, RunbookExecutionContext, RunbookSources};
+use base64::{engine::general_purpose, Engine};
 use kit::constants::{RE_EXECUTE_COMMAND, THIRD_PARTY_SIGNATURE_STATUS};
 use kit::types::commands::{
     ConstructInstance, PostConditionEvaluationResult, PreConditionEvaluationResult,
@@ -11,11 +15,14 @@ use kit::types::commands::{
 use kit::types::types::ObjectDefinition;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
+use std::str::FromStr;
+use txtx_addon_kit::bigdecimal::{BigDecimal, ToPrimitive};
 use txtx_addon_kit::constants::{
     SIGNATURE_APPROVED, SIGNATURE_SKIPPABLE, SIGNED_MESSAGE_BYTES, SIGNED_TRANSACTION_BYTES,
     TX_HASH,
 };
 use txtx_addon_kit::hcl::structure::Block as HclBlock;
+use txtx_addon_kit::hcl::Span;
 use txtx_addon_kit::helpers::hcl::visit_optional_untyped_attribute;
 use txtx_addon_kit::indexmap::IndexMap;
 use txtx_addon_kit::types::commands::{
@@ -25,7 +32,7 @@ use txtx_addon_kit::types::commands::{
 use txtx_addon_kit::types::embedded_runbooks::EmbeddedRunbookStatefulExecutionContext;
 use txtx_addon_kit::types::frontend::{
     ActionItemRequestUpdate, ActionItemResponse, ActionItemResponseType, Actions, Block,
-    BlockEvent, ErrorPanelData, Panel,
+    BlockEvent, ConstructStatus, ConstructStatusUpdate, ErrorPanelData, Panel,
 };
 use txtx_addon_kit::types::signers::SignersState;
 use txtx_addon_kit::types::stores::AddonDefaults;
@@ -36,10 +43,11 @@ use txtx_addon_kit::{
     hcl::{
         expr::{BinaryOperator, Expression, UnaryOperator},
         template::Element,
+        Formatted, Number,
     },
     types::{
         commands::{CommandExecutionResult, CommandInputsEvaluationResult},
-        diagnostics::Diagnostic,
+        diagnostics::{Diagnostic, DiagnosticLevel},
         frontend::{ActionItemRequest, ActionItemStatus},
         signers::SignerInstance,
         types::Value,
@@ -59,9 +67,92 @@ pub async fn run_signers_evaluation(
     action_item_requests: &mut BTreeMap<ConstructDid, Vec<&mut ActionItemRequest>>,
     action_item_responses: &BTreeMap<ConstructDid, Vec<ActionItemResponse>>,
     progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+) -> EvaluationPassResult {
+    let mut pass_result = evaluate_signers(
+        runbook_workspace_context,
+        runbook_execution_context,
+        runtime_context,
+        supervision_context,
+        action_item_requests,
+        action_item_responses,
+        progress_tx,
+    )
+    .await;
+    // the review and output items show the display form of the addon values
+    pass_result.actions.format_values(&|value| runtime_context.format_display(value));
+    pass_result
+}
+
+/// Checks that the signers bound to the signing commands have the capabilities required by these
+/// commands. A signer is bound by a reference to the signer itself (`signer.alice`), as opposed
+/// to a reference to one of its outputs (`signer.alice.address`).
+fn check_signers_capabilities(
+    runbook_workspace_context: &RunbookWorkspaceContext,
+    runbook_execution_context: &RunbookExecutionContext,
+    pass_result: &mut EvaluationPassResult,
+) {
+    for construct_did in runbook_execution_context.order_for_commands_execution.iter() {
+        let Some(command_instance) =
+            runbook_execution_context.commands_instances.get(construct_did)
+        else {
+            continue;
+        };
+        if !command_instance.specification.implements_signing_capability {
+            continue;
+        }
+        let construct_id = &runbook_workspace_context.expect_construct_id(construct_did);
+        let add_ctx_to_diag = add_ctx_to_diag(
+            "command".to_string(),
+            command_instance.specification.matcher.clone(),
+            command_instance.name.clone(),
+            command_instance.namespace.clone(),
+        );
+
+        let mut bound_signers = vec![];
+        for (_input, expr) in command_instance.get_expressions_referencing_commands_from_inputs() {
+            let Ok(Some((signer_did, components, _))) = runbook_workspace_context
+                .try_resolve_construct_reference_in_expression(&command_instance.package_id, &expr)
+            else {
+                continue;
+            };
+            if !components.is_empty() || bound_signers.contains(&signer_did) {
+                continue;
+            }
+            let Some(signer_instance) =
+                runbook_execution_context.signers_instances.get(&signer_did)
+            else {
+                continue;
+            };
+            if let Err(diag) = signer_instance.check_capabilities(command_instance) {
+                let diag = diag.set_span_range(expr.span());
+                pass_result.push_diagnostic(&diag, construct_id, &add_ctx_to_diag);
+            }
+            bound_signers.push(signer_did);
+        }
+    }
+}
+
+async fn evaluate_signers(
+    runbook_workspace_context: &RunbookWorkspaceContext,
+    runbook_execution_context: &mut RunbookExecutionContext,
+    runtime_context: &RuntimeContext,
+    supervision_context: &RunbookSupervisionContext,
+    action_item_requests: &mut BTreeMap<ConstructDid, Vec<&mut ActionItemRequest>>,
+    action_item_responses: &BTreeMap<ConstructDid, Vec<ActionItemResponse>>,
+    progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
 ) -> EvaluationPassResult {
     let mut pass_result = EvaluationPassResult::new(&Uuid::new_v4());
 
+    // no signer should prompt for a command it won't be able to sign
+    check_signers_capabilities(
+        runbook_workspace_context,
+        runbook_execution_context,
+        &mut pass_result,
+    );
+    if pass_result.has_diagnostics() {
+        return pass_result;
+    }
+
     let signers_instances = &runbook_execution_context.signers_instances;
     let instantiated_signers = runbook_execution_context.order_for_signers_initialization.clone();
 
@@ -83,6 +174,13 @@ pub async fn run_signers_evaluation(
             signer_instance.namespace.clone(),
         );
 
+        let status_update = construct_status_update(
+            &construct_did,
+            ConstructStatus::Pending,
+            runbook_workspace_context,
+            runbook_execution_context,
+        );
+
         let mut cached_dependency_execution_results = DependencyExecutionResultCache::new();
 
         let references_expressions =
@@ -113,13 +211,22 @@ pub async fn run_signers_evaluation(
             .get(&construct_did.clone());
 
         let addon_context_key = (package_id.did(), signer_instance.namespace.clone());
-        let addon_defaults = runbook_workspace_context.get_addon_defaults(&addon_context_key);
+        let addon_defaults = match runbook_workspace_context
+            .get_addon_defaults(&addon_context_key, signer_instance.get_addon_instance().as_deref())
+        {
+            Ok(addon_defaults) => addon_defaults,
+            Err(diag) => {
+                send_construct_status(&status_update, ConstructStatus::Failed, progress_tx);
+                pass_result.push_diagnostic(&diag, construct_id, &add_ctx_to_diag);
+                continue;
+            }
+        };
 
         let evaluated_inputs_res = perform_signer_inputs_evaluation(
             &signer_instance,
             &cached_dependency_execution_results,
             &input_evaluation_results,
-            addon_defaults,
+            &addon_defaults,
             &package_id,
             &runbook_workspace_context,
             &runbook_execution_context,
@@ -130,14 +237,21 @@ pub async fn run_signers_evaluation(
             Ok(result) => match result {
                 CommandInputEvaluationStatus::Complete(result) => result,
                 CommandInputEvaluationStatus::NeedsUserInteraction(_) => {
+                    send_construct_status(
+                        &status_update,
+                        ConstructStatus::AwaitingInput,
+                        progress_tx,
+                    );
                     continue;
                 }
                 CommandInputEvaluationStatus::Aborted(_, diags) => {
+                    send_construct_status(&status_update, ConstructStatus::Failed, progress_tx);
                     pass_result.append_diagnostics(diags, construct_id, &add_ctx_to_diag);
                     continue;
                 }
             },
             Err(diags) => {
+                send_construct_status(&status_update, ConstructStatus::Failed, progress_tx);
                 pass_result.append_diagnostics(diags, construct_id, &add_ctx_to_diag);
                 return pass_result;
             }
@@ -174,6 +288,11 @@ pub async fn run_signers_evaluation(
                         .commands_execution_results
                         .insert(construct_did, CommandExecutionResult::new());
                     pass_result.actions.append(&mut new_actions);
+                    send_construct_status(
+                        &status_update,
+                        ConstructStatus::AwaitingInput,
+                        progress_tx,
+                    );
                     continue;
                 }
                 pass_result.actions.append(&mut new_actions);
@@ -191,6 +310,7 @@ pub async fn run_signers_evaluation(
                 }
 
                 pass_result.push_diagnostic(&diag, construct_id, &add_ctx_to_diag);
+                send_construct_status(&status_update, ConstructStatus::Failed, progress_tx);
 
                 return pass_result;
             }
@@ -200,6 +320,7 @@ pub async fn run_signers_evaluation(
             .commands_inputs_evaluation_results
             .insert(construct_did.clone(), evaluated_inputs.clone());
 
+        send_construct_status(&status_update, ConstructStatus::Executing, progress_tx);
         let res = signer
             .perform_activation(
                 &construct_did,
@@ -215,6 +336,7 @@ pub async fn run_signers_evaluation(
             Err((signers_state, diag)) => {
                 runbook_execution_context.signers_state = Some(signers_state);
                 pass_result.push_diagnostic(&diag, construct_id, &add_ctx_to_diag);
+                send_construct_status(&status_update, ConstructStatus::Failed, progress_tx);
                 return pass_result;
             }
         };
@@ -224,6 +346,7 @@ pub async fn run_signers_evaluation(
         };
 
         runbook_execution_context.commands_execution_results.insert(construct_did.clone(), result);
+        send_construct_status(&status_update, ConstructStatus::Completed, progress_tx);
     }
 
     pass_result
@@ -258,8 +381,9 @@ impl EvaluationPassResult {
             .append(&mut other.pending_background_tasks_constructs_uuids);
     }
 
+    /// Builds the error panel of the pass if it has errors. Its warnings are listed along with them.
     pub fn compile_diagnostics_to_block(&self) -> Option<Block> {
-        if self.diagnostics.is_empty() {
+        if !self.has_diagnostics() {
             return None;
         };
         Some(Block {
@@ -272,8 +396,31 @@ impl EvaluationPassResult {
     pub fn diagnostics(&self) -> Vec<Diagnostic> {
         self.diagnostics.clone()
     }
+    /// Returns true if the pass has errors, which interrupt the execution. Warnings and notes
+    /// don't.
     pub fn has_diagnostics(&self) -> bool {
-        !self.diagnostics.is_empty()
+        self.diagnostics.iter().any(|diag| diag.is_error())
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.diagnostics.iter().any(|diag| !diag.is_error())
+    }
+
+    /// Upgrades the warnings and notes of the pass to errors, for the pass to fail on them.
+    pub fn deny_warnings(&mut self) {
+        for diag in self.diagnostics.iter_mut() {
+            diag.level = DiagnosticLevel::Error;
+        }
+    }
+
+    /// Removes the warnings and notes of the pass, to be reported without interrupting the
+    /// execution.
+    pub fn take_warnings(&mut self) -> Vec<Diagnostic> {
+        let (errors, warnings) = std::mem::take(&mut self.diagnostics)
+            .into_iter()
+            .partition(|diag: &Diagnostic| diag.is_error());
+        self.diagnostics = errors;
+        warnings
     }
 
     pub fn push_diagnostic(
@@ -320,6 +467,155 @@ impl Display for EvaluationPassResult {
     }
 }
 
+/// Builds the status update of a command or signer construct, or `None` for any other
+/// construct.
+pub fn construct_status_update(
+    construct_did: &ConstructDid,
+    status: ConstructStatus,
+    runbook_workspace_context: &RunbookWorkspaceContext,
+    runbook_execution_context: &RunbookExecutionContext,
+) -> Option<ConstructStatusUpdate> {
+    let (name, namespace, matcher) = if let Some(command_instance) =
+        runbook_execution_context.commands_instances.get(construct_did)
+    {
+        (
+            &command_instance.name,
+            &command_instance.namespace,
+            &command_instance.specification.matcher,
+        )
+    } else if let Some(signer_instance) =
+        runbook_execution_context.signers_instances.get(construct_did)
+    {
+        (&signer_instance.name, &signer_instance.namespace, &signer_instance.specification.matcher)
+    } else {
+        return None;
+    };
+    let construct_id = runbook_workspace_context.constructs.get(construct_did)?;
+    let dependencies = runbook_execution_context
+        .constructs_dependency_names
+        .get(construct_did)
+        .cloned()
+        .unwrap_or_default();
+
+    Some(ConstructStatusUpdate {
+        construct_did: construct_did.clone(),
+        construct_type: construct_id.construct_type_str().to_string(),
+        name: name.clone(),
+        namespace: namespace.clone(),
+        matcher: matcher.clone(),
+        dependencies,
+        status,
+    })
+}
+
+/// Resolves, for each command and signer construct, the names of the constructs referenced by
+/// its inputs, e.g. `action.deploy`. The names are resolved once, when the execution context is
+/// built, and then reused by every status update.
+pub fn index_constructs_dependency_names(
+    runbook_workspace_context: &RunbookWorkspaceContext,
+    runbook_execution_context: &RunbookExecutionContext,
+) -> HashMap<ConstructDid, Vec<String>> {
+    let commands = runbook_execution_context.commands_instances.iter().map(|(did, instance)| {
+        (did, &instance.package_id, instance.get_expressions_referencing_commands_from_inputs())
+    });
+    let signers = runbook_execution_context.signers_instances.iter().map(|(did, instance)| {
+        (did, &instance.package_id, instance.get_expressions_referencing_commands_from_inputs())
+    });
+
+    let mut constructs_dependency_names = HashMap::new();
+    for (construct_did, package_id, references_expressions) in commands.chain(signers) {
+        let mut dependencies = vec![];
+        for (_input, expr) in references_expressions.into_iter() {
+            let Ok(Some((dependency, _, _))) = runbook_workspace_context
+                .try_resolve_construct_reference_in_expression(package_id, &expr)
+            else {
+                continue;
+            };
+            let Some(dependency_id) = runbook_workspace_context.constructs.get(&dependency) else {
+                continue;
+            };
+            let dependency_name =
+                format!("{}.{}", dependency_id.construct_type_str(), dependency_id.construct_name);
+            if !dependencies.contains(&dependency_name) {
+                dependencies.push(dependency_name);
+            }
+        }
+        constructs_dependency_names.insert(construct_did.clone(), dependencies);
+    }
+    constructs_dependency_names
+}
+
+pub fn publish_construct_statuses(
+    construct_dids: &[ConstructDid],
+    status: ConstructStatus,
+    runbook_workspace_context: &RunbookWorkspaceContext,
+    runbook_execution_context: &RunbookExecutionContext,
+    progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+) {
+    let updates = construct_dids
+        .iter()
+        .filter_map(|construct_did| {
+            construct_status_update(
+                construct_did,
+                status,
+                runbook_workspace_context,
+                runbook_execution_context,
+            )
+        })
+        .collect::<Vec<_>>();
+    if !updates.is_empty() {
+        let _ = progress_tx.send(BlockEvent::UpdateConstructStatuses(updates));
+    }
+}
+
+fn send_construct_status(
+    status_update: &Option<ConstructStatusUpdate>,
+    status: ConstructStatus,
+    progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+) {
+    let Some(status_update) = status_update else { return };
+    let _ = progress_tx.send(BlockEvent::UpdateConstructStatuses(vec![ConstructStatusUpdate {
+        status,
+        ..status_update.clone()
+    }]));
+}
+
+/// Publishes the initial status of every command and signer construct of a flow, so that
+/// constructs executed in a previous run are reported as completed.
+pub fn publish_initial_construct_statuses(
+    runbook_workspace_context: &RunbookWorkspaceContext,
+    runbook_execution_context: &RunbookExecutionContext,
+    progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+) {
+    let updates = runbook_execution_context
+        .order_for_commands_execution
+        .iter()
+        .filter_map(|construct_did| {
+            let status = match runbook_execution_context
+                .commands_execution_results
+                .get(construct_did)
+            {
+                Some(execution_results)
+                    if runbook_execution_context.commands_instances.contains_key(construct_did)
+                        && should_skip_construct_evaluation(execution_results) =>
+                {
+                    ConstructStatus::Completed
+                }
+                _ => ConstructStatus::Pending,
+            };
+            construct_status_update(
+                construct_did,
+                status,
+                runbook_workspace_context,
+                runbook_execution_context,
+            )
+        })
+        .collect::<Vec<_>>();
+    if !updates.is_empty() {
+        let _ = progress_tx.send(BlockEvent::UpdateConstructStatuses(updates));
+    }
+}
+
 fn should_skip_construct_evaluation(execution_result: &CommandExecutionResult) -> bool {
     // Check if the execution result indicates that the construct should be skipped
     let has_re_execute_command =
@@ -372,6 +668,32 @@ pub async fn run_constructs_evaluation(
     action_item_requests: &mut BTreeMap<ConstructDid, Vec<&mut ActionItemRequest>>,
     action_item_responses: &BTreeMap<ConstructDid, Vec<ActionItemResponse>>,
     progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+) -> EvaluationPassResult {
+    let mut pass_result = evaluate_constructs(
+        background_tasks_uuid,
+        runbook_workspace_context,
+        runbook_execution_context,
+        runtime_context,
+        supervision_context,
+        action_item_requests,
+        action_item_responses,
+        progress_tx,
+    )
+    .await;
+    // the review and output items show the display form of the addon values
+    pass_result.actions.format_values(&|value| runtime_context.format_display(value));
+    pass_result
+}
+
+async fn evaluate_constructs(
+    background_tasks_uuid: &Uuid,
+    runbook_workspace_context: &RunbookWorkspaceContext,
+    runbook_execution_context: &mut RunbookExecutionContext,
+    runtime_context: &RuntimeContext,
+    supervision_context: &RunbookSupervisionContext,
+    action_item_requests: &mut BTreeMap<ConstructDid, Vec<&mut ActionItemRequest>>,
+    action_item_responses: &BTreeMap<ConstructDid, Vec<ActionItemResponse>>,
+    progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
 ) -> EvaluationPassResult {
     let mut pass_result = EvaluationPassResult::new(background_tasks_uuid);
 
@@ -429,7 +751,8 @@ pub async fn run_constructs_evaluation(
         }
 
         if let Some(_) = runbook_execution_context.commands_instances.get(&construct_did) {
-            match evaluate_command_instance(
+            let diagnostics_count = pass_result.diagnostics.len();
+            let loop_evaluation_result = evaluate_command_instance(
                 &construct_did,
                 &mut pass_result,
                 &mut unexecutable_nodes,
@@ -442,8 +765,17 @@ pub async fn run_constructs_evaluation(
                 action_item_responses,
                 progress_tx,
             )
-            .await
-            {
+            .await;
+            if pass_result.diagnostics.len() > diagnostics_count {
+                publish_construct_statuses(
+                    &[construct_did.clone()],
+                    ConstructStatus::Failed,
+                    runbook_workspace_context,
+                    runbook_execution_context,
+                    progress_tx,
+                );
+            }
+            match loop_evaluation_result {
                 LoopEvaluationResult::Continue => continue,
                 LoopEvaluationResult::Bail => {
                     return pass_result;
@@ -509,11 +841,26 @@ pub async fn evaluate_command_instance(
         command_instance.namespace.clone(),
     );
 
+    let status_update = construct_status_update(
+        construct_did,
+        ConstructStatus::Pending,
+        runbook_workspace_context,
+        runbook_execution_context,
+    );
+
     let package_id = command_instance.package_id.clone();
     let construct_id = &runbook_workspace_context.expect_construct_id(&construct_did);
 
     let addon_context_key = (package_id.did(), command_instance.namespace.clone());
-    let addon_defaults = runbook_workspace_context.get_addon_defaults(&addon_context_key);
+    let addon_defaults = match runbook_workspace_context
+        .get_addon_defaults(&addon_context_key, command_instance.get_addon_instance().as_deref())
+    {
+        Ok(addon_defaults) => addon_defaults,
+        Err(diag) => {
+            pass_result.push_diagnostic(&diag, construct_id, &add_ctx_to_diag);
+            return LoopEvaluationResult::Bail;
+        }
+    };
 
     let input_evaluation_results = runbook_execution_context
         .commands_inputs_evaluation_results
@@ -547,7 +894,7 @@ pub async fn evaluate_command_instance(
         command_instance,
         &cached_dependency_execution_results,
         &input_evaluation_results.as_ref(),
-        addon_defaults,
+        &addon_defaults,
         &action_item_responses.get(&construct_did),
         &package_id,
         runbook_workspace_context,
@@ -561,6 +908,7 @@ pub async fn evaluate_command_instance(
         Ok(result) => match result {
             CommandInputEvaluationStatus::Complete(result) => result,
             CommandInputEvaluationStatus::NeedsUserInteraction(_) => {
+                send_construct_status(&status_update, ConstructStatus::AwaitingInput, progress_tx);
                 return LoopEvaluationResult::Continue;
             }
             CommandInputEvaluationStatus::Aborted(_, diags) => {
@@ -595,12 +943,20 @@ pub async fn evaluate_command_instance(
                     return LoopEvaluationResult::Bail;
                 }
                 PreConditionEvaluationResult::SkipDownstream => {
+                    send_construct_status(&status_update, ConstructStatus::Skipped, progress_tx);
                     if let Some(deps) =
                         runbook_execution_context.commands_dependencies.get(&construct_did)
                     {
                         for dep in deps.iter() {
                             unexecutable_nodes.insert(dep.clone());
                         }
+                        publish_construct_statuses(
+                            deps,
+                            ConstructStatus::Skipped,
+                            runbook_workspace_context,
+                            runbook_execution_context,
+                            progress_tx,
+                        );
                     }
                     return LoopEvaluationResult::Continue;
                 }
@@ -625,6 +981,7 @@ pub async fn evaluate_command_instance(
                     &evaluated_inputs,
                     signers,
                     &runbook_execution_context.signers_instances,
+                    &runtime_context.authorization_context,
                 )
                 .await
             {
@@ -699,6 +1056,11 @@ pub async fn evaluate_command_instance(
                 let signers = match res {
                     Ok((updated_signers, mut new_actions)) => {
                         if new_actions.has_pending_actions() {
+                            send_construct_status(
+                                &status_update,
+                                ConstructStatus::AwaitingInput,
+                                progress_tx,
+                            );
                             pass_result.actions.append(&mut new_actions);
                             runbook_execution_context.signers_state = Some(updated_signers);
                             if let Some(deps) =
@@ -733,6 +1095,7 @@ pub async fn evaluate_command_instance(
                     action_item_requests.get_mut(&construct_did).unwrap_or(&mut empty_vec);
                 let action_items_response = action_item_responses.get(&nested_construct_did);
 
+                send_construct_status(&status_update, ConstructStatus::Executing, progress_tx);
                 let execution_result = command_instance
                     .perform_signed_execution(
                         &construct_did,
@@ -780,6 +1143,11 @@ pub async fn evaluate_command_instance(
                 ) {
                     Ok(mut new_actions) => {
                         if new_actions.has_pending_actions() {
+                            send_construct_status(
+                                &status_update,
+                                ConstructStatus::AwaitingInput,
+                                progress_tx,
+                            );
                             pass_result.actions.append(&mut new_actions);
                             if let Some(deps) =
                                 runbook_execution_context.commands_dependencies.get(&construct_did)
@@ -807,6 +1175,7 @@ pub async fn evaluate_command_instance(
                     action_item_requests.get_mut(&construct_did).unwrap_or(&mut empty_vec);
                 let action_items_response = action_item_responses.get(&nested_construct_did);
 
+                send_construct_status(&status_update, ConstructStatus::Executing, progress_tx);
                 let execution_result = {
                     command_instance
                         .perform_execution(
@@ -844,6 +1213,8 @@ pub async fn evaluate_command_instance(
                     return LoopEvaluationResult::Continue;
                 }
             };
+            let warnings = std::mem::take(&mut execution_result.diagnostics);
+            pass_result.append_diagnostics(warnings, construct_id, &add_ctx_to_diag);
 
             if let RunbookExecutionMode::Partial(ref mut executed_constructs) =
                 runbook_execution_context.execution_mode
@@ -891,6 +1262,11 @@ pub async fn evaluate_command_instance(
                 pass_result
                     .pending_background_tasks_constructs_uuids
                     .push((nested_construct_did.clone(), construct_did.clone()));
+                send_construct_status(
+                    &status_update,
+                    ConstructStatus::BackgroundTaskRunning,
+                    progress_tx,
+                );
 
                 // we need to be sure that each background task is completed before continuing the execution.
                 // so we will return a Continue result to ensure that the next nested evaluation is not executed.
@@ -932,7 +1308,7 @@ pub async fn evaluate_command_instance(
         command_instance,
         &cached_dependency_execution_results,
         &input_evaluation_results.as_ref(),
-        addon_defaults,
+        &addon_defaults,
         &action_item_responses.get(&construct_did),
         &package_id,
         runbook_workspace_context,
@@ -980,12 +1356,20 @@ pub async fn evaluate_command_instance(
                     return LoopEvaluationResult::Bail;
                 }
                 PostConditionEvaluationResult::SkipDownstream => {
+                    send_construct_status(&status_update, ConstructStatus::Completed, progress_tx);
                     if let Some(deps) =
                         runbook_execution_context.commands_dependencies.get(&construct_did)
                     {
                         for dep in deps.iter() {
                             unexecutable_nodes.insert(dep.clone());
                         }
+                        publish_construct_statuses(
+                            deps,
+                            ConstructStatus::Skipped,
+                            runbook_workspace_context,
+                            runbook_execution_context,
+                            progress_tx,
+                        );
                     }
                     return LoopEvaluationResult::Continue;
                 }
@@ -1001,6 +1385,7 @@ pub async fn evaluate_command_instance(
                         }
                     }
                     pass_result.nodes_to_re_execute.push(construct_did.clone());
+                    send_construct_status(&status_update, ConstructStatus::Pending, progress_tx);
                     return LoopEvaluationResult::Continue;
                 }
             }
@@ -1014,6 +1399,7 @@ pub async fn evaluate_command_instance(
     runbook_execution_context
         .commands_execution_results
         .insert(construct_did.clone(), command_execution_result);
+    send_construct_status(&status_update, ConstructStatus::Completed, progress_tx);
 
     if let RunbookExecutionMode::Partial(ref mut executed_constructs) =
         runbook_execution_context.execution_mode
@@ -1214,7 +1600,9 @@ pub fn eval_expression(
             ) {
                 (Some(value), _, _) => Value::integer(value.into()),
                 (_, Some(value), _) => Value::integer(value.into()),
-                (_, _, Some(value)) => Value::float(value),
+                (_, _, Some(value)) => decimal_from_number_literal(formatted_number, value)
+                    .map(Value::decimal)
+                    .unwrap_or(Value::float(value)),
                 (None, None, None) => unreachable!(), // todo(lgalabru): return Diagnostic
             }
         }
@@ -1346,7 +1734,7 @@ pub fn eval_expression(
                 "Directly referencing a variable is not supported. Did you mean `variable.{}`?",
                 _decorated_var.as_str()
             )
-            .into());
+            .with_diagnostic_code(&UNSUPPORTED_VARIABLE_REFERENCE));
         }
         // Represents conditional operator which selects one of two expressions based on the outcome of a boolean expression.
         Expression::Conditional(_conditional) => {
@@ -1356,8 +1744,33 @@ pub fn eval_expression(
         Expression::FuncCall(function_call) => {
             let func_namespace = function_call.name.namespace.first().map(|n| n.to_string());
             let func_name = function_call.name.name.to_string();
+            let mut arg_exprs = function_call.args.iter().collect::<Vec<_>>();
+            let expand_final = function_call.args.expand_final();
+
+            // a trailing object literal keyed by inputs of the function holds named arguments
+            let named_args_expr = match arg_exprs.last() {
+                Some(Expression::Object(object)) if !expand_final => {
+                    let keys = object
+                        .iter()
+                        .map(|(key, _)| match key {
+                            txtx_addon_kit::hcl::expr::ObjectKey::Ident(ident) => {
+                                Some(ident.as_str())
+                            }
+                            txtx_addon_kit::hcl::expr::ObjectKey::Expression(_) => None,
+                        })
+                        .collect::<Option<Vec<_>>>();
+                    let names_inputs = keys.is_some_and(|keys| {
+                        runtime_context
+                            .get_function(package_id.did(), func_namespace.clone(), &func_name)
+                            .is_ok_and(|spec| spec.names_inputs(keys.into_iter()))
+                    });
+                    names_inputs.then(|| arg_exprs.pop()).flatten()
+                }
+                _ => None,
+            };
+
             let mut args = vec![];
-            for expr in function_call.args.iter() {
+            for expr in arg_exprs.into_iter().chain(named_args_expr) {
                 let value = match eval_expression(
                     expr,
                     dependencies_execution_results,
@@ -1376,6 +1789,35 @@ pub fn eval_expression(
                 };
                 args.push(value);
             }
+            let named_args = match named_args_expr {
+                Some(_) => match args.pop() {
+                    Some(Value::Object(named_args)) => Some(named_args),
+                    _ => None,
+                },
+                None => None,
+            };
+            // `fn(a, list...)` expands the items of the final argument into arguments
+            if expand_final {
+                match args.pop() {
+                    Some(Value::Array(items)) => args.extend(*items),
+                    Some(other) => {
+                        return Ok(ExpressionEvaluationStatus::CompleteErr(diagnosed_error!(
+                            "function '{}': only a list can be expanded into arguments, found {}",
+                            func_name,
+                            other.get_type().to_string()
+                        )))
+                    }
+                    None => {}
+                }
+            }
+            if named_args.is_some() {
+                let spec = runtime_context.get_function(
+                    package_id.did(),
+                    func_namespace.clone(),
+                    &func_name,
+                )?;
+                args = spec.resolve_arguments(args, named_args)?;
+            }
             runtime_context
                 .execute_function(
                     package_id.did(),
@@ -1396,14 +1838,16 @@ pub fn eval_expression(
                     return Err(diagnosed_error!(
                         "unable to resolve expression '{}'",
                         expr.to_string().trim()
-                    ));
+                    )
+                    .with_diagnostic_code(&UNRESOLVED_CONSTRUCT_REFERENCE));
                 }
                 Err(e) => {
                     return Err(diagnosed_error!(
                         "unable to resolve expression '{}': {}",
                         expr.to_string().trim(),
                         e
-                    ))
+                    )
+                    .with_diagnostic_code(&UNRESOLVED_CONSTRUCT_REFERENCE))
                 }
             };
 
@@ -1541,6 +1985,24 @@ pub fn eval_expression(
     Ok(ExpressionEvaluationStatus::CompleteOk(value))
 }
 
+/// Keeps number literals that a float would silently round (integers beyond the `u64` range,
+/// such as `1e27`, or fractions with more significant digits than a `f64` holds) as decimals.
+fn decimal_from_number_literal(
+    formatted_number: &Formatted<Number>,
+    value: f64,
+) -> Option<BigDecimal> {
+    let repr: String = formatted_number.as_repr()?.chars().filter(|c| !c.is_whitespace()).collect();
+    let decimal = BigDecimal::from_str(&repr).ok()?;
+    let rounded = BigDecimal::from_str(&value.to_string()).ok();
+    let beyond_integers =
+        decimal.is_integer() && decimal.to_i64().is_none() && decimal.to_u64().is_none();
+    if beyond_integers || rounded.as_ref() != Some(&decimal) {
+        Some(decimal)
+    } else {
+        None
+    }
+}
+
 // pub struct EvaluatedExpression {
 //     value: Value,
 // }
@@ -1699,6 +2161,21 @@ pub fn perform_inputs_evaluation(
                     ActionItemResponseType::ProvideInput(update) => {
                         results.inputs.insert(&update.input_name, update.updated_value.clone());
                     }
+                    ActionItemResponseType::ProvideFile(file) => {
+                        match general_purpose::STANDARD.decode(&file.content) {
+                            Ok(content) => {
+                                results.inputs.insert(&file.input_name, file.to_value(content))
+                            }
+                            Err(e) => {
+                                fatal_error = true;
+                                diags.push(diagnosed_error!(
+                                    "unable to decode file '{}': {}",
+                                    file.filename,
+                                    e
+                                ));
+                            }
+                        }
+                    }
                     _ => {}
                 }
             })
@@ -1706,6 +2183,7 @@ pub fn perform_inputs_evaluation(
         None => {}
     }
 
+    let sensitive_inputs = get_sensitive_inputs(&inputs);
     for input in inputs.into_iter() {
         let input_name = input.name();
         let input_typing = input.typing();
@@ -1935,7 +2413,10 @@ pub fn perform_inputs_evaluation(
             }
 
             if !object_values.is_empty() {
-                results.insert(&input_name, Value::object(object_values));
+                results.insert(
+                    &input_name,
+                    addon_defaults.merge_object_default(&input_name, Value::object(object_values)),
+                );
             }
         } else if let Some(_) = input.as_array() {
             let mut array_values = vec![];
@@ -2045,9 +2526,28 @@ pub fn perform_inputs_evaluation(
                     continue;
                 }
             };
+            // malformed values of typed inputs, such as datetimes, are rejected here rather than by the addon
+            let value = match input_typing.coerce_value(value) {
+                Ok(value) => value,
+                Err(e) => {
+                    fatal_error = true;
+                    let e = diagnosed_error!("invalid input '{}': {}", input_name, e)
+                        .with_diagnostic_code(&INVALID_INPUT_VALUE);
+                    results.unevaluated_inputs.insert(input_name.clone(), Some(e.clone()));
+                    diags.push(e);
+                    continue;
+                }
+            };
+            if let Err(e) = record_matched_type(&input_name, input_typing, &value, &mut results) {
+                fatal_error = true;
+                results.unevaluated_inputs.insert(input_name.clone(), Some(e.clone()));
+                diags.push(e);
+                continue;
+            }
             results.insert(&input_name, value);
         }
     }
+    register_sensitive_inputs(&sensitive_inputs, &results, runtime_context);
 
     if fatal_error {
         return Ok(CommandInputEvaluationStatus::Aborted(results, diags));
@@ -2077,6 +2577,7 @@ pub fn perform_signer_inputs_evaluation(
     let inputs = signer_instance.inputs();
     let mut fatal_error = false;
 
+    let sensitive_inputs = get_sensitive_inputs(&inputs);
     for input in inputs.into_iter() {
         let input_name = input.name();
 
@@ -2264,10 +2765,16 @@ pub fn perform_signer_inputs_evaluation(
                     }
                 }
             };
+            if let Err(e) = record_matched_type(&input_name, input.typing(), &value, &mut results) {
+                fatal_error = true;
+                diags.push(e);
+                continue;
+            }
 
             results.insert(&input_name, value);
         }
     }
+    register_sensitive_inputs(&sensitive_inputs, &results, runtime_context);
 
     let status = match (fatal_error, require_user_interaction) {
         (false, false) => CommandInputEvaluationStatus::Complete(results),
@@ -2277,6 +2784,43 @@ pub fn perform_signer_inputs_evaluation(
     Ok(status)
 }
 
+/// Checks the value of an input accepting several types, and records the member it matched for
+/// the addon to tell the representations apart.
+fn record_matched_type(
+    input_name: &str,
+    input_typing: &Type,
+    value: &Value,
+    results: &mut CommandInputsEvaluationResult,
+) -> Result<(), Diagnostic> {
+    let Type::OneOf(_) = input_typing else {
+        return Ok(());
+    };
+    let matched_type = input_typing.matching_type(value).map_err(|e| {
+        diagnosed_error!("invalid input '{}': {}", input_name, e.message)
+            .with_diagnostic_code(&INVALID_INPUT_VALUE)
+    })?;
+    results.matched_types.insert(input_name.to_string(), matched_type.clone());
+    Ok(())
+}
+
+fn get_sensitive_inputs(inputs: &Vec<Box<dyn EvaluatableInput>>) -> Vec<String> {
+    inputs.iter().filter(|input| input.sensitive()).map(|input| input.name()).collect()
+}
+
+/// Records the values of the sensitive inputs, so that they get redacted from the diagnostics
+/// and events leaving the runtime.
+fn register_sensitive_inputs(
+    sensitive_inputs: &Vec<String>,
+    results: &CommandInputsEvaluationResult,
+    runtime_context: &RuntimeContext,
+) {
+    for input_name in sensitive_inputs.iter() {
+        if let Some(value) = results.inputs.get_value(input_name) {
+            runtime_context.secrets.register(value);
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct EvaluateMapInputResult {
     result: CommandInputsEvaluationResult,
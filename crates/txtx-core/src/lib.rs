@@ -26,22 +26,32 @@ use ::std::future::Future;
 use ::std::pin::Pin;
 use ::std::thread::sleep;
 use ::std::time::Duration;
+use ::std::time::Instant;
 
 use crate::runbook::flow_context::FlowContext;
 use constants::ACTION_ITEM_ENV;
 use constants::ACTION_ITEM_GENESIS;
 use constants::ACTION_ITEM_VALIDATE_BLOCK;
+use errors::codes::{ADDON_END_FAILED, ADDON_START_FAILED};
+use eval::publish_construct_statuses;
+use eval::publish_initial_construct_statuses;
 use eval::run_constructs_evaluation;
 use eval::run_signers_evaluation;
+use eval::EvaluationPassResult;
 use kit::constants::ACTION_ITEM_CHECK_BALANCE;
 use runbook::get_source_context_for_diagnostic;
+use runbook::RunbookSources;
 use tokio::sync::broadcast::error::TryRecvError;
+use txtx_addon_kit::channel::RecvTimeoutError;
 use txtx_addon_kit::channel::Sender;
 use txtx_addon_kit::constants::ACTION_ITEM_CHECK_ADDRESS;
+use txtx_addon_kit::futures::channel::mpsc::UnboundedReceiver;
+use txtx_addon_kit::futures::future::{poll_fn, select, Either};
+use txtx_addon_kit::futures::stream::{FuturesUnordered, StreamExt};
 use txtx_addon_kit::hcl::Span;
 use txtx_addon_kit::types::block_id::BlockId;
 use txtx_addon_kit::types::commands::CommandExecutionResult;
-use txtx_addon_kit::types::diagnostics::Diagnostic;
+use txtx_addon_kit::types::diagnostics::{Diagnostic, DiagnosticCode, DiagnosticLevel};
 use txtx_addon_kit::types::frontend::ActionItemRequest;
 use txtx_addon_kit::types::frontend::ActionItemRequestType;
 use txtx_addon_kit::types::frontend::ActionItemRequestUpdate;
@@ -51,15 +61,23 @@ use txtx_addon_kit::types::frontend::ActionItemStatus;
 use txtx_addon_kit::types::frontend::Actions;
 use txtx_addon_kit::types::frontend::Block;
 use txtx_addon_kit::types::frontend::BlockEvent;
+use txtx_addon_kit::types::frontend::ConstructStatus;
 use txtx_addon_kit::types::frontend::ErrorPanelData;
 use txtx_addon_kit::types::frontend::InputOption;
+use txtx_addon_kit::types::frontend::LogDispatcher;
+use txtx_addon_kit::types::frontend::LogLevel;
 use txtx_addon_kit::types::frontend::NormalizedActionItemRequestUpdate;
 use txtx_addon_kit::types::frontend::Panel;
 use txtx_addon_kit::types::frontend::PickInputOptionRequest;
 use txtx_addon_kit::types::frontend::ReviewedInputResponse;
 use txtx_addon_kit::types::frontend::ValidateBlockData;
+use txtx_addon_kit::types::heartbeat::{
+    BackgroundTasksHeartbeats, HeartbeatsTick, HEARTBEAT_INTERVAL,
+};
+use txtx_addon_kit::types::redaction::SecretRegistry;
 use txtx_addon_kit::types::types::RunbookSupervisionContext;
 use txtx_addon_kit::types::ConstructDid;
+use txtx_addon_kit::types::RunbookLifecycleContext;
 use txtx_addon_kit::uuid::Uuid;
 use types::Runbook;
 
@@ -74,9 +92,30 @@ lazy_static! {
       ;
 }
 
+/// Runs the runbook without supervision. The values of sensitive inputs are redacted from the
+/// events sent to `progress_tx`, from the returned diagnostics and from the warnings recorded in
+/// `runbook.warnings`.
 pub async fn start_unsupervised_runbook_runloop(
     runbook: &mut Runbook,
     progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+) -> Result<(), Vec<Diagnostic>> {
+    let secrets = SecretRegistry::new();
+    runbook.runtime_context.secrets = secrets.clone();
+    let progress_tx = secrets.redacting_sender(progress_tx);
+    let (started_addons, mut res) = match report_deprecations(runbook, &progress_tx) {
+        Ok(()) => start_runbook_addons(runbook, &progress_tx),
+        Err(diags) => (vec![], Err(diags)),
+    };
+    if res.is_ok() {
+        res = run_unsupervised_runbook_runloop(runbook, &progress_tx).await;
+    }
+    end_runbook_addons(runbook, &started_addons, &mut res, &progress_tx);
+    end_run(runbook, &secrets, res)
+}
+
+async fn run_unsupervised_runbook_runloop(
+    runbook: &mut Runbook,
+    progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
 ) -> Result<(), Vec<Diagnostic>> {
     runbook.supervision_context = RunbookSupervisionContext {
         review_input_default_values: false,
@@ -92,7 +131,13 @@ pub async fn start_unsupervised_runbook_runloop(
         let mut action_item_requests = BTreeMap::new();
         let action_item_responses = BTreeMap::new();
 
-        let pass_results = run_signers_evaluation(
+        publish_initial_construct_statuses(
+            &flow_context.workspace_context,
+            &flow_context.execution_context,
+            &progress_tx,
+        );
+
+        let mut pass_results = run_signers_evaluation(
             &flow_context.workspace_context,
             &mut flow_context.execution_context,
             &runbook.runtime_context,
@@ -103,6 +148,14 @@ pub async fn start_unsupervised_runbook_runloop(
         )
         .await;
 
+        report_pass_warnings(
+            &mut pass_results,
+            &mut runbook.warnings,
+            runbook.deny_warnings,
+            &runbook.sources,
+            &progress_tx,
+        );
+
         if pass_results.actions.has_pending_actions() {
             return Err(vec![diagnosed_error!(
                 "unsupervised executions should not be generating actions"
@@ -131,6 +184,14 @@ pub async fn start_unsupervised_runbook_runloop(
             )
             .await;
 
+            report_pass_warnings(
+                &mut pass_results,
+                &mut runbook.warnings,
+                runbook.deny_warnings,
+                &runbook.sources,
+                &progress_tx,
+            );
+
             if pass_results.has_diagnostics() {
                 return Err(pass_results.with_spans_filled(&runbook.sources));
             }
@@ -156,6 +217,7 @@ pub async fn start_unsupervised_runbook_runloop(
                     background_tasks_contructs_dids,
                     background_tasks_futures,
                     flow_context,
+                    &progress_tx,
                 )
                 .await
                 .map_err(|mut diag| {
@@ -176,7 +238,43 @@ pub async fn start_unsupervised_runbook_runloop(
     Ok(())
 }
 
+/// Runs the runbook under supervision. The values of sensitive inputs are redacted from the
+/// events sent to `block_tx`, from the returned diagnostics and from the warnings recorded in
+/// `runbook.warnings`.
 pub async fn start_supervised_runbook_runloop(
+    runbook: &mut Runbook,
+    block_tx: Sender<BlockEvent>,
+    action_item_responses_rx: tokio::sync::broadcast::Receiver<ActionItemResponse>,
+) -> Result<(), Vec<Diagnostic>> {
+    let secrets = SecretRegistry::new();
+    runbook.runtime_context.secrets = secrets.clone();
+    let block_tx = secrets.redacting_sender(&block_tx);
+    let (started_addons, mut res) = match report_deprecations(runbook, &block_tx) {
+        Ok(()) => start_runbook_addons(runbook, &block_tx),
+        Err(diags) => (vec![], Err(diags)),
+    };
+    if res.is_ok() {
+        res = run_supervised_runbook_runloop(runbook, block_tx.clone(), action_item_responses_rx)
+            .await;
+    }
+    end_runbook_addons(runbook, &started_addons, &mut res, &block_tx);
+    end_run(runbook, &secrets, res)
+}
+
+/// Egress of the diagnostics of a run: the warnings and errors are scrubbed of the secrets of
+/// the run, which are then released. Events are scrubbed by the sender of the run, which keeps
+/// the secrets until the last event is sent.
+fn end_run(
+    runbook: &mut Runbook,
+    secrets: &SecretRegistry,
+    res: Result<(), Vec<Diagnostic>>,
+) -> Result<(), Vec<Diagnostic>> {
+    runbook.warnings = secrets.redact_diagnostics(std::mem::take(&mut runbook.warnings));
+    runbook.runtime_context.secrets = SecretRegistry::new();
+    res.map_err(|diags| secrets.redact_diagnostics(diags))
+}
+
+async fn run_supervised_runbook_runloop(
     runbook: &mut Runbook,
     block_tx: Sender<BlockEvent>,
     mut action_item_responses_rx: tokio::sync::broadcast::Receiver<ActionItemResponse>,
@@ -254,7 +352,7 @@ pub async fn start_supervised_runbook_runloop(
                 runbook,
                 &payload,
                 &mut action_item_requests,
-                &action_item_responses,
+                action_item_responses,
                 &block_tx.clone(),
                 current_flow_index,
                 total_flows_count,
@@ -315,6 +413,7 @@ pub async fn start_supervised_runbook_runloop(
                             background_tasks_contructs_dids,
                             background_tasks_futures,
                             flow_context,
+                            &block_tx,
                         )
                         .await
                         .map_err(|mut diag| {
@@ -342,6 +441,14 @@ pub async fn start_supervised_runbook_runloop(
                     )
                     .await;
 
+                    report_pass_warnings(
+                        &mut pass_results,
+                        &mut runbook.warnings,
+                        runbook.deny_warnings,
+                        &runbook.sources,
+                        &block_tx,
+                    );
+
                     // if there were errors, return them to complete execution
                     if let Some(error_event) = pass_results.compile_diagnostics_to_block() {
                         let _ = block_tx.send(BlockEvent::Error(error_event));
@@ -374,6 +481,8 @@ pub async fn start_supervised_runbook_runloop(
                         for (key, action_items) in grouped_actions_items.into_iter() {
                             actions.push_group(key.as_str(), action_items);
                         }
+                        actions
+                            .format_values(&|value| runbook.runtime_context.format_display(value));
                         pass_results.actions.append(&mut actions);
 
                         flow_execution_completed = true;
@@ -432,6 +541,7 @@ pub async fn start_supervised_runbook_runloop(
             }
             ActionItemResponseType::PickInputOption(_) => {}
             ActionItemResponseType::ProvideInput(_) => {}
+            ActionItemResponseType::ProvideFile(_) => {}
             ActionItemResponseType::ReviewInput(ReviewedInputResponse {
                 value_checked,
                 force_execution,
@@ -480,6 +590,13 @@ pub async fn start_supervised_runbook_runloop(
                         &block_tx.clone(),
                     )
                     .await;
+                    report_pass_warnings(
+                        &mut pass_results,
+                        &mut runbook.warnings,
+                        runbook.deny_warnings,
+                        &runbook.sources,
+                        &block_tx,
+                    );
                     let mut updated_actions = vec![];
                     for action in pass_results
                         .actions
@@ -545,6 +662,13 @@ pub async fn start_supervised_runbook_runloop(
                     &block_tx.clone(),
                 )
                 .await;
+                report_pass_warnings(
+                    &mut pass_results,
+                    &mut runbook.warnings,
+                    runbook.deny_warnings,
+                    &runbook.sources,
+                    &block_tx,
+                );
 
                 let mut updated_actions = vec![];
                 for action in pass_results
@@ -575,6 +699,154 @@ pub async fn start_supervised_runbook_runloop(
     }
 }
 
+/// Records the deprecated items used by the runbook in `runbook.deprecations`, and warns about
+/// each of them.
+fn report_deprecations(
+    runbook: &mut Runbook,
+    progress_tx: &Sender<BlockEvent>,
+) -> Result<(), Vec<Diagnostic>> {
+    let (deprecations, diags) = runbook.collect_deprecations().into_iter().unzip();
+    runbook.deprecations = deprecations;
+    report_runbook_warnings(diags, runbook, progress_tx)
+}
+
+/// Calls the `on_runbook_start` hook of the addons instantiated by the runbook, stopping at the
+/// first failure. Returns the namespaces of the addons started, to be ended once the run is over.
+fn start_runbook_addons(
+    runbook: &mut Runbook,
+    progress_tx: &Sender<BlockEvent>,
+) -> (Vec<String>, Result<(), Vec<Diagnostic>>) {
+    let mut started_addons = vec![];
+    let mut reported = vec![];
+    let mut result = Ok(());
+    for (addon, addons_defaults) in runbook.get_instantiated_addons() {
+        let namespace = addon.get_namespace().to_string();
+        let mut ctx = RunbookLifecycleContext::new(
+            &runbook.runbook_id,
+            addons_defaults,
+            LogDispatcher::new(Uuid::new_v4(), &namespace, progress_tx),
+        );
+        let res = addon.on_runbook_start(&mut ctx);
+        reported.append(&mut ctx.take_diagnostics());
+        if let Err(diag) = res {
+            result = Err(vec![with_default_code(diag, &ADDON_START_FAILED)]);
+            break;
+        }
+        started_addons.push(namespace);
+    }
+    if let Err(diags) = report_runbook_warnings(reported, runbook, progress_tx) {
+        if result.is_ok() {
+            result = Err(diags);
+        }
+    }
+    (started_addons, result)
+}
+
+/// Calls the `on_runbook_end` hook of the addons started, in reverse order. Their failures are
+/// reported as warnings.
+fn end_runbook_addons(
+    runbook: &mut Runbook,
+    started_addons: &[String],
+    result: &mut Result<(), Vec<Diagnostic>>,
+    progress_tx: &Sender<BlockEvent>,
+) {
+    let mut reported = vec![];
+    for (addon, addons_defaults) in runbook.get_instantiated_addons().into_iter().rev() {
+        let namespace = addon.get_namespace();
+        if !started_addons.iter().any(|started| started == namespace) {
+            continue;
+        }
+        let mut ctx = RunbookLifecycleContext::new(
+            &runbook.runbook_id,
+            addons_defaults,
+            LogDispatcher::new(Uuid::new_v4(), namespace, progress_tx),
+        );
+        let res = addon.on_runbook_end(&mut ctx, result);
+        reported.append(&mut ctx.take_diagnostics());
+        if let Err(mut diag) = res {
+            diag.level = DiagnosticLevel::Warning;
+            reported.push(with_default_code(diag, &ADDON_END_FAILED));
+        }
+    }
+    if let Err(mut diags) = report_runbook_warnings(reported, runbook, progress_tx) {
+        match result {
+            Ok(()) => *result = Err(diags),
+            Err(errors) => errors.append(&mut diags),
+        }
+    }
+}
+
+fn with_default_code(diag: Diagnostic, code: &DiagnosticCode) -> Diagnostic {
+    match diag.code {
+        Some(_) => diag,
+        None => diag.with_diagnostic_code(code),
+    }
+}
+
+/// Records the warnings reported outside of the evaluation passes, by the lifecycle hooks of the
+/// addons or for deprecations, or fails with them when warnings are denied.
+fn report_runbook_warnings(
+    diags: Vec<Diagnostic>,
+    runbook: &mut Runbook,
+    progress_tx: &Sender<BlockEvent>,
+) -> Result<(), Vec<Diagnostic>> {
+    if diags.is_empty() {
+        return Ok(());
+    }
+    if runbook.deny_warnings {
+        return Err(diags
+            .into_iter()
+            .map(|mut diag| {
+                diag.level = DiagnosticLevel::Error;
+                diag
+            })
+            .collect());
+    }
+    for diag in diags {
+        log_warning(&diag, progress_tx);
+        runbook.warnings.push(diag);
+    }
+    Ok(())
+}
+
+fn log_warning(diag: &Diagnostic, progress_tx: &Sender<BlockEvent>) {
+    let summary = match diag.level {
+        DiagnosticLevel::Note => "Note",
+        _ => "Warning",
+    };
+    let _ = progress_tx.send(BlockEvent::static_log(
+        LogLevel::Warn,
+        Uuid::new_v4(),
+        "txtx::diagnostics".into(),
+        summary,
+        diag.to_string(),
+    ));
+}
+
+/// Reports the warnings and notes of a pass without interrupting the execution: they're logged
+/// to `progress_tx` and recorded in `warnings`. When `deny_warnings` is set, they're upgraded to
+/// errors instead, failing the pass.
+fn report_pass_warnings(
+    pass_results: &mut EvaluationPassResult,
+    warnings: &mut Vec<Diagnostic>,
+    deny_warnings: bool,
+    sources: &RunbookSources,
+    progress_tx: &Sender<BlockEvent>,
+) {
+    if !pass_results.has_warnings() {
+        return;
+    }
+    if deny_warnings {
+        pass_results.deny_warnings();
+        return;
+    }
+    pass_results.fill_diagnostic_span(sources);
+    for diag in pass_results.take_warnings() {
+        log_warning(&diag, progress_tx);
+        warnings.push(diag);
+    }
+}
+
 pub fn register_action_items_from_actions(
     actions: &Actions,
     action_item_requests: &mut BTreeMap<BlockId, ActionItemRequest>,
@@ -613,7 +885,7 @@ pub async fn reset_runbook_execution(
     runbook: &mut Runbook,
     payload: &ActionItemResponseType,
     action_item_requests: &mut BTreeMap<BlockId, ActionItemRequest>,
-    action_item_responses: &BTreeMap<ConstructDid, Vec<ActionItemResponse>>,
+    action_item_responses: &mut BTreeMap<ConstructDid, Vec<ActionItemResponse>>,
     progress_tx: &Sender<BlockEvent>,
     current_flow_index: usize,
     total_flows_count: usize,
@@ -625,12 +897,23 @@ pub async fn reset_runbook_execution(
         );
     };
 
+    let previous_signers = runbook.get_signers_dids();
+
     let reset = runbook.update_inputs_selector(Some(environment_key.to_string()), true).await?;
 
     if !reset {
         unimplemented!()
     }
 
+    // The signers are activated from scratch under the new environment: their state is rebuilt
+    // along with the contexts, and the actions collected under the previous environment are
+    // dropped, since an alias can now bind another signer, with other inputs.
+    for construct_did in previous_signers.union(&runbook.get_signers_dids()) {
+        action_item_responses.remove(construct_did);
+        action_item_requests
+            .retain(|_, request| request.construct_did.as_ref() != Some(construct_did));
+    }
+
     let _ = progress_tx.send(BlockEvent::Clear);
     let genesis_events = build_genesis_panel(
         runbook,
@@ -679,6 +962,12 @@ pub async fn build_genesis_panel(
     }
     actions.push_panel("runbook checklist", "");
 
+    publish_initial_construct_statuses(
+        &flow_context.workspace_context,
+        &flow_context.execution_context,
+        progress_tx,
+    );
+
     if environments.len() > 0 {
         let input_options: Vec<InputOption> = environments
             .iter()
@@ -714,6 +1003,14 @@ pub async fn build_genesis_panel(
     )
     .await;
 
+    report_pass_warnings(
+        &mut pass_result,
+        &mut runbook.warnings,
+        runbook.deny_warnings,
+        &runbook.sources,
+        progress_tx,
+    );
+
     if pass_result.has_diagnostics() {
         return Err(pass_result.with_spans_filled(&runbook.sources));
     }
@@ -764,6 +1061,7 @@ pub async fn process_background_tasks(
         Pin<Box<dyn Future<Output = Result<CommandExecutionResult, Diagnostic>> + Send>>,
     >,
     flow_context: &mut FlowContext,
+    progress_tx: &Sender<BlockEvent>,
 ) -> Result<(), Diagnostic> {
     if let Some(SupervisedBackgroundTaskContext { block_tx, action_item_id, .. }) =
         supervised_context.as_ref()
@@ -776,8 +1074,37 @@ pub async fn process_background_tasks(
             }]));
     }
 
-    let results: Vec<Result<CommandExecutionResult, Diagnostic>> =
-        txtx_addon_kit::futures::future::join_all(background_tasks_futures).await;
+    let construct_dids = background_tasks_contructs_dids
+        .iter()
+        .map(|(_, construct_did)| construct_did.clone())
+        .collect::<Vec<_>>();
+    let results = await_background_tasks(
+        &construct_dids,
+        background_tasks_futures,
+        &mut flow_context.execution_context.background_tasks_heartbeats,
+        HEARTBEAT_INTERVAL,
+        |tick| {
+            for heartbeat in tick.heartbeats {
+                let _ = progress_tx.send(BlockEvent::Heartbeat(heartbeat));
+            }
+            for (construct_did, silence) in tick.silent {
+                let construct_id =
+                    flow_context.workspace_context.expect_construct_id(&construct_did);
+                let _ = progress_tx.send(BlockEvent::static_log(
+                    LogLevel::Warn,
+                    Uuid::new_v4(),
+                    "txtx::background_tasks".into(),
+                    "Background task silent",
+                    format!(
+                        "'{}' made no progress for {}s and may be stalled",
+                        construct_id.construct_name,
+                        silence.as_secs()
+                    ),
+                ));
+            }
+        },
+    )
+    .await;
     for ((nested_construct_did, construct_did), result) in
         background_tasks_contructs_dids.into_iter().zip(results)
     {
@@ -786,8 +1113,24 @@ pub async fn process_background_tasks(
                 flow_context
                     .execution_context
                     .append_commands_execution_result(&nested_construct_did, &result);
+                // constructs with an execution result are skipped by the next evaluation passes,
+                // so their completion is published here
+                publish_construct_statuses(
+                    &[construct_did],
+                    ConstructStatus::Completed,
+                    &flow_context.workspace_context,
+                    &flow_context.execution_context,
+                    progress_tx,
+                );
             }
             Err(mut diag) => {
+                publish_construct_statuses(
+                    &[construct_did.clone()],
+                    ConstructStatus::Failed,
+                    &flow_context.workspace_context,
+                    &flow_context.execution_context,
+                    progress_tx,
+                );
                 let construct_id =
                     flow_context.workspace_context.expect_construct_id(&construct_did);
                 diag = diag.location(&construct_id.construct_location);
@@ -815,6 +1158,79 @@ pub async fn process_background_tasks(
     Ok(())
 }
 
+/// Awaits background tasks, ticking their heartbeats every `interval` from a dedicated thread.
+/// A task makes progress whenever it's polled: tasks are only polled once woken, so a task
+/// waiting on something that never happens stays silent. The results are in the order of the
+/// tasks.
+pub(crate) async fn await_background_tasks(
+    construct_dids: &[ConstructDid],
+    background_tasks_futures: Vec<
+        Pin<Box<dyn Future<Output = Result<CommandExecutionResult, Diagnostic>> + Send>>,
+    >,
+    heartbeats: &mut BackgroundTasksHeartbeats,
+    interval: Duration,
+    mut on_tick: impl FnMut(HeartbeatsTick),
+) -> Vec<Result<CommandExecutionResult, Diagnostic>> {
+    let now = Instant::now();
+    for construct_did in construct_dids.iter() {
+        heartbeats.start(construct_did, now);
+    }
+
+    let (polled_tx, polled_rx) = txtx_addon_kit::channel::unbounded();
+    let mut tasks = background_tasks_futures
+        .into_iter()
+        .enumerate()
+        .map(|(index, mut future)| {
+            let polled_tx = polled_tx.clone();
+            poll_fn(move |cx| {
+                let _ = polled_tx.send((index, Instant::now()));
+                future.as_mut().poll(cx).map(|result| (index, result))
+            })
+        })
+        .collect::<FuturesUnordered<_>>();
+    let (mut ticks, _stop_ticks_tx) = heartbeat_ticks(interval);
+
+    let mut results = construct_dids.iter().map(|_| None).collect::<Vec<_>>();
+    loop {
+        match select(tasks.next(), ticks.next()).await {
+            Either::Left((Some((index, result)), _)) => {
+                results[index] = Some(result);
+                let construct_did = &construct_dids[index];
+                let still_running = results
+                    .iter()
+                    .zip(construct_dids)
+                    .any(|(result, did)| result.is_none() && did == construct_did);
+                if !still_running {
+                    heartbeats.stop(construct_did);
+                }
+            }
+            Either::Left((None, _)) => break,
+            Either::Right(_) => {
+                for (index, polled_at) in polled_rx.try_iter() {
+                    heartbeats.record_progress(&construct_dids[index], polled_at);
+                }
+                on_tick(heartbeats.tick(Instant::now()));
+            }
+        }
+    }
+    results.into_iter().flatten().collect()
+}
+
+/// Ticks every `interval` from a dedicated thread, so that ticks keep coming whatever the
+/// executor. The thread ends once the returned sender is dropped.
+fn heartbeat_ticks(interval: Duration) -> (UnboundedReceiver<()>, Sender<()>) {
+    let (tick_tx, tick_rx) = txtx_addon_kit::futures::channel::mpsc::unbounded();
+    let (stop_tx, stop_rx) = txtx_addon_kit::channel::bounded::<()>(1);
+    ::std::thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+            if tick_tx.unbounded_send(()).is_err() {
+                break;
+            }
+        }
+    });
+    (tick_rx, stop_tx)
+}
+
 pub async fn process_signers_action_item_response(
     runbook: &mut Runbook,
     block_tx: &Sender<BlockEvent>,
@@ -845,6 +1261,14 @@ pub async fn process_signers_action_item_response(
     )
     .await;
 
+    report_pass_warnings(
+        &mut pass_result,
+        &mut runbook.warnings,
+        runbook.deny_warnings,
+        &runbook.sources,
+        block_tx,
+    );
+
     if pass_result.has_diagnostics() {
         pass_result.fill_diagnostic_span(&runbook.sources);
     }
@@ -15,12 +15,20 @@ pub struct WorkspaceManifestFile {
     pub id: String,
     pub runbooks: Vec<RunbookMetadataFile>,
     pub environments: IndexMap<String, IndexMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signers: Option<String>,
 }
 
 impl WorkspaceManifestFile {
     pub fn new(name: String) -> Self {
         let id = normalize_user_input(&name);
-        WorkspaceManifestFile { name, id, runbooks: vec![], environments: IndexMap::new() }
+        WorkspaceManifestFile {
+            name,
+            id,
+            runbooks: vec![],
+            environments: IndexMap::new(),
+            signers: None,
+        }
     }
 }
 
@@ -64,12 +72,22 @@ pub fn read_runbooks_from_manifest(
 
         let mut package_location = root_path.clone();
         package_location.append_path(&runbook_metadata.location)?;
-        if let Ok((_, runbook, sources)) = read_runbook_from_location(
+        if let Ok((_, runbook, mut sources)) = read_runbook_from_location(
             &package_location,
             &runbook_metadata.description,
             environment_selector,
             Some(&runbook_metadata.name),
         ) {
+            if let Some(signers) = &manifest.signers {
+                let mut signers_location = root_path.clone();
+                signers_location.append_path(signers)?;
+                add_shared_signers_sources(
+                    &mut sources,
+                    &signers_location,
+                    environment_selector,
+                    &runbook_metadata.name,
+                )?;
+            }
             runbooks.insert(
                 runbook_metadata.name.to_string(),
                 (
@@ -84,6 +102,34 @@ pub fn read_runbooks_from_manifest(
     Ok(runbooks)
 }
 
+/// Adds the shared signers file (or directory) declared in the manifest to the sources of a runbook,
+/// so that the signers it defines can be bound to the signer aliases of the environments.
+pub fn add_shared_signers_sources(
+    runbook_sources: &mut RunbookSources,
+    signers_location: &FileLocation,
+    environment_selector: &Option<String>,
+    runbook_name: &str,
+) -> Result<(), String> {
+    let files = match std::fs::read_dir(signers_location.to_string()) {
+        Ok(_) => get_txtx_files_paths(&signers_location.to_string(), environment_selector)
+            .map_err(|e| format!("unable to read signers directory: {}", e))?
+            .into_iter()
+            .map(FileLocation::from_path)
+            .collect(),
+        Err(_) => vec![signers_location.clone()],
+    };
+    for location in files.into_iter() {
+        if runbook_sources.tree.contains_key(&location) {
+            continue;
+        }
+        let file_content = location
+            .read_content_as_utf8()
+            .map_err(|e| format!("unable to read signers file {}: {}", location, e))?;
+        runbook_sources.add_source(runbook_name.to_string(), location, file_content);
+    }
+    Ok(())
+}
+
 pub fn read_runbook_from_location(
     location: &FileLocation,
     description: &Option<String>,
@@ -17,6 +17,9 @@ pub struct WorkspaceManifest {
     pub id: String,
     pub runbooks: Vec<RunbookMetadata>,
     pub environments: IndexMap<String, IndexMap<String, String>>,
+    /// Path (relative to the manifest) of a file or directory defining the signers shared by all the runbooks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signers: Option<String>,
     #[serde(skip_serializing, skip_deserializing)]
     pub location: Option<FileLocation>,
 }
@@ -29,6 +32,7 @@ impl WorkspaceManifest {
             id,
             runbooks: vec![],
             environments: IndexMap::new(),
+            signers: None,
             location: None,
         }
     }
@@ -106,14 +110,19 @@ impl WorkspaceManifest {
                 })
                 .collect::<Vec<_>>(),
             environments: manifest_file.environments.clone(),
+            signers: manifest_file.signers.clone(),
             location: Some(manifest_location.clone()),
         };
         Ok(manifest)
     }
 
+    /// Builds the inputs of the selected environment. The values of the environment are
+    /// overridden by the input files, in order, then by the cli inputs. An input file named `-`
+    /// is read from stdin.
     pub fn get_runbook_inputs(
         &self,
         selector: &Option<String>,
+        input_files: &Vec<String>,
         cli_inputs: &Vec<String>,
         buffer_stdin: Option<String>,
     ) -> Result<RunbookTopLevelInputsMap, String> {
@@ -126,6 +135,7 @@ impl WorkspaceManifest {
         let mut inputs_map =
             RunbookTopLevelInputsMap::from_environment_map(selector, &self.environments);
 
+        inputs_map.override_values_with_input_files(input_files, &buffer_stdin)?;
         inputs_map.override_values_with_cli_inputs(cli_inputs, buffer_stdin)?;
         Ok(inputs_map)
     }
@@ -0,0 +1,125 @@
+use txtx_addon_kit::hcl::expr::{Expression, FuncCall, ObjectKey};
+use txtx_addon_kit::hcl::structure::{Block, Structure};
+use txtx_addon_kit::hcl::visit::{visit_func_call, Visit};
+use txtx_addon_kit::types::commands::CommandInput;
+use txtx_addon_kit::types::deprecation::{DeprecatedItemKind, Deprecation, DeprecationUsage};
+use txtx_addon_kit::types::PackageDid;
+
+use super::RuntimeContext;
+
+/// The specification of a construct, as far as deprecations are concerned.
+pub struct DeprecatedConstructSpecification<'a> {
+    pub kind: DeprecatedItemKind,
+    /// Name of the command or signer, e.g. `evm::deploy_contract`
+    pub item: String,
+    pub deprecation: &'a Option<Deprecation>,
+    pub inputs: &'a [CommandInput],
+}
+
+/// Lists the deprecated items used by a construct: its command or signer, the inputs it sets and
+/// the functions called by its expressions, along with their arguments.
+pub fn collect_construct_deprecations(
+    construct: &str,
+    specification: DeprecatedConstructSpecification,
+    block: &Block,
+    package_did: &PackageDid,
+    runtime_context: &RuntimeContext,
+) -> Vec<DeprecationUsage> {
+    let mut usages = vec![];
+    if let Some(deprecation) = specification.deprecation {
+        usages.push(DeprecationUsage::new(
+            specification.kind,
+            specification.item.clone(),
+            construct,
+            deprecation,
+        ));
+    }
+
+    for structure in block.body.iter() {
+        let key = match structure {
+            Structure::Attribute(attribute) => attribute.key.as_str(),
+            Structure::Block(block) => block.ident.as_str(),
+        };
+        let Some(input) = specification.inputs.iter().find(|input| input.name == key) else {
+            continue;
+        };
+        if let Some(deprecation) = &input.deprecation {
+            usages.push(DeprecationUsage::new(
+                DeprecatedItemKind::Input,
+                format!("{}.{}", specification.item, input.name),
+                construct,
+                deprecation,
+            ));
+        }
+    }
+
+    let mut calls = FunctionCallsCollector { calls: vec![] };
+    calls.visit_body(&block.body);
+    for call in calls.calls {
+        let namespace = call.name.namespace.first().map(|n| n.to_string());
+        let name = call.name.name.to_string();
+        let Ok(function) =
+            runtime_context.get_function(package_did.clone(), namespace.clone(), &name)
+        else {
+            continue;
+        };
+        let item = match namespace {
+            Some(namespace) => format!("{}::{}", namespace, name),
+            None => name,
+        };
+        if let Some(deprecation) = &function.deprecation {
+            usages.push(DeprecationUsage::new(
+                DeprecatedItemKind::Function,
+                item.clone(),
+                construct,
+                deprecation,
+            ));
+        }
+
+        let mut args = call.args.iter().collect::<Vec<_>>();
+        let mut used_inputs = vec![];
+        if let Some(Expression::Object(object)) = args.last() {
+            let keys = object
+                .iter()
+                .map(|(key, _)| match key {
+                    ObjectKey::Ident(ident) => Some(ident.as_str()),
+                    ObjectKey::Expression(_) => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            if let Some(keys) = keys.filter(|keys| function.names_inputs(keys.iter().copied())) {
+                used_inputs.extend(keys.into_iter().map(|key| key.to_string()));
+                args.pop();
+            }
+        }
+        used_inputs.extend(function.inputs.iter().take(args.len()).map(|i| i.name.clone()));
+
+        for input in function.inputs.iter() {
+            let Some(deprecation) = &input.deprecation else {
+                continue;
+            };
+            if used_inputs.contains(&input.name) {
+                usages.push(DeprecationUsage::new(
+                    DeprecatedItemKind::Input,
+                    format!("{}.{}", item, input.name),
+                    construct,
+                    deprecation,
+                ));
+            }
+        }
+    }
+
+    usages.sort_by(|a, b| a.item.cmp(&b.item));
+    usages.dedup();
+    usages
+}
+
+struct FunctionCallsCollector {
+    calls: Vec<FuncCall>,
+}
+
+impl Visit for FunctionCallsCollector {
+    fn visit_func_call(&mut self, call: &FuncCall) {
+        self.calls.push(call.clone());
+        visit_func_call(self, call);
+    }
+}
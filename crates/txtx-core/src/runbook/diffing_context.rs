@@ -1122,6 +1122,243 @@ impl ConsolidatedChanges {
     }
 }
 
+/// Per-construct changes between two execution snapshots.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionSnapshotsDiff {
+    pub flows_added: Vec<String>,
+    pub flows_removed: Vec<String>,
+    pub flows: Vec<FlowSnapshotDiff>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FlowSnapshotDiff {
+    pub name: String,
+    pub constructs: Vec<ConstructSnapshotDiff>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConstructChangeKind {
+    Added,
+    Removed,
+    Updated,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConstructSnapshotDiff {
+    pub construct_did: ConstructDid,
+    pub construct_type: crate::types::ConstructType,
+    pub name: String,
+    pub change: ConstructChangeKind,
+    /// Whether a critical input changed, tainting the construct: it would be re-executed
+    pub would_re_execute: bool,
+    pub inputs: Vec<SnapshotValueChange>,
+    pub outputs: Vec<SnapshotValueChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotValueChange {
+    pub name: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub critical: bool,
+}
+
+impl ExecutionSnapshotsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.flows_added.is_empty()
+            && self.flows_removed.is_empty()
+            && self.flows.iter().all(|flow| flow.constructs.is_empty())
+    }
+}
+
+impl RunbookExecutionSnapshot {
+    /// Compares the constructs of the flows shared by this snapshot and the `new` one: the
+    /// constructs added and removed, and for the others, the inputs and outputs that changed.
+    pub fn diff_constructs(&self, new: &RunbookExecutionSnapshot) -> ExecutionSnapshotsDiff {
+        let mut diff = ExecutionSnapshotsDiff {
+            flows_added: new
+                .flows
+                .keys()
+                .filter(|f| !self.flows.contains_key(*f))
+                .cloned()
+                .collect(),
+            flows_removed: self
+                .flows
+                .keys()
+                .filter(|f| !new.flows.contains_key(*f))
+                .cloned()
+                .collect(),
+            flows: vec![],
+        };
+        for (flow_name, new_flow) in new.flows.iter() {
+            let Some(old_flow) = self.flows.get(flow_name) else {
+                continue;
+            };
+            let mut constructs = vec![];
+            for (construct_did, new_signer) in new_flow.signers.iter() {
+                let Some(old_signer) = old_flow.signers.get(construct_did) else {
+                    constructs
+                        .push(new_signer.construct_diff(construct_did, ConstructChangeKind::Added));
+                    continue;
+                };
+                let mut signer_diff =
+                    new_signer.construct_diff(construct_did, ConstructChangeKind::Updated);
+                if old_signer.inputs_fingerprint != new_signer.inputs_fingerprint {
+                    signer_diff.inputs.push(SnapshotValueChange {
+                        name: "inputs".into(),
+                        old: Some(format!("0x{}", old_signer.inputs_fingerprint)),
+                        new: Some(format!("0x{}", new_signer.inputs_fingerprint)),
+                        critical: true,
+                    });
+                    signer_diff.would_re_execute = true;
+                }
+                signer_diff.outputs = diff_values(
+                    old_signer.outputs.iter().map(|(k, v)| (k, v.to_string())),
+                    new_signer.outputs.iter().map(|(k, v)| (k, v.to_string())),
+                );
+                if !signer_diff.inputs.is_empty() || !signer_diff.outputs.is_empty() {
+                    constructs.push(signer_diff);
+                }
+            }
+            for (construct_did, old_signer) in old_flow.signers.iter() {
+                if !new_flow.signers.contains_key(construct_did) {
+                    constructs.push(
+                        old_signer.construct_diff(construct_did, ConstructChangeKind::Removed),
+                    );
+                }
+            }
+
+            for (construct_did, new_command) in new_flow.commands.iter() {
+                let Some(old_command) = old_flow.commands.get(construct_did) else {
+                    constructs.push(
+                        new_command.construct_diff(construct_did, ConstructChangeKind::Added),
+                    );
+                    continue;
+                };
+                let mut command_diff =
+                    new_command.construct_diff(construct_did, ConstructChangeKind::Updated);
+                let fingerprint = |input: &CommandInputSnapshot| {
+                    serde_json::to_string(&input.value_post_evaluation).unwrap_or_default()
+                };
+                let render = |input: &CommandInputSnapshot| match &input.value_post_evaluation {
+                    ValuePostEvaluation::Value(value) => value.to_string(),
+                    _ => fingerprint(input),
+                };
+                for (input_name, new_input) in new_command.inputs.iter() {
+                    let old_input = old_command.inputs.get(input_name);
+                    if old_input.map(fingerprint) == Some(fingerprint(new_input)) {
+                        continue;
+                    }
+                    let critical = new_input.critical || old_input.map_or(false, |i| i.critical);
+                    command_diff.would_re_execute |= critical;
+                    command_diff.inputs.push(SnapshotValueChange {
+                        name: input_name.clone(),
+                        old: old_input.map(render),
+                        new: Some(render(new_input)),
+                        critical,
+                    });
+                }
+                for (input_name, old_input) in old_command.inputs.iter() {
+                    if !new_command.inputs.contains_key(input_name) {
+                        command_diff.would_re_execute |= old_input.critical;
+                        command_diff.inputs.push(SnapshotValueChange {
+                            name: input_name.clone(),
+                            old: Some(render(old_input)),
+                            new: None,
+                            critical: old_input.critical,
+                        });
+                    }
+                }
+                command_diff.outputs = diff_values(
+                    old_command.outputs.iter().map(|(k, v)| (k, v.value.to_string())),
+                    new_command.outputs.iter().map(|(k, v)| (k, v.value.to_string())),
+                );
+                if !command_diff.inputs.is_empty() || !command_diff.outputs.is_empty() {
+                    constructs.push(command_diff);
+                }
+            }
+            for (construct_did, old_command) in old_flow.commands.iter() {
+                if !new_flow.commands.contains_key(construct_did) {
+                    constructs.push(
+                        old_command.construct_diff(construct_did, ConstructChangeKind::Removed),
+                    );
+                }
+            }
+            diff.flows.push(FlowSnapshotDiff { name: flow_name.clone(), constructs });
+        }
+        diff
+    }
+}
+
+impl SigningCommandSnapshot {
+    fn construct_diff(
+        &self,
+        construct_did: &ConstructDid,
+        change: ConstructChangeKind,
+    ) -> ConstructSnapshotDiff {
+        ConstructSnapshotDiff {
+            construct_did: construct_did.clone(),
+            construct_type: self.construct_type,
+            name: self.construct_name.clone(),
+            change,
+            would_re_execute: change == ConstructChangeKind::Added,
+            inputs: vec![],
+            outputs: vec![],
+        }
+    }
+}
+
+impl CommandSnapshot {
+    fn construct_diff(
+        &self,
+        construct_did: &ConstructDid,
+        change: ConstructChangeKind,
+    ) -> ConstructSnapshotDiff {
+        ConstructSnapshotDiff {
+            construct_did: construct_did.clone(),
+            construct_type: self.construct_type,
+            name: self.construct_name.clone(),
+            change,
+            // constructs that were not executed are executed on the next run
+            would_re_execute: change == ConstructChangeKind::Added
+                || (change == ConstructChangeKind::Updated && !self.executed),
+            inputs: vec![],
+            outputs: vec![],
+        }
+    }
+}
+
+fn diff_values<'a>(
+    old: impl Iterator<Item = (&'a String, String)>,
+    new: impl Iterator<Item = (&'a String, String)>,
+) -> Vec<SnapshotValueChange> {
+    let old = old.collect::<IndexMap<_, _>>();
+    let new = new.collect::<IndexMap<_, _>>();
+    let mut changes = vec![];
+    for (name, new_value) in new.iter() {
+        if old.get(name) != Some(new_value) {
+            changes.push(SnapshotValueChange {
+                name: name.to_string(),
+                old: old.get(name).cloned(),
+                new: Some(new_value.clone()),
+                critical: false,
+            });
+        }
+    }
+    for (name, old_value) in old.iter() {
+        if !new.contains_key(name) {
+            changes.push(SnapshotValueChange {
+                name: name.to_string(),
+                old: Some(old_value.clone()),
+                new: None,
+                critical: false,
+            });
+        }
+    }
+    changes
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Change {
     pub critical: bool,
@@ -1177,3 +1414,99 @@ fn now_as_string() -> String {
 }
 // Shortcut:
 // Support for constructs being removed / added / replaced
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command_snapshot(name: &str, amount: i128, tx_hash: &str) -> CommandSnapshot {
+        CommandSnapshot {
+            package_did: PackageDid(Did::from_components(vec!["package"])),
+            construct_type: crate::types::ConstructType::Action,
+            construct_name: name.to_string(),
+            construct_location: FileLocation::from_path_string("/tmp/main.tx").unwrap(),
+            construct_addon: Some("evm".into()),
+            upstream_constructs_dids: vec![],
+            inputs: IndexMap::from([
+                (
+                    "amount".to_string(),
+                    CommandInputSnapshot {
+                        value_pre_evaluation: None,
+                        value_post_evaluation: ValuePostEvaluation::Value(Value::integer(amount)),
+                        critical: true,
+                    },
+                ),
+                (
+                    "description".to_string(),
+                    CommandInputSnapshot {
+                        value_pre_evaluation: None,
+                        value_post_evaluation: ValuePostEvaluation::Value(Value::string(format!(
+                            "send {amount}"
+                        ))),
+                        critical: false,
+                    },
+                ),
+            ]),
+            outputs: IndexMap::from([(
+                "tx_hash".to_string(),
+                CommandOutputSnapshot { value: Value::string(tx_hash.to_string()), signed: false },
+            )]),
+            executed: true,
+        }
+    }
+
+    fn execution_snapshot(commands: Vec<(&str, CommandSnapshot)>) -> RunbookExecutionSnapshot {
+        let mut snapshot = RunbookExecutionSnapshot::new(
+            &RunbookId::new(None, None, "transfer"),
+            &RunbookTopLevelInputsMap::new(),
+        );
+        let flow = RunbookFlowSnapshot {
+            flow_inputs_fingerprints: IndexMap::new(),
+            addon_defaults_fingerprints: IndexMap::new(),
+            packages: IndexMap::new(),
+            signers: IndexMap::new(),
+            commands: commands
+                .into_iter()
+                .map(|(id, command)| (ConstructDid(Did::from_components(vec![id])), command))
+                .collect(),
+        };
+        snapshot.flows.insert("localnet".into(), flow);
+        snapshot
+    }
+
+    #[test]
+    fn test_diff_constructs() {
+        let old = execution_snapshot(vec![
+            ("transfer", command_snapshot("transfer", 1, "0x01")),
+            ("refund", command_snapshot("refund", 1, "0x02")),
+        ]);
+        let new = execution_snapshot(vec![
+            ("transfer", command_snapshot("transfer", 2, "0x03")),
+            ("airdrop", command_snapshot("airdrop", 1, "0x04")),
+        ]);
+
+        let diff = old.diff_constructs(&new);
+        assert!(diff.flows_added.is_empty() && diff.flows_removed.is_empty());
+        let constructs = &diff.flows[0].constructs;
+        let summary = constructs.iter().map(|c| (c.name.as_str(), c.change)).collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("transfer", ConstructChangeKind::Updated),
+                ("airdrop", ConstructChangeKind::Added),
+                ("refund", ConstructChangeKind::Removed),
+            ]
+        );
+
+        let transfer = &constructs[0];
+        assert!(transfer.would_re_execute);
+        assert_eq!(
+            transfer.inputs.iter().map(|i| (i.name.as_str(), i.critical)).collect::<Vec<_>>(),
+            vec![("amount", true), ("description", false)]
+        );
+        assert_eq!(transfer.outputs.len(), 1);
+        assert_eq!(transfer.outputs[0].new.as_deref(), Some("0x03"));
+
+        assert!(old.diff_constructs(&old).is_empty());
+    }
+}
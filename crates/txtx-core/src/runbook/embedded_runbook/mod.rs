@@ -1,5 +1,6 @@
 pub mod publishable;
 
+use crate::eval::index_constructs_dependency_names;
 use publishable::PublishableEmbeddedRunbookSpecification;
 use std::collections::HashMap;
 use txtx_addon_kit::hcl::structure::Block;
@@ -7,6 +8,7 @@ use txtx_addon_kit::helpers::fs::FileLocation;
 use txtx_addon_kit::types::commands::DependencyExecutionResultCache;
 use txtx_addon_kit::types::diagnostics::Diagnostic;
 use txtx_addon_kit::types::embedded_runbooks::EmbeddedRunbookStatefulExecutionContext;
+use txtx_addon_kit::types::heartbeat::BackgroundTasksHeartbeats;
 use txtx_addon_kit::types::stores::ValueStore;
 use txtx_addon_kit::types::PackageId;
 use txtx_addon_kit::types::{
@@ -110,6 +112,7 @@ impl EmbeddingRunbookContext {
                 .static_execution_context
                 .signed_commands
                 .clone(),
+            constructs_dependency_names: HashMap::new(),
             order_for_commands_execution: runbook_instance
                 .specification
                 .static_execution_context
@@ -121,6 +124,7 @@ impl EmbeddingRunbookContext {
                 .order_for_signers_initialization
                 .clone(),
             execution_mode: RunbookExecutionMode::Full,
+            background_tasks_heartbeats: BackgroundTasksHeartbeats::new(),
         };
 
         let mut workspace_context =
@@ -167,6 +171,9 @@ impl EmbeddingRunbookContext {
             }
         }
 
+        execution_context.constructs_dependency_names =
+            index_constructs_dependency_names(&workspace_context, &execution_context);
+
         for (key, value) in top_level_inputs.iter() {
             let construct_did = workspace_context.index_top_level_input(key, value);
             let mut result = CommandExecutionResult::new();
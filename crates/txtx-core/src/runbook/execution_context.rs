@@ -19,6 +19,7 @@ use txtx_addon_kit::types::frontend::ActionItemRequest;
 use txtx_addon_kit::types::frontend::ActionItemRequestType;
 use txtx_addon_kit::types::frontend::BlockEvent;
 use txtx_addon_kit::types::frontend::DisplayOutputRequest;
+use txtx_addon_kit::types::heartbeat::BackgroundTasksHeartbeats;
 use txtx_addon_kit::types::signers::SignerInstance;
 use txtx_addon_kit::types::signers::SignersState;
 use txtx_addon_kit::types::stores::AddonDefaults;
@@ -64,12 +65,16 @@ pub struct RunbookExecutionContext {
     pub signed_commands_upstream_dependencies: HashMap<ConstructDid, Vec<ConstructDid>>,
     /// Constructs depending on a given Construct being signed.
     pub signed_commands: HashSet<ConstructDid>,
+    /// Names of the constructs referenced by the inputs of each command and signer.
+    pub constructs_dependency_names: HashMap<ConstructDid, Vec<String>>,
     /// Commands execution order.
     pub order_for_commands_execution: Vec<ConstructDid>,
     /// Signing commands initialization order.
     pub order_for_signers_initialization: Vec<ConstructDid>,
     /// Wether or not this running context is enabled
     pub execution_mode: RunbookExecutionMode,
+    /// Heartbeats of the background tasks being awaited.
+    pub background_tasks_heartbeats: BackgroundTasksHeartbeats,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -94,9 +99,11 @@ impl RunbookExecutionContext {
             signers_downstream_dependencies: vec![],
             signed_commands_upstream_dependencies: HashMap::new(),
             signed_commands: HashSet::new(),
+            constructs_dependency_names: HashMap::new(),
             order_for_commands_execution: vec![],
             order_for_signers_initialization: vec![],
             execution_mode: RunbookExecutionMode::Ignored,
+            background_tasks_heartbeats: BackgroundTasksHeartbeats::new(),
         }
     }
 
@@ -196,6 +203,7 @@ impl RunbookExecutionContext {
                     name: command_instance.name.to_string(),
                     description: description.clone(),
                     value: value.clone(),
+                    formatted_value: None,
                 })
                 .to_request(&command_instance.name, "output")
                 .with_construct_did(construct_did)
@@ -296,7 +304,16 @@ impl RunbookExecutionContext {
 
         let addon_context_key =
             (command_instance.package_id.did(), command_instance.namespace.clone());
-        let addon_defaults = workspace_context.get_addon_defaults(&addon_context_key);
+        let addon_defaults = match workspace_context.get_addon_defaults(
+            &addon_context_key,
+            command_instance.get_addon_instance().as_deref(),
+        ) {
+            Ok(addon_defaults) => addon_defaults,
+            Err(diag) => {
+                pass_result.push_diagnostic(&diag, &construct_id, &add_ctx_to_diag);
+                return LoopEvaluationResult::Bail;
+            }
+        };
 
         let input_evaluation_results = self.commands_inputs_evaluation_results.get(&construct_did);
 
@@ -609,7 +626,10 @@ impl RunbookExecutionContext {
         let package_id = command_instance.package_id.clone();
         let construct_id = &workspace_context.expect_construct_id(&construct_did);
         let addon_context_key = (package_id.did(), command_instance.namespace.clone());
-        let addon_defaults = workspace_context.get_addon_defaults(&addon_context_key);
+        let addon_defaults = workspace_context.get_addon_defaults(
+            &addon_context_key,
+            command_instance.get_addon_instance().as_deref(),
+        )?;
 
         let evaluated_inputs_res = perform_inputs_evaluation(
             command_instance,
@@ -733,7 +753,10 @@ impl RunbookExecutionContext {
 
             let addon_context_key =
                 (command_instance.package_id.did(), command_instance.namespace.clone());
-            let addon_defaults = workspace_context.get_addon_defaults(&addon_context_key);
+            let addon_defaults = workspace_context.get_addon_defaults(
+                &addon_context_key,
+                command_instance.get_addon_instance().as_deref(),
+            )?;
 
             let mut execution_result = self
                 .commands_execution_results
@@ -13,6 +13,8 @@ use txtx_addon_kit::types::PackageDid;
 use txtx_addon_kit::types::PackageId;
 
 use super::{RunbookExecutionContext, RunbookWorkspaceContext};
+use crate::errors::codes::{DEPENDENCY_CYCLE, UNRESOLVED_CONSTRUCT_REFERENCE};
+use crate::eval::index_constructs_dependency_names;
 
 #[derive(Debug, Clone)]
 pub struct RunbookGraphContext {
@@ -28,6 +30,8 @@ pub struct RunbookGraphContext {
     pub instantiated_signers: VecDeque<(ConstructDid, bool)>,
     /// Keep track of the root DAGs (temporary - to be removed)
     pub graph_root: NodeIndex<u32>,
+    /// Dependencies contributed by the addons, on top of the ones expressed in the sources
+    pub domain_specific_dependencies: HashMap<ConstructDid, Vec<ConstructDid>>,
 }
 
 impl RunbookGraphContext {
@@ -45,6 +49,7 @@ impl RunbookGraphContext {
             constructs_dag_node_lookup: HashMap::new(),
             instantiated_signers: VecDeque::new(),
             graph_root,
+            domain_specific_dependencies: HashMap::new(),
         }
     }
 
@@ -60,8 +65,12 @@ impl RunbookGraphContext {
 
         let packages = workspace_context.packages.clone();
 
+        // Signers are collected across packages. Packages are scoped to a source directory, so a
+        // signers file shared by several runbooks is a package of its own, and an alias can bind a
+        // construct to a signer of that package: collecting signers package by package would only
+        // keep the signers referenced by the last package visited, and leave the others inactive.
+        let mut signers = VecDeque::new();
         for (package_id, package) in packages.iter() {
-            let mut signers = VecDeque::new();
             let mut instantiated_signers = HashSet::new();
 
             // add variable constructs to graph
@@ -92,6 +101,7 @@ impl RunbookGraphContext {
                                 dep.to_string().trim(),
                                 command_instance.name,
                             )
+                            .with_diagnostic_code(&UNRESOLVED_CONSTRUCT_REFERENCE)
                             .location(&construct_id.construct_location)
                             .set_span_range(command_instance.block.span()),
                         );
@@ -126,6 +136,7 @@ impl RunbookGraphContext {
                                 dep.to_string().trim(),
                                 command_instance.name,
                             )
+                            .with_diagnostic_code(&UNRESOLVED_CONSTRUCT_REFERENCE)
                             .location(&construct_id.construct_location)
                             .set_span_range(command_instance.block.span()),
                         );
@@ -160,6 +171,7 @@ impl RunbookGraphContext {
                                 dep.to_string().trim(),
                                 command_instance.name,
                             )
+                            .with_diagnostic_code(&UNRESOLVED_CONSTRUCT_REFERENCE)
                             .location(&construct_id.construct_location)
                             .set_span_range(command_instance.block.span()),
                         );
@@ -210,6 +222,7 @@ impl RunbookGraphContext {
                                 dep.to_string().trim(),
                                 command_instance.name,
                             )
+                            .with_diagnostic_code(&UNRESOLVED_CONSTRUCT_REFERENCE)
                             .location(&construct_id.construct_location)
                             .set_span_range(command_instance.block.span()),
                         );
@@ -252,6 +265,7 @@ impl RunbookGraphContext {
                                 dep.to_string().trim(),
                                 embedded_runbook_instance.name,
                             )
+                            .with_diagnostic_code(&UNRESOLVED_CONSTRUCT_REFERENCE)
                             .location(&construct_id.construct_location)
                             .set_span_range(embedded_runbook_instance.block.span()),
                         );
@@ -286,17 +300,18 @@ impl RunbookGraphContext {
                                 dep.to_string().trim(),
                                 signer_instance.name,
                             )
+                            .with_diagnostic_code(&UNRESOLVED_CONSTRUCT_REFERENCE)
                             .location(&construct_id.construct_location)
                             .set_span_range(signer_instance.block.span()),
                         );
                     }
                 }
             }
-            // this is the most idiomatic way I could find to get unique values from a hash set
-            let mut seen_signers = HashSet::new();
-            signers.retain(|w| seen_signers.insert(w.clone()));
-            self.instantiated_signers = signers;
         }
+        // this is the most idiomatic way I could find to get unique values from a hash set
+        let mut seen_signers = HashSet::new();
+        signers.retain(|w| seen_signers.insert(w.clone()));
+        self.instantiated_signers = signers;
 
         for (src, dst) in constructs_edges.iter() {
             let constructs_graph_nodes = self.constructs_dag_node_lookup.clone();
@@ -315,7 +330,9 @@ impl RunbookGraphContext {
             if let Err(_e) =
                 self.constructs_dag.add_edge(dst_node_index.clone(), src_node_index.clone(), 1)
             {
-                diags.push(diagnosed_error!("Cycling dependency"));
+                diags.push(
+                    diagnosed_error!("Cycling dependency").with_diagnostic_code(&DEPENDENCY_CYCLE),
+                );
             }
         }
 
@@ -390,6 +407,9 @@ impl RunbookGraphContext {
                 self.get_downstream_dependencies_for_construct_did(construct_did, true);
             execution_context.commands_dependencies.insert(construct_did.clone(), dependencies);
         }
+        execution_context.constructs_dependency_names =
+            index_constructs_dependency_names(workspace_context, execution_context);
+        self.domain_specific_dependencies = domain_specific_dependencies;
         Ok(())
     }
 
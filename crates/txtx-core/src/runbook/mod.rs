@@ -3,7 +3,7 @@ use flow_context::FlowContext;
 use kit::indexmap::IndexMap;
 use kit::types::cloud_interface::CloudServiceContext;
 use kit::types::frontend::ActionItemRequestType;
-use kit::types::types::AddonJsonConverter;
+use kit::types::types::{AddonDisplayFormatter, AddonJsonConverter};
 use kit::types::{ConstructDid, RunbookInstanceContext};
 use serde_json::{json, Value as JsonValue};
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -12,14 +12,16 @@ use txtx_addon_kit::hcl::Span;
 use txtx_addon_kit::helpers::fs::FileLocation;
 use txtx_addon_kit::helpers::hcl::RawHclContent;
 use txtx_addon_kit::types::commands::{CommandExecutionResult, DependencyExecutionResultCache};
-use txtx_addon_kit::types::diagnostics::DiagnosticSpan;
-use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::deprecation::{DeprecatedItemKind, DeprecationUsage};
+use txtx_addon_kit::types::diagnostics::{DiagnosticKind, DiagnosticSpan};
+use txtx_addon_kit::types::stores::{AddonDefaults, ValueMap, ValueStore};
 use txtx_addon_kit::types::types::RunbookSupervisionContext;
 use txtx_addon_kit::types::{diagnostics::Diagnostic, types::Value};
 use txtx_addon_kit::types::{AuthorizationContext, Did, PackageId, RunbookId};
 use txtx_addon_kit::Addon;
 
 pub mod collector;
+mod deprecations;
 mod diffing_context;
 pub mod embedded_runbook;
 mod execution_context;
@@ -31,13 +33,18 @@ pub mod variables;
 mod workspace_context;
 
 pub use diffing_context::ConsolidatedChanges;
-pub use diffing_context::{RunbookExecutionSnapshot, RunbookSnapshotContext, SynthesizedChange};
+pub use diffing_context::{
+    ConstructChangeKind, ExecutionSnapshotsDiff, RunbookExecutionSnapshot, RunbookSnapshotContext,
+    SynthesizedChange,
+};
 pub use execution_context::{RunbookExecutionContext, RunbookExecutionMode};
 pub use graph_context::RunbookGraphContext;
 pub use runtime_context::{AddonConstructFactory, RuntimeContext};
 pub use workspace_context::RunbookWorkspaceContext;
 
+use crate::errors::codes::DEPRECATED_ITEM_USED;
 use crate::manifest::{RunbookStateLocation, RunbookTransientStateLocation};
+use deprecations::{collect_construct_deprecations, DeprecatedConstructSpecification};
 
 #[derive(Debug)]
 pub struct Runbook {
@@ -55,6 +62,12 @@ pub struct Runbook {
     pub sources: RunbookSources,
     // The store that will contain _all_ of the environment variables (mainnet,testnet,etc), consolidated with the CLI inputs
     pub top_level_inputs_map: RunbookTopLevelInputsMap,
+    /// Warnings and notes reported during the execution, which don't interrupt it
+    pub warnings: Vec<Diagnostic>,
+    /// When true, warnings fail the execution as errors
+    pub deny_warnings: bool,
+    /// Deprecated commands, signers, functions and inputs used by the runbook
+    pub deprecations: Vec<DeprecationUsage>,
 }
 
 impl Runbook {
@@ -74,6 +87,9 @@ impl Runbook {
             sources: RunbookSources::new(),
             supervision_context: RunbookSupervisionContext::new(),
             top_level_inputs_map: RunbookTopLevelInputsMap::new(),
+            warnings: vec![],
+            deny_warnings: false,
+            deprecations: vec![],
         }
     }
 
@@ -93,6 +109,96 @@ impl Runbook {
         }
     }
 
+    /// Lists the addons instantiated by the packages of the runbook, sorted by namespace, along
+    /// with their defaults for each of these packages.
+    pub fn get_instantiated_addons(&self) -> Vec<(&Box<dyn Addon>, Vec<AddonDefaults>)> {
+        let addons_context = &self.runtime_context.addons_context;
+        let mut addon_ids = addons_context.registered_addons.keys().collect::<Vec<_>>();
+        addon_ids.sort();
+
+        let workspace_context = self.flow_contexts.first().map(|flow| &flow.workspace_context);
+        let mut package_dids = workspace_context
+            .map(|ctx| ctx.packages.keys().map(|package_id| package_id.did()).collect::<Vec<_>>())
+            .unwrap_or_default();
+        package_dids.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut addons = vec![];
+        for addon_id in addon_ids {
+            let (addon, _) = &addons_context.registered_addons[addon_id];
+            let mut addons_defaults = vec![];
+            for package_did in package_dids.iter() {
+                let key = (package_did.clone(), addon_id.to_string());
+                if !addons_context.addon_construct_factories.contains_key(&key) {
+                    continue;
+                }
+                if let Some(Ok(defaults)) =
+                    workspace_context.map(|ctx| ctx.get_addon_defaults(&key, None))
+                {
+                    addons_defaults.push(defaults);
+                }
+            }
+            addons.push((addon, addons_defaults));
+        }
+        addons
+    }
+
+    /// Lists the deprecated items used by the constructs of the runbook, once per construct, along
+    /// with the warnings to report for them.
+    pub fn collect_deprecations(&self) -> Vec<(DeprecationUsage, Diagnostic)> {
+        let mut deprecations = vec![];
+        for flow_context in self.flow_contexts.iter() {
+            let workspace_context = &flow_context.workspace_context;
+            let execution_context = &flow_context.execution_context;
+            let signers = execution_context.signers_instances.iter().map(|(did, signer)| {
+                let specification = DeprecatedConstructSpecification {
+                    kind: DeprecatedItemKind::Signer,
+                    item: format!("{}::{}", signer.namespace, signer.specification.matcher),
+                    deprecation: &signer.specification.deprecation,
+                    inputs: &signer.specification.inputs,
+                };
+                (did, specification, &signer.block, &signer.package_id)
+            });
+            let commands = execution_context.commands_instances.iter().map(|(did, command)| {
+                let specification = DeprecatedConstructSpecification {
+                    kind: DeprecatedItemKind::Command,
+                    item: format!("{}::{}", command.namespace, command.specification.matcher),
+                    deprecation: &command.specification.deprecation,
+                    inputs: &command.specification.inputs,
+                };
+                (did, specification, &command.block, &command.package_id)
+            });
+            for (construct_did, specification, block, package_id) in signers.chain(commands) {
+                let Some(construct_id) = workspace_context.constructs.get(construct_did) else {
+                    continue;
+                };
+                let construct =
+                    format!("{}.{}", construct_id.construct_type, construct_id.construct_name);
+                for usage in collect_construct_deprecations(
+                    &construct,
+                    specification,
+                    block,
+                    &package_id.did(),
+                    &self.runtime_context,
+                ) {
+                    if deprecations.iter().any(|(known, _)| known == &usage) {
+                        continue;
+                    }
+                    let mut diag = usage
+                        .to_diagnostic()
+                        .with_diagnostic_code(&DEPRECATED_ITEM_USED)
+                        .location(&construct_id.construct_location)
+                        .set_span_range(block.span());
+                    diag.span = get_source_context_for_diagnostic(&diag, &self.sources);
+                    deprecations.push((usage, diag));
+                }
+            }
+        }
+        deprecations.sort_by(|(a, _), (b, _)| {
+            (&a.construct, &a.item).cmp(&(&b.construct, &b.item))
+        });
+        deprecations
+    }
+
     pub fn enable_full_execution_mode(&mut self) {
         for r in self.flow_contexts.iter_mut() {
             r.execution_context.execution_mode = RunbookExecutionMode::Full
@@ -190,6 +296,9 @@ impl Runbook {
     }
 
     /// Clears all flow contexts stored on the runbook.
+    ///
+    /// Nothing is executed while building the contexts: unless classified otherwise, the
+    /// diagnostics returned are validation failures.
     pub async fn build_contexts_from_sources(
         &mut self,
         sources: RunbookSources,
@@ -197,9 +306,36 @@ impl Runbook {
         authorization_context: AuthorizationContext,
         get_addon_by_namespace: fn(&str) -> Option<Box<dyn Addon>>,
         cloud_service_context: CloudServiceContext,
+    ) -> Result<bool, Vec<Diagnostic>> {
+        self.try_build_contexts_from_sources(
+            sources,
+            top_level_inputs_map,
+            authorization_context,
+            get_addon_by_namespace,
+            cloud_service_context,
+        )
+        .await
+        .map_err(|diags| {
+            diags
+                .into_iter()
+                .map(|diag| diag.with_default_kind(DiagnosticKind::Validation))
+                .collect()
+        })
+    }
+
+    async fn try_build_contexts_from_sources(
+        &mut self,
+        sources: RunbookSources,
+        top_level_inputs_map: RunbookTopLevelInputsMap,
+        authorization_context: AuthorizationContext,
+        get_addon_by_namespace: fn(&str) -> Option<Box<dyn Addon>>,
+        cloud_service_context: CloudServiceContext,
     ) -> Result<bool, Vec<Diagnostic>> {
         // Re-initialize some shiny new contexts
         self.flow_contexts.clear();
+        let mut authorization_context = authorization_context;
+        authorization_context.runbook_name = Some(self.runbook_id.name.clone());
+        authorization_context.environment = top_level_inputs_map.current_environment.clone();
         let mut runtime_context = RuntimeContext::new(
             authorization_context,
             get_addon_by_namespace,
@@ -213,6 +349,10 @@ impl Runbook {
 
         // At this point we know if some batching is required
         for flow_context in flow_contexts.iter_mut() {
+            flow_context.workspace_context.signer_aliases =
+                top_level_inputs_map.current_signer_aliases();
+            flow_context.workspace_context.environment_addons_defaults =
+                top_level_inputs_map.current_addon_defaults();
             // Step 1: identify the addons at play and their globals
             runtime_context.register_addons_from_sources(
                 &mut flow_context.workspace_context,
@@ -335,6 +475,15 @@ impl Runbook {
         .await
     }
 
+    /// Returns the signers instantiated across all flows.
+    pub fn get_signers_dids(&self) -> HashSet<ConstructDid> {
+        self.flow_contexts
+            .iter()
+            .flat_map(|flow_context| flow_context.execution_context.signers_instances.keys())
+            .cloned()
+            .collect()
+    }
+
     pub fn get_inputs_selectors(&self) -> Vec<String> {
         self.top_level_inputs_map.environments.clone()
     }
@@ -653,6 +802,7 @@ impl RunbookOutputs {
     pub fn get_output_row_data(
         &self,
         filter: &Option<String>,
+        addon_formatters: &Vec<AddonDisplayFormatter>,
     ) -> IndexMap<String, Vec<Vec<String>>> {
         let mut output_row_data = IndexMap::new();
         for (flow_name, flow_outputs) in self.outputs.iter() {
@@ -667,7 +817,7 @@ impl RunbookOutputs {
 
                 let mut row = vec![];
                 row.push(output_name.to_string());
-                row.push(output_value.to_string());
+                row.push(output_value.to_display_string(addon_formatters));
                 row.push(output_description.clone().unwrap_or_else(|| "".to_string()));
                 flow_output_row.push(row);
             }
@@ -716,14 +866,29 @@ pub struct RunbookTopLevelInputsMap {
     current_environment: Option<String>,
     environments: Vec<String>,
     values: HashMap<Option<String>, Vec<(String, Value)>>,
+    /// Signer aliases of each environment, mapping the alias name to the name of the signer it is bound to
+    signer_aliases: HashMap<Option<String>, IndexMap<String, String>>,
+    /// Addon defaults of each environment, keyed by addon id
+    addon_defaults: HashMap<Option<String>, IndexMap<String, ValueMap>>,
 }
 
 pub const DEFAULT_TOP_LEVEL_INPUTS_NAME: &str = "default";
 pub const GLOBAL_TOP_LEVEL_INPUTS_NAME: &str = "global";
+/// Environment entries prefixed with `signer.` bind a signer alias instead of declaring an input
+pub const SIGNER_ALIAS_PREFIX: &str = "signer.";
+/// Environment entries prefixed with `addon.<addon_id>.` set a default of the addon instead of
+/// declaring an input, e.g. `addon.evm.fee.strategy: fast`
+pub const ADDON_DEFAULT_PREFIX: &str = "addon.";
 
 impl RunbookTopLevelInputsMap {
     pub fn new() -> Self {
-        Self { current_environment: None, environments: vec![], values: HashMap::new() }
+        Self {
+            current_environment: None,
+            environments: vec![],
+            values: HashMap::new(),
+            signer_aliases: HashMap::new(),
+            addon_defaults: HashMap::new(),
+        }
     }
     pub fn from_environment_map(
         selector: &Option<String>,
@@ -731,10 +896,21 @@ impl RunbookTopLevelInputsMap {
     ) -> Self {
         let mut environments = vec![];
         let mut values = HashMap::from_iter([(None, vec![])]);
+        let mut signer_aliases = HashMap::new();
+        let mut addon_defaults = HashMap::new();
 
         let mut global_values = vec![];
+        let mut global_signer_aliases = IndexMap::new();
+        let mut global_addon_defaults = IndexMap::new();
         if let Some(global_env_vars) = environments_map.get(GLOBAL_TOP_LEVEL_INPUTS_NAME) {
             for (key, value) in global_env_vars.iter() {
+                if let Some(alias) = key.strip_prefix(SIGNER_ALIAS_PREFIX) {
+                    global_signer_aliases.insert(alias.to_string(), value.to_string());
+                    continue;
+                }
+                if insert_addon_default(&mut global_addon_defaults, key, value) {
+                    continue;
+                }
                 global_values.push((key.to_string(), Value::parse_and_default_to_string(value)));
             }
         };
@@ -744,25 +920,48 @@ impl RunbookTopLevelInputsMap {
                 continue; // Skip global inputs, their values are added to all environments but should not be listed as an environment
             }
             let mut env_values = vec![];
+            let mut env_signer_aliases = global_signer_aliases.clone();
+            let mut env_addon_defaults = global_addon_defaults.clone();
             // Add global values to all environments
             for (key, value) in global_values.iter() {
                 env_values.push((key.to_string(), value.clone()));
             }
             // _Then_ add the environment specific values, overwriting the global ones in the case of collisions
             for (key, value) in inputs.iter() {
+                if let Some(alias) = key.strip_prefix(SIGNER_ALIAS_PREFIX) {
+                    env_signer_aliases.insert(alias.to_string(), value.to_string());
+                    continue;
+                }
+                if insert_addon_default(&mut env_addon_defaults, key, value) {
+                    continue;
+                }
                 env_values.push((key.to_string(), Value::parse_and_default_to_string(value)));
             }
             environments.push(selector.to_string());
             values.insert(Some(selector.to_string()), env_values);
+            signer_aliases.insert(Some(selector.to_string()), env_signer_aliases);
+            addon_defaults.insert(Some(selector.to_string()), env_addon_defaults);
         }
 
         Self {
             current_environment: selector.clone().or(environments.get(0).map(|v| v.to_string())),
             environments,
             values,
+            signer_aliases,
+            addon_defaults,
         }
     }
 
+    /// Returns the signer aliases bound by the current environment.
+    pub fn current_signer_aliases(&self) -> IndexMap<String, String> {
+        self.signer_aliases.get(&self.current_environment).cloned().unwrap_or_default()
+    }
+
+    /// Returns the addon defaults set by the current environment, keyed by addon id.
+    pub fn current_addon_defaults(&self) -> IndexMap<String, ValueMap> {
+        self.addon_defaults.get(&self.current_environment).cloned().unwrap_or_default()
+    }
+
     pub fn current_top_level_input_name(&self) -> String {
         self.current_environment
             .clone()
@@ -777,6 +976,71 @@ impl RunbookTopLevelInputsMap {
         current_map
     }
 
+    /// Returns the inputs of the current environment, once overridden by the input files and the
+    /// cli inputs.
+    pub fn current_values(&self) -> Vec<(String, Value)> {
+        self.values.get(&self.current_environment).cloned().unwrap_or_default()
+    }
+
+    /// Sets the value of `input_name` in every environment.
+    pub fn override_value(&mut self, input_name: &str, new_value: Value) {
+        for (_, values) in self.values.iter_mut() {
+            let mut found = false;
+            for (k, old_value) in values.iter_mut() {
+                if k.eq(input_name) {
+                    *old_value = new_value.clone();
+                    found = true;
+                }
+            }
+            if !found {
+                values.push((input_name.to_string(), new_value.clone()));
+            }
+        }
+    }
+
+    /// Overrides the values with the inputs of an input file: a JSON document if `file_name` has
+    /// a `.json` extension, a YAML document otherwise. The document must be a map of input names
+    /// to values, which keep their JSON / YAML types.
+    pub fn override_values_with_input_file(
+        &mut self,
+        file_name: &str,
+        content: &str,
+    ) -> Result<(), String> {
+        let inputs: IndexMap<String, JsonValue> = if file_name.ends_with(".json") {
+            serde_json::from_str(content).map_err(|e| e.to_string())
+        } else {
+            serde_yml::from_str(content).map_err(|e| e.to_string())
+        }
+        .map_err(|e| {
+            format!("unable to parse input file '{}': expected a map of inputs ({})", file_name, e)
+        })?;
+        for (input_name, value) in inputs.into_iter() {
+            self.override_value(&input_name, json_to_input_value(value));
+        }
+        Ok(())
+    }
+
+    /// Overrides the values with the inputs files, in order. An input file named `-` is read
+    /// from stdin.
+    pub fn override_values_with_input_files(
+        &mut self,
+        input_files: &Vec<String>,
+        buffer_stdin: &Option<String>,
+    ) -> Result<(), String> {
+        for input_file in input_files.iter() {
+            let content = if input_file.eq("-") {
+                buffer_stdin.clone().ok_or("--input-file -: no inputs piped to stdin".to_string())?
+            } else {
+                let location = FileLocation::from_path_string(input_file)?;
+                let bytes = location.read_content()?;
+                String::from_utf8(bytes)
+                    .map_err(|e| format!("unable to read input file '{}': {}", input_file, e))?
+            };
+            self.override_values_with_input_file(input_file, &content)?;
+        }
+        Ok(())
+    }
+
     pub fn override_values_with_cli_inputs(
         &mut self,
         inputs: &Vec<String>,
@@ -794,23 +1058,56 @@ impl RunbookTopLevelInputsMap {
                 _ => input_value.to_string(),
             };
             let new_value = Value::parse_and_default_to_string(&input_value);
-            for (_, values) in self.values.iter_mut() {
-                let mut found = false;
-                for (k, old_value) in values.iter_mut() {
-                    if k.eq(&input_name) {
-                        *old_value = new_value.clone();
-                        found = true;
-                    }
-                }
-                if !found {
-                    values.push((input_name.to_string(), new_value.clone()));
-                }
-            }
+            self.override_value(input_name, new_value);
         }
         Ok(())
     }
 }
 
+/// Records an `addon.<addon_id>.<key>` environment entry in `addon_defaults`, returning false if
+/// `key` doesn't set an addon default. Dotted keys set nested fields, deep-merged with the
+/// entries already recorded: `addon.evm.fee.strategy` sets the `strategy` field of the `fee` default.
+fn insert_addon_default(
+    addon_defaults: &mut IndexMap<String, ValueMap>,
+    key: &str,
+    value: &str,
+) -> bool {
+    let Some((addon_id, path)) =
+        key.strip_prefix(ADDON_DEFAULT_PREFIX).and_then(|path| path.split_once('.'))
+    else {
+        return false;
+    };
+    let mut segments = path.split('.');
+    let field = segments.next().unwrap_or_default();
+    let value = segments.rev().fold(Value::parse_and_default_to_string(value), |value, segment| {
+        Value::object(IndexMap::from([(segment.to_string(), value)]))
+    });
+    let mut overrides = ValueMap::new();
+    overrides.insert(field, value);
+    addon_defaults.entry(addon_id.to_string()).or_insert_with(ValueMap::new).deep_merge(&overrides);
+    true
+}
+
+// todo: coerce the values against the declared types of the inputs, once they can be declared
+fn json_to_input_value(value: JsonValue) -> Value {
+    match value {
+        JsonValue::Null => Value::null(),
+        JsonValue::Bool(b) => Value::bool(b),
+        JsonValue::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Value::integer(i as i128),
+            (None, Some(u)) => Value::integer(u as i128),
+            _ => Value::float(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(s) => Value::string(s),
+        JsonValue::Array(values) => {
+            Value::array(values.into_iter().map(json_to_input_value).collect())
+        }
+        JsonValue::Object(props) => Value::object(
+            props.into_iter().map(|(k, v)| (k, json_to_input_value(v))).collect(),
+        ),
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RunbookSources {
     /// Map of files required to construct the runbook
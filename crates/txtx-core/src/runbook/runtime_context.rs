@@ -3,6 +3,7 @@ use kit::types::cloud_interface::CloudServiceContext;
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use txtx_addon_kit::types::commands::DependencyExecutionResultCache;
+use txtx_addon_kit::types::redaction::SecretRegistry;
 use txtx_addon_kit::types::stores::AddonDefaults;
 use txtx_addon_kit::types::stores::ValueStore;
 use txtx_addon_kit::{
@@ -10,19 +11,20 @@ use txtx_addon_kit::{
     helpers::fs::FileLocation,
     types::{
         commands::{
-            CommandId, CommandInputsEvaluationResult, CommandInstance, CommandInstanceType,
-            PreCommandSpecification,
+            AssertionResult, CommandId, CommandInputsEvaluationResult, CommandInstance,
+            CommandInstanceType, PreCommandSpecification,
         },
         diagnostics::Diagnostic,
         functions::FunctionSpecification,
         signers::{SignerInstance, SignerSpecification},
-        types::Value,
+        types::{AddonDisplayFormatter, Value},
         AuthorizationContext, ConstructDid, ContractSourceTransform, Did, PackageDid, PackageId,
         RunbookId,
     },
     Addon,
 };
 
+use crate::errors::codes::{UNKNOWN_ADDON, UNKNOWN_COMMAND, UNKNOWN_FUNCTION};
 use crate::eval::eval_expression;
 use crate::{
     eval::{self, ExpressionEvaluationStatus},
@@ -45,6 +47,8 @@ pub struct RuntimeContext {
     pub authorization_context: AuthorizationContext,
     /// Cloud service configuration
     pub cloud_service_context: CloudServiceContext,
+    /// Secrets recorded while evaluating the sensitive inputs of the current run
+    pub secrets: SecretRegistry,
 }
 
 impl RuntimeContext {
@@ -59,6 +63,7 @@ impl RuntimeContext {
             concurrency: 1,
             authorization_context,
             cloud_service_context,
+            secrets: SecretRegistry::new(),
         }
     }
 
@@ -283,6 +288,9 @@ impl RuntimeContext {
         runbook_execution_context: &RunbookExecutionContext,
     ) -> Result<AddonDefaults, Diagnostic> {
         let mut addon_defaults = existing_addon_defaults.unwrap_or(AddonDefaults::new(addon_id));
+        // `addon "evm" "base" { ... }` sets the defaults of the `base` instance of the addon
+        let instance = block.labels.get(1).map(|label| label.as_str().to_string());
+        let scope = addon_defaults.scope_mut(instance.as_deref());
 
         let map_entries = self.evaluate_hcl_map_blocks(
             block.body.blocks().collect(),
@@ -294,7 +302,7 @@ impl RuntimeContext {
 
         for (key, value) in map_entries {
             // don't check for duplicate keys in map evaluation
-            addon_defaults.insert(&key, Value::array(value));
+            scope.insert(&key, Value::array(value));
         }
 
         for attribute in block.body.attributes() {
@@ -312,14 +320,14 @@ impl RuntimeContext {
                 Err(diag) => return Err(diag),
                 w => unimplemented!("{:?}", w),
             };
-            if addon_defaults.contains_key(&key) {
+            if scope.contains_key(&key) {
                 return Err(diagnosed_error!(
                     "duplicate key '{}' in '{}' addon defaults",
                     key,
                     addon_id
                 ));
             }
-            addon_defaults.insert(&key, value);
+            scope.insert(&key, value);
         }
         Ok(addon_defaults)
     }
@@ -436,14 +444,14 @@ impl RuntimeContext {
         Ok(entries)
     }
 
-    pub fn execute_function(
+    /// Looks up the specification of a function, from the standard functions or from the
+    /// functions of an addon registered by the package.
+    pub fn get_function(
         &self,
         package_did: PackageDid,
         namespace_opt: Option<String>,
         name: &str,
-        args: &Vec<Value>,
-        authorization_context: &AuthorizationContext,
-    ) -> Result<Value, Diagnostic> {
+    ) -> Result<&FunctionSpecification, Diagnostic> {
         let function = match namespace_opt {
             Some(namespace) => match self
                 .addons_context
@@ -456,19 +464,79 @@ impl RuntimeContext {
                         return Err(diagnosed_error!(
                             "could not find function {name} in namespace {}",
                             namespace
-                        ))
+                        )
+                        .with_diagnostic_code(&UNKNOWN_FUNCTION))
                     }
                 },
-                None => return Err(diagnosed_error!("could not find namespace {}", namespace)),
+                None => {
+                    return Err(diagnosed_error!("could not find namespace {}", namespace)
+                        .with_diagnostic_code(&UNKNOWN_FUNCTION))
+                }
             },
             None => match self.functions.get(name) {
                 Some(function) => function,
                 None => {
-                    return Err(diagnosed_error!("could not find function {name}"));
+                    return Err(diagnosed_error!("could not find function {name}")
+                        .with_diagnostic_code(&UNKNOWN_FUNCTION));
                 }
             },
         };
-        (function.runner)(function, authorization_context, args)
+        Ok(function)
+    }
+
+    pub fn execute_function(
+        &self,
+        package_did: PackageDid,
+        namespace_opt: Option<String>,
+        name: &str,
+        args: &Vec<Value>,
+        authorization_context: &AuthorizationContext,
+    ) -> Result<Value, Diagnostic> {
+        let function = self.get_function(package_did, namespace_opt, name)?;
+        let res = (function.runner)(function, authorization_context, args);
+        if !args.iter().any(|arg| arg.contains_addon_data()) {
+            return res;
+        }
+        // messages interpolating addon values print their display form rather than hex
+        let formatters = self.display_formatters();
+        let interpolate = |text: &str| {
+            args.iter()
+                .fold(text.to_string(), |text, arg| arg.interpolate_display(&text, &formatters))
+        };
+        match res {
+            Ok(value) => match AssertionResult::from_value(&value) {
+                Ok(AssertionResult::Failure(message)) if value.as_addon_data().is_some() => {
+                    Ok(AssertionResult::Failure(interpolate(&message)).to_value())
+                }
+                _ => Ok(value),
+            },
+            Err(mut diag) => {
+                diag.message = interpolate(&diag.message);
+                Err(diag)
+            }
+        }
+    }
+
+    /// Formatters of the addon values, provided by the registered addons (see
+    /// [Addon::format_display]).
+    pub fn display_formatters(&self) -> Vec<AddonDisplayFormatter> {
+        let mut addons = self.addons_context.registered_addons.iter().collect::<Vec<_>>();
+        addons.sort_by(|(a, _), (b, _)| a.cmp(b));
+        addons
+            .into_iter()
+            .map(|(_, (addon, _))| {
+                Box::new(move |value: &Value| addon.format_display(value)) as AddonDisplayFormatter
+            })
+            .collect()
+    }
+
+    /// Display form of a value holding addon values (e.g. a checksummed address), or `None` if
+    /// the value holds none.
+    pub fn format_display(&self, value: &Value) -> Option<String> {
+        if !value.contains_addon_data() {
+            return None;
+        }
+        Some(value.to_display_string(&self.display_formatters()))
     }
 }
 
@@ -505,7 +573,8 @@ impl AddonsContext {
             self.register(package_did, addon_id, scope)?;
             Ok(())
         } else {
-            Err(diagnosed_error!("addon '{}' not registered", addon_id))
+            Err(diagnosed_error!("addon '{}' not registered", addon_id)
+                .with_diagnostic_code(&UNKNOWN_ADDON))
         }
     }
 
@@ -517,7 +586,8 @@ impl AddonsContext {
     ) -> Result<(), Diagnostic> {
         let key = (package_did.clone(), addon_id.to_string());
         let Some(addon) = (self.get_addon_by_namespace)(addon_id) else {
-            return Err(diagnosed_error!("unable to find addon {}", addon_id));
+            return Err(diagnosed_error!("unable to find addon {}", addon_id)
+                .with_diagnostic_code(&UNKNOWN_ADDON));
         };
         if self.addon_construct_factories.contains_key(&key) {
             return Ok(());
@@ -544,7 +614,8 @@ impl AddonsContext {
             return Err(diagnosed_error!(
                 "unable to instantiate construct, addon '{}' unknown",
                 namespace
-            ));
+            )
+            .with_diagnostic_code(&UNKNOWN_ADDON));
         };
         Ok(factory)
     }
@@ -608,7 +679,8 @@ impl AddonConstructFactory {
                 command_name,
                 namespace,
                 command_id.action_name(),
-            ));
+            )
+            .with_diagnostic_code(&UNKNOWN_COMMAND));
         };
         let typing = match command_id {
             CommandId::Action(command_id) => CommandInstanceType::Action(command_id.clone()),
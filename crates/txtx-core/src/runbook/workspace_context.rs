@@ -20,7 +20,7 @@ use txtx_addon_kit::types::embedded_runbooks::{
 };
 use txtx_addon_kit::types::package::Package;
 use txtx_addon_kit::types::signers::SignerInstance;
-use txtx_addon_kit::types::stores::AddonDefaults;
+use txtx_addon_kit::types::stores::{AddonDefaults, ValueMap};
 use txtx_addon_kit::types::types::Value;
 use txtx_addon_kit::types::AddonInstance;
 use txtx_addon_kit::types::{ConstructDid, ConstructId, Did, PackageDid, PackageId, RunbookId};
@@ -53,6 +53,10 @@ pub struct RunbookWorkspaceContext {
     pub top_level_inputs_values: BTreeMap<ConstructDid, Value>,
     /// Lookup: Retrieve an addon's defaults given a package and addon id
     pub addons_defaults: HashMap<(PackageDid, String), AddonDefaults>,
+    /// Lookup: Retrieve the defaults of an addon declared by the active environment ('addon.<addon_id>.<key>' entries)
+    pub environment_addons_defaults: IndexMap<String, ValueMap>,
+    /// Lookup: Retrieve the name of the signer bound to a signer alias ('name' in signer.name) for the active environment
+    pub signer_aliases: IndexMap<String, String>,
 
     std_defaults: AddonDefaults,
 }
@@ -66,12 +70,51 @@ impl RunbookWorkspaceContext {
             top_level_inputs_did_lookup: BTreeMap::new(),
             top_level_inputs_values: BTreeMap::new(),
             addons_defaults: HashMap::new(),
+            environment_addons_defaults: IndexMap::new(),
+            signer_aliases: IndexMap::new(),
             std_defaults: AddonDefaults::new("std"),
         }
     }
 
-    pub fn get_addon_defaults(&self, key: &(PackageDid, String)) -> &AddonDefaults {
-        self.addons_defaults.get(key).unwrap_or(&self.std_defaults)
+    /// Resolves the signer bound to `alias` for the active environment. The bound signer is looked up
+    /// in `source_package_id` first, then in the other packages (such as a shared signers file).
+    pub fn resolve_signer_alias(
+        &self,
+        source_package_id: &PackageId,
+        alias: &str,
+    ) -> Option<ConstructDid> {
+        let signer_name = self.signer_aliases.get(alias)?;
+        if let Some(construct_did) = self
+            .packages
+            .get(source_package_id)
+            .and_then(|package| package.signers_did_lookup.get(signer_name))
+        {
+            return Some(construct_did.clone());
+        }
+        let mut packages = self.packages.iter().collect::<Vec<_>>();
+        packages.sort_by(|(a, _), (b, _)| a.did().0.cmp(&b.did().0));
+        packages
+            .into_iter()
+            .find_map(|(_, package)| package.signers_did_lookup.get(signer_name))
+            .cloned()
+    }
+
+    /// Resolves the defaults of an addon for a command of a package, targeting the addon block
+    /// labeled `instance` if any. See [AddonDefaults] for the order of precedence.
+    pub fn get_addon_defaults(
+        &self,
+        key: &(PackageDid, String),
+        instance: Option<&str>,
+    ) -> Result<AddonDefaults, Diagnostic> {
+        let environment_defaults = self.environment_addons_defaults.get(&key.1);
+        match self.addons_defaults.get(key) {
+            Some(addon_defaults) => addon_defaults.resolve(environment_defaults, instance),
+            None => {
+                let mut addon_defaults = AddonDefaults::new(&key.1);
+                addon_defaults.uuid = self.std_defaults.uuid.clone();
+                addon_defaults.resolve(environment_defaults, instance)
+            }
+        }
     }
 
     pub fn sorted_addons_defaults_fingerprints(
@@ -82,15 +125,21 @@ impl RunbookWorkspaceContext {
                 .clone()
                 .into_iter()
                 .map(|((package_did, addon_id), defaults)| {
-                    let mut addon_defaults_values = IndexMap::from([(
-                        addon_id,
-                        defaults
-                            .store
-                            .store
-                            .into_iter()
-                            .map(|(k, v)| (k, v.compute_fingerprint()))
-                            .collect(),
-                    )]);
+                    let mut fingerprints: IndexMap<String, Did> = defaults
+                        .store
+                        .store
+                        .into_iter()
+                        .map(|(k, v)| (k, v.compute_fingerprint()))
+                        .collect();
+                    for (label, instance_defaults) in defaults.instances.into_iter() {
+                        fingerprints.extend(
+                            instance_defaults
+                                .store
+                                .into_iter()
+                                .map(|(k, v)| (format!("{label}:{k}"), v.compute_fingerprint())),
+                        );
+                    }
+                    let mut addon_defaults_values = IndexMap::from([(addon_id, fingerprints)]);
                     addon_defaults_values.sort_keys();
                     (package_did, addon_defaults_values)
                 })
@@ -398,8 +447,13 @@ impl RunbookWorkspaceContext {
                             );
                             continue;
                         };
+                        // labeled blocks of the same addon are distinct instances of it
+                        let construct_name = match typed_block.labels.get(1) {
+                            Some(instance) => format!("{}.{}", addon_id, instance.as_str()),
+                            None => addon_id.to_string(),
+                        };
                         let _ = self.index_construct(
-                            addon_id.to_string(),
+                            construct_name,
                             location.clone(),
                             PreConstructData::Addon(typed_block.clone_inner()),
                             &package_id,
@@ -419,6 +473,21 @@ impl RunbookWorkspaceContext {
             }
         }
 
+        for (alias, signer_name) in self.signer_aliases.iter() {
+            let is_defined = self
+                .packages
+                .values()
+                .any(|package| package.signers_did_lookup.contains_key(signer_name));
+            if !is_defined {
+                diagnostics.push(diagnosed_error!(
+                    "signer alias '{}' is bound to unknown signer '{}' in environment '{}'",
+                    alias,
+                    signer_name,
+                    environment_selector.as_deref().unwrap_or("default")
+                ));
+            }
+        }
+
         if diagnostics.is_empty() {
             Ok(())
         } else {
@@ -522,7 +591,11 @@ impl RunbookWorkspaceContext {
                 ConstructInstanceType::Addon(AddonInstance {
                     block: block.clone(),
                     package_id: package_id.clone(),
-                    addon_id: construct_name.clone(),
+                    addon_id: block
+                        .labels
+                        .first()
+                        .map(|label| label.as_str().to_string())
+                        .unwrap_or(construct_name.clone()),
                 })
             }
             PreConstructData::Output(block) => {
@@ -638,10 +711,14 @@ impl RunbookWorkspaceContext {
             if let Some(input_names) =
                 self.get_top_level_input_name_from_expression_reference(&expr)
             {
-                let addon_defaults = self.get_addon_defaults(&(
-                    addon_instance.package_id.did(),
-                    addon_instance.addon_id.clone(),
-                ));
+                let instance =
+                    addon_instance.block.labels.get(1).map(|label| label.as_str().to_string());
+                let Ok(addon_defaults) = self.get_addon_defaults(
+                    &(addon_instance.package_id.did(), addon_instance.addon_id.clone()),
+                    instance.as_deref(),
+                ) else {
+                    continue;
+                };
                 for input_name in input_names {
                     if let Some(value) = addon_defaults.store.get_value(&input_name) {
                         embedded_runbook_inputs.push(EmbeddedRunbookInputSpecification::new_value(
@@ -874,6 +951,12 @@ impl RunbookWorkspaceContext {
                     let Some(signer_name) = components.pop_front() else {
                         continue;
                     };
+                    // Aliases bound by the active environment take precedence over the package's signers
+                    if let Some(construct_did) =
+                        self.resolve_signer_alias(source_package_id, &signer_name)
+                    {
+                        return Ok(Some((construct_did, components, subpath)));
+                    }
                     if let Some(construct_did) =
                         current_package.signers_did_lookup.get(&signer_name)
                     {
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use txtx_addon_kit::reqwest::{self, Method};
 use txtx_addon_kit::types::commands::{CommandExecutionFutureResult, PreCommandSpecification};
 use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
@@ -6,7 +8,7 @@ use txtx_addon_kit::types::types::RunbookSupervisionContext;
 use txtx_addon_kit::types::ConstructDid;
 use txtx_addon_kit::types::{
     commands::{CommandExecutionResult, CommandImplementation, CommandSpecification},
-    diagnostics::Diagnostic,
+    diagnostics::{Diagnostic, DiagnosticKind},
     types::{Type, Value},
 };
 use txtx_addon_kit::{define_command, indoc};
@@ -119,6 +121,7 @@ impl CommandImplementation for SendHttpRequest {
             Method::try_from(value).unwrap()
         };
         let request_headers = values.get_value("headers").cloned();
+        let timeout_ms = values.get_integer("timeout_ms").map(|t| t.max(0) as u64);
 
         let future = async move {
             let request_headers = request_headers
@@ -130,7 +133,13 @@ impl CommandImplementation for SendHttpRequest {
                 })
                 .transpose()?;
 
-            let client = reqwest::Client::new();
+            let mut client_builder = reqwest::Client::builder();
+            if let Some(timeout_ms) = timeout_ms {
+                client_builder = client_builder.timeout(Duration::from_millis(timeout_ms));
+            }
+            let client = client_builder
+                .build()
+                .map_err(|e| diagnosed_error!("unable to build http client - {e}"))?;
             let mut req_builder = client.request(method, url);
 
             if let Some(request_headers) = request_headers {
@@ -153,7 +162,12 @@ impl CommandImplementation for SendHttpRequest {
             }
 
             let res = req_builder.send().await.map_err(|e| {
-                Diagnostic::error_from_string(format!("unable to send http request - {e}"))
+                let diag =
+                    Diagnostic::error_from_string(format!("unable to send http request - {e}"));
+                match e.is_timeout() {
+                    true => diag.with_kind(DiagnosticKind::Timeout),
+                    false => diag,
+                }
             })?;
 
             let status_code = res.status();
@@ -1,6 +1,12 @@
 use txtx_addon_kit::types::commands::PreCommandSpecification;
 
 pub mod http;
+pub mod request_file;
+pub mod webhook;
 lazy_static! {
-    pub static ref ACTIONS: Vec<PreCommandSpecification> = vec![http::SEND_HTTP_REQUEST.clone(),];
+    pub static ref ACTIONS: Vec<PreCommandSpecification> = vec![
+        http::SEND_HTTP_REQUEST.clone(),
+        webhook::SEND_WEBHOOK.clone(),
+        request_file::REQUEST_FILE.clone()
+    ];
 }
@@ -0,0 +1,169 @@
+use txtx_addon_kit::constants::DESCRIPTION;
+use txtx_addon_kit::types::commands::{
+    return_synchronous_result, CommandExecutionFutureResult, PreCommandSpecification,
+};
+use txtx_addon_kit::types::frontend::{Actions, BlockEvent, ProvideFileRequest};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::RunbookSupervisionContext;
+use txtx_addon_kit::types::{
+    commands::{CommandExecutionResult, CommandImplementation, CommandSpecification},
+    diagnostics::Diagnostic,
+    types::{Type, Value},
+};
+use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+use txtx_addon_kit::{define_command, indoc};
+
+use crate::constants::ACTION_ITEM_PROVIDE_FILE;
+
+/// Input receiving the file uploaded in the supervisor
+pub const FILE: &str = "file";
+
+lazy_static! {
+    pub static ref REQUEST_FILE: PreCommandSpecification = define_command! {
+        RequestFile => {
+            name: "Request a file",
+            matcher: "request_file",
+            documentation: indoc!{r#"
+            `std::request_file` asks the operator of a supervised run to upload a file, and makes its content available to the rest of the runbook.
+            The upload can be restricted to some file extensions, and to a maximum size; files not meeting these expectations are rejected.
+            Files can only be requested in supervised runs."#},
+            implements_signing_capability: false,
+            implements_background_task_capability: false,
+            inputs: [
+                extensions: {
+                    documentation: "The extensions of the files accepted, without the leading dot (e.g. `csv`). Any file is accepted by default.",
+                    typing: Type::array(Type::string()),
+                    optional: true,
+                    tainting: true,
+                    internal: false
+                },
+                max_size: {
+                    documentation: "The maximum size of the file, in bytes.",
+                    typing: Type::integer(),
+                    optional: true,
+                    tainting: true,
+                    internal: false
+                },
+                file: {
+                    documentation: "The file uploaded in the supervisor.",
+                    typing: Type::arbitrary_object(),
+                    optional: true,
+                    tainting: false,
+                    internal: true
+                }
+            ],
+            outputs: [
+                filename: {
+                    documentation: "The name of the file uploaded.",
+                    typing: Type::string()
+                },
+                content: {
+                    documentation: "The content of the file uploaded.",
+                    typing: Type::buffer()
+                },
+                size: {
+                    documentation: "The size of the file uploaded, in bytes.",
+                    typing: Type::integer()
+                }
+            ],
+            example: indoc!{r#"
+            action "allowlist" "std::request_file" {
+              description = "Addresses allowed to mint, one per line"
+              extensions = ["csv", "txt"]
+              max_size = 65536
+            }
+
+            output "allowlist_name" {
+              value = action.allowlist.filename
+            }
+            // > allowlist_name: allowlist.csv
+            "#},
+        }
+    };
+}
+
+pub struct RequestFile;
+
+impl CommandImplementation for RequestFile {
+    fn check_instantiability(
+        _ctx: &CommandSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn check_executability(
+        construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &CommandSpecification,
+        values: &ValueStore,
+        supervision_context: &RunbookSupervisionContext,
+        auth_context: &AuthorizationContext,
+    ) -> Result<Actions, Diagnostic> {
+        let request = get_file_request(values)?;
+        if let Some(file) = values.get_value(FILE) {
+            let (filename, content) = get_file(file)?;
+            request
+                .check_file(&filename, content.len() as u64)
+                .map_err(|e| diagnosed_error!("{}", e))?;
+            return Ok(Actions::none());
+        }
+        if !supervision_context.is_supervised {
+            return Err(diagnosed_error!(
+                "action '{}': files can only be requested in supervised runs",
+                instance_name
+            ));
+        }
+
+        let markdown = values.get_markdown(auth_context)?;
+        let action = request
+            .to_action_type()
+            .to_request(instance_name, ACTION_ITEM_PROVIDE_FILE)
+            .with_construct_did(construct_did)
+            .with_some_markdown(markdown);
+        Ok(Actions::new_sub_group_of_items(None, vec![action]))
+    }
+
+    fn run_execution(
+        _construct_id: &ConstructDid,
+        _spec: &CommandSpecification,
+        values: &ValueStore,
+        _progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+        _auth_ctx: &AuthorizationContext,
+    ) -> CommandExecutionFutureResult {
+        let (filename, content) = get_file(values.get_expected_value(FILE)?)?;
+        let mut result = CommandExecutionResult::new();
+        result.outputs.insert("filename".into(), Value::string(filename));
+        result.outputs.insert("size".into(), Value::integer(content.len() as i128));
+        result.outputs.insert("content".into(), Value::buffer(content));
+        return_synchronous_result(Ok(result))
+    }
+}
+
+fn get_file_request(values: &ValueStore) -> Result<ProvideFileRequest, Diagnostic> {
+    let mut request = ProvideFileRequest::new(FILE);
+    request.description = values.get_string(DESCRIPTION).map(|d| d.to_string());
+    if let Some(extensions) = values.get_array("extensions") {
+        request.expected_extensions = extensions
+            .iter()
+            .map(|e| {
+                e.as_string()
+                    .map(|e| e.to_string())
+                    .ok_or_else(|| diagnosed_error!("'extensions' must be an array of strings"))
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    request.max_size =
+        values.get_uint("max_size").map_err(|e| diagnosed_error!("invalid 'max_size': {}", e))?;
+    Ok(request)
+}
+
+/// Returns the name and the content of the file uploaded.
+fn get_file(file: &Value) -> Result<(String, Vec<u8>), Diagnostic> {
+    let filename = file.as_object().and_then(|f| f.get("filename")).and_then(|f| f.as_string());
+    let content = file.as_object().and_then(|f| f.get("content")).and_then(|c| c.as_buffer_data());
+    match (filename, content) {
+        (Some(filename), Some(content)) => Ok((filename.to_string(), content.clone())),
+        _ => Err(diagnosed_error!("'{}' must be a file uploaded in the supervisor", FILE)),
+    }
+}
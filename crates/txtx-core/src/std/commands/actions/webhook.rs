@@ -0,0 +1,430 @@
+use std::time::Duration;
+
+use txtx_addon_kit::hex;
+use txtx_addon_kit::hmac::{Hmac, Mac};
+use txtx_addon_kit::reqwest::{self, StatusCode};
+use txtx_addon_kit::serde_json::{json, Value as JsonValue};
+use txtx_addon_kit::sha2::Sha256;
+use txtx_addon_kit::types::commands::{CommandExecutionFutureResult, PreCommandSpecification};
+use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::{format_datetime, RunbookSupervisionContext};
+use txtx_addon_kit::types::{
+    commands::{CommandExecutionResult, CommandImplementation, CommandSpecification},
+    diagnostics::{Diagnostic, DiagnosticKind},
+    types::{Type, Value},
+};
+use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+use txtx_addon_kit::{define_command, indoc};
+
+pub const DEFAULT_SIGNATURE_HEADER: &str = "X-Txtx-Signature";
+pub const DEFAULT_EVENT: &str = "runbook.notification";
+pub const DEFAULT_STATUS: &str = "success";
+pub const DEFAULT_MAX_RETRIES: u64 = 3;
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 1000;
+
+lazy_static! {
+    pub static ref SEND_WEBHOOK: PreCommandSpecification = define_command! {
+        SendWebhook => {
+            name: "Send a webhook notification",
+            matcher: "send_webhook",
+            documentation: indoc!{r#"
+            `std::send_webhook` posts a JSON notification to the given URL.
+            The payload is wrapped in a standard envelope (event, runbook, environment, construct, status and timestamp), so that receivers can build integrations against a stable shape.
+            The runbook and environment reported are the ones being executed.
+            The `timestamp` input is a datetime, provided as an ISO-8601 string (e.g. `2025-01-31T12:00:00Z`) or as a number of seconds since the Unix epoch; malformed values are rejected before the webhook is sent.
+            When a `secret` is provided, the body is signed with HMAC-SHA256, and the signature is sent in the `signature_header` header as `sha256=<hex digest>`.
+            Requests failing with a network error, a `429` or a `5xx` status are retried with an exponential backoff.
+            A notification the receiver doesn't acknowledge with a `2xx` status, including one still failing once the retries are exhausted, is reported as a warning, without failing the runbook."#},
+            implements_signing_capability: false,
+            implements_background_task_capability: false,
+            inputs: [
+                url: {
+                    documentation: "The URL of the webhook. Supported schemes are http and https.",
+                    typing: Type::string(),
+                    optional: false,
+                    tainting: true,
+                    internal: false
+                },
+                payload: {
+                    documentation: "The object sent to the receiver, serialized to JSON in the `payload` field of the envelope.",
+                    typing: Type::arbitrary_object(),
+                    optional: true,
+                    tainting: true,
+                    internal: false
+                },
+                event: {
+                    documentation: "The name of the event. The default is 'runbook.notification'.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    internal: false
+                },
+                status: {
+                    documentation: "The status reported in the envelope. The default is 'success'.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    internal: false
+                },
+                secret: {
+                    documentation: "The secret used to sign the request body with HMAC-SHA256.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    internal: false,
+                    sensitive: true
+                },
+                signature_header: {
+                    documentation: "The header carrying the signature of the body. The default is 'X-Txtx-Signature'.",
+                    typing: Type::string(),
+                    optional: true,
+                    tainting: true,
+                    internal: false
+                },
+                headers: {
+                    documentation: "A map of additional request header field names and values.",
+                    typing: Type::arbitrary_object(),
+                    optional: true,
+                    tainting: true,
+                    internal: false
+                },
+                timestamp: {
+                    documentation: "The time reported in the envelope, as an ISO-8601 string or a number of seconds since the Unix epoch. The default is the time the notification is sent.",
+                    typing: Type::datetime(),
+                    optional: true,
+                    tainting: true,
+                    internal: false
+                },
+                max_retries: {
+                    documentation: "The number of times a failed delivery is retried. The default is 3.",
+                    typing: Type::integer(),
+                    optional: true,
+                    tainting: false,
+                    internal: false
+                },
+                retry_delay_ms: {
+                    documentation: "The delay before the first retry, in milliseconds, doubled on each subsequent retry. The default is 1000.",
+                    typing: Type::integer(),
+                    optional: true,
+                    tainting: false,
+                    internal: false
+                },
+                timeout_ms: {
+                    documentation: "The timeout of each request, in milliseconds.",
+                    typing: Type::integer(),
+                    optional: true,
+                    tainting: false,
+                    internal: false
+                }
+            ],
+            outputs: [
+                delivered: {
+                    documentation: "Whether the receiver acknowledged the notification with a 2xx status.",
+                    typing: Type::bool()
+                },
+                status_code: {
+                    documentation: "The HTTP status code of the last delivery attempt, or null if no response was received.",
+                    typing: Type::integer()
+                },
+                attempts: {
+                    documentation: "The number of delivery attempts made.",
+                    typing: Type::integer()
+                },
+                response_body: {
+                    documentation: "The response body of the last delivery attempt, or null if no response was received.",
+                    typing: Type::string()
+                }
+            ],
+            example: indoc!{r#"
+            action "notify" "std::send_webhook" {
+              url = input.webhook_url
+              secret = input.webhook_secret
+              payload = {
+                program_id = action.deploy.program_id
+              }
+            }
+
+            output "delivered" {
+              value = action.notify.delivered
+            }
+            // > delivered: true
+            "#},
+        }
+    };
+}
+
+pub struct SendWebhook;
+
+impl CommandImplementation for SendWebhook {
+    fn check_instantiability(
+        _ctx: &CommandSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn check_executability(
+        _construct_id: &ConstructDid,
+        _instance_name: &str,
+        _spec: &CommandSpecification,
+        _values: &ValueStore,
+        _supervision_context: &RunbookSupervisionContext,
+        _auth_context: &txtx_addon_kit::types::AuthorizationContext,
+    ) -> Result<Actions, Diagnostic> {
+        Ok(Actions::none())
+    }
+
+    fn run_execution(
+        construct_id: &ConstructDid,
+        _spec: &CommandSpecification,
+        values: &ValueStore,
+        _progress_tx: &txtx_addon_kit::channel::Sender<BlockEvent>,
+        auth_ctx: &AuthorizationContext,
+    ) -> CommandExecutionFutureResult {
+        let url = values.get_expected_string("url")?.to_string();
+        let secret = values.get_string("secret").map(|s| s.to_string());
+        let signature_header =
+            values.get_string("signature_header").unwrap_or(DEFAULT_SIGNATURE_HEADER).to_string();
+        let request_headers = values.get_value("headers").cloned();
+        let max_retries = get_u64(values, "max_retries", DEFAULT_MAX_RETRIES)?;
+        let retry_delay_ms = get_u64(values, "retry_delay_ms", DEFAULT_RETRY_DELAY_MS)?;
+        let timeout_ms = values.get_integer("timeout_ms").map(|t| t.max(0) as u64);
+        let body = build_envelope(construct_id, values, auth_ctx)?.to_string();
+
+        let future = async move {
+            let mut client_builder = reqwest::Client::builder();
+            if let Some(timeout_ms) = timeout_ms {
+                client_builder = client_builder.timeout(Duration::from_millis(timeout_ms));
+            }
+            let client = client_builder
+                .build()
+                .map_err(|e| diagnosed_error!("unable to build http client - {e}"))?;
+
+            let mut req_builder = client
+                .post(&url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone());
+            if let Some(secret) = secret {
+                req_builder = req_builder.header(&signature_header, sign_body(&secret, &body));
+            }
+            if let Some(request_headers) = request_headers {
+                let request_headers = request_headers
+                    .as_object()
+                    .ok_or_else(|| diagnosed_error!("request headers must be an object"))?;
+                for (k, v) in request_headers.iter() {
+                    req_builder = req_builder.header(
+                        k,
+                        v.as_string().ok_or_else(|| {
+                            diagnosed_error!("request header value must be a string; found type '{}' for header '{}'", v.get_type().to_string(), k)
+                        })?,
+                    );
+                }
+            }
+
+            let mut attempts = 0;
+            let delivery = loop {
+                attempts += 1;
+                let request = req_builder.try_clone().ok_or_else(|| {
+                    diagnosed_error!("unable to send webhook: request can't be retried")
+                })?;
+                let delivery = match request.send().await {
+                    Ok(res) => {
+                        let status_code = res.status();
+                        Delivery::Response(status_code, res.text().await.unwrap_or_default())
+                    }
+                    Err(e) => {
+                        Delivery::Failure { error: e.to_string(), is_timeout: e.is_timeout() }
+                    }
+                };
+                if !delivery.is_retryable() || attempts > max_retries {
+                    break delivery;
+                }
+                let delay = retry_delay_ms.saturating_mul(1 << (attempts - 1).min(10));
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            };
+
+            Ok::<CommandExecutionResult, Diagnostic>(report_delivery(delivery, attempts))
+        };
+        #[cfg(feature = "wasm")]
+        panic!("async commands are not enabled for wasm");
+        #[cfg(not(feature = "wasm"))]
+        Ok(Box::pin(future))
+    }
+}
+
+fn get_u64(values: &ValueStore, key: &str, default: u64) -> Result<u64, Diagnostic> {
+    match values.get_integer(key) {
+        Some(value) => u64::try_from(value)
+            .map_err(|_| diagnosed_error!("'{}' must be a positive integer", key)),
+        None => Ok(default),
+    }
+}
+
+/// Wraps the `payload` input in the envelope sent to webhook receivers, along with the runbook and
+/// environment being executed.
+pub fn build_envelope(
+    construct_id: &ConstructDid,
+    values: &ValueStore,
+    auth_ctx: &AuthorizationContext,
+) -> Result<JsonValue, Diagnostic> {
+    let timestamp = values.get_datetime("timestamp")?.unwrap_or_else(chrono::Utc::now);
+    Ok(json!({
+        "event": values.get_string("event").unwrap_or(DEFAULT_EVENT),
+        "runbook": auth_ctx.runbook_name,
+        "environment": auth_ctx.environment,
+        "construct": construct_id.to_string(),
+        "status": values.get_string("status").unwrap_or(DEFAULT_STATUS),
+        "timestamp": format_datetime(&timestamp),
+        "payload": values.get_value("payload").map(|p| p.to_json(None)).unwrap_or(JsonValue::Null),
+    }))
+}
+
+/// Computes the `sha256=<hex digest>` HMAC-SHA256 signature of `body`.
+pub fn sign_body(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC can take a key of any size");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn is_retryable(status_code: StatusCode) -> bool {
+    status_code == StatusCode::TOO_MANY_REQUESTS || status_code.is_server_error()
+}
+
+/// The outcome of the last delivery attempt of a webhook.
+enum Delivery {
+    /// The receiver responded, with the given status and body.
+    Response(StatusCode, String),
+    /// No response was received.
+    Failure { error: String, is_timeout: bool },
+}
+
+impl Delivery {
+    fn is_retryable(&self) -> bool {
+        match self {
+            Delivery::Response(status_code, _) => is_retryable(*status_code),
+            Delivery::Failure { .. } => true,
+        }
+    }
+}
+
+/// Reports the outcome of a delivery. Notifications not acknowledged with a 2xx status, whether
+/// the receiver responded or not, are reported as warnings.
+fn report_delivery(delivery: Delivery, attempts: u64) -> CommandExecutionResult {
+    let mut result = CommandExecutionResult::new();
+    let (delivered, status_code, response_body) = match delivery {
+        Delivery::Response(status_code, response_body) => {
+            if !status_code.is_success() {
+                result.diagnostics.push(Diagnostic::warning(format!(
+                    "webhook not acknowledged after {attempts} attempts: receiver responded with status {status_code}"
+                )));
+            }
+            (
+                status_code.is_success(),
+                Value::integer(status_code.as_u16().into()),
+                Value::string(response_body),
+            )
+        }
+        Delivery::Failure { error, is_timeout } => {
+            let diag = Diagnostic::warning(format!(
+                "webhook not acknowledged after {attempts} attempts: {error}"
+            ));
+            result.diagnostics.push(match is_timeout {
+                true => diag.with_kind(DiagnosticKind::Timeout),
+                false => diag,
+            });
+            (false, Value::null(), Value::null())
+        }
+    };
+    result.outputs.insert("delivered".into(), Value::bool(delivered));
+    result.outputs.insert("status_code".into(), status_code);
+    result.outputs.insert("attempts".into(), Value::integer(attempts.into()));
+    result.outputs.insert("response_body".into(), response_body);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use txtx_addon_kit::types::types::ObjectType;
+    use txtx_addon_kit::types::Did;
+
+    use super::*;
+
+    #[test]
+    fn it_signs_body_with_hmac_sha256() {
+        // test vector from RFC 4231, case 2
+        assert_eq!(
+            sign_body("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn it_builds_envelope() {
+        let construct_id = ConstructDid(Did::from_components(vec!["notify".as_bytes()]));
+        let mut auth_ctx = AuthorizationContext::empty();
+        auth_ctx.runbook_name = Some("deploy".into());
+        auth_ctx.environment = Some("devnet".into());
+        let mut values = ValueStore::tmp();
+        values.insert("status", Value::string("failure".into()));
+        values.insert(
+            "payload",
+            ObjectType::from(vec![("program_id", Value::string("abc".into()))]).to_value(),
+        );
+
+        let envelope = build_envelope(&construct_id, &values, &auth_ctx).unwrap();
+        assert_eq!(envelope["event"], DEFAULT_EVENT);
+        assert_eq!(envelope["runbook"], "deploy");
+        assert_eq!(envelope["environment"], "devnet");
+        assert_eq!(envelope["construct"], construct_id.to_string());
+        assert_eq!(envelope["status"], "failure");
+        assert_eq!(envelope["payload"]["program_id"], "abc");
+    }
+
+    #[test]
+    fn it_reports_timestamp_input_in_envelope() {
+        let construct_id = ConstructDid(Did::from_components(vec!["notify".as_bytes()]));
+        let auth_ctx = AuthorizationContext::empty();
+        let mut values = ValueStore::tmp();
+        values.insert("timestamp", Value::parse_datetime("2025-01-31T13:00:00+01:00").unwrap());
+        let envelope = build_envelope(&construct_id, &values, &auth_ctx).unwrap();
+        assert_eq!(envelope["timestamp"], "2025-01-31T12:00:00Z");
+        assert_eq!(envelope["environment"], JsonValue::Null);
+
+        values.insert("timestamp", Value::string("next tuesday".into()));
+        assert!(build_envelope(&construct_id, &values, &auth_ctx).is_err());
+    }
+
+    #[test]
+    fn it_retries_on_throttling_and_server_errors() {
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable(StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable(StatusCode::OK));
+        assert!(!is_retryable(StatusCode::BAD_REQUEST));
+    }
+
+    #[test]
+    fn it_reports_exhausted_retries_the_same_way_with_or_without_response() {
+        let responded = report_delivery(
+            Delivery::Response(StatusCode::SERVICE_UNAVAILABLE, "unavailable".into()),
+            4,
+        );
+        let unreachable = report_delivery(
+            Delivery::Failure { error: "connection refused".into(), is_timeout: false },
+            4,
+        );
+        for result in [&responded, &unreachable] {
+            assert_eq!(result.outputs["delivered"].as_bool(), Some(false));
+            assert_eq!(result.outputs["attempts"].as_integer(), Some(4));
+            assert_eq!(result.diagnostics.len(), 1);
+            assert!(result.diagnostics[0].is_warning());
+        }
+        assert_eq!(responded.outputs["status_code"].as_integer(), Some(503));
+        assert_eq!(unreachable.outputs["status_code"].as_null(), Some(()));
+
+        let timed_out =
+            report_delivery(Delivery::Failure { error: "timed out".into(), is_timeout: true }, 4);
+        assert_eq!(timed_out.diagnostics[0].kind, Some(DiagnosticKind::Timeout));
+    }
+}
@@ -180,6 +180,7 @@ impl CommandImplementation for Variable {
                 default_value: Some(value.to_owned()),
                 input_name: "value".into(),
                 typing: value.get_type(),
+                sensitive: false,
             })
             .to_request(title, "provide_input")
             .with_some_description(description)
@@ -286,6 +287,7 @@ impl CommandImplementation for Output {
                 name: instance_name.into(),
                 description: None,
                 value: value.clone(),
+                formatted_value: None,
             })
             .to_request(instance_name, ACTION_ITEM_CHECK_OUTPUT)
             .with_construct_did(construct_did)
@@ -143,7 +143,7 @@ mod tests {
     }
 
     fn dummy_auth_ctx() -> AuthorizationContext {
-        AuthorizationContext { workspace_location: FileLocation::working_dir() }
+        AuthorizationContext::new(FileLocation::working_dir())
     }
 
     fn hex_to_buffer(hex: &str) -> Value {
@@ -1,3 +1,4 @@
+use super::arg_checker;
 use base64::{engine::general_purpose, Engine};
 use txtx_addon_kit::types::AuthorizationContext;
 use txtx_addon_kit::{
@@ -69,10 +70,11 @@ impl FunctionImplementation for Base64Decode {
     }
 
     fn run(
-        _fn_spec: &FunctionSpecification,
+        fn_spec: &FunctionSpecification,
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        arg_checker(fn_spec, args)?;
         let encoded = args.get(0).unwrap().expect_string();
         let decoded = general_purpose::STANDARD.decode(encoded).map_err(|e| {
             Diagnostic::error_from_string(format!(
@@ -119,10 +121,11 @@ impl FunctionImplementation for Base64Encode {
     }
 
     fn run(
-        _fn_spec: &FunctionSpecification,
+        fn_spec: &FunctionSpecification,
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        arg_checker(fn_spec, args)?;
         let bytes = get_bytes_for_encoding(args.get(0).unwrap())?;
         let encoded = general_purpose::STANDARD.encode(bytes);
         Ok(Value::string(encoded))
@@ -141,7 +144,7 @@ mod tests {
     }
 
     fn dummy_auth_ctx() -> AuthorizationContext {
-        AuthorizationContext { workspace_location: FileLocation::working_dir() }
+        AuthorizationContext::new(FileLocation::working_dir())
     }
 
     #[test_case(
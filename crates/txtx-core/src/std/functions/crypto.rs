@@ -59,10 +59,18 @@ impl FunctionImplementation for Secp256k1Recover {
         };
 
         let signature_bytes = signature.to_be_bytes();
-        let message = Message::parse_slice(&message.to_be_bytes()).unwrap();
-        let recovery_id = RecoveryId::parse(signature_bytes[0]).unwrap();
-        let signature = Signature::parse_standard_slice(&signature_bytes[1..]).unwrap();
-        let public_key = recover(&message, &signature, &recovery_id).unwrap();
+        let message = Message::parse_slice(&message.to_be_bytes())
+            .map_err(|e| diagnosed_error!("{}: invalid message: {:?}", fn_spec.name, e))?;
+        let Some((recovery_id, signature_bytes)) = signature_bytes.split_first() else {
+            return Err(diagnosed_error!("{}: invalid signature: empty", fn_spec.name));
+        };
+        let recovery_id = RecoveryId::parse(*recovery_id)
+            .map_err(|e| diagnosed_error!("{}: invalid recovery id: {:?}", fn_spec.name, e))?;
+        let signature = Signature::parse_standard_slice(signature_bytes)
+            .map_err(|e| diagnosed_error!("{}: invalid signature: {:?}", fn_spec.name, e))?;
+        let public_key = recover(&message, &signature, &recovery_id).map_err(|e| {
+            diagnosed_error!("{}: unable to recover public key: {:?}", fn_spec.name, e)
+        })?;
         let public_key_hex = txtx_addon_kit::hex::encode(public_key.serialize_compressed());
 
         Ok(Value::string(format!("0x{}", public_key_hex)))
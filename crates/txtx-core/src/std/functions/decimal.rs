@@ -0,0 +1,243 @@
+use super::{arg_checker, to_diag};
+use txtx_addon_kit::bigdecimal::{BigDecimal, RoundingMode};
+use txtx_addon_kit::types::types::{
+    decimal_from_base_units, decimal_to_base_units, decimal_to_i128,
+};
+use txtx_addon_kit::types::AuthorizationContext;
+use txtx_addon_kit::{
+    define_function, indoc,
+    types::{
+        diagnostics::Diagnostic,
+        functions::{FunctionImplementation, FunctionSpecification},
+        types::{Type, Value},
+    },
+};
+
+lazy_static! {
+    pub static ref FUNCTIONS: Vec<FunctionSpecification> = vec![
+        define_function! {
+            ToDecimal => {
+                name: "to_decimal",
+                documentation: "`to_decimal` converts a number, or a string holding a number, to an arbitrary-precision decimal.",
+                example: indoc!{r#"
+                    output "amount" {
+                        value = to_decimal("1.000000000000000001")
+                    }
+                    > amount: 1.000000000000000001
+                "#},
+                inputs: [
+                    value: {
+                        documentation: "The `integer`, `float`, `decimal` or `string` value to convert.",
+                        typing: vec![Type::integer(), Type::float(), Type::decimal(), Type::string()],
+                        optional: false
+                    }
+                ],
+                output: {
+                    documentation: "The value as a decimal.",
+                    typing: Type::decimal()
+                },
+            }
+        },
+        define_function! {
+            DecimalToString => {
+                name: "decimal_to_string",
+                documentation: "`decimal_to_string` formats a decimal in plain notation, optionally rounded to a number of fractional digits.",
+                example: indoc!{r#"
+                    output "amount" {
+                        value = decimal_to_string(to_decimal("2.345"), 2)
+                    }
+                    > amount: 2.34
+                "#},
+                inputs: [
+                    value: {
+                        documentation: "The `decimal` or `integer` value to format.",
+                        typing: vec![Type::decimal(), Type::integer()],
+                        optional: false
+                    },
+                    precision: {
+                        documentation: "The number of fractional digits to round the value to, rounding half to even.",
+                        typing: vec![Type::integer()],
+                        optional: true
+                    }
+                ],
+                output: {
+                    documentation: "The formatted decimal.",
+                    typing: Type::string()
+                },
+            }
+        },
+        define_function! {
+            ToBaseUnits => {
+                name: "to_base_units",
+                documentation: "`to_base_units` scales an amount by a number of decimals, e.g. to convert ETH to wei or USDC to its 6-decimals base units. Amounts with more fractional digits than `decimals` are rejected instead of truncated.",
+                example: indoc!{r#"
+                    output "usdc_amount" {
+                        value = to_base_units(1.5, 6)
+                    }
+                    > usdc_amount: 1500000
+                "#},
+                inputs: [
+                    amount: {
+                        documentation: "The `integer`, `float`, `decimal` or `string` amount to scale.",
+                        typing: vec![Type::integer(), Type::float(), Type::decimal(), Type::string()],
+                        optional: false
+                    },
+                    decimals: {
+                        documentation: "The number of decimals of the unit, between 0 and 255.",
+                        typing: vec![Type::integer()],
+                        optional: false
+                    }
+                ],
+                output: {
+                    documentation: "The amount in base units, as an `integer`, or as a `decimal` if it exceeds the integer range.",
+                    typing: Type::integer()
+                },
+            }
+        },
+        define_function! {
+            FromBaseUnits => {
+                name: "from_base_units",
+                documentation: "`from_base_units` scales an amount of base units back by a number of decimals, e.g. to convert wei to ETH.",
+                example: indoc!{r#"
+                    output "eth_amount" {
+                        value = from_base_units(1500000000000000000, 18)
+                    }
+                    > eth_amount: 1.5
+                "#},
+                inputs: [
+                    amount: {
+                        documentation: "The `integer` or `decimal` amount of base units.",
+                        typing: vec![Type::integer(), Type::decimal()],
+                        optional: false
+                    },
+                    decimals: {
+                        documentation: "The number of decimals of the unit, between 0 and 255.",
+                        typing: vec![Type::integer()],
+                        optional: false
+                    }
+                ],
+                output: {
+                    documentation: "The scaled amount.",
+                    typing: Type::decimal()
+                },
+            }
+        }
+    ];
+}
+
+fn get_decimals(fn_spec: &FunctionSpecification, args: &Vec<Value>) -> Result<u8, Diagnostic> {
+    let decimals = args.get(1).unwrap().as_integer().unwrap();
+    u8::try_from(decimals).map_err(|_| {
+        to_diag(fn_spec, format!("decimals must be between 0 and 255, got {}", decimals))
+    })
+}
+
+pub struct ToDecimal;
+impl FunctionImplementation for ToDecimal {
+    fn check_instantiability(
+        _fn_spec: &FunctionSpecification,
+        _auth_ctx: &AuthorizationContext,
+        _args: &Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn run(
+        fn_spec: &FunctionSpecification,
+        _auth_ctx: &AuthorizationContext,
+        args: &Vec<Value>,
+    ) -> Result<Value, Diagnostic> {
+        arg_checker(fn_spec, args)?;
+        let decimal = args.get(0).unwrap().to_decimal().map_err(|e| to_diag(fn_spec, e))?;
+        Ok(Value::decimal(decimal))
+    }
+}
+
+pub struct DecimalToString;
+impl FunctionImplementation for DecimalToString {
+    fn check_instantiability(
+        _fn_spec: &FunctionSpecification,
+        _auth_ctx: &AuthorizationContext,
+        _args: &Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn run(
+        fn_spec: &FunctionSpecification,
+        _auth_ctx: &AuthorizationContext,
+        args: &Vec<Value>,
+    ) -> Result<Value, Diagnostic> {
+        arg_checker(fn_spec, args)?;
+        let decimal = args.get(0).unwrap().to_decimal().map_err(|e| to_diag(fn_spec, e))?;
+        let decimal = match args.get(1) {
+            None | Some(Value::Null) => decimal,
+            Some(Value::Integer(precision)) => {
+                let precision = u8::try_from(*precision).map_err(|_| {
+                    to_diag(
+                        fn_spec,
+                        format!("precision must be between 0 and 255, got {}", precision),
+                    )
+                })?;
+                decimal.with_scale_round(precision as i64, RoundingMode::HalfEven)
+            }
+            Some(other) => {
+                return Err(to_diag(
+                    fn_spec,
+                    format!("expected an integer precision, got {}", other.get_type().to_string()),
+                ))
+            }
+        };
+        Ok(Value::string(decimal.to_plain_string()))
+    }
+}
+
+pub struct ToBaseUnits;
+impl FunctionImplementation for ToBaseUnits {
+    fn check_instantiability(
+        _fn_spec: &FunctionSpecification,
+        _auth_ctx: &AuthorizationContext,
+        _args: &Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn run(
+        fn_spec: &FunctionSpecification,
+        _auth_ctx: &AuthorizationContext,
+        args: &Vec<Value>,
+    ) -> Result<Value, Diagnostic> {
+        arg_checker(fn_spec, args)?;
+        let amount = args.get(0).unwrap().to_decimal().map_err(|e| to_diag(fn_spec, e))?;
+        let decimals = get_decimals(fn_spec, args)?;
+        let base_units =
+            decimal_to_base_units(&amount, decimals).map_err(|e| to_diag(fn_spec, e))?;
+        match decimal_to_i128(&base_units) {
+            Ok(integer) => Ok(Value::integer(integer)),
+            Err(_) => Ok(Value::decimal(base_units)),
+        }
+    }
+}
+
+pub struct FromBaseUnits;
+impl FunctionImplementation for FromBaseUnits {
+    fn check_instantiability(
+        _fn_spec: &FunctionSpecification,
+        _auth_ctx: &AuthorizationContext,
+        _args: &Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn run(
+        fn_spec: &FunctionSpecification,
+        _auth_ctx: &AuthorizationContext,
+        args: &Vec<Value>,
+    ) -> Result<Value, Diagnostic> {
+        arg_checker(fn_spec, args)?;
+        let amount: BigDecimal =
+            args.get(0).unwrap().to_decimal().map_err(|e| to_diag(fn_spec, e))?;
+        let decimals = get_decimals(fn_spec, args)?;
+        Ok(Value::decimal(decimal_from_base_units(&amount, decimals)))
+    }
+}
@@ -1,3 +1,4 @@
+use super::arg_checker;
 use txtx_addon_kit::types::AuthorizationContext;
 use txtx_addon_kit::{
     define_function, indoc,
@@ -92,10 +93,11 @@ impl FunctionImplementation for EncodeHex {
     }
 
     fn run(
-        _fn_spec: &FunctionSpecification,
+        fn_spec: &FunctionSpecification,
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        arg_checker(fn_spec, args)?;
         let bytes = get_bytes_for_encoding(args.get(0).unwrap())?;
         let hex = txtx_addon_kit::hex::encode(bytes);
         Ok(Value::string(format!("0x{}", hex)))
@@ -113,10 +115,11 @@ impl FunctionImplementation for DecodeHex {
     }
 
     fn run(
-        _fn_spec: &FunctionSpecification,
+        fn_spec: &FunctionSpecification,
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        arg_checker(fn_spec, args)?;
         let hex_string = args.get(0).unwrap().expect_string();
         let hex_string = if hex_string.starts_with("0x") { &hex_string[2..] } else { hex_string };
 
@@ -143,7 +146,7 @@ mod tests {
     }
 
     fn dummy_auth_ctx() -> AuthorizationContext {
-        AuthorizationContext { workspace_location: FileLocation::working_dir() }
+        AuthorizationContext::new(FileLocation::working_dir())
     }
 
     #[test_case(
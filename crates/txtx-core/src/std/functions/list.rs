@@ -1,3 +1,4 @@
+use super::to_diag;
 use txtx_addon_kit::types::AuthorizationContext;
 use txtx_addon_kit::{
     define_function, indoc,
@@ -48,19 +49,21 @@ impl FunctionImplementation for Index {
     }
 
     fn run(
-        _fn_spec: &FunctionSpecification,
+        fn_spec: &FunctionSpecification,
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
-        let Value::Array(list) = args.get(0).unwrap() else {
-            panic!("index function requires list for first input")
+        let Some(Value::Array(list)) = args.get(0) else {
+            return Err(to_diag(fn_spec, "index function requires list for first input".into()));
         };
-        let Value::Integer(index) = args.get(1).unwrap() else {
-            panic!("index function requires uint for second input")
+        let Some(Value::Integer(index)) = args.get(1) else {
+            return Err(to_diag(fn_spec, "index function requires uint for second input".into()));
         };
-        match list.get(*index as usize) {
+        match usize::try_from(*index).ok().and_then(|index| list.get(index)) {
             Some(r) => Ok(r.clone()),
-            None => panic!("index {} exceeds list bounds: {:?}", index, list),
+            None => {
+                Err(to_diag(fn_spec, format!("index {} exceeds list bounds: {:?}", index, list)))
+            }
         }
     }
 }
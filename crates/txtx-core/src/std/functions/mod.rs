@@ -3,6 +3,7 @@ pub mod base58;
 pub mod base64;
 pub mod big_endian;
 pub mod crypto;
+pub mod decimal;
 pub mod hash;
 pub mod hex;
 pub mod json;
@@ -39,6 +40,7 @@ lazy_static! {
         functions.extend(base58::FUNCTIONS.clone());
         functions.extend(assertions::FUNCTIONS.clone());
         functions.extend(big_endian::FUNCTIONS.clone());
+        functions.extend(decimal::FUNCTIONS.clone());
         functions
     };
 }
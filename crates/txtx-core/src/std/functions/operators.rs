@@ -1,3 +1,5 @@
+use txtx_addon_kit::bigdecimal::{BigDecimal, Signed, Zero};
+use txtx_addon_kit::chrono::{DateTime, Utc};
 use txtx_addon_kit::types::AuthorizationContext;
 use txtx_addon_kit::{
     define_function, indoc,
@@ -77,11 +79,11 @@ lazy_static! {
                 inputs: [
                     lhs: {
                         documentation: "The `int` dividend.",
-                        typing: vec![Type::integer()]
+                        typing: vec![Type::integer(), Type::decimal()]
                     },
                     rhs: {
                         documentation: "The `int` divisor.",
-                        typing: vec![Type::integer()]
+                        typing: vec![Type::integer(), Type::decimal()]
                     }
                 ],
                 output: {
@@ -103,11 +105,11 @@ lazy_static! {
                 inputs: [
                     lhs: {
                         documentation: "Any value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool(), Type::addon(""), Type::array(Type::null()), Type::arbitrary_object()]
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool(), Type::addon(""), Type::array(Type::null()), Type::arbitrary_object()]
                     },
                     rhs: {
                         documentation: "Any value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool(), Type::addon(""), Type::array(Type::null()), Type::arbitrary_object()]
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool(), Type::addon(""), Type::array(Type::null()), Type::arbitrary_object()]
                     }
                 ],
                 output: {
@@ -128,12 +130,12 @@ lazy_static! {
                 "#},
                 inputs: [
                     lhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     },
                     rhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     }
                 ],
                 output: {
@@ -154,12 +156,12 @@ lazy_static! {
                 "#},
                 inputs: [
                     lhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     },
                     rhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     }
                 ],
                 output: {
@@ -180,12 +182,12 @@ lazy_static! {
                 "#},
                 inputs: [
                     lhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     },
                     rhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     }
                 ],
                 output: {
@@ -206,12 +208,12 @@ lazy_static! {
                 "#},
                 inputs: [
                     lhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     },
                     rhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     }
                 ],
                 output: {
@@ -232,12 +234,12 @@ lazy_static! {
                 "#},
                 inputs: [
                     lhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     },
                     rhs: {
-                        documentation: "An `integer`, `float`, `string`, `boolean` or `null` value.",
-                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::string(), Type::bool()]
+                        documentation: "An `integer`, `float`, `decimal`, `datetime`, `string`, `boolean` or `null` value.",
+                        typing: vec![Type::null(), Type::integer(), Type::float(), Type::integer(), Type::decimal(), Type::datetime(), Type::string(), Type::bool()]
                     }
                 ],
                 output: {
@@ -259,11 +261,11 @@ lazy_static! {
                 inputs: [
                     lhs: {
                       documentation: "The `integer` minuend.",
-                      typing: vec![Type::integer()]
+                      typing: vec![Type::integer(), Type::decimal()]
                     },
                     rhs: {
                         documentation: "The `integer` subtrahend.",
-                        typing: vec![Type::integer()]
+                        typing: vec![Type::integer(), Type::decimal()]
                     }
                 ],
                 output: {
@@ -285,11 +287,11 @@ lazy_static! {
                 inputs: [
                     lhs: {
                         documentation: "The `integer` dividend.",
-                        typing: vec![Type::integer()]
+                        typing: vec![Type::integer(), Type::decimal()]
                     },
                     rhs: {
                         documentation: "The `integer` divisor.",
-                        typing: vec![Type::integer()]
+                        typing: vec![Type::integer(), Type::decimal()]
                     }
                 ],
                 output: {
@@ -311,12 +313,12 @@ lazy_static! {
                 inputs: [
                     lhs: {
                         documentation: "The first `integer` operand.",
-                        typing: vec![Type::integer()],
+                        typing: vec![Type::integer(), Type::decimal()],
                         optional: false
                     },
                     rhs: {
                         documentation: "The second `integer` operand.",
-                        typing: vec![Type::integer()],
+                        typing: vec![Type::integer(), Type::decimal()],
                         optional: false
                     }
                 ],
@@ -339,11 +341,11 @@ lazy_static! {
                 inputs: [
                     lhs: {
                         documentation: "The first `integer` operand.",
-                        typing: vec![Type::integer()]
+                        typing: vec![Type::integer(), Type::decimal()]
                     },
                     rhs: {
                         documentation: "The second `integer` operand.",
-                        typing: vec![Type::integer()]
+                        typing: vec![Type::integer(), Type::decimal()]
                     }
                 ],
                 output: {
@@ -389,6 +391,39 @@ lazy_static! {
     ];
 }
 
+/// Returns both operands as decimals when at least one of them is a decimal, promoting
+/// integers so that `1e27 + 1` keeps its precision.
+fn decimal_operands(args: &Vec<Value>) -> Result<Option<(BigDecimal, BigDecimal)>, Diagnostic> {
+    let (Some(lhs), Some(rhs)) = (args.get(0), args.get(1)) else { return Ok(None) };
+    if !matches!(lhs, Value::Decimal(_)) && !matches!(rhs, Value::Decimal(_)) {
+        return Ok(None);
+    }
+    let to_decimal = |value: &Value| match value {
+        Value::Integer(_) | Value::Decimal(_) => {
+            value.to_decimal().map_err(Diagnostic::error_from_string)
+        }
+        other => Err(Diagnostic::error_from_string(format!(
+            "cannot combine a decimal with a value of type {}",
+            other.get_type().to_string()
+        ))),
+    };
+    Ok(Some((to_decimal(lhs)?, to_decimal(rhs)?)))
+}
+
+/// Returns both operands as datetimes when at least one of them is a datetime, parsing the other
+/// one from an ISO-8601 string or epoch seconds, e.g. `action.auction.end_time > "2025-01-01T00:00:00Z"`.
+fn datetime_operands(
+    args: &Vec<Value>,
+) -> Result<Option<(DateTime<Utc>, DateTime<Utc>)>, Diagnostic> {
+    let (Some(lhs), Some(rhs)) = (args.get(0), args.get(1)) else { return Ok(None) };
+    if !matches!(lhs, Value::DateTime(_)) && !matches!(rhs, Value::DateTime(_)) {
+        return Ok(None);
+    }
+    let lhs = lhs.to_datetime().map_err(Diagnostic::error_from_string)?;
+    let rhs = rhs.to_datetime().map_err(Diagnostic::error_from_string)?;
+    Ok(Some((lhs, rhs)))
+}
+
 pub struct UnaryNegInteger;
 impl FunctionImplementation for UnaryNegInteger {
     fn check_instantiability(
@@ -484,6 +519,12 @@ impl FunctionImplementation for BinaryDivSignedInteger {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            if rhs.is_zero() {
+                return Err(Diagnostic::error_from_string("cannot divide by zero".to_string()));
+            }
+            return Ok(Value::decimal(lhs / rhs));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
         if rhs.eq(&0) {
@@ -510,6 +551,12 @@ impl FunctionImplementation for BinaryEq {
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
         arg_checker(fn_spec, args)?;
+        if let Ok(Some((lhs, rhs))) = decimal_operands(args) {
+            return Ok(Value::bool(lhs.eq(&rhs)));
+        }
+        if let Ok(Some((lhs, rhs))) = datetime_operands(args) {
+            return Ok(Value::bool(lhs.eq(&rhs)));
+        }
         let lhs = args.get(0).unwrap();
         let rhs = args.get(1).unwrap();
         Ok(Value::bool(lhs.eq(rhs)))
@@ -531,6 +578,12 @@ impl FunctionImplementation for BinaryGreater {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            return Ok(Value::bool(lhs.gt(&rhs)));
+        }
+        if let Some((lhs, rhs)) = datetime_operands(args)? {
+            return Ok(Value::bool(lhs.gt(&rhs)));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
         Ok(Value::bool(lhs.gt(&rhs)))
@@ -552,6 +605,12 @@ impl FunctionImplementation for BinaryGreaterEq {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            return Ok(Value::bool(lhs.ge(&rhs)));
+        }
+        if let Some((lhs, rhs)) = datetime_operands(args)? {
+            return Ok(Value::bool(lhs.ge(&rhs)));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
         Ok(Value::bool(lhs.ge(&rhs)))
@@ -573,6 +632,12 @@ impl FunctionImplementation for BinaryLess {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            return Ok(Value::bool(lhs.lt(&rhs)));
+        }
+        if let Some((lhs, rhs)) = datetime_operands(args)? {
+            return Ok(Value::bool(lhs.lt(&rhs)));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
         Ok(Value::bool(lhs.lt(&rhs)))
@@ -594,6 +659,12 @@ impl FunctionImplementation for BinaryLessEq {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            return Ok(Value::bool(lhs.le(&rhs)));
+        }
+        if let Some((lhs, rhs)) = datetime_operands(args)? {
+            return Ok(Value::bool(lhs.le(&rhs)));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
         Ok(Value::bool(lhs.le(&rhs)))
@@ -615,6 +686,12 @@ impl FunctionImplementation for BinaryNotEq {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            return Ok(Value::bool(!lhs.eq(&rhs)));
+        }
+        if let Some((lhs, rhs)) = datetime_operands(args)? {
+            return Ok(Value::bool(!lhs.eq(&rhs)));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
         Ok(Value::bool(!lhs.eq(&rhs)))
@@ -636,6 +713,9 @@ impl FunctionImplementation for BinaryMinus {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            return Ok(Value::decimal(lhs - rhs));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
         Ok(Value::integer(lhs - rhs))
@@ -657,9 +737,21 @@ impl FunctionImplementation for BinaryModulo {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            if rhs.is_zero() {
+                return Err(Diagnostic::error_from_string("cannot divide by zero".to_string()));
+            }
+            let remainder = lhs % rhs.clone();
+            let remainder = if remainder.is_negative() { remainder + rhs.abs() } else { remainder };
+            return Ok(Value::decimal(remainder));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
-        Ok(Value::integer(lhs.rem_euclid(*rhs)))
+        if rhs.eq(&0) {
+            Err(Diagnostic::error_from_string("cannot divide by zero".to_string()))
+        } else {
+            Ok(Value::integer(lhs.rem_euclid(*rhs)))
+        }
     }
 }
 
@@ -679,6 +771,9 @@ impl FunctionImplementation for BinaryMul {
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
         arg_checker(fn_spec, args)?;
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            return Ok(Value::decimal(lhs * rhs));
+        }
         let lhs = args.get(0).unwrap().as_integer().unwrap();
         let rhs = args.get(1).unwrap().as_integer().unwrap();
         Ok(Value::integer(lhs.saturating_mul(rhs)))
@@ -700,6 +795,9 @@ impl FunctionImplementation for BinaryPlus {
         _auth_ctx: &AuthorizationContext,
         args: &Vec<Value>,
     ) -> Result<Value, Diagnostic> {
+        if let Some((lhs, rhs)) = decimal_operands(args)? {
+            return Ok(Value::decimal(lhs + rhs));
+        }
         let Some(Value::Integer(lhs)) = args.get(0) else { unreachable!() };
         let Some(Value::Integer(rhs)) = args.get(1) else { unreachable!() };
         Ok(Value::integer(lhs + rhs))
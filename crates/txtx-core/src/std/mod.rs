@@ -1,14 +1,14 @@
 use txtx_addon_kit::{
     types::{
         commands::PreCommandSpecification, functions::FunctionSpecification,
-        signers::SignerSpecification,
+        signers::SignerSpecification, types::Value,
     },
     Addon,
 };
 
 use crate::constants::NAMESPACE;
 
-use self::{commands::actions::ACTIONS, functions::FUNCTIONS};
+use self::{commands::actions::ACTIONS, functions::FUNCTIONS, signers::SIGNERS, typing::StdValue};
 
 pub mod commands;
 pub mod functions;
@@ -48,6 +48,10 @@ impl Addon for StdAddon {
     }
 
     fn get_signers(&self) -> Vec<SignerSpecification> {
-        vec![]
+        SIGNERS.clone()
+    }
+
+    fn format_display(&self, value: &Value) -> Option<String> {
+        StdValue::format_display(value)
     }
 }
@@ -1 +1,10 @@
 // mod bip39;
+mod threshold;
+
+use txtx_addon_kit::types::signers::SignerSpecification;
+
+use threshold::STD_THRESHOLD_SIGNER;
+
+lazy_static! {
+    pub static ref SIGNERS: Vec<SignerSpecification> = vec![STD_THRESHOLD_SIGNER.clone()];
+}
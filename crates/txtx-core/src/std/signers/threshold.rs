@@ -0,0 +1,701 @@
+use std::collections::HashMap;
+
+use txtx_addon_kit::channel;
+use txtx_addon_kit::constants::{
+    PARTIAL_SIGNATURE, PARTIAL_SIGNATURES, PARTIAL_SIGNATURE_REQUESTED, SIGNATURE_APPROVED,
+    THIRD_PARTY_SIGNATURE_STATUS,
+};
+use txtx_addon_kit::futures::future;
+use txtx_addon_kit::types::commands::{CommandExecutionResult, CommandSpecification};
+use txtx_addon_kit::types::diagnostics::Diagnostic;
+use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, return_synchronous_result, CheckSignabilityOk, PartialSignature,
+    SignerActionErr, SignerActionsFutureResult, SignerActivateFutureResult, SignerImplementation,
+    SignerInstance, SignerSignFutureResult, SignerSpecification, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type, Value};
+use txtx_addon_kit::types::{AuthorizationContext, ConstructDid, Did};
+use txtx_addon_kit::{define_signer, hex, indoc};
+
+const MEMBERS: &str = "members";
+const THRESHOLD: &str = "threshold";
+
+lazy_static! {
+    pub static ref STD_THRESHOLD_SIGNER: SignerSpecification = define_signer! {
+        ThresholdSigner => {
+            name: "Threshold Signer",
+            matcher: "threshold",
+            documentation: indoc! {r#"
+            The `std::threshold` signer collects signatures from `threshold` of its `members` before handing them to the members' addon, which assembles the signed payload.
+            Signing requests are fanned out to every member: in supervised mode, each member gets its own action item, and the signer is ready as soon as enough members have approved.
+            Members must all come from the same addon, and that addon must support partial signatures."#},
+            inputs: [
+                members: {
+                    documentation: "The signers taking part in the threshold signer.",
+                    typing: Type::array(Type::string()),
+                    optional: false,
+                    tainting: true,
+                    sensitive: false
+                },
+                threshold: {
+                    documentation: "The number of member signatures required.",
+                    typing: Type::integer(),
+                    optional: false,
+                    tainting: true,
+                    sensitive: false
+                }
+            ],
+            outputs: [
+                members: {
+                    documentation: "The signers taking part in the threshold signer.",
+                    typing: Type::array(Type::string())
+                },
+                threshold: {
+                    documentation: "The number of member signatures required.",
+                    typing: Type::integer()
+                }
+            ],
+            example: indoc! {r#"
+            signer "council" "std::threshold" {
+                members = [signer.alice, signer.bob, signer.carol]
+                threshold = 2
+            }
+            "#}
+        }
+    };
+}
+
+pub struct ThresholdSigner;
+impl SignerImplementation for ThresholdSigner {
+    fn check_instantiability(
+        _ctx: &SignerSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn check_activability(
+        _construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _supervision_context: &RunbookSupervisionContext,
+        _auth_ctx: &AuthorizationContext,
+        _is_balance_check_required: bool,
+        _is_public_key_required: bool,
+    ) -> SignerActionsFutureResult {
+        let (members, threshold) = parse_threshold_config(instance_name, values, signers_instances)
+            .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+
+        signer_state.insert(
+            MEMBERS,
+            Value::array(members.iter().map(|(did, _)| Value::string(did.to_string())).collect()),
+        );
+        signer_state.insert(THRESHOLD, Value::integer(threshold as i128));
+        return_synchronous_actions(Ok((signers, signer_state, Actions::none())))
+    }
+
+    fn activate(
+        _construct_did: &ConstructDid,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _progress_tx: &channel::Sender<BlockEvent>,
+    ) -> SignerActivateFutureResult {
+        let mut result = CommandExecutionResult::new();
+        for key in [MEMBERS, THRESHOLD] {
+            let value = signer_state
+                .get_expected_value(key)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+            result.outputs.insert(key.into(), value.clone());
+        }
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+
+    fn check_signability(
+        caller_uuid: &ConstructDid,
+        title: &str,
+        description: &Option<String>,
+        meta_description: &Option<String>,
+        markdown: &Option<String>,
+        payload: &Value,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        signer_state: ValueStore,
+        mut signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        supervision_context: &RunbookSupervisionContext,
+        auth_ctx: &AuthorizationContext,
+    ) -> Result<CheckSignabilityOk, SignerActionErr> {
+        let (members, threshold) = get_members(&signer_state, signers_instances)
+            .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+        let collected = get_partial_signatures(caller_uuid, &signer_state)
+            .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+
+        let approved_count = members
+            .iter()
+            .filter(|(did, _)| {
+                collected.iter().any(|p| p.signer_did.eq(did))
+                    || has_approved(caller_uuid, did, &signers)
+            })
+            .count();
+        if approved_count >= threshold {
+            return Ok((signers, signer_state, Actions::none()));
+        }
+
+        // Fan the request out to every member that hasn't signed yet: the first `threshold`
+        // approvals are enough for the signing to proceed.
+        let mut actions = Actions::none();
+        for (member_did, member_instance) in members.iter() {
+            if collected.iter().any(|p| p.signer_did.eq(member_did)) {
+                continue;
+            }
+            let Some(member_state) = signers.pop_signer_state(member_did) else {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!("threshold signer member state not found"),
+                ));
+            };
+            let (new_signers, new_member_state, mut member_actions) =
+                (member_instance.specification.check_signability)(
+                    caller_uuid,
+                    title,
+                    description,
+                    meta_description,
+                    markdown,
+                    payload,
+                    &member_instance.specification,
+                    values,
+                    member_state,
+                    signers,
+                    signers_instances,
+                    supervision_context,
+                    auth_ctx,
+                )
+                .map_err(|(mut signers, member_state, diag)| {
+                    signers.push_signer_state(member_state);
+                    (signers, signer_state.clone(), diag)
+                })?;
+            signers = new_signers;
+            signers.push_signer_state(new_member_state);
+            actions.append(&mut member_actions);
+        }
+        Ok((signers, signer_state, actions))
+    }
+
+    fn sign(
+        caller_uuid: &ConstructDid,
+        title: &str,
+        payload: &Value,
+        _spec: &SignerSpecification,
+        values: &ValueStore,
+        mut signer_state: ValueStore,
+        mut signers: SignersState,
+        signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    ) -> SignerSignFutureResult {
+        let caller_uuid = caller_uuid.clone();
+        let title = title.to_string();
+        let payload = payload.clone();
+        let values = values.clone();
+        let signers_instances = signers_instances.clone();
+
+        let future = async move {
+            let (mut members, threshold) = get_members(&signer_state, &signers_instances)
+                .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+            let mut partial_signatures = get_partial_signatures(&caller_uuid, &signer_state)
+                .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+            let mut failures = vec![];
+
+            // members that approved the request are asked first
+            members.sort_by_key(|(did, _)| !has_approved(&caller_uuid, did, &signers));
+            let mut candidates = members
+                .iter()
+                .filter(|(did, _)| !partial_signatures.iter().any(|p| p.signer_did.eq(did)))
+                .collect::<Vec<_>>()
+                .into_iter();
+
+            // Members are asked concurrently, as many at a time as signatures are missing: when
+            // some of them fail, the next members are asked in their place.
+            while partial_signatures.len() < threshold {
+                let batch = candidates
+                    .by_ref()
+                    .take(threshold - partial_signatures.len())
+                    .collect::<Vec<_>>();
+                if batch.is_empty() {
+                    break;
+                }
+
+                let mut requests = vec![];
+                for (member_did, member_instance) in batch.iter() {
+                    let Some(mut member_state) = signers.pop_signer_state(member_did) else {
+                        return Err((
+                            signers,
+                            signer_state,
+                            diagnosed_error!("threshold signer member state not found"),
+                        ));
+                    };
+                    member_state.insert_scoped_value(
+                        &caller_uuid.to_string(),
+                        PARTIAL_SIGNATURE_REQUESTED,
+                        Value::bool(true),
+                    );
+                    let request = (member_instance.specification.sign)(
+                        &caller_uuid,
+                        &title,
+                        &payload,
+                        &member_instance.specification,
+                        &values,
+                        member_state,
+                        signers.clone(),
+                        &signers_instances,
+                    );
+                    requests.push(async move {
+                        match request {
+                            Ok(future) => future.await,
+                            Err(e) => Err(e),
+                        }
+                    });
+                }
+
+                let mut pending_result = None;
+                for ((member_did, member_instance), res) in
+                    batch.iter().zip(future::join_all(requests).await)
+                {
+                    let (member_state, result) = match res {
+                        Ok((_, member_state, result)) => (member_state, Ok(result)),
+                        Err((_, member_state, diag)) => (member_state, Err(diag)),
+                    };
+                    signers.push_signer_state(member_state);
+                    let result = match result {
+                        Ok(result) => result,
+                        Err(diag) => {
+                            failures.push(format!("{}: {}", member_instance.name, diag.message));
+                            continue;
+                        }
+                    };
+
+                    if let Some(partial_signature) = result.outputs.get(PARTIAL_SIGNATURE) {
+                        let mut partial_signature = PartialSignature::from_value(partial_signature)
+                            .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+                        partial_signature.signer_did = member_did.clone();
+                        partial_signatures.push(partial_signature);
+                    } else if result.outputs.get(THIRD_PARTY_SIGNATURE_STATUS).is_some() {
+                        // the member is waiting on a third party, signing will be resumed
+                        pending_result = Some(result);
+                    }
+                }
+                store_partial_signatures(&caller_uuid, &mut signer_state, &partial_signatures);
+
+                if let Some(result) = pending_result {
+                    return Ok((signers, signer_state, result));
+                }
+            }
+
+            if partial_signatures.len() < threshold {
+                return Err((
+                    signers,
+                    signer_state.clone(),
+                    diagnosed_error!(
+                        "threshold signer '{}' collected {} of the {} required signatures{}",
+                        signer_state.name,
+                        partial_signatures.len(),
+                        threshold,
+                        if failures.is_empty() {
+                            "".to_string()
+                        } else {
+                            format!(" ({})", failures.join("; "))
+                        }
+                    ),
+                ));
+            }
+
+            let Some(finalize) = members
+                .first()
+                .and_then(|(_, instance)| instance.specification.finalize_partial_signatures)
+            else {
+                return Err((
+                    signers,
+                    signer_state,
+                    diagnosed_error!("threshold signer members don't support partial signatures"),
+                ));
+            };
+            let result = finalize(&caller_uuid, &payload, &partial_signatures, &values)
+                .map_err(|diag| (signers.clone(), signer_state.clone(), diag))?;
+            Ok((signers, signer_state, result))
+        };
+        Ok(Box::pin(future))
+    }
+}
+
+/// Validates the `members` and `threshold` inputs of a threshold signer.
+fn parse_threshold_config(
+    instance_name: &str,
+    values: &ValueStore,
+    signers_instances: &HashMap<ConstructDid, SignerInstance>,
+) -> Result<(Vec<(ConstructDid, SignerInstance)>, usize), Diagnostic> {
+    let mut members: Vec<(ConstructDid, SignerInstance)> = vec![];
+    for member in values.get_expected_array(MEMBERS)?.iter() {
+        let Some(member_did) = member
+            .as_string()
+            .and_then(|member| hex::decode(member).ok())
+            .filter(|bytes| bytes.len() == 32)
+            .map(|bytes| ConstructDid(Did::from_bytes(&bytes)))
+        else {
+            return Err(diagnosed_error!(
+                "threshold signer '{}': members must be signer references",
+                instance_name
+            ));
+        };
+        let Some(member_instance) = signers_instances.get(&member_did) else {
+            return Err(diagnosed_error!(
+                "threshold signer '{}': members must be signer references",
+                instance_name
+            ));
+        };
+        if members.iter().any(|(did, _)| did.eq(&member_did)) {
+            return Err(diagnosed_error!(
+                "threshold signer '{}': signer '{}' is listed more than once",
+                instance_name,
+                member_instance.name
+            ));
+        }
+        if !member_instance.specification.supports_partial_signatures() {
+            return Err(diagnosed_error!(
+                "threshold signer '{}': signer '{}' ({}::{}) does not support partial signatures",
+                instance_name,
+                member_instance.name,
+                member_instance.namespace,
+                member_instance.specification.matcher
+            ));
+        }
+        if let Some((_, first_member)) = members.first() {
+            if first_member.namespace != member_instance.namespace {
+                return Err(diagnosed_error!(
+                    "threshold signer '{}': members must all be {} signers",
+                    instance_name,
+                    first_member.namespace
+                ));
+            }
+        }
+        members.push((member_did, member_instance.clone()));
+    }
+
+    let threshold = values.get_expected_uint(THRESHOLD)? as usize;
+    if members.is_empty() || threshold == 0 || threshold > members.len() {
+        return Err(diagnosed_error!(
+            "threshold signer '{}': threshold must be between 1 and the number of members ({}), got {}",
+            instance_name,
+            members.len(),
+            threshold
+        ));
+    }
+    Ok((members, threshold))
+}
+
+fn get_members(
+    signer_state: &ValueStore,
+    signers_instances: &HashMap<ConstructDid, SignerInstance>,
+) -> Result<(Vec<(ConstructDid, SignerInstance)>, usize), Diagnostic> {
+    let mut members = vec![];
+    for member in signer_state.get_expected_array(MEMBERS)?.iter() {
+        let member_did = ConstructDid::from_hex_string(member.expect_string());
+        let member_instance = signers_instances
+            .get(&member_did)
+            .ok_or(diagnosed_error!("threshold signer member instance not found"))?;
+        members.push((member_did, member_instance.clone()));
+    }
+    let threshold = signer_state.get_expected_uint(THRESHOLD)? as usize;
+    Ok((members, threshold))
+}
+
+fn has_approved(
+    caller_uuid: &ConstructDid,
+    member_did: &ConstructDid,
+    signers: &SignersState,
+) -> bool {
+    signers
+        .get_signer_state(member_did)
+        .and_then(|state| state.get_scoped_value(&caller_uuid.to_string(), SIGNATURE_APPROVED))
+        .is_some()
+}
+
+fn get_partial_signatures(
+    caller_uuid: &ConstructDid,
+    signer_state: &ValueStore,
+) -> Result<Vec<PartialSignature>, Diagnostic> {
+    let Some(value) = signer_state.get_scoped_value(&caller_uuid.to_string(), PARTIAL_SIGNATURES)
+    else {
+        return Ok(vec![]);
+    };
+    value.expect_array().iter().map(PartialSignature::from_value).collect()
+}
+
+fn store_partial_signatures(
+    caller_uuid: &ConstructDid,
+    signer_state: &mut ValueStore,
+    partial_signatures: &Vec<PartialSignature>,
+) {
+    signer_state.insert_scoped_value(
+        &caller_uuid.to_string(),
+        PARTIAL_SIGNATURES,
+        Value::array(partial_signatures.iter().map(|p| p.to_value()).collect()),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::hcl::expr::Ident;
+    use txtx_addon_kit::hcl::structure::Block;
+    use txtx_addon_kit::helpers::fs::FileLocation;
+    use txtx_addon_kit::types::signers::return_synchronous_actions;
+    use txtx_addon_kit::types::{PackageId, RunbookId};
+
+    use super::*;
+
+    const FAILS: &str = "fails";
+    const SIGNATURES: &str = "signatures";
+
+    lazy_static! {
+        static ref MOCK_MEMBER: SignerSpecification = define_signer! {
+            MockMember => {
+                name: "Mock Member",
+                matcher: "member",
+                documentation: "A member signer producing partial signatures.",
+                inputs: [],
+                outputs: [],
+                example: "",
+                finalize_partial_signatures: finalize_mock_signatures
+            }
+        };
+    }
+
+    struct MockMember;
+    impl SignerImplementation for MockMember {
+        fn check_instantiability(
+            _ctx: &SignerSpecification,
+            _args: Vec<Type>,
+        ) -> Result<Type, Diagnostic> {
+            unimplemented!()
+        }
+
+        fn check_activability(
+            _construct_did: &ConstructDid,
+            _instance_name: &str,
+            _spec: &SignerSpecification,
+            _values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+            _supervision_context: &RunbookSupervisionContext,
+            _auth_ctx: &AuthorizationContext,
+            _is_balance_check_required: bool,
+            _is_public_key_required: bool,
+        ) -> SignerActionsFutureResult {
+            return_synchronous_actions(Ok((signers, signer_state, Actions::none())))
+        }
+
+        fn activate(
+            _construct_did: &ConstructDid,
+            _spec: &SignerSpecification,
+            _values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+            _progress_tx: &channel::Sender<BlockEvent>,
+        ) -> SignerActivateFutureResult {
+            return_synchronous_result(Ok((signers, signer_state, CommandExecutionResult::new())))
+        }
+
+        fn check_signability(
+            _caller_uuid: &ConstructDid,
+            _title: &str,
+            _description: &Option<String>,
+            _meta_description: &Option<String>,
+            _markdown: &Option<String>,
+            _payload: &Value,
+            _spec: &SignerSpecification,
+            _values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+            _supervision_context: &RunbookSupervisionContext,
+            _auth_ctx: &AuthorizationContext,
+        ) -> Result<CheckSignabilityOk, SignerActionErr> {
+            Ok((signers, signer_state, Actions::none()))
+        }
+
+        fn sign(
+            caller_uuid: &ConstructDid,
+            _title: &str,
+            payload: &Value,
+            _spec: &SignerSpecification,
+            _values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        ) -> SignerSignFutureResult {
+            assert!(signer_state
+                .get_scoped_bool(&caller_uuid.to_string(), PARTIAL_SIGNATURE_REQUESTED)
+                .unwrap());
+            if signer_state.get_bool(FAILS).unwrap_or(false) {
+                let diag = diagnosed_error!("device disconnected");
+                return Err((signers, signer_state, diag));
+            }
+            let signature = format!("{}({})", signer_state.name, payload.expect_string());
+            let partial_signature = PartialSignature::new(
+                &ConstructDid(signer_state.uuid.clone()),
+                Value::string(signer_state.name.clone()),
+                Value::string(signature),
+            );
+            let mut result = CommandExecutionResult::new();
+            result.outputs.insert(PARTIAL_SIGNATURE.into(), partial_signature.to_value());
+            return_synchronous_result(Ok((signers, signer_state, result)))
+        }
+    }
+
+    fn finalize_mock_signatures(
+        _caller_uuid: &ConstructDid,
+        _payload: &Value,
+        partial_signatures: &Vec<PartialSignature>,
+        _values: &ValueStore,
+    ) -> Result<CommandExecutionResult, Diagnostic> {
+        let mut result = CommandExecutionResult::new();
+        result.outputs.insert(
+            SIGNATURES.into(),
+            Value::array(partial_signatures.iter().map(|p| p.signature.clone()).collect()),
+        );
+        Ok(result)
+    }
+
+    /// Sets up a threshold signer over `members`, the ones flagged `true` failing to sign.
+    fn setup(
+        members: &[(&str, bool)],
+        threshold: usize,
+    ) -> (ValueStore, SignersState, HashMap<ConstructDid, SignerInstance>) {
+        let package_id = PackageId {
+            runbook_id: RunbookId::new(None, None, "test"),
+            package_location: FileLocation::from_path_string(".").unwrap(),
+            package_name: "test".into(),
+        };
+        let mut signers = SignersState::new();
+        let mut signers_instances = HashMap::new();
+        let mut member_dids = vec![];
+        for (name, fails) in members {
+            let did = ConstructDid(Did::from_components(vec![name.as_bytes()]));
+            let mut member_state = ValueStore::new(name, &did.value());
+            member_state.insert(FAILS, Value::bool(*fails));
+            signers.push_signer_state(member_state);
+            signers_instances.insert(
+                did.clone(),
+                SignerInstance {
+                    specification: MOCK_MEMBER.clone(),
+                    name: name.to_string(),
+                    block: Block::new(Ident::new("signer")),
+                    package_id: package_id.clone(),
+                    namespace: "mock".into(),
+                },
+            );
+            member_dids.push(Value::string(did.to_string()));
+        }
+
+        let mut values = ValueStore::tmp();
+        values.insert(MEMBERS, Value::array(member_dids));
+        values.insert(THRESHOLD, Value::integer(threshold as i128));
+        let (members, threshold) =
+            parse_threshold_config("council", &values, &signers_instances).unwrap();
+
+        let council_did = Did::from_components(vec!["council".as_bytes()]);
+        let mut signer_state = ValueStore::new("council", &council_did);
+        signer_state.insert(
+            MEMBERS,
+            Value::array(members.iter().map(|(did, _)| Value::string(did.to_string())).collect()),
+        );
+        signer_state.insert(THRESHOLD, Value::integer(threshold as i128));
+        (signer_state, signers, signers_instances)
+    }
+
+    fn sign(
+        members: &[(&str, bool)],
+        threshold: usize,
+    ) -> Result<CommandExecutionResult, Diagnostic> {
+        let caller_uuid = ConstructDid(Did::from_components(vec!["sign_transaction".as_bytes()]));
+        let (signer_state, signers, signers_instances) = setup(members, threshold);
+        let future = ThresholdSigner::sign(
+            &caller_uuid,
+            "Sign",
+            &Value::string("payload".into()),
+            &STD_THRESHOLD_SIGNER,
+            &ValueStore::tmp(),
+            signer_state,
+            signers,
+            &signers_instances,
+        )
+        .map_err(|(_, _, diag)| diag)?;
+        let (signers, _, result) = block_on(future).map_err(|(_, _, diag)| diag)?;
+        // every member state is handed back, whether the member signed or not
+        assert_eq!(signers.store.len(), members.len());
+        Ok(result)
+    }
+
+    fn signatures(result: &CommandExecutionResult) -> Vec<String> {
+        result
+            .outputs
+            .get(SIGNATURES)
+            .unwrap()
+            .expect_array()
+            .iter()
+            .map(|v| v.expect_string().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_threshold_signer_collects_k_of_n_signatures() {
+        let result = sign(&[("alice", false), ("bob", false), ("carol", false)], 2).unwrap();
+        assert_eq!(signatures(&result), vec!["alice(payload)", "bob(payload)"]);
+    }
+
+    #[test]
+    fn test_threshold_signer_replaces_failing_members() {
+        let result = sign(&[("alice", true), ("bob", false), ("carol", false)], 2).unwrap();
+        assert_eq!(signatures(&result), vec!["bob(payload)", "carol(payload)"]);
+    }
+
+    #[test]
+    fn test_threshold_signer_fails_below_threshold() {
+        let diag = sign(&[("alice", false), ("bob", true), ("carol", true)], 2).unwrap_err();
+        assert!(diag.message.contains("collected 1 of the 2 required signatures"));
+        assert!(diag.message.contains("bob: device disconnected"));
+        assert!(diag.message.contains("carol: device disconnected"));
+    }
+
+    #[test]
+    fn test_threshold_config_requires_partial_signature_support() {
+        let (_, _, mut signers_instances) = setup(&[("alice", false), ("bob", false)], 1);
+        let mut values = ValueStore::tmp();
+        values.insert(
+            MEMBERS,
+            Value::array(
+                signers_instances.keys().map(|did| Value::string(did.to_string())).collect(),
+            ),
+        );
+        values.insert(THRESHOLD, Value::integer(3));
+        let diag = parse_threshold_config("council", &values, &signers_instances).unwrap_err();
+        assert!(diag.message.contains("threshold must be between 1 and the number of members (2)"));
+
+        values.insert(THRESHOLD, Value::integer(1));
+        for instance in signers_instances.values_mut() {
+            instance.specification.finalize_partial_signatures = None;
+        }
+        let diag = parse_threshold_config("council", &values, &signers_instances).unwrap_err();
+        assert!(diag.message.contains("does not support partial signatures"));
+    }
+}
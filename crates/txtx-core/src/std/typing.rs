@@ -1,4 +1,5 @@
-use txtx_addon_kit::types::types::Value;
+use txtx_addon_kit::types::commands::{AssertionResult, ASSERTION_TYPE_ID};
+use txtx_addon_kit::types::types::{Value, THIRD_PARTY_SIGNATURE};
 
 pub const STD_HASH: &str = "std::hash";
 
@@ -8,4 +9,46 @@ impl StdValue {
     pub fn hash(bytes: Vec<u8>) -> Value {
         Value::addon(bytes, STD_HASH)
     }
+
+    /// Formats std values for display: assertions by their outcome, and third party signatures
+    /// by their status.
+    pub fn format_display(value: &Value) -> Option<String> {
+        let addon_data = value.as_addon_data()?;
+        match addon_data.id.as_str() {
+            ASSERTION_TYPE_ID => match AssertionResult::from_value(value).ok()? {
+                AssertionResult::Success => Some("assertion succeeded".into()),
+                AssertionResult::Failure(message) => Some(message),
+            },
+            THIRD_PARTY_SIGNATURE if addon_data.bytes.len() == 1 && addon_data.bytes[0] < 5 => {
+                value.as_third_party_signature_status().map(|status| format!("{:?}", status))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_formats_assertions_by_outcome() {
+        let success = AssertionResult::Success.to_value();
+        assert_eq!(StdValue::format_display(&success), Some("assertion succeeded".into()));
+        let failure = AssertionResult::Failure("expected 1, found 2".into()).to_value();
+        assert_eq!(StdValue::format_display(&failure), Some("expected 1, found 2".into()));
+    }
+
+    #[test]
+    fn it_formats_third_party_signatures_by_status() {
+        let approved = Value::third_party_signature_approved();
+        assert_eq!(StdValue::format_display(&approved), Some("Approved".into()));
+        let invalid = Value::addon(vec![9], THIRD_PARTY_SIGNATURE);
+        assert_eq!(StdValue::format_display(&invalid), None);
+    }
+
+    #[test]
+    fn it_leaves_hashes_unformatted() {
+        assert_eq!(StdValue::format_display(&StdValue::hash(vec![0xab; 32])), None);
+    }
 }
@@ -1,3 +1,6 @@
+use txtx_addon_kit::helpers::fs::FileLocation;
+use txtx_addon_kit::types::cloud_interface::CloudServiceContext;
+use txtx_addon_kit::types::diagnostics::Diagnostic;
 use txtx_addon_kit::types::{
     frontend::{
         ActionItemResponse, ActionItemResponseType, ActionItemStatus, ProvidedInputResponse,
@@ -5,10 +8,13 @@ use txtx_addon_kit::types::{
     },
     types::Value,
 };
+use txtx_addon_kit::types::{AuthorizationContext, RunbookId};
 use txtx_addon_kit::{types::block_id::BlockId, Addon};
 use txtx_test_utils::test_harness::setup_test;
 
+use crate::runbook::RunbookTopLevelInputsMap;
 use crate::std::StdAddon;
+use crate::types::{Runbook, RunbookSources};
 
 pub fn get_addon_by_namespace(namespace: &str) -> Option<Box<dyn Addon>> {
     let available_addons: Vec<Box<dyn Addon>> = vec![Box::new(StdAddon::new())];
@@ -20,6 +26,33 @@ pub fn get_addon_by_namespace(namespace: &str) -> Option<Box<dyn Addon>> {
     None
 }
 
+/// Builds a runbook from a fixture with the types of this crate, unlike the runbooks built by
+/// `txtx_test_utils`, which depends on its own copy of `txtx-core`.
+async fn build_runbook_from_fixture(
+    file_name: &str,
+    fixture: &str,
+    get_addon_by_namespace: fn(&str) -> Option<Box<dyn Addon>>,
+) -> Result<Runbook, Vec<Diagnostic>> {
+    let mut runbook_sources = RunbookSources::new();
+    runbook_sources.add_source(
+        file_name.into(),
+        FileLocation::from_path_string(".").unwrap(),
+        fixture.into(),
+    );
+    let runbook_id = RunbookId { org: None, workspace: None, name: "test".into() };
+    let mut runbook = Runbook::new(runbook_id, None);
+    runbook
+        .build_contexts_from_sources(
+            runbook_sources,
+            RunbookTopLevelInputsMap::new(),
+            AuthorizationContext::empty(),
+            get_addon_by_namespace,
+            CloudServiceContext::empty(),
+        )
+        .await?;
+    Ok(runbook)
+}
+
 #[test]
 fn test_ab_c_runbook_no_env() {
     // Load Runbook ab_c.tx
@@ -113,3 +146,1168 @@ fn test_ab_c_runbook_no_env() {
 
     harness.expect_runbook_complete();
 }
+
+#[test]
+fn test_signer_aliases_are_scoped_to_environments() {
+    use txtx_addon_kit::indexmap::IndexMap;
+
+    let environments = IndexMap::from([
+        (
+            "global".to_string(),
+            IndexMap::from([("signer.deployer".to_string(), "local_keypair".to_string())]),
+        ),
+        (
+            "devnet".to_string(),
+            IndexMap::from([("rpc_api_url".to_string(), "http://localhost:8899".to_string())]),
+        ),
+        (
+            "mainnet".to_string(),
+            IndexMap::from([("signer.deployer".to_string(), "ledger".to_string())]),
+        ),
+    ]);
+
+    let devnet =
+        RunbookTopLevelInputsMap::from_environment_map(&Some("devnet".into()), &environments);
+    assert_eq!(devnet.current_signer_aliases().get("deployer").unwrap(), "local_keypair");
+    let inputs = devnet.current_top_level_inputs();
+    assert!(inputs.get_value("rpc_api_url").is_some());
+    assert!(inputs.get_value("signer.deployer").is_none());
+
+    let mainnet =
+        RunbookTopLevelInputsMap::from_environment_map(&Some("mainnet".into()), &environments);
+    assert_eq!(mainnet.current_signer_aliases().get("deployer").unwrap(), "ledger");
+}
+
+#[test]
+fn test_addon_defaults_are_scoped_to_environments() {
+    use txtx_addon_kit::indexmap::IndexMap;
+
+    let environments = IndexMap::from([
+        (
+            "global".to_string(),
+            IndexMap::from([
+                ("addon.evm.fee.strategy".to_string(), "normal".to_string()),
+                ("addon.evm.fee.max".to_string(), "100".to_string()),
+            ]),
+        ),
+        (
+            "devnet".to_string(),
+            IndexMap::from([("addon.evm.chain_id".to_string(), "31337".to_string())]),
+        ),
+        (
+            "mainnet".to_string(),
+            IndexMap::from([("addon.evm.fee.strategy".to_string(), "fast".to_string())]),
+        ),
+    ]);
+
+    let devnet =
+        RunbookTopLevelInputsMap::from_environment_map(&Some("devnet".into()), &environments);
+    let evm_defaults = devnet.current_addon_defaults().get("evm").cloned().unwrap();
+    assert_eq!(evm_defaults.get_integer("chain_id"), Some(31337));
+    let fee = evm_defaults.get_value("fee").and_then(|v| v.as_object()).unwrap();
+    assert_eq!(fee.get("strategy").and_then(|v| v.as_string()), Some("normal"));
+    assert!(devnet.current_top_level_inputs().get_value("addon.evm.chain_id").is_none());
+
+    // environment entries are deep-merged over the global ones
+    let mainnet =
+        RunbookTopLevelInputsMap::from_environment_map(&Some("mainnet".into()), &environments);
+    let evm_defaults = mainnet.current_addon_defaults().get("evm").cloned().unwrap();
+    let fee = evm_defaults.get_value("fee").and_then(|v| v.as_object()).unwrap();
+    assert_eq!(fee.get("strategy").and_then(|v| v.as_string()), Some("fast"));
+    assert_eq!(fee.get("max").and_then(|v| v.as_integer()), Some(100));
+}
+
+#[test]
+fn test_warnings_do_not_fail_evaluation_passes() {
+    use crate::eval::EvaluationPassResult;
+    use txtx_addon_kit::types::construct_type::ConstructType;
+    use txtx_addon_kit::types::diagnostics::DiagnosticLevel;
+    use txtx_addon_kit::types::frontend::Panel;
+    use txtx_addon_kit::types::{ConstructId, PackageId};
+    use txtx_addon_kit::uuid::Uuid;
+
+    let location = FileLocation::from_path_string("./main.tx").unwrap();
+    let construct_id = ConstructId {
+        package_id: PackageId {
+            runbook_id: RunbookId { org: None, workspace: None, name: "test".into() },
+            package_location: location.clone(),
+            package_name: "main".into(),
+        },
+        construct_location: location,
+        construct_type: ConstructType::Action,
+        construct_name: "notify".into(),
+    };
+
+    let mut pass_result = EvaluationPassResult::new(&Uuid::new_v4());
+    pass_result
+        .push_diagnostic(&Diagnostic::warning("fee unusually high"), &construct_id, &|d| d.clone());
+    assert!(!pass_result.has_diagnostics());
+    assert!(pass_result.compile_diagnostics_to_block().is_none());
+
+    pass_result.push_diagnostic(&Diagnostic::error("reverted"), &construct_id, &|d| d.clone());
+    assert!(pass_result.has_diagnostics());
+    let Some(Panel::ErrorPanel(panel)) =
+        pass_result.compile_diagnostics_to_block().map(|block| block.panel)
+    else {
+        panic!("expected an error panel");
+    };
+    assert_eq!(panel.level, DiagnosticLevel::Error);
+
+    let warnings = pass_result.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].is_warning());
+    assert!(!pass_result.has_warnings());
+
+    // denied warnings fail the pass
+    let mut pass_result = EvaluationPassResult::new(&Uuid::new_v4());
+    pass_result.push_diagnostic(
+        &Diagnostic::warning("glob matched nothing"),
+        &construct_id,
+        &|d| d.clone(),
+    );
+    pass_result.deny_warnings();
+    assert!(pass_result.has_diagnostics());
+}
+
+#[test]
+fn test_input_files_precedence() {
+    use txtx_addon_kit::indexmap::IndexMap;
+
+    let environments = IndexMap::from([(
+        "devnet".to_string(),
+        IndexMap::from([
+            ("rpc_api_url".to_string(), "http://localhost:8899".to_string()),
+            ("amount".to_string(), "1".to_string()),
+        ]),
+    )]);
+    let mut inputs_map =
+        RunbookTopLevelInputsMap::from_environment_map(&Some("devnet".into()), &environments);
+
+    inputs_map
+        .override_values_with_input_file("a.json", r#"{"amount": 2, "recipients": ["a", "b"]}"#)
+        .unwrap();
+    inputs_map.override_values_with_input_file("b.yaml", "amount: 3\nmemo: hello\n").unwrap();
+    inputs_map.override_values_with_cli_inputs(&vec!["memo=bye".into()], None).unwrap();
+
+    let inputs = inputs_map.current_top_level_inputs();
+    assert_eq!(inputs.get_string("rpc_api_url"), Some("http://localhost:8899"));
+    assert_eq!(inputs.get_value("amount").and_then(|v| v.as_integer()), Some(3));
+    let recipients = inputs.get_value("recipients").and_then(|v| v.as_array()).unwrap();
+    assert_eq!(recipients.iter().filter_map(|v| v.as_string()).collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(inputs.get_string("memo"), Some("bye"));
+
+    assert!(inputs_map.override_values_with_input_file("c.json", "[1, 2]").is_err());
+}
+
+mod leaky_signer {
+    use std::collections::HashMap;
+
+    use txtx_addon_kit::channel;
+    use txtx_addon_kit::types::commands::CommandSpecification;
+    use txtx_addon_kit::types::diagnostics::Diagnostic;
+    use txtx_addon_kit::types::frontend::{Actions, BlockEvent, LogLevel};
+    use txtx_addon_kit::types::signers::{
+        return_synchronous_actions, SignerActionsFutureResult, SignerActivateFutureResult,
+        SignerImplementation, SignerInstance, SignerSpecification, SignersState,
+    };
+    use txtx_addon_kit::types::stores::ValueStore;
+    use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type};
+    use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+    use txtx_addon_kit::uuid::Uuid;
+    use txtx_addon_kit::{define_signer, Addon};
+
+    lazy_static! {
+        pub static ref LEAKY_SIGNER: SignerSpecification = define_signer! {
+            LeakySigner => {
+                name: "Leaky Signer",
+                matcher: "mnemonic",
+                documentation: "A signer echoing its mnemonic in every channel before failing.",
+                inputs: [
+                    mnemonic: {
+                        documentation: "The mnemonic.",
+                        typing: Type::string(),
+                        optional: false,
+                        tainting: true,
+                        sensitive: true
+                    }
+                ],
+                outputs: [],
+                example: ""
+            }
+        };
+    }
+
+    #[derive(Debug)]
+    pub struct LeakyAddon;
+    impl Addon for LeakyAddon {
+        fn get_name(&self) -> &str {
+            "Leaky"
+        }
+        fn get_description(&self) -> &str {
+            "Leaky"
+        }
+        fn get_namespace(&self) -> &str {
+            "leaky"
+        }
+        fn get_signers(&self) -> Vec<SignerSpecification> {
+            vec![LEAKY_SIGNER.clone()]
+        }
+    }
+
+    pub struct LeakySigner;
+    impl SignerImplementation for LeakySigner {
+        fn check_instantiability(
+            _ctx: &SignerSpecification,
+            _args: Vec<Type>,
+        ) -> Result<Type, Diagnostic> {
+            unimplemented!()
+        }
+
+        fn check_activability(
+            _construct_did: &ConstructDid,
+            _instance_name: &str,
+            _spec: &SignerSpecification,
+            _values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+            _supervision_context: &RunbookSupervisionContext,
+            _auth_ctx: &AuthorizationContext,
+            _is_balance_check_required: bool,
+            _is_public_key_required: bool,
+        ) -> SignerActionsFutureResult {
+            return_synchronous_actions(Ok((signers, signer_state, Actions::none())))
+        }
+
+        fn activate(
+            _construct_did: &ConstructDid,
+            _spec: &SignerSpecification,
+            values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+            progress_tx: &channel::Sender<BlockEvent>,
+        ) -> SignerActivateFutureResult {
+            let mnemonic = values.get_string("mnemonic").unwrap_or_default();
+            let _ = progress_tx.send(BlockEvent::static_log(
+                LogLevel::Info,
+                Uuid::new_v4(),
+                "txtx::leaky".into(),
+                "Deriving",
+                format!("deriving key from '{mnemonic}'"),
+            ));
+            let first_words = mnemonic.split(' ').take(6).collect::<Vec<_>>().join(" ");
+            Err((
+                signers,
+                signer_state,
+                diagnosed_error!("invalid mnemonic '{mnemonic}' (starting with '{first_words}')"),
+            ))
+        }
+    }
+}
+
+#[test]
+fn test_failing_signer_never_reproduces_its_mnemonic() {
+    use std::time::Duration;
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::types::frontend::BlockEvent;
+
+    const MNEMONIC: &str =
+        "debris rally velvet hover copper stumble fiscal apron orbit kidney walnut shrug";
+
+    fn get_addon(namespace: &str) -> Option<Box<dyn Addon>> {
+        match namespace {
+            "leaky" => Some(Box::new(leaky_signer::LeakyAddon)),
+            _ => get_addon_by_namespace(namespace),
+        }
+    }
+
+    let fixture = format!(
+        r#"
+signer "deployer" "leaky::mnemonic" {{
+    mnemonic = "{MNEMONIC}"
+}}
+
+variable "deployer" {{
+    value = signer.deployer
+}}
+"#
+    );
+    let mut runbook =
+        block_on(build_runbook_from_fixture("leaky.tx", &fixture, get_addon)).unwrap();
+
+    let (progress_tx, progress_rx) = txtx_addon_kit::channel::unbounded::<BlockEvent>();
+    let diags = block_on(crate::start_unsupervised_runbook_runloop(&mut runbook, &progress_tx))
+        .expect_err("the signer should fail");
+    drop(progress_tx);
+    // the secrets of the run are released once it ends
+    assert!(runbook.runtime_context.secrets.is_empty());
+
+    let mut channels = diags.iter().map(|diag| diag.to_string()).collect::<Vec<_>>();
+    while let Ok(event) = progress_rx.recv_timeout(Duration::from_secs(2)) {
+        channels.push(serde_json::to_string(&event).unwrap());
+    }
+    assert!(channels.iter().any(|output| output.contains("<redacted>")));
+    for output in channels.iter() {
+        assert!(!output.contains(MNEMONIC), "mnemonic leaked: {output}");
+        assert!(
+            !output.contains("debris rally velvet hover copper stumble"),
+            "mnemonic leaked: {output}"
+        );
+        assert!(!output.contains("debris rally velvet"), "mnemonic leaked: {output}");
+    }
+}
+
+mod aliased_signer {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use txtx_addon_kit::channel;
+    use txtx_addon_kit::types::commands::CommandExecutionResult;
+    use txtx_addon_kit::types::diagnostics::Diagnostic;
+    use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
+    use txtx_addon_kit::types::signers::{
+        return_synchronous_actions, return_synchronous_result, SignerActionsFutureResult,
+        SignerActivateFutureResult, SignerImplementation, SignerInstance, SignerSpecification,
+        SignersState,
+    };
+    use txtx_addon_kit::types::stores::ValueStore;
+    use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type};
+    use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+    use txtx_addon_kit::{define_signer, Addon};
+
+    lazy_static! {
+        pub static ref ACTIVATED_KEYS: Mutex<Vec<String>> = Mutex::new(vec![]);
+        pub static ref KEYPAIR_SIGNER: SignerSpecification = define_signer! {
+            KeypairSigner => {
+                name: "Keypair Signer",
+                matcher: "keypair",
+                documentation: "A signer recording the key it is activated with.",
+                inputs: [
+                    key: {
+                        documentation: "The key.",
+                        typing: Type::string(),
+                        optional: false,
+                        tainting: true,
+                        sensitive: false
+                    }
+                ],
+                outputs: [],
+                example: ""
+            }
+        };
+    }
+
+    #[derive(Debug)]
+    pub struct AliasedAddon;
+    impl Addon for AliasedAddon {
+        fn get_name(&self) -> &str {
+            "Aliased"
+        }
+        fn get_description(&self) -> &str {
+            "Aliased"
+        }
+        fn get_namespace(&self) -> &str {
+            "aliased"
+        }
+        fn get_signers(&self) -> Vec<SignerSpecification> {
+            vec![KEYPAIR_SIGNER.clone()]
+        }
+    }
+
+    pub struct KeypairSigner;
+    impl SignerImplementation for KeypairSigner {
+        fn check_instantiability(
+            _ctx: &SignerSpecification,
+            _args: Vec<Type>,
+        ) -> Result<Type, Diagnostic> {
+            unimplemented!()
+        }
+
+        fn check_activability(
+            _construct_did: &ConstructDid,
+            _instance_name: &str,
+            _spec: &SignerSpecification,
+            _values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+            _supervision_context: &RunbookSupervisionContext,
+            _auth_ctx: &AuthorizationContext,
+            _is_balance_check_required: bool,
+            _is_public_key_required: bool,
+        ) -> SignerActionsFutureResult {
+            return_synchronous_actions(Ok((signers, signer_state, Actions::none())))
+        }
+
+        fn activate(
+            _construct_did: &ConstructDid,
+            _spec: &SignerSpecification,
+            values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+            _progress_tx: &channel::Sender<BlockEvent>,
+        ) -> SignerActivateFutureResult {
+            let key = values.get_string("key").unwrap_or_default();
+            ACTIVATED_KEYS.lock().unwrap().push(key.to_string());
+            return_synchronous_result(Ok((signers, signer_state, CommandExecutionResult::new())))
+        }
+    }
+}
+
+#[test]
+fn test_signer_aliases_bind_signers_of_a_shared_signers_file() {
+    use std::collections::BTreeMap;
+
+    use aliased_signer::{AliasedAddon, ACTIVATED_KEYS};
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::indexmap::IndexMap;
+    use txtx_addon_kit::types::frontend::BlockEvent;
+    use txtx_addon_kit::types::ConstructDid;
+
+    fn get_addon(namespace: &str) -> Option<Box<dyn Addon>> {
+        match namespace {
+            "aliased" => Some(Box::new(AliasedAddon)),
+            _ => get_addon_by_namespace(namespace),
+        }
+    }
+
+    // the runbook and the signers it binds live in distinct directories, hence distinct packages
+    let mut runbook_sources = RunbookSources::new();
+    runbook_sources.add_source(
+        "main".into(),
+        FileLocation::from_path_string("runbooks/main.tx").unwrap(),
+        r#"
+variable "deployer" {
+    value = signer.deployer
+}
+"#
+        .into(),
+    );
+    runbook_sources.add_source(
+        "signers".into(),
+        FileLocation::from_path_string("signers/signers.tx").unwrap(),
+        r#"
+signer "local" "aliased::keypair" {
+    key = "local-key"
+}
+
+signer "ledger" "aliased::keypair" {
+    key = "ledger-key"
+}
+"#
+        .into(),
+    );
+    let environments = IndexMap::from([
+        (
+            "devnet".to_string(),
+            IndexMap::from([("signer.deployer".to_string(), "local".to_string())]),
+        ),
+        (
+            "mainnet".to_string(),
+            IndexMap::from([("signer.deployer".to_string(), "ledger".to_string())]),
+        ),
+    ]);
+    let inputs_map =
+        RunbookTopLevelInputsMap::from_environment_map(&Some("devnet".into()), &environments);
+
+    let runbook_id = RunbookId { org: None, workspace: None, name: "test".into() };
+    let mut runbook = Runbook::new(runbook_id, None);
+    block_on(runbook.build_contexts_from_sources(
+        runbook_sources,
+        inputs_map,
+        AuthorizationContext::empty(),
+        get_addon,
+        CloudServiceContext::empty(),
+    ))
+    .unwrap();
+
+    let workspace_context = &runbook.flow_contexts[0].workspace_context;
+    let package_id = |name: &str| {
+        workspace_context.packages.keys().find(|id| id.package_name == name).unwrap().clone()
+    };
+    let signer_did = |name: &str| -> ConstructDid {
+        workspace_context.packages.get(&package_id("signers")).unwrap().signers_did_lookup[name]
+            .clone()
+    };
+    let (local_did, ledger_did) = (signer_did("local"), signer_did("ledger"));
+    assert_eq!(
+        workspace_context.resolve_signer_alias(&package_id("main"), "deployer"),
+        Some(local_did.clone())
+    );
+    assert_eq!(
+        workspace_context.resolve_signer_alias(&package_id("signers"), "deployer"),
+        Some(local_did.clone())
+    );
+    assert_eq!(workspace_context.resolve_signer_alias(&package_id("main"), "unknown"), None);
+
+    // the signer bound in the active environment is the only one activated
+    let (progress_tx, _progress_rx) = txtx_addon_kit::channel::unbounded::<BlockEvent>();
+    let mut action_item_requests = BTreeMap::new();
+    let mut action_item_responses = BTreeMap::new();
+    block_on(crate::build_genesis_panel(
+        &mut runbook,
+        &mut action_item_requests,
+        &action_item_responses,
+        &progress_tx,
+        0,
+        0,
+        1,
+    ))
+    .unwrap();
+    assert_eq!(*ACTIVATED_KEYS.lock().unwrap(), vec!["local-key".to_string()]);
+
+    // switching environments rebinds the alias, and drops what was collected for the signers
+    action_item_responses.insert(
+        local_did.clone(),
+        vec![ActionItemResponse {
+            action_item_id: BlockId::new(&[0]),
+            payload: ActionItemResponseType::ProvidePublicKey(
+                txtx_addon_kit::types::frontend::ProvidePublicKeyResponse {
+                    public_key: "local-public-key".into(),
+                },
+            ),
+        }],
+    );
+    block_on(crate::reset_runbook_execution(
+        &mut runbook,
+        &ActionItemResponseType::PickInputOption("mainnet".into()),
+        &mut action_item_requests,
+        &mut action_item_responses,
+        &progress_tx,
+        0,
+        1,
+    ))
+    .unwrap();
+    assert!(action_item_responses.is_empty());
+    assert_eq!(
+        *ACTIVATED_KEYS.lock().unwrap(),
+        vec!["local-key".to_string(), "ledger-key".to_string()]
+    );
+
+    let flow_context = &runbook.flow_contexts[0];
+    assert_eq!(
+        flow_context.workspace_context.resolve_signer_alias(&package_id("main"), "deployer"),
+        Some(ledger_did.clone())
+    );
+    let signers_state = flow_context.execution_context.signers_state.as_ref().unwrap();
+    assert!(signers_state.get_signer_state(&local_did).is_none());
+    assert!(signers_state.get_signer_state(&ledger_did).is_some());
+}
+
+mod hooked_addon {
+    use std::sync::Mutex;
+    use txtx_addon_kit::types::diagnostics::Diagnostic;
+    use txtx_addon_kit::types::RunbookLifecycleContext;
+    use txtx_addon_kit::Addon;
+
+    lazy_static! {
+        pub static ref HOOK_CALLS: Mutex<Vec<String>> = Mutex::new(vec![]);
+    }
+
+    /// An addon failing the hook named by its `fail` default
+    #[derive(Debug)]
+    pub struct HookedAddon;
+    impl HookedAddon {
+        fn hook(&self, ctx: &RunbookLifecycleContext, hook: &str) -> Result<(), Diagnostic> {
+            HOOK_CALLS.lock().unwrap().push(hook.to_string());
+            let fail = ctx.addons_defaults.iter().any(|defaults| {
+                defaults.store.get_expected_string("fail").map_or(false, |fail| fail == hook)
+            });
+            if fail {
+                return Err(diagnosed_error!("unable to {hook} the run"));
+            }
+            Ok(())
+        }
+    }
+    impl Addon for HookedAddon {
+        fn get_name(&self) -> &str {
+            "Hooked"
+        }
+        fn get_description(&self) -> &str {
+            "Hooked"
+        }
+        fn get_namespace(&self) -> &str {
+            "hooked"
+        }
+        fn on_runbook_start(&self, ctx: &mut RunbookLifecycleContext) -> Result<(), Diagnostic> {
+            self.hook(ctx, "start")
+        }
+        fn on_runbook_end(
+            &self,
+            ctx: &mut RunbookLifecycleContext,
+            _result: &Result<(), Vec<Diagnostic>>,
+        ) -> Result<(), Diagnostic> {
+            self.hook(ctx, "end")
+        }
+    }
+}
+
+#[test]
+fn test_addon_lifecycle_hooks() {
+    use hooked_addon::{HookedAddon, HOOK_CALLS};
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::types::frontend::BlockEvent;
+
+    fn get_addon(namespace: &str) -> Option<Box<dyn Addon>> {
+        match namespace {
+            "hooked" => Some(Box::new(HookedAddon)),
+            _ => get_addon_by_namespace(namespace),
+        }
+    }
+
+    let run = |fail: &str| {
+        HOOK_CALLS.lock().unwrap().clear();
+        let fixture = format!(
+            r#"
+addon "hooked" {{
+    fail = "{fail}"
+}}
+
+variable "value" {{
+    value = 1
+}}
+"#
+        );
+        let mut runbook =
+            block_on(build_runbook_from_fixture("hooked.tx", &fixture, get_addon)).unwrap();
+        let (progress_tx, _progress_rx) = txtx_addon_kit::channel::unbounded::<BlockEvent>();
+        let res = block_on(crate::start_unsupervised_runbook_runloop(&mut runbook, &progress_tx));
+        let executed = runbook.flow_contexts.iter().any(|flow| {
+            flow.execution_context
+                .commands_execution_results
+                .keys()
+                .any(|did| flow.execution_context.commands_instances.contains_key(did))
+        });
+        (res, runbook.warnings, executed, HOOK_CALLS.lock().unwrap().clone())
+    };
+
+    // a failing start hook aborts the run before any construct executes
+    let (res, _, executed, calls) = run("start");
+    let diags = res.expect_err("the start hook should fail the run");
+    assert_eq!(diags[0].message, "unable to start the run");
+    assert_eq!(diags[0].code.as_deref(), Some("CORE201"));
+    assert!(!executed);
+    assert_eq!(calls, vec!["start"]);
+
+    // a failing end hook is reported as a warning
+    let (res, warnings, executed, calls) = run("end");
+    assert!(res.is_ok());
+    assert!(executed);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].is_warning());
+    assert_eq!(warnings[0].code.as_deref(), Some("CORE202"));
+    assert_eq!(calls, vec!["start", "end"]);
+}
+
+mod legacy_addon {
+    use txtx_addon_kit::types::deprecation::Deprecation;
+    use txtx_addon_kit::types::diagnostics::Diagnostic;
+    use txtx_addon_kit::types::functions::{FunctionImplementation, FunctionSpecification};
+    use txtx_addon_kit::types::types::{Type, Value};
+    use txtx_addon_kit::types::AuthorizationContext;
+    use txtx_addon_kit::{define_function, Addon};
+
+    /// An addon exposing a deprecated function, with a deprecated input
+    #[derive(Debug)]
+    pub struct LegacyAddon;
+    impl Addon for LegacyAddon {
+        fn get_name(&self) -> &str {
+            "Legacy"
+        }
+        fn get_description(&self) -> &str {
+            "Legacy"
+        }
+        fn get_namespace(&self) -> &str {
+            "legacy"
+        }
+        fn get_functions(&self) -> Vec<FunctionSpecification> {
+            vec![define_function! {
+                Double => {
+                    name: "double",
+                    documentation: "",
+                    example: "",
+                    inputs: [
+                        value: {
+                            documentation: "",
+                            typing: vec![Type::integer()],
+                            optional: false
+                        },
+                        rounding: {
+                            documentation: "",
+                            typing: vec![Type::string()],
+                            optional: true,
+                            deprecated: Deprecation::since("v0.2.0")
+                        }
+                    ],
+                    deprecated: Deprecation::since("v0.2.0")
+                        .removal_planned("v0.4.0")
+                        .replaced_by("std::multiply"),
+                    output: {
+                        documentation: "",
+                        typing: Type::integer()
+                    },
+                }
+            }]
+        }
+    }
+
+    pub struct Double;
+    impl FunctionImplementation for Double {
+        fn check_instantiability(
+            _fn_spec: &FunctionSpecification,
+            _auth_ctx: &AuthorizationContext,
+            _args: &Vec<Type>,
+        ) -> Result<Type, Diagnostic> {
+            unimplemented!()
+        }
+
+        fn run(
+            _fn_spec: &FunctionSpecification,
+            _auth_ctx: &AuthorizationContext,
+            args: &Vec<Value>,
+        ) -> Result<Value, Diagnostic> {
+            Ok(Value::integer(args[0].expect_integer() * 2))
+        }
+    }
+}
+
+#[test]
+fn test_deprecations_are_reported_once_per_construct() {
+    use legacy_addon::LegacyAddon;
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::types::deprecation::DeprecatedItemKind;
+    use txtx_addon_kit::types::frontend::BlockEvent;
+
+    fn get_addon(namespace: &str) -> Option<Box<dyn Addon>> {
+        match namespace {
+            "legacy" => Some(Box::new(LegacyAddon)),
+            _ => get_addon_by_namespace(namespace),
+        }
+    }
+
+    let fixture = r#"
+addon "legacy" {
+}
+
+variable "doubled" {
+    value = legacy::double(legacy::double(1), "floor")
+}
+"#;
+    let mut runbook =
+        block_on(build_runbook_from_fixture("legacy.tx", fixture, get_addon)).unwrap();
+    let (progress_tx, _progress_rx) = txtx_addon_kit::channel::unbounded::<BlockEvent>();
+    let res = block_on(crate::start_unsupervised_runbook_runloop(&mut runbook, &progress_tx));
+    assert!(res.is_ok());
+
+    let deprecations = runbook
+        .deprecations
+        .iter()
+        .map(|usage| (usage.kind, usage.item.as_str(), usage.construct.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        deprecations,
+        vec![
+            (DeprecatedItemKind::Function, "legacy::double", "variable.doubled"),
+            (DeprecatedItemKind::Input, "legacy::double.rounding", "variable.doubled"),
+        ]
+    );
+    assert_eq!(runbook.warnings.len(), 2);
+    assert_eq!(runbook.warnings[0].code.as_deref(), Some("CORE107"));
+    assert_eq!(
+        runbook.warnings[0].message,
+        "variable.doubled: function 'legacy::double' is deprecated since v0.2.0 and will be removed in v0.4.0; use 'std::multiply' instead"
+    );
+
+    // deprecations fail the run when warnings are denied
+    let mut runbook =
+        block_on(build_runbook_from_fixture("legacy.tx", fixture, get_addon)).unwrap();
+    runbook.deny_warnings = true;
+    let diags = block_on(crate::start_unsupervised_runbook_runloop(&mut runbook, &progress_tx))
+        .expect_err("deprecations should be denied");
+    assert!(diags.iter().all(|diag| diag.is_error()));
+}
+
+mod picky_addon {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use txtx_addon_kit::types::commands::{
+        CommandImplementation, CommandSpecification, PreCommandSpecification,
+    };
+    use txtx_addon_kit::types::diagnostics::Diagnostic;
+    use txtx_addon_kit::types::frontend::Actions;
+    use txtx_addon_kit::types::signers::{
+        return_synchronous_actions, SignerActionsFutureResult, SignerCapabilities,
+        SignerImplementation, SignerInstance, SignerSpecification, SignersState,
+    };
+    use txtx_addon_kit::types::stores::ValueStore;
+    use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type};
+    use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+    use txtx_addon_kit::{define_command, define_signer, Addon};
+
+    pub static SIGNER_CHECKED: AtomicBool = AtomicBool::new(false);
+
+    lazy_static! {
+        pub static ref WALLET: SignerSpecification = define_signer! {
+            Wallet => {
+                name: "Wallet",
+                matcher: "wallet",
+                documentation: "A signer only able to sign transactions.",
+                inputs: [],
+                outputs: [],
+                example: "",
+                capabilities: SignerCapabilities {
+                    sign_transaction: true,
+                    ..SignerCapabilities::none()
+                }
+            }
+        };
+        pub static ref SIGN_MESSAGE: PreCommandSpecification = define_command! {
+            SignMessage => {
+                name: "Sign Message",
+                matcher: "sign_message",
+                documentation: "A command signing messages.",
+                implements_signing_capability: true,
+                implements_background_task_capability: false,
+                inputs: [
+                    signer: {
+                        documentation: "The signer.",
+                        typing: Type::string(),
+                        optional: false,
+                        tainting: true,
+                        internal: false
+                    }
+                ],
+                outputs: [],
+                example: "",
+                required_signer_capabilities: SignerCapabilities {
+                    sign_message: true,
+                    ..SignerCapabilities::none()
+                },
+            }
+        };
+    }
+
+    #[derive(Debug)]
+    pub struct PickyAddon;
+    impl Addon for PickyAddon {
+        fn get_name(&self) -> &str {
+            "Picky"
+        }
+        fn get_description(&self) -> &str {
+            "Picky"
+        }
+        fn get_namespace(&self) -> &str {
+            "picky"
+        }
+        fn get_signers(&self) -> Vec<SignerSpecification> {
+            vec![WALLET.clone()]
+        }
+        fn get_actions(&self) -> Vec<PreCommandSpecification> {
+            vec![SIGN_MESSAGE.clone()]
+        }
+    }
+
+    pub struct Wallet;
+    impl SignerImplementation for Wallet {
+        fn check_instantiability(
+            _ctx: &SignerSpecification,
+            _args: Vec<Type>,
+        ) -> Result<Type, Diagnostic> {
+            unimplemented!()
+        }
+
+        fn check_activability(
+            _construct_did: &ConstructDid,
+            _instance_name: &str,
+            _spec: &SignerSpecification,
+            _values: &ValueStore,
+            signer_state: ValueStore,
+            signers: SignersState,
+            _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+            _supervision_context: &RunbookSupervisionContext,
+            _auth_ctx: &AuthorizationContext,
+            _is_balance_check_required: bool,
+            _is_public_key_required: bool,
+        ) -> SignerActionsFutureResult {
+            SIGNER_CHECKED.store(true, Ordering::SeqCst);
+            return_synchronous_actions(Ok((signers, signer_state, Actions::none())))
+        }
+    }
+
+    pub struct SignMessage;
+    impl CommandImplementation for SignMessage {
+        fn check_instantiability(
+            _ctx: &CommandSpecification,
+            _args: Vec<Type>,
+        ) -> Result<Type, Diagnostic> {
+            unimplemented!()
+        }
+    }
+}
+
+#[test]
+fn test_signers_lacking_required_capabilities_are_rejected() {
+    use picky_addon::{PickyAddon, SIGNER_CHECKED};
+    use std::sync::atomic::Ordering;
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::types::frontend::BlockEvent;
+
+    fn get_addon(namespace: &str) -> Option<Box<dyn Addon>> {
+        match namespace {
+            "picky" => Some(Box::new(PickyAddon)),
+            _ => get_addon_by_namespace(namespace),
+        }
+    }
+
+    let fixture = r#"
+signer "alice" "picky::wallet" {
+}
+
+action "greeting" "picky::sign_message" {
+    signer = signer.alice
+}
+"#;
+    let mut runbook = block_on(build_runbook_from_fixture("picky.tx", fixture, get_addon)).unwrap();
+    let (progress_tx, _progress_rx) = txtx_addon_kit::channel::unbounded::<BlockEvent>();
+    let diags = block_on(crate::start_unsupervised_runbook_runloop(&mut runbook, &progress_tx))
+        .expect_err("the signer should be rejected");
+
+    let diag = diags
+        .iter()
+        .find(|diag| diag.message.contains("unable to sign messages"))
+        .expect("missing capability diagnostic");
+    assert!(diag.message.contains("signer 'alice' (picky::wallet)"), "{}", diag.message);
+    assert!(diag.message.contains("command 'greeting' (picky::sign_message)"), "{}", diag.message);
+    // the capabilities are checked before the signers are
+    assert!(!SIGNER_CHECKED.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_background_tasks_send_heartbeats_while_making_progress() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::Poll;
+    use std::time::{Duration, Instant};
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::futures::future::poll_fn;
+    use txtx_addon_kit::types::commands::CommandExecutionResult;
+    use txtx_addon_kit::types::heartbeat::BackgroundTasksHeartbeats;
+    use txtx_addon_kit::types::{ConstructDid, Did};
+
+    // completes after `steps` wake ups, each coming `step` after the previous one
+    fn task(
+        steps: u32,
+        step: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<CommandExecutionResult, Diagnostic>> + Send>> {
+        let mut remaining = steps;
+        Box::pin(poll_fn(move |cx| {
+            if remaining == 0 {
+                return Poll::Ready(Ok(CommandExecutionResult::new()));
+            }
+            remaining -= 1;
+            let waker = cx.waker().clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(step);
+                waker.wake();
+            });
+            Poll::Pending
+        }))
+    }
+
+    let polling = ConstructDid(Did::from_components(vec!["polling"]));
+    let waiting = ConstructDid(Did::from_components(vec!["waiting"]));
+    let mut heartbeats = BackgroundTasksHeartbeats::new();
+    let mut ticks = vec![];
+    let started_at = Instant::now();
+    let results = block_on(crate::await_background_tasks(
+        &[polling.clone(), waiting.clone()],
+        vec![task(40, Duration::from_millis(10)), task(1, Duration::from_millis(600))],
+        &mut heartbeats,
+        Duration::from_millis(50),
+        |tick| ticks.push(tick),
+    ));
+
+    assert!(started_at.elapsed() >= Duration::from_millis(600));
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result.is_ok()));
+    let heartbeats_count = |construct_did: &ConstructDid| {
+        ticks
+            .iter()
+            .flat_map(|tick| tick.heartbeats.iter())
+            .filter(|heartbeat| &heartbeat.construct_did == construct_did)
+            .count()
+    };
+    assert!(heartbeats_count(&polling) >= 3, "{:?}", ticks);
+    // the waiting task is polled once when it starts, then stays silent until it completes
+    assert_eq!(heartbeats_count(&waiting), 1, "{:?}", ticks);
+}
+
+mod waiting_addon {
+    use txtx_addon_kit::channel;
+    use txtx_addon_kit::types::cloud_interface::CloudServiceContext;
+    use txtx_addon_kit::types::commands::{
+        return_synchronous_result, CommandExecutionFutureResult, CommandExecutionResult,
+        CommandImplementation, CommandSpecification, PreCommandSpecification,
+    };
+    use txtx_addon_kit::types::diagnostics::Diagnostic;
+    use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
+    use txtx_addon_kit::types::stores::ValueStore;
+    use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type};
+    use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+    use txtx_addon_kit::uuid::Uuid;
+    use txtx_addon_kit::{define_command, Addon};
+
+    lazy_static! {
+        pub static ref WAIT: PreCommandSpecification = define_command! {
+            Wait => {
+                name: "Wait",
+                matcher: "wait",
+                documentation: "A command outputting its value from a background task.",
+                implements_signing_capability: false,
+                implements_background_task_capability: true,
+                inputs: [
+                    value: {
+                        documentation: "The value.",
+                        typing: Type::integer(),
+                        optional: false,
+                        tainting: true,
+                        internal: false
+                    }
+                ],
+                outputs: [
+                    value: {
+                        documentation: "The value.",
+                        typing: Type::integer()
+                    }
+                ],
+                example: "",
+            }
+        };
+    }
+
+    #[derive(Debug)]
+    pub struct WaitingAddon;
+    impl Addon for WaitingAddon {
+        fn get_name(&self) -> &str {
+            "Waiting"
+        }
+        fn get_description(&self) -> &str {
+            "Waiting"
+        }
+        fn get_namespace(&self) -> &str {
+            "waiting"
+        }
+        fn get_actions(&self) -> Vec<PreCommandSpecification> {
+            vec![WAIT.clone()]
+        }
+    }
+
+    pub struct Wait;
+    impl CommandImplementation for Wait {
+        fn check_instantiability(
+            _ctx: &CommandSpecification,
+            _args: Vec<Type>,
+        ) -> Result<Type, Diagnostic> {
+            unimplemented!()
+        }
+
+        fn check_executability(
+            _construct_did: &ConstructDid,
+            _instance_name: &str,
+            _spec: &CommandSpecification,
+            _values: &ValueStore,
+            _supervision_context: &RunbookSupervisionContext,
+            _auth_context: &AuthorizationContext,
+        ) -> Result<Actions, Diagnostic> {
+            Ok(Actions::none())
+        }
+
+        fn run_execution(
+            _construct_did: &ConstructDid,
+            _spec: &CommandSpecification,
+            _values: &ValueStore,
+            _progress_tx: &channel::Sender<BlockEvent>,
+            _auth_context: &AuthorizationContext,
+        ) -> CommandExecutionFutureResult {
+            return_synchronous_result(Ok(CommandExecutionResult::new()))
+        }
+
+        fn build_background_task(
+            _construct_did: &ConstructDid,
+            _spec: &CommandSpecification,
+            values: &ValueStore,
+            _outputs: &ValueStore,
+            _progress_tx: &channel::Sender<BlockEvent>,
+            _background_tasks_uuid: &Uuid,
+            _supervision_context: &RunbookSupervisionContext,
+            _cloud_service_context: &Option<CloudServiceContext>,
+        ) -> CommandExecutionFutureResult {
+            let value = values.get_expected_value("value")?.clone();
+            return_synchronous_result(Ok(CommandExecutionResult::from([("value", value)])))
+        }
+    }
+}
+
+#[test]
+fn test_construct_statuses_follow_background_tasks() {
+    use txtx_addon_kit::futures::executor::block_on;
+    use txtx_addon_kit::types::frontend::{BlockEvent, ConstructStatus, ConstructStatusStore};
+    use waiting_addon::WaitingAddon;
+
+    fn get_addon(namespace: &str) -> Option<Box<dyn Addon>> {
+        match namespace {
+            "waiting" => Some(Box::new(WaitingAddon)),
+            _ => get_addon_by_namespace(namespace),
+        }
+    }
+
+    let fixture = r#"
+action "first" "waiting::wait" {
+    value = 1
+}
+
+action "second" "waiting::wait" {
+    value = action.first.value
+}
+"#;
+    let mut runbook =
+        block_on(build_runbook_from_fixture("waiting.tx", fixture, get_addon)).unwrap();
+    let (progress_tx, progress_rx) = txtx_addon_kit::channel::unbounded::<BlockEvent>();
+    block_on(crate::start_unsupervised_runbook_runloop(&mut runbook, &progress_tx)).unwrap();
+    drop(progress_tx);
+
+    let mut store = ConstructStatusStore::new();
+    let mut transitions = vec![];
+    for event in progress_rx.try_iter() {
+        let BlockEvent::UpdateConstructStatuses(updates) = event else {
+            continue;
+        };
+        for update in updates {
+            if update.name == "first" && transitions.last() != Some(&update.status) {
+                transitions.push(update.status);
+            }
+            store.apply(update);
+        }
+    }
+
+    assert_eq!(
+        transitions,
+        vec![
+            ConstructStatus::Pending,
+            ConstructStatus::Executing,
+            ConstructStatus::BackgroundTaskRunning,
+            ConstructStatus::Completed,
+        ]
+    );
+    let entries = store.entries().collect::<Vec<_>>();
+    assert_eq!(entries.len(), 2);
+    for entry in entries.iter() {
+        assert_eq!(entry.construct.status, ConstructStatus::Completed, "{:?}", entry);
+        assert!(entry.duration.is_some());
+    }
+    let second = entries.iter().find(|entry| entry.construct.name == "second").unwrap();
+    assert_eq!(second.construct.dependencies, vec!["action.first".to_string()]);
+}
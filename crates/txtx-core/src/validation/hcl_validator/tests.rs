@@ -225,3 +225,63 @@ variable "chain_config" {
         assert!(!result.has_errors(), "Should not error when no flows are defined (might be runtime flow)");
     }
 }
+
+#[cfg(test)]
+mod action_parameters_tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::kit::types::commands::{CommandSpecification, PreCommandSpecification};
+    use crate::kit::Addon;
+    use crate::std::StdAddon;
+    use crate::validation::hcl_validator::validate_with_hcl_and_addons;
+
+    fn std_specs() -> HashMap<String, Vec<(String, CommandSpecification)>> {
+        let actions = StdAddon::new()
+            .get_actions()
+            .into_iter()
+            .filter_map(|action| match action {
+                PreCommandSpecification::Atomic(spec) => Some((spec.matcher.clone(), spec)),
+                PreCommandSpecification::Composite(_) => None,
+            })
+            .collect();
+        HashMap::from([("std".to_string(), actions)])
+    }
+
+    #[test]
+    fn test_literal_type_mismatch() {
+        let content = r#"
+action "ping" "std::send_http_request" {
+    url = "https://example.com"
+    timeout_ms = "200"
+}
+"#;
+
+        let mut result = ValidationResult::new();
+        validate_with_hcl_and_addons(content, &mut result, "runbook.tx", std_specs()).unwrap();
+
+        let error = result.errors.iter()
+            .find(|e| e.code.as_deref() == Some("type_mismatch"))
+            .expect("Should have a type mismatch error");
+        assert!(error.message.contains("timeout_ms"));
+        assert_eq!((error.line, error.column), (Some(4), Some(18)));
+    }
+
+    #[test]
+    fn test_references_are_not_type_checked() {
+        let content = r#"
+variable "timeout" {
+    value = "200"
+}
+action "ping" "std::send_http_request" {
+    url = "https://example.com"
+    timeout_ms = variable.timeout
+}
+"#;
+
+        let mut result = ValidationResult::new();
+        validate_with_hcl_and_addons(content, &mut result, "runbook.tx", std_specs()).unwrap();
+
+        assert!(!result.has_errors(), "Unexpected errors: {:?}", result.errors);
+    }
+}
@@ -7,6 +7,8 @@ use std::collections::HashMap;
 use txtx_addon_kit::constants::{
     DEPENDS_ON, DESCRIPTION, MARKDOWN, MARKDOWN_FILEPATH, POST_CONDITION, PRE_CONDITION,
 };
+use txtx_addon_kit::hcl::expr::Expression;
+use txtx_addon_kit::types::types::Type;
 
 use crate::kit::types::commands::CommandSpecification;
 use super::visitor::ValidationError;
@@ -62,3 +64,29 @@ pub fn is_inherited_property(attr_name: &str) -> bool {
         MARKDOWN | MARKDOWN_FILEPATH | DESCRIPTION | DEPENDS_ON | PRE_CONDITION | POST_CONDITION
     )
 }
+
+/// Check a literal value against the type of an input, returning the type found on mismatch.
+///
+/// Only literals are checked: the type of references and function calls is only known once
+/// evaluated, and inputs typed as addon types, buffers or objects accept several representations.
+pub fn literal_type_mismatch(expected: &Type, value: &Expression) -> Option<&'static str> {
+    let found = match value {
+        Expression::String(_) => "string",
+        Expression::Number(number) if number.value().is_f64() => "float",
+        Expression::Number(_) => "integer",
+        Expression::Bool(_) => "bool",
+        Expression::Array(_) => "array",
+        _ => return None,
+    };
+    let compatible = match expected {
+        Type::String => found == "string",
+        Type::Integer => found == "integer",
+        Type::Float => found == "integer" || found == "float",
+        Type::DateTime => found == "string" || found == "integer",
+        Type::Bool => found == "bool",
+        Type::Array(_) => found == "array",
+        Type::OneOf(types) => types.iter().any(|t| literal_type_mismatch(t, value).is_none()),
+        _ => true,
+    };
+    (!compatible).then_some(found)
+}
@@ -83,6 +83,33 @@ pub enum ValidationError {
         construct_type: String,
         cycle: Vec<String>,
     },
+
+    #[error("Type mismatch for parameter '{param}' of action '{action}': expected {expected}, found {found}")]
+    TypeMismatch {
+        param: String,
+        action: String,
+        expected: String,
+        found: String,
+    },
+}
+
+impl ValidationError {
+    /// Code of the error, attached to its diagnostic so that tools (e.g. the language server)
+    /// can tell the failures apart without parsing the message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::MissingLabel(_) => "missing_label",
+            Self::InvalidFormat { .. } => "invalid_format",
+            Self::UnknownNamespace { .. } => "unknown_namespace",
+            Self::UnknownAction { .. } => "unknown_action",
+            Self::UndefinedReference { .. } => "undefined_reference",
+            Self::MissingParameter { .. } => "missing_parameter",
+            Self::InvalidParameter { .. } => "invalid_parameter",
+            Self::InvalidOutputField { .. } => "invalid_output_field",
+            Self::CircularDependency { .. } => "circular_dependency",
+            Self::TypeMismatch { .. } => "type_mismatch",
+        }
+    }
 }
 
 /// Block types in HCL runbooks.
@@ -667,6 +694,7 @@ impl<'a> HclValidationVisitor<'a> {
     fn add_error(&mut self, error: ValidationError, position: Position) {
         self.result.errors.push(
             Diagnostic::error(error.to_string())
+                .with_code(error.code())
                 .with_file(self.file_path.to_string())
                 .with_line(position.line)
                 .with_column(position.column)
@@ -759,8 +787,30 @@ impl<'a> HclValidationVisitor<'a> {
                             )
                         });
 
+                    // Check the literal values against the types of the inputs
+                    let type_mismatch_errors = block.body.attributes()
+                        .filter_map(|attr| {
+                            let input = spec.inputs.iter().find(|input| input.name == attr.key.as_str())?;
+                            let found = validation_helpers::literal_type_mismatch(&input.typing, &attr.value)?;
+                            let position = optional_span_to_position(
+                                &self.source_mapper,
+                                attr.value.span().as_ref()
+                            );
+
+                            Some((
+                                ValidationError::TypeMismatch {
+                                    param: input.name.clone(),
+                                    action: action_type.to_string(),
+                                    expected: input.typing.to_string(),
+                                    found: found.to_string(),
+                                },
+                                position,
+                            ))
+                        });
+
                     errors.extend(invalid_param_errors);
                     errors.extend(missing_param_errors);
+                    errors.extend(type_mismatch_errors);
                 }
             }
         }
@@ -286,6 +286,7 @@ mod tests {
             id: "test".to_string(),
             runbooks: vec![],
             environments: IndexMap::new(),
+            signers: None,
             location: None,
         };
 
@@ -325,6 +326,7 @@ mod tests {
             id: "test".to_string(),
             runbooks: vec![],
             environments: IndexMap::new(),
+            signers: None,
             location: None,
         };
 
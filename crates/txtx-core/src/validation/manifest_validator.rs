@@ -362,6 +362,7 @@ mod tests {
             id: "test-id".to_string(),
             runbooks: Vec::new(),
             environments,
+            signers: None,
             location: None,
         }
     }
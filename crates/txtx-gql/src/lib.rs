@@ -5,13 +5,14 @@ use std::{collections::BTreeMap, sync::Arc};
 use subscription::Subscription;
 use tokio::sync::RwLock;
 use txtx_addon_kit::types::frontend::{
-    ActionItemResponse, Block, BlockEvent, LogEvent, SupervisorAddonData,
+    ActionItemResponse, Block, BlockEvent, ConstructStatusStore, LogEvent, SupervisorAddonData,
 };
 
 pub mod mutation;
 pub mod query;
 pub mod subscription;
 pub mod types;
+pub mod validation;
 
 pub use txtx_addon_kit as kit;
 
@@ -23,6 +24,7 @@ pub struct Context {
     pub runbook_description: Option<String>,
     pub block_store: Arc<RwLock<BTreeMap<usize, Block>>>,
     pub log_store: Arc<RwLock<Vec<LogEvent>>>,
+    pub construct_store: Arc<RwLock<ConstructStatusStore>>,
     pub block_broadcaster: tokio::sync::broadcast::Sender<BlockEvent>,
     pub log_broadcaster: tokio::sync::broadcast::Sender<LogEvent>,
     pub action_item_events_tx: tokio::sync::broadcast::Sender<ActionItemResponse>,
@@ -1,4 +1,8 @@
-use crate::Context;
+use crate::{
+    validation::{validate_action_item_response, ActionItemResponseError},
+    Context,
+};
+use juniper::FieldResult;
 use juniper_codegen::graphql_object;
 use txtx_addon_kit::{serde_json, types::frontend::ActionItemResponse};
 
@@ -12,8 +16,15 @@ impl Mutation {
         "1.0"
     }
 
-    fn update_action_item(context: &Context, event: String) -> Result<String, String> {
-        let event: ActionItemResponse = serde_json::from_str(&event).map_err(|e| e.to_string())?;
+    /// Forwards an action item response to the runloop, once checked against the action item
+    /// request it answers.
+    async fn update_action_item(context: &Context, event: String) -> FieldResult<String> {
+        let event: ActionItemResponse = serde_json::from_str(&event)
+            .map_err(|e| ActionItemResponseError::new("event", e.to_string()))?;
+        {
+            let block_store = context.block_store.read().await;
+            validate_action_item_response(&block_store, &event)?;
+        }
         let _ = context.action_item_events_tx.send(event);
         Ok("Ok".to_string())
     }
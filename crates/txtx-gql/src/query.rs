@@ -1,6 +1,11 @@
 use crate::{
     types::{
-        block::{GqlActionBlock, GqlErrorBlock, GqlLogEvent, GqlModalBlock},
+        block::{
+            BlockFilter, GqlActionBlock, GqlBlockPage, GqlErrorBlock, GqlLogEvent, GqlLogLevel,
+            GqlLogPage, GqlModalBlock, GqlPanelType, GqlPendingActionItem, GqlPendingActionItems,
+            LogFilter,
+        },
+        construct::GqlConstruct,
         runbook::RunbookMetadata,
     },
     Context,
@@ -10,6 +15,11 @@ use txtx_addon_kit::types::frontend::Panel;
 
 pub struct Query;
 
+pub const DEFAULT_BLOCKS_PAGE_SIZE: usize = 50;
+pub const MAX_BLOCKS_PAGE_SIZE: usize = 500;
+pub const DEFAULT_LOGS_PAGE_SIZE: usize = 100;
+pub const MAX_LOGS_PAGE_SIZE: usize = 1000;
+
 #[graphql_object(
     context = Context,
 )]
@@ -48,9 +58,75 @@ impl Query {
             .collect()
     }
 
-    async fn logs(context: &Context) -> Vec<GqlLogEvent> {
+    /// The blocks of the block store, paginated with the `after` cursor and filtered by panel type
+    /// and visibility. Clients catching up with a long-running runbook can fetch the backlog
+    /// page by page, then subscribe to `blockEvent` from the last `endCursor`.
+    async fn blocks(
+        context: &Context,
+        after: Option<i32>,
+        limit: Option<i32>,
+        panel_type: Option<GqlPanelType>,
+        visible: Option<bool>,
+    ) -> Result<GqlBlockPage, String> {
+        let after = after
+            .map(|after| usize::try_from(after).map_err(|_| "invalid cursor".to_string()))
+            .transpose()?;
+        let limit = match limit {
+            Some(limit) if limit <= 0 => return Err("limit must be positive".into()),
+            Some(limit) => (limit as usize).min(MAX_BLOCKS_PAGE_SIZE),
+            None => DEFAULT_BLOCKS_PAGE_SIZE,
+        };
+        let block_store = context.block_store.read().await;
+        Ok(GqlBlockPage::new(&block_store, after, limit, &BlockFilter { panel_type, visible }))
+    }
+
+    /// The action items awaiting a response, optionally filtered by `internal_key`
+    /// (e.g. `provide_signed_tx` to only list signature requests).
+    async fn pending_action_items(
+        context: &Context,
+        internal_key: Option<String>,
+    ) -> GqlPendingActionItems {
+        let block_store = context.block_store.read().await;
+        GqlPendingActionItems(GqlPendingActionItem::collect(block_store.values(), &internal_key))
+    }
+
+    /// The command and signer constructs of the active runbook, with their execution status.
+    async fn constructs(context: &Context) -> Vec<GqlConstruct> {
+        let construct_store = context.construct_store.read().await;
+        construct_store.entries().cloned().map(GqlConstruct).collect()
+    }
+
+    /// The stored log events, optionally filtered by minimum level and emitting construct.
+    async fn logs(
+        context: &Context,
+        min_level: Option<GqlLogLevel>,
+        construct_id: Option<String>,
+    ) -> Result<Vec<GqlLogEvent>, String> {
+        let filter = LogFilter::new(min_level, construct_id)?;
+        let log_store = context.log_store.read().await;
+        Ok(log_store.iter().filter(|e| filter.matches(e)).cloned().map(GqlLogEvent).collect())
+    }
+
+    /// The stored log events, paginated with the `after` cursor, for post-mortem viewing of
+    /// long-running runbooks. Live events can then be followed with the `logEvents` subscription.
+    async fn log_page(
+        context: &Context,
+        after: Option<i32>,
+        limit: Option<i32>,
+        min_level: Option<GqlLogLevel>,
+        construct_id: Option<String>,
+    ) -> Result<GqlLogPage, String> {
+        let filter = LogFilter::new(min_level, construct_id)?;
+        let after = after
+            .map(|after| usize::try_from(after).map_err(|_| "invalid cursor".to_string()))
+            .transpose()?;
+        let limit = match limit {
+            Some(limit) if limit <= 0 => return Err("limit must be positive".into()),
+            Some(limit) => (limit as usize).min(MAX_LOGS_PAGE_SIZE),
+            None => DEFAULT_LOGS_PAGE_SIZE,
+        };
         let log_store = context.log_store.read().await;
-        log_store.iter().cloned().map(GqlLogEvent).collect()
+        Ok(GqlLogPage::new(&log_store, after, limit, &filter))
     }
 
     fn runbook(context: &Context) -> RunbookMetadata {
@@ -2,13 +2,14 @@ use std::pin::Pin;
 
 use crate::{
     types::block::{
-        GqlActionBlock, GqlActionItemRequestUpdate, GqlErrorBlock, GqlLogEvent, GqlModalBlock,
-        GqlRunbookCompleteAdditionalInfo,
+        block_edges_stream, log_events_stream, BlockFilter, GqlActionBlock,
+        GqlActionItemRequestUpdate, GqlBlockEdge, GqlErrorBlock, GqlExecutionSummary, GqlLogEvent,
+        GqlLogLevel, GqlModalBlock, GqlPanelType, GqlRunbookCompleteAdditionalInfo, LogFilter,
     },
     Context,
 };
-use futures::Stream;
-use juniper::{graphql_subscription, FieldError};
+use futures::{Stream, StreamExt};
+use juniper::{graphql_subscription, FieldError, FieldResult};
 use txtx_addon_kit::types::frontend::BlockEvent;
 
 pub struct Subscription;
@@ -23,6 +24,9 @@ type GqlActionItemRequestUpdateStream =
 type ClearBlockEventStream = Pin<Box<dyn Stream<Item = Result<bool, FieldError>> + Send>>;
 type RunbookCompletedEventStream =
     Pin<Box<dyn Stream<Item = Result<Vec<GqlRunbookCompleteAdditionalInfo>, FieldError>> + Send>>;
+type ExecutionSummaryEventStream =
+    Pin<Box<dyn Stream<Item = Result<GqlExecutionSummary, FieldError>> + Send>>;
+type GqlBlockEdgeStream = Pin<Box<dyn Stream<Item = Result<GqlBlockEdge, FieldError>> + Send>>;
 type LogEventStream = Pin<Box<dyn Stream<Item = Result<GqlLogEvent, FieldError>> + Send>>;
 
 #[graphql_subscription(
@@ -80,6 +84,22 @@ impl Subscription {
         Box::pin(stream)
     }
 
+    /// Streams the blocks stored after the `after` cursor, then the new blocks as they are added
+    /// to the block store, without gaps or duplicates. Cursors restart from 0 once the block
+    /// store is cleared (see `clearBlocksEvent`).
+    async fn block_event(
+        context: &Context,
+        after: Option<i32>,
+        panel_type: Option<GqlPanelType>,
+        visible: Option<bool>,
+    ) -> GqlBlockEdgeStream {
+        let filter = BlockFilter { panel_type, visible };
+        let after = after.and_then(|after| usize::try_from(after).ok());
+        let block_store = context.block_store.read().await;
+        let stream = block_edges_stream(&block_store, &context.block_broadcaster, after, filter);
+        Box::pin(stream.map(Ok))
+    }
+
     async fn update_action_items_event(context: &Context) -> GqlActionItemRequestUpdateStream {
         let block_tx = context.block_broadcaster.clone();
         let mut block_rx = block_tx.subscribe();
@@ -130,6 +150,23 @@ impl Subscription {
         Box::pin(stream)
     }
 
+    /// Notifies that the execution summary is ready to be downloaded, once the runbook completed.
+    async fn execution_summary_event(context: &Context) -> ExecutionSummaryEventStream {
+        let block_tx = context.block_broadcaster.clone();
+        let mut block_rx = block_tx.subscribe();
+        let stream = async_stream::stream! {
+            loop {
+              if let Ok(block_event) = block_rx.recv().await {
+                match block_event {
+                  BlockEvent::RunbookCompleted(_) => yield Ok(GqlExecutionSummary),
+                  _ => {}
+                }
+              }
+            }
+        };
+        Box::pin(stream)
+    }
+
     async fn log_event(context: &Context) -> LogEventStream {
         let log_tx = context.log_broadcaster.clone();
         let mut log_rx = log_tx.subscribe();
@@ -142,4 +179,16 @@ impl Subscription {
         };
         Box::pin(stream)
     }
+
+    /// The stored log events matching the filter, followed by the new ones as they are emitted.
+    async fn log_events(
+        context: &Context,
+        min_level: Option<GqlLogLevel>,
+        construct_id: Option<String>,
+    ) -> FieldResult<LogEventStream> {
+        let filter = LogFilter::new(min_level, construct_id)?;
+        let log_store = context.log_store.read().await;
+        let stream = log_events_stream(&log_store, &context.log_broadcaster, filter);
+        Ok(Box::pin(stream.map(|log_event| Ok(GqlLogEvent(log_event)))))
+    }
 }
@@ -1,11 +1,22 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
 use crate::Context;
+use futures::Stream;
+use juniper::GraphQLEnum;
 use juniper_codegen::graphql_object;
+use tokio::sync::broadcast::Sender;
 use txtx_addon_kit::{
-    serde_json,
-    types::frontend::{
-        ActionGroup, ActionItemRequest, ActionPanelData, ActionSubGroup, Block, ErrorPanelData,
-        LogEvent, ModalPanelData, NormalizedActionItemRequestUpdate, Panel,
+    hex, serde_json,
+    types::{
+        frontend::{
+            ActionGroup, ActionItemRequest, ActionItemRequestType, ActionItemStatus,
+            ActionPanelData, ActionSubGroup, Block, BlockEvent, ErrorPanelData, LogEvent, LogLevel,
+            ModalPanelData, NormalizedActionItemRequestUpdate, Panel,
+        },
+        ConstructDid, Did,
     },
+    uuid::Uuid,
 };
 
 #[derive(Clone)]
@@ -155,6 +166,176 @@ impl GqlErrorBlock {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, GraphQLEnum)]
+pub enum GqlPanelType {
+    Action,
+    Modal,
+    Error,
+}
+
+impl GqlPanelType {
+    pub fn from_panel(panel: &Panel) -> Self {
+        match panel {
+            Panel::ActionPanel(_) => GqlPanelType::Action,
+            Panel::ModalPanel(_) => GqlPanelType::Modal,
+            Panel::ErrorPanel(_) => GqlPanelType::Error,
+        }
+    }
+}
+
+/// Filters applied to the blocks returned by the `blocks` query and `blockEvent` subscription.
+#[derive(Clone, Debug, Default)]
+pub struct BlockFilter {
+    pub panel_type: Option<GqlPanelType>,
+    pub visible: Option<bool>,
+}
+
+impl BlockFilter {
+    pub fn matches(&self, block: &Block) -> bool {
+        if let Some(panel_type) = self.panel_type {
+            if GqlPanelType::from_panel(&block.panel) != panel_type {
+                return false;
+            }
+        }
+        if let Some(visible) = self.visible {
+            if block.visible != visible {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A block of the block store, along with its cursor (its index in the store).
+#[derive(Clone)]
+pub struct GqlBlockEdge {
+    pub cursor: usize,
+    pub block: Block,
+}
+
+#[graphql_object(context = Context)]
+impl GqlBlockEdge {
+    pub fn cursor(&self) -> i32 {
+        self.cursor as i32
+    }
+
+    pub fn panel_type(&self) -> GqlPanelType {
+        GqlPanelType::from_panel(&self.block.panel)
+    }
+
+    pub fn uuid(&self) -> String {
+        self.block.uuid.to_string()
+    }
+
+    pub fn visible(&self) -> bool {
+        self.block.visible
+    }
+
+    pub fn action_panel(&self) -> Option<GqlActionPanelData> {
+        match &self.block.panel {
+            Panel::ActionPanel(panel_data) => Some(GqlActionPanelData::new(panel_data.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn modal_panel(&self) -> Option<GqlModalPanelData> {
+        match &self.block.panel {
+            Panel::ModalPanel(panel_data) => Some(GqlModalPanelData::new(panel_data.clone())),
+            _ => None,
+        }
+    }
+
+    pub fn error_panel(&self) -> Option<GqlErrorPanelData> {
+        match &self.block.panel {
+            Panel::ErrorPanel(panel_data) => Some(GqlErrorPanelData::new(panel_data.clone())),
+            _ => None,
+        }
+    }
+}
+
+pub struct GqlBlockPage {
+    pub edges: Vec<GqlBlockEdge>,
+    pub end_cursor: Option<usize>,
+    pub has_next_page: bool,
+}
+
+impl GqlBlockPage {
+    /// Builds the page of at most `limit` blocks matching `filter` stored after the `after` cursor.
+    pub fn new(
+        block_store: &BTreeMap<usize, Block>,
+        after: Option<usize>,
+        limit: usize,
+        filter: &BlockFilter,
+    ) -> Self {
+        let start = after.map(|after| Bound::Excluded(after)).unwrap_or(Bound::Unbounded);
+        let mut matching = block_store
+            .range((start, Bound::Unbounded))
+            .filter(|(_, block)| filter.matches(block))
+            .map(|(cursor, block)| GqlBlockEdge { cursor: *cursor, block: block.clone() });
+        let edges = matching.by_ref().take(limit).collect::<Vec<_>>();
+        let has_next_page = matching.next().is_some();
+        let end_cursor = edges.last().map(|edge| edge.cursor).or(after);
+        GqlBlockPage { edges, end_cursor, has_next_page }
+    }
+}
+
+#[graphql_object(context = Context)]
+impl GqlBlockPage {
+    pub fn blocks(&self) -> Vec<GqlBlockEdge> {
+        self.edges.clone()
+    }
+
+    /// The cursor to resume from, with the `blocks` query or the `blockEvent` subscription.
+    pub fn end_cursor(&self) -> Option<i32> {
+        self.end_cursor.map(|cursor| cursor as i32)
+    }
+
+    pub fn has_next_page(&self) -> bool {
+        self.has_next_page
+    }
+}
+
+/// Streams the blocks of `block_store` stored after the `after` cursor, then the blocks
+/// broadcasted by `block_broadcaster` as they are added to the store, keeping those matching
+/// `filter`. Must be called while holding the store lock, so that every block received was
+/// stored after the backlog snapshot.
+pub fn block_edges_stream(
+    block_store: &BTreeMap<usize, Block>,
+    block_broadcaster: &Sender<BlockEvent>,
+    after: Option<usize>,
+    filter: BlockFilter,
+) -> impl Stream<Item = GqlBlockEdge> + Send {
+    let mut block_rx = block_broadcaster.subscribe();
+    let start = after.map(|after| Bound::Excluded(after)).unwrap_or(Bound::Unbounded);
+    let backlog = block_store
+        .range((start, Bound::Unbounded))
+        .map(|(cursor, block)| GqlBlockEdge { cursor: *cursor, block: block.clone() })
+        .collect::<Vec<_>>();
+    let mut next_cursor = block_store.len();
+    async_stream::stream! {
+        for edge in backlog.into_iter() {
+            if filter.matches(&edge.block) {
+                yield edge;
+            }
+        }
+        loop {
+            if let Ok(block_event) = block_rx.recv().await {
+                match block_event {
+                    BlockEvent::Action(block) | BlockEvent::Modal(block) | BlockEvent::Error(block) => {
+                        let edge = GqlBlockEdge { cursor: next_cursor, block };
+                        next_cursor += 1;
+                        if filter.matches(&edge.block) {
+                            yield edge;
+                        }
+                    }
+                    BlockEvent::Clear => next_cursor = 0,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
 pub struct GqlActionPanelData {
     data: ActionPanelData,
 }
@@ -219,6 +400,25 @@ impl GqlErrorPanelData {
         self.data.description.clone()
     }
 
+    /// The highest level of the diagnostics of the panel: "error", "warning" or "note"
+    pub fn level(&self) -> String {
+        self.data.level.to_string()
+    }
+
+    /// The codes of the diagnostics of the panel, e.g. "EVM201", for clients to link their documentation
+    pub fn codes(&self) -> Vec<String> {
+        self.data
+            .groups
+            .iter()
+            .flat_map(|group| group.sub_groups.iter())
+            .flat_map(|sub_group| sub_group.action_items.iter())
+            .filter_map(|item| match &item.action_type {
+                ActionItemRequestType::DisplayErrorLog(request) => request.diagnostic.code.clone(),
+                _ => None,
+            })
+            .collect()
+    }
+
     pub fn groups(&self) -> Vec<GqlActionGroup> {
         self.data.groups.clone().into_iter().map(GqlActionGroup::new).collect()
     }
@@ -343,6 +543,163 @@ impl GqlLogEvent {
     pub fn status(&self) -> Option<String> {
         self.0.status()
     }
+
+    pub fn namespace(&self) -> String {
+        self.0.namespace().to_string()
+    }
+
+    pub fn completed_steps(&self) -> Option<i32> {
+        self.0.progress().and_then(|p| p.completed_steps).map(|steps| steps as i32)
+    }
+
+    pub fn total_steps(&self) -> Option<i32> {
+        self.0.progress().and_then(|p| p.total_steps).map(|steps| steps as i32)
+    }
+
+    pub fn percentage(&self) -> Option<f64> {
+        self.0.progress().and_then(|p| p.percentage())
+    }
+
+    pub fn eta_seconds(&self) -> Option<i32> {
+        self.0.progress().and_then(|p| p.eta_seconds).map(|eta| eta as i32)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, GraphQLEnum)]
+pub enum GqlLogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<GqlLogLevel> for LogLevel {
+    fn from(level: GqlLogLevel) -> Self {
+        match level {
+            GqlLogLevel::Trace => LogLevel::Trace,
+            GqlLogLevel::Debug => LogLevel::Debug,
+            GqlLogLevel::Info => LogLevel::Info,
+            GqlLogLevel::Warn => LogLevel::Warn,
+            GqlLogLevel::Error => LogLevel::Error,
+        }
+    }
+}
+
+/// Filters applied to the log events returned by the `logPage` query and `logEvents`
+/// subscription.
+#[derive(Clone, Debug, Default)]
+pub struct LogFilter {
+    pub min_level: Option<LogLevel>,
+    /// Log events are associated to the construct emitting them through their uuid.
+    pub construct_uuid: Option<Uuid>,
+}
+
+impl LogFilter {
+    /// Builds a filter from the GraphQL arguments, `construct_id` being the hex encoded did of
+    /// a construct.
+    pub fn new(
+        min_level: Option<GqlLogLevel>,
+        construct_id: Option<String>,
+    ) -> Result<Self, String> {
+        let construct_uuid = match construct_id {
+            Some(construct_id) => {
+                let bytes = hex::decode(construct_id.trim_start_matches("0x"))
+                    .map_err(|e| format!("invalid construct id: {e}"))?;
+                if bytes.len() != 32 {
+                    return Err("invalid construct id: expected 32 bytes".into());
+                }
+                Some(ConstructDid(Did::from_bytes(&bytes)).as_uuid())
+            }
+            None => None,
+        };
+        Ok(LogFilter { min_level: min_level.map(LogLevel::from), construct_uuid })
+    }
+
+    pub fn matches(&self, log_event: &LogEvent) -> bool {
+        if let Some(min_level) = &self.min_level {
+            if !min_level.should_log(&log_event.level()) {
+                return false;
+            }
+        }
+        if let Some(construct_uuid) = &self.construct_uuid {
+            if !log_event.uuid().eq(construct_uuid) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+pub struct GqlLogPage {
+    pub logs: Vec<LogEvent>,
+    pub end_cursor: Option<usize>,
+    pub has_next_page: bool,
+}
+
+impl GqlLogPage {
+    /// Builds the page of at most `limit` log events matching `filter` stored after the `after`
+    /// cursor (the index of a log event in the log store).
+    pub fn new(
+        log_store: &Vec<LogEvent>,
+        after: Option<usize>,
+        limit: usize,
+        filter: &LogFilter,
+    ) -> Self {
+        let start = after.map(|after| after + 1).unwrap_or(0);
+        let mut matching = log_store
+            .iter()
+            .enumerate()
+            .skip(start)
+            .filter(|(_, log_event)| filter.matches(log_event));
+        let page = matching.by_ref().take(limit).collect::<Vec<_>>();
+        let has_next_page = matching.next().is_some();
+        let end_cursor = page.last().map(|(cursor, _)| *cursor).or(after);
+        GqlLogPage {
+            logs: page.into_iter().map(|(_, log_event)| log_event.clone()).collect(),
+            end_cursor,
+            has_next_page,
+        }
+    }
+}
+
+#[graphql_object(context = Context)]
+impl GqlLogPage {
+    pub fn logs(&self) -> Vec<GqlLogEvent> {
+        self.logs.iter().cloned().map(GqlLogEvent).collect()
+    }
+
+    pub fn end_cursor(&self) -> Option<i32> {
+        self.end_cursor.map(|cursor| cursor as i32)
+    }
+
+    pub fn has_next_page(&self) -> bool {
+        self.has_next_page
+    }
+}
+
+/// Streams the log events of `log_store`, then the log events broadcasted by `log_broadcaster`
+/// as they are added to the store, keeping those matching `filter`. Must be called while holding
+/// the store lock, so that every log event received was stored after the backlog snapshot.
+pub fn log_events_stream(
+    log_store: &Vec<LogEvent>,
+    log_broadcaster: &Sender<LogEvent>,
+    filter: LogFilter,
+) -> impl Stream<Item = LogEvent> + Send {
+    let mut log_rx = log_broadcaster.subscribe();
+    let backlog = log_store.iter().filter(|e| filter.matches(e)).cloned().collect::<Vec<_>>();
+    async_stream::stream! {
+        for log_event in backlog.into_iter() {
+            yield log_event;
+        }
+        loop {
+            if let Ok(log_event) = log_rx.recv().await {
+                if filter.matches(&log_event) {
+                    yield log_event;
+                }
+            }
+        }
+    }
 }
 
 pub struct GqlRunbookCompleteAdditionalInfo(
@@ -367,3 +724,385 @@ impl GqlRunbookCompleteAdditionalInfo {
         self.0.details.clone()
     }
 }
+
+/// Where the summary of a completed execution can be downloaded from the supervisor.
+pub struct GqlExecutionSummary;
+
+#[graphql_object(context = Context)]
+impl GqlExecutionSummary {
+    pub fn json_url(&self) -> String {
+        "/api/v1/summary.json".into()
+    }
+
+    pub fn markdown_url(&self) -> String {
+        "/api/v1/summary.md".into()
+    }
+}
+
+#[derive(Clone)]
+pub struct GqlPendingActionItem {
+    pub action_item: ActionItemRequest,
+    pub panel_title: String,
+    pub group_title: String,
+}
+
+impl GqlPendingActionItem {
+    /// Collects the action items of the visible action and modal panels that are awaiting a
+    /// response, optionally restricted to a given `internal_key`.
+    pub fn collect<'a>(
+        blocks: impl Iterator<Item = &'a Block>,
+        internal_key: &Option<String>,
+    ) -> Vec<GqlPendingActionItem> {
+        let mut pending_action_items = vec![];
+        for block in blocks.filter(|b| b.visible) {
+            let (panel_title, groups) = match &block.panel {
+                Panel::ActionPanel(data) => (&data.title, &data.groups),
+                Panel::ModalPanel(data) => (&data.title, &data.groups),
+                Panel::ErrorPanel(_) => continue,
+            };
+            for group in groups.iter() {
+                for sub_group in group.sub_groups.iter() {
+                    for action_item in sub_group.action_items.iter() {
+                        if action_item.action_status != ActionItemStatus::Todo {
+                            continue;
+                        }
+                        if let Some(internal_key) = internal_key {
+                            if !action_item.internal_key.eq(internal_key) {
+                                continue;
+                            }
+                        }
+                        pending_action_items.push(GqlPendingActionItem {
+                            action_item: action_item.clone(),
+                            panel_title: panel_title.clone(),
+                            group_title: group.title.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        pending_action_items
+    }
+}
+
+#[graphql_object(context = Context)]
+impl GqlPendingActionItem {
+    pub fn action_item(&self) -> GqlActionItemRequest {
+        GqlActionItemRequest::new(self.action_item.clone())
+    }
+
+    pub fn construct_instance_name(&self) -> String {
+        self.action_item.construct_instance_name.clone()
+    }
+
+    pub fn panel_title(&self) -> String {
+        self.panel_title.clone()
+    }
+
+    pub fn group_title(&self) -> String {
+        self.group_title.clone()
+    }
+
+    pub fn expected_response_type(&self) -> Option<String> {
+        self.action_item.action_type.expected_response_type().map(|t| t.to_string())
+    }
+}
+
+pub struct GqlPendingActionItems(pub Vec<GqlPendingActionItem>);
+
+#[graphql_object(context = Context)]
+impl GqlPendingActionItems {
+    pub fn count(&self) -> i32 {
+        self.0.len() as i32
+    }
+
+    pub fn action_items(&self) -> Vec<GqlPendingActionItem> {
+        self.0.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use txtx_addon_kit::types::frontend::ProvideInputRequest;
+    use txtx_addon_kit::types::types::Type;
+
+    fn action_block(visible: bool) -> Block {
+        Block {
+            uuid: Uuid::new_v4(),
+            panel: Panel::new_action_panel("Review", "", vec![]),
+            visible,
+        }
+    }
+
+    fn error_block() -> Block {
+        let panel = Panel::ErrorPanel(ErrorPanelData::from_diagnostics(&vec![]));
+        Block { uuid: Uuid::new_v4(), panel, visible: true }
+    }
+
+    fn page(
+        block_store: &BTreeMap<usize, Block>,
+        after: Option<usize>,
+        limit: usize,
+        filter: &BlockFilter,
+    ) -> (Vec<usize>, Option<usize>, bool) {
+        let page = GqlBlockPage::new(block_store, after, limit, filter);
+        let cursors = page.edges.iter().map(|edge| edge.cursor).collect();
+        (cursors, page.end_cursor, page.has_next_page)
+    }
+
+    #[test]
+    fn test_blocks_are_paginated_after_the_cursor() {
+        let block_store = BTreeMap::from([
+            (0, action_block(true)),
+            (1, error_block()),
+            (2, action_block(false)),
+            (3, action_block(true)),
+            (4, action_block(true)),
+        ]);
+        let all = BlockFilter::default();
+        assert_eq!(page(&block_store, None, 2, &all), (vec![0, 1], Some(1), true));
+        assert_eq!(page(&block_store, Some(1), 2, &all), (vec![2, 3], Some(3), true));
+        assert_eq!(page(&block_store, Some(3), 2, &all), (vec![4], Some(4), false));
+        // a page ending on the last block has no next page
+        assert_eq!(page(&block_store, Some(2), 2, &all), (vec![3, 4], Some(4), false));
+        // the end cursor of an empty page is the cursor it was requested after
+        assert_eq!(page(&block_store, Some(4), 2, &all), (vec![], Some(4), false));
+        assert_eq!(page(&block_store, Some(10), 2, &all), (vec![], Some(10), false));
+        assert_eq!(page(&BTreeMap::new(), None, 2, &all), (vec![], None, false));
+    }
+
+    #[test]
+    fn test_blocks_pages_only_count_matching_blocks() {
+        let block_store = BTreeMap::from([
+            (0, action_block(true)),
+            (1, error_block()),
+            (2, action_block(false)),
+            (3, action_block(true)),
+            (4, error_block()),
+        ]);
+        let visible_actions =
+            BlockFilter { panel_type: Some(GqlPanelType::Action), visible: Some(true) };
+        assert_eq!(page(&block_store, None, 1, &visible_actions), (vec![0], Some(0), true));
+        assert_eq!(page(&block_store, Some(0), 1, &visible_actions), (vec![3], Some(3), false));
+        let errors = BlockFilter { panel_type: Some(GqlPanelType::Error), visible: None };
+        assert_eq!(page(&block_store, None, 5, &errors), (vec![1, 4], Some(4), false));
+    }
+
+    #[tokio::test]
+    async fn test_block_events_resume_from_the_cursor() {
+        let block_store =
+            BTreeMap::from([(0, action_block(true)), (1, error_block()), (2, action_block(true))]);
+        let (block_broadcaster, _) = tokio::sync::broadcast::channel(16);
+        let actions = BlockFilter { panel_type: Some(GqlPanelType::Action), visible: None };
+        let mut resumed = Box::pin(block_edges_stream(
+            &block_store,
+            &block_broadcaster,
+            Some(0),
+            actions.clone(),
+        ));
+        // resuming from the end cursor of the last page only streams the live blocks
+        let mut live =
+            Box::pin(block_edges_stream(&block_store, &block_broadcaster, Some(2), actions));
+
+        block_broadcaster.send(BlockEvent::Action(action_block(true))).unwrap();
+        block_broadcaster.send(BlockEvent::Error(error_block())).unwrap();
+        block_broadcaster.send(BlockEvent::Clear).unwrap();
+        block_broadcaster.send(BlockEvent::Action(action_block(true))).unwrap();
+
+        let mut cursors = vec![];
+        for _ in 0..3 {
+            cursors.push(resumed.next().await.unwrap().cursor);
+        }
+        assert_eq!(cursors, vec![2, 3, 0]);
+        let mut cursors = vec![];
+        for _ in 0..2 {
+            cursors.push(live.next().await.unwrap().cursor);
+        }
+        assert_eq!(cursors, vec![3, 0]);
+    }
+
+    fn action_item(construct_instance_name: &str, internal_key: &str) -> ActionItemRequest {
+        ActionItemRequestType::ProvideInput(ProvideInputRequest {
+            default_value: None,
+            input_name: "value".into(),
+            typing: Type::integer(),
+            sensitive: false,
+        })
+        .to_request(construct_instance_name, internal_key)
+    }
+
+    fn panel_block(panel: Panel, visible: bool) -> Block {
+        Block { uuid: Uuid::new_v4(), panel, visible }
+    }
+
+    fn groups(title: &str, action_items: Vec<ActionItemRequest>) -> Vec<ActionGroup> {
+        vec![ActionGroup::new(title, vec![ActionSubGroup::new(None, action_items, false)])]
+    }
+
+    fn collected(
+        block_store: &BTreeMap<usize, Block>,
+        internal_key: Option<&str>,
+    ) -> Vec<(String, String, String, Option<String>)> {
+        let internal_key = internal_key.map(|key| key.to_string());
+        GqlPendingActionItem::collect(block_store.values(), &internal_key)
+            .iter()
+            .map(|item| {
+                (
+                    item.construct_instance_name(),
+                    item.panel_title(),
+                    item.group_title(),
+                    item.expected_response_type(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pending_action_items_are_collected_from_visible_panels() {
+        let action_panel = Panel::new_action_panel(
+            "Runbook",
+            "",
+            groups(
+                "Inputs",
+                vec![
+                    action_item("a", "provide_input"),
+                    action_item("b", "provide_input").with_status(ActionItemStatus::Success(None)),
+                    action_item("c", "provide_signed_transaction"),
+                    action_item("d", "provide_input").with_status(ActionItemStatus::Blocked),
+                ],
+            ),
+        );
+        let modal_panel = Panel::new_modal_panel(
+            "Signers",
+            "",
+            groups("Wallets", vec![action_item("e", "check")]),
+        );
+        let hidden_panel = Panel::new_action_panel(
+            "Hidden",
+            "",
+            groups("Inputs", vec![action_item("f", "check")]),
+        );
+        let block_store = BTreeMap::from([
+            (0, panel_block(action_panel, true)),
+            (1, error_block()),
+            (2, panel_block(modal_panel, true)),
+            (3, panel_block(hidden_panel, false)),
+        ]);
+
+        let item = |name: &str, panel: &str, group: &str| {
+            (name.to_string(), panel.to_string(), group.to_string(), Some("ProvideInput".into()))
+        };
+        assert_eq!(
+            collected(&block_store, None),
+            vec![
+                item("a", "Runbook", "Inputs"),
+                item("c", "Runbook", "Inputs"),
+                item("e", "Signers", "Wallets"),
+            ]
+        );
+        assert_eq!(
+            collected(&block_store, Some("provide_signed_transaction")),
+            vec![item("c", "Runbook", "Inputs")]
+        );
+        assert_eq!(collected(&block_store, Some("unknown")), vec![]);
+        assert_eq!(collected(&BTreeMap::new(), None), vec![]);
+    }
+
+    fn static_log(level: LogLevel, uuid: Uuid, message: &str) -> LogEvent {
+        let BlockEvent::LogEvent(log_event) =
+            BlockEvent::static_log(level, uuid, "txtx".into(), "Test", message)
+        else {
+            unreachable!()
+        };
+        log_event
+    }
+
+    fn messages(logs: &[LogEvent]) -> Vec<String> {
+        logs.iter().map(|log_event| log_event.message()).collect()
+    }
+
+    #[test]
+    fn test_log_filters_are_built_from_construct_ids() {
+        let construct_did = ConstructDid(Did::from_bytes(&[7; 32]));
+        let construct_id = format!("0x{}", hex::encode([7; 32]));
+        let filter = LogFilter::new(Some(GqlLogLevel::Warn), Some(construct_id)).unwrap();
+        assert_eq!(filter.min_level, Some(LogLevel::Warn));
+        assert_eq!(filter.construct_uuid, Some(construct_did.as_uuid()));
+
+        assert!(LogFilter::new(None, Some("0xzz".into())).is_err());
+        assert!(LogFilter::new(None, Some(hex::encode([7; 16]))).is_err());
+    }
+
+    #[test]
+    fn test_logs_are_filtered_by_level_and_construct() {
+        let construct_uuid = Uuid::new_v4();
+        let log_store = vec![
+            static_log(LogLevel::Debug, construct_uuid, "debug"),
+            static_log(LogLevel::Warn, Uuid::new_v4(), "other warn"),
+            static_log(LogLevel::Info, construct_uuid, "info"),
+            static_log(LogLevel::Error, construct_uuid, "error"),
+        ];
+        let page = |after, limit, filter: &LogFilter| {
+            let page = GqlLogPage::new(&log_store, after, limit, filter);
+            (messages(&page.logs), page.end_cursor, page.has_next_page)
+        };
+
+        let all = LogFilter::default();
+        assert_eq!(page(None, 2, &all), (vec!["debug".into(), "other warn".into()], Some(1), true));
+        assert_eq!(page(Some(1), 2, &all), (vec!["info".into(), "error".into()], Some(3), false));
+        assert_eq!(page(Some(3), 2, &all), (vec![], Some(3), false));
+
+        let info = LogFilter { min_level: Some(LogLevel::Info), construct_uuid: None };
+        assert_eq!(page(None, 2, &info), (vec!["other warn".into(), "info".into()], Some(2), true));
+        assert_eq!(page(Some(2), 2, &info), (vec!["error".into()], Some(3), false));
+
+        let construct_info =
+            LogFilter { min_level: Some(LogLevel::Info), construct_uuid: Some(construct_uuid) };
+        assert_eq!(
+            page(None, 5, &construct_info),
+            (vec!["info".into(), "error".into()], Some(3), false)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_log_events_replay_the_backlog_then_stream_new_events() {
+        let log_store = vec![
+            static_log(LogLevel::Debug, Uuid::new_v4(), "stored debug"),
+            static_log(LogLevel::Warn, Uuid::new_v4(), "stored warn"),
+        ];
+        let (log_broadcaster, _) = tokio::sync::broadcast::channel(16);
+        let filter = LogFilter { min_level: Some(LogLevel::Warn), construct_uuid: None };
+        let mut log_events = Box::pin(log_events_stream(&log_store, &log_broadcaster, filter));
+
+        log_broadcaster.send(static_log(LogLevel::Info, Uuid::new_v4(), "new info")).unwrap();
+        log_broadcaster.send(static_log(LogLevel::Error, Uuid::new_v4(), "new error")).unwrap();
+
+        let mut received = vec![];
+        for _ in 0..2 {
+            received.push(log_events.next().await.unwrap());
+        }
+        assert_eq!(messages(&received), vec!["stored warn".to_string(), "new error".to_string()]);
+    }
+
+    #[test]
+    fn test_log_events_expose_their_progress() {
+        use txtx_addon_kit::types::frontend::{LogProgress, TransientLogEvent};
+
+        let pending = TransientLogEvent::pending_info(Uuid::new_v4(), "Confirming", "", "txtx");
+        let log_event = GqlLogEvent(LogEvent::Transient(
+            pending.clone().with_progress(LogProgress::steps(3, 12).with_eta_seconds(9)),
+        ));
+        assert_eq!(log_event.completed_steps(), Some(3));
+        assert_eq!(log_event.total_steps(), Some(12));
+        assert_eq!(log_event.percentage(), Some(25.0));
+        assert_eq!(log_event.eta_seconds(), Some(9));
+
+        let log_event = GqlLogEvent(LogEvent::Transient(pending));
+        assert_eq!(log_event.completed_steps(), None);
+        assert_eq!(log_event.percentage(), None);
+        let log_event = GqlLogEvent(static_log(LogLevel::Info, Uuid::new_v4(), "done"));
+        assert_eq!(log_event.total_steps(), None);
+        assert_eq!(log_event.eta_seconds(), None);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::Context;
+use juniper::GraphQLEnum;
+use juniper_codegen::graphql_object;
+use txtx_addon_kit::types::frontend::{ConstructStatus, ConstructStatusEntry};
+
+#[derive(Clone, Copy, Debug, PartialEq, GraphQLEnum)]
+pub enum GqlConstructStatus {
+    Pending,
+    AwaitingInput,
+    Executing,
+    BackgroundTaskRunning,
+    Completed,
+    Failed,
+    Skipped,
+}
+
+impl From<ConstructStatus> for GqlConstructStatus {
+    fn from(status: ConstructStatus) -> Self {
+        match status {
+            ConstructStatus::Pending => GqlConstructStatus::Pending,
+            ConstructStatus::AwaitingInput => GqlConstructStatus::AwaitingInput,
+            ConstructStatus::Executing => GqlConstructStatus::Executing,
+            ConstructStatus::BackgroundTaskRunning => GqlConstructStatus::BackgroundTaskRunning,
+            ConstructStatus::Completed => GqlConstructStatus::Completed,
+            ConstructStatus::Failed => GqlConstructStatus::Failed,
+            ConstructStatus::Skipped => GqlConstructStatus::Skipped,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct GqlConstruct(pub ConstructStatusEntry);
+
+#[graphql_object(context = Context)]
+impl GqlConstruct {
+    pub fn id(&self) -> String {
+        self.0.construct.construct_did.to_string()
+    }
+
+    pub fn construct_type(&self) -> String {
+        self.0.construct.construct_type.clone()
+    }
+
+    pub fn name(&self) -> String {
+        self.0.construct.name.clone()
+    }
+
+    pub fn namespace(&self) -> String {
+        self.0.construct.namespace.clone()
+    }
+
+    pub fn matcher(&self) -> String {
+        self.0.construct.matcher.clone()
+    }
+
+    pub fn status(&self) -> GqlConstructStatus {
+        self.0.construct.status.into()
+    }
+
+    pub fn dependencies(&self) -> Vec<String> {
+        self.0.construct.dependencies.clone()
+    }
+
+    /// The execution duration in milliseconds, once the construct is finished.
+    pub fn duration_ms(&self) -> Option<f64> {
+        self.0.duration.map(|duration| duration.as_secs_f64() * 1000.0)
+    }
+
+    /// When the running background task of the construct last sent a heartbeat, e.g.
+    /// `last update 45s ago`.
+    pub fn last_update(&self) -> Option<String> {
+        self.0.last_update()
+    }
+}
@@ -1,2 +1,3 @@
 pub mod block;
+pub mod construct;
 pub mod runbook;
@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use juniper::{graphql_value, FieldError};
+use txtx_addon_kit::types::block_id::BlockId;
+use txtx_addon_kit::types::frontend::{
+    ActionItemRequest, ActionItemRequestType, ActionItemResponse, ActionItemResponseType, Block,
+    Panel,
+};
+use txtx_addon_kit::types::types::{ObjectDefinition, Type};
+
+/// A rejected `ActionItemResponse`, reported as a GraphQL error carrying the offending field.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionItemResponseError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl ActionItemResponseError {
+    pub fn new(field: &'static str, message: impl ToString) -> Self {
+        ActionItemResponseError { field, message: message.to_string() }
+    }
+}
+
+impl Display for ActionItemResponseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl From<ActionItemResponseError> for FieldError {
+    fn from(error: ActionItemResponseError) -> Self {
+        let field = error.field;
+        FieldError::new(error.message, graphql_value!({ "field": field }))
+    }
+}
+
+pub fn find_action_item<'a>(
+    block_store: &'a BTreeMap<usize, Block>,
+    action_item_id: &BlockId,
+) -> Option<&'a ActionItemRequest> {
+    block_store
+        .values()
+        .flat_map(|block| match &block.panel {
+            Panel::ActionPanel(data) => data.groups.iter(),
+            Panel::ModalPanel(data) => data.groups.iter(),
+            Panel::ErrorPanel(data) => data.groups.iter(),
+        })
+        .flat_map(|group| group.sub_groups.iter())
+        .flat_map(|sub_group| sub_group.action_items.iter())
+        .find(|action_item| action_item.id.eq(action_item_id))
+}
+
+/// Checks that a response targets an action item of the block store, with a payload matching
+/// the request: the same response type, the same input, and a value of the expected type.
+pub fn validate_action_item_response(
+    block_store: &BTreeMap<usize, Block>,
+    response: &ActionItemResponse,
+) -> Result<(), ActionItemResponseError> {
+    let payload = &response.payload;
+    // panel validations aren't bound to a specific action item
+    if let ActionItemResponseType::ValidateBlock | ActionItemResponseType::ValidateModal = payload {
+        return Ok(());
+    }
+
+    let Some(request) = find_action_item(block_store, &response.action_item_id) else {
+        return Err(ActionItemResponseError::new(
+            "actionItemId",
+            format!("unknown action item {}", response.action_item_id),
+        ));
+    };
+
+    let expected = request.action_type.expected_response_type();
+    // the value of a provided input is confirmed with a review
+    let accepted = match (&request.action_type, payload) {
+        (ActionItemRequestType::ProvideInput(_), ActionItemResponseType::ReviewInput(_)) => true,
+        (_, payload) => expected == Some(payload.type_name()),
+    };
+    if !accepted {
+        return Err(ActionItemResponseError::new(
+            "payload",
+            match expected {
+                Some(expected) => format!(
+                    "action item {} expects a {expected} response, got {}",
+                    request.id,
+                    payload.type_name()
+                ),
+                None => format!("action item {} does not expect a response", request.id),
+            },
+        ));
+    }
+
+    match (&request.action_type, payload) {
+        (ActionItemRequestType::ReviewInput(request), ActionItemResponseType::ReviewInput(r))
+            if !request.input_name.eq(&r.input_name) =>
+        {
+            Err(unexpected_input_name(&request.input_name, &r.input_name))
+        }
+        (ActionItemRequestType::ProvideInput(request), ActionItemResponseType::ReviewInput(r))
+            if !request.input_name.eq(&r.input_name) =>
+        {
+            Err(unexpected_input_name(&request.input_name, &r.input_name))
+        }
+        (ActionItemRequestType::ProvideInput(request), ActionItemResponseType::ProvideInput(r)) => {
+            if !request.input_name.eq(&r.input_name) {
+                return Err(unexpected_input_name(&request.input_name, &r.input_name));
+            }
+            if is_checkable(&request.typing) {
+                request.typing.check_value(&r.updated_value).map_err(|diag| {
+                    ActionItemResponseError::new(
+                        "payload.updatedValue",
+                        format!("invalid value for input '{}': {}", r.input_name, diag.message),
+                    )
+                })?;
+            }
+            Ok(())
+        }
+        (ActionItemRequestType::ProvideFile(request), ActionItemResponseType::ProvideFile(r)) => {
+            if !request.input_name.eq(&r.input_name) {
+                return Err(unexpected_input_name(&request.input_name, &r.input_name));
+            }
+            request
+                .check_file(&r.filename, r.size())
+                .map_err(|e| ActionItemResponseError::new("payload", e))
+        }
+        (
+            ActionItemRequestType::PickInputOption(request),
+            ActionItemResponseType::PickInputOption(option),
+        ) if !request.options.iter().any(|o| o.value.eq(option)) => {
+            Err(ActionItemResponseError::new("payload", format!("unknown option '{option}'")))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn unexpected_input_name(expected: &str, found: &str) -> ActionItemResponseError {
+    ActionItemResponseError::new(
+        "payload.inputName",
+        format!("expected input '{expected}', got '{found}'"),
+    )
+}
+
+/// `Type::check_value` doesn't support tuples and enums
+fn is_checkable(typing: &Type) -> bool {
+    match typing {
+        Type::Object(ObjectDefinition::Tuple(_) | ObjectDefinition::Enum(_))
+        | Type::Map(ObjectDefinition::Tuple(_) | ObjectDefinition::Enum(_)) => false,
+        Type::OneOf(types) => types.iter().all(is_checkable),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use txtx_addon_kit::types::frontend::{
+        ActionGroup, ActionSubGroup, InputOption, PickInputOptionRequest, ProvideFileRequest,
+        ProvideInputRequest, ProvidedFileResponse, ProvidedInputResponse, ReviewInputRequest,
+        ReviewedInputResponse,
+    };
+    use txtx_addon_kit::types::types::Value;
+    use txtx_addon_kit::uuid::Uuid;
+
+    fn block_store(action_items: Vec<ActionItemRequest>) -> BTreeMap<usize, Block> {
+        let panel = Panel::new_action_panel(
+            "Review",
+            "",
+            vec![ActionGroup::new("Inputs", vec![ActionSubGroup::new(None, action_items, false)])],
+        );
+        BTreeMap::from([(0, Block::new(&Uuid::new_v4(), panel))])
+    }
+
+    fn provide_input_request() -> ActionItemRequest {
+        ActionItemRequestType::ProvideInput(ProvideInputRequest {
+            default_value: None,
+            input_name: "value".into(),
+            typing: Type::integer(),
+            sensitive: false,
+        })
+        .to_request("b", "provide_input")
+    }
+
+    fn provided(input_name: &str, value: Value) -> ActionItemResponseType {
+        ActionItemResponseType::ProvideInput(ProvidedInputResponse {
+            input_name: input_name.into(),
+            updated_value: value,
+        })
+    }
+
+    fn reviewed(input_name: &str) -> ActionItemResponseType {
+        ActionItemResponseType::ReviewInput(ReviewedInputResponse {
+            input_name: input_name.into(),
+            value_checked: true,
+            force_execution: false,
+        })
+    }
+
+    fn validate(
+        store: &BTreeMap<usize, Block>,
+        request: &ActionItemRequest,
+        payload: ActionItemResponseType,
+    ) -> Result<(), ActionItemResponseError> {
+        validate_action_item_response(
+            store,
+            &ActionItemResponse { action_item_id: request.id.clone(), payload },
+        )
+    }
+
+    #[test]
+    fn test_valid_responses_are_accepted() {
+        let request = provide_input_request();
+        let store = block_store(vec![request.clone()]);
+        assert_eq!(validate(&store, &request, provided("value", Value::integer(5))), Ok(()));
+        assert_eq!(validate(&store, &request, reviewed("value")), Ok(()));
+        assert_eq!(
+            validate_action_item_response(
+                &store,
+                &ActionItemResponse {
+                    action_item_id: BlockId::new(&vec![]),
+                    payload: ActionItemResponseType::ValidateBlock,
+                }
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_unknown_action_item_is_rejected() {
+        let request = provide_input_request();
+        let store = block_store(vec![]);
+        let err = validate(&store, &request, provided("value", Value::integer(5))).unwrap_err();
+        assert_eq!(err.field, "actionItemId");
+    }
+
+    #[test]
+    fn test_mismatched_response_type_is_rejected() {
+        let request = ActionItemRequestType::ReviewInput(ReviewInputRequest::new(
+            "value",
+            &Value::integer(1),
+        ))
+        .to_request("a", "check_input");
+        let store = block_store(vec![request.clone()]);
+        let err = validate(&store, &request, provided("value", Value::integer(5))).unwrap_err();
+        assert_eq!(err.field, "payload");
+        let err = validate(&store, &request, ActionItemResponseType::PickInputOption("x".into()))
+            .unwrap_err();
+        assert_eq!(err.field, "payload");
+    }
+
+    #[test]
+    fn test_mismatched_input_name_is_rejected() {
+        let request = provide_input_request();
+        let store = block_store(vec![request.clone()]);
+        let err = validate(&store, &request, provided("other", Value::integer(5))).unwrap_err();
+        assert_eq!(err.field, "payload.inputName");
+        let err = validate(&store, &request, reviewed("other")).unwrap_err();
+        assert_eq!(err.field, "payload.inputName");
+    }
+
+    #[test]
+    fn test_mistyped_value_is_rejected() {
+        let request = provide_input_request();
+        let store = block_store(vec![request.clone()]);
+        let err =
+            validate(&store, &request, provided("value", Value::string("5".into()))).unwrap_err();
+        assert_eq!(err.field, "payload.updatedValue");
+    }
+
+    #[test]
+    fn test_unexpected_file_is_rejected() {
+        let mut file_request = ProvideFileRequest::new("file");
+        file_request.expected_extensions = vec!["csv".into()];
+        file_request.max_size = Some(4);
+        let request = file_request.to_action_type().to_request("a", "provide_file");
+        let store = block_store(vec![request.clone()]);
+        let file = |filename: &str, content: &str| {
+            ActionItemResponseType::ProvideFile(ProvidedFileResponse {
+                input_name: "file".into(),
+                filename: filename.into(),
+                content: content.into(),
+            })
+        };
+        // "abc" and "abcde", base64 encoded
+        assert_eq!(validate(&store, &request, file("list.CSV", "YWJj")), Ok(()));
+        let err = validate(&store, &request, file("list.txt", "YWJj")).unwrap_err();
+        assert_eq!(err.field, "payload");
+        let err = validate(&store, &request, file("list.csv", "YWJjZGU=")).unwrap_err();
+        assert_eq!(err.field, "payload");
+    }
+
+    #[test]
+    fn test_unknown_option_is_rejected() {
+        let option = InputOption { value: "devnet".into(), displayed_value: "devnet".into() };
+        let request = ActionItemRequestType::PickInputOption(PickInputOptionRequest {
+            options: vec![option.clone()],
+            selected: option,
+        })
+        .to_request("", "env");
+        let store = block_store(vec![request.clone()]);
+        assert_eq!(
+            validate(&store, &request, ActionItemResponseType::PickInputOption("devnet".into())),
+            Ok(())
+        );
+        let err =
+            validate(&store, &request, ActionItemResponseType::PickInputOption("mainnet".into()))
+                .unwrap_err();
+        assert_eq!(err.field, "payload");
+    }
+}
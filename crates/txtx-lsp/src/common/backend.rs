@@ -1,10 +1,11 @@
 use crate::lsp_types::MessageType;
 use crate::state::{build_state, EditorState, WorkspaceState};
-use crate::utils::get_runbook_location;
+use crate::utils::{get_runbook_location, get_runbook_sibling_locations};
 use lsp_types::{
-    CompletionItem, CompletionParams, DocumentSymbol, DocumentSymbolParams, GotoDefinitionParams,
-    Hover, HoverParams, InitializeParams, InitializeResult, Location, SignatureHelp,
-    SignatureHelpParams,
+    CodeActionOrCommand, CodeActionParams, CompletionItem, CompletionParams, DocumentSymbol,
+    DocumentSymbolParams, GotoDefinitionParams, Hover, HoverParams, InitializeParams,
+    InitializeResult, Location, ReferenceParams, RenameParams, SignatureHelp, SignatureHelpParams,
+    SymbolInformation, WorkspaceEdit, WorkspaceSymbolParams,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
@@ -154,24 +155,49 @@ pub async fn process_notification(
                 editor_state.try_write(|es| {
                     es.insert_active_runbook(runbook_location.clone(), contract_source.as_str())
                 })?;
-            }
 
-            // Only build the initial protocal state if it does not exist
-            if editor_state.try_read(|es| es.workspaces.contains_key(&manifest_location))? {
-                return Ok(LspNotificationResponse::default());
+                // the other sources of the runbook are indexed, to resolve definitions and
+                // references across files
+                for sibling_location in get_runbook_sibling_locations(&runbook_location) {
+                    if editor_state
+                        .try_read(|es| es.documents_sources.contains_key(&sibling_location))?
+                    {
+                        continue;
+                    }
+                    let sibling_source = match file_accessor {
+                        None => sibling_location.read_content_as_utf8(),
+                        Some(file_accessor) => {
+                            file_accessor.read_file(sibling_location.to_string()).await
+                        }
+                    };
+                    if let Ok(sibling_source) = sibling_source {
+                        editor_state.try_write(|es| {
+                            es.index_runbook_source(sibling_location, &sibling_source)
+                        })?;
+                    }
+                }
             }
 
-            let mut protocol_state = WorkspaceState::new();
-            match build_state(&manifest_location, &mut protocol_state, file_accessor).await {
-                Ok(_) => {
-                    editor_state
-                        .try_write(|es| es.index_workspace(manifest_location, protocol_state))?;
-                    let (aggregated_diagnostics, notification) =
-                        editor_state.try_read(|es| es.get_aggregated_diagnostics())?;
-                    Ok(LspNotificationResponse { aggregated_diagnostics, notification })
+            // Only build the initial protocal state if it does not exist
+            let response = if editor_state
+                .try_read(|es| es.workspaces.contains_key(&manifest_location))?
+            {
+                LspNotificationResponse::default()
+            } else {
+                let mut protocol_state = WorkspaceState::new();
+                match build_state(&manifest_location, &mut protocol_state, file_accessor).await {
+                    Ok(_) => {
+                        editor_state.try_write(|es| {
+                            es.index_workspace(manifest_location, protocol_state)
+                        })?;
+                        let (aggregated_diagnostics, notification) =
+                            editor_state.try_read(|es| es.get_aggregated_diagnostics())?;
+                        LspNotificationResponse { aggregated_diagnostics, notification }
+                    }
+                    Err(e) => LspNotificationResponse::error(&e),
                 }
-                Err(e) => Ok(LspNotificationResponse::error(&e)),
-            }
+            };
+            add_runbook_diagnostics(editor_state, runbook_location, response)
         }
         LspNotification::RunbookSaved(runbook_location) => {
             let manifest_location = match editor_state
@@ -194,7 +220,8 @@ pub async fn process_notification(
 
                     let (aggregated_diagnostics, notification) =
                         editor_state.try_read(|es| es.get_aggregated_diagnostics())?;
-                    Ok(LspNotificationResponse { aggregated_diagnostics, notification })
+                    let response = LspNotificationResponse { aggregated_diagnostics, notification };
+                    add_runbook_diagnostics(editor_state, runbook_location, response)
                 }
                 Err(e) => Ok(LspNotificationResponse::error(&e)),
             }
@@ -214,13 +241,33 @@ pub async fn process_notification(
     }
 }
 
+/// Adds the validation diagnostics of a runbook source to the diagnostics of a response. They're
+/// added even if there are none, so that the diagnostics previously published are cleared.
+fn add_runbook_diagnostics(
+    editor_state: &EditorStateInput,
+    runbook_location: FileLocation,
+    mut response: LspNotificationResponse,
+) -> Result<LspNotificationResponse, String> {
+    // the runbooks of the workspace are already validated when it's (re)built
+    if response.aggregated_diagnostics.iter().any(|(location, _)| location.eq(&runbook_location)) {
+        return Ok(response);
+    }
+    let diagnostics = editor_state.try_read(|es| es.get_runbook_diagnostics(&runbook_location))?;
+    response.aggregated_diagnostics.push((runbook_location, diagnostics));
+    Ok(response)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub enum LspRequest {
     Completion(CompletionParams),
     SignatureHelp(SignatureHelpParams),
     Definition(GotoDefinitionParams),
+    References(ReferenceParams),
+    Rename(RenameParams),
+    CodeAction(CodeActionParams),
     Hover(HoverParams),
     DocumentSymbol(DocumentSymbolParams),
+    WorkspaceSymbol(WorkspaceSymbolParams),
     Initialize(InitializeParams),
 }
 
@@ -229,7 +276,12 @@ pub enum LspRequestResponse {
     CompletionItems(Vec<CompletionItem>),
     SignatureHelp(Option<SignatureHelp>),
     Definition(Option<Location>),
+    References(Vec<Location>),
+    /// The edit renaming a construct, or the reason why the new name was rejected
+    Rename(Result<Option<WorkspaceEdit>, String>),
+    CodeAction(Vec<CodeActionOrCommand>),
     DocumentSymbol(Vec<DocumentSymbol>),
+    WorkspaceSymbol(Vec<SymbolInformation>),
     Hover(Option<Hover>),
     Initialize(InitializeResult),
 }
@@ -271,6 +323,45 @@ pub fn process_request(
             Ok(LspRequestResponse::Definition(location))
         }
 
+        LspRequest::References(params) => {
+            let file_url = params.text_document_position.text_document.uri;
+            let runbook_location = match get_runbook_location(&file_url) {
+                Some(runbook_location) => runbook_location,
+                None => return Ok(LspRequestResponse::References(vec![])),
+            };
+            let position = params.text_document_position.position;
+            let include_declaration = params.context.include_declaration;
+            let references = editor_state
+                .try_read(|es| es.get_references(&runbook_location, &position, include_declaration))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::References(references))
+        }
+
+        LspRequest::Rename(params) => {
+            let file_url = params.text_document_position.text_document.uri;
+            let runbook_location = match get_runbook_location(&file_url) {
+                Some(runbook_location) => runbook_location,
+                None => return Ok(LspRequestResponse::Rename(Ok(None))),
+            };
+            let position = params.text_document_position.position;
+            let edit = editor_state
+                .try_read(|es| es.get_rename_edit(&runbook_location, &position, &params.new_name))
+                .unwrap_or(Ok(None));
+            Ok(LspRequestResponse::Rename(edit))
+        }
+
+        LspRequest::CodeAction(params) => {
+            let file_url = params.text_document.uri;
+            let runbook_location = match get_runbook_location(&file_url) {
+                Some(runbook_location) => runbook_location,
+                None => return Ok(LspRequestResponse::CodeAction(vec![])),
+            };
+            let actions = editor_state
+                .try_read(|es| es.get_code_actions(&runbook_location, &file_url, &params.range))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::CodeAction(actions))
+        }
+
         LspRequest::SignatureHelp(params) => {
             let file_url = params.text_document_position_params.text_document.uri;
             let runbook_location = match get_runbook_location(&file_url) {
@@ -306,6 +397,13 @@ pub fn process_request(
             Ok(LspRequestResponse::DocumentSymbol(document_symbols))
         }
 
+        LspRequest::WorkspaceSymbol(params) => {
+            let symbols = editor_state
+                .try_read(|es| es.get_workspace_symbols(&params.query))
+                .unwrap_or_default();
+            Ok(LspRequestResponse::WorkspaceSymbol(symbols))
+        }
+
         LspRequest::Hover(params) => {
             let file_url = params.text_document_position_params.text_document.uri;
             let runbook_location = match get_runbook_location(&file_url) {
@@ -1,7 +1,7 @@
 use lsp_types::{
-    CompletionOptions, HoverProviderCapability, ServerCapabilities, SignatureHelpOptions,
-    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
-    TextDocumentSyncSaveOptions,
+    CodeActionProviderCapability, CompletionOptions, HoverProviderCapability, ServerCapabilities,
+    SignatureHelpOptions, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TextDocumentSyncSaveOptions,
 };
 use serde::{Deserialize, Serialize};
 
@@ -23,7 +23,7 @@ impl InitializationOptions {
             completion: true,
             completion_smart_parenthesis_wrap: true,
             completion_include_native_placeholders: true,
-            document_symbols: false,
+            document_symbols: true,
             go_to_definition: true,
             hover: true,
             signature_help: true,
@@ -52,13 +52,27 @@ pub fn get_capabilities(initialization_options: &InitializationOptions) -> Serve
             true => Some(lsp_types::OneOf::Left(true)),
             false => None,
         },
+        workspace_symbol_provider: match initialization_options.document_symbols {
+            true => Some(lsp_types::OneOf::Left(true)),
+            false => None,
+        },
         definition_provider: match initialization_options.go_to_definition {
             true => Some(lsp_types::OneOf::Left(true)),
             false => None,
         },
+        references_provider: match initialization_options.go_to_definition {
+            true => Some(lsp_types::OneOf::Left(true)),
+            false => None,
+        },
+        rename_provider: match initialization_options.go_to_definition {
+            true => Some(lsp_types::OneOf::Left(true)),
+            false => None,
+        },
+        // quick fixes of the diagnostics published on save
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
         signature_help_provider: match initialization_options.signature_help {
             true => Some(SignatureHelpOptions {
-                trigger_characters: Some(vec![" ".to_string()]),
+                trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
                 retrigger_characters: None,
                 work_done_progress_options: Default::default(),
             }),
@@ -1,721 +1,480 @@
-use std::{collections::HashMap, vec};
+use std::collections::BTreeMap;
 
-use lazy_static::lazy_static;
 use lsp_types::{
-    CompletionItem, CompletionItemKind, Documentation, InsertTextFormat, MarkupContent, MarkupKind,
-    Position,
+    CompletionItem, CompletionItemKind, Documentation, InsertTextFormat, InsertTextMode,
+    MarkupContent, MarkupKind, Position,
 };
 use regex::Regex;
+use txtx_addon_kit::types::commands::{CommandInput, PreCommandSpecification};
+use txtx_addon_kit::types::functions::FunctionSpecification;
+use txtx_addon_kit::types::signers::SignerSpecification;
+use txtx_addon_kit::Addon;
 
 lazy_static! {
-    static ref COMPLETION_ITEMS_CLARITY_1: Vec<CompletionItem> =
-        build_default_native_keywords_list(ClarityVersion::Clarity1);
-    static ref COMPLETION_ITEMS_CLARITY_2: Vec<CompletionItem> =
-        build_default_native_keywords_list(ClarityVersion::Clarity2);
-    static ref VAR_FUNCTIONS: Vec<String> =
-        vec![NativeFunctions::SetVar.to_string(), NativeFunctions::FetchVar.to_string(),];
-    static ref MAP_FUNCTIONS: Vec<String> = vec![
-        NativeFunctions::InsertEntry.to_string(),
-        NativeFunctions::FetchEntry.to_string(),
-        NativeFunctions::SetEntry.to_string(),
-        NativeFunctions::DeleteEntry.to_string(),
-    ];
-    static ref FT_FUNCTIONS: Vec<String> = vec![
-        NativeFunctions::GetTokenBalance.to_string(),
-        NativeFunctions::GetTokenSupply.to_string(),
-        NativeFunctions::BurnToken.to_string(),
-        NativeFunctions::MintToken.to_string(),
-        NativeFunctions::TransferToken.to_string(),
-    ];
-    static ref NFT_FUNCTIONS: Vec<String> = vec![
-        NativeFunctions::GetAssetOwner.to_string(),
-        NativeFunctions::BurnAsset.to_string(),
-        NativeFunctions::MintAsset.to_string(),
-        NativeFunctions::TransferAsset.to_string(),
-    ];
-    static ref ITERATOR_FUNCTIONS: Vec<String> = vec![
-        NativeFunctions::Map.to_string(),
-        NativeFunctions::Filter.to_string(),
-        NativeFunctions::Fold.to_string(),
-    ];
-    static ref VALID_MAP_FUNCTIONS_CLARITY_1: Vec<CompletionItem> =
-        build_map_valid_cb_completion_items(ClarityVersion::Clarity1);
-    static ref VALID_MAP_FUNCTIONS_CLARITY_2: Vec<CompletionItem> =
-        build_map_valid_cb_completion_items(ClarityVersion::Clarity2);
-    static ref VALID_FILTER_FUNCTIONS_CLARITY_1: Vec<CompletionItem> =
-        build_filter_valid_cb_completion_items(ClarityVersion::Clarity1);
-    static ref VALID_FILTER_FUNCTIONS_CLARITY_2: Vec<CompletionItem> =
-        build_filter_valid_cb_completion_items(ClarityVersion::Clarity2);
-    static ref VALID_FOLD_FUNCTIONS_CLARITY_1: Vec<CompletionItem> =
-        build_fold_valid_cb_completion_items(ClarityVersion::Clarity1);
-    static ref VALID_FOLD_FUNCTIONS_CLARITY_2: Vec<CompletionItem> =
-        build_fold_valid_cb_completion_items(ClarityVersion::Clarity2);
+    static ref CONSTRUCT_TYPE_POSITION: Regex =
+        Regex::new(r#"^\s*(action|signer)\s+"[^"]*"\s+"[\w:]*$"#).unwrap();
+    static ref CONSTRUCT_HEADER: Regex =
+        Regex::new(r#"^\s*(action|signer)\s+"[^"]*"\s+"([\w:]+)"\s*$"#).unwrap();
+    static ref ATTRIBUTE_NAME_POSITION: Regex = Regex::new(r"^\s*[\w-]*$").unwrap();
+    static ref ATTRIBUTE_KEY: Regex = Regex::new(r"^\s*([A-Za-z_][\w-]*)\s*(=|\{)").unwrap();
 }
 
-#[derive(Clone, Debug, Default)]
-pub struct ContractDefinedData {
-    position: Position,
-    consts: Vec<(String, String)>,
-    locals: Vec<(String, String)>,
-    pub vars: Vec<String>,
-    pub maps: Vec<String>,
-    pub fts: Vec<String>,
-    pub nfts: Vec<String>,
-    pub functions_completion_items: Vec<CompletionItem>,
+/// What the cursor is pointing at, as far as completion is concerned.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompletionContext {
+    /// Outside of any block
+    TopLevel,
+    /// The type of an action, e.g. `action "deploy" "<here>"`
+    ActionType,
+    /// The type of a signer, e.g. `signer "deployer" "<here>"`
+    SignerType,
+    /// The name of an attribute, in the body of an action or a signer
+    Attribute { construct: String, construct_type: String, present: Vec<String> },
+    /// The value of an attribute, or any other expression
+    Expression,
 }
 
-impl<'a> ContractDefinedData {
-    pub fn new(expressions: &[SymbolicExpression], position: &Position) -> Self {
-        let mut defined_data = ContractDefinedData { position: *position, ..Default::default() };
-        traverse(&mut defined_data, expressions);
-        defined_data
-    }
-
-    // this methods is in charge of:
-    // 1. set the function completion item with its arguments
-    // 2. set the local binding names if the position is within this function
-    fn set_function_completion_with_bindings(
-        &mut self,
-        expr: &SymbolicExpression,
-        name: &ClarityName,
-        parameters: &[TypedVar<'a>],
-    ) {
-        let mut completion_args: Vec<String> = vec![];
-        for (i, typed_var) in parameters.iter().enumerate() {
-            if let Ok(signature) =
-                TypeSignature::parse_type_repr(DEFAULT_EPOCH, typed_var.type_expr, &mut ())
-            {
-                completion_args.push(format!("${{{}:{}:{}}}", i + 1, typed_var.name, signature));
+/// Completion items of the addons loaded by the language server, indexed once at startup.
+#[derive(Debug, Clone, Default)]
+pub struct AddonsCompletionIndex {
+    action_types: Vec<CompletionItem>,
+    signer_types: Vec<CompletionItem>,
+    action_blocks: Vec<CompletionItem>,
+    signer_blocks: Vec<CompletionItem>,
+    functions: Vec<CompletionItem>,
+    /// Inputs of the actions, keyed by `namespace::matcher`
+    action_inputs: BTreeMap<String, Vec<CommandInput>>,
+    /// Inputs of the signers, keyed by `namespace::matcher`
+    signer_inputs: BTreeMap<String, Vec<CommandInput>>,
+}
 
-                if is_position_within_span(&self.position, &expr.span, 0) {
-                    self.locals.push((typed_var.name.to_string(), signature.to_string()));
-                }
-            };
-        }
+impl AddonsCompletionIndex {
+    pub fn new(addons: &[Box<dyn Addon>]) -> Self {
+        let mut index = AddonsCompletionIndex::default();
+        for addon in addons.iter() {
+            let namespace = addon.get_namespace();
 
-        let insert_text = match completion_args.len() {
-            0 => Some(name.to_string()),
-            _ => Some(format!("{} {}", name, completion_args.join(" "))),
-        };
+            let mut functions = addon.build_function_lookup().into_values().collect::<Vec<_>>();
+            functions.sort_by(|a, b| a.name.cmp(&b.name));
+            for function in functions.iter() {
+                index.functions.push(function_completion_item(namespace, function));
+            }
 
-        self.functions_completion_items.push(CompletionItem {
-            label: name.to_string(),
-            kind: Some(CompletionItemKind::MODULE),
-            insert_text,
-            insert_text_format: Some(InsertTextFormat::SNIPPET),
-            ..Default::default()
-        });
-    }
+            let mut commands = addon.build_command_lookup().into_values().collect::<Vec<_>>();
+            commands.sort_by_key(command_matcher);
+            for command in commands.iter() {
+                let (matcher, documentation, example) = match command {
+                    PreCommandSpecification::Atomic(spec) => {
+                        (&spec.matcher, &spec.documentation, &spec.example)
+                    }
+                    PreCommandSpecification::Composite(spec) => {
+                        (&spec.matcher, &spec.documentation, &spec.example)
+                    }
+                };
+                let construct_type = format!("{}::{}", namespace, matcher);
+                let inputs = command_inputs(command);
+                index.action_types.push(construct_type_completion_item(
+                    &construct_type,
+                    documentation,
+                    example,
+                ));
+                index.action_blocks.push(block_completion_item(
+                    "action",
+                    &construct_type,
+                    documentation,
+                    example,
+                    &inputs,
+                ));
+                index.action_inputs.insert(construct_type, inputs);
+            }
 
-    pub fn populate_snippet_with_options(
-        &self,
-        version: &ClarityVersion,
-        name: &String,
-        snippet: &str,
-    ) -> Option<String> {
-        if VAR_FUNCTIONS.contains(name) && !self.vars.is_empty() {
-            let choices = self.vars.join(",");
-            return Some(snippet.replace("${1:var}", &format!("${{1|{}|}}", choices)));
-        }
-        if MAP_FUNCTIONS.contains(name) && !self.maps.is_empty() {
-            let choices = self.maps.join(",");
-            return Some(snippet.replace("${1:map-name}", &format!("${{1|{}|}}", choices)));
-        }
-        if FT_FUNCTIONS.contains(name) && !self.fts.is_empty() {
-            let choices = self.fts.join(",");
-            return Some(snippet.replace("${1:token-name}", &format!("${{1|{}|}}", choices)));
-        }
-        if NFT_FUNCTIONS.contains(name) && !self.nfts.is_empty() {
-            let choices = self.nfts.join(",");
-            return Some(snippet.replace("${1:asset-name}", &format!("${{1|{}|}}", choices)));
-        }
-        if ITERATOR_FUNCTIONS.contains(name) && !self.functions_completion_items.is_empty() {
-            let mut choices = self
-                .functions_completion_items
-                .iter()
-                .map(|f| f.label.to_string())
-                .collect::<Vec<String>>()
-                .join(",");
-            choices.push(',');
-            choices.push_str(
-                &get_iterator_cb_completion_item(version, name)
-                    .iter()
-                    .map(|i| i.insert_text.clone().unwrap())
-                    .collect::<Vec<String>>()
-                    .join(","),
-            );
-            return Some(snippet.replace("${1:func}", &format!("${{1|{}|}}", choices)));
+            let mut signers = addon.build_signer_lookup().into_values().collect::<Vec<_>>();
+            signers.sort_by(|a, b| a.matcher.cmp(&b.matcher));
+            for signer in signers.iter() {
+                let construct_type = format!("{}::{}", namespace, signer.matcher);
+                let inputs = signer_inputs(signer);
+                index.signer_types.push(construct_type_completion_item(
+                    &construct_type,
+                    &signer.documentation,
+                    &signer.example,
+                ));
+                index.signer_blocks.push(block_completion_item(
+                    "signer",
+                    &construct_type,
+                    &signer.documentation,
+                    &signer.example,
+                    &inputs,
+                ));
+                index.signer_inputs.insert(construct_type, inputs);
+            }
         }
-        None
+        index
     }
 
-    pub fn get_contract_completion_items(&self) -> Vec<CompletionItem> {
-        [&self.consts[..], &self.locals[..]]
-            .concat()
-            .iter()
-            .map(|(name, definition)| {
-                CompletionItem::new_simple(name.to_string(), definition.to_string())
-            })
-            .collect()
+    pub fn get_completion_items(&self, source: &str, position: &Position) -> Vec<CompletionItem> {
+        match get_completion_context(source, position) {
+            CompletionContext::TopLevel => {
+                [self.action_blocks.clone(), self.signer_blocks.clone()].concat()
+            }
+            CompletionContext::ActionType => self.action_types.clone(),
+            CompletionContext::SignerType => self.signer_types.clone(),
+            CompletionContext::Attribute { construct, construct_type, present } => {
+                let inputs = match construct.as_str() {
+                    "action" => self.action_inputs.get(&construct_type),
+                    _ => self.signer_inputs.get(&construct_type),
+                };
+                inputs
+                    .map(|inputs| {
+                        inputs
+                            .iter()
+                            .filter(|input| !present.contains(&input.name))
+                            .map(input_completion_item)
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+            CompletionContext::Expression => self.functions.clone(),
+        }
     }
 }
 
-impl<'a> ASTVisitor<'a> for ContractDefinedData {
-    fn visit_define_constant(
-        &mut self,
-        _expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        value: &'a SymbolicExpression,
-    ) -> bool {
-        self.consts.push((name.to_string(), value.to_string()));
-        true
-    }
-
-    fn visit_define_data_var(
-        &mut self,
-        _expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _data_type: &'a SymbolicExpression,
-        _initial: &'a SymbolicExpression,
-    ) -> bool {
-        self.vars.push(name.to_string());
-        true
-    }
-
-    fn visit_define_map(
-        &mut self,
-        _expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _key_type: &'a SymbolicExpression,
-        _value_type: &'a SymbolicExpression,
-    ) -> bool {
-        self.maps.push(name.to_string());
-        true
-    }
-
-    fn visit_define_ft(
-        &mut self,
-        _expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _supply: Option<&'a SymbolicExpression>,
-    ) -> bool {
-        self.fts.push(name.to_string());
-        true
+fn command_matcher(command: &PreCommandSpecification) -> String {
+    match command {
+        PreCommandSpecification::Atomic(spec) => spec.matcher.clone(),
+        PreCommandSpecification::Composite(spec) => spec.matcher.clone(),
     }
+}
 
-    fn visit_define_nft(
-        &mut self,
-        _expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _nft_type: &'a SymbolicExpression,
-    ) -> bool {
-        self.nfts.push(name.to_string());
-        true
+/// Returns the inputs that can be set in the body of an action, internal inputs excluded. The
+/// inputs of a composite action are the ones of its parts.
+pub(super) fn command_inputs(command: &PreCommandSpecification) -> Vec<CommandInput> {
+    let mut inputs: Vec<CommandInput> = vec![];
+    let mut collect = |candidates: &Vec<CommandInput>| {
+        for input in candidates.iter() {
+            if !input.internal && !inputs.iter().any(|i| i.name.eq(&input.name)) {
+                inputs.push(input.clone());
+            }
+        }
+    };
+    match command {
+        PreCommandSpecification::Atomic(spec) => {
+            collect(&spec.inputs);
+            collect(&spec.default_inputs);
+        }
+        PreCommandSpecification::Composite(spec) => {
+            for part in spec.parts.iter() {
+                collect(&command_inputs(part));
+            }
+            collect(&spec.default_inputs);
+        }
     }
+    inputs
+}
 
-    fn visit_define_public(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        _body: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_function_completion_with_bindings(expr, name, &parameters.unwrap_or_default());
-        true
-    }
+pub(super) fn signer_inputs(signer: &SignerSpecification) -> Vec<CommandInput> {
+    signer
+        .inputs
+        .iter()
+        .chain(signer.default_inputs.iter())
+        .filter(|input| !input.internal)
+        .cloned()
+        .collect()
+}
 
-    fn visit_define_read_only(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        _body: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_function_completion_with_bindings(expr, name, &parameters.unwrap_or_default());
-        true
-    }
+fn markdown(value: String) -> Option<Documentation> {
+    Some(Documentation::MarkupContent(MarkupContent { kind: MarkupKind::Markdown, value }))
+}
 
-    fn visit_define_private(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        _body: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_function_completion_with_bindings(expr, name, &parameters.unwrap_or_default());
-        true
+fn construct_type_completion_item(
+    construct_type: &str,
+    documentation: &str,
+    example: &str,
+) -> CompletionItem {
+    CompletionItem {
+        label: construct_type.to_string(),
+        kind: Some(CompletionItemKind::CLASS),
+        documentation: markdown(format!(
+            "{}\n\n## Example\n```hcl\n{}\n```",
+            documentation, example
+        )),
+        insert_text: Some(construct_type.to_string()),
+        insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
+        ..Default::default()
     }
+}
 
-    fn visit_let(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
-        _body: &'a [SymbolicExpression],
-    ) -> bool {
-        if is_position_within_span(&self.position, &expr.span, 0) {
-            for (name, value) in bindings {
-                self.locals.push((name.to_string(), value.to_string()));
-            }
-        }
-        true
+fn block_completion_item(
+    construct: &str,
+    construct_type: &str,
+    documentation: &str,
+    example: &str,
+    inputs: &[CommandInput],
+) -> CompletionItem {
+    // only the required inputs are inserted, the optional ones are completed in the block
+    let required_inputs = inputs.iter().filter(|input| !input.optional).collect::<Vec<_>>();
+    let body = required_inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| format!("    {} = ${{{}:{}}}", input.name, i + 2, input.name))
+        .collect::<Vec<_>>()
+        .join("\n");
+    CompletionItem {
+        label: construct_type.to_string(),
+        kind: Some(CompletionItemKind::CLASS),
+        detail: Some(format!("{} <name> \"{}\"", construct, construct_type)),
+        documentation: markdown(format!(
+            "{}\n\n## Inputs\n{}\n\n## Example\n```hcl\n{}\n```",
+            documentation,
+            inputs
+                .iter()
+                .map(|i| format!("`{}`: {}", i.name, i.documentation))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            example
+        )),
+        insert_text: Some(format!(
+            "{} \"${{1:name}}\" \"{}\" {{\n{}\n}}",
+            construct, construct_type, body
+        )),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        insert_text_mode: Some(InsertTextMode::ADJUST_INDENTATION),
+        ..Default::default()
     }
 }
 
-fn build_contract_calls_args(signature: &FunctionType) -> (Vec<String>, Vec<String>) {
-    let mut snippet_args = vec![];
-    let mut doc_args = vec![];
-    if let FunctionType::Fixed(function) = signature {
-        for (i, arg) in function.args.iter().enumerate() {
-            snippet_args.push(format!("${{{}:{}:{}}}", i + 1, arg.name, arg.signature));
-            doc_args.push(format!("{} `{}`", arg.name, arg.signature));
-        }
+fn input_completion_item(input: &CommandInput) -> CompletionItem {
+    let requirement = if input.optional { "optional" } else { "required" };
+    CompletionItem {
+        label: input.name.clone(),
+        kind: Some(CompletionItemKind::PROPERTY),
+        detail: Some(format!("{} ({})", input.typing.to_string(), requirement)),
+        documentation: markdown(input.documentation.clone()),
+        insert_text: Some(format!("{} = $0", input.name)),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        // required inputs are listed first
+        sort_text: Some(format!("{}{}", if input.optional { 1 } else { 0 }, input.name)),
+        ..Default::default()
     }
-    (snippet_args, doc_args)
 }
 
-pub fn get_contract_calls(analysis: &ContractAnalysis) -> Vec<CompletionItem> {
-    let mut inter_contract = vec![];
-    for (name, signature) in
-        analysis.public_function_types.iter().chain(analysis.read_only_function_types.iter())
-    {
-        let (snippet_args, doc_args) = build_contract_calls_args(signature);
-        let label = format!("contract-call? .{} {}", analysis.contract_identifier.name, name,);
-        let documentation = MarkupContent {
-            kind: MarkupKind::Markdown,
-            value: [vec![format!("**{}**", name.to_string())], doc_args].concat().join("\n\n"),
-        };
-        let insert_text = format!(
-            "contract-call? .{} {} {}",
-            analysis.contract_identifier.name,
-            name,
-            snippet_args.join(" "),
-        );
-
-        inter_contract.push(CompletionItem {
-            label,
-            detail: Some(analysis.contract_identifier.name.to_string()),
-            documentation: Some(Documentation::MarkupContent(documentation)),
-            kind: Some(CompletionItemKind::EVENT),
-            insert_text: Some(insert_text),
-            insert_text_format: Some(InsertTextFormat::SNIPPET),
-            ..Default::default()
-        });
+fn function_completion_item(namespace: &str, function: &FunctionSpecification) -> CompletionItem {
+    let signature = function.inputs.iter().map(|i| i.name.clone()).collect::<Vec<_>>().join(", ");
+    let placeholders = function
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| format!("${{{}:{}}}", i + 1, input.name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    CompletionItem {
+        label: format!("{}::{}", namespace, function.name),
+        kind: Some(CompletionItemKind::FUNCTION),
+        detail: Some(format!(
+            "{}::{}({}) -> {}",
+            namespace,
+            function.name,
+            signature,
+            function.output.typing.to_string()
+        )),
+        documentation: markdown(format!(
+            "{}\n\n## Arguments\n{}\n\n## Example\n```hcl\n{}\n```",
+            function.documentation,
+            function
+                .inputs
+                .iter()
+                .map(|i| format!("`{}`: {}", i.name, i.documentation))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            function.example
+        )),
+        insert_text: Some(format!("{}::{}({})", namespace, function.name, placeholders)),
+        insert_text_format: Some(InsertTextFormat::SNIPPET),
+        ..Default::default()
     }
-    inter_contract
 }
 
-pub fn build_completion_item_list(
-    expressions: &Vec<SymbolicExpression>,
-    position: &Position,
-    active_contract_defined_data: &ContractDefinedData,
-    contract_calls: Vec<CompletionItem>,
-    should_wrap: bool,
-    include_native_placeholders: bool,
-) -> Vec<CompletionItem> {
-    if let Some((function_name, param)) = get_function_at_position(position, expressions) {
-        // - for var-*, map-*, ft-* or nft-* methods, return the corresponding data names
-        let mut completion_strings: Option<Vec<String>> = None;
-        if VAR_FUNCTIONS.contains(&function_name.to_string()) && param == Some(0) {
-            completion_strings = Some(active_contract_defined_data.vars.clone());
-        } else if MAP_FUNCTIONS.contains(&function_name.to_string()) && param == Some(0) {
-            completion_strings = Some(active_contract_defined_data.maps.clone());
-        } else if FT_FUNCTIONS.contains(&function_name.to_string()) && param == Some(0) {
-            completion_strings = Some(active_contract_defined_data.fts.clone());
-        } else if NFT_FUNCTIONS.contains(&function_name.to_string()) && param == Some(0) {
-            completion_strings = Some(active_contract_defined_data.nfts.clone());
-        }
-
-        if let Some(completion_strings) = completion_strings {
-            return completion_strings
-                .iter()
-                .map(|s| CompletionItem::new_simple(String::from(s), String::from("")))
-                .collect();
-        }
+/// A block opened before the cursor and not closed yet.
+struct OpenBlock {
+    /// Content of the line opening the block, up to the brace
+    header: String,
+    /// Offset of the first character of the body
+    body_start: usize,
+}
 
-        // - for iterator methods (filter, fold, map) return the list of available and valid functions
-        if ITERATOR_FUNCTIONS.contains(&function_name.to_string()) && param == Some(0) {
-            let mut completion_items: Vec<CompletionItem> = vec![];
-            completion_items.append(
-                &mut active_contract_defined_data
-                    .functions_completion_items
-                    .iter()
-                    .map(|f| CompletionItem::new_simple(f.label.clone(), String::from("")))
-                    .collect::<Vec<CompletionItem>>(),
-            );
-            completion_items.append(&mut get_iterator_cb_completion_item(
-                clarity_version,
-                &function_name.to_string(),
-            ));
-            return completion_items;
+/// Returns the offset of a position in a source. Characters are counted as chars.
+pub(super) fn position_to_offset(source: &str, position: &Position) -> usize {
+    let mut offset = 0;
+    for (i, line) in source.split_inclusive('\n').enumerate() {
+        if i == position.line as usize {
+            let line = line.trim_end_matches('\n');
+            return offset
+                + line
+                    .char_indices()
+                    .nth(position.character as usize)
+                    .map(|(index, _)| index)
+                    .unwrap_or(line.len());
         }
+        offset += line.len();
     }
+    source.len()
+}
 
-    let native_keywords = match clarity_version {
-        ClarityVersion::Clarity1 => COMPLETION_ITEMS_CLARITY_1.to_vec(),
-        ClarityVersion::Clarity2 => COMPLETION_ITEMS_CLARITY_2.to_vec(),
-    };
-    let placeholder_pattern = Regex::new(r" \$\{\d+:[\w-]+\}").unwrap();
-
-    let mut completion_items = vec![];
-    completion_items.append(&mut active_contract_defined_data.get_contract_completion_items());
-    for mut item in [
-        native_keywords,
-        contract_calls,
-        active_contract_defined_data.functions_completion_items.clone(),
-    ]
-    .concat()
-    .drain(..)
-    {
-        match item.kind {
-            Some(
-                CompletionItemKind::EVENT
-                | CompletionItemKind::FUNCTION
-                | CompletionItemKind::MODULE
-                | CompletionItemKind::CLASS,
-            ) => {
-                let mut snippet = item.insert_text.take().unwrap();
-                let mut snippet_has_choices = false;
-                if item.kind == Some(CompletionItemKind::FUNCTION) {
-                    if let Some(populated_snippet) = active_contract_defined_data
-                        .populate_snippet_with_options(clarity_version, &item.label, &snippet)
-                    {
-                        snippet_has_choices = true;
-                        snippet = populated_snippet;
-                    }
-                }
-
-                if !include_native_placeholders {
-                    if snippet_has_choices {
-                        // for var-*, map-*, ft-* and nft-* methods
-                        // the variable name is kept but the other placeholders are removed
-                        let updated_snippet =
-                            placeholder_pattern.replace_all(&snippet, "").to_string();
-                        if updated_snippet.ne(&snippet) {
-                            snippet = updated_snippet;
-                            snippet.push_str(" $0");
-                        }
-                    } else if item.kind == Some(CompletionItemKind::FUNCTION)
-                        || item.kind == Some(CompletionItemKind::CLASS)
-                    {
-                        match item.label.as_str() {
-                            "+ (add)"
-                            | "- (subtract)"
-                            | "/ (divide)"
-                            | "* (multiply)"
-                            | "< (less than)"
-                            | "<= (less than or equal)"
-                            | "> (greater than)"
-                            | ">= (greater than or equal)" => {
-                                snippet = item.label.split_whitespace().next().unwrap().to_string()
-                            }
-                            _ => snippet.clone_from(&item.label),
-                        }
-                        snippet.push_str(" $0");
-                    }
-                }
-
-                item.insert_text =
-                    if should_wrap { Some(format!("({})", snippet)) } else { Some(snippet) };
+/// Lists the blocks enclosing the end of a text, outermost first. Braces in strings and comments
+/// are ignored, sources being edited are not required to be valid.
+fn open_blocks(text: &str) -> Vec<OpenBlock> {
+    let mut blocks = vec![];
+    let mut line_start = 0;
+    let mut in_string = false;
+    let mut in_comment = false;
+    let mut previous = '\0';
+    for (i, c) in text.char_indices() {
+        match c {
+            '\n' => {
+                line_start = i + 1;
+                in_comment = false;
             }
-            Some(CompletionItemKind::TYPE_PARAMETER) => {
-                if should_wrap {
-                    if let "tuple" | "buff" | "string-ascii" | "string-utf8" | "optional" | "list"
-                    | "response" = item.label.as_str()
-                    {
-                        item.insert_text = Some(format!("({} $0)", item.label));
-                        item.insert_text_format = Some(InsertTextFormat::SNIPPET);
-                    }
-                }
+            _ if in_comment => {}
+            '"' if previous != '\\' => in_string = !in_string,
+            _ if in_string => {}
+            '#' => in_comment = true,
+            '/' if previous == '/' => in_comment = true,
+            '{' => blocks
+                .push(OpenBlock { header: text[line_start..i].to_string(), body_start: i + 1 }),
+            '}' => {
+                blocks.pop();
             }
             _ => {}
         }
-
-        completion_items.push(item);
+        previous = c;
     }
-    completion_items
+    blocks
 }
 
-pub fn check_if_should_wrap(source: &str, position: &Position) -> bool {
-    if let Some(line) = source.lines().nth(position.line as usize) {
-        if position.character as usize > line.len() {
-            return false;
-        }
-
-        let mut chars = line[..position.character as usize].chars();
-        while let Some(char) = chars.next_back() {
-            match char {
-                '(' => return false,
-                char if char.is_whitespace() => return true,
-                _ => {}
+/// Returns the code of a line, without its strings and comment.
+fn strip_strings_and_comment(line: &str) -> String {
+    let mut code = String::new();
+    let mut in_string = false;
+    let mut previous = '\0';
+    for c in line.chars() {
+        match c {
+            '"' if previous != '\\' => in_string = !in_string,
+            _ if in_string => {}
+            '#' => break,
+            '/' if previous == '/' => {
+                code.pop();
+                break;
             }
+            _ => code.push(c),
         }
+        previous = c;
     }
-    true
+    code
 }
 
-pub fn build_default_native_keywords_list(version: ClarityVersion) -> Vec<CompletionItem> {
-    let clarity2_aliased_functions: Vec<NativeFunctions> =
-        vec![NativeFunctions::ElementAt, NativeFunctions::IndexOf, NativeFunctions::BitwiseXor];
-
-    let command = lsp_types::Command {
-        title: "triggerParameterHints".into(),
-        command: "editor.action.triggerParameterHints".into(),
-        arguments: None,
-    };
-
-    let native_functions: Vec<CompletionItem> = NativeFunctions::ALL
-        .iter()
-        .filter_map(|func| {
-            let mut api = make_api_reference(func);
-            if api.version > version {
-                return None;
-            }
-            if clarity2_aliased_functions.contains(func) {
-                if version >= ClarityVersion::Clarity2 {
-                    return None;
-                } else if api.version == ClarityVersion::Clarity1 {
-                    // only for element-at? and index-of?
-                    api.snippet = api.snippet.replace('?', "");
-                }
-            }
-
-            Some(CompletionItem {
-                label: api.name.to_string(),
-                kind: Some(CompletionItemKind::FUNCTION),
-                detail: Some(api.name),
-                documentation: Some(Documentation::MarkupContent(MarkupContent {
-                    kind: MarkupKind::Markdown,
-                    value: api.description,
-                })),
-                insert_text: Some(api.snippet.clone()),
-                insert_text_format: Some(InsertTextFormat::SNIPPET),
-                command: Some(command.clone()),
-                ..Default::default()
-            })
-        })
-        .collect();
-
-    let define_functions: Vec<CompletionItem> = DefineFunctions::ALL
-        .iter()
-        .filter_map(|func| {
-            let api = make_define_reference(func);
-            if api.version > version {
-                return None;
-            }
-            Some(CompletionItem {
-                label: api.name.to_string(),
-                kind: Some(CompletionItemKind::CLASS),
-                detail: Some(api.name),
-                documentation: Some(Documentation::MarkupContent(MarkupContent {
-                    kind: MarkupKind::Markdown,
-                    value: api.description,
-                })),
-                insert_text: Some(api.snippet.clone()),
-                insert_text_format: Some(InsertTextFormat::SNIPPET),
-                command: Some(command.clone()),
-                ..Default::default()
-            })
-        })
-        .collect();
-
-    let native_variables: Vec<CompletionItem> = NativeVariables::ALL
-        .iter()
-        .filter_map(|var| {
-            if let Some(api) = make_keyword_reference(var) {
-                if api.version > version {
-                    return None;
-                }
-                Some(CompletionItem {
-                    label: api.name.to_string(),
-                    kind: Some(CompletionItemKind::FIELD),
-                    detail: Some(api.name.to_string()),
-                    documentation: Some(Documentation::MarkupContent(MarkupContent {
-                        kind: MarkupKind::Markdown,
-                        value: api.description.to_string(),
-                    })),
-                    insert_text: Some(api.snippet.to_string()),
-                    insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
-                    ..Default::default()
-                })
-            } else {
-                None
+/// Lists the attributes and nested blocks set at the top level of a body.
+fn body_attributes(body: &str) -> Vec<String> {
+    let mut attributes = vec![];
+    let mut depth = 0;
+    for line in body.lines() {
+        if depth == 0 {
+            if let Some(captures) = ATTRIBUTE_KEY.captures(line) {
+                attributes.push(captures[1].to_string());
             }
-        })
-        .collect();
-
-    let block_properties: Vec<CompletionItem> = BlockInfoProperty::ALL
-        .iter()
-        .filter_map(|var| {
-            if var.get_version() > version {
-                return None;
+        }
+        for c in strip_strings_and_comment(line).chars() {
+            match c {
+                '{' | '[' | '(' => depth += 1,
+                '}' | ']' | ')' => depth -= 1,
+                _ => {}
             }
-            Some(CompletionItem {
-                label: var.to_string(),
-                kind: Some(CompletionItemKind::FIELD),
-                insert_text: Some(var.to_string()),
-                insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
-                ..Default::default()
-            })
-        })
-        .collect();
+        }
+        if depth < 0 {
+            break;
+        }
+    }
+    attributes
+}
 
-    let types = [
-        "uint",
-        "int",
-        "bool",
-        "list",
-        "tuple",
-        "buff",
-        "string-ascii",
-        "string-utf8",
-        "optional",
-        "response",
-        "principal",
-    ]
-    .iter()
-    .map(|t| CompletionItem {
-        label: t.to_string(),
-        kind: Some(CompletionItemKind::TYPE_PARAMETER),
-        insert_text: Some(t.to_string()),
-        insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
-        ..Default::default()
-    })
-    .collect();
+pub fn get_completion_context(source: &str, position: &Position) -> CompletionContext {
+    let offset = position_to_offset(source, position);
+    let text_before = &source[..offset];
+    let line_prefix = &text_before[text_before.rfind('\n').map(|i| i + 1).unwrap_or(0)..];
 
-    vec![native_functions, define_functions, native_variables, block_properties, types]
-        .into_iter()
-        .flatten()
-        .collect::<Vec<CompletionItem>>()
-}
+    if let Some(captures) = CONSTRUCT_TYPE_POSITION.captures(line_prefix) {
+        return match &captures[1] {
+            "action" => CompletionContext::ActionType,
+            _ => CompletionContext::SignerType,
+        };
+    }
 
-pub fn build_map_valid_cb_completion_items(version: ClarityVersion) -> Vec<CompletionItem> {
-    [
-        NativeFunctions::Add,
-        NativeFunctions::Subtract,
-        NativeFunctions::Multiply,
-        NativeFunctions::Divide,
-        NativeFunctions::CmpGeq,
-        NativeFunctions::CmpLeq,
-        NativeFunctions::CmpLess,
-        NativeFunctions::CmpGreater,
-        NativeFunctions::ToInt,
-        NativeFunctions::ToUInt,
-        NativeFunctions::Modulo,
-        NativeFunctions::Power,
-        NativeFunctions::Sqrti,
-        NativeFunctions::Log2,
-        NativeFunctions::BitwiseXor,
-        NativeFunctions::And,
-        NativeFunctions::Or,
-        NativeFunctions::Not,
-        NativeFunctions::BuffToIntLe,
-        NativeFunctions::BuffToUIntLe,
-        NativeFunctions::BuffToIntBe,
-        NativeFunctions::BuffToUIntBe,
-        NativeFunctions::IsStandard,
-        NativeFunctions::PrincipalDestruct,
-        NativeFunctions::PrincipalConstruct,
-        NativeFunctions::StringToInt,
-        NativeFunctions::StringToUInt,
-        NativeFunctions::IntToAscii,
-        NativeFunctions::IntToUtf8,
-        NativeFunctions::Hash160,
-        NativeFunctions::Sha256,
-        NativeFunctions::Sha512,
-        NativeFunctions::Sha512Trunc256,
-        NativeFunctions::Keccak256,
-        NativeFunctions::BitwiseAnd,
-        NativeFunctions::BitwiseOr,
-        NativeFunctions::BitwiseNot,
-        NativeFunctions::BitwiseLShift,
-        NativeFunctions::BitwiseRShift,
-        NativeFunctions::BitwiseXor2,
-    ]
-    .iter()
-    .filter_map(|func| build_iterator_cb_completion_item(func, version))
-    .collect()
+    let blocks = open_blocks(text_before);
+    let Some(block) = blocks.last() else {
+        return CompletionContext::TopLevel;
+    };
+    if !ATTRIBUTE_NAME_POSITION.is_match(line_prefix) {
+        return CompletionContext::Expression;
+    }
+    let Some(captures) = CONSTRUCT_HEADER.captures(&block.header) else {
+        return CompletionContext::Expression;
+    };
+    let current_line_start = offset - line_prefix.len();
+    let body = format!(
+        "{}{}",
+        &source[block.body_start..current_line_start],
+        source[offset..].split_once('\n').map(|(_, rest)| rest).unwrap_or_default()
+    );
+    CompletionContext::Attribute {
+        construct: captures[1].to_string(),
+        construct_type: captures[2].to_string(),
+        present: body_attributes(&body),
+    }
 }
 
-pub fn build_filter_valid_cb_completion_items(version: ClarityVersion) -> Vec<CompletionItem> {
-    [NativeFunctions::And, NativeFunctions::Or, NativeFunctions::Not]
-        .iter()
-        .filter_map(|func| build_iterator_cb_completion_item(func, version))
-        .collect()
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-pub fn build_fold_valid_cb_completion_items(version: ClarityVersion) -> Vec<CompletionItem> {
-    [
-        NativeFunctions::Add,
-        NativeFunctions::Subtract,
-        NativeFunctions::Multiply,
-        NativeFunctions::Divide,
-        NativeFunctions::CmpGeq,
-        NativeFunctions::CmpLeq,
-        NativeFunctions::CmpLess,
-        NativeFunctions::CmpGreater,
-        NativeFunctions::ToInt,
-        NativeFunctions::ToUInt,
-        NativeFunctions::Modulo,
-        NativeFunctions::Power,
-        NativeFunctions::Sqrti,
-        NativeFunctions::Log2,
-        NativeFunctions::BitwiseXor,
-        NativeFunctions::And,
-        NativeFunctions::Or,
-        NativeFunctions::Not,
-        NativeFunctions::IsStandard,
-        NativeFunctions::BitwiseAnd,
-        NativeFunctions::BitwiseOr,
-        NativeFunctions::BitwiseNot,
-        NativeFunctions::BitwiseLShift,
-        NativeFunctions::BitwiseRShift,
-        NativeFunctions::BitwiseXor2,
-    ]
-    .iter()
-    .filter_map(|func| build_iterator_cb_completion_item(func, version))
-    .collect()
+    const SOURCE: &str = r#"signer "deployer" "evm::secret_key" {
+    secret_key = input.secret_key
 }
 
-fn build_iterator_cb_completion_item(
-    func: &NativeFunctions,
-    version: ClarityVersion,
-) -> Option<CompletionItem> {
-    let api = make_api_reference(func);
-    if api.version > version {
-        return None;
+action "ping" "std::send_http_request" {
+    url = "https://example.com/{id}" # a comment with a {
+    headers = {
+        accept = "application/json"
     }
 
-    let insert_text = Some(api.snippet.split_whitespace().next().unwrap().to_string());
-
-    Some(CompletionItem {
-        label: api.name.clone(),
-        kind: Some(CompletionItemKind::FUNCTION),
-        detail: Some(api.name.clone()),
-        documentation: Some(Documentation::MarkupContent(MarkupContent {
-            kind: MarkupKind::Markdown,
-            value: api.description,
-        })),
-        insert_text,
-        insert_text_format: Some(InsertTextFormat::PLAIN_TEXT),
-        ..Default::default()
-    })
+    method =
 }
+"#;
 
-fn get_iterator_cb_completion_item(version: &ClarityVersion, func: &str) -> Vec<CompletionItem> {
-    if func.to_string().eq(&NativeFunctions::Map.to_string()) {
-        return match version {
-            ClarityVersion::Clarity1 => VALID_MAP_FUNCTIONS_CLARITY_1.to_vec(),
-            ClarityVersion::Clarity2 => VALID_MAP_FUNCTIONS_CLARITY_1.to_vec(),
-        };
+    fn context_at(line: u32, character: u32) -> CompletionContext {
+        get_completion_context(SOURCE, &Position { line, character })
     }
-    if func.to_string().eq(&NativeFunctions::Filter.to_string()) {
-        return match version {
-            ClarityVersion::Clarity1 => VALID_FILTER_FUNCTIONS_CLARITY_1.to_vec(),
-            ClarityVersion::Clarity2 => VALID_FILTER_FUNCTIONS_CLARITY_1.to_vec(),
-        };
-    }
-    match version {
-        ClarityVersion::Clarity1 => VALID_FOLD_FUNCTIONS_CLARITY_1.to_vec(),
-        ClarityVersion::Clarity2 => VALID_FOLD_FUNCTIONS_CLARITY_1.to_vec(),
+
+    #[test]
+    fn test_completion_context() {
+        assert_eq!(context_at(3, 0), CompletionContext::TopLevel);
+        assert_eq!(context_at(4, 15), CompletionContext::ActionType);
+        assert_eq!(context_at(4, 20), CompletionContext::ActionType);
+        assert_eq!(context_at(0, 19), CompletionContext::SignerType);
+        assert_eq!(context_at(10, 13), CompletionContext::Expression);
+        assert_eq!(context_at(7, 8), CompletionContext::Expression);
+        assert_eq!(
+            context_at(9, 4),
+            CompletionContext::Attribute {
+                construct: "action".into(),
+                construct_type: "std::send_http_request".into(),
+                present: vec!["url".into(), "headers".into(), "method".into()],
+            }
+        );
+        assert_eq!(
+            context_at(1, 4),
+            CompletionContext::Attribute {
+                construct: "signer".into(),
+                construct_type: "evm::secret_key".into(),
+                present: vec![],
+            }
+        );
     }
 }
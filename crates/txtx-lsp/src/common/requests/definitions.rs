@@ -1,578 +1,234 @@
-use std::collections::HashMap;
-
-use super::helpers::span_to_range;
-
-use clarity_repl::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
-use clarity_repl::clarity::functions::define::DefineFunctions;
-use clarity_repl::clarity::vm::types::{QualifiedContractIdentifier, StandardPrincipalData};
-use clarity_repl::clarity::{ClarityName, SymbolicExpression};
-use lsp_types::Range;
-
-#[cfg(feature = "wasm")]
-#[allow(unused_imports)]
-use crate::utils::log;
-
-#[derive(Clone, Debug, PartialEq)]
-pub enum DefinitionLocation {
-    Internal(Range),
-    External(QualifiedContractIdentifier, ClarityName),
+use lsp_types::{Position, Range, TextEdit};
+use txtx_addon_kit::hcl::parser::parse_body;
+use txtx_core::runbook::collector::{RunbookCollector, RunbookItem};
+use txtx_core::runbook::location::SourceLocation;
+
+/// A construct defined or referenced in a runbook source, e.g. `action.fund_wallet`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstructSymbol {
+    /// `variable`, `action` or `signer`
+    pub construct: String,
+    pub name: String,
+    pub range: Range,
 }
 
-// `global` holds all of the top-level user-defined keywords that are available in the global scope
-// `local` holds the locally user-defined keywords: function parameters, let and match bindings
-// when a user-defined keyword is used in the code, its position and definition location are stored in `tokens`
-#[derive(Clone, Debug, Default)]
-pub struct Definitions {
-    pub tokens: HashMap<(u32, u32), DefinitionLocation>,
-    global: HashMap<ClarityName, Range>,
-    local: HashMap<u64, HashMap<ClarityName, Range>>,
-    deployer: Option<StandardPrincipalData>,
+impl ConstructSymbol {
+    fn is_same_construct(&self, other: &ConstructSymbol) -> bool {
+        self.construct.eq(&other.construct) && self.name.eq(&other.name)
+    }
 }
 
-impl<'a> Definitions {
-    pub fn new(deployer: Option<StandardPrincipalData>) -> Self {
-        Self { deployer, ..Default::default() }
-    }
+/// Constructs defined and referenced in a runbook source, indexed with the runbook collector of
+/// txtx-core.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DocumentSymbolTable {
+    pub definitions: Vec<ConstructSymbol>,
+    pub references: Vec<ConstructSymbol>,
+}
 
-    pub fn run(&mut self, expressions: &'a [SymbolicExpression]) {
-        traverse(self, expressions);
-    }
+/// Returns the range of a text starting at a location of the collector, which lines and columns
+/// are 1-based.
+fn range_from(location: &SourceLocation, length: usize) -> Range {
+    let start = Position {
+        line: location.line.saturating_sub(1) as u32,
+        character: location.column.saturating_sub(1) as u32,
+    };
+    Range { start, end: Position { line: start.line, character: start.character + length as u32 } }
+}
 
-    fn set_function_parameters_scope(&mut self, expr: &SymbolicExpression) -> Option<()> {
-        let mut local_scope = HashMap::new();
-        let (_, binding_exprs) = expr.match_list()?.get(1)?.match_list()?.split_first()?;
-        for binding in binding_exprs {
-            if let Some(name) = binding
-                .match_list()
-                .and_then(|l| l.split_first())
-                .and_then(|(name, _)| name.match_atom())
-            {
-                local_scope.insert(name.to_owned(), span_to_range(&binding.span));
+/// Returns the range of the name label of a block, falling back on the start of the block.
+fn definition_range(source: &str, location: &SourceLocation, name: &str) -> Range {
+    let line = source.lines().nth(location.line.saturating_sub(1)).unwrap_or_default();
+    let label = format!("\"{}\"", name);
+    match line.find(&label) {
+        Some(index) => {
+            let character = line[..index].chars().count() as u32;
+            let start = Position { line: location.line.saturating_sub(1) as u32, character };
+            Range {
+                start,
+                end: Position { line: start.line, character: character + label.len() as u32 },
             }
         }
-        self.local.insert(expr.id, local_scope);
-        Some(())
+        None => range_from(location, 0),
     }
+}
 
-    // helper method to retrieve definitions of global keyword used in methods such as
-    // (var-get <global-keyword>) (map-insert <global-keyword> ...) (nft-burn <global-keyword> ...)
-    fn set_definition_for_arg_at_index(
-        &mut self,
-        expr: &SymbolicExpression,
-        token: &ClarityName,
-        index: usize,
-    ) -> Option<()> {
-        let range = self.global.get(token)?;
-        let keyword = expr.match_list()?.get(index)?;
-        self.tokens.insert(
-            (keyword.span.start_line, keyword.span.start_column),
-            DefinitionLocation::Internal(*range),
-        );
-        Some(())
-    }
+fn contains(range: &Range, position: &Position) -> bool {
+    range.start <= *position && *position <= range.end
 }
 
-impl<'a> ASTVisitor<'a> for Definitions {
-    fn traverse_expr(&mut self, expr: &'a SymbolicExpression) -> bool {
-        use clarity_repl::clarity::vm::representations::SymbolicExpressionType::*;
-        match &expr.expr {
-            AtomValue(value) => self.visit_atom_value(expr, value),
-            Atom(name) => self.visit_atom(expr, name),
-            List(exprs) => {
-                let result = self.traverse_list(expr, exprs);
-                // clear local scope after traversing it
-                self.local.remove(&expr.id);
-                result
-            }
-            LiteralValue(value) => self.visit_literal_value(expr, value),
-            Field(field) => self.visit_field(expr, field),
-            TraitReference(name, trait_def) => self.visit_trait_reference(expr, name, trait_def),
-        }
+/// Returns the part of a single-line range starting `offset` characters after its start.
+fn sub_range(range: &Range, offset: usize, length: usize) -> Range {
+    let character = range.start.character + offset as u32;
+    Range {
+        start: Position { line: range.start.line, character },
+        end: Position { line: range.start.line, character: character + length as u32 },
     }
+}
 
-    fn visit_atom(&mut self, expr: &'a SymbolicExpression, atom: &'a ClarityName) -> bool {
-        // iterate on local scopes to find if the variable is declared in one of them
-        // the order does not matter because variable shadowing is not allowed
-        for scope in self.local.values() {
-            if let Some(range) = scope.get(atom) {
-                self.tokens.insert(
-                    (expr.span.start_line, expr.span.start_column),
-                    DefinitionLocation::Internal(*range),
-                );
-                return true;
-            }
-        }
+/// Returns whether a name can be used as the name of a construct, i.e. is an HCL identifier so
+/// that the construct can be referenced.
+pub fn is_valid_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
 
-        if let Some(range) = self.global.get(atom) {
-            self.tokens.insert(
-                (expr.span.start_line, expr.span.start_column),
-                DefinitionLocation::Internal(*range),
-            );
+impl DocumentSymbolTable {
+    /// Indexes a runbook source, or returns `None` if the source can't be parsed.
+    pub fn new(source: &str) -> Option<Self> {
+        let body = parse_body(source).ok()?;
+        let items = RunbookCollector::new(source.to_string(), String::new()).collect(&body);
+
+        let mut table = DocumentSymbolTable::default();
+        for item in items.iter() {
+            let (construct, name, location) = match item {
+                RunbookItem::VariableDef { name, location, .. } => ("variable", name, location),
+                RunbookItem::ActionDef { name, location, .. } => ("action", name, location),
+                RunbookItem::SignerDef { name, location, .. } => ("signer", name, location),
+                RunbookItem::VariableReference { name, full_path, location }
+                | RunbookItem::SignerReference { name, full_path, location } => {
+                    let construct = full_path.split('.').next().unwrap_or_default();
+                    table.references.push(ConstructSymbol {
+                        construct: construct.to_string(),
+                        name: name.clone(),
+                        range: range_from(location, full_path.chars().count()),
+                    });
+                    continue;
+                }
+                RunbookItem::ActionReference { action_name, full_path, location, .. } => {
+                    table.references.push(ConstructSymbol {
+                        construct: "action".into(),
+                        name: action_name.clone(),
+                        range: range_from(location, full_path.chars().count()),
+                    });
+                    continue;
+                }
+                _ => continue,
+            };
+            table.definitions.push(ConstructSymbol {
+                construct: construct.to_string(),
+                name: name.clone(),
+                range: definition_range(source, location, name),
+            });
         }
-        true
-    }
-
-    fn visit_var_set(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _value: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, name, 1);
-        true
-    }
-
-    fn visit_var_get(&mut self, expr: &'a SymbolicExpression, name: &'a ClarityName) -> bool {
-        self.set_definition_for_arg_at_index(expr, name, 1);
-        true
-    }
-
-    fn visit_map_insert(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
-        _value: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, name, 1);
-        true
-    }
-
-    fn visit_map_get(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, name, 1);
-        true
-    }
-
-    fn visit_map_set(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
-        _value: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, name, 1);
-        true
-    }
-
-    fn visit_map_delete(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, name, 1);
-        true
-    }
-
-    fn visit_call_user_defined(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _args: &'a [SymbolicExpression],
-    ) -> bool {
-        if let Some(range) = self.global.get(name) {
-            self.tokens.insert(
-                (expr.span.start_line, expr.span.start_column + 1),
-                DefinitionLocation::Internal(*range),
+        Some(table)
+    }
+
+    /// Returns the construct defined or referenced at a position.
+    pub fn symbol_at(&self, position: &Position) -> Option<&ConstructSymbol> {
+        self.references
+            .iter()
+            .chain(self.definitions.iter())
+            .find(|symbol| contains(&symbol.range, position))
+    }
+
+    pub fn find_definition(&self, symbol: &ConstructSymbol) -> Option<&ConstructSymbol> {
+        self.definitions.iter().find(|definition| definition.is_same_construct(symbol))
+    }
+
+    /// Returns the edits renaming a construct in the source: the label of its definition, if
+    /// defined in the source, and the name in each of its references.
+    pub fn rename_edits(&self, symbol: &ConstructSymbol, new_name: &str) -> Vec<TextEdit> {
+        let definitions = self
+            .definitions
+            .iter()
+            .filter(|definition| definition.is_same_construct(symbol))
+            .filter_map(|definition| {
+                let length = definition.name.chars().count();
+                // the range of a definition which label wasn't found is empty
+                let label_length =
+                    definition.range.end.character - definition.range.start.character;
+                (label_length as usize == length + 2)
+                    .then(|| sub_range(&definition.range, 1, length))
+            });
+        let references =
+            self.references.iter().filter(|reference| reference.is_same_construct(symbol)).map(
+                |reference| {
+                    let offset = reference.construct.chars().count() + 1;
+                    sub_range(&reference.range, offset, reference.name.chars().count())
+                },
             );
-        }
-        true
-    }
-
-    fn visit_ft_mint(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-        _amount: &'a SymbolicExpression,
-        _recipient: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_ft_burn(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-        _amount: &'a SymbolicExpression,
-        _sender: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_ft_get_balance(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-        _owner: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_ft_get_supply(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_ft_transfer(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-        _amount: &'a SymbolicExpression,
-        _sender: &'a SymbolicExpression,
-        _recipient: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_nft_burn(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-        _identifier: &'a SymbolicExpression,
-        _sender: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_nft_get_owner(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-        _identifier: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_nft_mint(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-        _identifier: &'a SymbolicExpression,
-        _recipient: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_nft_transfer(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        token: &'a ClarityName,
-        _identifier: &'a SymbolicExpression,
-        _sender: &'a SymbolicExpression,
-        _recipient: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, token, 1);
-        true
-    }
-
-    fn visit_map(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        func: &'a ClarityName,
-        _sequences: &'a [SymbolicExpression],
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, func, 1);
-        true
-    }
-
-    fn visit_filter(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        func: &'a ClarityName,
-        _sequence: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, func, 1);
-        true
-    }
-
-    fn visit_fold(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        func: &'a ClarityName,
-        _sequence: &'a SymbolicExpression,
-        _initial: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_definition_for_arg_at_index(expr, func, 1);
-        true
-    }
-
-    fn visit_static_contract_call(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        identifier: &'a QualifiedContractIdentifier,
-        function_name: &'a ClarityName,
-        _args: &'a [SymbolicExpression],
-    ) -> bool {
-        if let Some(list) = expr.match_list() {
-            if let Some(SymbolicExpression { span, .. }) = list.get(2) {
-                let identifier = if identifier.issuer == StandardPrincipalData::transient() {
-                    match &self.deployer {
-                        Some(deployer) => QualifiedContractIdentifier::parse(&format!(
-                            "{}.{}",
-                            deployer, identifier.name
-                        ))
-                        .expect("failed to set contract name"),
-                        None => identifier.to_owned(),
-                    }
-                } else {
-                    identifier.to_owned()
-                };
-
-                self.tokens.insert(
-                    (span.start_line, span.start_column),
-                    DefinitionLocation::External(identifier, function_name.to_owned()),
-                );
-            };
-        };
-
-        true
-    }
-
-    fn traverse_define_private(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        parameters: Option<Vec<TypedVar<'a>>>,
-        body: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_function_parameters_scope(expr);
-        self.traverse_expr(body) && self.visit_define_private(expr, name, parameters, body)
-    }
-
-    fn visit_define_private(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        _body: &'a SymbolicExpression,
-    ) -> bool {
-        self.global.insert(name.clone(), span_to_range(&expr.span));
-        true
-    }
-
-    fn traverse_define_read_only(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        parameters: Option<Vec<TypedVar<'a>>>,
-        body: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_function_parameters_scope(expr);
-        self.traverse_expr(body) && self.visit_define_read_only(expr, name, parameters, body)
-    }
-
-    fn visit_define_read_only(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        _body: &'a SymbolicExpression,
-    ) -> bool {
-        self.global.insert(name.clone(), span_to_range(&expr.span));
-        true
-    }
-
-    fn traverse_define_public(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        parameters: Option<Vec<TypedVar<'a>>>,
-        body: &'a SymbolicExpression,
-    ) -> bool {
-        self.set_function_parameters_scope(expr);
-        self.traverse_expr(body) && self.visit_define_public(expr, name, parameters, body)
-    }
-
-    fn visit_define_public(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        _body: &'a SymbolicExpression,
-    ) -> bool {
-        self.global.insert(name.clone(), span_to_range(&expr.span));
-        true
-    }
-
-    fn visit_define_constant(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _value: &'a SymbolicExpression,
-    ) -> bool {
-        self.global.insert(name.clone(), span_to_range(&expr.span));
-        true
-    }
-
-    fn visit_define_data_var(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _data_type: &'a SymbolicExpression,
-        _initial: &'a SymbolicExpression,
-    ) -> bool {
-        self.global.insert(name.clone(), span_to_range(&expr.span));
-        true
+        definitions
+            .chain(references)
+            .map(|range| TextEdit { range, new_text: new_name.to_string() })
+            .collect()
+    }
+
+    pub fn find_references<'a>(
+        &'a self,
+        symbol: &'a ConstructSymbol,
+        include_declaration: bool,
+    ) -> impl Iterator<Item = &'a ConstructSymbol> + 'a {
+        let definitions = self.definitions.iter().filter(move |_| include_declaration);
+        self.references
+            .iter()
+            .chain(definitions)
+            .filter(move |candidate| candidate.is_same_construct(symbol))
     }
+}
 
-    fn visit_define_map(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _key_type: &'a SymbolicExpression,
-        _value_type: &'a SymbolicExpression,
-    ) -> bool {
-        self.global.insert(name.clone(), span_to_range(&expr.span));
-        true
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn visit_define_ft(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _supply: Option<&'a SymbolicExpression>,
-    ) -> bool {
-        self.global.insert(name.clone(), span_to_range(&expr.span));
-        true
-    }
+    const SOURCE: &str = r#"variable "amount" {
+    value = 10
+}
 
-    fn visit_define_nft(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a ClarityName,
-        _nft_type: &'a SymbolicExpression,
-    ) -> bool {
-        self.global.insert(name.clone(), span_to_range(&expr.span));
-        true
-    }
+action "fund_wallet" "std::send_http_request" {
+    url = variable.amount
+}
 
-    fn traverse_let(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
-        body: &'a [SymbolicExpression],
-    ) -> bool {
-        let local_scope = || -> Option<HashMap<ClarityName, Range>> {
-            let mut result = HashMap::new();
+output "status_code" {
+    value = action.fund_wallet.status_code
+}
+"#;
 
-            let binding_exprs = expr.match_list()?.get(1)?.match_list()?;
-            for binding in binding_exprs {
-                if let Some(name) = binding
-                    .match_list()
-                    .and_then(|l| l.split_first())
-                    .and_then(|(name, _)| name.match_atom())
-                {
-                    result.insert(name.to_owned(), span_to_range(&binding.span));
-                }
-            }
-            Some(result)
-        };
-        if let Some(local_scope) = local_scope() {
-            self.local.insert(expr.id, local_scope);
-        }
+    #[test]
+    fn test_symbol_table() {
+        let table = DocumentSymbolTable::new(SOURCE).unwrap();
+        let reference = table.symbol_at(&Position { line: 9, character: 20 }).unwrap();
+        assert_eq!(reference.construct, "action");
+        assert_eq!(reference.name, "fund_wallet");
 
-        for binding in bindings.values() {
-            if !self.traverse_expr(binding) {
-                return false;
-            }
-        }
+        let definition = table.find_definition(reference).unwrap();
+        assert_eq!(definition.range.start, Position { line: 4, character: 7 });
+        assert_eq!(definition.range.end, Position { line: 4, character: 20 });
 
-        for expr in body {
-            if !self.traverse_expr(expr) {
-                return false;
-            }
-        }
-        self.visit_let(expr, bindings, body)
-    }
+        // the definition resolves to the same construct as its references
+        let symbol = table.symbol_at(&Position { line: 4, character: 10 }).unwrap();
+        assert_eq!(table.find_references(symbol, false).count(), 1);
+        assert_eq!(table.find_references(symbol, true).count(), 2);
 
-    fn traverse_match_option(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        input: &'a SymbolicExpression,
-        some_name: &'a ClarityName,
-        some_branch: &'a SymbolicExpression,
-        none_branch: &'a SymbolicExpression,
-    ) -> bool {
-        self.local
-            .insert(expr.id, HashMap::from([(some_name.clone(), span_to_range(&input.span))]));
-        self.traverse_expr(input)
-            && self.traverse_expr(some_branch)
-            && self.traverse_expr(none_branch)
-            && self.visit_match_option(expr, input, some_name, some_branch, none_branch)
-    }
+        let variable = table.symbol_at(&Position { line: 0, character: 12 }).unwrap();
+        assert_eq!(table.find_references(variable, false).count(), 1);
 
-    fn traverse_match_response(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        input: &'a SymbolicExpression,
-        ok_name: &'a ClarityName,
-        ok_branch: &'a SymbolicExpression,
-        err_name: &'a ClarityName,
-        err_branch: &'a SymbolicExpression,
-    ) -> bool {
-        self.local.insert(
-            expr.id,
-            HashMap::from([
-                (ok_name.clone(), span_to_range(&input.span)),
-                (err_name.clone(), span_to_range(&input.span)),
-            ]),
-        );
-        self.traverse_expr(input)
-            && self.traverse_expr(ok_branch)
-            && self.traverse_expr(err_branch)
-            && self.visit_match_response(expr, input, ok_name, ok_branch, err_name, err_branch)
+        assert!(DocumentSymbolTable::new("action \"broken\" {").is_none());
     }
-}
 
-pub fn get_definitions(
-    expressions: &[SymbolicExpression],
-    issuer: Option<StandardPrincipalData>,
-) -> HashMap<(u32, u32), DefinitionLocation> {
-    let mut definitions_visitor = Definitions::new(issuer);
-    definitions_visitor.run(expressions);
-    definitions_visitor.tokens
+    #[test]
+    fn test_rename_edits() {
+        // references in string templates are renamed too
+        let template = r#"
+output "message" {
+    value = "got ${action.fund_wallet.status_code}"
 }
+"#;
+        let table = DocumentSymbolTable::new(&format!("{}{}", SOURCE, template)).unwrap();
+        let symbol = table.symbol_at(&Position { line: 4, character: 10 }).unwrap();
+        let edits = table.rename_edits(symbol, "fund");
+        let ranges = edits.iter().map(|edit| edit.range).collect::<Vec<_>>();
+        let range = |line, start, end| Range {
+            start: Position { line, character: start },
+            end: Position { line, character: end },
+        };
+        assert_eq!(ranges, vec![range(4, 8, 19), range(9, 19, 30), range(13, 26, 37)]);
+        assert!(edits.iter().all(|edit| edit.new_text.eq("fund")));
 
-pub fn get_public_function_definitions(
-    expressions: &Vec<SymbolicExpression>,
-) -> HashMap<ClarityName, Range> {
-    let mut definitions = HashMap::new();
-
-    for expression in expressions {
-        if let Some((function_name, args)) = expression
-            .match_list()
-            .and_then(|l| l.split_first())
-            .and_then(|(function_name, args)| Some((function_name.match_atom()?, args)))
-        {
-            if let Some(DefineFunctions::PublicFunction | DefineFunctions::ReadOnlyFunction) =
-                DefineFunctions::lookup_by_name(function_name)
-            {
-                if let Some(function_name) = args
-                    .split_first()
-                    .and_then(|(args_list, _)| args_list.match_list()?.split_first())
-                    .and_then(|(function_name, _)| function_name.match_atom())
-                {
-                    definitions.insert(function_name.to_owned(), span_to_range(&expression.span));
-                }
-            }
-        }
+        assert!(is_valid_identifier("fund_wallet-2"));
+        assert!(!is_valid_identifier("2fund"));
+        assert!(!is_valid_identifier("fund.wallet"));
+        assert!(!is_valid_identifier(""));
     }
-
-    definitions
 }
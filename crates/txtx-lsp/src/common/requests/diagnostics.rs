@@ -0,0 +1,417 @@
+use std::collections::{BTreeMap, HashMap};
+
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic as LspDiagnostic, Position, Range,
+    TextEdit, Url, WorkspaceEdit,
+};
+use txtx_addon_kit::hcl::parser::parse_body;
+use txtx_addon_kit::hcl::structure::{Block, BlockLabel, Body};
+use txtx_addon_kit::hcl::Span;
+use txtx_addon_kit::types::commands::{
+    CommandInput, CommandSpecification, PreCommandSpecification,
+};
+use txtx_addon_kit::types::diagnostic_types::DiagnosticSpan;
+use txtx_addon_kit::types::diagnostics::Diagnostic;
+use txtx_addon_kit::types::types::Type;
+use txtx_addon_kit::Addon;
+use txtx_core::validation::hcl_validator::validate_with_hcl_and_addons;
+use txtx_core::validation::{FileBoundaryMap, ValidationResult};
+
+use super::completion::{command_inputs, position_to_offset};
+use super::hover::offset_to_position;
+use crate::utils::txtx_diagnostic_to_lsp_type;
+
+/// Name given to the source validated in the combined content of its runbook.
+const VALIDATED_SOURCE: &str = "source";
+/// Name given to the other sources of the runbook in the combined content.
+const SIBLING_SOURCE: &str = "sibling";
+
+/// Validates runbook sources against the specifications of the addons loaded by the language
+/// server, as `txtx check` does, and suggests fixes for the simplest diagnostics.
+#[derive(Debug, Clone, Default)]
+pub struct RunbookValidator {
+    /// Specifications of the actions, keyed by namespace, as expected by the validator
+    addon_specs: HashMap<String, Vec<(String, CommandSpecification)>>,
+    /// Inputs of the actions, keyed by `namespace::matcher`
+    action_inputs: BTreeMap<String, Vec<CommandInput>>,
+}
+
+impl RunbookValidator {
+    pub fn new(addons: &[Box<dyn Addon>]) -> Self {
+        let mut validator = RunbookValidator::default();
+        for addon in addons.iter() {
+            let namespace = addon.get_namespace();
+            let mut specs = vec![];
+            for command in addon.get_actions().into_iter() {
+                let spec = match &command {
+                    PreCommandSpecification::Atomic(spec) => spec.clone(),
+                    // composite actions are validated against their first part, like in txtx check
+                    PreCommandSpecification::Composite(composite) => {
+                        let Some(PreCommandSpecification::Atomic(first)) = composite.parts.first()
+                        else {
+                            continue;
+                        };
+                        let mut spec = first.clone();
+                        spec.name = composite.name.clone();
+                        spec.matcher = composite.matcher.clone();
+                        spec
+                    }
+                };
+                validator
+                    .action_inputs
+                    .insert(format!("{}::{}", namespace, spec.matcher), command_inputs(&command));
+                specs.push((spec.matcher.clone(), spec));
+            }
+            validator.addon_specs.insert(namespace.to_string(), specs);
+        }
+        validator
+    }
+
+    /// Validates a runbook source. The other sources of its runbook are validated along, so that
+    /// the constructs they define can be referenced, but only the diagnostics of the source are
+    /// returned.
+    pub fn validate(&self, source: &str, siblings: &[&str]) -> Vec<Diagnostic> {
+        if let Err(e) = parse_body(source) {
+            let location = e.location();
+            let span = token_span(source, location.line(), location.column());
+            return vec![Diagnostic::error_from_string(format!("parsing error: {}", e.message()))
+                .set_diagnostic_span(Some(span))];
+        }
+
+        let mut combined_content = String::new();
+        let mut boundary_map = FileBoundaryMap::new();
+        boundary_map.add_file(VALIDATED_SOURCE.into(), source.lines().count());
+        combined_content.push_str(source);
+        combined_content.push('\n');
+        // the siblings that can't be parsed are reported when they're opened
+        for sibling in siblings.iter().filter(|sibling| parse_body(sibling).is_ok()) {
+            boundary_map.add_file(SIBLING_SOURCE.into(), sibling.lines().count());
+            combined_content.push_str(sibling);
+            combined_content.push('\n');
+        }
+
+        let mut result = ValidationResult::new();
+        if let Err(e) = validate_with_hcl_and_addons(
+            &combined_content,
+            &mut result,
+            VALIDATED_SOURCE,
+            self.addon_specs.clone(),
+        ) {
+            return vec![Diagnostic::error_from_string(e)];
+        }
+        result.map_errors_to_source_files(&boundary_map);
+
+        result
+            .errors
+            .into_iter()
+            .chain(result.warnings)
+            .filter(|diagnostic| diagnostic.file.as_deref() == Some(VALIDATED_SOURCE))
+            .map(|diagnostic| {
+                let span = diagnostic
+                    .line
+                    .zip(diagnostic.column)
+                    .map(|(line, column)| token_span(source, line, column));
+                diagnostic.set_diagnostic_span(span)
+            })
+            .collect()
+    }
+
+    /// Returns the quick fixes of the diagnostics of a runbook source overlapping a range.
+    pub fn get_code_actions(
+        &self,
+        uri: &Url,
+        source: &str,
+        siblings: &[&str],
+        range: &Range,
+    ) -> Vec<CodeActionOrCommand> {
+        let Ok(body) = parse_body(source) else {
+            return vec![];
+        };
+        let diagnostics = self
+            .validate(source, siblings)
+            .iter()
+            .map(|diagnostic| (diagnostic.code.clone(), txtx_diagnostic_to_lsp_type(diagnostic)))
+            .filter(|(_, diagnostic)| {
+                diagnostic.range.start <= range.end && range.start <= diagnostic.range.end
+            })
+            .collect::<Vec<_>>();
+
+        let mut actions = vec![];
+        let mut fixed_blocks = vec![];
+        for (code, diagnostic) in diagnostics.iter() {
+            let text = text_at(source, &diagnostic.range);
+            let offset = position_to_offset(source, &diagnostic.range.start);
+            let Some(block) = body.blocks().find(|block| contains(block, offset)) else {
+                continue;
+            };
+            match code.as_deref() {
+                Some("invalid_parameter") => {
+                    let Some(name) = self.closest_input(block, &text) else { continue };
+                    let edit = TextEdit { range: diagnostic.range, new_text: name.clone() };
+                    actions.push(quick_fix(
+                        format!("Rename to '{}'", name),
+                        uri,
+                        edit,
+                        diagnostic.clone(),
+                    ));
+                }
+                Some("missing_parameter") => {
+                    // one diagnostic is reported per missing input, all at the block identifier
+                    if fixed_blocks.contains(&block.span()) {
+                        continue;
+                    }
+                    fixed_blocks.push(block.span());
+                    for input in self.missing_inputs(block) {
+                        let Some(edit) = insert_input(source, block, input) else { continue };
+                        let quoted_name = format!("'{}'", input.name);
+                        let Some((_, diagnostic)) = diagnostics.iter().find(|(code, d)| {
+                            code.as_deref() == Some("missing_parameter")
+                                && d.message.contains(&quoted_name)
+                        }) else {
+                            continue;
+                        };
+                        actions.push(quick_fix(
+                            format!("Insert missing input '{}'", input.name),
+                            uri,
+                            edit,
+                            diagnostic.clone(),
+                        ));
+                    }
+                }
+                Some("undefined_reference") => {
+                    let Some(name) = text.strip_prefix("variable.") else { continue };
+                    let name = name.split('.').next().unwrap_or_default();
+                    let Some(start) =
+                        block.span().map(|span| offset_to_position(source, span.start))
+                    else {
+                        continue;
+                    };
+                    let position = Position { line: start.line, character: 0 };
+                    let edit = TextEdit {
+                        range: Range { start: position, end: position },
+                        new_text: format!("variable \"{}\" {{\n    value = \"\"\n}}\n\n", name),
+                    };
+                    actions.push(quick_fix(
+                        format!("Create variable '{}'", name),
+                        uri,
+                        edit,
+                        diagnostic.clone(),
+                    ));
+                }
+                _ => {}
+            }
+        }
+        actions
+    }
+
+    fn block_inputs(&self, block: &Block) -> Option<&Vec<CommandInput>> {
+        if !block.ident.as_str().eq("action") {
+            return None;
+        }
+        let Some(BlockLabel::String(action_type)) = block.labels.get(1) else {
+            return None;
+        };
+        self.action_inputs.get(action_type.value())
+    }
+
+    /// Returns the input of an action closest to an unknown attribute, among the ones not set.
+    fn closest_input(&self, block: &Block, attribute: &str) -> Option<String> {
+        let present = block_attributes(&block.body);
+        self.block_inputs(block)?
+            .iter()
+            .filter(|input| !present.contains(&input.name))
+            .map(|input| (edit_distance(attribute, &input.name), &input.name))
+            .filter(|(distance, _)| *distance <= (attribute.chars().count() / 3).max(2))
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, name)| name.clone())
+    }
+
+    fn missing_inputs(&self, block: &Block) -> Vec<&CommandInput> {
+        let present = block_attributes(&block.body);
+        let Some(inputs) = self.block_inputs(block) else {
+            return vec![];
+        };
+        inputs.iter().filter(|input| !input.optional && !present.contains(&input.name)).collect()
+    }
+}
+
+fn block_attributes(body: &Body) -> Vec<String> {
+    body.attributes()
+        .map(|attribute| attribute.key.to_string())
+        .chain(body.blocks().map(|block| block.ident.to_string()))
+        .collect()
+}
+
+fn contains(block: &Block, offset: usize) -> bool {
+    block.span().is_some_and(|span| span.start <= offset && offset <= span.end)
+}
+
+fn quick_fix(
+    title: String,
+    uri: &Url,
+    edit: TextEdit,
+    diagnostic: LspDiagnostic,
+) -> CodeActionOrCommand {
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+            ..WorkspaceEdit::default()
+        }),
+        is_preferred: Some(true),
+        ..CodeAction::default()
+    })
+}
+
+/// Inserts an input with a placeholder value at the end of a block.
+fn insert_input(source: &str, block: &Block, input: &CommandInput) -> Option<TextEdit> {
+    let closing_brace = block.span()?.end.checked_sub(1)?;
+    let position = offset_to_position(source, closing_brace);
+    let line_start = source[..closing_brace].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let attribute = format!("    {} = {}\n", input.name, placeholder(&input.typing));
+    let (position, new_text) = match source[line_start..closing_brace].trim().is_empty() {
+        true => (Position { line: position.line, character: 0 }, attribute),
+        // blocks written on a single line, e.g. `action "ping" "std::send_http_request" {}`
+        false => (position, format!("\n{}", attribute)),
+    };
+    Some(TextEdit { range: Range { start: position, end: position }, new_text })
+}
+
+fn placeholder(typing: &Type) -> &'static str {
+    match typing {
+        Type::Integer | Type::Float => "0",
+        Type::Bool => "false",
+        Type::Array(_) => "[]",
+        Type::Object(_) | Type::Map(_) => "{}",
+        _ => "\"\"",
+    }
+}
+
+fn text_at(source: &str, range: &Range) -> String {
+    let start = position_to_offset(source, &range.start);
+    let end = position_to_offset(source, &range.end);
+    source.get(start..end).unwrap_or_default().to_string()
+}
+
+fn is_token_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')
+}
+
+/// Returns the span of the token at a line and column of a source (both 1-based), which is where
+/// the validator reports its diagnostics: a reference such as `variable.amount`, an attribute
+/// key, or a quoted string.
+fn token_span(source: &str, line: usize, column: usize) -> DiagnosticSpan {
+    let text = source.lines().nth(line.saturating_sub(1)).unwrap_or_default();
+    let mut chars = text.chars().skip(column.saturating_sub(1));
+    let length = match chars.next() {
+        Some('"') => {
+            let mut escaped = false;
+            let closing_quote = chars.position(|c| {
+                let closing = c == '"' && !escaped;
+                escaped = c == '\\' && !escaped;
+                closing
+            });
+            closing_quote.map(|i| i + 2).unwrap_or(1)
+        }
+        Some(c) if is_token_char(c) => 1 + chars.take_while(|c| is_token_char(*c)).count(),
+        _ => 1,
+    };
+    DiagnosticSpan {
+        line_start: line as u32,
+        line_end: line as u32,
+        column_start: column as u32,
+        column_end: (column + length - 1) as u32,
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use txtx_core::std::StdAddon;
+
+    const SOURCE: &str = r#"action "ping" "std::send_http_request" {
+    ulr = "https://example.com"
+    timeout_ms = "200"
+}
+
+output "status_code" {
+    value = variable.expected_status
+}
+"#;
+
+    fn validator() -> RunbookValidator {
+        let addons: Vec<Box<dyn Addon>> = vec![Box::new(StdAddon::new())];
+        RunbookValidator::new(&addons)
+    }
+
+    fn titles(range: Range) -> Vec<String> {
+        let uri = Url::parse("file:///runbook/main.tx").unwrap();
+        validator()
+            .get_code_actions(&uri, SOURCE, &[], &range)
+            .into_iter()
+            .filter_map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => Some(action.title),
+                CodeActionOrCommand::Command(_) => None,
+            })
+            .collect()
+    }
+
+    fn line_range(line: u32) -> Range {
+        Range { start: Position { line, character: 0 }, end: Position { line, character: 40 } }
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let diagnostics = validator().validate(SOURCE, &[]);
+        let span_of = |code: &str| {
+            let diagnostic = diagnostics.iter().find(|d| d.code.as_deref() == Some(code)).unwrap();
+            let span = diagnostic.span.clone().unwrap();
+            (span.line_start, span.column_start, span.column_end)
+        };
+        assert_eq!(span_of("invalid_parameter"), (2, 5, 7));
+        assert_eq!(span_of("missing_parameter"), (1, 1, 6));
+        assert_eq!(span_of("type_mismatch"), (3, 18, 22));
+        assert_eq!(span_of("undefined_reference"), (7, 13, 36));
+
+        // the variable defined in another source of the runbook can be referenced
+        let sibling = "variable \"expected_status\" {\n    value = 200\n}\n";
+        let diagnostics = validator().validate(SOURCE, &[sibling]);
+        assert!(!diagnostics.iter().any(|d| d.code.as_deref() == Some("undefined_reference")));
+    }
+
+    #[test]
+    fn test_code_actions() {
+        assert_eq!(titles(line_range(1)), vec!["Rename to 'url'".to_string()]);
+        assert_eq!(titles(line_range(0)), vec!["Insert missing input 'url'".to_string()]);
+        assert_eq!(titles(line_range(6)), vec!["Create variable 'expected_status'".to_string()]);
+        assert!(titles(line_range(4)).is_empty());
+    }
+
+    #[test]
+    fn test_insert_input() {
+        let source = "action \"ping\" \"std::send_http_request\" {\n}\n";
+        let body = parse_body(source).unwrap();
+        let block = body.blocks().next().unwrap();
+        let validator = validator();
+        let input = validator.missing_inputs(block)[0];
+        let edit = insert_input(source, block, input).unwrap();
+        assert_eq!(edit.range.start, Position { line: 1, character: 0 });
+        assert_eq!(edit.new_text, "    url = \"\"\n");
+    }
+}
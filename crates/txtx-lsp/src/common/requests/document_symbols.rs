@@ -1,448 +1,165 @@
-use std::collections::HashMap;
-
-use clarity_repl::{
-    analysis::ast_visitor::{traverse, ASTVisitor},
-    clarity::{representations::Span, ClarityName, SymbolicExpression, SymbolicExpressionType},
-};
-use lsp_types::{DocumentSymbol, SymbolKind};
-use serde::{Deserialize, Serialize};
-
-use super::helpers::span_to_range;
+use std::ops::Range as ByteRange;
+
+use lsp_types::{DocumentSymbol, Location, Range, SymbolInformation, SymbolKind, Url};
+use txtx_addon_kit::hcl::parser::parse_body;
+use txtx_addon_kit::hcl::structure::{Block, BlockLabel, Body, Structure};
+use txtx_addon_kit::hcl::Span;
+
+use super::hover::offset_to_position;
+
+fn symbol_kind(block_ident: &str) -> SymbolKind {
+    match block_ident {
+        "addon" => SymbolKind::NAMESPACE,
+        "signer" => SymbolKind::KEY,
+        "variable" => SymbolKind::VARIABLE,
+        "action" => SymbolKind::FUNCTION,
+        "output" => SymbolKind::FIELD,
+        "flow" => SymbolKind::MODULE,
+        "runbook" => SymbolKind::PACKAGE,
+        _ => SymbolKind::OBJECT,
+    }
+}
 
-fn symbolic_expression_to_name(symbolic_expr: &SymbolicExpression) -> String {
-    match &symbolic_expr.expr {
-        SymbolicExpressionType::Atom(name) => name.to_string(),
-        SymbolicExpressionType::List(list) => symbolic_expression_to_name(&(*list).to_vec()[0]),
-        _ => "".to_string(),
+fn label(block: &Block, index: usize) -> Option<(String, Option<ByteRange<usize>>)> {
+    match block.labels.get(index)? {
+        BlockLabel::String(label) => Some((label.value().to_string(), label.span())),
+        BlockLabel::Ident(label) => Some((label.to_string(), label.span())),
     }
 }
 
-#[derive(Eq, PartialEq, Copy, Clone, Serialize, Deserialize)]
-#[serde(transparent)]
-struct ClaritySymbolKind(i32);
-impl ClaritySymbolKind {
-    pub const FUNCTION: SymbolKind = SymbolKind::FUNCTION;
-    pub const BEGIN: SymbolKind = SymbolKind::NAMESPACE;
-    pub const LET: SymbolKind = SymbolKind::NAMESPACE;
-    pub const NAMESPACE: SymbolKind = SymbolKind::NAMESPACE;
-    pub const LET_BINDING: SymbolKind = SymbolKind::VARIABLE;
-    pub const IMPL_TRAIT: SymbolKind = SymbolKind::NAMESPACE;
-    pub const TRAIT: SymbolKind = SymbolKind::STRUCT;
-    pub const TOKEN: SymbolKind = SymbolKind::NAMESPACE;
-    pub const CONSTANT: SymbolKind = SymbolKind::CONSTANT;
-    pub const VARIABLE: SymbolKind = SymbolKind::VARIABLE;
-    pub const MAP: SymbolKind = SymbolKind::STRUCT;
-    pub const KEY: SymbolKind = SymbolKind::KEY;
-    pub const VALUE: SymbolKind = SymbolKind::PROPERTY;
-    pub const FLOW: SymbolKind = SymbolKind::OBJECT;
-    pub const RESPONSE: SymbolKind = SymbolKind::OBJECT;
+fn range(source: &str, span: &ByteRange<usize>) -> Range {
+    Range {
+        start: offset_to_position(source, span.start),
+        end: offset_to_position(source, span.end),
+    }
 }
 
-fn build_symbol(
-    name: &str,
-    detail: Option<String>,
-    kind: SymbolKind,
-    span: &Span,
-    children: Option<Vec<DocumentSymbol>>,
-) -> DocumentSymbol {
-    let range = span_to_range(span);
-    #[allow(deprecated)]
-    DocumentSymbol {
-        name: name.to_string(),
-        kind,
+#[allow(deprecated)]
+fn block_symbol(source: &str, block: &Block) -> Option<DocumentSymbol> {
+    let span = block.span()?;
+    // blocks are named after their first label, e.g. `action "deploy" "evm::deploy_contract"`,
+    // and nested blocks without labels after their identifier, e.g. `pre_condition`
+    let (name, name_span) =
+        label(block, 0).unwrap_or_else(|| (block.ident.to_string(), block.ident.span()));
+    let detail = label(block, 1).map(|(construct_type, _)| construct_type);
+    let children = body_symbols(source, &block.body);
+    Some(DocumentSymbol {
+        name,
         detail,
+        kind: symbol_kind(block.ident.as_str()),
         tags: None,
         deprecated: None,
-        selection_range: range,
-        range,
-        children,
-    }
+        range: range(source, &span),
+        selection_range: range(source, &name_span.unwrap_or(span)),
+        children: (!children.is_empty()).then_some(children),
+    })
 }
 
-#[derive(Clone, Debug)]
-pub struct ASTSymbols {
-    pub symbols: Vec<DocumentSymbol>,
-    pub children_map: HashMap<u64, Vec<DocumentSymbol>>,
+#[allow(deprecated)]
+fn body_symbols(source: &str, body: &Body) -> Vec<DocumentSymbol> {
+    body.iter()
+        .filter_map(|structure| match structure {
+            Structure::Block(block) => block_symbol(source, block),
+            Structure::Attribute(attribute) => {
+                let span = attribute.span()?;
+                Some(DocumentSymbol {
+                    name: attribute.key.to_string(),
+                    detail: None,
+                    kind: SymbolKind::PROPERTY,
+                    tags: None,
+                    deprecated: None,
+                    range: range(source, &span),
+                    selection_range: range(source, &attribute.key.span().unwrap_or(span)),
+                    children: None,
+                })
+            }
+        })
+        .collect()
 }
 
-impl<'a> ASTSymbols {
-    pub fn new() -> ASTSymbols {
-        Self { symbols: Vec::new(), children_map: HashMap::new() }
-    }
-
-    pub fn get_symbols(mut self, expressions: &'a [SymbolicExpression]) -> Vec<DocumentSymbol> {
-        traverse(&mut self, expressions);
-        self.symbols
-    }
+/// Returns the outline of a runbook source: its blocks, with the attributes and the nested blocks
+/// of their bodies as children. Returns `None` if the source can't be parsed.
+pub fn get_document_symbols(source: &str) -> Option<Vec<DocumentSymbol>> {
+    let body = parse_body(source).ok()?;
+    Some(body_symbols(source, &body))
 }
 
-impl<'a> ASTVisitor<'a> for ASTSymbols {
-    fn visit_impl_trait(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        trait_identifier: &clarity_repl::clarity::vm::types::TraitIdentifier,
-    ) -> bool {
-        self.symbols.push(build_symbol(
-            "impl-trait",
-            Some(trait_identifier.name.to_string()),
-            ClaritySymbolKind::IMPL_TRAIT,
-            &expr.span,
-            None,
-        ));
-        true
-    }
-
-    fn visit_define_data_var(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        data_type: &'a SymbolicExpression,
-        initial: &'a SymbolicExpression,
-    ) -> bool {
-        let symbol_type = symbolic_expression_to_name(data_type);
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            Some(symbol_type),
-            ClaritySymbolKind::VARIABLE,
-            &expr.span,
-            self.children_map.remove(&initial.id),
-        ));
-
-        true
-    }
-
-    fn visit_tuple(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        values: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
-    ) -> bool {
-        let mut symbols: Vec<DocumentSymbol> = Vec::new();
-        for (name, expr) in values.iter() {
-            if let Some(name) = name {
-                symbols.push(build_symbol(
-                    name.as_str(),
-                    None,
-                    ClaritySymbolKind::VALUE,
-                    &expr.span,
-                    self.children_map.remove(&expr.id),
-                ));
+/// Returns the constructs of a runbook source whose name contains a query, named after the way
+/// they're referenced, e.g. `action.deploy`.
+#[allow(deprecated)]
+pub fn get_workspace_symbols(uri: &Url, source: &str, query: &str) -> Vec<SymbolInformation> {
+    let Ok(body) = parse_body(source) else {
+        return vec![];
+    };
+    let query = query.to_lowercase();
+    let container_name = uri.path_segments().and_then(|mut s| s.next_back()).map(String::from);
+    body.blocks()
+        .filter_map(|block| {
+            let (name, _) = label(block, 0)?;
+            let name = format!("{}.{}", block.ident.as_str(), name);
+            if !name.to_lowercase().contains(&query) {
+                return None;
             }
-        }
-        self.children_map.insert(expr.id, symbols);
-        true
-    }
-
-    fn visit_define_constant(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        _value: &'a SymbolicExpression,
-    ) -> bool {
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            None,
-            ClaritySymbolKind::CONSTANT,
-            &expr.span,
-            None,
-        ));
-        true
-    }
-
-    fn visit_define_map(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        key_type: &'a SymbolicExpression,
-        value_type: &'a SymbolicExpression,
-    ) -> bool {
-        let children = vec![
-            build_symbol(
-                "key",
-                Some(symbolic_expression_to_name(key_type)),
-                ClaritySymbolKind::KEY,
-                &key_type.span,
-                None,
-            ),
-            build_symbol(
-                "value",
-                Some(symbolic_expression_to_name(value_type)),
-                ClaritySymbolKind::VALUE,
-                &value_type.span,
-                None,
-            ),
-        ];
-
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            None,
-            ClaritySymbolKind::MAP,
-            &expr.span,
-            Some(children),
-        ));
-        true
-    }
-
-    fn visit_define_trait(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        functions: &'a [SymbolicExpression],
-    ) -> bool {
-        let mut children = Vec::new();
-        let methods = functions[0].match_list().unwrap();
-        for expr in methods {
-            let list = expr.match_list().unwrap();
-            let name = &list[0].match_atom().unwrap();
-            children.push(build_symbol(
-                name.to_owned(),
-                Some("trait method".to_owned()),
-                ClaritySymbolKind::FUNCTION,
-                &expr.span,
-                None,
-            ))
-        }
-
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            None,
-            ClaritySymbolKind::TRAIT,
-            &expr.span,
-            Some(children),
-        ));
-        true
-    }
-
-    fn visit_define_private(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        _parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        body: &'a SymbolicExpression,
-    ) -> bool {
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            Some("private".to_owned()),
-            ClaritySymbolKind::FUNCTION,
-            &expr.span,
-            self.children_map.remove(&body.id),
-        ));
-        true
-    }
-
-    fn visit_define_public(
-        &mut self,
-        expr: &'a clarity_repl::clarity::SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        _parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        body: &'a clarity_repl::clarity::SymbolicExpression,
-    ) -> bool {
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            Some("public".to_owned()),
-            ClaritySymbolKind::FUNCTION,
-            &expr.span,
-            self.children_map.remove(&body.id),
-        ));
-        true
-    }
-
-    fn visit_define_read_only(
-        &mut self,
-        expr: &'a clarity_repl::clarity::SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        _parameters: Option<Vec<clarity_repl::analysis::ast_visitor::TypedVar<'a>>>,
-        body: &'a clarity_repl::clarity::SymbolicExpression,
-    ) -> bool {
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            Some("read-only".to_owned()),
-            ClaritySymbolKind::FUNCTION,
-            &expr.span,
-            self.children_map.remove(&body.id),
-        ));
-        true
-    }
-
-    fn visit_define_ft(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        _supply: Option<&'a SymbolicExpression>,
-    ) -> bool {
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            Some("FT".to_owned()),
-            ClaritySymbolKind::TOKEN,
-            &expr.span,
-            None,
-        ));
-        true
-    }
-
-    fn visit_define_nft(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        name: &'a clarity_repl::clarity::ClarityName,
-        nft_type: &'a SymbolicExpression,
-    ) -> bool {
-        let nft_type = match nft_type.expr.clone() {
-            SymbolicExpressionType::Atom(name) => name.to_string(),
-            SymbolicExpressionType::List(_) => "tuple".to_string(),
-            _ => "".to_string(),
-        };
-
-        self.symbols.push(build_symbol(
-            &name.to_owned(),
-            Some(format!("NFT {}", &nft_type)),
-            ClaritySymbolKind::TOKEN,
-            &expr.span,
-            None,
-        ));
-        true
-    }
-
-    fn visit_begin(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        statements: &'a [SymbolicExpression],
-    ) -> bool {
-        let mut children = Vec::new();
-        for statement in statements.iter() {
-            if let Some(mut child) = self.children_map.remove(&statement.id) {
-                children.append(&mut child);
-            }
-        }
-
-        self.children_map.insert(
-            expr.id,
-            vec![build_symbol("begin", None, ClaritySymbolKind::BEGIN, &expr.span, Some(children))],
-        );
-        true
-    }
-
-    fn visit_let(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
-        body: &'a [SymbolicExpression],
-    ) -> bool {
-        let mut children: Vec<DocumentSymbol> = Vec::new();
-
-        let mut bindings_children: Vec<DocumentSymbol> = Vec::new();
-        for (name, expr) in bindings.iter() {
-            bindings_children.push(build_symbol(
-                name.as_str(),
-                None,
-                ClaritySymbolKind::LET_BINDING,
-                &expr.span,
-                self.children_map.remove(&expr.id),
-            ))
-        }
-        if !bindings_children.is_empty() {
-            let start = bindings_children.first().unwrap().range.start;
-            let end = bindings_children.last().unwrap().range.start;
-            let bindings_span = Span {
-                start_line: start.line + 1,
-                start_column: start.character + 1,
-                end_line: end.line + 1,
-                end_column: end.character + 1,
-            };
-            children.push(build_symbol(
-                "bindings",
-                None,
-                ClaritySymbolKind::NAMESPACE,
-                &bindings_span,
-                Some(bindings_children),
-            ));
-        }
-
-        let mut body_children = Vec::new();
-        for statement in body.iter() {
-            if let Some(children) = self.children_map.remove(&statement.id) {
-                for child in children {
-                    body_children.push(child);
-                }
-            }
-        }
-        if !body_children.is_empty() {
-            let start = body_children.first().unwrap().range.start;
-            let end = body_children.last().unwrap().range.start;
-            let body_span = Span {
-                start_line: start.line + 1,
-                start_column: start.character + 1,
-                end_line: end.line + 1,
-                end_column: end.character + 1,
-            };
-            children.push(build_symbol(
-                "body",
-                None,
-                ClaritySymbolKind::NAMESPACE,
-                &body_span,
-                Some(body_children),
-            ));
-        }
-
-        self.children_map.insert(
-            expr.id,
-            vec![build_symbol("let", None, ClaritySymbolKind::LET, &expr.span, Some(children))],
-        );
-        true
-    }
-
-    fn visit_asserts(
-        &mut self,
-        expr: &'a SymbolicExpression,
-        cond: &'a SymbolicExpression,
-        thrown: &'a SymbolicExpression,
-    ) -> bool {
-        let mut children = Vec::new();
-
-        if self.children_map.contains_key(&cond.id) {
-            children.append(&mut self.children_map.remove(&cond.id).unwrap())
-        }
-        if self.children_map.contains_key(&thrown.id) {
-            children.append(&mut self.children_map.remove(&thrown.id).unwrap())
-        }
-
-        self.children_map.insert(
-            expr.id,
-            vec![build_symbol(
-                "asserts!",
-                None,
-                ClaritySymbolKind::FLOW,
-                &expr.span,
-                Some(children),
-            )],
-        );
-
-        true
-    }
+            Some(SymbolInformation {
+                name,
+                kind: symbol_kind(block.ident.as_str()),
+                tags: None,
+                deprecated: None,
+                location: Location { uri: uri.clone(), range: range(source, &block.span()?) },
+                container_name: container_name.clone(),
+            })
+        })
+        .collect()
+}
 
-    fn visit_try(&mut self, expr: &'a SymbolicExpression, input: &'a SymbolicExpression) -> bool {
-        let children = self.children_map.remove(&input.id);
-        self.children_map.insert(
-            expr.id,
-            vec![build_symbol("try!", None, ClaritySymbolKind::FLOW, &expr.span, children)],
-        );
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_types::Position;
 
-        true
-    }
+    const SOURCE: &str = r#"addon "evm" {
+    chain_id = 11155111
+}
 
-    fn visit_ok(&mut self, expr: &'a SymbolicExpression, value: &'a SymbolicExpression) -> bool {
-        let children = self.children_map.remove(&value.id);
-        self.children_map.insert(
-            expr.id,
-            vec![build_symbol("ok", None, ClaritySymbolKind::RESPONSE, &expr.span, children)],
-        );
-        true
+action "deploy" "evm::deploy_contract" {
+    contract = variable.contract
+    pre_condition {
+        assertion = true
     }
+}
 
-    fn visit_err(&mut self, expr: &'a SymbolicExpression, value: &'a SymbolicExpression) -> bool {
-        let children = self.children_map.remove(&value.id);
-        self.children_map.insert(
-            expr.id,
-            vec![build_symbol("err", None, ClaritySymbolKind::RESPONSE, &expr.span, children)],
-        );
-        true
+output "contract_address" {
+    value = action.deploy.contract_address
+}
+"#;
+
+    #[test]
+    fn test_document_symbols() {
+        let symbols = get_document_symbols(SOURCE).unwrap();
+        let names = symbols.iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["evm", "deploy", "contract_address"]);
+
+        let action = &symbols[1];
+        assert_eq!(action.kind, SymbolKind::FUNCTION);
+        assert_eq!(action.detail.as_deref(), Some("evm::deploy_contract"));
+        assert_eq!(action.range.start, Position { line: 4, character: 0 });
+        assert_eq!(action.selection_range.start, Position { line: 4, character: 7 });
+
+        let children = action.children.as_ref().unwrap();
+        assert_eq!(children[0].name, "contract");
+        assert_eq!(children[1].name, "pre_condition");
+        assert_eq!(children[1].children.as_ref().unwrap()[0].name, "assertion");
+
+        assert!(get_document_symbols("action \"broken\" {").is_none());
+    }
+
+    #[test]
+    fn test_workspace_symbols() {
+        let uri = Url::parse("file:///workspace/runbooks/deploy.tx").unwrap();
+        let symbols = get_workspace_symbols(&uri, SOURCE, "DEPLOY");
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "action.deploy");
+        assert_eq!(symbols[0].container_name.as_deref(), Some("deploy.tx"));
+        assert_eq!(get_workspace_symbols(&uri, SOURCE, "").len(), 3);
     }
 }
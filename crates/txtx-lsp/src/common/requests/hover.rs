@@ -1,22 +1,345 @@
-use clarity_repl::clarity::{ClarityVersion, SymbolicExpression};
-use lsp_types::Position;
+use std::collections::BTreeMap;
+use std::ops::Range as ByteRange;
 
-use super::{api_ref::API_REF, helpers::get_expression_name_at_position};
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position, Range};
+use txtx_addon_kit::hcl::expr::{Expression, TraversalOperator};
+use txtx_addon_kit::hcl::parser::parse_body;
+use txtx_addon_kit::hcl::structure::{Block, BlockLabel, Body};
+use txtx_addon_kit::hcl::visit::{visit_expr, Visit};
+use txtx_addon_kit::hcl::Span;
+use txtx_addon_kit::types::commands::{CommandInput, CommandOutput, PreCommandSpecification};
+use txtx_addon_kit::types::functions::FunctionSpecification;
+use txtx_addon_kit::Addon;
 
-pub fn get_expression_documentation(
-    position: &Position,
-    clarity_version: ClarityVersion,
-    expressions: &Vec<SymbolicExpression>,
-) -> Option<String> {
-    let expression_name = get_expression_name_at_position(position, expressions)?;
+use super::completion::{command_inputs, position_to_offset, signer_inputs};
 
-    match API_REF.get(&expression_name.to_string()) {
-        Some((version, documentation, _)) => {
-            if version <= &clarity_version {
-                return Some(documentation.to_owned());
+/// Documentation of an action or a signer type.
+#[derive(Debug, Clone)]
+struct ConstructDocumentation {
+    documentation: String,
+    example: String,
+    inputs: Vec<CommandInput>,
+    outputs: Vec<CommandOutput>,
+}
+
+/// Specifications of the addons loaded by the language server, indexed once at startup.
+#[derive(Debug, Clone, Default)]
+pub struct AddonsHoverIndex {
+    /// Actions, keyed by `namespace::matcher`
+    actions: BTreeMap<String, ConstructDocumentation>,
+    /// Signers, keyed by `namespace::matcher`
+    signers: BTreeMap<String, ConstructDocumentation>,
+    /// Functions, keyed by `namespace::name`
+    functions: BTreeMap<String, FunctionSpecification>,
+}
+
+/// The node of a runbook hovered.
+#[derive(Debug, Clone, PartialEq)]
+enum HoverTarget {
+    ConstructType { construct: String, construct_type: String },
+    Attribute { construct: String, construct_type: String, name: String },
+    Function { name: String },
+    Reference { path: Vec<String> },
+}
+
+fn command_outputs(command: &PreCommandSpecification) -> Vec<CommandOutput> {
+    match command {
+        PreCommandSpecification::Atomic(spec) => spec.outputs.clone(),
+        PreCommandSpecification::Composite(spec) => {
+            let mut outputs: Vec<CommandOutput> = vec![];
+            for output in spec.parts.iter().flat_map(command_outputs) {
+                if !outputs.iter().any(|o| o.name.eq(&output.name)) {
+                    outputs.push(output);
+                }
+            }
+            outputs
+        }
+    }
+}
+
+impl AddonsHoverIndex {
+    pub fn new(addons: &[Box<dyn Addon>]) -> Self {
+        let mut index = AddonsHoverIndex::default();
+        for addon in addons.iter() {
+            let namespace = addon.get_namespace();
+            for (name, function) in addon.build_function_lookup().into_iter() {
+                index.functions.insert(format!("{}::{}", namespace, name), function);
+            }
+            for command in addon.build_command_lookup().into_values() {
+                let (matcher, documentation, example) = match &command {
+                    PreCommandSpecification::Atomic(spec) => {
+                        (&spec.matcher, &spec.documentation, &spec.example)
+                    }
+                    PreCommandSpecification::Composite(spec) => {
+                        (&spec.matcher, &spec.documentation, &spec.example)
+                    }
+                };
+                index.actions.insert(
+                    format!("{}::{}", namespace, matcher),
+                    ConstructDocumentation {
+                        documentation: documentation.clone(),
+                        example: example.clone(),
+                        inputs: command_inputs(&command),
+                        outputs: command_outputs(&command),
+                    },
+                );
+            }
+            for (matcher, signer) in addon.build_signer_lookup().into_iter() {
+                index.signers.insert(
+                    format!("{}::{}", namespace, matcher),
+                    ConstructDocumentation {
+                        documentation: signer.documentation.clone(),
+                        example: signer.example.clone(),
+                        inputs: signer_inputs(&signer),
+                        outputs: signer.outputs.clone(),
+                    },
+                );
+            }
+        }
+        index
+    }
+
+    pub fn get_hover(&self, source: &str, position: &Position) -> Option<Hover> {
+        let body = parse_body(source).ok()?;
+        let offset = position_to_offset(source, position);
+        let (target, span) = find_hover_target(&body, offset)?;
+        let value = match target {
+            HoverTarget::ConstructType { construct, construct_type } => {
+                let documentation = self.construct(&construct, &construct_type)?;
+                format!(
+                    "**{}**\n\n{}\n\n## Example\n```hcl\n{}\n```",
+                    construct_type, documentation.documentation, documentation.example
+                )
+            }
+            HoverTarget::Attribute { construct, construct_type, name } => {
+                let documentation = self.construct(&construct, &construct_type)?;
+                let input = documentation.inputs.iter().find(|input| input.name.eq(&name))?;
+                let requirement = if input.optional { "optional" } else { "required" };
+                format!(
+                    "**{}** `{}` ({})\n\n{}",
+                    input.name,
+                    input.typing.to_string(),
+                    requirement,
+                    input.documentation
+                )
+            }
+            HoverTarget::Function { name } => {
+                let function = self.functions.get(&name)?;
+                let arguments = function
+                    .inputs
+                    .iter()
+                    .map(|input| {
+                        let typing = input
+                            .typing
+                            .iter()
+                            .map(|t| t.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" | ");
+                        let requirement = if input.optional { ", optional" } else { "" };
+                        format!(
+                            "`{}` `{}`{}: {}",
+                            input.name, typing, requirement, input.documentation
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!(
+                    "**{}**({}) -> `{}`\n\n{}\n\n## Arguments\n{}\n\n## Example\n```hcl\n{}\n```",
+                    name,
+                    function.inputs.iter().map(|i| i.name.clone()).collect::<Vec<_>>().join(", "),
+                    function.output.typing.to_string(),
+                    function.documentation,
+                    arguments.join("\n\n"),
+                    function.example
+                )
+            }
+            HoverTarget::Reference { path } => self.reference_documentation(&body, &path)?,
+        };
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value }),
+            range: Some(Range {
+                start: offset_to_position(source, span.start),
+                end: offset_to_position(source, span.end),
+            }),
+        })
+    }
+
+    fn construct(&self, construct: &str, construct_type: &str) -> Option<&ConstructDocumentation> {
+        match construct {
+            "action" => self.actions.get(construct_type),
+            "signer" => self.signers.get(construct_type),
+            _ => None,
+        }
+    }
+
+    /// Documents a reference to an action or a signer, with the type of the output referenced
+    /// when the path goes down to it, e.g. `action.deploy.tx_hash`.
+    fn reference_documentation(&self, body: &Body, path: &[String]) -> Option<String> {
+        let (construct, name) = (path.first()?, path.get(1)?);
+        let construct_type = body.blocks().find_map(|block| {
+            let (block_construct, block_name, block_type) = construct_labels(block)?;
+            (block_construct.eq(construct) && block_name.eq(name)).then_some(block_type)
+        })?;
+        let documentation = self.construct(construct, &construct_type)?;
+        let Some(output_name) = path.get(2) else {
+            return Some(format!(
+                "**{}.{}** `{}`\n\n{}",
+                construct, name, construct_type, documentation.documentation
+            ));
+        };
+        let output = documentation.outputs.iter().find(|output| output.name.eq(output_name))?;
+        Some(format!(
+            "**{}.{}.{}** `{}`\n\n{}",
+            construct,
+            name,
+            output.name,
+            output.typing.to_string(),
+            output.documentation
+        ))
+    }
+}
+
+/// Returns the construct, name and type of an action or a signer block.
+fn construct_labels(block: &Block) -> Option<(String, String, String)> {
+    let construct = block.ident.as_str();
+    if !construct.eq("action") && !construct.eq("signer") {
+        return None;
+    }
+    let Some(BlockLabel::String(name)) = block.labels.first() else {
+        return None;
+    };
+    let Some(BlockLabel::String(construct_type)) = block.labels.get(1) else {
+        return None;
+    };
+    Some((construct.to_string(), name.to_string(), construct_type.to_string()))
+}
+
+fn contains(span: Option<ByteRange<usize>>, offset: usize) -> bool {
+    span.is_some_and(|span| span.start <= offset && offset <= span.end)
+}
+
+fn find_hover_target(body: &Body, offset: usize) -> Option<(HoverTarget, ByteRange<usize>)> {
+    let block = body.blocks().find(|block| contains(block.span(), offset))?;
+    if let Some((construct, _, construct_type)) = construct_labels(block) {
+        if let Some(label) = block.labels.get(1).filter(|label| contains(label.span(), offset)) {
+            let target = HoverTarget::ConstructType { construct, construct_type };
+            return Some((target, label.span()?));
+        }
+        for attribute in block.body.attributes() {
+            if contains(attribute.key.span(), offset) {
+                let target = HoverTarget::Attribute {
+                    construct,
+                    construct_type,
+                    name: attribute.key.to_string(),
+                };
+                return Some((target, attribute.key.span()?));
+            }
+        }
+    }
+
+    let mut finder = ExpressionTargetFinder { offset, target: None };
+    finder.visit_body(&block.body);
+    finder.target
+}
+
+/// Finds the innermost function call or reference at an offset.
+struct ExpressionTargetFinder {
+    offset: usize,
+    target: Option<(HoverTarget, ByteRange<usize>)>,
+}
+
+impl Visit for ExpressionTargetFinder {
+    fn visit_expr(&mut self, expr: &Expression) {
+        if !contains(expr.span(), self.offset) {
+            return;
+        }
+        match expr {
+            Expression::FuncCall(function_call) => {
+                let name = &function_call.name;
+                let start = name.namespace.first().and_then(|ns| ns.span()).or(name.name.span());
+                let span = start.zip(name.name.span()).map(|(start, end)| start.start..end.end);
+                if contains(span.clone(), self.offset) {
+                    let namespace =
+                        name.namespace.first().map(|ns| ns.to_string()).unwrap_or("std".into());
+                    let target = HoverTarget::Function {
+                        name: format!("{}::{}", namespace, name.name.as_str()),
+                    };
+                    self.target = span.map(|span| (target, span));
+                }
+            }
+            Expression::Traversal(traversal) => {
+                if let Some(root) = traversal.expr.as_variable() {
+                    let mut path = vec![root.to_string()];
+                    for operator in traversal.operators.iter() {
+                        match operator.value() {
+                            TraversalOperator::GetAttr(attribute) => {
+                                path.push(attribute.to_string())
+                            }
+                            _ => break,
+                        }
+                    }
+                    self.target =
+                        traversal.span().map(|span| (HoverTarget::Reference { path }, span));
+                }
             }
-            None
+            _ => {}
         }
-        None => None,
+        visit_expr(self, expr);
+    }
+}
+
+pub(super) fn offset_to_position(source: &str, offset: usize) -> Position {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    Position { line: line as u32, character: before[line_start..].chars().count() as u32 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"action "ping" "std::send_http_request" {
+    url = std::encode_hex("ping")
+}
+
+output "status_code" {
+    value = action.ping.status_code
+}
+"#;
+
+    fn target_at(line: u32, character: u32) -> Option<HoverTarget> {
+        let body = parse_body(SOURCE).unwrap();
+        let offset = position_to_offset(SOURCE, &Position { line, character });
+        find_hover_target(&body, offset).map(|(target, _)| target)
+    }
+
+    #[test]
+    fn test_hover_targets() {
+        assert_eq!(
+            target_at(0, 20),
+            Some(HoverTarget::ConstructType {
+                construct: "action".into(),
+                construct_type: "std::send_http_request".into()
+            })
+        );
+        assert_eq!(
+            target_at(1, 5),
+            Some(HoverTarget::Attribute {
+                construct: "action".into(),
+                construct_type: "std::send_http_request".into(),
+                name: "url".into()
+            })
+        );
+        assert_eq!(
+            target_at(1, 18),
+            Some(HoverTarget::Function { name: "std::encode_hex".into() })
+        );
+        assert_eq!(
+            target_at(5, 20),
+            Some(HoverTarget::Reference {
+                path: vec!["action".into(), "ping".into(), "status_code".into()]
+            })
+        );
+        assert_eq!(target_at(3, 0), None);
     }
 }
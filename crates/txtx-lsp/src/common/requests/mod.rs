@@ -1 +1,7 @@
 pub mod capabilities;
+pub mod completion;
+pub mod definitions;
+pub mod diagnostics;
+pub mod document_symbols;
+pub mod hover;
+pub mod signature_help;
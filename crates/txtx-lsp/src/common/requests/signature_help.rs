@@ -1,78 +1,192 @@
-use clarity_repl::clarity::docs::FunctionAPI;
-use lsp_types::{ParameterInformation, ParameterLabel, Position, SignatureInformation};
+use std::collections::BTreeMap;
 
-use crate::state::ActiveContractData;
+use lsp_types::{
+    Documentation, MarkupContent, MarkupKind, ParameterInformation, ParameterLabel, Position,
+    SignatureHelp, SignatureInformation,
+};
+use txtx_addon_kit::types::functions::FunctionSpecification;
+use txtx_addon_kit::Addon;
 
-use super::{api_ref::API_REF, helpers::get_function_at_position};
+use super::completion::position_to_offset;
 
-pub fn get_signatures(
-    contract: &ActiveContractData,
-    position: &Position,
-) -> Option<Vec<SignatureInformation>> {
-    let (function_name, mut active_parameter) =
-        get_function_at_position(position, contract.expressions.as_ref()?)?;
+/// Signatures of the functions of the addons loaded by the language server, indexed once at
+/// startup.
+#[derive(Debug, Clone, Default)]
+pub struct AddonsSignatureIndex {
+    /// Functions, keyed by `namespace::name`
+    functions: BTreeMap<String, FunctionSpecification>,
+}
+
+/// A context opened and not closed yet before the cursor.
+#[derive(Debug, Clone, PartialEq)]
+enum Context {
+    /// A function call, with the index of the argument being written
+    Call { name: String, argument: usize },
+    /// Parentheses, brackets or braces which aren't a function call
+    Group,
+    /// A quoted string
+    String,
+    /// An interpolation in a quoted string, e.g. `${variable.amount}`
+    Template,
+}
 
-    if [
-        "define-read-only",
-        "define-public",
-        "define-private",
-        "define-trait,",
-        "let",
-        "begin",
-        "tuple",
-    ]
-    .contains(&function_name.as_str())
-    {
-        // showing signature help for define-<function>, define-trait, let and bug adds to much noise
-        // it doesn't make sense for the tuple {} notation
-        return None;
+/// Returns the name of the innermost function call open at an offset, with the index of the
+/// argument being written. Commas in strings, comments, lists and objects aren't counted.
+fn find_open_call(source: &str, offset: usize) -> Option<(String, usize)> {
+    let text = &source[..offset.min(source.len())];
+    let mut contexts: Vec<Context> = vec![];
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if let Some(Context::String) = contexts.last() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => {
+                    contexts.pop();
+                }
+                '$' if chars.peek().is_some_and(|(_, next)| *next == '{') => {
+                    chars.next();
+                    contexts.push(Context::Template);
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '#' => {
+                chars.by_ref().find(|(_, c)| *c == '\n');
+            }
+            '/' if chars.peek().is_some_and(|(_, next)| *next == '/') => {
+                chars.by_ref().find(|(_, c)| *c == '\n');
+            }
+            '/' if chars.peek().is_some_and(|(_, next)| *next == '*') => {
+                chars.next();
+                let mut previous = ' ';
+                for (_, c) in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            '"' => contexts.push(Context::String),
+            '(' => {
+                let name = text[..index]
+                    .trim_end()
+                    .rsplit(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == ':'))
+                    .next()
+                    .unwrap_or_default();
+                match name.chars().next() {
+                    Some(first) if first.is_ascii_alphabetic() || first == '_' => {
+                        contexts.push(Context::Call { name: name.to_string(), argument: 0 })
+                    }
+                    _ => contexts.push(Context::Group),
+                }
+            }
+            '[' | '{' => contexts.push(Context::Group),
+            ')' | ']' | '}' => {
+                contexts.pop();
+            }
+            ',' => {
+                if let Some(Context::Call { argument, .. }) = contexts.last_mut() {
+                    *argument += 1;
+                }
+            }
+            _ => {}
+        }
     }
+    contexts.into_iter().rev().find_map(|context| match context {
+        Context::Call { name, argument } => Some((name, argument)),
+        _ => None,
+    })
+}
 
-    let (version, _, reference) = API_REF.get(&function_name.to_string())?;
-    let FunctionAPI { signature, output_type, .. } = (*reference).as_ref()?;
+fn markdown(value: String) -> Documentation {
+    Documentation::MarkupContent(MarkupContent { kind: MarkupKind::Markdown, value })
+}
 
-    if version > &contract.clarity_version {
-        return None;
+impl AddonsSignatureIndex {
+    pub fn new(addons: &[Box<dyn Addon>]) -> Self {
+        let mut index = AddonsSignatureIndex::default();
+        for addon in addons.iter() {
+            let namespace = addon.get_namespace();
+            for (name, function) in addon.build_function_lookup().into_iter() {
+                index.functions.insert(format!("{}::{}", namespace, name), function);
+            }
+        }
+        index
     }
 
-    let signatures = signature
-        .split(" |")
-        .map(|mut signature| {
-            signature = signature.trim();
-            let mut signature_without_parenthesis = signature.chars();
-            signature_without_parenthesis.next();
-            signature_without_parenthesis.next_back();
-            let signature_without_parenthesis = signature_without_parenthesis.as_str();
-            let parameters = signature_without_parenthesis.split(' ').collect::<Vec<&str>>();
-            let (_, parameters) = parameters.split_first().expect("invalid signature format");
+    /// Returns the signature of the innermost function call open at a position, with the
+    /// argument under the cursor as active parameter.
+    pub fn get_signature_help(&self, source: &str, position: &Position) -> Option<SignatureHelp> {
+        let offset = position_to_offset(source, position);
+        let (name, argument) = find_open_call(source, offset)?;
+        // functions of the std addon can be called without their namespace
+        let name = if name.contains("::") { name } else { format!("std::{}", name) };
+        let function = self.functions.get(&name)?;
 
-            if active_parameter.unwrap_or_default() >= parameters.len().try_into().unwrap() {
-                if let Some(variadic_index) = parameters.iter().position(|p| p.contains("...")) {
-                    active_parameter = Some(variadic_index.try_into().unwrap());
-                }
+        let mut label = format!("{}(", name);
+        let mut parameters = vec![];
+        for (i, input) in function.inputs.iter().enumerate() {
+            if i > 0 {
+                label.push_str(", ");
             }
-            let label = if output_type.eq("Not Applicable") {
-                String::from(signature)
-            } else {
-                format!("{} -> {}", &signature, &output_type)
-            };
+            let start = label.chars().count() as u32;
+            label.push_str(&input.name);
+            let end = label.chars().count() as u32;
+            let typing = input.typing.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(" | ");
+            let requirement = if input.optional { "optional" } else { "required" };
+            parameters.push(ParameterInformation {
+                label: ParameterLabel::LabelOffsets([start, end]),
+                documentation: Some(markdown(format!(
+                    "`{}` ({}): {}",
+                    typing, requirement, input.documentation
+                ))),
+            });
+        }
+        label.push_str(&format!(") -> {}", function.output.typing.to_string()));
 
-            SignatureInformation {
-                active_parameter,
-                documentation: None,
+        Some(SignatureHelp {
+            signatures: vec![SignatureInformation {
                 label,
-                parameters: Some(
-                    parameters
-                        .iter()
-                        .map(|param| ParameterInformation {
-                            documentation: None,
-                            label: ParameterLabel::Simple(param.to_string()),
-                        })
-                        .collect::<Vec<ParameterInformation>>(),
-                ),
-            }
+                documentation: Some(markdown(function.documentation.clone())),
+                parameters: Some(parameters),
+                active_parameter: Some(argument as u32),
+            }],
+            active_signature: Some(0),
+            active_parameter: Some(argument as u32),
         })
-        .collect::<Vec<SignatureInformation>>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Some(signatures)
+    fn open_call(text: &str) -> Option<(String, usize)> {
+        find_open_call(text, text.len())
+    }
+
+    #[test]
+    fn test_find_open_call() {
+        assert_eq!(
+            open_call("value = evm::get_contract_from_foundry_project("),
+            Some(("evm::get_contract_from_foundry_project".into(), 0))
+        );
+        assert_eq!(open_call("value = encode_hex(\"a\", "), Some(("encode_hex".into(), 1)));
+        // commas in strings, templates, lists and nested calls aren't counted
+        assert_eq!(
+            open_call("value = f(\"a, b\", [1, 2], \"${g(1, 2)}\", "),
+            Some(("f".into(), 3))
+        );
+        // help is given for the innermost call open
+        assert_eq!(open_call("value = f(1, g(2, "), Some(("g".into(), 1)));
+        assert_eq!(open_call("value = f(1, g(2, 3), "), Some(("f".into(), 2)));
+        assert_eq!(open_call("value = f(\"(\", "), Some(("f".into(), 1)));
+        // a comment, a string or a group isn't a call
+        assert_eq!(open_call("# f(\nvalue = (1, "), None);
+        assert_eq!(open_call("value = f(1)"), None);
+    }
 }
@@ -1,298 +1,26 @@
 use lsp_types::{
-    CompletionItem, CompletionItemKind, CompletionItemLabelDetails, DocumentSymbol, Hover,
-    InsertTextFormat, InsertTextMode, MarkupContent, MarkupKind, MessageType, Position,
-    SignatureHelp,
+    CodeActionOrCommand, DocumentSymbol, Hover, MessageType, Position, Range, SignatureHelp,
+    SymbolInformation, Url, WorkspaceEdit,
 };
-use std::borrow::BorrowMut;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 use std::vec;
 use txtx_addon_kit::helpers::fs::{FileAccessor, FileLocation};
 use txtx_addon_kit::types::diagnostics::{Diagnostic as TxtxDiagnostic, DiagnosticLevel};
 use txtx_addon_kit::types::RunbookId;
 use txtx_addon_kit::Addon;
 use txtx_addon_network_evm::EvmNetworkAddon;
+use txtx_core::manifest::file::read_runbook_from_location;
+use txtx_core::manifest::WorkspaceManifest;
 use txtx_core::std::StdAddon;
 
 use super::requests::capabilities::InitializationOptions;
-
-lazy_static! {
-    pub static ref FUNCTIONS: Vec<CompletionItem> = {
-        let addons: Vec<Box<dyn Addon>> = vec![Box::new(StdAddon::new()), Box::new(EvmNetworkAddon::new())];
-        let mut completion_items = vec![];
-        for addon in addons.iter() {
-            for func in addon.get_functions() {
-                completion_items.push(lsp_types::CompletionItem {
-                    // The label of this completion item. By default
-                    // also the text that is inserted when selecting
-                    // this completion.
-                    label: format!("{}::{}", addon.get_namespace(), func.name),
-                    // Additional details for the label
-                    label_details: Some(CompletionItemLabelDetails {
-                        detail: Some(format!("1) {}", func.documentation)),
-                        description: Some(format!("2) {}", func.documentation))
-                    }), //Option<CompletionItemLabelDetails>,
-                    // The kind of this completion item. Based of the kind
-                    // an icon is chosen by the editor.
-                    kind: Some(CompletionItemKind::FUNCTION), //Option<CompletionItemKind>,
-                    // A human-readable string with additional information
-                    // about this item, like type or symbol information.
-                    detail: Some(format!("{}::{}({})", addon.get_namespace(), func.name, func.inputs.iter().map(|i| i.name.clone()).collect::<Vec<_>>().join(", "))), //Option<String>,
-                    // A human-readable string that represents a doc-comment.
-                    documentation: Some(lsp_types::Documentation::MarkupContent(MarkupContent {
-                        kind: MarkupKind::Markdown,
-                        value: format!("{}\n\n## Arguments\n{}\n\n## Example\n```hcl\n{}\n```", func.documentation, func.inputs.iter().map(|i| format!("`{}`: {}", i.name, i.documentation)).collect::<Vec<_>>().join("\n\n"), func.example)
-                    })),
-                    // Indicates if this item is deprecated.
-                    deprecated: None, //Option<bool>,
-                    // Select this item when showing.
-                    preselect: None, //Option<bool>,
-                    // A string that should be used when comparing this item
-                    // with other items. When `falsy` the label is used
-                    // as the sort text for this item.
-                    sort_text: None, // Option<String>,
-                    // A string that should be used when filtering a set of
-                    // completion items. When `falsy` the label is used as the
-                    // filter text for this item.
-                    filter_text: None, // Option<String>,
-                    // A string that should be inserted into a document when selecting
-                    // this completion. When `falsy` the label is used as the insert text
-                    // for this item.
-                    //
-                    // The `insertText` is subject to interpretation by the client side.
-                    // Some tools might not take the string literally. For example
-                    // VS Code when code complete is requested in this example
-                    // `con<cursor position>` and a completion item with an `insertText` of
-                    // `console` is provided it will only insert `sole`. Therefore it is
-                    // recommended to use `textEdit` instead since it avoids additional client
-                    // side interpretation.
-                    insert_text: Some(format!("{}::{}({})", addon.get_namespace(), func.name, func.inputs.iter().enumerate().map(|(i, input)| format!("${{{}:{}}}", i, input.name)).collect::<Vec<_>>().join(", "))),
-                    // The format of the insert text. The format applies to both the `insertText` property
-                    // and the `newText` property of a provided `textEdit`. If omitted defaults to `InsertTextFormat.PlainText`.
-                    insert_text_format: Some(InsertTextFormat::SNIPPET), // Option<InsertTextFormat>,
-                    // How whitespace and indentation is handled during completion
-                    // item insertion. If not provided the client's default value depends on
-                    // the `textDocument.completion.insertTextMode` client capability.
-                    insert_text_mode: Some(InsertTextMode::ADJUST_INDENTATION),
-                    // An edit which is applied to a document when selecting
-                    // this completion. When an edit is provided the value of
-                    // insertText is ignored.
-                    //
-                    // Most editors support two different operation when accepting a completion item. One is to insert a
-                    // completion text and the other is to replace an existing text with a completion text. Since this can
-                    // usually not predetermined by a server it can report both ranges. Clients need to signal support for
-                    // `InsertReplaceEdits` via the `textDocument.completion.insertReplaceSupport` client capability
-                    // property.
-                    //
-                    // *Note 1:* The text edit's range as well as both ranges from a insert replace edit must be a
-                    // [single line] and they must contain the position at which completion has been requested.
-                    // *Note 2:* If an `InsertReplaceEdit` is returned the edit's insert range must be a prefix of
-                    // the edit's replace range, that means it must be contained and starting at the same position.
-                    text_edit: None,
-                    // An optional array of additional text edits that are applied when
-                    // selecting this completion. Edits must not overlap with the main edit
-                    // nor with themselves.
-                    additional_text_edits: None,
-                    // An optional command that is executed *after* inserting this completion. *Note* that
-                    // additional modifications to the current document should be described with the
-                    // additionalTextEdits-property.
-                    command: None,
-                    // An optional set of characters that when pressed while this completion is
-                    // active will accept it first and then type that character. *Note* that all
-                    // commit characters should have `length=1` and that superfluous characters
-                    // will be ignored.
-                    commit_characters: None, //Option<Vec<String>>,
-                    data: None, // Option<Value>,
-                    ..Default::default()
-                });
-            }
-        }
-        completion_items
-    };
-
-    pub static ref ACTIONS: Vec<CompletionItem> = {
-        let addons: Vec<Box<dyn Addon>> = vec![Box::new(StdAddon::new()), Box::new(EvmNetworkAddon::new())];
-        let mut completion_items = vec![];
-        for addon in addons.iter() {
-            for action in addon.get_actions() {
-                let spec = action.expect_atomic_specification();
-                completion_items.push(lsp_types::CompletionItem {
-                    // The label of this completion item. By default
-                    // also the text that is inserted when selecting
-                    // this completion.
-                    label: format!("{}::{}", addon.get_namespace(), spec.matcher),
-                    // Additional details for the label
-                    label_details: None, //Option<CompletionItemLabelDetails>,
-                    // The kind of this completion item. Based of the kind
-                    // an icon is chosen by the editor.
-                    kind: Some(CompletionItemKind::CLASS), //Option<CompletionItemKind>,
-                    // A human-readable string with additional information
-                    // about this item, like type or symbol information.
-                    detail: Some(format!("action <name> \"{}::{}\" {{\n{}\n}}", addon.get_namespace(), spec.matcher, spec.inputs.iter().map(|i| i.name.clone()).collect::<Vec<_>>().join("\n"))), //Option<String>,
-                    // A human-readable string that represents a doc-comment.
-                    documentation: Some(lsp_types::Documentation::MarkupContent(MarkupContent {
-                        kind: MarkupKind::Markdown,
-                        value: format!("{}\n\n## Arguments\n{}\n\n## Example\n```hcl\n{}\n```", spec.documentation, spec.inputs.iter().map(|i| format!("`{}`: {}", i.name, i.documentation)).collect::<Vec<_>>().join("\n\n"), spec.example)
-                    })),
-                    // Indicates if this item is deprecated.
-                    deprecated: None, //Option<bool>,
-                    // Select this item when showing.
-                    preselect: None, //Option<bool>,
-                    // A string that should be used when comparing this item
-                    // with other items. When `falsy` the label is used
-                    // as the sort text for this item.
-                    sort_text: None, // Option<String>,
-                    // A string that should be used when filtering a set of
-                    // completion items. When `falsy` the label is used as the
-                    // filter text for this item.
-                    filter_text: None, // Option<String>,
-                    // A string that should be inserted into a document when selecting
-                    // this completion. When `falsy` the label is used as the insert text
-                    // for this item.
-                    //
-                    // The `insertText` is subject to interpretation by the client side.
-                    // Some tools might not take the string literally. For example
-                    // VS Code when code complete is requested in this example
-                    // `con<cursor position>` and a completion item with an `insertText` of
-                    // `console` is provided it will only insert `sole`. Therefore it is
-                    // recommended to use `textEdit` instead since it avoids additional client
-                    // side interpretation.
-                    insert_text: Some(format!("action \"${{1:name}}\" \"{}::{}\" {{\n{}\n}}", addon.get_namespace(), spec.matcher, spec.inputs.iter().enumerate().map(|(i, input)| format!("    // {}\n    {} = ${{{}:{}}}", input.documentation, input.name, i+2, input.name)).collect::<Vec<_>>().join("\n"))),
-                    // The format of the insert text. The format applies to both the `insertText` property
-                    // and the `newText` property of a provided `textEdit`. If omitted defaults to `InsertTextFormat.PlainText`.
-                    insert_text_format: Some(InsertTextFormat::SNIPPET), // Option<InsertTextFormat>,
-                    // How whitespace and indentation is handled during completion
-                    // item insertion. If not provided the client's default value depends on
-                    // the `textDocument.completion.insertTextMode` client capability.
-                    insert_text_mode: Some(InsertTextMode::ADJUST_INDENTATION),
-                    // An edit which is applied to a document when selecting
-                    // this completion. When an edit is provided the value of
-                    // insertText is ignored.
-                    //
-                    // Most editors support two different operation when accepting a completion item. One is to insert a
-                    // completion text and the other is to replace an existing text with a completion text. Since this can
-                    // usually not predetermined by a server it can report both ranges. Clients need to signal support for
-                    // `InsertReplaceEdits` via the `textDocument.completion.insertReplaceSupport` client capability
-                    // property.
-                    //
-                    // *Note 1:* The text edit's range as well as both ranges from a insert replace edit must be a
-                    // [single line] and they must contain the position at which completion has been requested.
-                    // *Note 2:* If an `InsertReplaceEdit` is returned the edit's insert range must be a prefix of
-                    // the edit's replace range, that means it must be contained and starting at the same position.
-                    text_edit: None,
-                    // An optional array of additional text edits that are applied when
-                    // selecting this completion. Edits must not overlap with the main edit
-                    // nor with themselves.
-                    additional_text_edits: None,
-                    // An optional command that is executed *after* inserting this completion. *Note* that
-                    // additional modifications to the current document should be described with the
-                    // additionalTextEdits-property.
-                    command: None,
-                    // An optional set of characters that when pressed while this completion is
-                    // active will accept it first and then type that character. *Note* that all
-                    // commit characters should have `length=1` and that superfluous characters
-                    // will be ignored.
-                    commit_characters: None, //Option<Vec<String>>,
-                    data: None, // Option<Value>,
-                    ..Default::default()
-                });
-            }
-        }
-        completion_items
-    };
-
-
-    pub static ref WALLETS: Vec<CompletionItem> = {
-        let addons: Vec<Box<dyn Addon>> = vec![Box::new(StdAddon::new()), Box::new(EvmNetworkAddon::new())];
-        let mut completion_items = vec![];
-        for addon in addons.iter() {
-            for signer in addon.get_signers() {
-                let spec = signer;
-                completion_items.push(lsp_types::CompletionItem {
-                    // The label of this completion item. By default
-                    // also the text that is inserted when selecting
-                    // this completion.
-                    label: format!("{}::{}", addon.get_namespace(), spec.matcher),
-                    // Additional details for the label
-                    label_details: None, //Option<CompletionItemLabelDetails>,
-                    // The kind of this completion item. Based of the kind
-                    // an icon is chosen by the editor.
-                    kind: Some(CompletionItemKind::CLASS), //Option<CompletionItemKind>,
-                    // A human-readable string with additional information
-                    // about this item, like type or symbol information.
-                    detail: Some(format!("signer <name> \"{}::{}\" {{\n{}\n}}", addon.get_namespace(), spec.matcher, spec.inputs.iter().map(|i| i.name.clone()).collect::<Vec<_>>().join("\n"))), //Option<String>,
-                    // A human-readable string that represents a doc-comment.
-                    documentation: Some(lsp_types::Documentation::MarkupContent(MarkupContent {
-                        kind: MarkupKind::Markdown,
-                        value: format!("{}\n\n## Arguments\n{}\n\n## Example\n```hcl\n{}\n```", spec.documentation, spec.inputs.iter().map(|i| format!("`{}`: {}", i.name, i.documentation)).collect::<Vec<_>>().join("\n\n"), spec.example)
-                    })),
-                    // Indicates if this item is deprecated.
-                    deprecated: None, //Option<bool>,
-                    // Select this item when showing.
-                    preselect: None, //Option<bool>,
-                    // A string that should be used when comparing this item
-                    // with other items. When `falsy` the label is used
-                    // as the sort text for this item.
-                    sort_text: None, // Option<String>,
-                    // A string that should be used when filtering a set of
-                    // completion items. When `falsy` the label is used as the
-                    // filter text for this item.
-                    filter_text: None, // Option<String>,
-                    // A string that should be inserted into a document when selecting
-                    // this completion. When `falsy` the label is used as the insert text
-                    // for this item.
-                    //
-                    // The `insertText` is subject to interpretation by the client side.
-                    // Some tools might not take the string literally. For example
-                    // VS Code when code complete is requested in this example
-                    // `con<cursor position>` and a completion item with an `insertText` of
-                    // `console` is provided it will only insert `sole`. Therefore it is
-                    // recommended to use `textEdit` instead since it avoids additional client
-                    // side interpretation.
-                    insert_text: Some(format!("signer \"${{1:name}}\" \"{}::{}\" {{\n{}\n}}", addon.get_namespace(), spec.matcher, spec.inputs.iter().enumerate().map(|(i, input)| format!("    // {}\n    {} = ${{{}:{}}}", input.documentation, input.name, i+2, input.name)).collect::<Vec<_>>().join("\n"))),
-                    // The format of the insert text. The format applies to both the `insertText` property
-                    // and the `newText` property of a provided `textEdit`. If omitted defaults to `InsertTextFormat.PlainText`.
-                    insert_text_format: Some(InsertTextFormat::SNIPPET), // Option<InsertTextFormat>,
-                    // How whitespace and indentation is handled during completion
-                    // item insertion. If not provided the client's default value depends on
-                    // the `textDocument.completion.insertTextMode` client capability.
-                    insert_text_mode: Some(InsertTextMode::ADJUST_INDENTATION),
-                    // An edit which is applied to a document when selecting
-                    // this completion. When an edit is provided the value of
-                    // insertText is ignored.
-                    //
-                    // Most editors support two different operation when accepting a completion item. One is to insert a
-                    // completion text and the other is to replace an existing text with a completion text. Since this can
-                    // usually not predetermined by a server it can report both ranges. Clients need to signal support for
-                    // `InsertReplaceEdits` via the `textDocument.completion.insertReplaceSupport` client capability
-                    // property.
-                    //
-                    // *Note 1:* The text edit's range as well as both ranges from a insert replace edit must be a
-                    // [single line] and they must contain the position at which completion has been requested.
-                    // *Note 2:* If an `InsertReplaceEdit` is returned the edit's insert range must be a prefix of
-                    // the edit's replace range, that means it must be contained and starting at the same position.
-                    text_edit: None,
-                    // An optional array of additional text edits that are applied when
-                    // selecting this completion. Edits must not overlap with the main edit
-                    // nor with themselves.
-                    additional_text_edits: None,
-                    // An optional command that is executed *after* inserting this completion. *Note* that
-                    // additional modifications to the current document should be described with the
-                    // additionalTextEdits-property.
-                    command: None,
-                    // An optional set of characters that when pressed while this completion is
-                    // active will accept it first and then type that character. *Note* that all
-                    // commit characters should have `length=1` and that superfluous characters
-                    // will be ignored.
-                    commit_characters: None, //Option<Vec<String>>,
-                    data: None, // Option<Value>,
-                    ..Default::default()
-                });
-            }
-        }
-        completion_items
-    };
-
-}
+use super::requests::completion::AddonsCompletionIndex;
+use super::requests::definitions::{is_valid_identifier, ConstructSymbol, DocumentSymbolTable};
+use super::requests::diagnostics::RunbookValidator;
+use super::requests::document_symbols::{get_document_symbols, get_workspace_symbols};
+use super::requests::hover::AddonsHoverIndex;
+use super::requests::signature_help::AddonsSignatureIndex;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct ActiveRunbookData {
@@ -355,38 +83,55 @@ pub struct EditorState {
     pub runbooks_lookup: HashMap<FileLocation, RunbookMetadata>,
     pub active_runbooks: HashMap<FileLocation, ActiveRunbookData>,
     pub settings: InitializationOptions,
+    pub completion_index: Arc<AddonsCompletionIndex>,
+    pub hover_index: Arc<AddonsHoverIndex>,
+    pub signature_index: Arc<AddonsSignatureIndex>,
+    pub validator: Arc<RunbookValidator>,
+    /// Constructs defined and referenced in the runbook sources opened, and in their siblings
+    pub documents_symbols: HashMap<FileLocation, DocumentSymbolTable>,
+    /// Latest content of the runbook sources opened, and of their siblings
+    pub documents_sources: HashMap<FileLocation, String>,
 }
 
 impl EditorState {
     pub fn new() -> EditorState {
+        let addons: Vec<Box<dyn Addon>> =
+            vec![Box::new(StdAddon::new()), Box::new(EvmNetworkAddon::new())];
+        EditorState::new_with_addons(&addons)
+    }
+
+    /// Builds an editor state completing and documenting the constructs and functions of the
+    /// given addons.
+    pub fn new_with_addons(addons: &[Box<dyn Addon>]) -> EditorState {
         EditorState {
             workspaces: HashMap::new(),
             runbooks_lookup: HashMap::new(),
             active_runbooks: HashMap::new(),
             settings: InitializationOptions::default(),
+            completion_index: Arc::new(AddonsCompletionIndex::new(addons)),
+            hover_index: Arc::new(AddonsHoverIndex::new(addons)),
+            signature_index: Arc::new(AddonsSignatureIndex::new(addons)),
+            validator: Arc::new(RunbookValidator::new(addons)),
+            documents_symbols: HashMap::new(),
+            documents_sources: HashMap::new(),
         }
     }
 
     pub fn index_workspace(&mut self, manifest_location: FileLocation, workspace: WorkspaceState) {
-        let mut base_location = manifest_location.clone();
+        let base_location =
+            manifest_location.get_parent_location().unwrap_or(manifest_location.clone());
 
-        match base_location.borrow_mut() {
-            FileLocation::FileSystem { path } => {
-                let mut parent = path.clone();
-                parent.pop();
-                parent.pop();
-            }
-            FileLocation::Url { url } => {
-                let mut segments = url.path_segments_mut().expect("could not find root location");
-                segments.pop();
-                segments.pop();
+        for (runbook_location, source) in workspace.sources.iter() {
+            // the content of the sources opened is more recent than the one on disk
+            if !self.active_runbooks.contains_key(runbook_location) {
+                self.index_runbook_source(runbook_location.clone(), source);
             }
-        };
+        }
 
         for (runbook_location, _runbook_state) in workspace.runbooks.iter() {
             let relative_path = runbook_location
                 .get_relative_path_from_base(&base_location)
-                .expect("could not find relative location");
+                .unwrap_or(runbook_location.to_string());
 
             self.runbooks_lookup.insert(
                 runbook_location.clone(),
@@ -424,89 +169,185 @@ impl EditorState {
 
     pub fn get_completion_items_for_runbook(
         &self,
-        _runbook_location: &FileLocation,
-        _position: &Position,
+        runbook_location: &FileLocation,
+        position: &Position,
     ) -> Vec<lsp_types::CompletionItem> {
-        // let active_runbook = match self.active_runbooks.get(runbook_location) {
-        //     Some(contract) => contract,
-        //     None => return vec![],
-        // };
-
-        // let modules = self
-        //     .runbooks_lookup
-        //     .get(runbook_location)
-        //     .and_then(|d| self.workspaces.get(&d.manifest_location))
-        //     .map(|p| p.get_contract_calls_for_contract(runbook_location))
-        //     .unwrap_or_default();
-
-        // let expressions = active_runbook.expressions.as_ref();
-        // let active_contract_defined_data =
-        // ContractDefinedData::new(expressions.unwrap_or(&vec![]), position);
-
-        // build_completion_item_list(
-        //     &active_runbook.clarity_version,
-        //     expressions.unwrap_or(&vec![]),
-        //     &Position {
-        //         line: position.line + 1,
-        //         character: position.character + 1,
-        //     },
-        //     &active_contract_defined_data,
-        //     contract_calls,
-        //     should_wrap,
-        //     self.settings.completion_include_native_placeholders,
-        // )
-        let functions = FUNCTIONS.clone();
-        let mut actions = ACTIONS.clone();
-        let mut signers = WALLETS.clone();
-        let mut completion_items = functions;
-        completion_items.append(&mut actions);
-        completion_items.append(&mut signers);
-        completion_items
+        let Some(active_runbook) = self.active_runbooks.get(runbook_location) else {
+            return vec![];
+        };
+        self.completion_index.get_completion_items(&active_runbook.source, position)
     }
 
     pub fn get_document_symbols_for_runbook(
         &self,
-        _runbook_location: &FileLocation,
+        runbook_location: &FileLocation,
     ) -> Vec<DocumentSymbol> {
-        vec![]
+        self.documents_sources
+            .get(runbook_location)
+            .and_then(|source| get_document_symbols(source))
+            .unwrap_or_default()
+    }
+
+    /// Searches the constructs of the runbooks of the workspaces, and of the sources opened.
+    pub fn get_workspace_symbols(&self, query: &str) -> Vec<SymbolInformation> {
+        let mut sources = self.documents_sources.iter().collect::<Vec<_>>();
+        sources.sort_by_key(|(location, _)| location.to_string());
+        let mut symbols = vec![];
+        for (location, source) in sources.into_iter() {
+            let Some(uri) = location.to_url_string().ok().and_then(|url| Url::parse(&url).ok())
+            else {
+                continue;
+            };
+            symbols.append(&mut get_workspace_symbols(&uri, source, query));
+        }
+        symbols
+    }
+
+    /// Returns the symbol tables of the sources of the runbook a source belongs to, i.e. the
+    /// sources of the same directory, starting with the source itself.
+    fn get_runbook_sources_symbols(
+        &self,
+        runbook_location: &FileLocation,
+    ) -> Vec<(&FileLocation, &DocumentSymbolTable)> {
+        let parent_location = runbook_location.get_parent_location().ok();
+        let mut sources = self
+            .documents_symbols
+            .iter()
+            .filter(|(location, _)| {
+                location.eq(&runbook_location)
+                    || (parent_location.is_some()
+                        && location.get_parent_location().ok().eq(&parent_location))
+            })
+            .collect::<Vec<_>>();
+        sources.sort_by_key(|(location, _)| !location.eq(&runbook_location));
+        sources
+    }
+
+    /// Returns the content of the other sources of the runbook a source belongs to.
+    fn get_runbook_siblings_sources(&self, runbook_location: &FileLocation) -> Vec<&str> {
+        let Ok(parent_location) = runbook_location.get_parent_location() else {
+            return vec![];
+        };
+        self.documents_sources
+            .iter()
+            .filter(|(location, _)| {
+                !location.eq(&runbook_location)
+                    && location.get_parent_location().ok().as_ref() == Some(&parent_location)
+            })
+            .map(|(_, source)| source.as_str())
+            .collect()
+    }
+
+    /// Validates a runbook source as `txtx check` does, resolving the constructs defined in the
+    /// other sources of its runbook.
+    pub fn get_runbook_diagnostics(&self, runbook_location: &FileLocation) -> Vec<TxtxDiagnostic> {
+        let Some(source) = self.documents_sources.get(runbook_location) else {
+            return vec![];
+        };
+        self.validator.validate(source, &self.get_runbook_siblings_sources(runbook_location))
+    }
+
+    pub fn get_code_actions(
+        &self,
+        runbook_location: &FileLocation,
+        uri: &Url,
+        range: &Range,
+    ) -> Vec<CodeActionOrCommand> {
+        let Some(source) = self.documents_sources.get(runbook_location) else {
+            return vec![];
+        };
+        let siblings = self.get_runbook_siblings_sources(runbook_location);
+        self.validator.get_code_actions(uri, source, &siblings, range)
     }
 
     pub fn get_definition_location(
         &self,
-        _runbook_location: &FileLocation,
-        _position: &Position,
+        runbook_location: &FileLocation,
+        position: &Position,
     ) -> Option<lsp_types::Location> {
-        // let runbook = self.active_runbooks.get(runbook_location)?;
-        // let _position = Position {
-        //     line: position.line + 1,
-        //     character: position.character + 1,
-        // };
-        None
+        let symbol = self.documents_symbols.get(runbook_location)?.symbol_at(position)?;
+        self.get_runbook_sources_symbols(runbook_location).into_iter().find_map(
+            |(location, symbols)| {
+                let definition = symbols.find_definition(symbol)?;
+                Some(lsp_types::Location {
+                    uri: lsp_types::Url::parse(&location.to_url_string().ok()?).ok()?,
+                    range: definition.range,
+                })
+            },
+        )
+    }
+
+    pub fn get_references(
+        &self,
+        runbook_location: &FileLocation,
+        position: &Position,
+        include_declaration: bool,
+    ) -> Vec<lsp_types::Location> {
+        let Some(symbol) =
+            self.documents_symbols.get(runbook_location).and_then(|s| s.symbol_at(position))
+        else {
+            return vec![];
+        };
+        let mut references = vec![];
+        for (location, symbols) in self.get_runbook_sources_symbols(runbook_location) {
+            let Some(uri) =
+                location.to_url_string().ok().and_then(|url| lsp_types::Url::parse(&url).ok())
+            else {
+                continue;
+            };
+            for reference in symbols.find_references(symbol, include_declaration) {
+                references.push(lsp_types::Location { uri: uri.clone(), range: reference.range });
+            }
+        }
+        references
+    }
+
+    /// Renames the construct defined or referenced at a position, in all the sources of its
+    /// runbook. The new name is rejected if it's not an identifier, or if it's already the name of
+    /// another construct of the same kind.
+    pub fn get_rename_edit(
+        &self,
+        runbook_location: &FileLocation,
+        position: &Position,
+        new_name: &str,
+    ) -> Result<Option<WorkspaceEdit>, String> {
+        let Some(symbol) =
+            self.documents_symbols.get(runbook_location).and_then(|s| s.symbol_at(position))
+        else {
+            return Ok(None);
+        };
+        if !is_valid_identifier(new_name) {
+            return Err(format!("'{}' is not a valid construct name", new_name));
+        }
+        let sources = self.get_runbook_sources_symbols(runbook_location);
+        let renamed = ConstructSymbol { name: new_name.to_string(), ..symbol.clone() };
+        if !symbol.name.eq(new_name)
+            && sources.iter().any(|(_, symbols)| symbols.find_definition(&renamed).is_some())
+        {
+            return Err(format!("{} '{}' is already defined", symbol.construct, new_name));
+        }
+
+        let mut changes = HashMap::new();
+        for (location, symbols) in sources.into_iter() {
+            let edits = symbols.rename_edits(symbol, new_name);
+            let Some(uri) = location.to_url_string().ok().and_then(|url| Url::parse(&url).ok())
+            else {
+                continue;
+            };
+            if !edits.is_empty() {
+                changes.insert(uri, edits);
+            }
+        }
+        Ok(Some(WorkspaceEdit { changes: Some(changes), ..WorkspaceEdit::default() }))
     }
 
     pub fn get_hover_data(
         &self,
-        _runbook_location: &FileLocation,
-        _position: &lsp_types::Position,
+        runbook_location: &FileLocation,
+        position: &lsp_types::Position,
     ) -> Option<Hover> {
-        // let runbook = self.active_runbooks.get(runbook_location)?;
-        // let position = Position {
-        //     line: position.line + 1,
-        //     character: position.character + 1,
-        // };
-        // let documentation = get_expression_documentation(
-        //     &position,
-        //     contract.clarity_version,
-        //     contract.expressions.as_ref()?,
-        // )?;
-
-        Some(Hover {
-            contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
-                kind: lsp_types::MarkupKind::Markdown,
-                value: "hover".to_string(),
-            }),
-            range: None,
-        })
+        let runbook = self.active_runbooks.get(runbook_location)?;
+        self.hover_index.get_hover(&runbook.source, position)
     }
 
     pub fn get_signature_help(
@@ -515,16 +356,9 @@ impl EditorState {
         position: &lsp_types::Position,
         _active_signature: Option<u32>,
     ) -> Option<SignatureHelp> {
-        let _runbook = self.active_runbooks.get(runbook_location)?;
-        let _position = Position { line: position.line + 1, character: position.character + 1 };
-        // let signatures = get_signatures(contract, &position)?;
-
-        // Some(SignatureHelp {
-        //     signatures,
-        //     active_signature,
-        //     active_parameter: None,
-        // })
-        None
+        // functions have a single signature, which stays the active one
+        let runbook = self.active_runbooks.get(runbook_location)?;
+        self.signature_index.get_signature_help(&runbook.source, position)
     }
 
     pub fn get_aggregated_diagnostics(
@@ -561,6 +395,16 @@ impl EditorState {
                 for note in state.notes.iter() {
                     diags.push(note.clone());
                 }
+
+                // Collect the diagnostics of the validation of the source
+                for diag in self.get_runbook_diagnostics(runbook_url) {
+                    if diag.is_error() {
+                        erroring_files.insert(relative_path.clone());
+                    } else if diag.is_warning() {
+                        warning_files.insert(relative_path.clone());
+                    }
+                    diags.push(diag);
+                }
                 runbooks.push((runbook_url.clone(), diags));
             }
         }
@@ -595,20 +439,32 @@ impl EditorState {
 
     pub fn insert_active_runbook(&mut self, runbook_location: FileLocation, source: &str) {
         let runbook = ActiveRunbookData::new(source);
+        self.index_runbook_source(runbook_location.clone(), source);
         self.active_runbooks.insert(runbook_location, runbook);
     }
 
+    /// Indexes the constructs of a runbook source. The previous index is kept if the source can't
+    /// be parsed, which is usually the case while it's being edited.
+    pub fn index_runbook_source(&mut self, runbook_location: FileLocation, source: &str) {
+        if let Some(symbols) = DocumentSymbolTable::new(source) {
+            self.documents_symbols.insert(runbook_location.clone(), symbols);
+        }
+        self.documents_sources.insert(runbook_location, source.to_string());
+    }
+
     pub fn update_active_contract(
         &mut self,
         runbook_location: &FileLocation,
-        _source: &str,
+        source: &str,
         _with_definitions: bool,
     ) -> Result<(), String> {
-        let _runbook = self
+        let runbook = self
             .active_runbooks
             .get_mut(runbook_location)
             .ok_or("contract not in active_contracts")?;
         // runbook.update_sources(source, with_definitions);
+        runbook.source = source.to_string();
+        self.index_runbook_source(runbook_location.clone(), source);
         Ok(())
     }
 }
@@ -616,6 +472,8 @@ impl EditorState {
 #[derive(Clone, Default, Debug)]
 pub struct WorkspaceState {
     runbooks: HashMap<FileLocation, RunbookState>,
+    /// Content of the runbook sources, as read when the workspace was built
+    sources: HashMap<FileLocation, String>,
 }
 
 impl WorkspaceState {
@@ -696,81 +554,52 @@ impl WorkspaceState {
 }
 
 pub async fn build_state(
-    _manifest_location: &FileLocation,
-    _workspace_state: &mut WorkspaceState,
-    _file_accessor: Option<&dyn FileAccessor>,
+    manifest_location: &FileLocation,
+    workspace_state: &mut WorkspaceState,
+    file_accessor: Option<&dyn FileAccessor>,
 ) -> Result<(), String> {
-    // let manifest = match file_accessor {
-    //     None => WorkspaceManifest::from_location(manifest_location)?,
-    //     Some(file_accessor) => {
-    //         WorkspaceManifest::from_file_accessor(manifest_location, file_accessor).await?
-    //     }
-    // };
-
-    //     let (_manifest, _runbook_name, mut runbook, runbook_state) =
-    //     load_runbook_from_manifest(&cmd.manifest_path, &cmd.runbook, &cmd.environment).await?;
-
-    // match &runbook_state {
-    //     Some(RunbookState::File(state_file_location)) => {
-    //         let ctx = RunbookSnapshotContext::new();
-    //         let old = load_runbook_execution_snapshot(state_file_location)?;
-    //         for run in runbook.running_contexts.iter_mut() {
-    //             let frontier = HashSet::new();
-    //             let _res = run
-    //                 .execution_context
-    //                 .simulate_execution(
-    //                     &runbook.runtime_context,
-    //                     &run.workspace_context,
-    //                     &runbook.supervision_context,
-    //                     &frontier,
-    //                 )
-    //                 .await;
-    //         }
-
-    // for (contract_id, mut result) in contracts.into_iter() {
-    //     let (_, runbook_location) = match deployment.contracts.get(&contract_id) {
-    //         Some(entry) => entry,
-    //         None => continue,
-    //     };
-    //     locations.insert(contract_id.clone(), runbook_location.clone());
-    //     if let Some(contract_metadata) = manifest.contracts_settings.get(runbook_location) {
-    //         clarity_versions.insert(contract_id.clone(), contract_metadata.clarity_version);
-    //     }
-
-    //     match result {
-    //         Ok(mut execution_result) => {
-    //             if let Some(entry) = artifacts.diags.get_mut(&contract_id) {
-    //                 entry.append(&mut execution_result.diagnostics);
-    //             }
-
-    //             if let EvaluationResult::Contract(contract_result) = execution_result.result {
-    //                 if let Some(ast) = artifacts.asts.get(&contract_id) {
-    //                     definitions.insert(
-    //                         contract_id.clone(),
-    //                         get_public_function_definitions(&ast.expressions),
-    //                     );
-    //                 }
-    //                 analyses.insert(contract_id.clone(), Some(contract_result.contract.analysis));
-    //             };
-    //         }
-    //         Err(ref mut diags) => {
-    //             if let Some(entry) = artifacts.diags.get_mut(&contract_id) {
-    //                 entry.append(diags);
-    //             }
-    //             continue;
-    //         }
-    //     };
-    // }
-
-    // protocol_state.consolidate(
-    //     &mut locations,
-    //     &mut artifacts.asts,
-    //     &mut artifacts.deps,
-    //     &mut artifacts.diags,
-    //     &mut definitions,
-    //     &mut analyses,
-    //     &mut clarity_versions,
-    // );
+    let manifest = match file_accessor {
+        None => WorkspaceManifest::from_location(manifest_location)?,
+        Some(file_accessor) => {
+            WorkspaceManifest::from_file_accessor(manifest_location, file_accessor).await?
+        }
+    };
+    let root_location = manifest_location.get_parent_location()?;
+
+    for runbook_metadata in manifest.runbooks.iter() {
+        let mut runbook_location = root_location.clone();
+        runbook_location.append_path(&runbook_metadata.location)?;
+        let runbook_id = RunbookId::new(None, None, &runbook_metadata.name);
+
+        // a runbook is either a source, or a directory of sources which can only be listed on
+        // the file system
+        let sources = match file_accessor {
+            None => match read_runbook_from_location(
+                &runbook_location,
+                &runbook_metadata.description,
+                &None,
+                Some(&runbook_metadata.name),
+            ) {
+                Ok((_, _, sources)) => sources
+                    .tree
+                    .into_iter()
+                    .map(|(location, (_, content))| (location, content.to_string()))
+                    .collect::<Vec<_>>(),
+                Err(_) => continue,
+            },
+            Some(file_accessor) => {
+                match file_accessor.read_file(runbook_location.to_string()).await {
+                    Ok(source) => vec![(runbook_location, source)],
+                    Err(_) => continue,
+                }
+            }
+        };
 
+        for (location, source) in sources.into_iter() {
+            let runbook_state = RunbookState::new(runbook_id.clone(), vec![], location.clone());
+            workspace_state.runbooks.insert(location.clone(), runbook_state);
+            workspace_state.sources.insert(location, source);
+        }
+    }
     Ok(())
 }
@@ -1,6 +1,6 @@
 use lsp_types::Diagnostic as LspDiagnostic;
 use lsp_types::Url;
-use lsp_types::{DiagnosticSeverity, Position, Range};
+use lsp_types::{CodeDescription, DiagnosticSeverity, NumberOrString, Position, Range};
 use txtx_addon_kit::helpers::fs::FileLocation;
 use txtx_addon_kit::types::diagnostics::{
     Diagnostic as TxtxDiagnostic, DiagnosticLevel as TxtxLevel,
@@ -41,8 +41,12 @@ pub fn txtx_diagnostic_to_lsp_type(diagnostic: &TxtxDiagnostic) -> LspDiagnostic
             TxtxLevel::Warning => Some(DiagnosticSeverity::WARNING),
             TxtxLevel::Note => Some(DiagnosticSeverity::INFORMATION),
         },
-        code: None,
-        code_description: None,
+        code: diagnostic.code.clone().map(NumberOrString::String),
+        code_description: diagnostic
+            .help_url
+            .as_ref()
+            .and_then(|url| Url::parse(url).ok())
+            .map(|href| CodeDescription { href }),
         source: Some("txtx".to_string()),
         message: diagnostic.message.clone(),
         related_information: None,
@@ -66,3 +70,21 @@ pub fn get_runbook_location(text_document_uri: &Url) -> Option<FileLocation> {
     }
     FileLocation::try_parse(&file_location, None)
 }
+
+/// Lists the other txtx sources of the directory of a runbook source, which are part of the same
+/// runbook. Sources that are not on the file system are not listed.
+pub fn get_runbook_sibling_locations(runbook_location: &FileLocation) -> Vec<FileLocation> {
+    let FileLocation::FileSystem { path } = runbook_location else {
+        return vec![];
+    };
+    let Some(Ok(entries)) = path.parent().map(std::fs::read_dir) else {
+        return vec![];
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|entry_path| entry_path.extension().is_some_and(|ext| ext.eq("tx")))
+        .filter(|entry_path| !entry_path.eq(path))
+        .map(FileLocation::from_path)
+        .collect()
+}
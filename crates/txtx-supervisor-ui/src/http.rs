@@ -9,21 +9,91 @@ use actix_web::{middleware, App, HttpRequest, HttpResponse, HttpServer};
 use juniper_actix::{graphiql_handler, graphql_handler, playground_handler, subscriptions};
 use juniper_graphql_ws::ConnectionConfig;
 use std::error::Error as StdError;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::RwLock;
 use txtx_core::kit::types::frontend::{ClientType, DiscoveryResponse};
+use txtx_core::kit::Addon;
 use txtx_gql::Context as GraphContext;
+
+use crate::sessions::{list_sessions, load_session, SessionStoreConfig};
+use crate::summary::{ExecutionSummary, RunbookCompletionStore};
 use txtx_gql::{new_graphql_schema, GraphqlSchema};
 
+/// Binds the supervisor to `port` on every address `network_binding_ip_address` resolves to
+/// (e.g. both `127.0.0.1` and `::1` for `localhost`), or to the first of the
+/// `port_fallback_range` successive ports available on all of them if it's already in use.
+pub fn bind_listeners(
+    network_binding_ip_address: &str,
+    port: u16,
+    port_fallback_range: u16,
+) -> Result<Vec<TcpListener>, String> {
+    let addresses = (network_binding_ip_address, port)
+        .to_socket_addrs()
+        .map_err(|e| format!("unable to resolve {network_binding_ip_address}: {e}"))?
+        .collect::<Vec<_>>();
+    let last_port = port.saturating_add(port_fallback_range);
+    let mut candidate = port;
+    loop {
+        match bind_addresses(&addresses, candidate) {
+            Ok(listeners) if listeners.is_empty() => {
+                return Err(format!("no address of {network_binding_ip_address} can be bound"))
+            }
+            Ok(listeners) => return Ok(listeners),
+            Err(e) if e.kind() == ErrorKind::AddrInUse && candidate < last_port => candidate += 1,
+            Err(e) if e.kind() == ErrorKind::AddrInUse && candidate == port => {
+                return Err(format!("port {port} is already in use"))
+            }
+            Err(e) if e.kind() == ErrorKind::AddrInUse => {
+                return Err(format!("ports {port} to {last_port} are already in use"))
+            }
+            Err(e) => {
+                return Err(format!("unable to bind {network_binding_ip_address}:{candidate}: {e}"))
+            }
+        }
+    }
+}
+
+/// Binds every address to `port`, skipping the addresses unavailable on this host (e.g. `::1`
+/// when IPv6 is disabled). When `port` is 0, the port picked for the first address is reused
+/// for the others.
+fn bind_addresses(addresses: &[SocketAddr], port: u16) -> std::io::Result<Vec<TcpListener>> {
+    let mut listeners: Vec<TcpListener> = vec![];
+    for address in addresses {
+        let port = match listeners.first() {
+            Some(listener) => listener.local_addr()?.port(),
+            None => port,
+        };
+        match TcpListener::bind(SocketAddr::new(address.ip(), port)) {
+            Ok(listener) => listeners.push(listener),
+            Err(e) if e.kind() == ErrorKind::AddrNotAvailable => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(listeners)
+}
+
 pub async fn start_server(
     gql_context: GraphContext,
-    network_binding: &str,
+    listeners: Vec<TcpListener>,
+    session_store: Option<SessionStoreConfig>,
+    completion_store: Arc<RwLock<RunbookCompletionStore>>,
+    addons: Vec<Box<dyn Addon>>,
 ) -> Result<ServerHandle, Box<dyn StdError>> {
     let gql_context = Data::new(gql_context);
+    let session_store = Data::new(session_store);
+    let completion_store = Data::new(completion_store);
+    let addons = Data::new(addons);
 
-    let server = HttpServer::new(move || {
+    let mut server = HttpServer::new(move || {
         App::new()
             .app_data(Data::new(new_graphql_schema()))
             .app_data(gql_context.clone())
+            .app_data(session_store.clone())
+            .app_data(completion_store.clone())
+            .app_data(addons.clone())
             .wrap(
                 Cors::default()
                     .allow_any_origin()
@@ -37,7 +107,11 @@ pub async fn start_server(
             .wrap(middleware::Logger::default())
             .service(
                 web::scope("/api/v1")
-                    .route("/discovery", web::get().to(discovery)),
+                    .route("/discovery", web::get().to(discovery))
+                    .route("/sessions", web::get().to(get_sessions))
+                    .route("/sessions/{session_id}", web::get().to(get_session))
+                    .route("/summary.json", web::get().to(get_summary_json))
+                    .route("/summary.md", web::get().to(get_summary_markdown)),
             )
             .service(
                 web::scope("/gql/v1")
@@ -49,9 +123,11 @@ pub async fn start_server(
             .service(web::resource("/graphiql").route(web::get().to(graphiql)))
             .service(dist)
     })
-    .workers(5)
-    .bind(network_binding)?
-    .run();
+    .workers(5);
+    for listener in listeners {
+        server = server.listen(listener)?;
+    }
+    let server = server.run();
     let handle = server.handle();
     tokio::spawn(server);
 
@@ -107,6 +183,75 @@ async fn discovery() -> impl Responder {
         .json(DiscoveryResponse { needs_credentials: false, client_type: ClientType::Operator })
 }
 
+async fn get_sessions(session_store: Data<Option<SessionStoreConfig>>) -> impl Responder {
+    match session_store.as_ref() {
+        Some(config) => HttpResponse::Ok().json(list_sessions(&config.dir)),
+        None => HttpResponse::NotFound().body("sessions are not persisted"),
+    }
+}
+
+async fn get_session(
+    session_id: web::Path<String>,
+    session_store: Data<Option<SessionStoreConfig>>,
+) -> impl Responder {
+    let Some(config) = session_store.as_ref() else {
+        return HttpResponse::NotFound().body("sessions are not persisted");
+    };
+    match load_session(&config.dir, &session_id, config.max_rotated_log_files) {
+        Ok(Some(session)) => HttpResponse::Ok().json(session),
+        Ok(None) => HttpResponse::NotFound().body("404 Not Found"),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+async fn get_summary_json(
+    context: Data<GraphContext>,
+    completion_store: Data<Arc<RwLock<RunbookCompletionStore>>>,
+    addons: Data<Vec<Box<dyn Addon>>>,
+) -> impl Responder {
+    let block_store = context.block_store.read().await;
+    let construct_store = context.construct_store.read().await;
+    let completion = completion_store.read().await;
+    let summary = ExecutionSummary {
+        runbook_name: &context.runbook_name,
+        block_store: &block_store,
+        construct_store: &construct_store,
+        completion: &completion,
+    };
+    HttpResponse::Ok()
+        .insert_header(attachment(&context.runbook_name, "json"))
+        .json(summary.to_json(&addons))
+}
+
+async fn get_summary_markdown(
+    context: Data<GraphContext>,
+    completion_store: Data<Arc<RwLock<RunbookCompletionStore>>>,
+    addons: Data<Vec<Box<dyn Addon>>>,
+) -> impl Responder {
+    let block_store = context.block_store.read().await;
+    let construct_store = context.construct_store.read().await;
+    let completion = completion_store.read().await;
+    let summary = ExecutionSummary {
+        runbook_name: &context.runbook_name,
+        block_store: &block_store,
+        construct_store: &construct_store,
+        completion: &completion,
+    };
+    HttpResponse::Ok()
+        .content_type("text/markdown; charset=utf-8")
+        .insert_header(attachment(&context.runbook_name, "md"))
+        .body(summary.to_markdown(&addons))
+}
+
+fn attachment(runbook_name: &str, extension: &str) -> header::ContentDisposition {
+    header::ContentDisposition {
+        disposition: header::DispositionType::Attachment,
+        parameters: vec![header::DispositionParam::Filename(format!(
+            "{runbook_name}-summary.{extension}"
+        ))],
+    }
+}
+
 async fn post_graphql(
     req: HttpRequest,
     payload: web::Payload,
@@ -138,6 +283,7 @@ async fn subscriptions(
         runbook_description: context.runbook_description.clone(),
         block_store: context.block_store.clone(),
         log_store: context.log_store.clone(),
+        construct_store: context.construct_store.clone(),
         block_broadcaster: context.block_broadcaster.clone(),
         log_broadcaster: context.log_broadcaster.clone(),
         action_item_events_tx: context.action_item_events_tx.clone(),
@@ -146,3 +292,24 @@ async fn subscriptions(
     let config = config.with_keep_alive_interval(Duration::from_secs(15));
     subscriptions::ws_handler(req, stream, schema.into_inner(), config).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_binds_every_address_of_localhost_on_the_same_port() {
+        let listeners = bind_listeners("localhost", 0, 0).unwrap();
+        let expected = ("localhost", 0).to_socket_addrs().unwrap().count();
+        assert!(!listeners.is_empty() && listeners.len() <= expected);
+        let port = listeners[0].local_addr().unwrap().port();
+        for listener in listeners.iter() {
+            assert_eq!(listener.local_addr().unwrap().port(), port);
+        }
+
+        // the port is now busy on every address, so the next one is picked
+        let fallback = bind_listeners("localhost", port, 1).unwrap();
+        assert!(fallback.iter().all(|listener| listener.local_addr().unwrap().port() != port));
+        assert!(bind_listeners("localhost", port, 0).is_err());
+    }
+}
@@ -1,27 +1,35 @@
 pub mod http;
+pub mod sessions;
+pub mod summary;
 
 use std::{collections::BTreeMap, sync::Arc};
 
 use actix_web::dev::ServerHandle;
 use include_dir::{include_dir, Dir};
+use sessions::{record_session, SessionRecorder, SessionStoreConfig};
+use summary::RunbookCompletionStore;
 use tokio::sync::{broadcast::Sender as TokioBroadcastSender, RwLock};
 use txtx_addon_kit::{
     channel::Sender,
     types::frontend::{
-        ActionItemResponse, Block as ActionBlock, BlockEvent, LogEvent, SupervisorAddonData,
+        ActionItemResponse, Block as ActionBlock, BlockEvent, ConstructStatusStore, LogEvent,
+        SupervisorAddonData,
     },
+    Addon,
 };
 use txtx_gql::Context as GqlContext;
 
 pub const OUT_DIR: &str = env!("OUT_DIR");
 pub static ASSETS: Dir<'_> = include_dir!("$OUT_DIR/supervisor");
 
-pub const DEFAULT_BINDING_PORT: &str = "8488";
+pub const DEFAULT_BINDING_PORT: u16 = 8488;
 pub const DEFAULT_BINDING_ADDRESS: &str = "localhost";
+/// Number of successive ports tried when the default port is already in use.
+pub const DEFAULT_PORT_FALLBACK_RANGE: &str = "10";
 
 #[derive(Debug, Clone)]
 pub enum SupervisorEvents {
-    /// The supervisor has started, with the network binding it's running on.
+    /// The supervisor has started, with the network binding it's actually running on.
     Started(String),
 }
 
@@ -31,14 +39,30 @@ pub async fn start_supervisor_ui(
     supervisor_addon_data: Vec<SupervisorAddonData>,
     block_store: Arc<RwLock<BTreeMap<usize, ActionBlock>>>,
     log_store: Arc<RwLock<Vec<LogEvent>>>,
+    construct_store: Arc<RwLock<ConstructStatusStore>>,
+    completion_store: Arc<RwLock<RunbookCompletionStore>>,
+    addons: Vec<Box<dyn Addon>>,
     block_broadcaster: TokioBroadcastSender<BlockEvent>,
     log_broadcaster: TokioBroadcastSender<LogEvent>,
     action_item_events_tx: TokioBroadcastSender<ActionItemResponse>,
     _kill_loops_tx: Sender<bool>,
     network_binding_ip_address: &str,
     network_binding_port: u16,
+    port_fallback_range: u16,
     supervisor_events_tx: Sender<SupervisorEvents>,
+    session_store: Option<SessionStoreConfig>,
 ) -> Result<ServerHandle, String> {
+    if let Some(config) = session_store.clone() {
+        let recorder = SessionRecorder::start(config, &runbook_name)?;
+        tokio::spawn(record_session(
+            recorder,
+            log_store.clone(),
+            block_store.clone(),
+            log_broadcaster.subscribe(),
+            block_broadcaster.subscribe(),
+        ));
+    }
+
     let gql_context = GqlContext {
         protocol_name: runbook_name.clone(),
         runbook_name,
@@ -46,17 +70,24 @@ pub async fn start_supervisor_ui(
         runbook_description,
         block_store,
         log_store,
+        construct_store,
         block_broadcaster: block_broadcaster.clone(),
         log_broadcaster: log_broadcaster.clone(),
         action_item_events_tx: action_item_events_tx.clone(),
     };
 
-    let network_binding = format!("{}:{}", network_binding_ip_address, network_binding_port);
-    let _ = supervisor_events_tx.send(SupervisorEvents::Started(network_binding.clone()));
+    let listeners =
+        http::bind_listeners(network_binding_ip_address, network_binding_port, port_fallback_range)
+            .map_err(|e| format!("Failed to start web ui: {e}"))?;
+    let port =
+        listeners[0].local_addr().map_err(|e| format!("Failed to start web ui: {e}"))?.port();
+    let network_binding = format!("{}:{}", network_binding_ip_address, port);
 
-    let handle = http::start_server(gql_context, &network_binding)
-        .await
-        .map_err(|e| format!("Failed to start web ui: {e}"))?;
+    let handle =
+        http::start_server(gql_context, listeners, session_store, completion_store, addons)
+            .await
+            .map_err(|e| format!("Failed to start web ui: {e}"))?;
+    let _ = supervisor_events_tx.send(SupervisorEvents::Started(network_binding));
 
     Ok(handle)
 }
@@ -0,0 +1,478 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use log::warn;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver as TokioBroadcastReceiver;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+use txtx_addon_kit::serde_json::{self, json, Value as JsonValue};
+use txtx_addon_kit::types::frontend::{ActionItemRequestType, Block, BlockEvent, LogEvent, Panel};
+
+/// Size of a session log file triggering its rotation.
+pub const DEFAULT_MAX_SESSION_LOG_BYTES: u64 = 10 * 1024 * 1024;
+/// Number of rotated log files kept per session, the oldest ones being discarded.
+pub const DEFAULT_MAX_ROTATED_LOG_FILES: usize = 2;
+/// Number of sessions kept in the sessions directory, the oldest ones being discarded.
+pub const DEFAULT_MAX_SESSIONS: usize = 20;
+/// The block store is written at most once per interval, whatever the number of block events.
+const BLOCKS_WRITE_INTERVAL: Duration = Duration::from_millis(500);
+
+const LOGS_EXTENSION: &str = "logs.jsonl";
+const BLOCKS_EXTENSION: &str = "blocks.json";
+
+#[derive(Debug, Clone)]
+pub struct SessionStoreConfig {
+    pub dir: PathBuf,
+    pub max_log_bytes: u64,
+    pub max_rotated_log_files: usize,
+    pub max_sessions: usize,
+}
+
+impl SessionStoreConfig {
+    pub fn new(dir: PathBuf) -> Self {
+        SessionStoreConfig {
+            dir,
+            max_log_bytes: DEFAULT_MAX_SESSION_LOG_BYTES,
+            max_rotated_log_files: DEFAULT_MAX_ROTATED_LOG_FILES,
+            max_sessions: DEFAULT_MAX_SESSIONS,
+        }
+    }
+}
+
+/// A supervised execution persisted to disk: its log events are appended to
+/// `<id>.logs.jsonl` (rotated to `<id>.logs.<n>.jsonl`), and the latest snapshot of its block
+/// store is written to `<id>.blocks.json`.
+pub struct SessionRecorder {
+    config: SessionStoreConfig,
+    id: String,
+    log_file: Option<File>,
+    log_bytes: u64,
+    persisted_logs: usize,
+}
+
+impl SessionRecorder {
+    pub fn start(config: SessionStoreConfig, runbook_name: &str) -> Result<Self, String> {
+        fs::create_dir_all(&config.dir)
+            .map_err(|e| format!("unable to create sessions directory: {e}"))?;
+        prune_sessions(&config.dir, config.max_sessions.saturating_sub(1));
+
+        let runbook_name = runbook_name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect::<String>();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let id = format!("{runbook_name}-{now}");
+
+        Ok(SessionRecorder { config, id, log_file: None, log_bytes: 0, persisted_logs: 0 })
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn log_path(&self, rotation: usize) -> PathBuf {
+        log_path(&self.config.dir, &self.id, rotation)
+    }
+
+    /// Appends the log events of the store that were not persisted yet.
+    pub fn append_logs(&mut self, log_store: &Vec<LogEvent>) -> Result<(), String> {
+        if log_store.len() < self.persisted_logs {
+            self.persisted_logs = 0;
+        }
+        for log_event in log_store.iter().skip(self.persisted_logs) {
+            let mut line = serde_json::to_string(log_event)
+                .map_err(|e| format!("unable to serialize log event: {e}"))?;
+            line.push('\n');
+            if self.log_bytes > 0 && self.log_bytes + line.len() as u64 > self.config.max_log_bytes
+            {
+                self.rotate_logs()?;
+            }
+            if self.log_file.is_none() {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.log_path(0))
+                    .map_err(|e| format!("unable to open session log file: {e}"))?;
+                self.log_file = Some(file);
+            }
+            let file = self.log_file.as_mut().unwrap();
+            file.write_all(line.as_bytes())
+                .map_err(|e| format!("unable to write session log file: {e}"))?;
+            self.log_bytes += line.len() as u64;
+            self.persisted_logs += 1;
+        }
+        Ok(())
+    }
+
+    fn rotate_logs(&mut self) -> Result<(), String> {
+        self.log_file = None;
+        self.log_bytes = 0;
+        let max_rotated = self.config.max_rotated_log_files;
+        if max_rotated == 0 {
+            return fs::remove_file(self.log_path(0))
+                .map_err(|e| format!("unable to rotate session log file: {e}"));
+        }
+        let _ = fs::remove_file(self.log_path(max_rotated));
+        for rotation in (0..max_rotated).rev() {
+            let path = self.log_path(rotation);
+            if path.exists() {
+                fs::rename(&path, self.log_path(rotation + 1))
+                    .map_err(|e| format!("unable to rotate session log file: {e}"))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn blocks_path(&self) -> PathBuf {
+        blocks_path(&self.config.dir, &self.id)
+    }
+}
+
+pub fn write_blocks(path: &Path, block_store: &BTreeMap<usize, Block>) -> Result<(), String> {
+    let blocks = block_store.values().map(redact_sensitive_inputs).collect::<Vec<_>>();
+    let blocks =
+        serde_json::to_vec(&blocks).map_err(|e| format!("unable to serialize block store: {e}"))?;
+    fs::write(path, blocks).map_err(|e| format!("unable to write session blocks: {e}"))
+}
+
+/// Drops the values of the sensitive inputs (passphrases...) of a block, which are never persisted.
+fn redact_sensitive_inputs(block: &Block) -> Block {
+    let mut block = block.clone();
+    let groups = match &mut block.panel {
+        Panel::ActionPanel(data) => &mut data.groups,
+        Panel::ModalPanel(data) => &mut data.groups,
+        Panel::ErrorPanel(_) => return block,
+    };
+    let action_items = groups
+        .iter_mut()
+        .flat_map(|group| group.sub_groups.iter_mut())
+        .flat_map(|sub_group| sub_group.action_items.iter_mut());
+    for item in action_items {
+        if let ActionItemRequestType::ProvideInput(request) = &mut item.action_type {
+            if request.sensitive {
+                request.default_value = None;
+            }
+        }
+    }
+    block
+}
+
+/// Persists the log events and the block store of the running session as they are broadcasted.
+pub async fn record_session(
+    mut recorder: SessionRecorder,
+    log_store: Arc<RwLock<Vec<LogEvent>>>,
+    block_store: Arc<RwLock<BTreeMap<usize, Block>>>,
+    mut log_rx: TokioBroadcastReceiver<LogEvent>,
+    mut block_rx: TokioBroadcastReceiver<BlockEvent>,
+) {
+    let blocks_path = recorder.blocks_path();
+    tokio::spawn(async move {
+        loop {
+            match block_rx.recv().await {
+                Ok(BlockEvent::LogEvent(_)) | Ok(BlockEvent::Heartbeat(_)) => continue,
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+            // the events received in the meantime are covered by the same write
+            let closed =
+                skip_block_events_until(&mut block_rx, Instant::now() + BLOCKS_WRITE_INTERVAL)
+                    .await;
+            let block_store = block_store.read().await;
+            if let Err(e) = write_blocks(&blocks_path, &block_store) {
+                warn!(target: "txtx::supervisor", "failed to persist supervisor session: {e}");
+            }
+            if closed {
+                break;
+            }
+        }
+    });
+
+    loop {
+        // the store is the source of truth, lagging behind the broadcaster only delays writes
+        let closed = matches!(log_rx.recv().await, Err(RecvError::Closed));
+        let log_store = log_store.read().await;
+        if let Err(e) = recorder.append_logs(&log_store) {
+            warn!(target: "txtx::supervisor", "failed to persist supervisor session: {e}");
+        }
+        if closed {
+            break;
+        }
+    }
+}
+
+/// Skips the block events received until `deadline`, returning whether the channel was closed.
+async fn skip_block_events_until(
+    block_rx: &mut TokioBroadcastReceiver<BlockEvent>,
+    deadline: Instant,
+) -> bool {
+    loop {
+        match tokio::time::timeout_at(deadline, block_rx.recv()).await {
+            Err(_) => return false,
+            Ok(Err(RecvError::Closed)) => return true,
+            Ok(_) => continue,
+        }
+    }
+}
+
+fn log_path(dir: &Path, id: &str, rotation: usize) -> PathBuf {
+    match rotation {
+        0 => dir.join(format!("{id}.{LOGS_EXTENSION}")),
+        rotation => dir.join(format!("{id}.logs.{rotation}.jsonl")),
+    }
+}
+
+fn blocks_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{id}.{BLOCKS_EXTENSION}"))
+}
+
+fn is_valid_session_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Returns the ids of the sessions stored in `dir`, from the most recent to the oldest, along
+/// with the total size of their files.
+fn collect_sessions(dir: &Path) -> Vec<(String, SystemTime, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut sessions: BTreeMap<String, (SystemTime, u64)> = BTreeMap::new();
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some((id, _)) = file_name.split_once('.') else { continue };
+        if !is_valid_session_id(id) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else { continue };
+        let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+        let session = sessions.entry(id.to_string()).or_insert((modified, 0));
+        session.0 = session.0.max(modified);
+        session.1 += metadata.len();
+    }
+    let mut sessions =
+        sessions.into_iter().map(|(id, (modified, size))| (id, modified, size)).collect::<Vec<_>>();
+    sessions.sort_by(|a, b| b.1.cmp(&a.1));
+    sessions
+}
+
+fn prune_sessions(dir: &Path, max_sessions: usize) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let expired = collect_sessions(dir)
+        .into_iter()
+        .skip(max_sessions)
+        .map(|(id, _, _)| id)
+        .collect::<Vec<_>>();
+    if expired.is_empty() {
+        return;
+    }
+    for entry in entries.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some((id, _)) = file_name.split_once('.') else { continue };
+        if expired.iter().any(|expired_id| expired_id.eq(id)) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+pub fn list_sessions(dir: &Path) -> JsonValue {
+    let sessions = collect_sessions(dir)
+        .into_iter()
+        .map(|(id, modified, size)| {
+            let updated_at =
+                modified.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+            json!({ "id": id, "updatedAt": updated_at, "sizeBytes": size })
+        })
+        .collect::<Vec<_>>();
+    JsonValue::Array(sessions)
+}
+
+/// Loads the log events and the block store persisted for the session `id`.
+pub fn load_session(
+    dir: &Path,
+    id: &str,
+    max_rotated_log_files: usize,
+) -> Result<Option<JsonValue>, String> {
+    if !is_valid_session_id(id) {
+        return Ok(None);
+    }
+    let blocks_path = blocks_path(dir, id);
+    let log_paths = (0..=max_rotated_log_files)
+        .rev()
+        .map(|rotation| log_path(dir, id, rotation))
+        .filter(|path| path.exists())
+        .collect::<Vec<_>>();
+    if log_paths.is_empty() && !blocks_path.exists() {
+        return Ok(None);
+    }
+
+    let mut logs = vec![];
+    for path in log_paths.iter() {
+        let file = File::open(path).map_err(|e| format!("unable to read session logs: {e}"))?;
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| format!("unable to read session logs: {e}"))?;
+            // a line can be truncated if the process was interrupted while writing it
+            if let Ok(log_event) = serde_json::from_str::<JsonValue>(&line) {
+                logs.push(log_event);
+            }
+        }
+    }
+
+    let blocks = match fs::read(&blocks_path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| format!("unable to read session blocks: {e}"))?,
+        Err(_) => JsonValue::Array(vec![]),
+    };
+
+    Ok(Some(json!({ "id": id, "logs": logs, "blocks": blocks })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use txtx_addon_kit::types::frontend::{
+        ActionGroup, ActionSubGroup, LogLevel, ProvideInputRequest,
+    };
+    use txtx_addon_kit::types::types::{Type, Value};
+    use txtx_addon_kit::uuid::Uuid;
+
+    fn sessions_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("txtx-sessions-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn log_event(message: &str) -> LogEvent {
+        let BlockEvent::LogEvent(log_event) =
+            BlockEvent::static_log(LogLevel::Info, Uuid::new_v4(), "txtx".into(), "Test", message)
+        else {
+            unreachable!()
+        };
+        log_event
+    }
+
+    fn logged_messages(session: &JsonValue) -> Vec<String> {
+        session["logs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|log| serde_json::from_value::<LogEvent>(log.clone()).unwrap().message())
+            .collect()
+    }
+
+    fn provide_input_block(name: &str, sensitive: bool) -> Block {
+        let item = ActionItemRequestType::ProvideInput(ProvideInputRequest {
+            default_value: Some(Value::string("hunter2".into())),
+            input_name: name.into(),
+            typing: Type::string(),
+            sensitive,
+        })
+        .to_request("deployer", name);
+        let panel = Panel::new_action_panel(
+            "Inputs",
+            "",
+            vec![ActionGroup::new("Inputs", vec![ActionSubGroup::new(None, vec![item], false)])],
+        );
+        Block::new(&Uuid::new_v4(), panel)
+    }
+
+    #[test]
+    fn test_sensitive_inputs_are_not_persisted() {
+        let dir = std::env::temp_dir().join(format!("txtx-session-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("session.blocks.json");
+        let block_store = BTreeMap::from([
+            (0, provide_input_block("passphrase", true)),
+            (1, provide_input_block("label", false)),
+        ]);
+
+        write_blocks(&path, &block_store).unwrap();
+        let blocks = fs::read_to_string(&path).unwrap();
+        assert_eq!(blocks.matches("hunter2").count(), 1);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_logs_are_rotated_and_loaded_in_order() {
+        let dir = sessions_dir();
+        let mut config = SessionStoreConfig::new(dir.clone());
+        let line_len = serde_json::to_string(&log_event("message 0")).unwrap().len() as u64 + 1;
+        config.max_log_bytes = 2 * line_len;
+        config.max_rotated_log_files = 2;
+        let mut recorder = SessionRecorder::start(config, "deploy").unwrap();
+
+        let mut log_store = vec![];
+        for i in 0..7 {
+            log_store.push(log_event(&format!("message {i}")));
+            recorder.append_logs(&log_store).unwrap();
+        }
+
+        // 2 logs per file: the current file, and the 2 rotated ones, the oldest being dropped
+        for rotation in 0..=2 {
+            assert!(recorder.log_path(rotation).exists(), "missing rotation {rotation}");
+        }
+        assert!(!recorder.log_path(3).exists());
+        let session = load_session(&dir, recorder.id(), 2).unwrap().unwrap();
+        assert_eq!(
+            logged_messages(&session),
+            vec!["message 2", "message 3", "message 4", "message 5", "message 6"]
+        );
+        assert_eq!(session["blocks"], json!([]));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_truncated_logs_and_blocks_are_loaded() {
+        let dir = sessions_dir();
+        let mut recorder =
+            SessionRecorder::start(SessionStoreConfig::new(dir.clone()), "deploy").unwrap();
+        recorder.append_logs(&vec![log_event("first"), log_event("second")]).unwrap();
+        // the process was interrupted while writing a log
+        let mut file = OpenOptions::new().append(true).open(recorder.log_path(0)).unwrap();
+        file.write_all(b"{\"type\":\"Static\",\"log\":{").unwrap();
+        let block_store = BTreeMap::from([(0, provide_input_block("label", false))]);
+        write_blocks(&recorder.blocks_path(), &block_store).unwrap();
+
+        let session = load_session(&dir, recorder.id(), 2).unwrap().unwrap();
+        assert_eq!(session["id"], recorder.id());
+        assert_eq!(logged_messages(&session), vec!["first", "second"]);
+        assert_eq!(session["blocks"].as_array().unwrap().len(), 1);
+
+        assert_eq!(load_session(&dir, "unknown", 2).unwrap(), None);
+        assert_eq!(load_session(&dir, "../deploy", 2).unwrap(), None);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_oldest_sessions_are_pruned() {
+        let dir = sessions_dir();
+        let now = SystemTime::now();
+        for (i, id) in ["oldest", "older", "recent"].iter().enumerate() {
+            for path in [log_path(&dir, id, 0), log_path(&dir, id, 1), blocks_path(&dir, id)] {
+                let file = File::create(path).unwrap();
+                file.set_modified(now - Duration::from_secs(60 * (3 - i as u64))).unwrap();
+            }
+        }
+
+        let mut config = SessionStoreConfig::new(dir.clone());
+        config.max_sessions = 3;
+        let mut recorder = SessionRecorder::start(config, "deploy").unwrap();
+        recorder.append_logs(&vec![log_event("started")]).unwrap();
+
+        // the new session is kept along with the 2 most recent ones
+        let sessions = list_sessions(&dir);
+        let ids = sessions
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|session| session["id"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![recorder.id(), "recent", "older"]);
+        let remaining_files = fs::read_dir(&dir).unwrap().count();
+        assert_eq!(remaining_files, 7);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::BTreeMap;
+
+use txtx_addon_kit::serde_json::{self, json, Value as JsonValue};
+use txtx_addon_kit::types::frontend::{
+    ActionItemRequestType, Block, ConstructStatus, ConstructStatusStore, Panel,
+};
+use txtx_addon_kit::types::types::{AddonJsonConverter, RunbookCompleteAdditionalInfo, Value};
+use txtx_addon_kit::Addon;
+
+/// The additional info reported by the constructs (deployments, transaction links, etc.) once
+/// the runbook completed, `None` while it's still running.
+pub type RunbookCompletionStore = Option<Vec<RunbookCompleteAdditionalInfo>>;
+
+/// A snapshot of a supervised execution, rendered from the block, construct and completion
+/// stores at request time.
+pub struct ExecutionSummary<'a> {
+    pub runbook_name: &'a str,
+    pub block_store: &'a BTreeMap<usize, Block>,
+    pub construct_store: &'a ConstructStatusStore,
+    pub completion: &'a RunbookCompletionStore,
+}
+
+impl<'a> ExecutionSummary<'a> {
+    fn outputs(&self) -> Vec<(&'a str, &'a Option<String>, &'a Value)> {
+        self.block_store
+            .values()
+            .flat_map(|block| match &block.panel {
+                Panel::ActionPanel(data) => data.groups.iter(),
+                Panel::ModalPanel(data) => data.groups.iter(),
+                Panel::ErrorPanel(data) => data.groups.iter(),
+            })
+            .flat_map(|group| group.sub_groups.iter())
+            .flat_map(|sub_group| sub_group.action_items.iter())
+            .filter_map(|action_item| match &action_item.action_type {
+                ActionItemRequestType::DisplayOutput(output) => {
+                    Some((output.name.as_str(), &output.description, &output.value))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn to_json(&self, addons: &Vec<Box<dyn Addon>>) -> JsonValue {
+        let converters = json_converters(addons);
+
+        let mut outputs = json!({});
+        for (name, description, value) in self.outputs() {
+            let mut output_json = json!({ "value": value.to_json(Some(&converters)) });
+            if let Some(description) = description {
+                output_json["description"] = description.clone().into();
+            }
+            outputs[name] = output_json;
+        }
+
+        let constructs = self
+            .construct_store
+            .entries()
+            .map(|entry| {
+                json!({
+                    "id": entry.construct.construct_did.to_string(),
+                    "constructType": entry.construct.construct_type,
+                    "name": entry.construct.name,
+                    "namespace": entry.construct.namespace,
+                    "status": entry.construct.status,
+                    "dependencies": entry.construct.dependencies,
+                    "durationMs": entry.duration.map(|d| d.as_millis() as u64),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let additional_info = self
+            .completion
+            .iter()
+            .flatten()
+            .map(|info| {
+                json!({
+                    "constructName": info.construct_name,
+                    "title": info.title,
+                    "details": info.details,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        json!({
+            "runbook": self.runbook_name,
+            "completed": self.completion.is_some(),
+            "outputs": outputs,
+            "constructs": constructs,
+            "additionalInfo": additional_info,
+        })
+    }
+
+    pub fn to_markdown(&self, addons: &Vec<Box<dyn Addon>>) -> String {
+        let converters = json_converters(addons);
+        let mut report = format!("# Runbook `{}`\n\n", self.runbook_name);
+        let status = if self.completion.is_some() { "completed" } else { "in progress" };
+        report.push_str(&format!("Status: **{status}**\n"));
+
+        let outputs = self.outputs();
+        if !outputs.is_empty() {
+            report.push_str("\n## Outputs\n");
+            for (name, description, value) in outputs {
+                report.push_str(&format!("\n### {name}\n\n"));
+                if let Some(description) = description {
+                    report.push_str(&format!("{description}\n\n"));
+                }
+                match value.to_json(Some(&converters)) {
+                    JsonValue::String(value) => report.push_str(&format!("`{value}`\n")),
+                    value => report.push_str(&format!(
+                        "```json\n{}\n```\n",
+                        serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string())
+                    )),
+                }
+            }
+        }
+
+        let mut constructs = self.construct_store.entries().peekable();
+        if constructs.peek().is_some() {
+            report.push_str("\n## Constructs\n\n");
+            report.push_str("| Construct | Status | Duration |\n|---|---|---|\n");
+            for entry in constructs {
+                let duration = entry
+                    .duration
+                    .map(|d| format!("{:.2}s", d.as_secs_f64()))
+                    .or_else(|| entry.last_update())
+                    .unwrap_or_else(|| "-".into());
+                report.push_str(&format!(
+                    "| `{}.{}` | {} | {} |\n",
+                    entry.construct.construct_type,
+                    entry.construct.name,
+                    status_label(&entry.construct.status),
+                    duration
+                ));
+            }
+        }
+
+        if let Some(additional_info) = self.completion {
+            for info in additional_info.iter() {
+                report.push_str(&format!(
+                    "\n## {} ({})\n\n{}\n",
+                    info.title, info.construct_name, info.details
+                ));
+            }
+        }
+        report
+    }
+}
+
+fn json_converters(addons: &Vec<Box<dyn Addon>>) -> Vec<AddonJsonConverter<'_>> {
+    addons
+        .iter()
+        .map(|addon| Box::new(move |value: &Value| addon.to_json(value)) as AddonJsonConverter)
+        .collect()
+}
+
+fn status_label(status: &ConstructStatus) -> &'static str {
+    match status {
+        ConstructStatus::Pending => "pending",
+        ConstructStatus::AwaitingInput => "awaiting input",
+        ConstructStatus::Executing => "executing",
+        ConstructStatus::BackgroundTaskRunning => "background task running",
+        ConstructStatus::Completed => "completed",
+        ConstructStatus::Failed => "failed",
+        ConstructStatus::Skipped => "skipped",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use txtx_addon_kit::types::frontend::{
+        ActionGroup, ActionSubGroup, ConstructStatusUpdate, DisplayOutputRequest,
+    };
+    use txtx_addon_kit::types::{ConstructDid, Did};
+    use txtx_addon_kit::uuid::Uuid;
+
+    use super::*;
+
+    fn output(name: &str, description: Option<&str>, value: Value) -> Block {
+        let action_item = ActionItemRequestType::DisplayOutput(DisplayOutputRequest {
+            name: name.into(),
+            description: description.map(|d| d.to_string()),
+            value,
+            formatted_value: None,
+        })
+        .to_request(name, "output");
+        let panel = Panel::new_action_panel(
+            "Outputs",
+            "",
+            vec![ActionGroup::new(
+                "Outputs",
+                vec![ActionSubGroup::new(None, vec![action_item], false)],
+            )],
+        );
+        Block::new(&Uuid::new_v4(), panel)
+    }
+
+    fn construct_store() -> ConstructStatusStore {
+        let update = |name: &str, status| ConstructStatusUpdate {
+            construct_did: ConstructDid(Did::from_components(vec![name])),
+            construct_type: "action".into(),
+            name: name.into(),
+            namespace: "std".into(),
+            matcher: "send_http_request".into(),
+            dependencies: vec![],
+            status,
+        };
+        let mut construct_store = ConstructStatusStore::new();
+        construct_store.apply(update("ping", ConstructStatus::Executing));
+        construct_store.apply(update("ping", ConstructStatus::Completed));
+        construct_store.apply(update("notify", ConstructStatus::Pending));
+        construct_store
+    }
+
+    fn completion() -> RunbookCompletionStore {
+        Some(vec![RunbookCompleteAdditionalInfo {
+            construct_did: ConstructDid(Did::from_components(vec!["ping"])),
+            construct_name: "ping".into(),
+            title: "Requests".into(),
+            details: "1 request sent".into(),
+        }])
+    }
+
+    #[test]
+    fn test_summary_is_rendered_as_json() {
+        let block_store = BTreeMap::from([
+            (0, output("greeting", Some("The greeting"), Value::string("hello".into()))),
+            (1, output("count", None, Value::integer(2))),
+        ]);
+        let construct_store = construct_store();
+        let completion = completion();
+        let summary = ExecutionSummary {
+            runbook_name: "main",
+            block_store: &block_store,
+            construct_store: &construct_store,
+            completion: &completion,
+        };
+
+        let json = summary.to_json(&vec![]);
+        assert_eq!(json["runbook"], "main");
+        assert_eq!(json["completed"], true);
+        assert_eq!(
+            json["outputs"],
+            json!({
+                "greeting": { "value": "hello", "description": "The greeting" },
+                "count": { "value": 2 },
+            })
+        );
+        let constructs = json["constructs"].as_array().unwrap();
+        assert_eq!(constructs.len(), 2);
+        assert_eq!(constructs[0]["name"], "ping");
+        assert_eq!(constructs[0]["status"], "completed");
+        assert!(constructs[0]["durationMs"].is_u64());
+        assert_eq!(constructs[1]["status"], "pending");
+        assert!(constructs[1]["durationMs"].is_null());
+        assert_eq!(
+            json["additionalInfo"],
+            json!([{ "constructName": "ping", "title": "Requests", "details": "1 request sent" }])
+        );
+    }
+
+    #[test]
+    fn test_summary_is_rendered_as_markdown() {
+        let block_store = BTreeMap::from([
+            (0, output("greeting", Some("The greeting"), Value::string("hello".into()))),
+            (1, output("count", None, Value::integer(2))),
+        ]);
+        let construct_store = construct_store();
+        let completion = completion();
+        let summary = ExecutionSummary {
+            runbook_name: "main",
+            block_store: &block_store,
+            construct_store: &construct_store,
+            completion: &completion,
+        };
+
+        let report = summary.to_markdown(&vec![]);
+        assert!(report.starts_with("# Runbook `main`\n\nStatus: **completed**\n"));
+        assert!(report.contains("\n### greeting\n\nThe greeting\n\n`hello`\n"));
+        assert!(report.contains("\n### count\n\n```json\n2\n```\n"));
+        assert!(report.contains("| `action.ping` | completed | "));
+        assert!(report.contains("| `action.notify` | pending | - |\n"));
+        assert!(report.contains("\n## Requests (ping)\n\n1 request sent\n"));
+
+        // a running execution has no completion info yet
+        let summary = ExecutionSummary { completion: &None, ..summary };
+        let report = summary.to_markdown(&vec![]);
+        assert!(report.contains("Status: **in progress**"));
+        assert!(!report.contains("## Requests"));
+    }
+
+    #[test]
+    fn test_empty_summary() {
+        let block_store = BTreeMap::new();
+        let construct_store = ConstructStatusStore::new();
+        let summary = ExecutionSummary {
+            runbook_name: "main",
+            block_store: &block_store,
+            construct_store: &construct_store,
+            completion: &None,
+        };
+        assert_eq!(summary.to_markdown(&vec![]), "# Runbook `main`\n\nStatus: **in progress**\n");
+        assert_eq!(summary.to_json(&vec![])["outputs"], json!({}));
+    }
+}
@@ -5,12 +5,15 @@ use std::collections::HashMap;
 use txtx_addon_kit::{types::commands::CommandSpecification, Addon};
 use txtx_core::std::StdAddon;
 
-/// Get all available addons for testing
+use crate::auto_signer::TestAddon;
+
+/// Get all available addons for testing, including the `test` addon providing `test::auto_signer`
 pub fn get_all_addons() -> Vec<Box<dyn Addon>> {
     vec![
         Box::new(StdAddon::new()),
         Box::new(txtx_addon_network_evm::EvmNetworkAddon::new()),
         Box::new(txtx_addon_network_svm::SvmNetworkAddon::new()),
+        Box::new(TestAddon::new()),
     ]
 }
 
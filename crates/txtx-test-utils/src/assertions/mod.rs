@@ -1,5 +1,11 @@
 //! Common assertion macros for txtx tests
 
+mod snapshot;
+
+pub use snapshot::{
+    assert_outputs_snapshot, OutputsSnapshot, SnapshotOutputs, UPDATE_SNAPSHOTS_ENV,
+};
+
 /// Assert that a result contains a specific error pattern
 #[macro_export]
 macro_rules! assert_error {
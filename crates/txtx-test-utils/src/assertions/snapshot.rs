@@ -0,0 +1,219 @@
+//! Snapshot assertions for runbook outputs
+//!
+//! The outputs of a runbook are serialized to JSON, with the values of the addons converted by
+//! their `Addon::to_json` implementation, and compared against a snapshot file stored along the
+//! tests. Volatile values (tx hashes, timestamps, uuids) are normalized beforehand, so that
+//! snapshots stay stable across runs.
+//!
+//! Snapshots are stored in `tests/snapshots/<name>.json` under the crate being tested, and are
+//! written instead of compared when the `TXTX_UPDATE_SNAPSHOTS` environment variable is set:
+//!
+//! ```sh
+//! TXTX_UPDATE_SNAPSHOTS=1 cargo test
+//! ```
+
+use std::path::PathBuf;
+
+use regex::Regex;
+use txtx_addon_kit::serde_json::{self, json, Map, Value as JsonValue};
+use txtx_addon_kit::types::types::{AddonJsonConverter, Value};
+use txtx_addon_kit::Addon;
+use txtx_core::runbook::RunbookOutputs;
+
+use crate::addon_registry::get_all_addons;
+use crate::builders::ExecutionResult;
+
+/// Environment variable enabling the update of the snapshots instead of their comparison.
+pub const UPDATE_SNAPSHOTS_ENV: &str = "TXTX_UPDATE_SNAPSHOTS";
+
+/// Results of an execution whose outputs can be snapshotted.
+pub trait SnapshotOutputs {
+    fn outputs_to_json(&self, addon_converters: &Vec<AddonJsonConverter>) -> JsonValue;
+}
+
+impl SnapshotOutputs for ExecutionResult {
+    fn outputs_to_json(&self, _addon_converters: &Vec<AddonJsonConverter>) -> JsonValue {
+        let mut outputs = self.outputs.iter().collect::<Vec<_>>();
+        outputs.sort();
+        JsonValue::Object(
+            outputs
+                .into_iter()
+                .map(|(name, value)| (name.clone(), json!({ "value": value })))
+                .collect::<Map<_, _>>(),
+        )
+    }
+}
+
+impl SnapshotOutputs for RunbookOutputs {
+    fn outputs_to_json(&self, addon_converters: &Vec<AddonJsonConverter>) -> JsonValue {
+        self.to_json(addon_converters)
+    }
+}
+
+/// Compares the outputs of an execution against a snapshot.
+pub struct OutputsSnapshot {
+    redactions: Vec<(Regex, String)>,
+    directory: PathBuf,
+    update: bool,
+}
+
+impl OutputsSnapshot {
+    /// Create a snapshot comparison redacting tx hashes, timestamps and uuids, stored in the
+    /// `tests/snapshots` directory of the crate being tested
+    pub fn new() -> Self {
+        let directory = std::env::var("CARGO_MANIFEST_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join("tests")
+            .join("snapshots");
+        let update = std::env::var(UPDATE_SNAPSHOTS_ENV).is_ok_and(|v| !v.is_empty() && v != "0");
+        Self { redactions: vec![], directory, update }
+            .with_redaction(r"^0x[0-9a-fA-F]{64}$", "[tx_hash]")
+            .with_redaction(
+                r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:?\d{2})?",
+                "[timestamp]",
+            )
+            .with_redaction(
+                r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
+                "[uuid]",
+            )
+    }
+
+    /// Replace the matches of a pattern in the string values of the outputs
+    pub fn with_redaction(mut self, pattern: &str, replacement: &str) -> Self {
+        let regex = Regex::new(pattern).expect("invalid redaction pattern");
+        self.redactions.push((regex, replacement.to_string()));
+        self
+    }
+
+    /// Store the snapshots in another directory
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = directory.into();
+        self
+    }
+
+    /// Write the snapshots instead of comparing them, overriding `TXTX_UPDATE_SNAPSHOTS`
+    pub fn with_update_mode(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Serialize the outputs of an execution, with their volatile values redacted
+    pub fn serialize(&self, result: &impl SnapshotOutputs) -> String {
+        let addons = get_all_addons();
+        let addon_converters = addons
+            .iter()
+            .map(|addon| Box::new(move |value: &Value| addon.to_json(value)) as AddonJsonConverter)
+            .collect::<Vec<_>>();
+        let outputs = self.redact(result.outputs_to_json(&addon_converters));
+        format!("{}\n", serde_json::to_string_pretty(&outputs).unwrap())
+    }
+
+    fn redact(&self, value: JsonValue) -> JsonValue {
+        match value {
+            JsonValue::String(string) => JsonValue::String(self.redactions.iter().fold(
+                string,
+                |string, (regex, replacement)| {
+                    regex.replace_all(&string, replacement.as_str()).into_owned()
+                },
+            )),
+            JsonValue::Array(values) => {
+                JsonValue::Array(values.into_iter().map(|v| self.redact(v)).collect())
+            }
+            JsonValue::Object(map) => {
+                JsonValue::Object(map.into_iter().map(|(k, v)| (k, self.redact(v))).collect())
+            }
+            value => value,
+        }
+    }
+
+    /// Compare the outputs of an execution against the snapshot `name`, or write the snapshot in
+    /// update mode
+    pub fn assert_matches(&self, result: &impl SnapshotOutputs, name: &str) {
+        let actual = self.serialize(result);
+        let path = self.directory.join(format!("{}.json", name));
+        if self.update {
+            std::fs::create_dir_all(&self.directory).expect("unable to create snapshot directory");
+            std::fs::write(&path, actual).expect("unable to write snapshot");
+            return;
+        }
+        let Ok(expected) = std::fs::read_to_string(&path) else {
+            panic!(
+                "Snapshot '{}' not found at {}, run the tests with {}=1 to create it:\n{}",
+                name,
+                path.display(),
+                UPDATE_SNAPSHOTS_ENV,
+                actual
+            );
+        };
+        assert!(
+            expected.eq(&actual),
+            "Outputs don't match snapshot '{}' ({}), run the tests with {}=1 to update it.\nExpected:\n{}\nActual:\n{}",
+            name,
+            path.display(),
+            UPDATE_SNAPSHOTS_ENV,
+            expected,
+            actual
+        );
+    }
+}
+
+/// Assert that the outputs of an execution match the snapshot `name`, with the default
+/// redactions
+pub fn assert_outputs_snapshot(result: &impl SnapshotOutputs, name: &str) {
+    OutputsSnapshot::new().assert_matches(result, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execution_result() -> ExecutionResult {
+        ExecutionResult {
+            success: true,
+            outputs: [
+                ("tx_hash".to_string(), format!("0x{}", "ab".repeat(32))),
+                ("deployed_at".to_string(), "deployed at 2024-03-01T12:30:00Z".to_string()),
+                ("id".to_string(), "67e55044-10b1-426f-9247-bb680e5fe0c8".to_string()),
+                ("amount".to_string(), "10".to_string()),
+            ]
+            .into(),
+            errors: vec![],
+        }
+    }
+
+    #[test]
+    fn test_volatile_values_are_redacted() {
+        let snapshot = OutputsSnapshot::new().with_redaction(r"^1\d$", "[amount]");
+        let serialized: JsonValue =
+            serde_json::from_str(&snapshot.serialize(&execution_result())).unwrap();
+        assert_eq!(serialized["tx_hash"]["value"], "[tx_hash]");
+        assert_eq!(serialized["deployed_at"]["value"], "deployed at [timestamp]");
+        assert_eq!(serialized["id"]["value"], "[uuid]");
+        assert_eq!(serialized["amount"]["value"], "[amount]");
+
+        let mut outputs = RunbookOutputs::new();
+        outputs.add_output(
+            "flow",
+            "address",
+            &Value::string("0x5fbdb2315678afecb367f032d93f642f64180aa3".into()),
+            &None,
+        );
+        let serialized: JsonValue = serde_json::from_str(&snapshot.serialize(&outputs)).unwrap();
+        assert_eq!(serialized["address"]["value"], "0x5fbdb2315678afecb367f032d93f642f64180aa3");
+    }
+
+    #[test]
+    fn test_snapshot_update_and_comparison() {
+        let directory = std::env::temp_dir().join(format!("txtx-snapshots-{}", std::process::id()));
+        OutputsSnapshot::new()
+            .with_directory(&directory)
+            .with_update_mode(true)
+            .assert_matches(&execution_result(), "outputs");
+        OutputsSnapshot::new()
+            .with_directory(&directory)
+            .with_update_mode(false)
+            .assert_matches(&execution_result(), "outputs");
+        let _ = std::fs::remove_dir_all(&directory);
+    }
+}
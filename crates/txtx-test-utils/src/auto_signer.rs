@@ -0,0 +1,475 @@
+//! A mock signer signing automatically in integration tests
+//!
+//! The `test::auto_signer` signer derives its keypair from the name of the signer, so that tests
+//! can assert against stable addresses, and signs any payload presented without requesting any
+//! action item. Transactions are signed the way their network expects them:
+//! - EVM transactions handed over by `evm::sign_transaction` are signed as EIP-2718 envelopes with
+//!   the secp256k1 key, without being broadcasted;
+//! - SVM transactions are signed with the Ed25519 keypair derived from the same secret key;
+//! - EVM message digests, handed over by `evm::sign_message` and `evm::sign_typed_data`, are
+//!   signed as `r || s || v` signatures.
+//!
+//! Other payloads are signed as messages: the signature is a secp256k1 signature over the keccak
+//! hash of the payload bytes. Stacks transactions aren't supported, the Stacks addon not being
+//! part of this workspace.
+
+use std::collections::HashMap;
+
+use alloy_eips::eip2718::Encodable2718;
+use alloy_network::{EthereumWallet, TransactionBuilder};
+use alloy_rpc_types::TransactionRequest;
+use alloy_signer_local::PrivateKeySigner;
+use solana_keypair::{keypair_from_seed, Keypair};
+use solana_signer::Signer;
+use txtx_addon_kit::channel;
+use txtx_addon_kit::constants::{SIGNED_MESSAGE_BYTES, SIGNED_TRANSACTION_BYTES, TX_HASH};
+use txtx_addon_kit::hex;
+use txtx_addon_kit::keccak_hash::keccak;
+use txtx_addon_kit::secp256k1::{sign, Message, PublicKey, SecretKey};
+use txtx_addon_kit::serde_json;
+use txtx_addon_kit::sha2::{Digest, Sha256};
+use txtx_addon_kit::types::commands::{CommandExecutionResult, CommandSpecification};
+use txtx_addon_kit::types::diagnostics::Diagnostic;
+use txtx_addon_kit::types::frontend::{Actions, BlockEvent};
+use txtx_addon_kit::types::signers::{
+    return_synchronous_actions, return_synchronous_result, CheckSignabilityOk, SignerActionErr,
+    SignerActionsFutureResult, SignerActivateFutureResult, SignerImplementation, SignerInstance,
+    SignerSignFutureResult, SignerSpecification, SignersState,
+};
+use txtx_addon_kit::types::stores::ValueStore;
+use txtx_addon_kit::types::types::{RunbookSupervisionContext, Type, Value};
+use txtx_addon_kit::types::{AuthorizationContext, ConstructDid};
+use txtx_addon_kit::Addon;
+use txtx_addon_network_svm::typing::{SvmValue, SVM_TRANSACTION};
+use txtx_addon_network_svm::utils::build_transaction_from_svm_value;
+
+pub const NAMESPACE: &str = "test";
+pub const AUTO_SIGNER: &str = "auto_signer";
+
+const SECRET_KEY: &str = "secret_key";
+const PUBLIC_KEY: &str = "public_key";
+const ADDRESS: &str = "address";
+const SVM_ADDRESS: &str = "svm_address";
+const SIGNATURE: &str = "signature";
+/// Key under which `evm::sign_message` and `evm::sign_typed_data` hand the digest to sign over.
+const EVM_MESSAGE_BYTES: &str = "message_bytes";
+/// Key under which `evm::sign_transaction` hands the JSON transaction request over to its signer.
+const EVM_UNSIGNED_TRANSACTION_BYTES: &str = "secret_key_wallet_unsigned_transaction_bytes";
+/// Key under which the SVM commands expect their signers to keep the transaction to sign.
+const SVM_TRANSACTION_BYTES: &str = "transaction_bytes";
+const SVM_PARTIALLY_SIGNED_TRANSACTION_BYTES: &str = "partially_signed_transaction_bytes";
+
+/// Addon registering the signers only available in tests.
+#[derive(Debug)]
+pub struct TestAddon;
+
+impl TestAddon {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Addon for TestAddon {
+    fn get_name(&self) -> &str {
+        "Test Utilities"
+    }
+
+    fn get_description(&self) -> &str {
+        "Signers for integration tests, which must not be used outside of them."
+    }
+
+    fn get_namespace(&self) -> &str {
+        NAMESPACE
+    }
+
+    fn get_signers(&self) -> Vec<SignerSpecification> {
+        vec![auto_signer_specification()]
+    }
+}
+
+pub fn auto_signer_specification() -> SignerSpecification {
+    txtx_addon_kit::define_signer! {
+        AutoSigner => {
+            name: "Auto Signer",
+            matcher: AUTO_SIGNER,
+            documentation: txtx_addon_kit::indoc! {r#"The `test::auto_signer` signer derives its keypair from its name and signs any payload without review."#},
+            inputs: [],
+            outputs: [
+                public_key: {
+                    documentation: "The uncompressed secp256k1 public key of the signer, hex encoded.",
+                    typing: Type::string()
+                },
+                address: {
+                    documentation: "The EVM style address of the signer.",
+                    typing: Type::string()
+                },
+                svm_address: {
+                    documentation: "The SVM address of the signer, base58 encoded.",
+                    typing: Type::string()
+                }
+            ],
+            example: txtx_addon_kit::indoc! {r#"
+                signer "deployer" "test::auto_signer" {
+                }
+            "#}
+        }
+    }
+}
+
+/// The keypair of an auto signer, derived from its name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoSignerKeypair {
+    pub secret_key: Vec<u8>,
+    /// Uncompressed public key, hex encoded
+    pub public_key: String,
+    /// Last 20 bytes of the keccak hash of the public key, hex encoded and prefixed with `0x`
+    pub address: String,
+    /// Public key of the Ed25519 keypair seeded with the secret key, base58 encoded
+    pub svm_address: String,
+}
+
+impl AutoSignerKeypair {
+    /// Derives the keypair of the signer named `name`, the same across runs.
+    pub fn from_name(name: &str) -> Self {
+        let secret_key = Sha256::digest(format!("txtx-test-utils::auto_signer::{}", name));
+        // a sha256 digest is a valid secp256k1 secret key but with a negligible probability
+        let secret = SecretKey::parse_slice(&secret_key).expect("invalid derived secret key");
+        let public_key = PublicKey::from_secret_key(&secret).serialize();
+        let address = &keccak(&public_key[1..]).0[12..];
+        AutoSignerKeypair {
+            secret_key: secret_key.to_vec(),
+            public_key: hex::encode(public_key),
+            address: format!("0x{}", hex::encode(address)),
+            svm_address: svm_keypair(&secret_key).pubkey().to_string(),
+        }
+    }
+
+    /// Signs the keccak hash of a payload, returning the 65 bytes of the recoverable signature.
+    pub fn sign(&self, payload: &[u8]) -> Vec<u8> {
+        let secret = SecretKey::parse_slice(&self.secret_key).expect("invalid secret key");
+        let message = Message::parse(&keccak(payload).0);
+        let (signature, recovery_id) = sign(&message, &secret);
+        let mut bytes = signature.serialize().to_vec();
+        bytes.push(recovery_id.serialize());
+        bytes
+    }
+
+    /// Signs a 32 bytes digest as EVM signers do, returning the signature encoded as `r || s || v`.
+    pub fn sign_digest(&self, digest: &[u8]) -> Result<Vec<u8>, Diagnostic> {
+        let secret = SecretKey::parse_slice(&self.secret_key).expect("invalid secret key");
+        let message = Message::parse_slice(digest)
+            .map_err(|e| Diagnostic::error_from_string(format!("invalid message digest: {e}")))?;
+        let (signature, recovery_id) = sign(&message, &secret);
+        let mut bytes = signature.serialize().to_vec();
+        bytes.push(27 + recovery_id.serialize());
+        Ok(bytes)
+    }
+}
+
+fn svm_keypair(secret_key: &[u8]) -> Keypair {
+    keypair_from_seed(secret_key).expect("invalid secret key")
+}
+
+/// Signs an EVM transaction request, returning the EIP-2718 encoded envelope and its hash.
+async fn sign_evm_transaction(
+    secret_key: &[u8],
+    transaction_request_bytes: &[u8],
+) -> Result<(Vec<u8>, Vec<u8>), Diagnostic> {
+    let signer = PrivateKeySigner::from_slice(secret_key)
+        .map_err(|e| Diagnostic::error_from_string(format!("invalid secret key: {e}")))?;
+    let mut transaction: TransactionRequest = serde_json::from_slice(transaction_request_bytes)
+        .map_err(|e| {
+            Diagnostic::error_from_string(format!("error deserializing transaction: {e}"))
+        })?;
+    if transaction.to.is_none() {
+        transaction.set_create();
+    }
+    let envelope = transaction.build(&EthereumWallet::from(signer)).await.map_err(|e| {
+        Diagnostic::error_from_string(format!("failed to build transaction envelope: {e}"))
+    })?;
+    Ok((envelope.encoded_2718(), envelope.tx_hash().to_vec()))
+}
+
+/// Signs an SVM transaction, returning it along with whether it's now fully signed.
+fn sign_svm_transaction(secret_key: &[u8], payload: &Value) -> Result<(Value, bool), Diagnostic> {
+    let mut transaction = build_transaction_from_svm_value(payload)?;
+    transaction
+        .try_partial_sign(&[&svm_keypair(secret_key)], transaction.message.recent_blockhash)
+        .map_err(|e| Diagnostic::error_from_string(format!("failed to sign transaction: {e}")))?;
+    Ok((SvmValue::transaction(&transaction)?, transaction.is_signed()))
+}
+
+pub struct AutoSigner;
+impl SignerImplementation for AutoSigner {
+    fn check_instantiability(
+        _ctx: &SignerSpecification,
+        _args: Vec<Type>,
+    ) -> Result<Type, Diagnostic> {
+        unimplemented!()
+    }
+
+    fn check_activability(
+        _construct_did: &ConstructDid,
+        instance_name: &str,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _supervision_context: &RunbookSupervisionContext,
+        _auth_ctx: &AuthorizationContext,
+        _is_balance_check_required: bool,
+        _is_public_key_required: bool,
+    ) -> SignerActionsFutureResult {
+        let keypair = AutoSignerKeypair::from_name(instance_name);
+        signer_state.insert(SECRET_KEY, Value::buffer(keypair.secret_key));
+        signer_state.insert(PUBLIC_KEY, Value::string(keypair.public_key));
+        signer_state.insert(ADDRESS, Value::string(keypair.address));
+        signer_state.insert(SVM_ADDRESS, Value::string(keypair.svm_address));
+        return_synchronous_actions(Ok((signers, signer_state, Actions::none())))
+    }
+
+    fn activate(
+        _construct_did: &ConstructDid,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _progress_tx: &channel::Sender<BlockEvent>,
+    ) -> SignerActivateFutureResult {
+        let mut result = CommandExecutionResult::new();
+        for output in [PUBLIC_KEY, ADDRESS, SVM_ADDRESS] {
+            let value = signer_state
+                .get_expected_value(output)
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+            result.outputs.insert(output.into(), value.clone());
+        }
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+
+    fn check_signability(
+        construct_did: &ConstructDid,
+        _title: &str,
+        _description: &Option<String>,
+        _meta_description: &Option<String>,
+        _markdown: &Option<String>,
+        payload: &Value,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        mut signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+        _supervision_context: &RunbookSupervisionContext,
+        _auth_ctx: &AuthorizationContext,
+    ) -> Result<CheckSignabilityOk, SignerActionErr> {
+        // the SVM commands read the transaction to sign back from the state of their signer
+        if !payload.is_null() {
+            signer_state.insert_scoped_value(
+                &construct_did.to_string(),
+                SVM_TRANSACTION_BYTES,
+                payload.clone(),
+            );
+        }
+        Ok((signers, signer_state, Actions::none()))
+    }
+
+    fn sign(
+        caller_uuid: &ConstructDid,
+        _title: &str,
+        payload: &Value,
+        _spec: &SignerSpecification,
+        _values: &ValueStore,
+        signer_state: ValueStore,
+        signers: SignersState,
+        _signers_instances: &HashMap<ConstructDid, SignerInstance>,
+    ) -> SignerSignFutureResult {
+        let secret_key = signer_state
+            .get_expected_buffer_bytes(SECRET_KEY)
+            .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+        let keypair = AutoSignerKeypair {
+            secret_key,
+            public_key: String::new(),
+            address: String::new(),
+            svm_address: String::new(),
+        };
+        let mut result = CommandExecutionResult::new();
+
+        if let Some(digest) =
+            signer_state.get_scoped_value(&caller_uuid.to_string(), EVM_MESSAGE_BYTES)
+        {
+            let signature = keypair
+                .sign_digest(&digest.to_be_bytes())
+                .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+            result.outputs.insert(SIGNED_MESSAGE_BYTES.into(), Value::buffer(signature));
+            return return_synchronous_result(Ok((signers, signer_state, result)));
+        }
+
+        if let Ok(transaction_request_bytes) = signer_state.get_expected_scoped_buffer_bytes(
+            &caller_uuid.to_string(),
+            EVM_UNSIGNED_TRANSACTION_BYTES,
+        ) {
+            let future = async move {
+                let (signed_bytes, tx_hash) =
+                    sign_evm_transaction(&keypair.secret_key, &transaction_request_bytes)
+                        .await
+                        .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+                result.outputs.insert(TX_HASH.into(), Value::buffer(tx_hash));
+                result.outputs.insert(SIGNED_TRANSACTION_BYTES.into(), Value::buffer(signed_bytes));
+                Ok((signers, signer_state, result))
+            };
+            return Ok(Box::pin(future));
+        }
+
+        match payload {
+            Value::Addon(addon) if addon.id == SVM_TRANSACTION => {
+                let (transaction, is_signed) =
+                    sign_svm_transaction(&keypair.secret_key, payload)
+                        .map_err(|e| (signers.clone(), signer_state.clone(), e))?;
+                let output = if is_signed {
+                    SIGNED_TRANSACTION_BYTES
+                } else {
+                    SVM_PARTIALLY_SIGNED_TRANSACTION_BYTES
+                };
+                result.outputs.insert(output.into(), transaction);
+            }
+            Value::Addon(addon) => {
+                let diag = Diagnostic::error_from_string(format!(
+                    "the auto signer is unable to sign '{}' payloads",
+                    addon.id
+                ));
+                return Err((signers, signer_state, diag));
+            }
+            _ => {
+                let payload_bytes = payload.to_be_bytes();
+                let signature = keypair.sign(&payload_bytes);
+                let mut signed_bytes = payload_bytes;
+                signed_bytes.extend(&signature);
+                result.outputs.insert(SIGNATURE.into(), Value::buffer(signature));
+                result.outputs.insert(SIGNED_MESSAGE_BYTES.into(), Value::buffer(signed_bytes));
+            }
+        }
+        return_synchronous_result(Ok((signers, signer_state, result)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::TxEnvelope;
+    use alloy_eips::eip2718::Decodable2718;
+    use alloy_primitives::{Address, U256};
+    use solana_hash::Hash;
+    use solana_instruction::{AccountMeta, Instruction};
+    use solana_message::Message as SvmMessage;
+    use solana_transaction::Transaction;
+    use txtx_addon_kit::secp256k1::{recover, RecoveryId, Signature};
+    use txtx_addon_kit::types::Did;
+    use txtx_addon_network_svm::Pubkey;
+
+    fn sign_as(
+        name: &str,
+        caller: &ConstructDid,
+        payload: &Value,
+        signer_state: ValueStore,
+    ) -> CommandExecutionResult {
+        let mut signer_state = signer_state;
+        signer_state
+            .insert(SECRET_KEY, Value::buffer(AutoSignerKeypair::from_name(name).secret_key));
+        let future = AutoSigner::sign(
+            caller,
+            "",
+            payload,
+            &auto_signer_specification(),
+            &ValueStore::tmp(),
+            signer_state,
+            SignersState::new(),
+            &HashMap::new(),
+        )
+        .unwrap_or_else(|(_, _, e)| panic!("{}", e.message));
+        let (_, _, result) = hiro_system_kit::nestable_block_on(future)
+            .unwrap_or_else(|(_, _, e)| panic!("{}", e.message));
+        result
+    }
+
+    #[test]
+    fn test_keypair_is_derived_from_name() {
+        let alice = AutoSignerKeypair::from_name("alice");
+        assert_eq!(alice, AutoSignerKeypair::from_name("alice"));
+        assert_ne!(alice.address, AutoSignerKeypair::from_name("bob").address);
+        assert_eq!(alice.address.len(), 42);
+    }
+
+    #[test]
+    fn test_signature_is_recoverable() {
+        let alice = AutoSignerKeypair::from_name("alice");
+        let payload = Value::string("0xdeadbeef".into()).to_be_bytes();
+        let signature = alice.sign(&payload);
+        assert_eq!(signature, alice.sign(&payload));
+
+        let message = Message::parse(&keccak(&payload).0);
+        let recovery_id = RecoveryId::parse(signature[64]).unwrap();
+        let signature = Signature::parse_standard_slice(&signature[..64]).unwrap();
+        let public_key = recover(&message, &signature, &recovery_id).unwrap();
+        assert_eq!(hex::encode(public_key.serialize()), alice.public_key);
+    }
+
+    #[test]
+    fn test_evm_transactions_are_signed_as_envelopes() {
+        let from: Address = AutoSignerKeypair::from_name("alice").address.parse().unwrap();
+        let request = TransactionRequest::default()
+            .with_from(from)
+            .with_to(Address::repeat_byte(1))
+            .with_value(U256::from(1))
+            .with_nonce(0)
+            .with_chain_id(1)
+            .with_gas_limit(21_000)
+            .with_max_fee_per_gas(20_000_000_000)
+            .with_max_priority_fee_per_gas(1_000_000_000);
+        let caller = ConstructDid(Did::from_components(vec!["transfer"]));
+        let mut signer_state = ValueStore::tmp();
+        signer_state.insert_scoped_value(
+            &caller.to_string(),
+            EVM_UNSIGNED_TRANSACTION_BYTES,
+            Value::buffer(serde_json::to_vec(&request).unwrap()),
+        );
+
+        let result = sign_as("alice", &caller, &Value::null(), signer_state);
+        let signed_bytes =
+            result.outputs.get(SIGNED_TRANSACTION_BYTES).unwrap().expect_buffer_bytes();
+        let envelope = TxEnvelope::decode_2718(&mut signed_bytes.as_slice()).unwrap();
+        let signed = envelope.as_eip1559().expect("expected an EIP-1559 transaction");
+        let signer =
+            signed.signature().recover_address_from_prehash(&signed.signature_hash()).unwrap();
+        assert_eq!(signer, from);
+        assert_eq!(
+            result.outputs.get(TX_HASH).unwrap().expect_buffer_bytes(),
+            envelope.tx_hash().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_svm_transactions_are_signed_by_each_signer() {
+        let alice: Pubkey = AutoSignerKeypair::from_name("alice").svm_address.parse().unwrap();
+        let bob: Pubkey = AutoSignerKeypair::from_name("bob").svm_address.parse().unwrap();
+        let instruction = Instruction::new_with_bytes(
+            Pubkey::new_unique(),
+            &[1],
+            vec![AccountMeta::new(alice, true), AccountMeta::new(bob, true)],
+        );
+        let mut transaction =
+            Transaction::new_unsigned(SvmMessage::new(&[instruction], Some(&alice)));
+        transaction.message.recent_blockhash = Hash::new_from_array([1; 32]);
+        let caller = ConstructDid(Did::from_components(vec!["transfer"]));
+
+        let payload = SvmValue::transaction(&transaction).unwrap();
+        let result = sign_as("alice", &caller, &payload, ValueStore::tmp());
+        assert!(result.outputs.get(SIGNED_TRANSACTION_BYTES).is_none());
+        let partially_signed = result.outputs.get(SVM_PARTIALLY_SIGNED_TRANSACTION_BYTES).unwrap();
+
+        let result = sign_as("bob", &caller, partially_signed, ValueStore::tmp());
+        let signed = result.outputs.get(SIGNED_TRANSACTION_BYTES).unwrap();
+        let signed = build_transaction_from_svm_value(signed).unwrap();
+        assert!(signed.verify().is_ok());
+    }
+}
@@ -5,7 +5,7 @@ mod runbook_builder;
 mod runbook_builder_enhanced;
 
 pub use runbook_builder::{
-    ExecutionResult, MockConfig, ParseResult, RunbookBuilder, ValidationResult,
+    ExecutionResult, FlowBuilder, MockConfig, ParseResult, RunbookBuilder, ValidationResult,
 };
 pub use runbook_builder_enhanced::{
     create_test_manifest_from_envs, create_test_manifest_with_env, RunbookBuilderExt,
@@ -106,6 +106,8 @@ pub struct RunbookBuilder {
     manifest: Option<WorkspaceManifest>,
     /// Current environment for validation
     current_environment: Option<String>,
+    /// Paths of the files of the embedded runbooks
+    embedded_runbooks: Vec<String>,
 }
 
 /// Configuration for a mock blockchain
@@ -115,6 +117,52 @@ pub struct MockConfig {
     pub initial_state: serde_json::Value,
 }
 
+/// Builder for the inputs of a flow block, see [RunbookBuilder::with_flow]
+#[derive(Clone, Default)]
+pub struct FlowBuilder {
+    inputs: Vec<(String, String)>,
+}
+
+impl FlowBuilder {
+    /// Add an input to the flow
+    pub fn input(mut self, name: &str, value: &str) -> Self {
+        self.inputs.push((name.to_string(), format_value(value)));
+        self
+    }
+
+    /// Add a description to the flow
+    pub fn description(self, description: &str) -> Self {
+        self.input("description", &format!("{:?}", description))
+    }
+}
+
+/// Formats a value written in a test: references, quoted strings, lists and integers are kept
+/// as is, other values are quoted.
+fn format_value(value: &str) -> String {
+    if value.starts_with("env.")
+        || value.starts_with("input.")
+        || value.starts_with("action.")
+        || value.starts_with("variable.")
+        || value.starts_with("signer.")
+        || value.starts_with('"')
+        || value.starts_with('[')
+        || value.parse::<i64>().is_ok()
+    {
+        value.to_string()
+    } else {
+        format!(r#""{}""#, value)
+    }
+}
+
+/// Renders a block with a single label and its attributes.
+fn render_block(block_type: &str, name: &str, attributes: &[(String, String)]) -> String {
+    let attributes = attributes
+        .iter()
+        .map(|(key, value)| format!("    {} = {}\n", key, value))
+        .collect::<String>();
+    format!("\n{} \"{}\" {{\n{}}}", block_type, name, attributes)
+}
+
 impl RunbookBuilder {
     // ==========================================
     // Construction and Configuration
@@ -132,6 +180,7 @@ impl RunbookBuilder {
             current_action: None,
             manifest: None,
             current_environment: None,
+            embedded_runbooks: Vec::new(),
         }
     }
 
@@ -186,19 +235,7 @@ variable "{}" {{
     value = {}
 }}"#,
             name,
-            if value.starts_with("env.")
-                || value.starts_with("input.")
-                || value.starts_with("action.")
-                || value.starts_with("variable.")
-                || value.starts_with("signer.")
-                || value.starts_with('"')
-                || value.starts_with('[')
-                || value.parse::<i64>().is_ok()
-            {
-                value.to_string()
-            } else {
-                format!(r#""{}""#, value)
-            }
+            format_value(value)
         ));
         self
     }
@@ -221,23 +258,7 @@ action "{}" "{}" {{"#,
     /// Add an input to the current action
     pub fn input(mut self, name: &str, value: &str) -> Self {
         if self.current_action.is_some() {
-            self.building_content.push(format!(
-                "    {} = {}",
-                name,
-                if value.starts_with("signer.")
-                    || value.starts_with("input.")
-                    || value.starts_with("action.")
-                    || value.starts_with("variable.")
-                    || value.starts_with("env.")
-                    || value.starts_with('"')
-                    || value.starts_with('[')
-                    || value.parse::<i64>().is_ok()
-                {
-                    value.to_string()
-                } else {
-                    format!(r#""{}""#, value)
-                }
-            ));
+            self.building_content.push(format!("    {} = {}", name, format_value(value)));
         }
         self
     }
@@ -283,6 +304,62 @@ signer "{}" "{}" {{
         self
     }
 
+    /// Add a `test::auto_signer` signer, which keypair is derived from its name and which signs
+    /// any payload without review
+    pub fn with_auto_signer(self, name: &str) -> Self {
+        let signer_type =
+            format!("{}::{}", crate::auto_signer::NAMESPACE, crate::auto_signer::AUTO_SIGNER);
+        self.signer(name, &signer_type, vec![])
+    }
+
+    /// Add a flow block, which inputs are set with a [FlowBuilder]
+    ///
+    /// ```rust
+    /// use txtx_test_utils::RunbookBuilder;
+    ///
+    /// let mut builder = RunbookBuilder::new()
+    ///     .with_flow("mainnet", |flow| flow.input("chain_id", "1"))
+    ///     .with_flow("sepolia", |flow| flow.input("chain_id", "11155111"));
+    ///
+    /// assert!(builder.build_content().contains("flow \"sepolia\""));
+    /// ```
+    pub fn with_flow(mut self, name: &str, build: impl FnOnce(FlowBuilder) -> FlowBuilder) -> Self {
+        self.close_current_action();
+        let flow = build(FlowBuilder::default());
+        self.building_content.push(render_block("flow", name, &flow.inputs));
+        self
+    }
+
+    /// Embed the runbook built by `child` under the name `name`, passing it `inputs`
+    ///
+    /// The child runbook is added to the files of the builder as `embedded/<name>.tx`, along with
+    /// its own files, and is referenced by the `location` of the `runbook` block generated. Its
+    /// diagnostics are reported against that file by [RunbookBuilder::validate].
+    pub fn with_embedded_runbook(
+        mut self,
+        name: &str,
+        mut child: RunbookBuilder,
+        inputs: Vec<(&str, &str)>,
+    ) -> Self {
+        self.close_current_action();
+        let location = format!("embedded/{}.tx", name);
+        self.files.insert(location.clone(), child.build_content());
+        self.files.extend(child.files.clone());
+        self.embedded_runbooks.push(location.clone());
+        self.embedded_runbooks.extend(child.embedded_runbooks.clone());
+
+        let mut attributes = vec![("location".to_string(), format!("{:?}", location))];
+        attributes.extend(inputs.into_iter().map(|(k, v)| (k.to_string(), format_value(v))));
+        self.building_content.push(render_block("runbook", name, &attributes));
+        self
+    }
+
+    fn close_current_action(&mut self) {
+        if self.current_action.take().is_some() {
+            self.building_content.push("}".to_string());
+        }
+    }
+
     // ==========================================
     // Internal Accessors for From/Into Traits
     // ==========================================
@@ -420,7 +497,16 @@ signer "{}" "{}" {{
             // This is appropriate when:
             // - No manifest/environments are provided (pure syntax validation)
             // - Environments are provided but no current environment is set (can't validate properly)
-            crate::simple_validator::validate_content(&content)
+            let mut result = crate::simple_validator::validate_content(&content);
+            // embedded runbooks are validated on their own, against their file
+            for location in self.embedded_runbooks.iter() {
+                let embedded =
+                    crate::simple_validator::validate_file_content(&self.files[location], location);
+                result.success &= embedded.success;
+                result.errors.extend(embedded.errors);
+                result.warnings.extend(embedded.warnings);
+            }
+            result
         }
     }
 
@@ -483,6 +569,50 @@ mod tests {
         assert!(content.contains("string = \"test value\""));
     }
 
+    #[test]
+    fn test_auto_signer() {
+        let mut builder = RunbookBuilder::new()
+            .with_auto_signer("deployer")
+            .action("deploy", "evm::deploy_contract")
+            .input("signer", "signer.deployer");
+
+        let content = builder.build_content();
+        assert!(content.contains("signer \"deployer\" \"test::auto_signer\""));
+        assert!(content.contains("signer = signer.deployer"));
+    }
+
+    #[test]
+    fn test_flows_and_embedded_runbooks() {
+        let child = RunbookBuilder::new()
+            .variable("amount", "input.amount")
+            .output("doubled", "variable.amount");
+        let mut builder = RunbookBuilder::new()
+            .with_flow("mainnet", |flow| flow.description("Mainnet").input("chain_id", "1"))
+            .action("first", "std::send_http_request")
+            .input("url", "https://example.com")
+            .with_embedded_runbook("child", child, vec![("amount", "10")]);
+
+        let content = builder.build_content();
+        assert!(content
+            .contains("flow \"mainnet\" {\n    description = \"Mainnet\"\n    chain_id = 1\n}"));
+        assert!(content.contains(
+            "runbook \"child\" {\n    location = \"embedded/child.tx\"\n    amount = 10\n}"
+        ));
+        // the action open is closed before the runbook block
+        assert_eq!(content.matches('{').count(), content.matches('}').count());
+        assert!(builder.files()["embedded/child.tx"].contains("output \"doubled\""));
+
+        // diagnostics of the embedded runbook are reported against its file
+        let broken = RunbookBuilder::new().output("broken", "action.missing.value");
+        let result =
+            RunbookBuilder::new().with_embedded_runbook("broken", broken, vec![]).validate();
+        assert!(!result.success);
+        assert!(result
+            .errors
+            .iter()
+            .all(|error| error.file.as_deref() == Some("embedded/broken.tx")));
+    }
+
     #[test]
     fn test_multi_file_support() {
         // Test multi-file runbook construction
@@ -216,6 +216,7 @@ impl RunbookBuilder {
             id: "test-id".to_string(),
             runbooks: Vec::new(),
             environments: IndexMap::new(),
+            signers: None,
             location: None,
         };
 
@@ -239,6 +240,7 @@ pub fn create_test_manifest_with_env(
         id: "test-id".to_string(),
         runbooks: Vec::new(),
         environments: IndexMap::new(),
+        signers: None,
         location: None,
     };
 
@@ -262,6 +264,7 @@ pub fn create_test_manifest_from_envs(
         id: "test-id".to_string(),
         runbooks: Vec::new(),
         environments: IndexMap::new(),
+        signers: None,
         location: None,
     };
 
@@ -0,0 +1,370 @@
+//! Property-based fuzzing helpers for addon functions
+//!
+//! [FunctionFuzzer] runs each function of an addon with arbitrary arguments, conforming to the
+//! types declared by its [FunctionSpecification] and deliberately violating them, and asserts
+//! that the function returns a value or a diagnostic but never panics. Encode/decode pairs can be
+//! registered by name to assert that decoding an encoded value gives the value back:
+//!
+//! ```rust,ignore
+//! use txtx_test_utils::fuzz::{arb_value, FunctionFuzzer};
+//!
+//! FunctionFuzzer::new(&StdAddon::new())
+//!     .with_round_trip("encode_hex", "decode_hex", arb_value(&Type::buffer()))
+//!     .run();
+//! ```
+//!
+//! The strategies generating the arguments are exposed to write dedicated properties.
+
+use std::collections::BTreeSet;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestCaseError, TestError, TestRunner};
+use txtx_addon_kit::bigdecimal::BigDecimal;
+use txtx_addon_kit::chrono::{DateTime, Utc};
+use txtx_addon_kit::indexmap::IndexMap;
+use txtx_addon_kit::types::functions::FunctionSpecification;
+use txtx_addon_kit::types::types::{ObjectDefinition, ObjectProperty, Type, Value};
+use txtx_addon_kit::types::AuthorizationContext;
+use txtx_addon_kit::Addon;
+
+/// Strings generated: printable ASCII text, or `0x` prefixed hex which functions commonly decode.
+fn arb_string() -> BoxedStrategy<String> {
+    prop_oneof!["[ -~]{0,48}", "0x[0-9a-f]{0,72}"].boxed()
+}
+
+fn arb_bytes() -> BoxedStrategy<Vec<u8>> {
+    prop::collection::vec(any::<u8>(), 0..72).boxed()
+}
+
+fn arb_object(properties: &[ObjectProperty]) -> BoxedStrategy<Value> {
+    let entries = properties
+        .iter()
+        .map(|property| {
+            let name = property.name.clone();
+            let value = arb_value(&property.typing).prop_map(move |value| (name.clone(), value));
+            if property.optional {
+                prop::option::of(value).boxed()
+            } else {
+                value.prop_map(Some).boxed()
+            }
+        })
+        .collect::<Vec<_>>();
+    entries
+        .prop_map(|entries| {
+            Value::object(entries.into_iter().flatten().collect::<IndexMap<_, _>>())
+        })
+        .boxed()
+}
+
+/// Generates decimals with up to 18 fractional digits, like token amounts.
+fn arb_decimal() -> BoxedStrategy<BigDecimal> {
+    (any::<i64>(), 0..=18i64)
+        .prop_map(|(digits, scale)| BigDecimal::new(digits.into(), scale))
+        .boxed()
+}
+
+/// Generates datetimes between the Unix epoch and the year 2100, with sub-second precision.
+fn arb_datetime() -> BoxedStrategy<DateTime<Utc>> {
+    (0..4_102_444_800i64, 0..1_000_000_000u32)
+        .prop_map(|(secs, nanos)| DateTime::from_timestamp(secs, nanos).unwrap())
+        .boxed()
+}
+
+/// Generates values conforming to a type. Integers are drawn from the `i64` range, so that
+/// arithmetic on two of them doesn't overflow.
+pub fn arb_value(typing: &Type) -> BoxedStrategy<Value> {
+    match typing {
+        Type::Bool => any::<bool>().prop_map(Value::bool).boxed(),
+        Type::Null(_) => Just(Value::null()).boxed(),
+        Type::Integer => any::<i64>().prop_map(|i| Value::integer(i as i128)).boxed(),
+        Type::Float => any::<f64>().prop_map(Value::float).boxed(),
+        Type::Decimal => arb_decimal().prop_map(Value::decimal).boxed(),
+        Type::DateTime => arb_datetime().prop_map(Value::datetime).boxed(),
+        Type::String => arb_string().prop_map(Value::string).boxed(),
+        Type::Buffer => arb_bytes().prop_map(Value::buffer).boxed(),
+        Type::Addon(id) => {
+            let id = id.clone();
+            arb_bytes().prop_map(move |bytes| Value::addon(bytes, &id)).boxed()
+        }
+        Type::Array(inner) => {
+            prop::collection::vec(arb_value(inner), 0..4).prop_map(Value::array).boxed()
+        }
+        Type::Object(definition) | Type::Map(definition) => match definition {
+            ObjectDefinition::Strict(properties)
+            | ObjectDefinition::Arbitrary(Some(properties))
+            | ObjectDefinition::Tuple(properties) => arb_object(properties),
+            ObjectDefinition::Enum(properties) if !properties.is_empty() => {
+                prop::sample::select(properties.clone())
+                    .prop_flat_map(|property| arb_object(&[property]))
+                    .boxed()
+            }
+            _ => prop::collection::vec(("[a-z_]{1,12}", arb_any_value()), 0..4)
+                .prop_map(|entries| Value::object(entries.into_iter().collect()))
+                .boxed(),
+        },
+        Type::OneOf(types) => {
+            prop::sample::select(types.clone()).prop_flat_map(|t| arb_value(&t)).boxed()
+        }
+    }
+}
+
+/// Generates values of any type, including nested arrays and objects.
+pub fn arb_any_value() -> BoxedStrategy<Value> {
+    let leaf = prop_oneof![
+        any::<bool>().prop_map(Value::bool),
+        Just(Value::null()),
+        any::<i64>().prop_map(|i| Value::integer(i as i128)),
+        any::<f64>().prop_map(Value::float),
+        arb_decimal().prop_map(Value::decimal),
+        arb_datetime().prop_map(Value::datetime),
+        arb_string().prop_map(Value::string),
+        arb_bytes().prop_map(Value::buffer),
+        ("[a-z]{1,8}::[a-z_]{1,12}", arb_bytes()).prop_map(|(id, bytes)| Value::addon(bytes, &id)),
+    ];
+    leaf.prop_recursive(3, 16, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 1..4).prop_map(Value::array),
+            prop::collection::vec(("[a-z_]{1,12}", inner), 0..4)
+                .prop_map(|entries| Value::object(entries.into_iter().collect())),
+        ]
+    })
+    .boxed()
+}
+
+/// Returns whether the arguments checker of the addon kit accepts a value for a type.
+fn is_accepted(value: &Value, typing: &Type) -> bool {
+    let value_type = value.get_type();
+    match (&value_type, typing) {
+        (Type::Addon(_), Type::Addon(_)) => true,
+        (Type::Array(_), _) if value.expect_array().is_empty() => true,
+        (_, Type::Array(inner)) if matches!(**inner, Type::Null(_)) => true,
+        _ => value_type.eq(typing),
+    }
+}
+
+/// Generates values accepted by none of the types of an input.
+pub fn arb_violating_value(typing: &[Type]) -> BoxedStrategy<Value> {
+    let typing = typing.to_vec();
+    arb_any_value()
+        .prop_filter("value conforming to the input type", move |value| {
+            !typing.iter().any(|t| is_accepted(value, t))
+        })
+        .boxed()
+}
+
+/// Generates arguments conforming to the inputs of a function. Trailing optional inputs may be
+/// omitted.
+pub fn arb_arguments(spec: &FunctionSpecification) -> BoxedStrategy<Vec<Value>> {
+    let inputs = spec
+        .inputs
+        .iter()
+        .map(|input| {
+            let value = prop::sample::select(input.typing.clone())
+                .prop_flat_map(|typing| arb_value(&typing));
+            if input.optional {
+                prop::option::of(value).boxed()
+            } else {
+                value.prop_map(Some).boxed()
+            }
+        })
+        .collect::<Vec<_>>();
+    inputs
+        .prop_map(|values| values.into_iter().map_while(|value| value).collect::<Vec<_>>())
+        .boxed()
+}
+
+/// Generates arguments conforming to the inputs of a function but one, which type is violated.
+pub fn arb_violating_arguments(spec: &FunctionSpecification) -> BoxedStrategy<Vec<Value>> {
+    let typings = spec.inputs.iter().map(|input| input.typing.clone()).collect::<Vec<_>>();
+    arb_arguments(spec)
+        .prop_filter("no argument to violate", |args| !args.is_empty())
+        .prop_flat_map(move |args| {
+            let typings = typings.clone();
+            (0..args.len()).prop_flat_map(move |position| {
+                let args = args.clone();
+                arb_violating_value(&typings[position]).prop_map(move |value| {
+                    let mut args = args.clone();
+                    args[position] = value;
+                    args
+                })
+            })
+        })
+        .boxed()
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// Runs a function, turning a panic into a test case failure.
+pub fn run_function(
+    spec: &FunctionSpecification,
+    args: &Vec<Value>,
+) -> Result<Result<Value, String>, TestCaseError> {
+    let auth_ctx = AuthorizationContext::empty();
+    match catch_unwind(AssertUnwindSafe(|| (spec.runner)(spec, &auth_ctx, args))) {
+        Ok(result) => Ok(result.map_err(|diag| diag.message)),
+        Err(payload) => Err(TestCaseError::fail(format!("panicked: {}", panic_message(payload)))),
+    }
+}
+
+struct RoundTrip {
+    encode: String,
+    decode: String,
+    values: BoxedStrategy<Value>,
+}
+
+/// Fuzzes the functions of an addon, reporting every function which panicked with the minimal
+/// arguments found.
+pub struct FunctionFuzzer {
+    namespace: String,
+    functions: Vec<FunctionSpecification>,
+    config: Config,
+    skipped: BTreeSet<String>,
+    type_checked_by_caller: BTreeSet<String>,
+    round_trips: Vec<RoundTrip>,
+}
+
+impl FunctionFuzzer {
+    pub fn new(addon: &dyn Addon) -> Self {
+        let mut functions = addon.build_function_lookup().into_values().collect::<Vec<_>>();
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            namespace: addon.get_namespace().to_string(),
+            functions,
+            config: Config::with_cases(64),
+            skipped: BTreeSet::new(),
+            type_checked_by_caller: BTreeSet::new(),
+            round_trips: vec![],
+        }
+    }
+
+    /// Set the number of cases generated for each property
+    pub fn with_cases(mut self, cases: u32) -> Self {
+        self.config = Config::with_cases(cases);
+        self
+    }
+
+    /// Don't fuzz a function, e.g. one reading the filesystem
+    pub fn skip(mut self, name: &str) -> Self {
+        self.skipped.insert(name.to_string());
+        self
+    }
+
+    /// Only fuzz a function with arguments conforming to its inputs, for functions only called
+    /// once the types of their arguments were checked, e.g. the operators
+    pub fn skip_type_violations(mut self, name: &str) -> Self {
+        self.type_checked_by_caller.insert(name.to_string());
+        self
+    }
+
+    /// Assert that `decode(encode(value)) == value` for the values generated
+    pub fn with_round_trip(
+        mut self,
+        encode: &str,
+        decode: &str,
+        values: BoxedStrategy<Value>,
+    ) -> Self {
+        self.round_trips.push(RoundTrip {
+            encode: encode.to_string(),
+            decode: decode.to_string(),
+            values,
+        });
+        self
+    }
+
+    fn get_function(&self, name: &str) -> &FunctionSpecification {
+        self.functions.iter().find(|spec| spec.name.eq(name)).unwrap_or_else(|| {
+            panic!("function {}::{} not found", self.namespace, name);
+        })
+    }
+
+    fn check(
+        &self,
+        property: &str,
+        strategy: &BoxedStrategy<Vec<Value>>,
+        spec: &FunctionSpecification,
+    ) -> Option<String> {
+        let mut runner = TestRunner::new(self.config.clone());
+        match runner.run(strategy, |args| run_function(spec, &args).map(|_| ())) {
+            Ok(()) => None,
+            // no arguments could be generated, e.g. an input accepting values of any type
+            Err(TestError::Abort(_)) => None,
+            Err(e) => Some(format!("{}::{} ({}): {}", self.namespace, spec.name, property, e)),
+        }
+    }
+
+    fn check_round_trip(&self, round_trip: &RoundTrip) -> Option<String> {
+        let encode = self.get_function(&round_trip.encode);
+        let decode = self.get_function(&round_trip.decode);
+        let mut runner = TestRunner::new(self.config.clone());
+        runner
+            .run(&round_trip.values, |value| {
+                let encoded = run_function(encode, &vec![value.clone()])?
+                    .map_err(|e| TestCaseError::fail(format!("encoding failed: {}", e)))?;
+                let decoded = run_function(decode, &vec![encoded])?
+                    .map_err(|e| TestCaseError::fail(format!("decoding failed: {}", e)))?;
+                prop_assert_eq!(decoded, value);
+                Ok(())
+            })
+            .err()
+            .map(|e| {
+                format!(
+                    "{ns}::{} / {ns}::{} (round trip): {}",
+                    round_trip.encode,
+                    round_trip.decode,
+                    e,
+                    ns = self.namespace
+                )
+            })
+    }
+
+    /// Fuzz the functions and the round trips registered, panicking with the failures found
+    pub fn run(self) {
+        let mut failures = vec![];
+        for spec in self.functions.iter().filter(|spec| !self.skipped.contains(&spec.name)) {
+            failures.extend(self.check("conforming arguments", &arb_arguments(spec), spec));
+            if !spec.inputs.is_empty() && !self.type_checked_by_caller.contains(&spec.name) {
+                failures.extend(self.check(
+                    "violating argument types",
+                    &arb_violating_arguments(spec),
+                    spec,
+                ));
+            }
+        }
+        for round_trip in self.round_trips.iter() {
+            failures.extend(self.check_round_trip(round_trip));
+        }
+        assert!(failures.is_empty(), "fuzzing found failures:\n{}", failures.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_violating_values_are_rejected() {
+        let mut runner = TestRunner::new(Config::with_cases(32));
+        let typing = vec![Type::string(), Type::buffer()];
+        runner
+            .run(&arb_violating_value(&typing), |value| {
+                prop_assert!(!typing.iter().any(|t| is_accepted(&value, t)));
+                Ok(())
+            })
+            .unwrap();
+        runner
+            .run(&arb_value(&Type::array(Type::integer())), |value| {
+                prop_assert!(is_accepted(&value, &Type::array(Type::integer())));
+                Ok(())
+            })
+            .unwrap();
+    }
+}
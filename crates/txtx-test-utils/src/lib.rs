@@ -1,9 +1,13 @@
 mod addon_registry;
 pub mod assertions;
+pub mod auto_signer;
 pub mod builders;
+pub mod fuzz;
+pub mod mock_rpc;
 mod simple_validator;
 pub mod test_harness;
 
+pub use auto_signer::{AutoSignerKeypair, TestAddon};
 pub use builders::RunbookBuilder;
 pub use txtx_core::std::StdAddon;
 
@@ -0,0 +1,414 @@
+//! Embedded JSON-RPC server for addon integration tests
+//!
+//! [MockRpc] listens on an ephemeral local port and answers JSON-RPC requests with the responses
+//! scripted by a test, so that addons can be pointed at its URL instead of a live endpoint:
+//!
+//! ```rust,ignore
+//! use txtx_test_utils::mock_rpc::{scenarios, MockRpc, RpcRule};
+//!
+//! let rpc = MockRpc::start()
+//!     .with_scenario(scenarios::evm_deploy_happy_path())
+//!     .with_rule(RpcRule::new("eth_getBalance").respond_with(json!("0x0")));
+//!
+//! // ... run a runbook with `rpc_api_url = rpc.url()`
+//!
+//! assert_eq!(rpc.calls("eth_sendRawTransaction"), 1);
+//! ```
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use txtx_addon_kit::serde_json::{self, json, Value as JsonValue};
+
+/// Error code returned for the requests matching none of the rules.
+pub const METHOD_NOT_FOUND: i64 = -32601;
+
+type ParamsPredicate = Box<dyn Fn(&JsonValue) -> bool + Send>;
+
+/// A JSON-RPC request received by the mock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcRequest {
+    pub method: String,
+    pub params: JsonValue,
+}
+
+/// The response of a rule, either a result or a JSON-RPC error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RpcResponse {
+    Result(JsonValue),
+    Error { code: i64, message: String },
+}
+
+/// Answers the requests to a method, optionally only those which parameters match a predicate.
+pub struct RpcRule {
+    method: String,
+    params: Option<ParamsPredicate>,
+    response: RpcResponse,
+    /// Responses overriding `response` for some calls, by 1-based call index
+    overrides: Vec<(usize, RpcResponse)>,
+    calls: usize,
+}
+
+impl RpcRule {
+    /// Create a rule answering every call to `method` with a `null` result
+    pub fn new(method: &str) -> Self {
+        Self {
+            method: method.to_string(),
+            params: None,
+            response: RpcResponse::Result(JsonValue::Null),
+            overrides: vec![],
+            calls: 0,
+        }
+    }
+
+    /// Only answer the calls which parameters match a predicate
+    pub fn with_params(mut self, predicate: impl Fn(&JsonValue) -> bool + Send + 'static) -> Self {
+        self.params = Some(Box::new(predicate));
+        self
+    }
+
+    /// Answer with a result fixture
+    pub fn respond_with(mut self, result: JsonValue) -> Self {
+        self.response = RpcResponse::Result(result);
+        self
+    }
+
+    /// Answer with a JSON-RPC error
+    pub fn respond_with_error(mut self, code: i64, message: &str) -> Self {
+        self.response = RpcResponse::Error { code, message: message.to_string() };
+        self
+    }
+
+    /// Answer the `n`th call matching this rule (starting at 1) with a JSON-RPC error instead,
+    /// e.g. to exercise the retries of an addon
+    pub fn fail_call(mut self, n: usize, code: i64, message: &str) -> Self {
+        self.overrides.push((n, RpcResponse::Error { code, message: message.to_string() }));
+        self
+    }
+
+    fn matches(&self, request: &RpcRequest) -> bool {
+        self.method.eq(&request.method)
+            && self.params.as_ref().map_or(true, |predicate| predicate(&request.params))
+    }
+
+    fn next_response(&mut self) -> RpcResponse {
+        self.calls += 1;
+        self.overrides
+            .iter()
+            .find(|(n, _)| *n == self.calls)
+            .map(|(_, response)| response.clone())
+            .unwrap_or_else(|| self.response.clone())
+    }
+}
+
+#[derive(Default)]
+struct MockRpcState {
+    rules: Vec<RpcRule>,
+    requests: Vec<RpcRequest>,
+}
+
+impl MockRpcState {
+    /// Answers a single JSON-RPC request with the first rule matching it.
+    fn handle(&mut self, request: &JsonValue) -> JsonValue {
+        let id = request.get("id").cloned().unwrap_or(JsonValue::Null);
+        let request = RpcRequest {
+            method: request.get("method").and_then(|m| m.as_str()).unwrap_or_default().to_string(),
+            params: request.get("params").cloned().unwrap_or(JsonValue::Null),
+        };
+        let response = match self.rules.iter_mut().find(|rule| rule.matches(&request)) {
+            Some(rule) => rule.next_response(),
+            None => RpcResponse::Error {
+                code: METHOD_NOT_FOUND,
+                message: format!("no mock response for method '{}'", request.method),
+            },
+        };
+        self.requests.push(request);
+        match response {
+            RpcResponse::Result(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            RpcResponse::Error { code, message } => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message }
+            }),
+        }
+    }
+}
+
+/// An embedded HTTP server answering JSON-RPC requests with scripted responses, and recording
+/// them for assertions. The server stops when the mock is dropped.
+pub struct MockRpc {
+    url: String,
+    state: Arc<Mutex<MockRpcState>>,
+    shutdown: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockRpc {
+    /// Start a server listening on an ephemeral local port
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("unable to bind mock rpc server");
+        listener.set_nonblocking(true).expect("unable to configure mock rpc server");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(MockRpcState::default()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let state = state.clone();
+            let shutdown = shutdown.clone();
+            std::thread::spawn(move || {
+                while !shutdown.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, _)) => {
+                            let _ = serve_connection(stream, &state);
+                        }
+                        Err(_) => std::thread::sleep(Duration::from_millis(5)),
+                    }
+                }
+            })
+        };
+        Self { url, state, shutdown, handle: Some(handle) }
+    }
+
+    /// Add a rule, taking precedence over the rules added after it
+    pub fn with_rule(self, rule: RpcRule) -> Self {
+        self.state.lock().unwrap().rules.push(rule);
+        self
+    }
+
+    /// Add the rules of a scenario, e.g. [scenarios::evm_deploy_happy_path]
+    pub fn with_scenario(self, rules: Vec<RpcRule>) -> Self {
+        self.state.lock().unwrap().rules.extend(rules);
+        self
+    }
+
+    /// The URL of the server, to be used as `rpc_api_url` by addons
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// The requests received so far, in order
+    pub fn requests(&self) -> Vec<RpcRequest> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// The number of requests received so far for a method
+    pub fn calls(&self, method: &str) -> usize {
+        self.state.lock().unwrap().requests.iter().filter(|r| r.method.eq(method)).count()
+    }
+}
+
+impl Drop for MockRpc {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Reads a single HTTP request from a connection, and answers it before closing the connection.
+fn serve_connection(stream: TcpStream, state: &Arc<Mutex<MockRpcState>>) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut content_length = 0;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+
+    let response = match serde_json::from_slice::<JsonValue>(&body) {
+        Ok(JsonValue::Array(batch)) => {
+            let mut state = state.lock().unwrap();
+            JsonValue::Array(batch.iter().map(|request| state.handle(request)).collect())
+        }
+        Ok(request) => state.lock().unwrap().handle(&request),
+        Err(e) => json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32700, "message": format!("parse error: {}", e) }
+        }),
+    };
+    let response = response.to_string();
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.len(),
+        response
+    )?;
+    stream.flush()
+}
+
+/// Ready-made rules for common flows.
+pub mod scenarios {
+    use super::RpcRule;
+    use txtx_addon_kit::serde_json::json;
+
+    pub const EVM_CHAIN_ID: u64 = 31337;
+    pub const EVM_TX_HASH: &str =
+        "0x9f8d4b3f3c5d2d1e0a6b7c8d9e0f1a2b3c4d5e6f708192a3b4c5d6e7f8091a2b";
+    pub const EVM_CONTRACT_ADDRESS: &str = "0x5fbdb2315678afecb367f032d93f642f64180aa3";
+    pub const SVM_BLOCKHASH: &str = "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N";
+    pub const SVM_SIGNATURE: &str =
+        "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+
+    /// An EVM contract deployment going through at the first attempt, on a local devnet.
+    pub fn evm_deploy_happy_path() -> Vec<RpcRule> {
+        vec![
+            RpcRule::new("eth_chainId").respond_with(json!(format!("0x{:x}", EVM_CHAIN_ID))),
+            RpcRule::new("net_version").respond_with(json!(EVM_CHAIN_ID.to_string())),
+            RpcRule::new("eth_blockNumber").respond_with(json!("0x1")),
+            RpcRule::new("eth_getBalance").respond_with(json!("0xde0b6b3a7640000")),
+            RpcRule::new("eth_getTransactionCount").respond_with(json!("0x0")),
+            RpcRule::new("eth_getCode").respond_with(json!("0x")),
+            RpcRule::new("eth_gasPrice").respond_with(json!("0x3b9aca00")),
+            RpcRule::new("eth_maxPriorityFeePerGas").respond_with(json!("0x3b9aca00")),
+            RpcRule::new("eth_estimateGas").respond_with(json!("0x1e8480")),
+            RpcRule::new("eth_feeHistory").respond_with(json!({
+                "oldestBlock": "0x1",
+                "baseFeePerGas": ["0x3b9aca00", "0x3b9aca00"],
+                "gasUsedRatio": [0.5],
+                "reward": [["0x3b9aca00"]]
+            })),
+            RpcRule::new("eth_getBlockByNumber").respond_with(json!({
+                "number": "0x1",
+                "hash": "0x8f5e5c2a3d1b0c9e7f6a5b4c3d2e1f0a9b8c7d6e5f4a3b2c1d0e9f8a7b6c5d4e",
+                "timestamp": "0x0",
+                "baseFeePerGas": "0x3b9aca00",
+                "gasLimit": "0x1c9c380",
+                "gasUsed": "0x0",
+                "transactions": []
+            })),
+            RpcRule::new("eth_sendRawTransaction").respond_with(json!(EVM_TX_HASH)),
+            RpcRule::new("eth_getTransactionReceipt").respond_with(json!({
+                "transactionHash": EVM_TX_HASH,
+                "transactionIndex": "0x0",
+                "blockNumber": "0x2",
+                "blockHash": "0x8f5e5c2a3d1b0c9e7f6a5b4c3d2e1f0a9b8c7d6e5f4a3b2c1d0e9f8a7b6c5d4e",
+                "from": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                "to": null,
+                "contractAddress": EVM_CONTRACT_ADDRESS,
+                "cumulativeGasUsed": "0x1e8480",
+                "gasUsed": "0x1e8480",
+                "effectiveGasPrice": "0x3b9aca00",
+                "logs": [],
+                "logsBloom": format!("0x{}", "0".repeat(512)),
+                "status": "0x1",
+                "type": "0x2"
+            })),
+        ]
+    }
+
+    /// An SVM transaction which first submission is rejected because its blockhash expired, and
+    /// which goes through once resubmitted.
+    pub fn svm_blockhash_expiry_then_success() -> Vec<RpcRule> {
+        vec![
+            RpcRule::new("getVersion").respond_with(json!({ "solana-core": "2.1.0" })),
+            RpcRule::new("getLatestBlockhash").respond_with(json!({
+                "context": { "slot": 1 },
+                "value": { "blockhash": SVM_BLOCKHASH, "lastValidBlockHeight": 150 }
+            })),
+            RpcRule::new("getBlockHeight").respond_with(json!(100)),
+            RpcRule::new("getBalance")
+                .respond_with(json!({ "context": { "slot": 1 }, "value": 1_000_000_000u64 })),
+            RpcRule::new("getMinimumBalanceForRentExemption").respond_with(json!(890_880)),
+            RpcRule::new("getAccountInfo")
+                .respond_with(json!({ "context": { "slot": 1 }, "value": null })),
+            RpcRule::new("sendTransaction").respond_with(json!(SVM_SIGNATURE)).fail_call(
+                1,
+                -32002,
+                "Transaction simulation failed: Blockhash not found",
+            ),
+            RpcRule::new("getSignatureStatuses").respond_with(json!({
+                "context": { "slot": 2 },
+                "value": [{
+                    "slot": 2,
+                    "confirmations": null,
+                    "err": null,
+                    "status": { "Ok": null },
+                    "confirmationStatus": "finalized"
+                }]
+            })),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(url: &str, body: JsonValue) -> JsonValue {
+        let mut stream = TcpStream::connect(url.trim_start_matches("http://")).unwrap();
+        let body = body.to_string();
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        serde_json::from_str(body).unwrap()
+    }
+
+    fn call(url: &str, method: &str, params: JsonValue) -> JsonValue {
+        post(url, json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+    }
+
+    #[test]
+    fn test_rules_and_request_log() {
+        let rpc = MockRpc::start()
+            .with_rule(
+                RpcRule::new("eth_getBalance")
+                    .with_params(|params| params[0] == "0x01")
+                    .respond_with(json!("0x1")),
+            )
+            .with_rule(RpcRule::new("eth_getBalance").respond_with(json!("0x0")));
+
+        assert_eq!(call(rpc.url(), "eth_getBalance", json!(["0x01"]))["result"], "0x1");
+        assert_eq!(call(rpc.url(), "eth_getBalance", json!(["0x02"]))["result"], "0x0");
+        let response = call(rpc.url(), "eth_call", json!([]));
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let batch = post(
+            rpc.url(),
+            json!([
+                { "jsonrpc": "2.0", "id": 1, "method": "eth_getBalance", "params": ["0x01"] },
+                { "jsonrpc": "2.0", "id": 2, "method": "eth_getBalance", "params": ["0x02"] }
+            ]),
+        );
+        assert_eq!(batch[1]["id"], 2);
+
+        assert_eq!(rpc.calls("eth_getBalance"), 4);
+        assert_eq!(rpc.requests()[2], RpcRequest { method: "eth_call".into(), params: json!([]) });
+    }
+
+    #[test]
+    fn test_scenario_with_failing_call() {
+        let rpc = MockRpc::start().with_scenario(scenarios::svm_blockhash_expiry_then_success());
+
+        let first = call(rpc.url(), "sendTransaction", json!(["tx"]));
+        assert_eq!(first["error"]["code"], -32002);
+        let second = call(rpc.url(), "sendTransaction", json!(["tx"]));
+        assert_eq!(second["result"], scenarios::SVM_SIGNATURE);
+        assert_eq!(rpc.calls("sendTransaction"), 2);
+    }
+}
@@ -42,6 +42,30 @@ pub fn validate_content(content: &str) -> ValidationResult {
     ValidationResult { success: errors.is_empty(), errors, warnings: vec![] }
 }
 
+/// Validate the content of a runbook file, keeping the location of the diagnostics in the file
+pub fn validate_file_content(content: &str, file_path: &str) -> ValidationResult {
+    let mut core_result =
+        CoreResult { errors: Vec::new(), warnings: Vec::new(), suggestions: Vec::new() };
+
+    let addons = get_all_addons();
+    let addon_specs = extract_addon_specifications(&addons);
+
+    if let Err(e) = hcl_validator::validate_with_hcl_and_addons(
+        content,
+        &mut core_result,
+        file_path,
+        addon_specs,
+    ) {
+        core_result.errors.push(Diagnostic::error_from_string(e).with_file(file_path));
+    }
+
+    ValidationResult {
+        success: core_result.errors.is_empty(),
+        errors: core_result.errors,
+        warnings: core_result.warnings,
+    }
+}
+
 /// Validate runbook content with manifest and environment support using ValidationContext
 pub fn validate_content_with_manifest(
     content: &str,
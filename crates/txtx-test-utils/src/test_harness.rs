@@ -1,7 +1,8 @@
+use std::collections::BTreeSet;
 use std::time::Duration;
 
 use txtx_addon_kit::{
-    channel::{Receiver, Sender},
+    channel::{Receiver, RecvTimeoutError, Sender},
     futures::executor::block_on,
     helpers::fs::FileLocation,
     types::{
@@ -9,8 +10,9 @@ use txtx_addon_kit::{
         cloud_interface::CloudServiceContext,
         diagnostics::Diagnostic,
         frontend::{
-            ActionItemRequest, ActionItemResponse, ActionItemStatus, ActionPanelData, BlockEvent,
-            LogEvent, ModalPanelData, NormalizedActionItemRequestUpdate,
+            ActionItemRequest, ActionItemRequestType, ActionItemResponse, ActionItemResponseType,
+            ActionItemStatus, ActionPanelData, Block, BlockEvent, LogEvent, ModalPanelData,
+            NormalizedActionItemRequestUpdate, Panel, ProvidedFileResponse, ReviewedInputResponse,
         },
         types::Value,
         AuthorizationContext, RunbookId,
@@ -21,6 +23,7 @@ use txtx_core::{
     runbook::RunbookTopLevelInputsMap,
     start_supervised_runbook_runloop,
     types::{Runbook, RunbookSources},
+    SET_ENV_ACTION,
 };
 #[allow(unused)]
 pub struct TestHarness {
@@ -37,8 +40,19 @@ impl TestHarness {
         let _ = self.action_item_events_tx.send(response.clone());
     }
 
+    /// Receives the next event, skipping the construct status updates published along the
+    /// panels and the heartbeats of background tasks.
+    fn recv_timeout(&self, timeout: Duration) -> Result<BlockEvent, RecvTimeoutError> {
+        loop {
+            match self.block_rx.recv_timeout(timeout)? {
+                BlockEvent::UpdateConstructStatuses(_) | BlockEvent::Heartbeat(_) => continue,
+                event => return Ok(event),
+            }
+        }
+    }
+
     pub fn receive_event(&self) -> BlockEvent {
-        let Ok(event) = self.block_rx.recv_timeout(Duration::from_secs(5)) else {
+        let Ok(event) = self.recv_timeout(Duration::from_secs(5)) else {
             panic!("unable to receive input block");
         };
         event
@@ -63,7 +77,7 @@ impl TestHarness {
         response: Option<ActionItemResponse>,
         expected_updates: Vec<(&BlockId, Option<ActionItemStatus>)>,
     ) -> Vec<NormalizedActionItemRequestUpdate> {
-        let Ok(event) = self.block_rx.recv_timeout(Duration::from_secs(5)) else {
+        let Ok(event) = self.recv_timeout(Duration::from_secs(5)) else {
             panic!(
                 "unable to receive input block after sending action item response: {:?}",
                 response
@@ -84,7 +98,7 @@ impl TestHarness {
     }
 
     pub fn expect_log_event(&self, response: Option<ActionItemResponse>) -> LogEvent {
-        let Ok(event) = self.block_rx.recv_timeout(Duration::from_secs(5)) else {
+        let Ok(event) = self.recv_timeout(Duration::from_secs(5)) else {
             panic!(
                 "unable to receive input block after sending action item response: {:?}",
                 response
@@ -120,7 +134,7 @@ impl TestHarness {
         expected_title: &str,
         expected_group_lengths: Vec<Vec<usize>>,
     ) -> ActionPanelData {
-        let Ok(event) = self.block_rx.recv_timeout(Duration::from_secs(5)) else {
+        let Ok(event) = self.recv_timeout(Duration::from_secs(5)) else {
             panic!(
                 "unable to receive input block after sending action item response: {:?}",
                 response
@@ -157,7 +171,7 @@ impl TestHarness {
         expected_title: &str,
         expected_group_lengths: Vec<Vec<usize>>,
     ) -> ModalPanelData {
-        let Ok(event) = self.block_rx.recv_timeout(Duration::from_secs(5)) else {
+        let Ok(event) = self.recv_timeout(Duration::from_secs(5)) else {
             panic!(
                 "unable to receive input block after sending action item response: {:?}",
                 response
@@ -188,13 +202,13 @@ impl TestHarness {
     }
 
     pub fn expect_noop(&self) {
-        let Err(_) = self.block_rx.recv_timeout(Duration::from_secs(2)) else {
+        let Err(_) = self.recv_timeout(Duration::from_secs(2)) else {
             panic!("unable to receive input block")
         };
     }
 
     pub fn expect_runbook_complete(&self) {
-        let Ok(event) = self.block_rx.recv_timeout(Duration::from_secs(5)) else {
+        let Ok(event) = self.recv_timeout(Duration::from_secs(5)) else {
             assert!(false, "unable to receive input block");
             panic!()
         };
@@ -232,9 +246,24 @@ pub async fn build_runbook_from_fixture(
     file_name: &str,
     fixture: &str,
     get_addon_by_namespace: fn(&str) -> Option<Box<dyn Addon>>,
+) -> Result<Runbook, Vec<Diagnostic>> {
+    build_runbook_from_fixture_with_inputs(
+        file_name,
+        fixture,
+        RunbookTopLevelInputsMap::new(),
+        get_addon_by_namespace,
+    )
+    .await
+}
+
+/// Builds a runbook from a fixture, with the top level inputs of its environments.
+pub async fn build_runbook_from_fixture_with_inputs(
+    file_name: &str,
+    fixture: &str,
+    runbook_inputs: RunbookTopLevelInputsMap,
+    get_addon_by_namespace: fn(&str) -> Option<Box<dyn Addon>>,
 ) -> Result<Runbook, Vec<Diagnostic>> {
     let runbook_sources = runbook_sources_from_fixture(file_name, fixture);
-    let runbook_inputs = RunbookTopLevelInputsMap::new();
 
     let runbook_id = RunbookId { org: None, workspace: None, name: "test".into() };
 
@@ -257,7 +286,28 @@ pub fn setup_test(
     fixture: &str,
     get_addon_by_namespace: fn(&str) -> Option<Box<dyn Addon>>,
 ) -> TestHarness {
-    let future = build_runbook_from_fixture(file_name, fixture, get_addon_by_namespace);
+    setup_test_with_inputs(
+        file_name,
+        fixture,
+        RunbookTopLevelInputsMap::new(),
+        get_addon_by_namespace,
+    )
+}
+
+/// Runs the supervised runloop of a fixture with the top level inputs of its environments, e.g.
+/// built with [RunbookTopLevelInputsMap::from_environment_map].
+pub fn setup_test_with_inputs(
+    file_name: &str,
+    fixture: &str,
+    runbook_inputs: RunbookTopLevelInputsMap,
+    get_addon_by_namespace: fn(&str) -> Option<Box<dyn Addon>>,
+) -> TestHarness {
+    let future = build_runbook_from_fixture_with_inputs(
+        file_name,
+        fixture,
+        runbook_inputs,
+        get_addon_by_namespace,
+    );
     let mut runbook = block_on(future).expect("unable to build runbook from fixture");
 
     let (block_tx, block_rx) = txtx_addon_kit::channel::unbounded::<BlockEvent>();
@@ -283,3 +333,220 @@ pub fn setup_test(
     });
     harness
 }
+
+type ResponseScript = Box<dyn Fn(&ActionItemRequest) -> Option<ActionItemResponseType> + Send>;
+
+/// A response to send for the action items matching a script.
+struct ScriptedResponse {
+    description: String,
+    script: ResponseScript,
+    /// Whether the response is only sent for the first action item matching the script
+    once: bool,
+    used: bool,
+}
+
+/// Drives the supervised runloop of a [TestHarness] like a frontend would, answering the action
+/// items of the panels with scripted responses, and recording the block events received.
+///
+/// ```rust,ignore
+/// let mut runbook = ScriptedRunbook::new(setup_test("main.tx", fixture, get_addon))
+///     .pick_environment("mainnet")
+///     .review_all_inputs()
+///     .approve_validate_blocks();
+/// runbook.run_until_complete();
+/// assert!(runbook.events().iter().any(|event| matches!(event, BlockEvent::Clear)));
+/// ```
+///
+/// Waiting for an event times out, failing the test with the events received so far instead of
+/// hanging.
+pub struct ScriptedRunbook {
+    harness: TestHarness,
+    responses: Vec<ScriptedResponse>,
+    events: Vec<BlockEvent>,
+    answered: BTreeSet<BlockId>,
+    timeout: Duration,
+}
+
+impl ScriptedRunbook {
+    pub fn new(harness: TestHarness) -> Self {
+        Self {
+            harness,
+            responses: vec![],
+            events: vec![],
+            answered: BTreeSet::new(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Set how long to wait for each event before failing the test
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Answer the action items for which `script` returns a response
+    pub fn respond_when(
+        mut self,
+        description: &str,
+        script: impl Fn(&ActionItemRequest) -> Option<ActionItemResponseType> + Send + 'static,
+    ) -> Self {
+        self.responses.push(ScriptedResponse {
+            description: description.to_string(),
+            script: Box::new(script),
+            once: false,
+            used: false,
+        });
+        self
+    }
+
+    /// Answer the first action item for which `script` returns a response
+    pub fn respond_once_when(
+        mut self,
+        description: &str,
+        script: impl Fn(&ActionItemRequest) -> Option<ActionItemResponseType> + Send + 'static,
+    ) -> Self {
+        self = self.respond_when(description, script);
+        self.responses.last_mut().unwrap().once = true;
+        self
+    }
+
+    /// Check the inputs reviewed for the construct named `construct_name`
+    pub fn review_inputs_of(self, construct_name: &str) -> Self {
+        let construct_name = construct_name.to_string();
+        self.respond_when(&format!("review inputs of {}", construct_name), move |request| {
+            if !request.construct_instance_name.eq(&construct_name) {
+                return None;
+            }
+            review_input(request)
+        })
+    }
+
+    /// Check all the inputs reviewed
+    pub fn review_all_inputs(self) -> Self {
+        self.respond_when("review all inputs", review_input)
+    }
+
+    /// Upload the same file, with its content base64 encoded, for all the files requested
+    pub fn provide_files(self, filename: &str, content: &str) -> Self {
+        let (filename, content) = (filename.to_string(), content.to_string());
+        self.respond_when(&format!("provide file {}", filename), move |request| {
+            let ActionItemRequestType::ProvideFile(file) = &request.action_type else {
+                return None;
+            };
+            Some(ActionItemResponseType::ProvideFile(ProvidedFileResponse {
+                input_name: file.input_name.clone(),
+                filename: filename.clone(),
+                content: content.clone(),
+            }))
+        })
+    }
+
+    /// Approve the next block to validate
+    pub fn approve_next_validate_block(self) -> Self {
+        self.respond_once_when("approve next block", validate_block)
+    }
+
+    /// Approve all the blocks to validate
+    pub fn approve_validate_blocks(self) -> Self {
+        self.respond_when("approve blocks", validate_block)
+    }
+
+    /// Select an environment in the genesis panel, resetting the execution of the runbook
+    pub fn pick_environment(self, environment: &str) -> Self {
+        let environment = environment.to_string();
+        self.respond_once_when(&format!("pick environment {}", environment), move |request| {
+            match request.action_type {
+                ActionItemRequestType::PickInputOption(_) if request.id == SET_ENV_ACTION.id => {
+                    Some(ActionItemResponseType::PickInputOption(environment.clone()))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// The block events received so far
+    pub fn events(&self) -> &[BlockEvent] {
+        &self.events
+    }
+
+    /// Receive the next event, answering the action items of the panels received
+    pub fn next_event(&mut self) -> BlockEvent {
+        let Ok(event) = self.harness.recv_timeout(self.timeout) else {
+            panic!(
+                "no event received after {:?}\n=> scripted responses: {:?}\n=> events received: {:#?}",
+                self.timeout,
+                self.responses
+                    .iter()
+                    .map(|r| format!("{} (used: {})", r.description, r.used))
+                    .collect::<Vec<_>>(),
+                self.events
+            );
+        };
+        self.events.push(event.clone());
+        match &event {
+            BlockEvent::Action(block) | BlockEvent::Modal(block) => self.answer(block),
+            BlockEvent::Clear => self.answered.clear(),
+            BlockEvent::Error(block) => panic!("runbook execution failed: {:#?}", block),
+            _ => {}
+        }
+        event
+    }
+
+    /// Receive events until the runbook completes, failing the test on errors and timeouts
+    pub fn run_until_complete(&mut self) {
+        while !matches!(self.next_event(), BlockEvent::RunbookCompleted(_)) {}
+    }
+
+    fn answer(&mut self, block: &Block) {
+        let groups = match &block.panel {
+            Panel::ActionPanel(data) => &data.groups,
+            Panel::ModalPanel(data) => &data.groups,
+            Panel::ErrorPanel(_) => return,
+        };
+        let action_items = groups
+            .iter()
+            .flat_map(|group| group.sub_groups.iter())
+            .flat_map(|sub_group| sub_group.action_items.iter());
+        for request in action_items {
+            // the environment picker is displayed as completed, with the default environment
+            let is_env_picker = request.id == SET_ENV_ACTION.id;
+            if (!is_env_picker && matches!(request.action_status, ActionItemStatus::Success(_)))
+                || self.answered.contains(&request.id)
+            {
+                continue;
+            }
+            let scripted =
+                self.responses.iter_mut().filter(|r| !(r.once && r.used)).find_map(|response| {
+                    (response.script)(request).map(|payload| (response, payload))
+                });
+            if let Some((response, payload)) = scripted {
+                response.used = true;
+                self.answered.insert(request.id.clone());
+                self.harness
+                    .send(&ActionItemResponse { action_item_id: request.id.clone(), payload });
+                if is_env_picker {
+                    // the panel is cleared and built again for the environment picked
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn review_input(request: &ActionItemRequest) -> Option<ActionItemResponseType> {
+    let ActionItemRequestType::ReviewInput(review) = &request.action_type else {
+        return None;
+    };
+    Some(ActionItemResponseType::ReviewInput(ReviewedInputResponse {
+        input_name: review.input_name.clone(),
+        value_checked: true,
+        force_execution: review.force_execution,
+    }))
+}
+
+fn validate_block(request: &ActionItemRequest) -> Option<ActionItemResponseType> {
+    match request.action_type {
+        ActionItemRequestType::ValidateBlock(_) => Some(ActionItemResponseType::ValidateBlock),
+        _ => None,
+    }
+}
@@ -0,0 +1,32 @@
+use txtx_addon_kit::types::types::Type;
+use txtx_addon_network_evm::EvmNetworkAddon;
+use txtx_test_utils::fuzz::{arb_value, FunctionFuzzer};
+use txtx_test_utils::StdAddon;
+
+#[test]
+fn fuzz_std_functions() {
+    let mut fuzzer = FunctionFuzzer::new(&StdAddon::new())
+        // unary operators are evaluated in place by the runtime
+        .skip("neg_integer")
+        .skip("not_bool")
+        .with_round_trip("encode_hex", "decode_hex", arb_value(&Type::buffer()))
+        .with_round_trip("encode_base58", "decode_base58", arb_value(&Type::buffer()))
+        .with_round_trip("encode_base64", "decode_base64", arb_value(&Type::buffer()));
+    // binary operators are only called once the types of their operands are checked
+    for operator in [
+        "and_bool", "or_bool", "div", "eq", "gt", "gte", "lt", "lte", "neq", "minus", "modulo",
+        "multiply", "add",
+    ] {
+        fuzzer = fuzzer.skip_type_violations(operator);
+    }
+    fuzzer.run();
+}
+
+#[test]
+fn fuzz_evm_functions() {
+    FunctionFuzzer::new(&EvmNetworkAddon::new())
+        // read compilation artifacts from the filesystem
+        .skip("get_contract_from_foundry_project")
+        .skip("get_contract_from_hardhat_project")
+        .run();
+}
@@ -0,0 +1,216 @@
+use alloy_network::{EthereumWallet, TransactionBuilder};
+use alloy_primitives::{Address, Signature, U256};
+use alloy_rpc_types::TransactionRequest;
+use alloy_signer_local::PrivateKeySigner;
+use txtx_addon_kit::indexmap::IndexMap;
+use txtx_addon_kit::types::frontend::{ActionItemRequest, BlockEvent, Panel};
+use txtx_addon_kit::types::types::Value;
+use txtx_addon_kit::Addon;
+use txtx_addon_kit::{hex, serde_json};
+use txtx_addon_network_evm::EvmNetworkAddon;
+use txtx_core::runbook::RunbookTopLevelInputsMap;
+use txtx_test_utils::test_harness::{setup_test, setup_test_with_inputs, ScriptedRunbook};
+use txtx_test_utils::{AutoSignerKeypair, StdAddon, TestAddon};
+
+fn get_addon_by_namespace(namespace: &str) -> Option<Box<dyn Addon>> {
+    let available_addons: Vec<Box<dyn Addon>> = vec![
+        Box::new(StdAddon::new()),
+        Box::new(TestAddon::new()),
+        Box::new(EvmNetworkAddon::new()),
+    ];
+    available_addons.into_iter().find(|addon| namespace.starts_with(addon.get_namespace()))
+}
+
+/// The action items of the panels received, in order.
+fn action_items(events: &[BlockEvent]) -> Vec<&ActionItemRequest> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            BlockEvent::Action(block) | BlockEvent::Modal(block) => Some(&block.panel),
+            _ => None,
+        })
+        .flat_map(|panel| match panel {
+            Panel::ActionPanel(data) => &data.groups[..],
+            Panel::ModalPanel(data) => &data.groups[..],
+            Panel::ErrorPanel(_) => &[],
+        })
+        .flat_map(|group| group.sub_groups.iter())
+        .flat_map(|sub_group| sub_group.action_items.iter())
+        .collect()
+}
+
+/// An EIP-1559 transfer sent by `from`, as the transaction request `evm::sign_transaction` takes.
+fn evm_transfer_request(from: &str) -> TransactionRequest {
+    TransactionRequest::default()
+        .with_from(from.parse().unwrap())
+        .with_to(Address::repeat_byte(1))
+        .with_value(U256::from(1))
+        .with_nonce(0)
+        .with_chain_id(1)
+        .with_gas_limit(21_000)
+        .with_max_fee_per_gas(20_000_000_000)
+        .with_max_priority_fee_per_gas(1_000_000_000)
+}
+
+#[test]
+fn test_picking_environment_resets_execution() {
+    let fixture = r#"
+variable "api_url" {
+    value = input.api_url
+    description = "URL of the API"
+}
+
+output "api_url" {
+    value = variable.api_url
+}
+"#;
+    let environments = IndexMap::from_iter([
+        ("devnet".to_string(), IndexMap::from_iter([("api_url".into(), "http://devnet".into())])),
+        ("mainnet".to_string(), IndexMap::from_iter([("api_url".into(), "http://mainnet".into())])),
+    ]);
+    let inputs =
+        RunbookTopLevelInputsMap::from_environment_map(&Some("devnet".into()), &environments);
+    let harness = setup_test_with_inputs("main.tx", fixture, inputs, get_addon_by_namespace);
+
+    let mut runbook = ScriptedRunbook::new(harness)
+        .pick_environment("mainnet")
+        .review_inputs_of("api_url")
+        .approve_validate_blocks();
+    runbook.run_until_complete();
+
+    let events = runbook.events();
+    let reset = events
+        .iter()
+        .position(|event| matches!(event, BlockEvent::Clear))
+        .expect("execution not reset after picking an environment");
+    let reviewed_values = action_items(&events[reset..])
+        .into_iter()
+        .filter_map(|item| item.action_type.as_review_input())
+        .map(|review| review.value.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(reviewed_values, vec![Value::string("http://mainnet".into())]);
+}
+
+#[test]
+fn test_auto_signer_requires_no_action() {
+    let fixture = r#"
+signer "deployer" "test::auto_signer" {
+}
+
+variable "amount" {
+    value = 10
+    description = "Amount to transfer"
+}
+
+action "greeting" "evm::sign_message" {
+    message = "Hello, txtx!"
+    signer = signer.deployer
+}
+
+output "amount" {
+    value = variable.amount
+}
+
+output "signature" {
+    value = action.greeting.signed_message_bytes
+}
+"#;
+    let harness = setup_test("main.tx", fixture, get_addon_by_namespace);
+
+    let mut runbook = ScriptedRunbook::new(harness).review_all_inputs().approve_validate_blocks();
+    runbook.run_until_complete();
+
+    let signer_items = action_items(runbook.events())
+        .into_iter()
+        .filter(|item| item.construct_instance_name.eq("deployer"))
+        .count();
+    assert_eq!(signer_items, 0);
+
+    let signatures = action_items(runbook.events())
+        .into_iter()
+        .filter_map(|item| item.action_type.as_display_output())
+        .filter(|output| output.name.eq("signature"))
+        .map(|output| output.value.expect_buffer_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(signatures.len(), 1);
+    let signature = Signature::from_raw(&signatures[0]).unwrap();
+    let signer = signature.recover_address_from_msg("Hello, txtx!").unwrap();
+    assert_eq!(signer.to_string().to_lowercase(), AutoSignerKeypair::from_name("deployer").address);
+}
+
+#[test]
+fn test_requested_file_is_provided_to_the_action() {
+    let fixture = r#"
+action "allowlist" "std::request_file" {
+    description = "Addresses allowed to mint"
+    extensions = ["csv"]
+    max_size = 1024
+}
+
+output "filename" {
+    value = action.allowlist.filename
+}
+
+output "size" {
+    value = action.allowlist.size
+}
+"#;
+    let harness = setup_test("main.tx", fixture, get_addon_by_namespace);
+
+    // "0xabc,0xdef", base64 encoded
+    let mut runbook = ScriptedRunbook::new(harness)
+        .provide_files("allowlist.csv", "MHhhYmMsMHhkZWY=")
+        .review_all_inputs()
+        .approve_validate_blocks();
+    runbook.run_until_complete();
+
+    let outputs = action_items(runbook.events())
+        .into_iter()
+        .filter_map(|item| item.action_type.as_display_output())
+        .map(|output| (output.name.clone(), output.value.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        outputs,
+        vec![
+            ("filename".to_string(), Value::string("allowlist.csv".into())),
+            ("size".to_string(), Value::integer(11)),
+        ]
+    );
+}
+
+#[test]
+fn test_auto_signer_signs_evm_transactions() {
+    let deployer = AutoSignerKeypair::from_name("deployer");
+    let request = evm_transfer_request(&deployer.address);
+    let fixture = format!(
+        r#"
+signer "deployer" "test::auto_signer" {{
+}}
+
+action "transfer" "evm::sign_transaction" {{
+    transaction_payload_bytes = "0x{}"
+    signer = signer.deployer
+}}
+
+output "tx_hash" {{
+    value = action.transfer.tx_hash
+}}
+"#,
+        hex::encode(serde_json::to_vec(&request).unwrap())
+    );
+    let harness = setup_test("main.tx", &fixture, get_addon_by_namespace);
+
+    let mut runbook = ScriptedRunbook::new(harness).review_all_inputs().approve_validate_blocks();
+    runbook.run_until_complete();
+
+    // the same transaction, signed by alloy with the secret key of the signer
+    let wallet = EthereumWallet::from(PrivateKeySigner::from_slice(&deployer.secret_key).unwrap());
+    let envelope = hiro_system_kit::nestable_block_on(request.build(&wallet)).unwrap();
+    let tx_hashes = action_items(runbook.events())
+        .into_iter()
+        .filter_map(|item| item.action_type.as_display_output())
+        .filter(|output| output.name.eq("tx_hash"))
+        .map(|output| output.value.expect_buffer_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(tx_hashes, vec![envelope.tx_hash().to_vec()]);
+}